@@ -0,0 +1,87 @@
+//! Compares decoding from `Bytes`, `Vec<u8>` and `Arc<[u8]>` inputs, with and without
+//! `DecodeConfig::copy_out`.
+//!
+//! Run with `cargo bench --bench decode_input`.
+
+use bytes::Bytes;
+use senax_encoder::{
+    decode, decode_from_arc_bytes, decode_from_vec, encode, with_decode_config, Decode,
+    DecodeConfig, Encode,
+};
+use std::hint::black_box;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+#[derive(Encode, Decode, Debug, PartialEq)]
+struct Message {
+    id: u64,
+    topic: String,
+    headers: Vec<(String, String)>,
+    body: Bytes,
+}
+
+fn message(body_len: usize) -> Message {
+    Message {
+        id: 42,
+        topic: "orders.created".to_string(),
+        headers: vec![
+            ("content-type".to_string(), "application/json".to_string()),
+            (
+                "trace-id".to_string(),
+                "4bf92f3577b34da6a3ce929d0e0e4736".to_string(),
+            ),
+        ],
+        body: Bytes::from(vec![0xAB; body_len]),
+    }
+}
+
+fn bench(name: &str, mut f: impl FnMut()) {
+    // Warm up, then run for about half a second
+    for _ in 0..100 {
+        f();
+    }
+    let start = Instant::now();
+    let mut iterations = 0u64;
+    while start.elapsed() < Duration::from_millis(500) {
+        for _ in 0..100 {
+            f();
+        }
+        iterations += 100;
+    }
+    let per_iter = start.elapsed() / iterations as u32;
+    println!("{:<40} {:>10.2?}/iter", name, per_iter);
+}
+
+fn main() {
+    let copy_out = DecodeConfig {
+        copy_out: true,
+        ..Default::default()
+    };
+    for body_len in [64, 64 * 1024] {
+        let encoded = encode(&message(body_len)).unwrap();
+        let vec = encoded.to_vec();
+        let arc: Arc<[u8]> = vec.clone().into();
+        println!("body {} bytes", body_len);
+
+        bench("Bytes", || {
+            black_box(decode::<Message>(&mut encoded.clone()).unwrap());
+        });
+        bench("Bytes, copy_out", || {
+            with_decode_config(&copy_out, || {
+                black_box(decode::<Message>(&mut encoded.clone()).unwrap());
+            })
+        });
+        // Includes cloning the input, which decode_from_vec consumes
+        bench("Vec<u8>", || {
+            black_box(decode_from_vec::<Message>(vec.clone()).unwrap());
+        });
+        bench("Arc<[u8]>", || {
+            black_box(decode_from_arc_bytes::<Message>(arc.clone()).unwrap());
+        });
+        bench("Arc<[u8]>, copy_out", || {
+            with_decode_config(&copy_out, || {
+                black_box(decode_from_arc_bytes::<Message>(arc.clone()).unwrap());
+            })
+        });
+    }
+}
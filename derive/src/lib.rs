@@ -4,7 +4,7 @@ use crc::{Crc, CRC_64_ECMA_182};
 use itertools::izip;
 use proc_macro::TokenStream;
 use proc_macro2::Span;
-use quote::quote;
+use quote::{quote, ToTokens};
 use std::collections::{HashMap, HashSet};
 use syn::{
     parse_macro_input, Attribute, Data, DeriveInput, Fields, GenericArgument, Ident, PathArguments,
@@ -23,12 +23,19 @@ const CRC64: Crc<u64> = Crc::<u64>::new(&CRC_64_ECMA_182);
 /// # Arguments
 ///
 /// * `name` - The field name to hash
+/// * `salt` - The container's `#[senax(id_salt = "...")]`, hashed before the name and a NUL byte
 ///
 /// # Returns
 ///
 /// A 64-bit field ID (never 0, as 0 is reserved as a terminator)
-fn calculate_id_from_name(name: &str) -> u64 {
-    let crc64_hash = CRC64.checksum(name.as_bytes());
+fn calculate_id_from_name(name: &str, salt: Option<&str>) -> u64 {
+    let mut digest = CRC64.digest();
+    if let Some(salt) = salt {
+        digest.update(salt.as_bytes());
+        digest.update(&[0]);
+    }
+    digest.update(name.as_bytes());
+    let crc64_hash = digest.finalize();
     // Ensure it's not 0 (0 is reserved as terminator)
     if crc64_hash == 0 {
         u64::MAX
@@ -37,6 +44,264 @@ fn calculate_id_from_name(name: &str) -> u64 {
     }
 }
 
+/// Naming convention applied by `#[senax(rename_all = "...")]` before field and variant IDs
+/// are hashed
+///
+/// The names and their spelling follow serde's `rename_all`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RenameRule {
+    Lower,
+    Upper,
+    Pascal,
+    Camel,
+    Snake,
+    ScreamingSnake,
+    Kebab,
+    ScreamingKebab,
+}
+
+impl RenameRule {
+    fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "lowercase" => Self::Lower,
+            "UPPERCASE" => Self::Upper,
+            "PascalCase" => Self::Pascal,
+            "camelCase" => Self::Camel,
+            "snake_case" => Self::Snake,
+            "SCREAMING_SNAKE_CASE" => Self::ScreamingSnake,
+            "kebab-case" => Self::Kebab,
+            "SCREAMING-KEBAB-CASE" => Self::ScreamingKebab,
+            _ => return None,
+        })
+    }
+
+    /// Rewrites a Rust field name (`snake_case`) or variant name (`PascalCase`)
+    fn apply(self, name: &str) -> String {
+        let words = split_words(name);
+        let capitalize = |word: &str| {
+            let mut chars = word.chars();
+            chars.next().map_or_else(String::new, |first| {
+                first
+                    .to_uppercase()
+                    .chain(chars.flat_map(char::to_lowercase))
+                    .collect()
+            })
+        };
+        match self {
+            Self::Lower => words.concat().to_lowercase(),
+            Self::Upper => words.concat().to_uppercase(),
+            Self::Pascal => words.iter().map(|w| capitalize(w)).collect(),
+            Self::Camel => words
+                .iter()
+                .enumerate()
+                .map(|(i, w)| {
+                    if i == 0 {
+                        w.to_lowercase()
+                    } else {
+                        capitalize(w)
+                    }
+                })
+                .collect(),
+            Self::Snake => words.join("_").to_lowercase(),
+            Self::ScreamingSnake => words.join("_").to_uppercase(),
+            Self::Kebab => words.join("-").to_lowercase(),
+            Self::ScreamingKebab => words.join("-").to_uppercase(),
+        }
+    }
+}
+
+/// Splits an identifier into words at `_`/`-` and at case changes (`HTTPServer` gives
+/// `HTTP`, `Server`)
+fn split_words(name: &str) -> Vec<String> {
+    let chars: Vec<char> = name.chars().collect();
+    let mut words = Vec::new();
+    let mut word = String::new();
+    for (i, &c) in chars.iter().enumerate() {
+        if c == '_' || c == '-' {
+            if !word.is_empty() {
+                words.push(std::mem::take(&mut word));
+            }
+            continue;
+        }
+        if c.is_uppercase() && !word.is_empty() {
+            let prev = chars[i - 1];
+            let next_is_lower = chars.get(i + 1).is_some_and(|n| n.is_lowercase());
+            if !prev.is_uppercase() || next_is_lower {
+                words.push(std::mem::take(&mut word));
+            }
+        }
+        word.push(c);
+    }
+    if !word.is_empty() {
+        words.push(word);
+    }
+    words
+}
+
+/// Wire IDs pinned by a `#[senax(ids_file = "...")]` mapping file
+///
+/// The file is a small TOML subset: `[Type]` sections pin the fields of a struct or the
+/// variants of an enum, `[Type.Variant]` sections pin the fields of a struct-like variant, and
+/// each entry is `name = id` with a decimal or `0x` hexadecimal ID. `#` starts a comment.
+#[derive(Debug, Clone)]
+struct IdPins {
+    path: String,
+    sections: HashMap<String, HashMap<String, u64>>,
+}
+
+impl IdPins {
+    /// Reads the file at `path`, relative to the crate being compiled
+    fn load(path: &str) -> syn::Result<Self> {
+        let full_path = match std::env::var("CARGO_MANIFEST_DIR") {
+            Ok(dir) => std::path::Path::new(&dir).join(path),
+            Err(_) => std::path::PathBuf::from(path),
+        };
+        let text = std::fs::read_to_string(&full_path).map_err(|e| {
+            syn::Error::new(
+                Span::call_site(),
+                format!(
+                    "Failed to read #[senax(ids_file = \"{}\")] ({}): {}",
+                    path,
+                    full_path.display(),
+                    e
+                ),
+            )
+        })?;
+        Self::parse(&full_path.to_string_lossy(), &text)
+    }
+
+    fn parse(path: &str, text: &str) -> syn::Result<Self> {
+        let mut sections: HashMap<String, HashMap<String, u64>> = HashMap::new();
+        let mut section = None;
+        for (i, line) in text.lines().enumerate() {
+            let line_no = i + 1;
+            let line = line.split_once('#').map_or(line, |(line, _)| line).trim();
+            if line.is_empty() {
+                continue;
+            }
+            if let Some(name) = line.strip_prefix('[') {
+                let name = name
+                    .strip_suffix(']')
+                    .ok_or_else(|| {
+                        invalid_pin_line(path, line_no, "expected `[Type]` or `[Type.Variant]`")
+                    })?
+                    .trim();
+                if name.is_empty() || sections.contains_key(name) {
+                    return Err(invalid_pin_line(
+                        path,
+                        line_no,
+                        &format!("empty or repeated section `[{}]`", name),
+                    ));
+                }
+                sections.insert(name.to_string(), HashMap::new());
+                section = Some(name.to_string());
+                continue;
+            }
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| invalid_pin_line(path, line_no, "expected `name = id`"))?;
+            let (key, value) = (key.trim(), value.trim().replace('_', ""));
+            let id = match value.strip_prefix("0x") {
+                Some(hex) => u64::from_str_radix(hex, 16),
+                None => value.parse::<u64>(),
+            }
+            .map_err(|_| {
+                invalid_pin_line(
+                    path,
+                    line_no,
+                    &format!("invalid ID `{}` for `{}`", value, key),
+                )
+            })?;
+            if id == 0 {
+                return Err(invalid_pin_line(
+                    path,
+                    line_no,
+                    "ID 0 is reserved as a terminator",
+                ));
+            }
+            let Some(section) = &section else {
+                return Err(invalid_pin_line(
+                    path,
+                    line_no,
+                    "entry outside of a `[Type]` section",
+                ));
+            };
+            if sections
+                .entry(section.clone())
+                .or_default()
+                .insert(key.to_string(), id)
+                .is_some()
+            {
+                return Err(invalid_pin_line(
+                    path,
+                    line_no,
+                    &format!("`{}` is pinned twice in `[{}]`", key, section),
+                ));
+            }
+        }
+        Ok(IdPins {
+            path: path.to_string(),
+            sections,
+        })
+    }
+
+    /// The pins for the fields or variants of `type_name`, or of its variant `variant`
+    fn scope(&self, type_name: &Ident, variant: Option<&Ident>) -> PinScope<'_> {
+        let section = match variant {
+            Some(variant) => format!("{}.{}", type_name, variant),
+            None => type_name.to_string(),
+        };
+        PinScope {
+            path: &self.path,
+            ids: self.sections.get(&section),
+            section,
+        }
+    }
+}
+
+fn invalid_pin_line(path: &str, line: usize, message: &str) -> syn::Error {
+    syn::Error::new(Span::call_site(), format!("{}:{}: {}", path, line, message))
+}
+
+/// One section of an [`IdPins`] file
+struct PinScope<'a> {
+    path: &'a str,
+    section: String,
+    ids: Option<&'a HashMap<String, u64>>,
+}
+
+impl PinScope<'_> {
+    /// Returns the pinned ID of `name`, failing at `item` if it is not pinned or if its
+    /// explicit `#[senax(id = N)]` disagrees with the pin
+    fn pinned_id(
+        &self,
+        item: &impl ToTokens,
+        name: &str,
+        explicit: Option<u64>,
+        current: u64,
+    ) -> syn::Result<u64> {
+        match self.ids.and_then(|ids| ids.get(name)) {
+            Some(&pin) => match explicit.filter(|&id| id != pin) {
+                Some(id) => Err(syn::Error::new_spanned(
+                    item,
+                    format!(
+                        "`{}` in `[{}]` has #[senax(id = {})] but {} pins it to {}",
+                        name, self.section, id, self.path, pin
+                    ),
+                )),
+                None => Ok(pin),
+            },
+            None => Err(syn::Error::new_spanned(
+                item,
+                format!(
+                    "`{}` is not pinned in {}; add `{} = 0x{:016X}` under `[{}]` to keep its current ID",
+                    name, self.path, name, current, self.section
+                ),
+            )),
+        }
+    }
+}
+
 /// Generate structure information text for CRC64 hashing
 ///
 /// This function creates a deterministic text representation of the structure
@@ -46,13 +311,17 @@ fn calculate_id_from_name(name: &str) -> u64 {
 /// # Arguments
 ///
 /// * `input` - The parsed derive input containing structure information
+/// * `id_salt` - The container's `#[senax(id_salt = "...")]`, so salting also changes the pack hash
 ///
 /// # Returns
 ///
 /// A string containing the structure information
-fn generate_structure_info(input: &DeriveInput) -> String {
+fn generate_structure_info(input: &DeriveInput, id_salt: Option<&str>) -> String {
     let mut info = String::new();
     info.push_str(&format!("type:{}", input.ident));
+    if let Some(salt) = id_salt {
+        info.push_str(&format!("|salt:{}", salt));
+    }
 
     match &input.data {
         Data::Struct(s) => {
@@ -130,6 +399,36 @@ fn has_default_attribute(attrs: &[Attribute]) -> bool {
     attrs.iter().any(|attr| attr.path().is_ident("default"))
 }
 
+/// Collect the `///` doc comments of an item, one line per `#[doc]` attribute
+///
+/// The leading space rustdoc leaves after `///` is removed. Returns `None` without docs.
+fn doc_comment(attrs: &[Attribute]) -> Option<String> {
+    let lines: Vec<String> = attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("doc"))
+        .filter_map(|attr| match &attr.meta {
+            syn::Meta::NameValue(syn::MetaNameValue {
+                value:
+                    syn::Expr::Lit(syn::ExprLit {
+                        lit: syn::Lit::Str(s),
+                        ..
+                    }),
+                ..
+            }) => Some(s.value()),
+            _ => None,
+        })
+        .map(|line| {
+            line.strip_prefix(' ')
+                .unwrap_or(&line)
+                .trim_end()
+                .to_string()
+        })
+        .collect();
+    let doc = lines.join("\n");
+    let doc = doc.trim_matches('\n');
+    (!doc.is_empty()).then(|| doc.to_string())
+}
+
 /// Field attributes parsed from `#[senax(...)]` annotations
 ///
 /// This struct represents the various attributes that can be applied to fields
@@ -143,6 +442,20 @@ fn has_default_attribute(attrs: &[Attribute]) -> bool {
 /// * `skip_decode` - Whether to ignore this field during decoding
 /// * `skip_default` - Whether to use default value if field is missing
 /// * `rename` - Optional alternative name for ID calculation (maintains compatibility when renaming)
+/// * `tolerant` - Whether a tuple enum variant accepts payloads with extra trailing fields
+/// * `explicit_id` - Whether the ID was given via `id` or `rename` rather than derived from the field name
+/// * `prefix` - Whether the field belongs to the leading fields read by the generated `decode_prefix`
+/// * `encrypt` - Whether the field's encoded bytes are sealed with the installed field key
+/// * `redact` - Whether the field is replaced by its default value when `EncodeConfig::redact` is set
+/// * `flat_keys` - Whether a tuple-keyed map field is written without per-key tuple headers
+/// * `ext_block` - Whether the field is an `ExtBlock` of vendor extensions
+/// * `sensitive` - Whether the field's decoded value is held in `Zeroizing` storage until the struct is built
+/// * `max_len` - Optional maximum length of a string/collection field, enforced on encode/decode and used by `MaxEncodedSize`
+/// * `sort` - Whether a sequence/set field's elements are written in ascending order
+/// * `min` / `max` - Optional inclusive bounds on a numeric field, checked during decode
+/// * `deprecated` - Whether the field is no longer encoded but still decoded, with each read reported
+/// * `skip_encode_if` - Optional predicate on the field value; the field is not written when it returns `true`
+/// * `with` - Optional `Remote` proxy type that encodes the field, for types that implement no codec traits
 #[derive(Debug, Clone)]
 #[allow(dead_code)] // The rename field is used indirectly in ID calculation
 struct FieldAttributes {
@@ -152,6 +465,21 @@ struct FieldAttributes {
     skip_decode: bool,
     skip_default: bool,
     rename: Option<String>,
+    tolerant: bool,
+    explicit_id: bool,
+    prefix: bool,
+    encrypt: bool,
+    redact: bool,
+    flat_keys: bool,
+    ext_block: bool,
+    sensitive: bool,
+    max_len: Option<usize>,
+    sort: bool,
+    min: Option<proc_macro2::TokenStream>,
+    max: Option<proc_macro2::TokenStream>,
+    deprecated: bool,
+    skip_encode_if: Option<proc_macro2::TokenStream>,
+    with: Option<proc_macro2::TokenStream>,
 }
 
 /// Container attributes parsed from `#[senax(...)]` annotations at struct/enum level
@@ -162,10 +490,40 @@ struct FieldAttributes {
 ///
 /// * `disable_encode` - Whether to generate stub implementations for Encode/Decode traits
 /// * `disable_pack` - Whether to generate stub implementations for Pack/Unpack traits
-#[derive(Debug, Clone, Default)]
+/// * `accept_tuple` - Whether a named struct also decodes from tuple struct payloads
+/// * `accept_named` - Whether a tuple struct also decodes from named struct payloads by field ID
+/// * `krate` - Path to the senax-encoder crate used in generated code (defaults to `senax_encoder`)
+/// * `discriminant_ids` - Whether enum variants use their discriminants (`Variant = N`) as IDs
+/// * `string_repr` - Whether unit enum variants are encoded as their (renamed) name string
+/// * `max_size` - Whether to implement `MaxEncodedSize` for the type
+/// * `wire_info` - Whether to implement `WireInfo` for the type
+/// * `schema` - Whether to implement `HasSchema` for the type
+/// * `builder` - Whether to generate a `<Name>Builder` used by the derived decoder
+/// * `union_as` - The tagged view type a union is converted to and from for serialization
+/// * `rename_all` - Naming convention applied to struct field names or enum variant names before hashing
+/// * `id_salt` - Salt mixed into every field and variant ID hashed from a name
+/// * `id_pins` - Field and variant IDs pinned by an `ids_file` mapping file
+/// * `bound` - Where predicates replacing the automatic type parameter bounds of the codec impls
+/// * `recursive` - Whether to guard decoding with the nesting depth limit even without a self-referencing field
+#[derive(Clone)]
 struct ContainerAttributes {
     disable_encode: bool,
     disable_pack: bool,
+    accept_tuple: bool,
+    accept_named: bool,
+    krate: syn::Path,
+    discriminant_ids: bool,
+    string_repr: bool,
+    max_size: bool,
+    wire_info: bool,
+    schema: bool,
+    builder: bool,
+    union_as: Option<syn::Type>,
+    rename_all: Option<RenameRule>,
+    id_salt: Option<String>,
+    id_pins: Option<IdPins>,
+    bound: Option<Vec<syn::WherePredicate>>,
+    recursive: bool,
 }
 
 /// Extract and parse `#[senax(...)]` attribute values from container (struct/enum) attributes
@@ -179,29 +537,136 @@ struct ContainerAttributes {
 ///
 /// # Returns
 ///
-/// A `ContainerAttributes` struct with parsed values.
+/// A `ContainerAttributes` struct with parsed values, or the error to report at compile time
+/// for a malformed attribute or an unreadable `ids_file`.
 ///
 /// # Supported Attributes
 ///
-/// * `#[senax(disable_encode)]` - Generate stub implementations for Encode/Decode traits (unimplemented!() only)
-/// * `#[senax(disable_pack)]` - Generate stub implementations for Pack/Unpack traits (unimplemented!() only)
-fn get_container_attributes(attrs: &[Attribute]) -> ContainerAttributes {
+/// * `#[senax(disable_encode)]` - Generate stub implementations for Encode/Decode traits (returning an error)
+/// * `#[senax(disable_pack)]` - Generate stub implementations for Pack/Unpack traits (returning an error)
+/// * `#[senax(accept_tuple)]` - Let a named struct decode from tuple struct payloads by field position
+/// * `#[senax(accept_named)]` - Let a tuple struct decode from named struct payloads by field ID
+/// * `#[senax(crate = "path")]` - Path to senax-encoder in generated code (for re-exports or renamed dependencies)
+/// * `#[senax(discriminant_ids)]` - Use explicit enum discriminants as variant IDs instead of CRC64
+/// * `#[senax(string_repr)]` - Encode unit enum variants as their (renamed) name string
+/// * `#[senax(max_size)]` - Implement `MaxEncodedSize` (requires bounded field types)
+/// * `#[senax(wire_info)]` - Implement `WireInfo` describing the field and variant IDs
+/// * `#[senax(schema)]` - Implement `HasSchema` describing the wire shape as a `TypeSchema`
+/// * `#[senax(builder)]` - Generate a `<Name>Builder` for a named struct
+/// * `#[senax(union_as = "View")]` - Serialize a union through a tagged view type
+/// * `#[senax(rename_all = "camelCase")]` - Apply a naming convention to field/variant names before hashing
+/// * `#[senax(id_salt = "v2")]` - Mix a salt into every field/variant ID hashed from a name
+/// * `#[senax(ids_file = "senax-ids.toml")]` - Take every field/variant ID from a mapping file
+/// * `#[senax(bound = "T: Encoder")]` - Replace the automatic type parameter bounds of the codec impls
+/// * `#[senax(recursive)]` - Apply the decode depth limit to a mutually recursive type
+fn get_container_attributes(attrs: &[Attribute]) -> syn::Result<ContainerAttributes> {
     let mut disable_encode = false;
     let mut disable_pack = false;
+    let mut accept_tuple = false;
+    let mut accept_named = false;
+    let mut krate = None;
+    let mut discriminant_ids = false;
+    let mut string_repr = false;
+    let mut max_size = false;
+    let mut wire_info = false;
+    let mut schema = false;
+    let mut builder = false;
+    let mut union_as = None;
+    let mut rename_all = None;
+    let mut id_salt = None;
+    let mut ids_file = None;
+    let mut bound = None;
+    let mut recursive = false;
 
     for attr in attrs {
         if attr.path().is_ident("senax") {
             let parsed = attr.parse_args_with(|input: syn::parse::ParseStream| {
                 let mut parsed_disable_encode = false;
                 let mut parsed_disable_pack = false;
+                let mut parsed_accept_tuple = false;
+                let mut parsed_accept_named = false;
+                let mut parsed_krate = None;
+                let mut parsed_discriminant_ids = false;
+                let mut parsed_string_repr = false;
+                let mut parsed_max_size = false;
+                let mut parsed_wire_info = false;
+                let mut parsed_schema = false;
+                let mut parsed_builder = false;
+                let mut parsed_union_as = None;
+                let mut parsed_rename_all = None;
+                let mut parsed_id_salt = None;
+                let mut parsed_ids_file = None;
+                let mut parsed_bound = None;
+                let mut parsed_recursive = false;
 
                 while !input.is_empty() {
-                    let ident = input.parse::<syn::Ident>()?;
+                    // `crate` is a keyword, so parse it as an ident explicitly
+                    let ident: syn::Ident = input.call(syn::ext::IdentExt::parse_any)?;
 
                     if ident == "disable_encode" {
                         parsed_disable_encode = true;
                     } else if ident == "disable_pack" {
                         parsed_disable_pack = true;
+                    } else if ident == "accept_tuple" {
+                        parsed_accept_tuple = true;
+                    } else if ident == "accept_named" {
+                        parsed_accept_named = true;
+                    } else if ident == "crate" {
+                        input.parse::<syn::Token![=]>()?;
+                        let lit_str = input.parse::<syn::LitStr>()?;
+                        parsed_krate = Some(lit_str.parse::<syn::Path>()?);
+                    } else if ident == "discriminant_ids" {
+                        parsed_discriminant_ids = true;
+                    } else if ident == "string_repr" {
+                        parsed_string_repr = true;
+                    } else if ident == "max_size" {
+                        parsed_max_size = true;
+                    } else if ident == "wire_info" {
+                        parsed_wire_info = true;
+                    } else if ident == "schema" {
+                        parsed_schema = true;
+                    } else if ident == "builder" {
+                        parsed_builder = true;
+                    } else if ident == "union_as" {
+                        input.parse::<syn::Token![=]>()?;
+                        let lit_str = input.parse::<syn::LitStr>()?;
+                        parsed_union_as = Some(lit_str.parse::<syn::Type>()?);
+                    } else if ident == "rename_all" {
+                        input.parse::<syn::Token![=]>()?;
+                        let lit_str = input.parse::<syn::LitStr>()?;
+                        let Some(rule) = RenameRule::from_name(&lit_str.value()) else {
+                            return Err(syn::Error::new(
+                                lit_str.span(),
+                                format!(
+                                    "Unknown #[senax(rename_all = \"{}\")]; expected one of \"lowercase\", \"UPPERCASE\", \"PascalCase\", \"camelCase\", \"snake_case\", \"SCREAMING_SNAKE_CASE\", \"kebab-case\", \"SCREAMING-KEBAB-CASE\"",
+                                    lit_str.value()
+                                ),
+                            ));
+                        };
+                        parsed_rename_all = Some(rule);
+                    } else if ident == "id_salt" {
+                        input.parse::<syn::Token![=]>()?;
+                        let lit_str = input.parse::<syn::LitStr>()?;
+                        if lit_str.value().is_empty() {
+                            return Err(syn::Error::new(
+                                lit_str.span(),
+                                "#[senax(id_salt = \"...\")] must not be empty",
+                            ));
+                        }
+                        parsed_id_salt = Some(lit_str.value());
+                    } else if ident == "ids_file" {
+                        input.parse::<syn::Token![=]>()?;
+                        let lit_str = input.parse::<syn::LitStr>()?;
+                        parsed_ids_file = Some(lit_str.value());
+                    } else if ident == "bound" {
+                        input.parse::<syn::Token![=]>()?;
+                        let lit_str = input.parse::<syn::LitStr>()?;
+                        let predicates = lit_str.parse_with(
+                            syn::punctuated::Punctuated::<syn::WherePredicate, syn::Token![,]>::parse_terminated,
+                        )?;
+                        parsed_bound = Some(predicates.into_iter().collect());
+                    } else if ident == "recursive" {
+                        parsed_recursive = true;
                     } else {
                         return Err(syn::Error::new(
                             ident.span(),
@@ -215,22 +680,236 @@ fn get_container_attributes(attrs: &[Attribute]) -> ContainerAttributes {
                     }
                 }
 
-                Ok((parsed_disable_encode, parsed_disable_pack))
-            });
+                Ok((
+                    parsed_disable_encode,
+                    parsed_disable_pack,
+                    parsed_accept_tuple,
+                    parsed_accept_named,
+                    parsed_krate,
+                    parsed_discriminant_ids,
+                    parsed_string_repr,
+                    parsed_max_size,
+                    parsed_wire_info,
+                    parsed_schema,
+                    parsed_builder,
+                    parsed_union_as,
+                    parsed_rename_all,
+                    parsed_id_salt,
+                    parsed_ids_file,
+                    parsed_bound,
+                    parsed_recursive,
+                ))
+            })?;
 
-            if let Ok((parsed_disable_encode, parsed_disable_pack)) = parsed {
-                disable_encode = disable_encode || parsed_disable_encode;
-                disable_pack = disable_pack || parsed_disable_pack;
+            let (
+                parsed_disable_encode,
+                parsed_disable_pack,
+                parsed_accept_tuple,
+                parsed_accept_named,
+                parsed_krate,
+                parsed_discriminant_ids,
+                parsed_string_repr,
+                parsed_max_size,
+                parsed_wire_info,
+                parsed_schema,
+                parsed_builder,
+                parsed_union_as,
+                parsed_rename_all,
+                parsed_id_salt,
+                parsed_ids_file,
+                parsed_bound,
+                parsed_recursive,
+            ) = parsed;
+            disable_encode = disable_encode || parsed_disable_encode;
+            disable_pack = disable_pack || parsed_disable_pack;
+            accept_tuple = accept_tuple || parsed_accept_tuple;
+            accept_named = accept_named || parsed_accept_named;
+            if parsed_krate.is_some() {
+                krate = parsed_krate;
+            }
+            discriminant_ids = discriminant_ids || parsed_discriminant_ids;
+            string_repr = string_repr || parsed_string_repr;
+            max_size = max_size || parsed_max_size;
+            wire_info = wire_info || parsed_wire_info;
+            schema = schema || parsed_schema;
+            builder = builder || parsed_builder;
+            if parsed_union_as.is_some() {
+                union_as = parsed_union_as;
+            }
+            if parsed_rename_all.is_some() {
+                rename_all = parsed_rename_all;
+            }
+            if parsed_id_salt.is_some() {
+                id_salt = parsed_id_salt;
+            }
+            if parsed_ids_file.is_some() {
+                ids_file = parsed_ids_file;
+            }
+            if parsed_bound.is_some() {
+                bound = parsed_bound;
             }
+            recursive = recursive || parsed_recursive;
         }
     }
 
-    ContainerAttributes {
+    Ok(ContainerAttributes {
         disable_encode,
         disable_pack,
+        accept_tuple,
+        accept_named,
+        krate: krate.unwrap_or_else(|| syn::parse_quote!(senax_encoder)),
+        discriminant_ids,
+        string_repr,
+        max_size,
+        wire_info,
+        schema,
+        builder,
+        union_as,
+        rename_all,
+        id_salt,
+        id_pins: ids_file.as_deref().map(IdPins::load).transpose()?,
+        bound,
+        recursive,
+    })
+}
+
+/// The where clause of a derived `Encoder`, `Decoder`, `Packer` or `Unpacker` impl
+///
+/// Keeps the type's own predicates and adds `T: #bound + 'static` for every type parameter
+/// that appears in a field the impl writes or reads (`uses_field` decides for named fields;
+/// `Vec<T>` and the other collections need `T: 'static`). A container `#[senax(bound)]`
+/// replaces the added predicates, for parameters that need more or less than that.
+fn codec_where_clause(
+    input: &DeriveInput,
+    container_attrs: &ContainerAttributes,
+    bound: proc_macro2::TokenStream,
+    uses_field: fn(&FieldAttributes) -> bool,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let mut predicates: Vec<proc_macro2::TokenStream> = input
+        .generics
+        .where_clause
+        .as_ref()
+        .map(|w| w.predicates.iter().map(|p| quote! { #p }).collect())
+        .unwrap_or_default();
+    if let Some(custom) = &container_attrs.bound {
+        predicates.extend(custom.iter().map(|p| quote! { #p }));
+    } else {
+        let fields: Vec<&syn::Field> = match &input.data {
+            Data::Struct(s) => s.fields.iter().collect(),
+            Data::Enum(e) => e.variants.iter().flat_map(|v| v.fields.iter()).collect(),
+            Data::Union(_) => Vec::new(),
+        };
+        let mut field_types: Vec<proc_macro2::TokenStream> = Vec::new();
+        for f in fields {
+            if let Some(ident) = &f.ident {
+                let attrs =
+                    get_field_attributes(f, &f.attrs, &ident.to_string(), None, None, None)?;
+                if !uses_field(&attrs) {
+                    continue;
+                }
+            }
+            let ty = &f.ty;
+            field_types.push(quote! { #ty });
+        }
+        for param in input.generics.type_params() {
+            let ident = &param.ident;
+            if field_types
+                .iter()
+                .any(|ty| mentions_ident(ty.clone(), ident))
+            {
+                predicates.push(quote! { #ident: #bound + 'static });
+            }
+        }
+    }
+    Ok(if predicates.is_empty() {
+        quote! {}
+    } else {
+        quote! { where #(#predicates),* }
+    })
+}
+
+/// Returns `true` if `ident` occurs anywhere in `tokens`
+fn mentions_ident(tokens: proc_macro2::TokenStream, ident: &Ident) -> bool {
+    tokens.into_iter().any(|tt| match tt {
+        proc_macro2::TokenTree::Ident(i) => i == *ident,
+        proc_macro2::TokenTree::Group(g) => mentions_ident(g.stream(), ident),
+        _ => false,
+    })
+}
+
+/// Wraps a derived `decode` or `unpack` body in `decode_nested` if the type is recursive
+///
+/// A type is recursive if it has `#[senax(recursive)]` or a field type names the type itself
+/// (`Box<Expr>`, `Vec<Node>`, `Option<Box<Self>>`). Recursion through other types is not
+/// detected, hence the attribute.
+fn depth_guarded(
+    input: &DeriveInput,
+    container_attrs: &ContainerAttributes,
+    body: proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    let self_ident = Ident::new("Self", Span::call_site());
+    let recursive = container_attrs.recursive
+        || match &input.data {
+            Data::Struct(s) => s.fields.iter().collect::<Vec<_>>(),
+            Data::Enum(e) => e.variants.iter().flat_map(|v| v.fields.iter()).collect(),
+            Data::Union(_) => Vec::new(),
+        }
+        .into_iter()
+        .any(|f| {
+            let ty = &f.ty;
+            mentions_ident(quote! { #ty }, &input.ident)
+                || mentions_ident(quote! { #ty }, &self_ident)
+        });
+    if recursive {
+        let krate = &container_attrs.krate;
+        quote! { #krate::decode_nested(|| { #body }) }
+    } else {
+        body
     }
 }
 
+impl ContainerAttributes {
+    /// The `ids_file` pins for the fields or variants of `type_name`, or of its variant `variant`
+    fn pins(&self, type_name: &Ident, variant: Option<&Ident>) -> Option<PinScope<'_>> {
+        self.id_pins
+            .as_ref()
+            .map(|pins| pins.scope(type_name, variant))
+    }
+}
+
+/// Returns the `#[senax(union_as = "View")]` type of a union
+///
+/// Fails for unions without the attribute (their active field is unknown to the derive) and
+/// for the attribute on structs and enums.
+fn union_view<'a>(
+    input: &DeriveInput,
+    attrs: &'a ContainerAttributes,
+) -> syn::Result<Option<&'a syn::Type>> {
+    match (&input.data, &attrs.union_as) {
+        (Data::Union(_), Some(view)) => Ok(Some(view)),
+        (Data::Union(_), None) => Err(union_without_view(input)),
+        (_, Some(view)) => Err(syn::Error::new_spanned(
+            view,
+            format!(
+                "#[senax(union_as)] is only supported on unions, not '{}'",
+                input.ident
+            ),
+        )),
+        (_, None) => Ok(None),
+    }
+}
+
+/// The error for a union derived without `#[senax(union_as = "View")]`
+fn union_without_view(input: &DeriveInput) -> syn::Error {
+    syn::Error::new_spanned(
+        &input.ident,
+        format!(
+            "Union '{}' needs #[senax(union_as = \"EnumName\")] naming a tagged view type that implements From<&{}> and that {} implements From<EnumName>",
+            input.ident, input.ident, input.ident
+        ),
+    )
+}
+
 /// Extract and parse `#[senax(...)]` attribute values from field attributes
 ///
 /// This function parses the senax attributes applied to a field and returns
@@ -238,13 +917,19 @@ fn get_container_attributes(attrs: &[Attribute]) -> ContainerAttributes {
 ///
 /// # Arguments
 ///
+/// * `item` - The field or variant, where errors are reported
 /// * `attrs` - The attributes array from the field
 /// * `field_name` - The name of the field (used for ID calculation if no explicit ID is provided)
+/// * `rename_all` - The container's naming convention, for struct fields and enum variants
+/// * `id_salt` - The container's ID salt, mixed into IDs hashed from a name
+/// * `pins` - The container's `ids_file` section for this field or variant, if any
 ///
 /// # Returns
 ///
 /// A `FieldAttributes` struct with parsed values. If no explicit ID is provided,
-/// the ID is calculated using CRC64 hash of either the rename value or the field name.
+/// the ID is calculated using CRC64 hash of either the rename value or the field name
+/// (converted by `rename_all`, if given). A malformed `#[senax(...)]`, conflicting
+/// attributes or a missing `ids_file` pin are returned as the error to report.
 ///
 /// # Supported Attributes
 ///
@@ -253,16 +938,51 @@ fn get_container_attributes(attrs: &[Attribute]) -> ContainerAttributes {
 /// * `#[senax(skip_encode)]` - Skip this field during encoding
 /// * `#[senax(skip_decode)]` - Skip this field during decoding
 /// * `#[senax(skip_default)]` - Skip encoding if field value is default, use default if missing during decode
+/// * `#[senax(skip)]` - Shorthand for `skip_encode, skip_decode`
+/// * `#[senax(skip_encode_if = "path::to::fn")]` - Skip encoding when `fn(&T) -> bool` returns `true`, use default if missing during decode
+/// * `#[senax(with = "path::to::Proxy")]` - Encode the field through a `Remote` proxy declared with `senax_remote!`
 /// * `#[senax(rename="name")]` - Alternative name for ID calculation
+/// * `#[senax(tolerant)]` - On a tuple enum variant, skip extra trailing fields instead of failing
+/// * `#[senax(prefix)]` - Include this leading field in the struct generated for `decode_prefix`
+/// * `#[senax(encrypt)]` - Seal the field's encoded bytes with the key installed by `field_crypto::with_field_key`
+/// * `#[senax(redact)]` - Write the field as its default value when `EncodeConfig::redact` is set
+/// * `#[senax(flat_keys)]` - Write a tuple-keyed map without the tuple header on every key
+/// * `#[senax(ext_block)]` - Mark an `ExtBlock` field of vendor extensions (omitted when empty, empty when missing)
+/// * `#[senax(sensitive)]` - Wipe the decoded value if decoding the struct fails (requires the `zeroize` feature)
+/// * `#[senax(max_len = N)]` - Bound a string/collection field to `N` elements (checked on encode/decode, used by `MaxEncodedSize`)
+/// * `#[senax(sort)]` - Write a sequence/set field's elements in ascending order (requires `Ord`)
+/// * `#[senax(min = X, max = Y)]` - Inclusive bounds on a numeric field, checked during decode
+/// * `#[senax(deprecated)]` - Stop encoding the field but keep decoding it (defaulting when missing), reporting each read to `deprecated_fields`
 ///
 /// Multiple attributes can be combined: `#[senax(id=123, default, skip_encode)]`
-fn get_field_attributes(attrs: &[Attribute], field_name: &str) -> FieldAttributes {
+fn get_field_attributes(
+    item: &impl ToTokens,
+    attrs: &[Attribute],
+    field_name: &str,
+    rename_all: Option<RenameRule>,
+    id_salt: Option<&str>,
+    pins: Option<&PinScope>,
+) -> syn::Result<FieldAttributes> {
     let mut id = None;
     let mut default = false;
     let mut skip_encode = false;
     let mut skip_decode = false;
     let mut skip_default = false;
     let mut rename = None;
+    let mut tolerant = false;
+    let mut prefix = false;
+    let mut encrypt = false;
+    let mut redact = false;
+    let mut flat_keys = false;
+    let mut ext_block = false;
+    let mut sensitive = false;
+    let mut max_len = None;
+    let mut sort = false;
+    let mut min = None;
+    let mut max = None;
+    let mut deprecated = false;
+    let mut skip_encode_if = None;
+    let mut with = None;
 
     for attr in attrs {
         if attr.path().is_ident("senax") {
@@ -274,6 +994,20 @@ fn get_field_attributes(attrs: &[Attribute], field_name: &str) -> FieldAttribute
                 let mut parsed_skip_decode = false;
                 let mut parsed_skip_default = false;
                 let mut parsed_rename = None;
+                let mut parsed_tolerant = false;
+                let mut parsed_prefix = false;
+                let mut parsed_encrypt = false;
+                let mut parsed_redact = false;
+                let mut parsed_flat_keys = false;
+                let mut parsed_ext_block = false;
+                let mut parsed_sensitive = false;
+                let mut parsed_max_len = None;
+                let mut parsed_sort = false;
+                let mut parsed_min = None;
+                let mut parsed_max = None;
+                let mut parsed_deprecated = false;
+                let mut parsed_skip_encode_if = None;
+                let mut parsed_with = None;
 
                 while !input.is_empty() {
                     let ident = input.parse::<syn::Ident>()?;
@@ -300,10 +1034,54 @@ fn get_field_attributes(attrs: &[Attribute], field_name: &str) -> FieldAttribute
                         parsed_skip_decode = true;
                     } else if ident == "skip_default" {
                         parsed_skip_default = true;
+                    } else if ident == "skip" {
+                        parsed_skip_encode = true;
+                        parsed_skip_decode = true;
+                    } else if ident == "skip_encode_if" {
+                        input.parse::<syn::Token![=]>()?;
+                        let lit_str = input.parse::<syn::LitStr>()?;
+                        let path = lit_str.parse::<syn::Path>()?;
+                        parsed_skip_encode_if = Some(quote! { #path });
+                    } else if ident == "with" {
+                        input.parse::<syn::Token![=]>()?;
+                        let lit_str = input.parse::<syn::LitStr>()?;
+                        let path = lit_str.parse::<syn::Path>()?;
+                        parsed_with = Some(quote! { #path });
                     } else if ident == "rename" {
                         input.parse::<syn::Token![=]>()?;
                         let lit_str = input.parse::<syn::LitStr>()?;
                         parsed_rename = Some(lit_str.value());
+                    } else if ident == "tolerant" {
+                        parsed_tolerant = true;
+                    } else if ident == "prefix" {
+                        parsed_prefix = true;
+                    } else if ident == "encrypt" {
+                        parsed_encrypt = true;
+                    } else if ident == "redact" {
+                        parsed_redact = true;
+                    } else if ident == "flat_keys" {
+                        parsed_flat_keys = true;
+                    } else if ident == "ext_block" {
+                        parsed_ext_block = true;
+                    } else if ident == "sensitive" {
+                        parsed_sensitive = true;
+                    } else if ident == "deprecated" {
+                        parsed_deprecated = true;
+                    } else if ident == "sort" {
+                        parsed_sort = true;
+                    } else if ident == "max_len" {
+                        input.parse::<syn::Token![=]>()?;
+                        let lit = input.parse::<syn::LitInt>()?;
+                        parsed_max_len = Some(lit.base10_parse::<usize>()?);
+                    } else if ident == "min" || ident == "max" {
+                        input.parse::<syn::Token![=]>()?;
+                        // A literal, possibly negated, typed by the field
+                        let bound = input.parse::<syn::Expr>()?;
+                        if ident == "min" {
+                            parsed_min = Some(quote! { #bound });
+                        } else {
+                            parsed_max = Some(quote! { #bound });
+                        }
                     } else {
                         return Err(syn::Error::new(
                             ident.span(),
@@ -324,37 +1102,132 @@ fn get_field_attributes(attrs: &[Attribute], field_name: &str) -> FieldAttribute
                     parsed_skip_decode,
                     parsed_skip_default,
                     parsed_rename,
+                    parsed_tolerant,
+                    parsed_prefix,
+                    parsed_encrypt,
+                    parsed_redact,
+                    parsed_flat_keys,
+                    parsed_ext_block,
+                    parsed_sensitive,
+                    parsed_max_len,
+                    parsed_sort,
+                    parsed_min,
+                    parsed_max,
+                    parsed_deprecated,
+                    parsed_skip_encode_if,
+                    parsed_with,
                 ))
             });
 
-            if let Ok((
+            let (
                 parsed_id,
                 parsed_default,
                 parsed_skip_encode,
                 parsed_skip_decode,
                 parsed_skip_default,
                 parsed_rename,
-            )) = parsed
-            {
-                if let Some(id_val) = parsed_id {
-                    id = Some(id_val);
-                }
-                default = default || parsed_default;
-                skip_encode = skip_encode || parsed_skip_encode;
-                skip_decode = skip_decode || parsed_skip_decode;
-                skip_default = skip_default || parsed_skip_default;
-                if let Some(rename_val) = parsed_rename {
-                    rename = Some(rename_val);
-                }
-            } else {
-                eprintln!(
-                    "Warning: #[senax(...)] attribute for field '{}' is not in the correct format.",
-                    field_name
-                );
+                parsed_tolerant,
+                parsed_prefix,
+                parsed_encrypt,
+                parsed_redact,
+                parsed_flat_keys,
+                parsed_ext_block,
+                parsed_sensitive,
+                parsed_max_len,
+                parsed_sort,
+                parsed_min,
+                parsed_max,
+                parsed_deprecated,
+                parsed_skip_encode_if,
+                parsed_with,
+            ) = parsed?;
+            if let Some(id_val) = parsed_id {
+                id = Some(id_val);
+            }
+            default = default || parsed_default;
+            skip_encode = skip_encode || parsed_skip_encode;
+            skip_decode = skip_decode || parsed_skip_decode;
+            skip_default = skip_default || parsed_skip_default;
+            tolerant = tolerant || parsed_tolerant;
+            prefix = prefix || parsed_prefix;
+            encrypt = encrypt || parsed_encrypt;
+            redact = redact || parsed_redact;
+            flat_keys = flat_keys || parsed_flat_keys;
+            ext_block = ext_block || parsed_ext_block;
+            sensitive = sensitive || parsed_sensitive;
+            deprecated = deprecated || parsed_deprecated;
+            sort = sort || parsed_sort;
+            if parsed_max_len.is_some() {
+                max_len = parsed_max_len;
+            }
+            if parsed_min.is_some() {
+                min = parsed_min;
+            }
+            if parsed_max.is_some() {
+                max = parsed_max;
+            }
+            if parsed_skip_encode_if.is_some() {
+                skip_encode_if = parsed_skip_encode_if;
+            }
+            if parsed_with.is_some() {
+                with = parsed_with;
+            }
+            if let Some(rename_val) = parsed_rename {
+                rename = Some(rename_val);
             }
         }
     }
 
+    // A deprecated field is only read from old payloads, so new ones may lack it
+    skip_encode = skip_encode || deprecated;
+    default = default || deprecated;
+    // A conditionally skipped field may be missing from payloads
+    default = default || skip_encode_if.is_some();
+    if ext_block && (with.is_some() || encrypt || flat_keys) {
+        return Err(syn::Error::new_spanned(
+            item,
+            format!(
+                "#[senax(ext_block)] cannot be combined with with, encrypt or flat_keys on field '{}'",
+                field_name
+            ),
+        ));
+    }
+    // An empty extension block is omitted, so payloads may lack it
+    default = default || ext_block;
+    skip_default = skip_default || ext_block;
+    if sensitive && (prefix || ext_block) {
+        return Err(syn::Error::new_spanned(
+            item,
+            format!(
+                "#[senax(sensitive)] cannot be combined with prefix or ext_block on field '{}'",
+                field_name
+            ),
+        ));
+    }
+    if sort && (with.is_some() || encrypt || flat_keys || ext_block) {
+        return Err(syn::Error::new_spanned(
+            item,
+            format!(
+                "#[senax(sort)] cannot be combined with with, encrypt, flat_keys or ext_block on field '{}'",
+                field_name
+            ),
+        ));
+    }
+    if with.is_some() && (encrypt || flat_keys) {
+        return Err(syn::Error::new_spanned(
+            item,
+            format!(
+                "#[senax(with)] cannot be combined with encrypt or flat_keys on field '{}'",
+                field_name
+            ),
+        ));
+    }
+
+    let explicit_id = id.is_some() || rename.is_some();
+    if rename.is_none() {
+        rename = rename_all.map(|rule| rule.apply(field_name));
+    }
+
     // ID calculation: Use explicit ID if provided, otherwise calculate CRC64 from rename or field name
     let calculated_id = id.unwrap_or_else(|| {
         let name_for_id = if let Some(ref rename_val) = rename {
@@ -362,16 +1235,197 @@ fn get_field_attributes(attrs: &[Attribute], field_name: &str) -> FieldAttribute
         } else {
             field_name
         };
-        calculate_id_from_name(name_for_id)
+        calculate_id_from_name(name_for_id, id_salt)
     });
+    // A mapping file overrides every other ID source, so it counts as explicit
+    let (calculated_id, explicit_id) = match pins {
+        Some(pins) => (pins.pinned_id(item, field_name, id, calculated_id)?, true),
+        None => (calculated_id, explicit_id),
+    };
 
-    FieldAttributes {
+    Ok(FieldAttributes {
         id: calculated_id,
         default,
         skip_encode,
         skip_decode,
         skip_default,
         rename,
+        tolerant,
+        explicit_id,
+        prefix,
+        encrypt,
+        redact,
+        flat_keys,
+        ext_block,
+        sensitive,
+        max_len,
+        sort,
+        min,
+        max,
+        deprecated,
+        skip_encode_if,
+        with,
+    })
+}
+
+/// Compute the discriminant of every enum variant when `#[senax(discriminant_ids)]` is set
+///
+/// Follows Rust's rules: a variant without `= N` takes the previous discriminant plus one,
+/// starting at 0. Only non-negative integer literals are supported.
+///
+/// # Returns
+///
+/// One entry per variant; all `None` when the container attribute is not set.
+fn get_discriminant_ids(
+    e: &syn::DataEnum,
+    container_attrs: &ContainerAttributes,
+) -> Vec<Option<u64>> {
+    if !container_attrs.discriminant_ids {
+        return vec![None; e.variants.len()];
+    }
+    let mut next = 0u64;
+    e.variants
+        .iter()
+        .map(|v| {
+            // Discriminants other than integer literals are rejected by `check_input`
+            let value = discriminant_literal(v).ok().flatten().unwrap_or(next);
+            next = value.wrapping_add(1);
+            Some(value)
+        })
+        .collect()
+}
+
+/// The explicit `= N` discriminant of a variant, failing unless it is a non-negative integer
+/// literal
+fn discriminant_literal(v: &syn::Variant) -> syn::Result<Option<u64>> {
+    let Some((_, expr)) = &v.discriminant else {
+        return Ok(None);
+    };
+    match expr {
+        syn::Expr::Lit(syn::ExprLit {
+            lit: syn::Lit::Int(lit),
+            ..
+        }) => lit.base10_parse::<u64>().ok(),
+        _ => None,
+    }
+    .map(Some)
+    .ok_or_else(|| {
+        syn::Error::new_spanned(
+            expr,
+            format!(
+                "Discriminant of variant '{}' must be a non-negative integer literal to be used with #[senax(discriminant_ids)]",
+                v.ident
+            ),
+        )
+    })
+}
+
+/// Reject inputs the derives cannot expand, as compile errors at the offending tokens
+///
+/// Fields typed `Option` must name their inner type (`Option<T>`), with
+/// `#[senax(discriminant_ids)]` every explicit discriminant must be an integer literal, and
+/// `#[senax(accept_named)]` needs a tuple struct whose fields all carry an `id` or `rename`.
+fn check_input(input: &DeriveInput, container_attrs: &ContainerAttributes) -> syn::Result<()> {
+    let fields: Vec<&syn::Field> = match &input.data {
+        Data::Struct(s) => s.fields.iter().collect(),
+        Data::Enum(e) => e.variants.iter().flat_map(|v| v.fields.iter()).collect(),
+        Data::Union(u) => u.fields.named.iter().collect(),
+    };
+    for f in fields {
+        if option_segment(&f.ty).is_some() && extract_inner_type_from_option(&f.ty).is_none() {
+            return Err(syn::Error::new_spanned(
+                &f.ty,
+                "expected `Option<T>`; the senax derives read the inner type of `Option` fields",
+            ));
+        }
+    }
+    if container_attrs.accept_named {
+        let Data::Struct(syn::DataStruct {
+            fields: Fields::Unnamed(fields),
+            ..
+        }) = &input.data
+        else {
+            return Err(syn::Error::new_spanned(
+                &input.ident,
+                "#[senax(accept_named)] is only supported on tuple structs",
+            ));
+        };
+        for (i, f) in fields.unnamed.iter().enumerate() {
+            let attrs = get_field_attributes(
+                f,
+                &f.attrs,
+                &i.to_string(),
+                None,
+                container_attrs.id_salt.as_deref(),
+                None,
+            )?;
+            if !attrs.explicit_id {
+                return Err(syn::Error::new_spanned(
+                    f,
+                    "#[senax(accept_named)] matches named payloads by field ID; give this field #[senax(id=...)] or #[senax(rename=...)]",
+                ));
+            }
+        }
+    }
+    if let Data::Enum(e) = &input.data {
+        if container_attrs.discriminant_ids {
+            for v in &e.variants {
+                discriminant_literal(v)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Resolve the wire ID of an enum variant
+///
+/// An explicit `#[senax(id=...)]`/`rename` wins, then the discriminant (if enabled),
+/// then the CRC64 of the variant name.
+fn resolve_variant_id(variant_attrs: &FieldAttributes, discriminant_id: Option<u64>) -> u64 {
+    match discriminant_id {
+        Some(id) if !variant_attrs.explicit_id => id,
+        _ => variant_attrs.id,
+    }
+}
+
+/// The `#[senax(with)]` proxy of a field, for derives that do not otherwise read field attributes
+fn field_with(f: &syn::Field) -> syn::Result<Option<proc_macro2::TokenStream>> {
+    let name = f
+        .ident
+        .as_ref()
+        .map(ToString::to_string)
+        .unwrap_or_default();
+    Ok(get_field_attributes(f, &f.attrs, &name, None, None, None)?.with)
+}
+
+/// Whether a field is marked `#[senax(sort)]`
+fn field_sort(f: &syn::Field) -> syn::Result<bool> {
+    let name = f
+        .ident
+        .as_ref()
+        .map(ToString::to_string)
+        .unwrap_or_default();
+    Ok(get_field_attributes(f, &f.attrs, &name, None, None, None)?.sort)
+}
+
+/// The type a field is written as: its own type, or the representation of its
+/// `#[senax(with)]` proxy (inside the `Option` for optional fields)
+fn wire_type(
+    f: &syn::Field,
+    attrs: &FieldAttributes,
+    krate: &syn::Path,
+) -> proc_macro2::TokenStream {
+    let ty = &f.ty;
+    match &attrs.with {
+        Some(with) => {
+            let repr = quote! { <#with as #krate::remote::Remote>::Repr };
+            if is_option_type(ty) {
+                quote! { ::core::option::Option<#repr> }
+            } else {
+                repr
+            }
+        }
+        None => quote! { #ty },
     }
 }
 
@@ -379,15 +1433,7 @@ fn get_field_attributes(attrs: &[Attribute], field_name: &str) -> FieldAttribute
 ///
 /// This helper function determines whether a given type is wrapped in an `Option`.
 fn is_option_type(ty: &Type) -> bool {
-    if let Type::Path(type_path) = ty {
-        type_path
-            .path
-            .segments
-            .last()
-            .is_some_and(|seg| seg.ident == "Option")
-    } else {
-        false
-    }
+    extract_inner_type_from_option(ty).is_some()
 }
 
 /// Extract the inner type `T` from `Option<T>`
@@ -395,23 +1441,51 @@ fn is_option_type(ty: &Type) -> bool {
 /// This helper function extracts the wrapped type from an `Option` type.
 /// Returns `None` if the type is not an `Option`.
 fn extract_inner_type_from_option(ty: &Type) -> Option<&Type> {
-    if let Type::Path(type_path) = ty {
-        if type_path
+    let segment = option_segment(ty)?;
+    if let PathArguments::AngleBracketed(args) = &segment.arguments {
+        if let Some(GenericArgument::Type(inner_ty)) = args.args.first() {
+            return Some(inner_ty);
+        }
+    }
+    None
+}
+
+/// The last path segment of `ty` if it is named `Option`, whatever its arguments
+fn option_segment(ty: &Type) -> Option<&syn::PathSegment> {
+    match ty {
+        Type::Path(type_path) => type_path
             .path
             .segments
             .last()
-            .is_some_and(|seg| seg.ident == "Option")
-        {
-            if let PathArguments::AngleBracketed(args) =
-                &type_path.path.segments.last().unwrap().arguments
-            {
-                if let Some(GenericArgument::Type(inner_ty)) = args.args.first() {
-                    return Some(inner_ty);
-                }
-            }
+            .filter(|seg| seg.ident == "Option"),
+        _ => None,
+    }
+}
+
+/// Denies clippy's panicking lints on every impl in a derive's output
+///
+/// Generated code reports malformed input as `EncoderError` and never unwraps, so it builds
+/// in crates that deny these lints (or enable senax-encoder's `panic-free` feature) and any
+/// regression in the derive fails their clippy runs instead of slipping through.
+fn deny_panics(tokens: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+    let Ok(mut file) = syn::parse2::<syn::File>(tokens.clone()) else {
+        return tokens;
+    };
+    for item in &mut file.items {
+        if let syn::Item::Impl(item) = item {
+            item.attrs.push(syn::parse_quote! {
+                #[deny(
+                    clippy::unwrap_used,
+                    clippy::expect_used,
+                    clippy::panic,
+                    clippy::unreachable,
+                    clippy::todo,
+                    clippy::unimplemented
+                )]
+            });
         }
     }
-    None
+    quote!(#file)
 }
 
 /// Derive macro for implementing the `Encode` trait
@@ -423,12 +1497,34 @@ fn extract_inner_type_from_option(ty: &Type) -> Option<&Type> {
 /// # Supported Attributes
 ///
 /// ## Container-level attributes:
-/// * `#[senax(disable_encode)]` - Generate stub implementation (unimplemented!() only) for Encode/Decode
+/// * `#[senax(disable_encode)]` - Generate stub implementation (returning an error) for Encode/Decode
+/// * `#[senax(crate = "path")]` - Path to senax-encoder used in generated code
+/// * `#[senax(discriminant_ids)]` - Use explicit enum discriminants as variant IDs
+/// * `#[senax(string_repr)]` - Encode unit enum variants as their name string
+/// * `#[senax(rename_all = "camelCase")]` - Convert field/variant names to a naming convention before hashing
+/// * `#[senax(id_salt = "v2")]` - Mix a salt into every field/variant ID hashed from a name
+/// * `#[senax(ids_file = "senax-ids.toml")]` - Take every field/variant ID from a mapping file, failing on unpinned names
+/// * `#[senax(bound = "T: ...")]` - Replace the automatic `T: Encoder`/`T: Decoder` bounds on type parameters
+/// * `#[senax(recursive)]` - Limit the decode nesting depth of a mutually recursive type (self-referencing types are detected)
+/// * `#[senax(max_size)]` - Also implement `MaxEncodedSize` with a compile-time size bound
+/// * `#[senax(wire_info)]` - Also implement `WireInfo` listing the field and variant IDs
+/// * `#[senax(schema)]` - Also implement `HasSchema` describing field names, IDs, types and doc comments
+/// * `#[senax(union_as = "View")]` - Encode a union by converting it to its tagged view type
 ///
 /// ## Field-level attributes:
 /// * `#[senax(id=N)]` - Set explicit field/variant ID
 /// * `#[senax(skip_encode)]` - Skip field during encoding
+/// * `#[senax(skip)]` - Skip field during encoding and decoding
+/// * `#[senax(skip_encode_if = "path::to::fn")]` - Skip field during encoding when `fn(&T) -> bool` returns `true`
+/// * `#[senax(with = "Proxy")]` - Encode the field through a `senax_remote!` proxy
 /// * `#[senax(rename="name")]` - Use alternative name for ID calculation
+/// * `#[senax(encrypt)]` - Seal the field's encoded bytes with the installed field key
+/// * `#[senax(redact)]` - Write the field as its default value when `EncodeConfig::redact` is set
+/// * `#[senax(flat_keys)]` - Write a tuple-keyed map without the tuple header on every key
+/// * `#[senax(ext_block)]` - Mark an `ExtBlock` field of vendor extensions (omitted when empty, empty when missing)
+/// * `#[senax(max_len = N)]` - Fail when a string/collection field holds more than `N` elements; bounds `MaxEncodedSize`
+/// * `#[senax(sort)]` - Write a sequence/set field's elements in ascending order, for deterministic payloads
+/// * `#[senax(deprecated)]` - Stop writing the field (same as skip_encode for encode)
 ///
 /// # Examples
 ///
@@ -452,20 +1548,42 @@ fn extract_inner_type_from_option(ty: &Type) -> Option<&Type> {
 #[proc_macro_derive(Encode, attributes(senax))]
 pub fn derive_encode(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
+    deny_panics(expand_encode(&input).unwrap_or_else(syn::Error::into_compile_error)).into()
+}
+
+fn expand_encode(input: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
     let name = &input.ident;
     let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
 
     // Check for container-level disable_encode attribute
-    let container_attrs = get_container_attributes(&input.attrs);
+    let container_attrs = get_container_attributes(&input.attrs)?;
+    check_input(input, &container_attrs)?;
+    let krate = &container_attrs.krate;
     if container_attrs.disable_encode {
-        return TokenStream::from(quote! {
-            impl #impl_generics senax_encoder::Encoder for #name #ty_generics #where_clause {
-                fn encode(&self, _writer: &mut bytes::BytesMut) -> senax_encoder::Result<()> {
-                    unimplemented!("Encode trait is disabled for {}", stringify!(#name))
+        return Ok(quote! {
+            impl #impl_generics #krate::Encoder for #name #ty_generics #where_clause {
+                fn encode(&self, _writer: &mut #krate::bytes::BytesMut) -> #krate::Result<()> {
+                    Err(#krate::EncoderError::Encode(
+                        concat!("Encode trait is disabled for ", stringify!(#name)).into(),
+                    ))
+                }
+
+                fn is_default(&self) -> bool {
+                    false
+                }
+            }
+        });
+    }
+
+    if let Some(view) = union_view(input, &container_attrs)? {
+        return Ok(quote! {
+            impl #impl_generics #krate::Encoder for #name #ty_generics #where_clause {
+                fn encode(&self, writer: &mut #krate::bytes::BytesMut) -> #krate::Result<()> {
+                    #krate::Encoder::encode(&<#view as ::core::convert::From<&Self>>::from(self), writer)
                 }
 
                 fn is_default(&self) -> bool {
-                    unimplemented!("Encode trait is disabled for {}", stringify!(#name))
+                    #krate::Encoder::is_default(&<#view as ::core::convert::From<&Self>>::from(self))
                 }
             }
         });
@@ -480,7 +1598,14 @@ pub fn derive_encode(input: TokenStream) -> TokenStream {
                 let mut used_ids_struct = HashSet::new();
                 for f in &fields.named {
                     let field_name_str = f.ident.as_ref().unwrap().to_string();
-                    let field_attrs = get_field_attributes(&f.attrs, &field_name_str);
+                    let field_attrs = get_field_attributes(
+                        f,
+                        &f.attrs,
+                        &field_name_str,
+                        container_attrs.rename_all,
+                        container_attrs.id_salt.as_deref(),
+                        container_attrs.pins(name, None).as_ref(),
+                    )?;
 
                     // Skip fields marked with skip_encode
                     if field_attrs.skip_encode {
@@ -488,40 +1613,110 @@ pub fn derive_encode(input: TokenStream) -> TokenStream {
                     }
 
                     if !used_ids_struct.insert(field_attrs.id) {
-                        panic!("Field ID (0x{:016X}) is duplicated for struct '{}'. Please specify a different ID for field '{}' using #[senax(id=...)].", field_attrs.id, name, field_name_str);
+                        return Err(syn::Error::new_spanned(
+                            f,
+                            format!("Field ID (0x{:016X}) is duplicated for struct '{}'. Please specify a different ID for field '{}' using #[senax(id=...)].", field_attrs.id, name, field_name_str),
+                        ));
                     }
 
                     let field_ident = &f.ident;
                     let ty = &f.ty;
                     let is_option = is_option_type(ty);
                     let field_id = field_attrs.id;
+                    let write_id = write_field_id_and_name(
+                        krate,
+                        field_id,
+                        field_attrs.rename.as_deref().unwrap_or(&field_name_str),
+                    );
+                    let write_value = |value: proc_macro2::TokenStream| {
+                        let check_len = field_attrs.max_len.map(|max_len| {
+                            quote! { #krate::max_size::check_max_len(#value, #max_len)?; }
+                        });
+                        let write = if field_attrs.encrypt {
+                            quote! { #krate::field_crypto::encode_encrypted_field(#value, #field_id, writer)?; }
+                        } else if field_attrs.flat_keys {
+                            quote! { #krate::core::encode_flat_key_map(#value, writer)?; }
+                        } else if field_attrs.ext_block {
+                            quote! { <#krate::ext_block::ExtBlock as #krate::Encoder>::encode(#value, writer)?; }
+                        } else if let Some(with) = &field_attrs.with {
+                            quote! { #krate::remote::encode::<#with>(#value, writer)?; }
+                        } else if field_attrs.sort {
+                            quote! { #krate::core::encode_sorted(#value, writer)?; }
+                        } else {
+                            quote! { #krate::Encoder::encode(#value, writer)?; }
+                        };
+                        quote! { #check_len #write }
+                    };
+                    // Redacted skip_default fields are omitted rather than written as their default
+                    let not_redacted = if field_attrs.redact {
+                        quote! { && !#krate::current_encode_config().redact }
+                    } else {
+                        quote! {}
+                    };
 
-                    if is_option {
-                        field_encode.push(quote! {
+                    let encode_field = if is_option {
+                        let write_val = write_value(quote! { val });
+                        let write_option = quote! {
                             if let Some(val) = &self.#field_ident {
-                                senax_encoder::core::write_field_id_optimized(writer, #field_id)?;
-                                senax_encoder::Encoder::encode(&val, writer)?;
+                                #write_id
+                                #write_val
                             }
-                        });
+                        };
+                        if field_attrs.redact {
+                            quote! {
+                                if !#krate::current_encode_config().redact {
+                                    #write_option
+                                }
+                            }
+                        } else {
+                            write_option
+                        }
                     } else if field_attrs.skip_default {
                         // For skip_default fields, check if the value is default before encoding
-                        field_encode.push(quote! {
-                            if senax_encoder::Encoder::is_default(&self.#field_ident) == false {
-                                senax_encoder::core::write_field_id_optimized(writer, #field_id)?;
-                                senax_encoder::Encoder::encode(&self.#field_ident, writer)?;
+                        let write_field = write_value(quote! { &self.#field_ident });
+                        let is_default = match &field_attrs.with {
+                            Some(with) => {
+                                quote! { #krate::remote::is_default::<#with>(&self.#field_ident) }
                             }
-                        });
+                            None => quote! { #krate::Encoder::is_default(&self.#field_ident) },
+                        };
+                        quote! {
+                            if #is_default == false #not_redacted {
+                                #write_id
+                                #write_field
+                            }
+                        }
+                    } else if field_attrs.redact {
+                        let write_field = write_value(quote! { &self.#field_ident });
+                        let write_default = write_value(quote! { &<#ty as Default>::default() });
+                        quote! {
+                            #write_id
+                            if #krate::current_encode_config().redact {
+                                #write_default
+                            } else {
+                                #write_field
+                            }
+                        }
                     } else {
-                        field_encode.push(quote! {
-                            senax_encoder::core::write_field_id_optimized(writer, #field_id)?;
-                            senax_encoder::Encoder::encode(&self.#field_ident, writer)?;
-                        });
-                    }
+                        let write_field = write_value(quote! { &self.#field_ident });
+                        quote! {
+                            #write_id
+                            #write_field
+                        }
+                    };
+                    field_encode.push(match &field_attrs.skip_encode_if {
+                        Some(predicate) => quote! {
+                            if !#predicate(&self.#field_ident) {
+                                #encode_field
+                            }
+                        },
+                        None => encode_field,
+                    });
                 }
                 quote! {
-                    writer.put_u8(senax_encoder::core::TAG_STRUCT_NAMED);
+                    writer.put_u8(#krate::core::TAG_STRUCT_NAMED);
                     #(#field_encode)*
-                    senax_encoder::core::write_field_id_optimized(writer, 0)?;
+                    #krate::core::write_field_id_optimized(writer, 0)?;
                 }
             }
             Fields::Unnamed(fields) => {
@@ -529,35 +1724,51 @@ pub fn derive_encode(input: TokenStream) -> TokenStream {
                 let field_encode = fields.unnamed.iter().enumerate().map(|(i, _)| {
                     let index = syn::Index::from(i);
                     quote! {
-                        senax_encoder::Encoder::encode(&self.#index, writer)?;
+                        #krate::Encoder::encode(&self.#index, writer)?;
                     }
                 });
                 quote! {
-                    writer.put_u8(senax_encoder::core::TAG_STRUCT_UNNAMED);
+                    writer.put_u8(#krate::core::TAG_STRUCT_UNNAMED);
                     let count: usize = #field_count;
-                    senax_encoder::Encoder::encode(&count, writer)?;
+                    #krate::Encoder::encode(&count, writer)?;
                     #(#field_encode)*
                 }
             }
             Fields::Unit => quote! {
-                writer.put_u8(senax_encoder::core::TAG_STRUCT_UNIT);
+                writer.put_u8(#krate::core::TAG_STRUCT_UNIT);
             },
         },
         Data::Enum(e) => {
             let mut variant_encode = Vec::new();
             let mut used_ids_enum = HashSet::new();
 
-            for v in &e.variants {
+            let discriminant_ids = get_discriminant_ids(e, &container_attrs);
+            for (v, discriminant_id) in e.variants.iter().zip(discriminant_ids) {
                 let variant_name_str = v.ident.to_string();
-                let variant_attrs = get_field_attributes(&v.attrs, &variant_name_str);
-                let variant_id = variant_attrs.id;
+                let variant_attrs = get_field_attributes(
+                    v,
+                    &v.attrs,
+                    &variant_name_str,
+                    container_attrs.rename_all,
+                    container_attrs.id_salt.as_deref(),
+                    container_attrs.pins(name, None).as_ref(),
+                )?;
+                let variant_id = resolve_variant_id(&variant_attrs, discriminant_id);
                 let is_default_variant = has_default_attribute(&v.attrs);
 
                 if !used_ids_enum.insert(variant_id) {
-                    panic!("Variant ID (0x{:016X}) is duplicated for enum '{}'. Please specify a different ID for variant '{}' using #[senax(id=...)].", variant_id, name, variant_name_str);
+                    return Err(syn::Error::new_spanned(
+                        v,
+                        format!("Variant ID (0x{:016X}) is duplicated for enum '{}'. Please specify a different ID for variant '{}' using #[senax(id=...)].", variant_id, name, variant_name_str),
+                    ));
                 }
 
                 let variant_ident = &v.ident;
+                let write_variant_id = write_field_id_and_name(
+                    krate,
+                    variant_id,
+                    variant_attrs.rename.as_deref().unwrap_or(&variant_name_str),
+                );
 
                 // Generate is_default check for this variant if it has #[default] attribute
                 if is_default_variant {
@@ -571,7 +1782,7 @@ pub fn derive_encode(input: TokenStream) -> TokenStream {
                             let field_default_checks: Vec<_> = field_idents
                                 .iter()
                                 .map(|ident| {
-                                    quote! { senax_encoder::Encoder::is_default(#ident) }
+                                    quote! { #krate::Encoder::is_default(#ident) }
                                 })
                                 .collect();
 
@@ -595,7 +1806,7 @@ pub fn derive_encode(input: TokenStream) -> TokenStream {
                             let field_default_checks: Vec<_> = field_bindings
                                 .iter()
                                 .map(|binding| {
-                                    quote! { senax_encoder::Encoder::is_default(#binding) }
+                                    quote! { #krate::Encoder::is_default(#binding) }
                                 })
                                 .collect();
 
@@ -621,57 +1832,78 @@ pub fn derive_encode(input: TokenStream) -> TokenStream {
 
                 match &v.fields {
                     Fields::Named(fields) => {
-                        let field_idents: Vec<_> = fields
-                            .named
-                            .iter()
-                            .map(|f| f.ident.as_ref().unwrap())
-                            .collect();
+                        let mut field_patterns = Vec::new();
                         let mut field_encode = Vec::new();
                         let mut used_ids_struct = HashSet::new();
                         for f in &fields.named {
                             let field_name_str = f.ident.as_ref().unwrap().to_string();
-                            let field_attrs = get_field_attributes(&f.attrs, &field_name_str);
+                            let field_attrs = get_field_attributes(
+                                f,
+                                &f.attrs,
+                                &field_name_str,
+                                None,
+                                container_attrs.id_salt.as_deref(),
+                                container_attrs.pins(name, Some(variant_ident)).as_ref(),
+                            )?;
 
-                            // Skip fields marked with skip_encode
+                            // Skip fields marked with skip_encode, without binding them
+                            let field_ident = f.ident.as_ref().unwrap();
                             if field_attrs.skip_encode {
+                                field_patterns.push(quote! { #field_ident: _ });
                                 continue;
                             }
+                            field_patterns.push(quote! { #field_ident });
 
                             if !used_ids_struct.insert(field_attrs.id) {
-                                panic!("Field ID (0x{:016X}) is duplicated for enum variant '{}'. Please specify a different ID for field '{}' using #[senax(id=...)].", field_attrs.id, variant_ident, field_name_str);
+                                return Err(syn::Error::new_spanned(
+                                    f,
+                                    format!("Field ID (0x{:016X}) is duplicated for enum variant '{}'. Please specify a different ID for field '{}' using #[senax(id=...)].", field_attrs.id, variant_ident, field_name_str),
+                                ));
                             }
-                            let field_ident = &f.ident;
                             let ty = &f.ty;
                             let is_option = is_option_type(ty);
                             let field_id = field_attrs.id;
-                            if is_option {
-                                field_encode.push(quote! {
+                            let write_id = write_field_id_and_name(
+                                krate,
+                                field_id,
+                                field_attrs.rename.as_deref().unwrap_or(&field_name_str),
+                            );
+                            let encode_field = if is_option {
+                                quote! {
                                     if let Some(val) = #field_ident {
-                                        senax_encoder::core::write_field_id_optimized(writer, #field_id)?;
-                                        senax_encoder::Encoder::encode(&val, writer)?;
+                                        #write_id
+                                        #krate::Encoder::encode(&val, writer)?;
                                     }
-                                });
+                                }
                             } else if field_attrs.skip_default {
                                 // For skip_default fields, check if the value is default before encoding
-                                field_encode.push(quote! {
-                                    if senax_encoder::Encoder::is_default(#field_ident) == false {
-                                        senax_encoder::core::write_field_id_optimized(writer, #field_id)?;
-                                        senax_encoder::Encoder::encode(&#field_ident, writer)?;
+                                quote! {
+                                    if #krate::Encoder::is_default(#field_ident) == false {
+                                        #write_id
+                                        #krate::Encoder::encode(&#field_ident, writer)?;
                                     }
-                                });
+                                }
                             } else {
-                                field_encode.push(quote! {
-                                    senax_encoder::core::write_field_id_optimized(writer, #field_id)?;
-                                    senax_encoder::Encoder::encode(&#field_ident, writer)?;
-                                });
-                            }
-                        }
-                        variant_encode.push(quote! {
-                            #name::#variant_ident { #(#field_idents),* } => {
-                                writer.put_u8(senax_encoder::core::TAG_ENUM_NAMED);
-                                senax_encoder::core::write_field_id_optimized(writer, #variant_id)?;
+                                quote! {
+                                    #write_id
+                                    #krate::Encoder::encode(&#field_ident, writer)?;
+                                }
+                            };
+                            field_encode.push(match &field_attrs.skip_encode_if {
+                                Some(predicate) => quote! {
+                                    if !#predicate(#field_ident) {
+                                        #encode_field
+                                    }
+                                },
+                                None => encode_field,
+                            });
+                        }
+                        variant_encode.push(quote! {
+                            #name::#variant_ident { #(#field_patterns),* } => {
+                                writer.put_u8(#krate::core::TAG_ENUM_NAMED);
+                                #write_variant_id
                                 #(#field_encode)*
-                                senax_encoder::core::write_field_id_optimized(writer, 0)?;
+                                #krate::core::write_field_id_optimized(writer, 0)?;
                             }
                         });
                     }
@@ -683,21 +1915,29 @@ pub fn derive_encode(input: TokenStream) -> TokenStream {
                         let field_bindings_ref = &field_bindings;
                         variant_encode.push(quote! {
                             #name::#variant_ident( #(#field_bindings_ref),* ) => {
-                                writer.put_u8(senax_encoder::core::TAG_ENUM_UNNAMED);
-                                senax_encoder::core::write_field_id_optimized(writer, #variant_id)?;
+                                writer.put_u8(#krate::core::TAG_ENUM_UNNAMED);
+                                #write_variant_id
                                 let count: usize = #field_count;
-                                senax_encoder::Encoder::encode(&count, writer)?;
+                                #krate::Encoder::encode(&count, writer)?;
                                 #(
-                                    senax_encoder::Encoder::encode(&#field_bindings_ref, writer)?;
+                                    #krate::Encoder::encode(&#field_bindings_ref, writer)?;
                                 )*
                             }
                         });
                     }
+                    Fields::Unit if container_attrs.string_repr => {
+                        let wire_name = variant_attrs.rename.as_ref().unwrap_or(&variant_name_str);
+                        variant_encode.push(quote! {
+                            #name::#variant_ident => {
+                                #krate::core::write_str(#wire_name, writer)?;
+                            }
+                        });
+                    }
                     Fields::Unit => {
                         variant_encode.push(quote! {
                             #name::#variant_ident => {
-                                writer.put_u8(senax_encoder::core::TAG_ENUM);
-                                senax_encoder::core::write_field_id_optimized(writer, #variant_id)?;
+                                writer.put_u8(#krate::core::TAG_ENUM);
+                                #write_variant_id
                             }
                         });
                     }
@@ -709,7 +1949,7 @@ pub fn derive_encode(input: TokenStream) -> TokenStream {
                 }
             }
         }
-        Data::Union(_) => unimplemented!("Unions are not supported"),
+        Data::Union(_) => return Err(union_without_view(input)),
     };
 
     let is_default_impl = match &input.data {
@@ -729,8 +1969,8 @@ pub fn derive_encode(input: TokenStream) -> TokenStream {
     };
 
     let encode_method = quote! {
-        fn encode(&self, writer: &mut bytes::BytesMut) -> senax_encoder::Result<()> {
-            use bytes::{Buf, BufMut};
+        fn encode(&self, writer: &mut #krate::bytes::BytesMut) -> #krate::Result<()> {
+            use #krate::bytes::{Buf, BufMut};
             #encode_fields
             Ok(())
         }
@@ -740,10 +1980,528 @@ pub fn derive_encode(input: TokenStream) -> TokenStream {
         }
     };
 
-    TokenStream::from(quote! {
-        impl #impl_generics senax_encoder::Encoder for #name #ty_generics #where_clause {
+    let max_size_impl = if container_attrs.max_size {
+        generate_max_encoded_size(input, &container_attrs)?
+    } else {
+        quote! {}
+    };
+
+    let wire_info_impl = if container_attrs.wire_info {
+        generate_wire_info(input, &container_attrs)?
+    } else {
+        quote! {}
+    };
+
+    let schema_impl = if container_attrs.schema {
+        generate_schema(input, &container_attrs)?
+    } else {
+        quote! {}
+    };
+
+    let ids_file_dependency = ids_file_dependency(&container_attrs);
+
+    let where_clause = codec_where_clause(
+        input,
+        &container_attrs,
+        quote! { #krate::Encoder },
+        |attrs| !attrs.skip_encode,
+    )?;
+
+    Ok(quote! {
+        impl #impl_generics #krate::Encoder for #name #ty_generics #where_clause {
             #encode_method
         }
+
+        #ids_file_dependency
+        #max_size_impl
+        #wire_info_impl
+        #schema_impl
+    })
+}
+
+/// Makes the crate depend on its `ids_file`, so editing the file re-runs the derives
+fn ids_file_dependency(container_attrs: &ContainerAttributes) -> proc_macro2::TokenStream {
+    match &container_attrs.id_pins {
+        Some(pins) => {
+            let path = &pins.path;
+            quote! { const _: &[u8] = include_bytes!(#path); }
+        }
+        None => quote! {},
+    }
+}
+
+/// Generate the `WireInfo` implementation for `#[senax(wire_info)]`
+///
+/// Lists the fields written by the derived `encode` with their IDs (positions for tuple
+/// fields) and source types, and the variants of an enum with their IDs and fields.
+fn generate_wire_info(
+    input: &DeriveInput,
+    container_attrs: &ContainerAttributes,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let name = &input.ident;
+    let krate = &container_attrs.krate;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let wire_fields = |fields: &Fields, rename_all: Option<RenameRule>, variant: Option<&Ident>| {
+        let pins = container_attrs.pins(name, variant);
+        let mut entries = Vec::new();
+        for (i, f) in fields.iter().enumerate() {
+            let field_name_str = f
+                .ident
+                .as_ref()
+                .map_or_else(|| i.to_string(), |ident| ident.to_string());
+            let attrs = get_field_attributes(
+                f,
+                &f.attrs,
+                &field_name_str,
+                rename_all,
+                container_attrs.id_salt.as_deref(),
+                f.ident.as_ref().and(pins.as_ref()),
+            )?;
+            if attrs.skip_encode {
+                continue;
+            }
+            let id = if f.ident.is_some() {
+                attrs.id
+            } else {
+                i as u64
+            };
+            let ty = &f.ty;
+            let type_name = quote!(#ty).to_string().replace(' ', "");
+            entries.push(quote! {
+                #krate::wire_info::WireField {
+                    name: #field_name_str,
+                    id: #id,
+                    type_name: #type_name,
+                }
+            });
+        }
+        syn::Result::Ok(quote! { &[#(#entries),*] })
+    };
+
+    let method = match &input.data {
+        Data::Struct(s) => {
+            let fields = wire_fields(&s.fields, container_attrs.rename_all, None)?;
+            quote! {
+                fn wire_fields() -> &'static [#krate::wire_info::WireField] {
+                    #fields
+                }
+            }
+        }
+        Data::Enum(e) => {
+            let discriminant_ids = get_discriminant_ids(e, container_attrs);
+            let variants: Vec<_> = e
+                .variants
+                .iter()
+                .zip(discriminant_ids)
+                .map(|(v, discriminant_id)| {
+                    let variant_name_str = v.ident.to_string();
+                    let variant_attrs = get_field_attributes(
+                        v,
+                        &v.attrs,
+                        &variant_name_str,
+                        container_attrs.rename_all,
+                        container_attrs.id_salt.as_deref(),
+                        container_attrs.pins(name, None).as_ref(),
+                    )?;
+                    let variant_id = resolve_variant_id(&variant_attrs, discriminant_id);
+                    let fields = wire_fields(&v.fields, None, Some(&v.ident))?;
+                    Ok(quote! {
+                        #krate::wire_info::WireVariant {
+                            name: #variant_name_str,
+                            id: #variant_id,
+                            fields: #fields,
+                        }
+                    })
+                })
+                .collect::<syn::Result<_>>()?;
+            quote! {
+                fn wire_variants() -> &'static [#krate::wire_info::WireVariant] {
+                    &[#(#variants),*]
+                }
+            }
+        }
+        Data::Union(_) => return Err(union_without_view(input)),
+    };
+
+    Ok(quote! {
+        impl #impl_generics #krate::wire_info::WireInfo for #name #ty_generics #where_clause {
+            #method
+        }
+    })
+}
+
+/// Generate the `HasSchema` implementation for `#[senax(schema)]`
+///
+/// Describes the fields written by the derived `encode` with their wire names and IDs, and
+/// the variants of an enum. Field types contribute their own schemas, so type parameters are
+/// bounded by `HasSchema`.
+fn generate_schema(
+    input: &DeriveInput,
+    container_attrs: &ContainerAttributes,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let name = &input.ident;
+    let name_str = name.to_string();
+    let krate = &container_attrs.krate;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    // Encrypted fields are written as sealed binary data
+    let field_schema = |f: &syn::Field, attrs: &FieldAttributes| {
+        if attrs.encrypt {
+            return quote! { #krate::schema::TypeSchema::Bytes };
+        }
+        let ty = wire_type(f, attrs, krate);
+        quote! { <#ty as #krate::schema::HasSchema>::schema() }
+    };
+
+    let named_fields = |fields: &syn::FieldsNamed,
+                        struct_name: &str,
+                        rename_all: Option<RenameRule>,
+                        variant: Option<&Ident>| {
+        let pins = container_attrs.pins(name, variant);
+        let mut entries = Vec::new();
+        for f in &fields.named {
+            let field_name_str = f.ident.as_ref().unwrap().to_string();
+            let attrs = get_field_attributes(
+                f,
+                &f.attrs,
+                &field_name_str,
+                rename_all,
+                container_attrs.id_salt.as_deref(),
+                pins.as_ref(),
+            )?;
+            if attrs.skip_encode {
+                continue;
+            }
+            let wire_name = attrs.rename.as_ref().unwrap_or(&field_name_str);
+            let id = attrs.id;
+            let ty = field_schema(f, &attrs);
+            let with_default = if attrs.default || attrs.skip_default || attrs.skip_decode {
+                quote! { .with_default() }
+            } else {
+                quote! {}
+            };
+            // Doc comments only annotate the schema; they are never written to payloads
+            let with_doc = match doc_comment(&f.attrs) {
+                Some(doc) => quote! { .with_doc(#doc) },
+                None => quote! {},
+            };
+            entries.push(quote! {
+                #krate::schema::FieldSchema::new(#wire_name, #ty).with_id(#id) #with_default #with_doc
+            });
+        }
+        syn::Result::Ok(quote! {
+            #krate::schema::TypeSchema::Struct(#krate::schema::StructSchema::new(
+                #struct_name,
+                vec![#(#entries),*],
+            ))
+        })
+    };
+
+    let unnamed_fields =
+        |fields: &syn::FieldsUnnamed| -> syn::Result<Vec<proc_macro2::TokenStream>> {
+            let mut types = Vec::new();
+            for (i, f) in fields.unnamed.iter().enumerate() {
+                let attrs = get_field_attributes(
+                    f,
+                    &f.attrs,
+                    &i.to_string(),
+                    None,
+                    container_attrs.id_salt.as_deref(),
+                    None,
+                )?;
+                if !attrs.skip_encode {
+                    types.push(field_schema(f, &attrs));
+                }
+            }
+            Ok(types)
+        };
+
+    let schema = match &input.data {
+        Data::Struct(s) => match &s.fields {
+            Fields::Named(fields) => {
+                named_fields(fields, &name_str, container_attrs.rename_all, None)?
+            }
+            Fields::Unnamed(fields) => {
+                let types = unnamed_fields(fields)?;
+                quote! {
+                    #krate::schema::TypeSchema::TupleStruct(#krate::schema::TupleStructSchema {
+                        name: #name_str.to_string(),
+                        fields: vec![#(#types),*],
+                    })
+                }
+            }
+            Fields::Unit => quote! {
+                #krate::schema::TypeSchema::TupleStruct(#krate::schema::TupleStructSchema {
+                    name: #name_str.to_string(),
+                    fields: Vec::new(),
+                })
+            },
+        },
+        Data::Enum(e) => {
+            let discriminant_ids = get_discriminant_ids(e, container_attrs);
+            let variants: Vec<_> = e
+                .variants
+                .iter()
+                .zip(discriminant_ids)
+                .map(|(v, discriminant_id)| {
+                    let variant_name_str = v.ident.to_string();
+                    let variant_attrs = get_field_attributes(
+                        v,
+                        &v.attrs,
+                        &variant_name_str,
+                        container_attrs.rename_all,
+                        container_attrs.id_salt.as_deref(),
+                        container_attrs.pins(name, None).as_ref(),
+                    )?;
+                    let variant_id = resolve_variant_id(&variant_attrs, discriminant_id);
+                    let wire_name = variant_attrs.rename.as_ref().unwrap_or(&variant_name_str);
+                    let fields = match &v.fields {
+                        Fields::Named(fields) => {
+                            let fields = named_fields(fields, wire_name, None, Some(&v.ident))?;
+                            quote! { Some(#fields) }
+                        }
+                        Fields::Unnamed(fields) => {
+                            let types = unnamed_fields(fields)?;
+                            quote! { Some(#krate::schema::TypeSchema::Tuple(vec![#(#types),*])) }
+                        }
+                        Fields::Unit => quote! { None },
+                    };
+                    Ok(quote! {
+                        #krate::schema::VariantSchema {
+                            name: #wire_name.to_string(),
+                            id: #variant_id,
+                            fields: #fields,
+                        }
+                    })
+                })
+                .collect::<syn::Result<_>>()?;
+            let string_repr = container_attrs.string_repr;
+            quote! {
+                #krate::schema::TypeSchema::Enum(#krate::schema::EnumSchema {
+                    name: #name_str.to_string(),
+                    variants: vec![#(#variants),*],
+                    string_repr: #string_repr,
+                })
+            }
+        }
+        Data::Union(_) => return Err(union_without_view(input)),
+    };
+
+    let mut predicates: Vec<proc_macro2::TokenStream> = where_clause
+        .map(|w| w.predicates.iter().map(|p| quote! { #p }).collect())
+        .unwrap_or_default();
+    predicates.extend(input.generics.type_params().map(|param| {
+        let ident = &param.ident;
+        quote! { #ident: #krate::schema::HasSchema }
+    }));
+    Ok(quote! {
+        impl #impl_generics #krate::schema::HasSchema for #name #ty_generics
+        where
+            #(#predicates),*
+        {
+            fn schema() -> #krate::schema::TypeSchema {
+                #krate::schema::describe(::std::any::type_name::<Self>(), || #schema)
+            }
+        }
+    })
+}
+
+/// Generate code writing a field or variant ID, followed by its name index when a
+/// self-describing encode is in progress, or its name instead during a named encode
+fn write_field_id_and_name(krate: &syn::Path, id: u64, name: &str) -> proc_macro2::TokenStream {
+    quote! {
+        #krate::self_describing::write_field_header(writer, #id, #name)?;
+    }
+}
+
+/// Returns `(wire name, id)` if a field or variant written by name would not hash to its ID
+/// (explicit `id`, `id_salt`, pinned IDs), so its decoder must map the name itself
+fn name_alias(wire_name: &str, id: u64) -> Option<(String, u64)> {
+    (id != calculate_id_from_name(wire_name, None)).then(|| (wire_name.to_string(), id))
+}
+
+/// Generate code reading a field or variant ID from `reader`, resolving names written by
+/// `encode_named` through `aliases`
+fn read_field_id(
+    krate: &syn::Path,
+    reader: proc_macro2::TokenStream,
+    aliases: &[(String, u64)],
+) -> proc_macro2::TokenStream {
+    if aliases.is_empty() {
+        return quote! { #krate::core::read_field_id_optimized(#reader)? };
+    }
+    let names = aliases.iter().map(|(name, _)| name);
+    let ids = aliases.iter().map(|(_, id)| id);
+    quote! {
+        #krate::core::read_field_id_aliased(#reader, &[#( (#names, #ids) ),*])?
+    }
+}
+
+/// Generate the `MaxEncodedSize` implementation for `#[senax(max_size)]`
+///
+/// The size expression mirrors the layout written by the derived `encode`. Every field type
+/// (or, with `max_len`, every length-bounded field type) becomes a where-clause bound, so an
+/// unbounded field type is reported as a missing trait implementation.
+fn generate_max_encoded_size(
+    input: &DeriveInput,
+    container_attrs: &ContainerAttributes,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let name = &input.ident;
+    let krate = &container_attrs.krate;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let mut bounds = Vec::new();
+
+    // Size of one field value; `named` fields write an `Option`'s inner value without TAG_SOME
+    let value_size = |bounds: &mut Vec<proc_macro2::TokenStream>,
+                      f: &syn::Field,
+                      attrs: &FieldAttributes,
+                      named: bool| {
+        let option_inner = if named {
+            extract_inner_type_from_option(&f.ty)
+        } else {
+            None
+        };
+        let ty = option_inner.unwrap_or(&f.ty);
+        let ty = match &attrs.with {
+            Some(with) => quote! { <#with as #krate::remote::Remote>::Repr },
+            None => quote! { #ty },
+        };
+        let size = if let Some(max_len) = attrs.max_len {
+            bounds.push(quote! { #ty: #krate::max_size::MaxElementSize });
+            quote! {
+                #krate::max_size::max_len_size(
+                    #max_len,
+                    <#ty as #krate::max_size::MaxElementSize>::MAX_ELEMENT_SIZE,
+                )
+            }
+        } else {
+            bounds.push(quote! { #ty: #krate::MaxEncodedSize });
+            quote! { <#ty as #krate::MaxEncodedSize>::MAX_ENCODED_SIZE }
+        };
+        if attrs.encrypt {
+            // TAG_BINARY, the length, a 24-byte nonce and a 16-byte tag around the plaintext
+            quote! { (1 + 9 + 24 + 16 + #size) }
+        } else if attrs.flat_keys {
+            // The key arity after the tag; flat keys are never longer than tagged tuples
+            quote! { (1 + #size) }
+        } else {
+            size
+        }
+    };
+
+    // `[field_id] [value]` pairs followed by the zero terminator
+    let named_fields_size = |bounds: &mut Vec<proc_macro2::TokenStream>,
+                             fields: &syn::FieldsNamed,
+                             variant: Option<&Ident>| {
+        let mut sizes = Vec::new();
+        for f in &fields.named {
+            let field_name_str = f.ident.as_ref().unwrap().to_string();
+            let attrs = get_field_attributes(
+                f,
+                &f.attrs,
+                &field_name_str,
+                container_attrs.rename_all,
+                container_attrs.id_salt.as_deref(),
+                container_attrs.pins(name, variant).as_ref(),
+            )?;
+            if attrs.skip_encode {
+                continue;
+            }
+            let field_id = attrs.id;
+            let size = value_size(bounds, f, &attrs, true);
+            sizes.push(quote! { #krate::max_size::field_id_size(#field_id) + #size });
+        }
+        syn::Result::Ok(quote! { 0 #(+ #sizes)* + 1 })
+    };
+
+    // The values of a tuple struct or variant, in order
+    let unnamed_fields_sizes = |bounds: &mut Vec<proc_macro2::TokenStream>,
+                                fields: &syn::FieldsUnnamed| {
+        let mut sizes = Vec::new();
+        for (i, f) in fields.unnamed.iter().enumerate() {
+            let attrs = get_field_attributes(
+                f,
+                &f.attrs,
+                &i.to_string(),
+                None,
+                container_attrs.id_salt.as_deref(),
+                None,
+            )?;
+            sizes.push(value_size(bounds, f, &attrs, false));
+        }
+        syn::Result::Ok(sizes)
+    };
+
+    let size = match &input.data {
+        Data::Struct(s) => match &s.fields {
+            Fields::Named(fields) => {
+                let fields_size = named_fields_size(&mut bounds, fields, None)?;
+                quote! { 1 + #fields_size }
+            }
+            Fields::Unnamed(fields) => {
+                let field_count = fields.unnamed.len();
+                let sizes = unnamed_fields_sizes(&mut bounds, fields)?;
+                quote! { 1 + #krate::max_size::uint_size(#field_count as u128) #(+ #sizes)* }
+            }
+            Fields::Unit => quote! { 1 },
+        },
+        Data::Enum(e) => {
+            let discriminant_ids = get_discriminant_ids(e, container_attrs);
+            let mut variant_sizes = Vec::new();
+            for (v, discriminant_id) in e.variants.iter().zip(discriminant_ids) {
+                let variant_name_str = v.ident.to_string();
+                let variant_attrs = get_field_attributes(
+                    v,
+                    &v.attrs,
+                    &variant_name_str,
+                    container_attrs.rename_all,
+                    container_attrs.id_salt.as_deref(),
+                    container_attrs.pins(name, None).as_ref(),
+                )?;
+                let variant_id = resolve_variant_id(&variant_attrs, discriminant_id);
+                let header = quote! { 1 + #krate::max_size::field_id_size(#variant_id) };
+                variant_sizes.push(match &v.fields {
+                    Fields::Named(fields) => {
+                        let fields_size = named_fields_size(&mut bounds, fields, Some(&v.ident))?;
+                        quote! { #header + #fields_size }
+                    }
+                    Fields::Unnamed(fields) => {
+                        let field_count = fields.unnamed.len();
+                        let sizes = unnamed_fields_sizes(&mut bounds, fields)?;
+                        quote! {
+                            #header + #krate::max_size::uint_size(#field_count as u128) #(+ #sizes)*
+                        }
+                    }
+                    Fields::Unit if container_attrs.string_repr => {
+                        let wire_name = variant_attrs.rename.as_ref().unwrap_or(&variant_name_str);
+                        // Same layout as `core::write_str`
+                        let len = wire_name.len();
+                        if len <= 40 {
+                            quote! { 1 + #len }
+                        } else {
+                            quote! { #krate::max_size::max_len_size(#len, 1) }
+                        }
+                    }
+                    Fields::Unit => header,
+                });
+            }
+            quote! { #krate::max_size::max_of(&[#(#variant_sizes),*]) }
+        }
+        Data::Union(_) => return Err(union_without_view(input)),
+    };
+
+    let mut predicates: Vec<proc_macro2::TokenStream> = where_clause
+        .map(|w| w.predicates.iter().map(|p| quote! { #p }).collect())
+        .unwrap_or_default();
+    predicates.extend(bounds);
+    Ok(quote! {
+        impl #impl_generics #krate::MaxEncodedSize for #name #ty_generics
+        where
+            #(#predicates),*
+        {
+            const MAX_ENCODED_SIZE: usize = #size;
+        }
     })
 }
 
@@ -756,14 +2514,37 @@ pub fn derive_encode(input: TokenStream) -> TokenStream {
 /// # Supported Attributes
 ///
 /// ## Container-level attributes:
-/// * `#[senax(disable_encode)]` - Generate stub implementation (unimplemented!() only) for Encode/Decode
+/// * `#[senax(disable_encode)]` - Generate stub implementation (returning an error) for Encode/Decode
+/// * `#[senax(accept_tuple)]` - Also decode named structs from tuple struct payloads (by field position)
+/// * `#[senax(accept_named)]` - Also decode tuple structs from named struct payloads (by field ID; every field needs `id` or `rename`)
+/// * `#[senax(crate = "path")]` - Path to senax-encoder used in generated code
+/// * `#[senax(discriminant_ids)]` - Use explicit enum discriminants as variant IDs
+/// * `#[senax(string_repr)]` - Encode unit enum variants as their name string
+/// * `#[senax(rename_all = "camelCase")]` - Convert field/variant names to a naming convention before hashing
+/// * `#[senax(id_salt = "v2")]` - Mix a salt into every field/variant ID hashed from a name
+/// * `#[senax(ids_file = "senax-ids.toml")]` - Take every field/variant ID from a mapping file, failing on unpinned names
+/// * `#[senax(bound = "T: ...")]` - Replace the automatic `T: Encoder`/`T: Decoder` bounds on type parameters
+/// * `#[senax(recursive)]` - Limit the decode nesting depth of a mutually recursive type (self-referencing types are detected)
+/// * `#[senax(builder)]` - Generate `<Name>Builder` and decode through its `try_build`
+/// * `#[senax(union_as = "View")]` - Decode a union as its tagged view type and convert it back
 ///
 /// ## Field-level attributes:
 /// * `#[senax(id=N)]` - Set explicit field/variant ID
 /// * `#[senax(default)]` - Use default value if field is missing
 /// * `#[senax(skip_decode)]` - Skip field during decoding (use default value)
 /// * `#[senax(skip_default)]` - Use default value if field is missing (same as default for decode)
+/// * `#[senax(skip)]` - Skip field during decoding (same as skip_decode for decode)
+/// * `#[senax(skip_encode_if = "path::to::fn")]` - Use default value if field is missing (same as default for decode)
+/// * `#[senax(with = "Proxy")]` - Decode the field through a `senax_remote!` proxy
 /// * `#[senax(rename="name")]` - Use alternative name for ID calculation
+/// * `#[senax(tolerant)]` - On a tuple enum variant, skip extra trailing fields written by newer versions
+/// * `#[senax(encrypt)]` - Decrypt the field with the installed field key
+/// * `#[senax(flat_keys)]` - Read a tuple-keyed map written with or without per-key tuple headers
+/// * `#[senax(ext_block)]` - Read an `ExtBlock` field verbatim (empty when missing)
+/// * `#[senax(sensitive)]` - Hold the decoded value in `Zeroizing` storage, wiping it if decoding fails
+/// * `#[senax(max_len = N)]` - Reject a string/collection field holding more than `N` elements
+/// * `#[senax(min = X, max = Y)]` - Reject a numeric field outside the inclusive range
+/// * `#[senax(deprecated)]` - Still read the field (default if missing) and report each read to `deprecated_fields`
 ///
 /// # Examples
 ///
@@ -789,21 +2570,55 @@ pub fn derive_encode(input: TokenStream) -> TokenStream {
 #[proc_macro_derive(Decode, attributes(senax))]
 pub fn derive_decode(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
+    deny_panics(expand_decode(&input).unwrap_or_else(syn::Error::into_compile_error)).into()
+}
+
+fn expand_decode(input: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
     let name = &input.ident;
     let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
 
     // Check for container-level disable_encode attribute
-    let container_attrs = get_container_attributes(&input.attrs);
+    let container_attrs = get_container_attributes(&input.attrs)?;
+    check_input(input, &container_attrs)?;
+    let krate = &container_attrs.krate;
     if container_attrs.disable_encode {
-        return TokenStream::from(quote! {
-            impl #impl_generics senax_encoder::Decoder for #name #ty_generics #where_clause {
-                fn decode(_reader: &mut bytes::Bytes) -> senax_encoder::Result<Self> {
-                    unimplemented!("Decode trait is disabled for {}", stringify!(#name))
+        return Ok(quote! {
+            impl #impl_generics #krate::Decoder for #name #ty_generics #where_clause {
+                fn decode(_reader: &mut #krate::bytes::Bytes) -> #krate::Result<Self> {
+                    Err(#krate::EncoderError::Decode(
+                        concat!("Decode trait is disabled for ", stringify!(#name)).into(),
+                    ))
+                }
+            }
+        });
+    }
+
+    if let Some(view) = union_view(input, &container_attrs)? {
+        return Ok(quote! {
+            impl #impl_generics #krate::Decoder for #name #ty_generics #where_clause {
+                fn decode(reader: &mut #krate::bytes::Bytes) -> #krate::Result<Self> {
+                    <#view as #krate::Decoder>::decode(reader).map(::core::convert::From::from)
                 }
             }
         });
     }
 
+    // Companion `<Name>Prefix` struct and `decode_prefix` for `#[senax(prefix)]` fields
+    let mut prefix_items = quote! {};
+    // Companion `<Name>Builder` for `#[senax(builder)]`
+    let mut builder_items = quote! {};
+    if container_attrs.builder
+        && !matches!(&input.data, Data::Struct(s) if matches!(s.fields, Fields::Named(_)))
+    {
+        return Err(syn::Error::new_spanned(
+            name,
+            format!(
+                "#[senax(builder)] is only supported on structs with named fields, not '{}'",
+                name
+            ),
+        ));
+    }
+
     let decode_fields = match &input.data {
         Data::Struct(s) => match &s.fields {
             Fields::Named(fields) => {
@@ -813,18 +2628,33 @@ pub fn derive_decode(input: TokenStream) -> TokenStream {
                 let mut field_is_option_flags = Vec::new();
                 let mut field_attrs_list = Vec::new();
                 let mut used_ids_struct_decode = HashMap::new();
+                let mut field_aliases = Vec::new();
 
                 for f in &fields.named {
                     let field_name_str = f.ident.as_ref().unwrap().to_string();
-                    let field_attrs = get_field_attributes(&f.attrs, &field_name_str);
+                    let field_attrs = get_field_attributes(
+                        f,
+                        &f.attrs,
+                        &field_name_str,
+                        container_attrs.rename_all,
+                        container_attrs.id_salt.as_deref(),
+                        container_attrs.pins(name, None).as_ref(),
+                    )?;
 
                     if let Some(dup_field_name) =
                         used_ids_struct_decode.insert(field_attrs.id, field_name_str.clone())
                     {
-                        panic!("Field ID (0x{:016X}) is duplicated for struct '{}'. Please specify a different ID for field '{}' and '{}' using #[senax(id=...)].", 
-                              field_attrs.id, name, dup_field_name, field_name_str);
+                        return Err(syn::Error::new_spanned(
+                            f,
+                            format!("Field ID (0x{:016X}) is duplicated for struct '{}'. Please specify a different ID for field '{}' and '{}' using #[senax(id=...)].",
+                                    field_attrs.id, name, dup_field_name, field_name_str),
+                        ));
                     }
 
+                    field_aliases.extend(name_alias(
+                        field_attrs.rename.as_deref().unwrap_or(&field_name_str),
+                        field_attrs.id,
+                    ));
                     field_idents.push(f.ident.as_ref().unwrap().clone());
                     field_original_types.push(f.ty.clone());
                     field_ids_for_match.push(field_attrs.id);
@@ -832,6 +2662,39 @@ pub fn derive_decode(input: TokenStream) -> TokenStream {
                     field_attrs_list.push(field_attrs);
                 }
 
+                if container_attrs.builder {
+                    // The builder is public and holds plain `Option`s, which are never wiped
+                    if let Some((f, _)) = fields
+                        .named
+                        .iter()
+                        .zip(&field_attrs_list)
+                        .find(|(_, attrs)| attrs.sensitive)
+                    {
+                        return Err(syn::Error::new_spanned(
+                            f,
+                            format!(
+                                "#[senax(sensitive)] fields are not supported with #[senax(builder)] on '{}'",
+                                name
+                            ),
+                        ));
+                    }
+                    builder_items = generate_builder(
+                        input,
+                        &fields.named.iter().collect::<Vec<_>>(),
+                        &field_attrs_list,
+                        krate,
+                    );
+                }
+
+                if field_attrs_list.iter().any(|attrs| attrs.prefix) {
+                    prefix_items = generate_decode_prefix(
+                        input,
+                        &fields.named.iter().collect::<Vec<_>>(),
+                        &field_attrs_list,
+                        krate,
+                    )?;
+                }
+
                 let field_value_definitions = field_idents
                     .iter()
                     .zip(field_original_types.iter())
@@ -841,40 +2704,77 @@ pub fn derive_decode(input: TokenStream) -> TokenStream {
                             // Fields marked with skip_decode don't store values
                             None
                         } else if is_option_type(original_ty) {
-                            Some(quote! { #ident: #original_ty, })
+                            Some(field_value_slot(
+                                ident,
+                                quote! { #original_ty },
+                                attrs,
+                                krate,
+                            ))
                         } else {
-                            Some(quote! { #ident: Option<#original_ty>, })
+                            Some(field_value_slot(
+                                ident,
+                                quote! { Option<#original_ty> },
+                                attrs,
+                                krate,
+                            ))
                         }
-                    });
+                    })
+                    .collect::<Vec<_>>();
 
+                let read_id = read_field_id(krate, quote! { reader }, &field_aliases);
                 let match_arms = field_idents
                     .iter()
                     .zip(field_original_types.iter())
                     .zip(field_ids_for_match.iter())
                     .zip(field_attrs_list.iter())
-                    .filter_map(|(((ident, original_ty), id_val), attrs)| {
+                    .map(|(((ident, original_ty), id_val), attrs)| {
+                        let record_deprecated = if attrs.deprecated {
+                            quote! {
+                                #krate::deprecated_fields::record_deprecated_field(
+                                    stringify!(#name),
+                                    stringify!(#ident),
+                                );
+                            }
+                        } else {
+                            quote! {}
+                        };
                         if attrs.skip_decode {
-                            // Fields marked with skip_decode don't generate match arms (values are skipped)
-                            None
-                        } else if is_option_type(original_ty) {
-                            let inner_ty = extract_inner_type_from_option(original_ty)
-                                .unwrap_or_else(|| {
-                                    panic!(
-                                        "Failed to extract inner type from Option for field {}",
-                                        ident
-                                    )
-                                });
-                            Some(quote! {
+                            // Known to the type, so skipped even when unknown fields are denied
+                            quote! {
+                                x if x == #id_val => { #krate::core::skip_value(reader)?; }
+                            }
+                        } else if let Some(inner_ty) = extract_inner_type_from_option(original_ty) {
+                            let read_value = decode_field_value(
+                                inner_ty,
+                                attrs,
+                                krate,
+                                quote! { reader },
+                                ident,
+                                name,
+                            );
+                            let slot = assign_field_value(ident, attrs);
+                            quote! {
                                 x if x == #id_val => {
-                                    field_values.#ident = Some(<#inner_ty as senax_encoder::Decoder>::decode(reader)?);
+                                    #record_deprecated
+                                    #slot = Some(#read_value);
                                 }
-                            })
+                            }
                         } else {
-                            Some(quote! {
+                            let read_value = decode_field_value(
+                                original_ty,
+                                attrs,
+                                krate,
+                                quote! { reader },
+                                ident,
+                                name,
+                            );
+                            let slot = assign_field_value(ident, attrs);
+                            quote! {
                                 x if x == #id_val => {
-                                    field_values.#ident = Some(<#original_ty as senax_encoder::Decoder>::decode(reader)?);
+                                    #record_deprecated
+                                    #slot = Some(#read_value);
                                 }
-                            })
+                            }
                         }
                     });
 
@@ -889,19 +2789,22 @@ pub fn derive_decode(input: TokenStream) -> TokenStream {
                                 #ident: Default::default(),
                             }
                         } else if *is_opt_flag {
+                            let value = take_field_value(ident, attrs);
                             quote! {
-                                #ident: field_values.#ident,
+                                #ident: #value,
                             }
                         } else if attrs.default || attrs.skip_default {
                             // Fields marked with default or skip_default use default value if missing
+                            let value = take_field_value(ident, attrs);
                             quote! {
-                                #ident: field_values.#ident.unwrap_or_default(),
+                                #ident: #value.unwrap_or_default(),
                             }
                         } else {
+                            let value = take_field_value(ident, attrs);
                             quote! {
-                                #ident: field_values.#ident.ok_or_else(||
-                                    senax_encoder::EncoderError::StructDecode(
-                                        senax_encoder::StructDecodeError::MissingRequiredField {
+                                #ident: #value.ok_or_else(||
+                                    #krate::EncoderError::StructDecode(
+                                        #krate::StructDecodeError::MissingRequiredField {
                                             field: stringify!(#ident),
                                             struct_name: stringify!(#name),
                                         }
@@ -911,41 +2814,108 @@ pub fn derive_decode(input: TokenStream) -> TokenStream {
                         }
                     });
 
-                quote! {
-                    if reader.remaining() == 0 {
-                        return Err(senax_encoder::EncoderError::InsufficientData);
-                    }
-                    let tag = reader.get_u8();
-                    if tag != senax_encoder::core::TAG_STRUCT_NAMED {
-                        return Err(senax_encoder::EncoderError::StructDecode(
-                            senax_encoder::StructDecodeError::InvalidTag {
-                                expected: senax_encoder::core::TAG_STRUCT_NAMED,
-                                actual: tag,
+                // With accept_tuple, the i-th value of a tuple payload fills the i-th declared field
+                let tuple_payload_branch = if container_attrs.accept_tuple {
+                    let position_arms = field_idents
+                        .iter()
+                        .zip(field_original_types.iter())
+                        .zip(field_attrs_list.iter())
+                        .enumerate()
+                        .map(|(i, ((ident, original_ty), attrs))| {
+                            if attrs.skip_decode {
+                                quote! { #i => { #krate::core::skip_value(reader)?; } }
+                            } else if is_option_type(original_ty) {
+                                let slot = assign_field_value(ident, attrs);
+                                quote! {
+                                    #i => { #slot = <#original_ty as #krate::Decoder>::decode(reader)?; }
+                                }
+                            } else {
+                                let slot = assign_field_value(ident, attrs);
+                                quote! {
+                                    #i => { #slot = Some(<#original_ty as #krate::Decoder>::decode(reader)?); }
+                                }
                             }
-                        ));
+                        });
+                    quote! {
+                        else if tag == #krate::core::TAG_STRUCT_UNNAMED {
+                            let count = <usize as #krate::Decoder>::decode(reader)?;
+                            for position in 0..count {
+                                match position {
+                                    #( #position_arms )*
+                                    _ => { #krate::core::skip_value(reader)?; }
+                                }
+                            }
+                        }
                     }
+                } else {
+                    quote! {}
+                };
+
+                // With a builder, the decode loop fills it and `try_build` reports missing fields
+                let (field_values_init, build) = if container_attrs.builder {
+                    let builder_name = Ident::new(&format!("{}Builder", name), name.span());
+                    (
+                        quote! {
+                            let mut field_values = <#builder_name #ty_generics as Default>::default();
+                        },
+                        quote! { field_values.try_build() },
+                    )
+                } else {
+                    (
+                        declare_field_values(&field_value_definitions),
+                        quote! {
+                            Ok(#name {
+                                #( #struct_assignments )*
+                            })
+                        },
+                    )
+                };
 
-                    #[derive(Default)]
-                    struct FieldValues {
-                        #( #field_value_definitions )*
+                quote! {
+                    if reader.remaining() == 0 {
+                        return Err(#krate::EncoderError::UnexpectedEof { needed: 1, available: 0 });
                     }
+                    let tag = reader.get_u8();
 
-                    let mut field_values = FieldValues::default();
+                    #field_values_init
 
-                    loop {
-                        let field_id = senax_encoder::core::read_field_id_optimized(reader)?;
-                        if field_id == 0 {
-                            break;
-                        }
-                        match field_id {
-                            #( #match_arms )*
-                            _unknown_id => { senax_encoder::core::skip_value(reader)?; }
+                    if tag == #krate::core::TAG_STRUCT_NAMED {
+                        loop {
+                            let field_id = #read_id;
+                            if field_id == 0 {
+                                break;
+                            }
+                            match field_id {
+                                #( #match_arms )*
+                                unknown_id => {
+                                    if #krate::current_decode_config().deny_unknown_fields {
+                                        return Err(#krate::EncoderError::StructDecode(
+                                            #krate::StructDecodeError::UnknownField {
+                                                field_id: unknown_id,
+                                                struct_name: stringify!(#name),
+                                            }
+                                        ));
+                                    }
+                                    #krate::unknown_fields::skip_unknown_field(
+                                        reader,
+                                        stringify!(#name),
+                                        unknown_id,
+                                    )?;
+                                }
+                            }
                         }
                     }
+                    #tuple_payload_branch
+                    else {
+                        return Err(#krate::EncoderError::StructDecode(
+                            #krate::StructDecodeError::InvalidTag {
+                                expected: #krate::core::TAG_STRUCT_NAMED,
+                                actual: tag,
+                            }
+                        ));
+                    }
 
-                    Ok(#name {
-                        #( #struct_assignments )*
-                    })
+                    #build
                 }
             }
             Fields::Unnamed(fields) => {
@@ -953,26 +2923,102 @@ pub fn derive_decode(input: TokenStream) -> TokenStream {
                 let field_decode = fields.unnamed.iter().map(|f| {
                     let field_ty = &f.ty;
                     quote! {
-                        <#field_ty as senax_encoder::Decoder>::decode(reader)?
+                        <#field_ty as #krate::Decoder>::decode(reader)?
                     }
                 });
+
+                // With accept_named, named payloads (e.g. written before a named struct became a
+                // tuple struct) are matched by the field IDs given with `id`/`rename`
+                let named_payload_branch = if container_attrs.accept_named {
+                    let mut slot_definitions = Vec::new();
+                    let mut id_arms = Vec::new();
+                    let mut slot_assignments = Vec::new();
+                    let mut used_ids_tuple = HashMap::new();
+                    for (i, f) in fields.unnamed.iter().enumerate() {
+                        let position_str = i.to_string();
+                        let field_attrs = get_field_attributes(
+                            f,
+                            &f.attrs,
+                            &position_str,
+                            None,
+                            container_attrs.id_salt.as_deref(),
+                            None,
+                        )?;
+                        if let Some(dup) = used_ids_tuple.insert(field_attrs.id, i) {
+                            return Err(syn::Error::new_spanned(
+                                f,
+                                format!("Field ID (0x{:016X}) is duplicated for struct '{}'. Please specify a different ID for field '{}' and '{}' using #[senax(id=...)].",
+                                        field_attrs.id, name, dup, i),
+                            ));
+                        }
+                        let slot = Ident::new(&format!("field{}", i), Span::call_site());
+                        let ty = &f.ty;
+                        let inner_ty = extract_inner_type_from_option(ty);
+                        let value_ty = inner_ty.unwrap_or(ty);
+                        let id_val = field_attrs.id;
+                        id_arms.push(quote! {
+                            x if x == #id_val => {
+                                #slot = Some(<#value_ty as #krate::Decoder>::decode(reader)?);
+                            }
+                        });
+                        slot_definitions.push(quote! { let mut #slot: Option<#value_ty> = None; });
+                        slot_assignments.push(if inner_ty.is_some() {
+                            quote! { #slot }
+                        } else if field_attrs.default || field_attrs.skip_default {
+                            quote! { #slot.unwrap_or_default() }
+                        } else {
+                            quote! {
+                                #slot.ok_or_else(||
+                                    #krate::EncoderError::StructDecode(
+                                        #krate::StructDecodeError::MissingRequiredField {
+                                            field: #position_str,
+                                            struct_name: stringify!(#name),
+                                        }
+                                    )
+                                )?
+                            }
+                        });
+                    }
+                    quote! {
+                        if tag == #krate::core::TAG_STRUCT_NAMED {
+                            #( #slot_definitions )*
+                            loop {
+                                let field_id = #krate::core::read_field_id_optimized(reader)?;
+                                if field_id == 0 {
+                                    break;
+                                }
+                                match field_id {
+                                    #( #id_arms )*
+                                    _ => { #krate::core::skip_value(reader)?; }
+                                }
+                            }
+                            return Ok(#name(
+                                #( #slot_assignments ),*
+                            ));
+                        }
+                    }
+                } else {
+                    quote! {}
+                };
+
                 quote! {
                     if reader.remaining() == 0 {
-                        return Err(senax_encoder::EncoderError::InsufficientData);
+                        return Err(#krate::EncoderError::UnexpectedEof { needed: 1, available: 0 });
                     }
                     let tag = reader.get_u8();
-                    if tag != senax_encoder::core::TAG_STRUCT_UNNAMED {
-                        return Err(senax_encoder::EncoderError::StructDecode(
-                            senax_encoder::StructDecodeError::InvalidTag {
-                                expected: senax_encoder::core::TAG_STRUCT_UNNAMED,
+                    #named_payload_branch
+                    if tag != #krate::core::TAG_STRUCT_UNNAMED {
+                        return Err(#krate::EncoderError::StructDecode(
+                            #krate::StructDecodeError::InvalidTag {
+                                expected: #krate::core::TAG_STRUCT_UNNAMED,
                                 actual: tag,
                             }
                         ));
                     }
-                    let count = <usize as senax_encoder::Decoder>::decode(reader)?;
+                    let count = <usize as #krate::Decoder>::decode(reader)?;
                     if count != #field_count {
-                        return Err(senax_encoder::EncoderError::StructDecode(
-                            senax_encoder::StructDecodeError::FieldCountMismatch {
+                        return Err(#krate::EncoderError::StructDecode(
+                            #krate::StructDecodeError::FieldCountMismatch {
                                 struct_name: stringify!(#name),
                                 expected: #field_count,
                                 actual: count,
@@ -986,13 +3032,13 @@ pub fn derive_decode(input: TokenStream) -> TokenStream {
             }
             Fields::Unit => quote! {
                 if reader.remaining() == 0 {
-                    return Err(senax_encoder::EncoderError::InsufficientData);
+                    return Err(#krate::EncoderError::UnexpectedEof { needed: 1, available: 0 });
                 }
                 let tag = reader.get_u8();
-                if tag != senax_encoder::core::TAG_STRUCT_UNIT {
-                    return Err(senax_encoder::EncoderError::StructDecode(
-                        senax_encoder::StructDecodeError::InvalidTag {
-                            expected: senax_encoder::core::TAG_STRUCT_UNIT,
+                if tag != #krate::core::TAG_STRUCT_UNIT {
+                    return Err(#krate::EncoderError::StructDecode(
+                        #krate::StructDecodeError::InvalidTag {
+                            expected: #krate::core::TAG_STRUCT_UNIT,
                             actual: tag,
                         }
                     ));
@@ -1002,20 +3048,37 @@ pub fn derive_decode(input: TokenStream) -> TokenStream {
         },
         Data::Enum(e) => {
             let mut unit_variant_arms = Vec::new();
+            let mut unit_variant_name_arms = Vec::new();
             let mut named_variant_arms = Vec::new();
             let mut unnamed_variant_arms = Vec::new();
             let mut used_ids_enum_decode = HashMap::new();
+            let mut variant_aliases = Vec::new();
 
-            for v in &e.variants {
+            let discriminant_ids = get_discriminant_ids(e, &container_attrs);
+            for (v, discriminant_id) in e.variants.iter().zip(discriminant_ids) {
                 let variant_name_str = v.ident.to_string();
-                let variant_attrs = get_field_attributes(&v.attrs, &variant_name_str);
-                let variant_id = variant_attrs.id;
+                let variant_attrs = get_field_attributes(
+                    v,
+                    &v.attrs,
+                    &variant_name_str,
+                    container_attrs.rename_all,
+                    container_attrs.id_salt.as_deref(),
+                    container_attrs.pins(name, None).as_ref(),
+                )?;
+                let variant_id = resolve_variant_id(&variant_attrs, discriminant_id);
+                variant_aliases.extend(name_alias(
+                    variant_attrs.rename.as_deref().unwrap_or(&variant_name_str),
+                    variant_id,
+                ));
 
                 if let Some(dup_variant) =
                     used_ids_enum_decode.insert(variant_id, variant_name_str.clone())
                 {
-                    panic!("Variant ID (0x{:016X}) is duplicated for enum '{}'. Please specify a different ID for variant '{}' and '{}' using #[senax(id=...)].", 
-                          variant_id, name, dup_variant, variant_name_str);
+                    return Err(syn::Error::new_spanned(
+                        v,
+                        format!("Variant ID (0x{:016X}) is duplicated for enum '{}'. Please specify a different ID for variant '{}' and '{}' using #[senax(id=...)].",
+                                variant_id, name, dup_variant, variant_name_str),
+                    ));
                 }
 
                 let variant_ident = &v.ident;
@@ -1033,11 +3096,26 @@ pub fn derive_decode(input: TokenStream) -> TokenStream {
                             .iter()
                             .map(|f| {
                                 get_field_attributes(
+                                    f,
                                     &f.attrs,
                                     &f.ident.as_ref().unwrap().to_string(),
+                                    None,
+                                    container_attrs.id_salt.as_deref(),
+                                    container_attrs.pins(name, Some(variant_ident)).as_ref(),
+                                )
+                            })
+                            .collect::<syn::Result<_>>()?;
+                        let field_aliases: Vec<_> = field_idents
+                            .iter()
+                            .zip(field_attrs_list.iter())
+                            .filter_map(|(ident, attrs)| {
+                                name_alias(
+                                    attrs.rename.as_deref().unwrap_or(&ident.to_string()),
+                                    attrs.id,
                                 )
                             })
                             .collect();
+                        let read_id = read_field_id(krate, quote! { reader }, &field_aliases);
 
                         let mut field_value_definitions_enum = Vec::new();
                         let mut match_arms_enum_named = Vec::new();
@@ -1051,23 +3129,48 @@ pub fn derive_decode(input: TokenStream) -> TokenStream {
                             if attrs.skip_decode {
                                 // Fields marked with skip_decode don't store values
                             } else if is_option_type(ty) {
-                                field_value_definitions_enum.push(quote! { #ident: #ty, });
+                                field_value_definitions_enum.push(field_value_slot(
+                                    ident,
+                                    quote! { #ty },
+                                    attrs,
+                                    krate,
+                                ));
                             } else {
-                                field_value_definitions_enum.push(quote! { #ident: Option<#ty>, });
+                                field_value_definitions_enum.push(field_value_slot(
+                                    ident,
+                                    quote! { Option<#ty> },
+                                    attrs,
+                                    krate,
+                                ));
                             }
 
+                            let record_deprecated = if attrs.deprecated {
+                                quote! {
+                                    #krate::deprecated_fields::record_deprecated_field(
+                                        concat!(stringify!(#name), "::", stringify!(#variant_ident)),
+                                        stringify!(#ident),
+                                    );
+                                }
+                            } else {
+                                quote! {}
+                            };
                             if attrs.skip_decode {
-                                // Fields marked with skip_decode don't generate match arms
-                            } else if is_option_type(ty) {
-                                let inner_ty = extract_inner_type_from_option(ty).unwrap();
+                                // Known to the type, so skipped even when unknown fields are denied
+                                let field_id = attrs.id;
+                                match_arms_enum_named.push(quote! {
+                                    x if x == #field_id => { #krate::core::skip_value(reader)?; }
+                                });
+                            } else if let Some(inner_ty) = extract_inner_type_from_option(ty) {
                                 let field_id = attrs.id;
+                                let slot = assign_field_value(ident, attrs);
                                 match_arms_enum_named.push(quote! {
-                                    x if x == #field_id => { field_values.#ident = Some(<#inner_ty as senax_encoder::Decoder>::decode(reader)?); }
+                                    x if x == #field_id => { #record_deprecated #slot = Some(<#inner_ty as #krate::Decoder>::decode(reader)?); }
                                 });
                             } else {
                                 let field_id = attrs.id;
+                                let slot = assign_field_value(ident, attrs);
                                 match_arms_enum_named.push(quote! {
-                                    x if x == #field_id => { field_values.#ident = Some(<#ty as senax_encoder::Decoder>::decode(reader)?); }
+                                    x if x == #field_id => { #record_deprecated #slot = Some(<#ty as #krate::Decoder>::decode(reader)?); }
                                 });
                             }
 
@@ -1076,18 +3179,20 @@ pub fn derive_decode(input: TokenStream) -> TokenStream {
                                 struct_assignments_enum_named
                                     .push(quote! { #ident: Default::default(), });
                             } else if is_option_type(ty) {
-                                struct_assignments_enum_named
-                                    .push(quote! { #ident: field_values.#ident, });
+                                let value = take_field_value(ident, attrs);
+                                struct_assignments_enum_named.push(quote! { #ident: #value, });
                             } else if attrs.default || attrs.skip_default {
                                 // Fields marked with default or skip_default use default value if missing
+                                let value = take_field_value(ident, attrs);
                                 struct_assignments_enum_named.push(quote! {
-                                    #ident: field_values.#ident.unwrap_or_default(),
+                                    #ident: #value.unwrap_or_default(),
                                 });
                             } else {
+                                let value = take_field_value(ident, attrs);
                                 struct_assignments_enum_named.push(quote! {
-                                    #ident: field_values.#ident.ok_or_else(||
-                                        senax_encoder::EncoderError::EnumDecode(
-                                            senax_encoder::EnumDecodeError::MissingRequiredField {
+                                    #ident: #value.ok_or_else(||
+                                        #krate::EncoderError::EnumDecode(
+                                            #krate::EnumDecodeError::MissingRequiredField {
                                                 field: stringify!(#ident),
                                                 enum_name: stringify!(#name),
                                                 variant_name: stringify!(#variant_ident),
@@ -1098,21 +3203,36 @@ pub fn derive_decode(input: TokenStream) -> TokenStream {
                             }
                         }
 
+                        let declare_field_values =
+                            declare_field_values(&field_value_definitions_enum);
                         named_variant_arms.push(quote! {
                             x if x == #variant_id => {
-                                #[derive(Default)]
-                                struct FieldValues { #(#field_value_definitions_enum)* }
-                                let mut field_values = FieldValues::default();
+                                #declare_field_values
                                 loop {
                                     let field_id = {
                                         if reader.remaining() == 0 { break; }
-                                        let id = senax_encoder::core::read_field_id_optimized(reader)?;
+                                        let id = #read_id;
                                         if id == 0 { break; }
                                         id
                                     };
                                     match field_id {
                                         #(#match_arms_enum_named)*
-                                        _unknown_id => { senax_encoder::core::skip_value(reader)?; }
+                                        unknown_id => {
+                                            if #krate::current_decode_config().deny_unknown_fields {
+                                                return Err(#krate::EncoderError::EnumDecode(
+                                                    #krate::EnumDecodeError::UnknownField {
+                                                        field_id: unknown_id,
+                                                        enum_name: stringify!(#name),
+                                                        variant_name: stringify!(#variant_ident),
+                                                    }
+                                                ));
+                                            }
+                                            #krate::unknown_fields::skip_unknown_field(
+                                                reader,
+                                                concat!(stringify!(#name), "::", stringify!(#variant_ident)),
+                                                unknown_id,
+                                            )?;
+                                        }
                                     }
                                 }
                                 Ok(#name::#variant_ident { #(#struct_assignments_enum_named)* })
@@ -1122,26 +3242,55 @@ pub fn derive_decode(input: TokenStream) -> TokenStream {
                     Fields::Unnamed(fields) => {
                         let field_types: Vec<_> = fields.unnamed.iter().map(|f| &f.ty).collect();
                         let field_count = field_types.len();
-                        unnamed_variant_arms.push(quote! {
-                            x if x == #variant_id => {
-                                let count = <usize as senax_encoder::Decoder>::decode(reader)?;
-                                if count != #field_count {
-                                    return Err(senax_encoder::EncoderError::EnumDecode(
-                                        senax_encoder::EnumDecodeError::FieldCountMismatch {
-                                            enum_name: stringify!(#name),
-                                            variant_name: stringify!(#variant_ident),
-                                            expected: #field_count,
-                                            actual: count,
-                                        }
-                                    ));
-                                }
-                                Ok(#name::#variant_ident(
+                        if variant_attrs.tolerant {
+                            // Older readers decode the fields they know and skip the rest
+                            let field_vars: Vec<_> = (0..field_count)
+                                .map(|i| Ident::new(&format!("field{}", i), Span::call_site()))
+                                .collect();
+                            unnamed_variant_arms.push(quote! {
+                                x if x == #variant_id => {
+                                    let count = <usize as #krate::Decoder>::decode(reader)?;
+                                    if count < #field_count {
+                                        return Err(#krate::EncoderError::EnumDecode(
+                                            #krate::EnumDecodeError::FieldCountMismatch {
+                                                enum_name: stringify!(#name),
+                                                variant_name: stringify!(#variant_ident),
+                                                expected: #field_count,
+                                                actual: count,
+                                            }
+                                        ));
+                                    }
                                     #(
-                                        <#field_types as senax_encoder::Decoder>::decode(reader)?,
+                                        let #field_vars = <#field_types as #krate::Decoder>::decode(reader)?;
                                     )*
-                                ))
-                            }
-                        });
+                                    for _ in #field_count..count {
+                                        #krate::core::skip_value(reader)?;
+                                    }
+                                    Ok(#name::#variant_ident(#(#field_vars),*))
+                                }
+                            });
+                        } else {
+                            unnamed_variant_arms.push(quote! {
+                                x if x == #variant_id => {
+                                    let count = <usize as #krate::Decoder>::decode(reader)?;
+                                    if count != #field_count {
+                                        return Err(#krate::EncoderError::EnumDecode(
+                                            #krate::EnumDecodeError::FieldCountMismatch {
+                                                enum_name: stringify!(#name),
+                                                variant_name: stringify!(#variant_ident),
+                                                expected: #field_count,
+                                                actual: count,
+                                            }
+                                        ));
+                                    }
+                                    Ok(#name::#variant_ident(
+                                        #(
+                                            <#field_types as #krate::Decoder>::decode(reader)?,
+                                        )*
+                                    ))
+                                }
+                            });
+                        }
                     }
                     Fields::Unit => {
                         unit_variant_arms.push(quote! {
@@ -1149,53 +3298,78 @@ pub fn derive_decode(input: TokenStream) -> TokenStream {
                                 Ok(#name::#variant_ident)
                             }
                         });
+                        let wire_name = variant_attrs.rename.as_ref().unwrap_or(&variant_name_str);
+                        unit_variant_name_arms.push(quote! {
+                            #wire_name => Ok(#name::#variant_ident),
+                        });
                     }
                 }
             }
+            // With string_repr, unit variants may arrive as their name; the ID form is still accepted
+            let string_decode = if container_attrs.string_repr {
+                quote! {
+                    if (#krate::core::TAG_STRING_BASE..=#krate::core::TAG_STRING_LONG).contains(&reader.chunk()[0]) {
+                        let variant_name = <String as #krate::Decoder>::decode(reader)?;
+                        return match variant_name.as_str() {
+                            #(#unit_variant_name_arms)*
+                            _ => Err(#krate::EncoderError::EnumDecode(
+                                #krate::EnumDecodeError::UnknownVariantName {
+                                    name: variant_name,
+                                    enum_name: stringify!(#name),
+                                }
+                            )),
+                        };
+                    }
+                }
+            } else {
+                quote! {}
+            };
+            let read_variant_id = read_field_id(krate, quote! { reader }, &variant_aliases);
             quote! {
                 if reader.remaining() == 0 {
-                    return Err(senax_encoder::EncoderError::InsufficientData);
+                    return Err(#krate::EncoderError::UnexpectedEof { needed: 1, available: 0 });
                 }
+                #string_decode
                 let tag = reader.get_u8();
                 match tag {
-                    senax_encoder::core::TAG_ENUM => {
-                        let variant_id = senax_encoder::core::read_field_id_optimized(reader)?;
+                    #krate::core::TAG_ENUM => {
+                        let variant_id = #read_variant_id;
                         match variant_id {
                             #(#unit_variant_arms)*
-                            _ => Err(senax_encoder::EncoderError::EnumDecode(
-                                senax_encoder::EnumDecodeError::UnknownVariantId {
+                            _ => Err(#krate::EncoderError::EnumDecode(
+                                #krate::EnumDecodeError::UnknownVariantId {
                                     variant_id,
                                     enum_name: stringify!(#name),
                                 }
                             ))
                         }
                     }
-                    senax_encoder::core::TAG_ENUM_NAMED => {
-                        let variant_id = senax_encoder::core::read_field_id_optimized(reader)?;
+                    #krate::core::TAG_ENUM_NAMED => {
+                        let variant_id = #read_variant_id;
                         match variant_id {
                             #(#named_variant_arms)*
-                            _ => Err(senax_encoder::EncoderError::EnumDecode(
-                                senax_encoder::EnumDecodeError::UnknownVariantId {
+                            _ => Err(#krate::EncoderError::EnumDecode(
+                                #krate::EnumDecodeError::UnknownVariantId {
                                     variant_id,
                                     enum_name: stringify!(#name),
                                 }
                             ))
                         }
                     }
-                    senax_encoder::core::TAG_ENUM_UNNAMED => {
-                        let variant_id = senax_encoder::core::read_field_id_optimized(reader)?;
+                    #krate::core::TAG_ENUM_UNNAMED => {
+                        let variant_id = #read_variant_id;
                         match variant_id {
                              #(#unnamed_variant_arms)*
-                            _ => Err(senax_encoder::EncoderError::EnumDecode(
-                                senax_encoder::EnumDecodeError::UnknownVariantId {
+                            _ => Err(#krate::EncoderError::EnumDecode(
+                                #krate::EnumDecodeError::UnknownVariantId {
                                     variant_id,
                                     enum_name: stringify!(#name),
                                 }
                             ))
                         }
                     }
-                    unknown_tag => Err(senax_encoder::EncoderError::EnumDecode(
-                        senax_encoder::EnumDecodeError::UnknownTag {
+                    unknown_tag => Err(#krate::EncoderError::EnumDecode(
+                        #krate::EnumDecodeError::UnknownTag {
                             tag: unknown_tag,
                             enum_name: stringify!(#name),
                         }
@@ -1203,23 +3377,398 @@ pub fn derive_decode(input: TokenStream) -> TokenStream {
                 }
             }
         }
-        Data::Union(_) => unimplemented!("Unions are not supported"),
+        Data::Union(_) => return Err(union_without_view(input)),
     };
 
+    let decode_fields = depth_guarded(input, &container_attrs, decode_fields);
     let decode_method = quote! {
-        fn decode(reader: &mut bytes::Bytes) -> senax_encoder::Result<Self> {
-            use bytes::{Buf, BufMut};
+        fn decode(reader: &mut #krate::bytes::Bytes) -> #krate::Result<Self> {
+            use #krate::bytes::{Buf, BufMut};
             #decode_fields
         }
     };
 
-    TokenStream::from(quote! {
-        impl #impl_generics senax_encoder::Decoder for #name #ty_generics #where_clause {
+    let ids_file_dependency = ids_file_dependency(&container_attrs);
+
+    let where_clause = codec_where_clause(
+        input,
+        &container_attrs,
+        quote! { #krate::Decoder },
+        |attrs| !attrs.skip_decode,
+    )?;
+
+    Ok(quote! {
+        impl #impl_generics #krate::Decoder for #name #ty_generics #where_clause {
             #decode_method
         }
+
+        #ids_file_dependency
+        #prefix_items
+
+        #builder_items
     })
 }
 
+/// Declares `field_values`, which holds an `Option` per decoded field until the value is built.
+///
+/// The holder struct takes the field types as type parameters because items nested in
+/// `decode` cannot name the generic parameters of the type being derived.
+fn declare_field_values(
+    fields: &[(&Ident, proc_macro2::TokenStream, proc_macro2::TokenStream)],
+) -> proc_macro2::TokenStream {
+    let idents: Vec<_> = fields.iter().map(|(ident, _, _)| *ident).collect();
+    let types = fields.iter().map(|(_, ty, _)| ty);
+    let inits = fields.iter().map(|(_, _, init)| init);
+    let params: Vec<_> = (0..fields.len())
+        .map(|i| Ident::new(&format!("__SenaxField{}", i), Span::call_site()))
+        .collect();
+    quote! {
+        struct FieldValues<#(#params),*> {
+            #( #idents: #params, )*
+        }
+
+        let mut field_values = FieldValues::<#(#types),*> {
+            #( #idents: #inits, )*
+        };
+    }
+}
+
+/// The `field_values` slot of one field: its type and initial value.
+///
+/// `#[senax(sensitive)]` fields keep their `Option` in `sensitive::Zeroizing`, so a value
+/// decoded before a later field fails is wiped when `field_values` is dropped.
+fn field_value_slot<'a>(
+    ident: &'a Ident,
+    option_ty: proc_macro2::TokenStream,
+    attrs: &FieldAttributes,
+    krate: &syn::Path,
+) -> (
+    &'a Ident,
+    proc_macro2::TokenStream,
+    proc_macro2::TokenStream,
+) {
+    if attrs.sensitive {
+        (
+            ident,
+            quote! { #krate::sensitive::Zeroizing<#option_ty> },
+            quote! { #krate::sensitive::Zeroizing::new(None) },
+        )
+    } else {
+        (ident, option_ty, quote! { None })
+    }
+}
+
+/// The place a decoded value is assigned to in `field_values`.
+fn assign_field_value(ident: &Ident, attrs: &FieldAttributes) -> proc_macro2::TokenStream {
+    if attrs.sensitive {
+        quote! { *field_values.#ident }
+    } else {
+        quote! { field_values.#ident }
+    }
+}
+
+/// Moves a decoded `Option` out of `field_values`.
+fn take_field_value(ident: &Ident, attrs: &FieldAttributes) -> proc_macro2::TokenStream {
+    if attrs.sensitive {
+        quote! { field_values.#ident.take() }
+    } else {
+        quote! { field_values.#ident }
+    }
+}
+
+/// Generate the expression that reads one named field value of type `ty` from `reader`
+///
+/// `#[senax(encrypt)]` fields are read through `field_crypto::decode_encrypted_field`,
+/// `#[senax(flat_keys)]` fields through `core::decode_flat_key_map`, `#[senax(ext_block)]`
+/// fields as `ext_block::ExtBlock` and `#[senax(with)]` fields through `remote::decode`;
+/// `#[senax(max_len = N)]` fields are then checked with `max_size::check_max_len` and
+/// `#[senax(min, max)]` fields with `core::check_field_range`, inside
+/// `sensitive::check_sensitive` for `#[senax(sensitive)]` fields so a rejected value is wiped.
+fn decode_field_value(
+    ty: &Type,
+    attrs: &FieldAttributes,
+    krate: &syn::Path,
+    reader: proc_macro2::TokenStream,
+    field: &Ident,
+    struct_name: &Ident,
+) -> proc_macro2::TokenStream {
+    let id_val = attrs.id;
+    let read = if attrs.encrypt {
+        quote! { #krate::field_crypto::decode_encrypted_field::<#ty>(#reader, #id_val)? }
+    } else if attrs.flat_keys {
+        quote! { #krate::core::decode_flat_key_map::<#ty, _, _>(#reader)? }
+    } else if attrs.ext_block {
+        quote! { <#krate::ext_block::ExtBlock as #krate::Decoder>::decode(#reader)? }
+    } else if let Some(with) = &attrs.with {
+        quote! { #krate::remote::decode::<#with>(#reader)? }
+    } else {
+        quote! { <#ty as #krate::Decoder>::decode(#reader)? }
+    };
+    // Sensitive values are checked through a reference, so a rejected value can be wiped
+    let value = if attrs.sensitive {
+        quote! { value }
+    } else {
+        quote! { &value }
+    };
+    let mut checks = Vec::new();
+    if let Some(max_len) = attrs.max_len {
+        checks.push(quote! { #krate::max_size::check_max_len(#value, #max_len)?; });
+    }
+    if attrs.min.is_some() || attrs.max.is_some() {
+        let bound = |b: &Option<proc_macro2::TokenStream>| match b {
+            Some(b) => quote! { Some(#b) },
+            None => quote! { None },
+        };
+        let (min, max) = (bound(&attrs.min), bound(&attrs.max));
+        checks.push(quote! {
+            #krate::core::check_field_range(
+                #value,
+                #min,
+                #max,
+                stringify!(#field),
+                stringify!(#struct_name),
+            )?;
+        });
+    }
+    if checks.is_empty() {
+        return read;
+    }
+    if attrs.sensitive {
+        return quote! {
+            #krate::sensitive::check_sensitive::<#ty>(#read, |value| {
+                #(#checks)*
+                Ok(())
+            })?
+        };
+    }
+    quote! {{
+        let value: #ty = #read;
+        #(#checks)*
+        value
+    }}
+}
+
+/// Generate the `<Name>Prefix` struct and the inherent `decode_prefix` function
+///
+/// `decode_prefix` reads the leading `#[senax(prefix)]` fields of an encoded struct and stops at
+/// the first other field (or the terminator), returning the prefix and the unread remainder.
+fn generate_decode_prefix(
+    input: &DeriveInput,
+    fields: &[&syn::Field],
+    field_attrs_list: &[FieldAttributes],
+    krate: &syn::Path,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let name = &input.ident;
+    let vis = &input.vis;
+    if !input.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &input.generics,
+            format!(
+                "#[senax(prefix)] is not supported on generic struct '{}'",
+                name
+            ),
+        ));
+    }
+    let prefix_name = Ident::new(&format!("{}Prefix", name), name.span());
+
+    let mut definitions = Vec::new();
+    let mut slots = Vec::new();
+    let mut arms = Vec::new();
+    let mut assignments = Vec::new();
+    let mut prefix_count = 0usize;
+    for (f, attrs) in fields.iter().zip(field_attrs_list.iter()) {
+        if !attrs.prefix {
+            continue;
+        }
+        prefix_count += 1;
+        let ident = f.ident.as_ref().unwrap();
+        let field_vis = &f.vis;
+        let ty = &f.ty;
+        let id_val = attrs.id;
+        definitions.push(quote! { #field_vis #ident: #ty, });
+        if let Some(inner_ty) = extract_inner_type_from_option(ty) {
+            let read_value =
+                decode_field_value(inner_ty, attrs, krate, quote! { &mut peek }, ident, name);
+            slots.push(quote! { let mut #ident: #ty = None; });
+            arms.push(quote! {
+                x if x == #id_val => { #ident = Some(#read_value); }
+            });
+            assignments.push(quote! { #ident, });
+        } else {
+            let read_value =
+                decode_field_value(ty, attrs, krate, quote! { &mut peek }, ident, name);
+            slots.push(quote! { let mut #ident: Option<#ty> = None; });
+            arms.push(quote! {
+                x if x == #id_val => { #ident = Some(#read_value); }
+            });
+            if attrs.default || attrs.skip_default {
+                assignments.push(quote! { #ident: #ident.unwrap_or_default(), });
+            } else {
+                assignments.push(quote! {
+                    #ident: #ident.ok_or_else(||
+                        #krate::EncoderError::StructDecode(
+                            #krate::StructDecodeError::MissingRequiredField {
+                                field: stringify!(#ident),
+                                struct_name: stringify!(#name),
+                            }
+                        )
+                    )?,
+                });
+            }
+        }
+    }
+
+    let doc = format!(
+        "Leading `#[senax(prefix)]` fields of [`{}`], read by [`{}::decode_prefix`].",
+        name, name
+    );
+    Ok(quote! {
+        #[doc = #doc]
+        #vis struct #prefix_name {
+            #( #definitions )*
+        }
+
+        impl #name {
+            /// Decodes only the leading `#[senax(prefix)]` fields of an encoded value.
+            ///
+            /// Reading stops at the first field that is not a prefix field. Returns the prefix
+            /// and the unread remainder of `bytes` (the remaining fields and the terminator,
+            /// followed by any trailing data). `bytes` is the output of `Encoder::encode`,
+            /// without the magic number.
+            #vis fn decode_prefix(
+                bytes: &#krate::bytes::Bytes,
+            ) -> #krate::Result<(#prefix_name, #krate::bytes::Bytes)> {
+                use #krate::bytes::Buf;
+                let mut reader = bytes.clone();
+                if reader.remaining() == 0 {
+                    return Err(#krate::EncoderError::UnexpectedEof { needed: 1, available: 0 });
+                }
+                let tag = reader.get_u8();
+                if tag != #krate::core::TAG_STRUCT_NAMED {
+                    return Err(#krate::EncoderError::StructDecode(
+                        #krate::StructDecodeError::InvalidTag {
+                            expected: #krate::core::TAG_STRUCT_NAMED,
+                            actual: tag,
+                        }
+                    ));
+                }
+                #( #slots )*
+                let mut seen = 0usize;
+                while seen < #prefix_count {
+                    let mut peek = reader.clone();
+                    let field_id = #krate::core::read_field_id_optimized(&mut peek)?;
+                    match field_id {
+                        #( #arms )*
+                        _ => break,
+                    }
+                    reader = peek;
+                    seen += 1;
+                }
+                Ok((#prefix_name { #( #assignments )* }, reader))
+            }
+        }
+    })
+}
+
+/// Generate the `<Name>Builder` companion struct for `#[senax(builder)]`
+///
+/// The builder holds every field as an `Option` and is also used as the field storage of the
+/// derived `decode`, so `try_build` reports the same missing-field errors as decoding.
+fn generate_builder(
+    input: &DeriveInput,
+    fields: &[&syn::Field],
+    field_attrs_list: &[FieldAttributes],
+    krate: &syn::Path,
+) -> proc_macro2::TokenStream {
+    let name = &input.ident;
+    let vis = &input.vis;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let builder_name = Ident::new(&format!("{}Builder", name), name.span());
+
+    let mut definitions = Vec::new();
+    let mut initializers = Vec::new();
+    let mut setters = Vec::new();
+    let mut assignments = Vec::new();
+    for (f, attrs) in fields.iter().zip(field_attrs_list.iter()) {
+        let ident = f.ident.as_ref().unwrap();
+        let ty = &f.ty;
+        initializers.push(quote! { #ident: None, });
+        let setter_doc = format!("Sets `{}`.", ident);
+        if let Some(inner_ty) = extract_inner_type_from_option(ty).filter(|_| is_option_type(ty)) {
+            definitions.push(quote! { #ident: #ty, });
+            setters.push(quote! {
+                #[doc = #setter_doc]
+                #vis fn #ident(mut self, value: #inner_ty) -> Self {
+                    self.#ident = Some(value);
+                    self
+                }
+            });
+            assignments.push(quote! { #ident: self.#ident, });
+            continue;
+        }
+        definitions.push(quote! { #ident: Option<#ty>, });
+        setters.push(quote! {
+            #[doc = #setter_doc]
+            #vis fn #ident(mut self, value: #ty) -> Self {
+                self.#ident = Some(value);
+                self
+            }
+        });
+        if attrs.default || attrs.skip_default || attrs.skip_decode {
+            assignments.push(quote! { #ident: self.#ident.unwrap_or_default(), });
+        } else {
+            assignments.push(quote! {
+                #ident: self.#ident.ok_or_else(||
+                    #krate::EncoderError::StructDecode(
+                        #krate::StructDecodeError::MissingRequiredField {
+                            field: stringify!(#ident),
+                            struct_name: stringify!(#name),
+                        }
+                    )
+                )?,
+            });
+        }
+    }
+
+    let doc = format!(
+        "Builder for [`{}`], created by [`{}::builder`].",
+        name, name
+    );
+    quote! {
+        #[doc = #doc]
+        #vis struct #builder_name #impl_generics #where_clause {
+            #( #definitions )*
+        }
+
+        impl #impl_generics Default for #builder_name #ty_generics #where_clause {
+            fn default() -> Self {
+                Self { #( #initializers )* }
+            }
+        }
+
+        impl #impl_generics #builder_name #ty_generics #where_clause {
+            #( #setters )*
+
+            /// Builds the value, failing with `MissingRequiredField` if a required field was not set.
+            ///
+            /// Fields marked `default`, `skip_default` or `skip_decode` fall back to their default
+            /// value and `Option` fields to `None`.
+            #vis fn try_build(self) -> #krate::Result<#name #ty_generics> {
+                Ok(#name {
+                    #( #assignments )*
+                })
+            }
+        }
+
+        impl #impl_generics #name #ty_generics #where_clause {
+            /// Returns a builder with no fields set.
+            #vis fn builder() -> #builder_name #ty_generics {
+                Default::default()
+            }
+        }
+    }
+}
+
 /// Derive macro for implementing the `Pack` trait (Packer only)
 ///
 /// This procedural macro automatically generates an implementation of the `Packer` trait
@@ -1228,7 +3777,15 @@ pub fn derive_decode(input: TokenStream) -> TokenStream {
 /// # Supported Attributes
 ///
 /// ## Container-level attributes:
-/// * `#[senax(disable_pack)]` - Generate stub implementation (unimplemented!() only) for Pack/Unpack
+/// * `#[senax(disable_pack)]` - Generate stub implementation (returning an error) for Pack/Unpack
+/// * `#[senax(crate = "path")]` - Path to senax-encoder used in generated code
+/// * `#[senax(discriminant_ids)]` - Use explicit enum discriminants as variant IDs
+/// * `#[senax(bound = "T: ...")]` - Replace the automatic `T: Packer`/`T: Unpacker` bounds on type parameters
+/// * `#[senax(recursive)]` - Limit the unpack nesting depth of a mutually recursive type (self-referencing types are detected)
+///
+/// ## Field-level attributes:
+/// * `#[senax(with = "Proxy")]` - Pack the field through a `senax_remote!` proxy
+/// * `#[senax(sort)]` - Write a sequence/set field's elements in ascending order
 ///
 /// # Examples
 ///
@@ -1249,71 +3806,127 @@ pub fn derive_decode(input: TokenStream) -> TokenStream {
 #[proc_macro_derive(Pack, attributes(senax))]
 pub fn derive_pack(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
+    deny_panics(expand_pack(&input).unwrap_or_else(syn::Error::into_compile_error)).into()
+}
+
+fn expand_pack(input: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
     let name = &input.ident;
     let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
 
     // Check for container-level disable_pack attribute
-    let container_attrs = get_container_attributes(&input.attrs);
+    let container_attrs = get_container_attributes(&input.attrs)?;
+    check_input(input, &container_attrs)?;
+    let krate = &container_attrs.krate;
     if container_attrs.disable_pack {
-        return TokenStream::from(quote! {
-            impl #impl_generics senax_encoder::Packer for #name #ty_generics #where_clause {
-                fn pack(&self, _writer: &mut bytes::BytesMut) -> senax_encoder::Result<()> {
-                    unimplemented!("Pack trait is disabled for {}", stringify!(#name))
+        return Ok(quote! {
+            impl #impl_generics #krate::Packer for #name #ty_generics #where_clause {
+                fn pack(&self, _writer: &mut #krate::bytes::BytesMut) -> #krate::Result<()> {
+                    Err(#krate::EncoderError::Encode(
+                        concat!("Pack trait is disabled for ", stringify!(#name)).into(),
+                    ))
+                }
+            }
+        });
+    }
+
+    if let Some(view) = union_view(input, &container_attrs)? {
+        return Ok(quote! {
+            impl #impl_generics #krate::Packer for #name #ty_generics #where_clause {
+                fn pack(&self, writer: &mut #krate::bytes::BytesMut) -> #krate::Result<()> {
+                    #krate::Packer::pack(&<#view as ::core::convert::From<&Self>>::from(self), writer)
                 }
             }
         });
     }
 
     // Generate structure information and CRC64 hash for pack format
-    let structure_info = generate_structure_info(&input);
+    let structure_info = generate_structure_info(input, container_attrs.id_salt.as_deref());
     let structure_hash = CRC64.checksum(structure_info.as_bytes());
 
     // Generate pack implementation for structs and enums (no field IDs for struct fields)
     let pack_fields = match &input.data {
-        Data::Struct(s) => match &s.fields {
-            Fields::Named(fields) => {
-                let field_encode = fields.named.iter().map(|f| {
+        Data::Struct(s) => {
+            match &s.fields {
+                Fields::Named(fields) => {
+                    let field_encode = fields.named.iter().map(|f| {
                     let field_ident = &f.ident;
+                    if field_sort(f)? {
+                        return Ok(if is_option_type(&f.ty) {
+                            quote! {
+                                match &self.#field_ident {
+                                    Some(val) => {
+                                        writer.put_u8(#krate::core::TAG_SOME);
+                                        #krate::core::pack_sorted(val, writer)?;
+                                    }
+                                    None => writer.put_u8(#krate::core::TAG_NONE),
+                                }
+                            }
+                        } else {
+                            quote! {
+                                #krate::core::pack_sorted(&self.#field_ident, writer)?;
+                            }
+                        });
+                    }
+                    Ok(match field_with(f)? {
+                        Some(with) if is_option_type(&f.ty) => quote! {
+                            #krate::remote::pack_option::<#with>(&self.#field_ident, writer)?;
+                        },
+                        Some(with) => quote! {
+                            #krate::remote::pack::<#with>(&self.#field_ident, writer)?;
+                        },
+                        None => quote! {
+                            #krate::Packer::pack(&self.#field_ident, writer)?;
+                        },
+                    })
+                }).collect::<syn::Result<Vec<_>>>()?;
                     quote! {
-                        senax_encoder::Packer::pack(&self.#field_ident, writer)?;
+                        // Write structure hash first for named structs
+                        writer.put_u64_le(#structure_hash);
+                        #(#field_encode)*
                     }
-                });
-                quote! {
-                    // Write structure hash first for named structs
-                    writer.put_u64_le(#structure_hash);
-                    #(#field_encode)*
                 }
-            }
-            Fields::Unnamed(fields) => {
-                let field_count = fields.unnamed.len();
-                let field_encode = fields.unnamed.iter().enumerate().map(|(i, _)| {
-                    let index = syn::Index::from(i);
+                Fields::Unnamed(fields) => {
+                    let field_count = fields.unnamed.len();
+                    let field_encode = fields.unnamed.iter().enumerate().map(|(i, _)| {
+                        let index = syn::Index::from(i);
+                        quote! {
+                            #krate::Packer::pack(&self.#index, writer)?;
+                        }
+                    });
                     quote! {
-                        senax_encoder::Packer::pack(&self.#index, writer)?;
+                        // Write field count for unnamed structs
+                        let count: usize = #field_count;
+                        #krate::Encoder::encode(&count, writer)?;
+                        #(#field_encode)*
                     }
-                });
-                quote! {
-                    // Write field count for unnamed structs
-                    let count: usize = #field_count;
-                    senax_encoder::Encoder::encode(&count, writer)?;
-                    #(#field_encode)*
                 }
+                Fields::Unit => quote! {
+                    // Unit structs don't need any additional data
+                },
             }
-            Fields::Unit => quote! {
-                // Unit structs don't need any additional data
-            },
-        },
+        }
         Data::Enum(e) => {
             let mut variant_pack = Vec::new();
             let mut used_ids_enum_pack = HashSet::new();
 
-            for v in &e.variants {
+            let discriminant_ids = get_discriminant_ids(e, &container_attrs);
+            for (v, discriminant_id) in e.variants.iter().zip(discriminant_ids) {
                 let variant_name_str = v.ident.to_string();
-                let variant_attrs = get_field_attributes(&v.attrs, &variant_name_str);
-                let variant_id = variant_attrs.id;
+                let variant_attrs = get_field_attributes(
+                    v,
+                    &v.attrs,
+                    &variant_name_str,
+                    container_attrs.rename_all,
+                    container_attrs.id_salt.as_deref(),
+                    container_attrs.pins(name, None).as_ref(),
+                )?;
+                let variant_id = resolve_variant_id(&variant_attrs, discriminant_id);
 
                 if !used_ids_enum_pack.insert(variant_id) {
-                    panic!("Variant ID (0x{:016X}) is duplicated for enum '{}'. Please specify a different ID for variant '{}' using #[senax(id=...)].", variant_id, name, variant_name_str);
+                    return Err(syn::Error::new_spanned(
+                        v,
+                        format!("Variant ID (0x{:016X}) is duplicated for enum '{}'. Please specify a different ID for variant '{}' using #[senax(id=...)].", variant_id, name, variant_name_str),
+                    ));
                 }
 
                 let variant_ident = &v.ident;
@@ -1328,13 +3941,13 @@ pub fn derive_pack(input: TokenStream) -> TokenStream {
                         // For pack, encode fields in order without field IDs
                         let field_pack = field_idents.iter().map(|field_ident| {
                             quote! {
-                                senax_encoder::Packer::pack(#field_ident, writer)?;
+                                #krate::Packer::pack(#field_ident, writer)?;
                             }
                         });
                         variant_pack.push(quote! {
                             #name::#variant_ident { #(#field_idents),* } => {
                                 // Write variant ID first, then structure hash for named enums
-                                senax_encoder::core::write_field_id_optimized(writer, #variant_id)?;
+                                #krate::core::write_field_id_optimized(writer, #variant_id)?;
                                 writer.put_u64_le(#structure_hash);
                                 #(#field_pack)*
                             }
@@ -1349,11 +3962,11 @@ pub fn derive_pack(input: TokenStream) -> TokenStream {
                         variant_pack.push(quote! {
                             #name::#variant_ident( #(#field_bindings_ref),* ) => {
                                 // Write variant ID first, then field count for unnamed enums
-                                senax_encoder::core::write_field_id_optimized(writer, #variant_id)?;
+                                #krate::core::write_field_id_optimized(writer, #variant_id)?;
                                 let count: usize = #field_count;
-                                senax_encoder::Encoder::encode(&count, writer)?;
+                                #krate::Encoder::encode(&count, writer)?;
                                 #(
-                                    senax_encoder::Packer::pack(&#field_bindings_ref, writer)?;
+                                    #krate::Packer::pack(&#field_bindings_ref, writer)?;
                                 )*
                             }
                         });
@@ -1362,7 +3975,7 @@ pub fn derive_pack(input: TokenStream) -> TokenStream {
                         variant_pack.push(quote! {
                             #name::#variant_ident => {
                                 // Unit enums only need variant ID
-                                senax_encoder::core::write_field_id_optimized(writer, #variant_id)?;
+                                #krate::core::write_field_id_optimized(writer, #variant_id)?;
                             }
                         });
                     }
@@ -1374,19 +3987,22 @@ pub fn derive_pack(input: TokenStream) -> TokenStream {
                 }
             }
         }
-        Data::Union(_) => unimplemented!("Unions are not supported"),
+        Data::Union(_) => return Err(union_without_view(input)),
     };
 
     let pack_method = quote! {
-        fn pack(&self, writer: &mut bytes::BytesMut) -> senax_encoder::Result<()> {
-            use bytes::{Buf, BufMut};
+        fn pack(&self, writer: &mut #krate::bytes::BytesMut) -> #krate::Result<()> {
+            use #krate::bytes::{Buf, BufMut};
             #pack_fields
             Ok(())
         }
     };
 
-    TokenStream::from(quote! {
-        impl #impl_generics senax_encoder::Packer for #name #ty_generics #where_clause {
+    let where_clause =
+        codec_where_clause(input, &container_attrs, quote! { #krate::Packer }, |_| true)?;
+
+    Ok(quote! {
+        impl #impl_generics #krate::Packer for #name #ty_generics #where_clause {
             #pack_method
         }
     })
@@ -1400,7 +4016,11 @@ pub fn derive_pack(input: TokenStream) -> TokenStream {
 /// # Supported Attributes
 ///
 /// ## Container-level attributes:
-/// * `#[senax(disable_pack)]` - Generate stub implementation (unimplemented!() only) for Pack/Unpack
+/// * `#[senax(disable_pack)]` - Generate stub implementation (returning an error) for Pack/Unpack
+/// * `#[senax(crate = "path")]` - Path to senax-encoder used in generated code
+/// * `#[senax(discriminant_ids)]` - Use explicit enum discriminants as variant IDs
+/// * `#[senax(bound = "T: ...")]` - Replace the automatic `T: Packer`/`T: Unpacker` bounds on type parameters
+/// * `#[senax(recursive)]` - Limit the unpack nesting depth of a mutually recursive type (self-referencing types are detected)
 ///
 /// # Examples
 ///
@@ -1421,71 +4041,104 @@ pub fn derive_pack(input: TokenStream) -> TokenStream {
 #[proc_macro_derive(Unpack, attributes(senax))]
 pub fn derive_unpack(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
+    deny_panics(expand_unpack(&input).unwrap_or_else(syn::Error::into_compile_error)).into()
+}
+
+fn expand_unpack(input: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
     let name = &input.ident;
     let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
 
     // Check for container-level disable_pack attribute
-    let container_attrs = get_container_attributes(&input.attrs);
+    let container_attrs = get_container_attributes(&input.attrs)?;
+    check_input(input, &container_attrs)?;
+    let krate = &container_attrs.krate;
     if container_attrs.disable_pack {
-        return TokenStream::from(quote! {
-            impl #impl_generics senax_encoder::Unpacker for #name #ty_generics #where_clause {
-                fn unpack(_reader: &mut bytes::Bytes) -> senax_encoder::Result<Self> {
-                    unimplemented!("Unpack trait is disabled for {}", stringify!(#name))
+        return Ok(quote! {
+            impl #impl_generics #krate::Unpacker for #name #ty_generics #where_clause {
+                fn unpack(_reader: &mut #krate::bytes::Bytes) -> #krate::Result<Self> {
+                    Err(#krate::EncoderError::Decode(
+                        concat!("Unpack trait is disabled for ", stringify!(#name)).into(),
+                    ))
+                }
+            }
+        });
+    }
+
+    if let Some(view) = union_view(input, &container_attrs)? {
+        return Ok(quote! {
+            impl #impl_generics #krate::Unpacker for #name #ty_generics #where_clause {
+                fn unpack(reader: &mut #krate::bytes::Bytes) -> #krate::Result<Self> {
+                    <#view as #krate::Unpacker>::unpack(reader).map(::core::convert::From::from)
                 }
             }
         });
     }
 
     // Generate structure information and CRC64 hash for pack format validation
-    let structure_info = generate_structure_info(&input);
+    let structure_info = generate_structure_info(input, container_attrs.id_salt.as_deref());
     let structure_hash = CRC64.checksum(structure_info.as_bytes());
 
     // Generate unpack implementation for structs and enums (no field IDs for struct fields)
     let unpack_fields = match &input.data {
         Data::Struct(s) => match &s.fields {
             Fields::Named(fields) => {
-                let field_assignments = fields.named.iter().map(|f| {
-                    let field_ident = &f.ident;
-                    let field_ty = &f.ty;
-                    quote! {
-                        #field_ident: <#field_ty as senax_encoder::Unpacker>::unpack(reader)?,
-                    }
-                });
+                let field_assignments = fields
+                    .named
+                    .iter()
+                    .map(|f| {
+                        let field_ident = &f.ident;
+                        let field_ty = &f.ty;
+                        Ok(match field_with(f)? {
+                            Some(with) if is_option_type(field_ty) => quote! {
+                                #field_ident: #krate::remote::unpack_option::<#with>(reader)?,
+                            },
+                            Some(with) => quote! {
+                                #field_ident: #krate::remote::unpack::<#with>(reader)?,
+                            },
+                            None => quote! {
+                                #field_ident: <#field_ty as #krate::Unpacker>::unpack(reader)?,
+                            },
+                        })
+                    })
+                    .collect::<syn::Result<Vec<_>>>()?;
                 quote! {
-                    // Read and validate structure hash for named structs
-                    if reader.remaining() < 8 {
-                        return Err(senax_encoder::EncoderError::InsufficientData);
-                    }
-                    let received_hash = reader.get_u64_le();
-                    if received_hash != #structure_hash {
-                        return Err(senax_encoder::EncoderError::StructDecode(
-                            senax_encoder::StructDecodeError::StructureHashMismatch {
-                                struct_name: stringify!(#name),
-                                expected: #structure_hash,
-                                actual: received_hash,
-                            }
-                        ));
-                    }
+                                   // Read and validate structure hash for named structs
+                                   if reader.remaining() < 8 {
+                                       return Err(#krate::EncoderError::UnexpectedEof {
+                needed: 8,
+                available: reader.remaining(),
+                });
+                                   }
+                                   let received_hash = reader.get_u64_le();
+                                   if received_hash != #structure_hash {
+                                       return Err(#krate::EncoderError::StructDecode(
+                                           #krate::StructDecodeError::StructureHashMismatch {
+                                               struct_name: stringify!(#name),
+                                               expected: #structure_hash,
+                                               actual: received_hash,
+                                           }
+                                       ));
+                                   }
 
-                    Ok(#name {
-                        #(#field_assignments)*
-                    })
-                }
+                                   Ok(#name {
+                                       #(#field_assignments)*
+                                   })
+                               }
             }
             Fields::Unnamed(fields) => {
                 let expected_field_count = fields.unnamed.len();
                 let field_decode = fields.unnamed.iter().map(|f| {
                     let field_ty = &f.ty;
                     quote! {
-                        <#field_ty as senax_encoder::Unpacker>::unpack(reader)?
+                        <#field_ty as #krate::Unpacker>::unpack(reader)?
                     }
                 });
                 quote! {
                     // Read and validate field count for unnamed structs
-                    let field_count = <usize as senax_encoder::Decoder>::decode(reader)?;
+                    let field_count = <usize as #krate::Decoder>::decode(reader)?;
                     if field_count != #expected_field_count {
-                        return Err(senax_encoder::EncoderError::StructDecode(
-                            senax_encoder::StructDecodeError::FieldCountMismatch {
+                        return Err(#krate::EncoderError::StructDecode(
+                            #krate::StructDecodeError::FieldCountMismatch {
                                 struct_name: stringify!(#name),
                                 expected: #expected_field_count,
                                 actual: field_count,
@@ -1507,13 +4160,24 @@ pub fn derive_unpack(input: TokenStream) -> TokenStream {
             let mut variant_unpack = Vec::new();
             let mut used_ids_enum_unpack = HashSet::new();
 
-            for v in &e.variants {
+            let discriminant_ids = get_discriminant_ids(e, &container_attrs);
+            for (v, discriminant_id) in e.variants.iter().zip(discriminant_ids) {
                 let variant_name_str = v.ident.to_string();
-                let variant_attrs = get_field_attributes(&v.attrs, &variant_name_str);
-                let variant_id = variant_attrs.id;
+                let variant_attrs = get_field_attributes(
+                    v,
+                    &v.attrs,
+                    &variant_name_str,
+                    container_attrs.rename_all,
+                    container_attrs.id_salt.as_deref(),
+                    container_attrs.pins(name, None).as_ref(),
+                )?;
+                let variant_id = resolve_variant_id(&variant_attrs, discriminant_id);
 
                 if !used_ids_enum_unpack.insert(variant_id) {
-                    panic!("Variant ID (0x{:016X}) is duplicated for enum '{}'. Please specify a different ID for variant '{}' using #[senax(id=...)].", variant_id, name, variant_name_str);
+                    return Err(syn::Error::new_spanned(
+                        v,
+                        format!("Variant ID (0x{:016X}) is duplicated for enum '{}'. Please specify a different ID for variant '{}' using #[senax(id=...)].", variant_id, name, variant_name_str),
+                    ));
                 }
 
                 let variant_ident = &v.ident;
@@ -1534,7 +4198,7 @@ pub fn derive_unpack(input: TokenStream) -> TokenStream {
                                 .zip(field_types.iter())
                                 .map(|(ident, ty)| {
                                     quote! {
-                                        #ident: <#ty as senax_encoder::Unpacker>::unpack(reader)?,
+                                        #ident: <#ty as #krate::Unpacker>::unpack(reader)?,
                                     }
                                 });
 
@@ -1542,12 +4206,15 @@ pub fn derive_unpack(input: TokenStream) -> TokenStream {
                             x if x == #variant_id => {
                                 // Read and validate structure hash for named variants
                                 if reader.remaining() < 8 {
-                                    return Err(senax_encoder::EncoderError::InsufficientData);
+                                    return Err(#krate::EncoderError::UnexpectedEof {
+ needed: 8,
+ available: reader.remaining(),
+ });
                                 }
                                 let received_hash = reader.get_u64_le();
                                 if received_hash != #structure_hash {
-                                    return Err(senax_encoder::EncoderError::EnumDecode(
-                                        senax_encoder::EnumDecodeError::StructureHashMismatch {
+                                    return Err(#krate::EncoderError::EnumDecode(
+                                        #krate::EnumDecodeError::StructureHashMismatch {
                                             enum_name: stringify!(#name),
                                             variant_name: stringify!(#variant_ident),
                                             expected: #structure_hash,
@@ -1565,10 +4232,10 @@ pub fn derive_unpack(input: TokenStream) -> TokenStream {
                         variant_unpack.push(quote! {
                             x if x == #variant_id => {
                                 // Read and validate field count for unnamed variants
-                                let field_count = <usize as senax_encoder::Decoder>::decode(reader)?;
+                                let field_count = <usize as #krate::Decoder>::decode(reader)?;
                                 if field_count != #expected_field_count {
-                                    return Err(senax_encoder::EncoderError::EnumDecode(
-                                        senax_encoder::EnumDecodeError::FieldCountMismatch {
+                                    return Err(#krate::EncoderError::EnumDecode(
+                                        #krate::EnumDecodeError::FieldCountMismatch {
                                             enum_name: stringify!(#name),
                                             variant_name: stringify!(#variant_ident),
                                             expected: #expected_field_count,
@@ -1578,7 +4245,7 @@ pub fn derive_unpack(input: TokenStream) -> TokenStream {
                                 }
                                 Ok(#name::#variant_ident(
                                     #(
-                                        <#field_types as senax_encoder::Unpacker>::unpack(reader)?,
+                                        <#field_types as #krate::Unpacker>::unpack(reader)?,
                                     )*
                                 ))
                             }
@@ -1596,11 +4263,11 @@ pub fn derive_unpack(input: TokenStream) -> TokenStream {
 
             // Now we can support mixed variants since variant ID comes first
             quote! {
-                let variant_id = senax_encoder::core::read_field_id_optimized(reader)?;
+                let variant_id = #krate::core::read_field_id_optimized(reader)?;
                 match variant_id {
                     #(#variant_unpack)*
-                    _ => Err(senax_encoder::EncoderError::EnumDecode(
-                        senax_encoder::EnumDecodeError::UnknownVariantId {
+                    _ => Err(#krate::EncoderError::EnumDecode(
+                        #krate::EnumDecodeError::UnknownVariantId {
                             variant_id,
                             enum_name: stringify!(#name),
                         }
@@ -1608,19 +4275,76 @@ pub fn derive_unpack(input: TokenStream) -> TokenStream {
                 }
             }
         }
-        Data::Union(_) => unimplemented!("Unions are not supported"),
+        Data::Union(_) => return Err(union_without_view(input)),
     };
 
+    let unpack_fields = depth_guarded(input, &container_attrs, unpack_fields);
     let unpack_method = quote! {
-        fn unpack(reader: &mut bytes::Bytes) -> senax_encoder::Result<Self> {
-            use bytes::{Buf, BufMut};
+        fn unpack(reader: &mut #krate::bytes::Bytes) -> #krate::Result<Self> {
+            use #krate::bytes::{Buf, BufMut};
             #unpack_fields
         }
     };
 
-    TokenStream::from(quote! {
-        impl #impl_generics senax_encoder::Unpacker for #name #ty_generics #where_clause {
+    let where_clause =
+        codec_where_clause(input, &container_attrs, quote! { #krate::Unpacker }, |_| {
+            true
+        })?;
+
+    Ok(quote! {
+        impl #impl_generics #krate::Unpacker for #name #ty_generics #where_clause {
             #unpack_method
         }
     })
 }
+
+/// Expands the senax derives listed in the item's `#[derive(...)]` attributes and returns the
+/// generated code as a string literal.
+///
+/// Used by `senax_encoder::derive_test` to snapshot expansions. Derives other than `Encode`,
+/// `Decode`, `Pack` and `Unpack` are rejected.
+#[cfg(feature = "derive-test")]
+#[doc(hidden)]
+#[proc_macro]
+pub fn expand_derives(input: TokenStream) -> TokenStream {
+    let mut input = parse_macro_input!(input as DeriveInput);
+    let mut derives = Vec::new();
+    for attr in input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("derive"))
+    {
+        let parsed = attr.parse_args_with(
+            syn::punctuated::Punctuated::<syn::Path, syn::Token![,]>::parse_terminated,
+        );
+        match parsed {
+            Ok(paths) => derives.extend(paths),
+            Err(e) => return e.to_compile_error().into(),
+        }
+    }
+    input.attrs.retain(|attr| !attr.path().is_ident("derive"));
+
+    let mut expanded = proc_macro2::TokenStream::new();
+    for path in &derives {
+        let name = path
+            .segments
+            .last()
+            .map(|segment| segment.ident.to_string());
+        let derived = match name.as_deref() {
+            Some("Encode") => expand_encode(&input),
+            Some("Decode") => expand_decode(&input),
+            Some("Pack") => expand_pack(&input),
+            Some("Unpack") => expand_unpack(&input),
+            _ => Err(syn::Error::new_spanned(
+                path,
+                "expected Encode, Decode, Pack or Unpack",
+            )),
+        };
+        match derived {
+            Ok(tokens) => expanded.extend(deny_panics(tokens)),
+            Err(e) => return e.to_compile_error().into(),
+        }
+    }
+    let expanded = syn::LitStr::new(&expanded.to_string(), Span::call_site());
+    quote!(#expanded).into()
+}
@@ -0,0 +1,170 @@
+//! Runtime description of what this build can read and write.
+
+use crate::core::*;
+use crate::{Decode, Encode};
+
+/// Cargo features, in the order they are reported.
+const FEATURES: &[(&str, bool)] = &[
+    ("ahash", cfg!(feature = "ahash")),
+    ("arbitrary_precision", cfg!(feature = "arbitrary_precision")),
+    ("arrayvec", cfg!(feature = "arrayvec")),
+    ("bigdecimal", cfg!(feature = "bigdecimal")),
+    ("bincode", cfg!(feature = "bincode")),
+    ("chacha20poly1305", cfg!(feature = "chacha20poly1305")),
+    ("chrono", cfg!(feature = "chrono")),
+    ("defmt", cfg!(feature = "defmt")),
+    ("derive-test", cfg!(feature = "derive-test")),
+    ("doc-store", cfg!(feature = "doc-store")),
+    ("futures", cfg!(feature = "futures")),
+    ("fxhash", cfg!(feature = "fxhash")),
+    ("heapless", cfg!(feature = "heapless")),
+    ("indexmap", cfg!(feature = "indexmap")),
+    ("lz4", cfg!(feature = "lz4")),
+    ("postcard", cfg!(feature = "postcard")),
+    ("raw_value", cfg!(feature = "raw_value")),
+    ("rust_decimal", cfg!(feature = "rust_decimal")),
+    ("senax_serde", cfg!(feature = "senax_serde")),
+    ("serde_json", cfg!(feature = "serde_json")),
+    ("serde_yaml", cfg!(feature = "serde_yaml")),
+    ("smallvec", cfg!(feature = "smallvec")),
+    ("smol_str", cfg!(feature = "smol_str")),
+    ("textenc", cfg!(feature = "textenc")),
+    ("time", cfg!(feature = "time")),
+    ("tokio", cfg!(feature = "tokio")),
+    ("toml", cfg!(feature = "toml")),
+    ("ulid", cfg!(feature = "ulid")),
+    (
+        "unicode-normalization",
+        cfg!(feature = "unicode-normalization"),
+    ),
+    ("uuid", cfg!(feature = "uuid")),
+    ("zeroize", cfg!(feature = "zeroize")),
+    ("zstd", cfg!(feature = "zstd")),
+];
+
+/// What a build of senax-encoder supports, as reported by [`capabilities`].
+///
+/// The descriptor is itself encodable, so long-lived services can exchange it with peers built
+/// against other crate versions and avoid sending values the peer cannot read. Fields added in
+/// later versions are skipped by older peers.
+#[derive(Encode, Decode, Debug, Clone, PartialEq, Eq)]
+#[senax(crate = "crate")]
+pub struct Capabilities {
+    /// Version of the senax-encoder crate (`CARGO_PKG_VERSION`).
+    pub crate_version: String,
+    /// Tags this build can decode into typed values, in ascending order.
+    pub tags: Vec<u8>,
+    /// Largest tuple arity that implements the encoding traits ([`MAX_TUPLE_ARITY`]).
+    pub max_tuple_arity: u32,
+    /// Cargo features compiled in, in alphabetical order.
+    pub features: Vec<String>,
+}
+
+impl Capabilities {
+    /// Returns `true` if values with `tag` can be decoded.
+    pub fn supports_tag(&self, tag: u8) -> bool {
+        self.tags.binary_search(&tag).is_ok()
+    }
+
+    /// Returns `true` if the Cargo feature `name` is compiled in.
+    pub fn has_feature(&self, name: &str) -> bool {
+        self.features.iter().any(|feature| feature == name)
+    }
+
+    /// Returns the tags this build supports that `peer` cannot decode.
+    ///
+    /// Values using these tags (e.g. `chrono` or `uuid` types) must not be sent to the peer.
+    pub fn tags_unsupported_by(&self, peer: &Capabilities) -> Vec<u8> {
+        self.tags
+            .iter()
+            .copied()
+            .filter(|tag| !peer.supports_tag(*tag))
+            .collect()
+    }
+
+    /// Returns what both builds support: the common tags and features, the smaller tuple arity
+    /// and the older crate version.
+    pub fn common(&self, peer: &Capabilities) -> Capabilities {
+        let older = if version_key(&peer.crate_version) < version_key(&self.crate_version) {
+            &peer.crate_version
+        } else {
+            &self.crate_version
+        };
+        Capabilities {
+            crate_version: older.clone(),
+            tags: self
+                .tags
+                .iter()
+                .copied()
+                .filter(|tag| peer.supports_tag(*tag))
+                .collect(),
+            max_tuple_arity: self.max_tuple_arity.min(peer.max_tuple_arity),
+            features: self
+                .features
+                .iter()
+                .filter(|feature| peer.has_feature(feature))
+                .cloned()
+                .collect(),
+        }
+    }
+}
+
+/// Describes this build: the crate version, the tags it can decode, the largest tuple arity
+/// and the Cargo features compiled in.
+///
+/// # Example
+/// ```rust
+/// use senax_encoder::core::{MAX_TUPLE_ARITY, TAG_STRING_LONG};
+///
+/// let local = senax_encoder::capabilities();
+/// assert!(local.supports_tag(TAG_STRING_LONG));
+/// assert_eq!(local.max_tuple_arity as usize, MAX_TUPLE_ARITY);
+///
+/// // Exchange descriptors with a peer and stay within what both sides understand
+/// let bytes = senax_encoder::encode(&local).unwrap();
+/// let peer: senax_encoder::Capabilities = senax_encoder::decode(&mut bytes.clone()).unwrap();
+/// assert!(local.tags_unsupported_by(&peer).is_empty());
+/// ```
+pub fn capabilities() -> Capabilities {
+    Capabilities {
+        crate_version: env!("CARGO_PKG_VERSION").to_string(),
+        tags: (0..=u8::MAX).filter(|tag| tag_supported(*tag)).collect(),
+        max_tuple_arity: MAX_TUPLE_ARITY as u32,
+        features: FEATURES
+            .iter()
+            .filter(|(_, enabled)| *enabled)
+            .map(|(name, _)| name.to_string())
+            .collect(),
+    }
+}
+
+// With every feature enabled all arms are `true`, which clippy would rather see as `matches!`
+#[allow(clippy::match_like_matches_macro)]
+fn tag_supported(tag: u8) -> bool {
+    match tag {
+        TAG_ZERO..=TAG_U8_127 | TAG_NONE | TAG_SOME | TAG_U8..=TAG_MAP => true,
+        TAG_MAP_STRING_KEYS | TAG_MAP_FLAT_KEYS | TAG_MAP_ID_KEYS => true,
+        TAG_DURATION | TAG_SYSTEM_TIME => true,
+        TAG_CHRONO_DATETIME
+        | TAG_CHRONO_NAIVE_DATE
+        | TAG_CHRONO_NAIVE_TIME
+        | TAG_CHRONO_NAIVE_DATETIME => cfg!(any(feature = "chrono", feature = "time")),
+        TAG_DECIMAL => cfg!(any(feature = "rust_decimal", feature = "bigdecimal")),
+        TAG_UUID => cfg!(any(feature = "uuid", feature = "ulid")),
+        TAG_JSON_NULL..=TAG_JSON_OBJECT => cfg!(any(
+            feature = "serde_json",
+            feature = "serde_yaml",
+            feature = "toml"
+        )),
+        _ => false,
+    }
+}
+
+/// Orders `major.minor.patch` versions numerically; pre-release suffixes are ignored.
+fn version_key(version: &str) -> Vec<u64> {
+    version
+        .split(['.', '-', '+'])
+        .take(3)
+        .map(|part| part.parse().unwrap_or(0))
+        .collect()
+}
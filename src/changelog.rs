@@ -0,0 +1,248 @@
+//! Snapshot plus incremental changelog replication.
+//!
+//! A [`Snapshot`] is the encoded state of a value at a version. A [`ChangeLog`] starts from a
+//! snapshot and records one [`Patch`] per update, so a replica that holds any earlier version
+//! catches up by applying the patches recorded since ([`ChangeLog::since`]) with [`replay`],
+//! and a new replica starts from [`ChangeLog::head`]. [`ChangeLog::compact`] folds the patches
+//! into a new base snapshot.
+//!
+//! Snapshots, patches and changelogs all implement [`Encoder`]/[`Decoder`], so each can be
+//! stored or sent as a message. Patches are applied to encoded payloads; values are only
+//! decoded when asked for.
+//!
+//! # Example
+//! ```rust
+//! use senax_encoder::changelog::{replay, ChangeLog, Snapshot};
+//! use senax_encoder::{Decode, Encode};
+//!
+//! #[derive(Encode, Decode, Debug, PartialEq, Clone)]
+//! struct Room { topic: String, members: Vec<String> }
+//!
+//! let mut room = Room { topic: "lobby".into(), members: vec![] };
+//! let mut log = ChangeLog::new(Snapshot::new(0, &room).unwrap());
+//! let replica = log.head();
+//!
+//! room.members.push("ann".into());
+//! log.record(&room).unwrap();
+//! room.topic = "standup".into();
+//! log.record(&room).unwrap();
+//!
+//! let patches = log.since(replica.version()).unwrap();
+//! let replica = replay(&replica, patches).unwrap();
+//! assert_eq!(replica.version(), 2);
+//! assert_eq!(replica.value().unwrap(), room);
+//! ```
+
+use crate::patch::{diff_payloads, Patch};
+use crate::{decode, encode, Decoder, Encoder, EncoderError, Result};
+use bytes::{Bytes, BytesMut};
+use std::marker::PhantomData;
+
+/// The encoded state of a `T` at a version.
+#[derive(Debug)]
+pub struct Snapshot<T> {
+    version: u64,
+    payload: Bytes,
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<T> Clone for Snapshot<T> {
+    fn clone(&self) -> Self {
+        Self::from_payload(self.version, self.payload.clone())
+    }
+}
+
+impl<T> PartialEq for Snapshot<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.version == other.version && self.payload == other.payload
+    }
+}
+
+impl<T> Snapshot<T> {
+    /// Wraps an `encode()` payload (with magic number) of a `T`.
+    pub fn from_payload(version: u64, payload: Bytes) -> Self {
+        Self {
+            version,
+            payload,
+            marker: PhantomData,
+        }
+    }
+
+    /// Returns the version of the state.
+    pub fn version(&self) -> u64 {
+        self.version
+    }
+
+    /// Returns the encoded state (with magic number).
+    pub fn payload(&self) -> &Bytes {
+        &self.payload
+    }
+
+    /// Applies `patch` and returns the snapshot of the next version.
+    pub fn apply(&self, patch: &Patch) -> Result<Self> {
+        let version = self
+            .version
+            .checked_add(1)
+            .ok_or_else(|| EncoderError::Encode("Snapshot version overflowed u64".to_string()))?;
+        Ok(Self::from_payload(
+            version,
+            patch.apply_payload(&self.payload)?,
+        ))
+    }
+}
+
+impl<T: Encoder> Snapshot<T> {
+    /// Encodes `value` as the state at `version`.
+    pub fn new(version: u64, value: &T) -> Result<Self> {
+        Ok(Self::from_payload(version, encode(value)?))
+    }
+}
+
+impl<T: Decoder> Snapshot<T> {
+    /// Decodes the state.
+    pub fn value(&self) -> Result<T> {
+        decode(&mut self.payload.clone())
+    }
+}
+
+impl<T> Encoder for Snapshot<T> {
+    fn encode(&self, writer: &mut BytesMut) -> Result<()> {
+        (self.version, &self.payload).encode(writer)
+    }
+
+    fn is_default(&self) -> bool {
+        false
+    }
+}
+
+impl<T> Decoder for Snapshot<T> {
+    fn decode(reader: &mut Bytes) -> Result<Self> {
+        let (version, payload) = Decoder::decode(reader)?;
+        Ok(Self::from_payload(version, payload))
+    }
+}
+
+/// Applies a chain of patches to `snapshot`, one version per patch.
+pub fn replay<'a, T>(
+    snapshot: &Snapshot<T>,
+    patches: impl IntoIterator<Item = &'a Patch>,
+) -> Result<Snapshot<T>> {
+    let mut snapshot = snapshot.clone();
+    for patch in patches {
+        snapshot = snapshot.apply(patch)?;
+    }
+    Ok(snapshot)
+}
+
+/// Applies a chain of patches encoded with [`encode`] to `snapshot`.
+pub fn replay_encoded<T>(
+    snapshot: &Snapshot<T>,
+    patches: impl IntoIterator<Item = Bytes>,
+) -> Result<Snapshot<T>> {
+    let mut snapshot = snapshot.clone();
+    for mut patch in patches {
+        snapshot = snapshot.apply(&decode(&mut patch)?)?;
+    }
+    Ok(snapshot)
+}
+
+/// A base snapshot followed by the patches of every later version.
+#[derive(Debug)]
+pub struct ChangeLog<T> {
+    base: Snapshot<T>,
+    patches: Vec<Patch>,
+    head: Snapshot<T>,
+}
+
+impl<T> Clone for ChangeLog<T> {
+    fn clone(&self) -> Self {
+        Self {
+            base: self.base.clone(),
+            patches: self.patches.clone(),
+            head: self.head.clone(),
+        }
+    }
+}
+
+impl<T> ChangeLog<T> {
+    /// Starts a changelog at `base`.
+    pub fn new(base: Snapshot<T>) -> Self {
+        Self {
+            head: base.clone(),
+            base,
+            patches: Vec::new(),
+        }
+    }
+
+    /// Returns the base snapshot.
+    pub fn base(&self) -> &Snapshot<T> {
+        &self.base
+    }
+
+    /// Returns the latest state.
+    pub fn head(&self) -> Snapshot<T> {
+        self.head.clone()
+    }
+
+    /// Returns the latest version.
+    pub fn version(&self) -> u64 {
+        self.head.version
+    }
+
+    /// Returns the patches after the base snapshot, oldest first.
+    pub fn patches(&self) -> &[Patch] {
+        &self.patches
+    }
+
+    /// Returns the patches that bring a replica at `version` up to date, or `None` if that
+    /// version is older than the base snapshot or newer than the head.
+    pub fn since(&self, version: u64) -> Option<&[Patch]> {
+        let start = version.checked_sub(self.base.version)?;
+        self.patches.get(usize::try_from(start).ok()?..)
+    }
+
+    /// Appends a patch as the next version.
+    pub fn push(&mut self, patch: Patch) -> Result<()> {
+        self.head = self.head.apply(&patch)?;
+        self.patches.push(patch);
+        Ok(())
+    }
+
+    /// Makes the head the new base snapshot and drops the patches.
+    pub fn compact(&mut self) {
+        self.base = self.head.clone();
+        self.patches.clear();
+    }
+}
+
+impl<T: Encoder> ChangeLog<T> {
+    /// Records `value` as the next version and returns the patch from the previous one.
+    pub fn record(&mut self, value: &T) -> Result<&Patch> {
+        let patch = diff_payloads(&self.head.payload, &encode(value)?)?;
+        let index = self.patches.len();
+        self.push(patch)?;
+        Ok(&self.patches[index])
+    }
+}
+
+impl<T> Encoder for ChangeLog<T> {
+    fn encode(&self, writer: &mut BytesMut) -> Result<()> {
+        (&self.base, &self.patches).encode(writer)
+    }
+
+    fn is_default(&self) -> bool {
+        false
+    }
+}
+
+impl<T> Decoder for ChangeLog<T> {
+    fn decode(reader: &mut Bytes) -> Result<Self> {
+        let (base, patches): (Snapshot<T>, Vec<Patch>) = Decoder::decode(reader)?;
+        let head = replay(&base, &patches)?;
+        Ok(Self {
+            base,
+            patches,
+            head,
+        })
+    }
+}
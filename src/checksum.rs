@@ -0,0 +1,140 @@
+//! Payloads with a trailing checksum, for transports that may corrupt data.
+//!
+//! [`encode_checked`] appends a checksum of the [`encode`] output:
+//!
+//! ```text
+//! [payload: encode() output] [checksum: 4 or 8 bytes little-endian]
+//! ```
+//!
+//! [`decode_checked`] verifies it before decoding and fails with
+//! [`EncoderError::ChecksumMismatch`] if any byte changed. The envelope carries no marker for
+//! the algorithm, so both ends must use the same [`Checksum`].
+
+use crate::{decode_exact, encode, Decoder, Encoder, EncoderError, Result};
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+
+/// Checksum algorithm of an [`encode_checked`] envelope.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Checksum {
+    /// CRC-32 (ISO-HDLC, as used by zlib and Ethernet), 4 bytes.
+    Crc32,
+    /// CRC-64/ECMA-182, the checksum behind derived field IDs, 8 bytes.
+    Crc64,
+}
+
+impl Checksum {
+    /// Returns the number of bytes the checksum takes.
+    pub const fn size(self) -> usize {
+        match self {
+            Checksum::Crc32 => 4,
+            Checksum::Crc64 => 8,
+        }
+    }
+
+    /// Computes the checksum of `bytes`, widened to `u64`.
+    ///
+    /// # Example
+    /// ```rust
+    /// use senax_encoder::Checksum;
+    ///
+    /// assert_eq!(Checksum::Crc32.compute(b"123456789"), 0xCBF4_3926);
+    /// assert_eq!(Checksum::Crc64.compute(b"123456789"), 0x6C40_DF5F_0B49_7347);
+    /// ```
+    pub fn compute(self, bytes: &[u8]) -> u64 {
+        match self {
+            Checksum::Crc32 => crc32(bytes) as u64,
+            Checksum::Crc64 => crc64(bytes),
+        }
+    }
+}
+
+const CRC32_TABLE: [u32; 256] = {
+    let mut table = [0u32; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ 0xEDB8_8320
+            } else {
+                crc >> 1
+            };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+};
+
+const CRC64_TABLE: [u64; 256] = {
+    let mut table = [0u64; 256];
+    let mut i = 0;
+    while i < 256 {
+        table[i] = crate::core::crc64_update(0, &[i as u8]);
+        i += 1;
+    }
+    table
+};
+
+fn crc32(bytes: &[u8]) -> u32 {
+    !bytes.iter().fold(!0u32, |crc, &b| {
+        CRC32_TABLE[((crc ^ b as u32) & 0xFF) as usize] ^ (crc >> 8)
+    })
+}
+
+fn crc64(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0u64, |crc, &b| {
+        CRC64_TABLE[((crc >> 56) as u8 ^ b) as usize] ^ (crc << 8)
+    })
+}
+
+/// Encodes a value like [`encode`] and appends its checksum.
+///
+/// # Example
+/// ```rust
+/// use senax_encoder::{decode_checked, encode_checked, Checksum, EncoderError};
+///
+/// let envelope = encode_checked(&"payload".to_string(), Checksum::Crc32).unwrap();
+/// let text: String = decode_checked(&mut envelope.clone(), Checksum::Crc32).unwrap();
+/// assert_eq!(text, "payload");
+///
+/// let mut corrupted = envelope.to_vec();
+/// corrupted[4] ^= 0x01;
+/// let err = decode_checked::<String>(&mut corrupted.into(), Checksum::Crc32).unwrap_err();
+/// assert!(matches!(err, EncoderError::ChecksumMismatch { .. }));
+/// ```
+pub fn encode_checked<T: Encoder>(value: &T, checksum: Checksum) -> Result<Bytes> {
+    let payload = encode(value)?;
+    let mut writer = BytesMut::with_capacity(payload.len() + checksum.size());
+    writer.put_slice(&payload);
+    let sum = checksum.compute(&payload);
+    match checksum {
+        Checksum::Crc32 => writer.put_u32_le(sum as u32),
+        Checksum::Crc64 => writer.put_u64_le(sum),
+    }
+    Ok(writer.freeze())
+}
+
+/// Verifies the checksum of an envelope written by [`encode_checked`] and decodes its payload.
+///
+/// The envelope must make up all of `reader`, which is consumed. Fails with
+/// [`EncoderError::ChecksumMismatch`] before decoding if the checksum does not match, and with
+/// [`EncoderError::TrailingData`] if the value does not fill the payload.
+pub fn decode_checked<T: Decoder>(reader: &mut Bytes, checksum: Checksum) -> Result<T> {
+    let Some(payload_len) = reader.len().checked_sub(checksum.size()) else {
+        return Err(EncoderError::eof(checksum.size(), reader.len()));
+    };
+    let mut payload = reader.split_to(payload_len);
+    let mut stored = std::mem::take(reader);
+    let expected = match checksum {
+        Checksum::Crc32 => stored.get_u32_le() as u64,
+        Checksum::Crc64 => stored.get_u64_le(),
+    };
+    let actual = checksum.compute(&payload);
+    if actual != expected {
+        return Err(EncoderError::ChecksumMismatch { expected, actual });
+    }
+    decode_exact(&mut payload)
+}
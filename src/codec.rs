@@ -0,0 +1,241 @@
+//! `tokio` codec and async helpers for length-delimited messages.
+//!
+//! [`SenaxCodec`] implements `tokio_util::codec::Encoder` and `Decoder`, so
+//! `tokio_util::codec::Framed` (or `FramedRead` / `FramedWrite`) turns a socket into a
+//! `Stream` + `Sink` of values. [`encode_async`] and [`decode_async`] write and read single
+//! frames without a codec. All of them use the [record log](crate::record_log) frame format,
+//! the same as the `futures`-based `framed` module:
+//!
+//! ```text
+//! [payload_len: u32 little-endian] [payload: encode() output] ...
+//! ```
+
+use crate::frame::{self, HEADER_LEN};
+use crate::{decode, encode_to, Decoder, Encoder, EncoderError, Result};
+use bytes::{Buf, BufMut, BytesMut};
+use std::marker::PhantomData;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+/// Default limit on the payload length accepted by [`SenaxCodec`] and [`decode_async`].
+pub const DEFAULT_MAX_FRAME_LEN: usize = crate::DEFAULT_MAX_FRAME_LEN;
+
+/// A `tokio_util` codec encoding and decoding values of type `T` as length-delimited frames.
+///
+/// # Example
+/// ```rust
+/// use futures::{SinkExt, StreamExt};
+/// use senax_encoder::codec::SenaxCodec;
+/// use tokio_util::codec::Framed;
+///
+/// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+/// let (client, server) = tokio::io::duplex(1024);
+/// let mut client = Framed::new(client, SenaxCodec::<String>::new());
+/// let mut server = Framed::new(server, SenaxCodec::<String>::new());
+///
+/// client.send("ping".to_string()).await.unwrap();
+/// assert_eq!(server.next().await.unwrap().unwrap(), "ping");
+/// # });
+/// ```
+pub struct SenaxCodec<T> {
+    max_frame_len: usize,
+    marker: PhantomData<fn(T) -> T>,
+}
+
+impl<T> SenaxCodec<T> {
+    /// Creates a codec accepting frames up to [`DEFAULT_MAX_FRAME_LEN`].
+    pub fn new() -> Self {
+        Self {
+            max_frame_len: DEFAULT_MAX_FRAME_LEN,
+            marker: PhantomData,
+        }
+    }
+
+    /// Sets the longest payload accepted; longer frames fail to decode and encode.
+    pub fn with_max_frame_len(mut self, max_frame_len: usize) -> Self {
+        self.max_frame_len = max_frame_len;
+        self
+    }
+
+    /// The longest payload accepted.
+    pub fn max_frame_len(&self) -> usize {
+        self.max_frame_len
+    }
+
+    /// Reads the payload length at the start of `src` and checks it against the limit.
+    fn frame_len(&self, src: &[u8]) -> Result<usize> {
+        let len = frame::read_header(src)? as usize;
+        self.check_len(len)?;
+        Ok(len)
+    }
+
+    fn check_len(&self, len: usize) -> Result<()> {
+        if len > self.max_frame_len {
+            return Err(EncoderError::Decode(format!(
+                "Frame of {} bytes exceeds the limit of {} bytes",
+                len, self.max_frame_len
+            )));
+        }
+        Ok(())
+    }
+}
+
+impl<T> Default for SenaxCodec<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Clone for SenaxCodec<T> {
+    fn clone(&self) -> Self {
+        Self {
+            max_frame_len: self.max_frame_len,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<T> std::fmt::Debug for SenaxCodec<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SenaxCodec")
+            .field("max_frame_len", &self.max_frame_len)
+            .finish()
+    }
+}
+
+impl<T: Encoder> tokio_util::codec::Encoder<T> for SenaxCodec<T> {
+    type Error = EncoderError;
+
+    fn encode(&mut self, item: T, dst: &mut BytesMut) -> Result<()> {
+        write_frame(&item, dst, self.max_frame_len)
+    }
+}
+
+impl<T: Decoder> tokio_util::codec::Decoder for SenaxCodec<T> {
+    type Item = T;
+    type Error = EncoderError;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<T>> {
+        if src.len() < HEADER_LEN {
+            return Ok(None);
+        }
+        let len = self.frame_len(src)?;
+        if src.len() < HEADER_LEN + len {
+            src.reserve(HEADER_LEN + len - src.len());
+            return Ok(None);
+        }
+        src.advance(HEADER_LEN);
+        let mut payload = src.split_to(len).freeze();
+        decode(&mut payload).map(Some)
+    }
+
+    fn decode_eof(&mut self, src: &mut BytesMut) -> Result<Option<T>> {
+        match self.decode(src)? {
+            Some(item) => Ok(Some(item)),
+            None if src.is_empty() => Ok(None),
+            None => {
+                // The peer closed the connection inside a frame
+                let needed = self
+                    .frame_len(src)
+                    .map_or(HEADER_LEN, |len| HEADER_LEN + len);
+                let available = src.len();
+                src.clear();
+                Err(EncoderError::eof(needed, available))
+            }
+        }
+    }
+}
+
+/// Appends `value` to `dst` as one frame.
+fn write_frame<T: Encoder>(value: &T, dst: &mut BytesMut, max_frame_len: usize) -> Result<()> {
+    let start = dst.len();
+    dst.put_u32_le(0);
+    if let Err(e) = encode_to(value, dst) {
+        dst.truncate(start);
+        return Err(e);
+    }
+    let len = dst.len() - start - HEADER_LEN;
+    let header = if len > max_frame_len {
+        Err(EncoderError::Encode(format!(
+            "Message of {} bytes exceeds the frame limit of {} bytes",
+            len, max_frame_len
+        )))
+    } else {
+        frame::header(len)
+    };
+    match header {
+        Ok(header) => {
+            dst[start..start + HEADER_LEN].copy_from_slice(&header);
+            Ok(())
+        }
+        Err(e) => {
+            dst.truncate(start);
+            Err(e)
+        }
+    }
+}
+
+/// Writes `value` to `writer` as one frame.
+///
+/// The writer is not flushed, so several frames can be batched through a `BufWriter`.
+///
+/// # Example
+/// ```rust
+/// use senax_encoder::codec::{decode_async, encode_async};
+///
+/// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+/// let mut buf = Vec::new();
+/// encode_async(&mut buf, &42u32).await.unwrap();
+/// encode_async(&mut buf, &7u32).await.unwrap();
+///
+/// let mut reader = buf.as_slice();
+/// assert_eq!(decode_async::<_, u32>(&mut reader).await.unwrap(), Some(42));
+/// assert_eq!(decode_async::<_, u32>(&mut reader).await.unwrap(), Some(7));
+/// assert_eq!(decode_async::<_, u32>(&mut reader).await.unwrap(), None);
+/// # });
+/// ```
+pub async fn encode_async<W, T>(writer: &mut W, value: &T) -> Result<()>
+where
+    W: AsyncWrite + Unpin,
+    T: Encoder,
+{
+    let mut frame = BytesMut::new();
+    write_frame(value, &mut frame, DEFAULT_MAX_FRAME_LEN)?;
+    writer.write_all(&frame).await?;
+    Ok(())
+}
+
+/// Reads one frame from `reader` and decodes it as `T`.
+///
+/// Returns `Ok(None)` if the reader ends before the next frame, and
+/// [`EncoderError::UnexpectedEof`] if it ends inside one. Frames longer than
+/// [`DEFAULT_MAX_FRAME_LEN`] are rejected before their payload is read.
+pub async fn decode_async<R, T>(reader: &mut R) -> Result<Option<T>>
+where
+    R: AsyncRead + Unpin,
+    T: Decoder,
+{
+    let mut header = [0u8; HEADER_LEN];
+    let mut filled = 0;
+    while filled < HEADER_LEN {
+        let n = reader.read(&mut header[filled..]).await?;
+        if n == 0 {
+            return if filled == 0 {
+                Ok(None)
+            } else {
+                Err(EncoderError::eof(HEADER_LEN, filled))
+            };
+        }
+        filled += n;
+    }
+    let len = SenaxCodec::<T>::new().frame_len(&header)?;
+    let mut payload = vec![0u8; len];
+    let mut filled = 0;
+    while filled < len {
+        let n = reader.read(&mut payload[filled..]).await?;
+        if n == 0 {
+            return Err(EncoderError::eof(HEADER_LEN + len, HEADER_LEN + filled));
+        }
+        filled += n;
+    }
+    decode(&mut payload.into()).map(Some)
+}
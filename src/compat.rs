@@ -0,0 +1,219 @@
+//! Byte-level comparison of the `encode` and `pack` formats.
+//!
+//! The two formats share their scalar encodings, so it is tempting to feed `pack` output to a
+//! `decode` consumer. They are not interchangeable in general: the payloads start with
+//! different magic numbers (0xA55A and 0xDADA), `encode` puts a `TAG_STRUCT_UNNAMED` header
+//! in front of tuple structs, named structs are written with field IDs only by `encode`,
+//! floats are written as decimal strings by `encode` but as IEEE 754 bytes by `pack`, and `u8`
+//! values above 127 are packed as a raw byte.
+//!
+//! [`compare_pack_encode`] computes how the two bodies (without magic numbers) relate for a
+//! given value, and [`assert_pack_encode_equivalent`] turns that into a test assertion.
+//!
+//! With the `bincode` and `postcard` features, `from_bincode` and `from_postcard` read
+//! payloads written by those crates through the type's serde implementation and write them
+//! again with [`encode`](crate::encode), for migrating stored data to senax. Neither format
+//! records field names or types, so the type must be the one the payload was written with.
+
+use crate::core::TAG_STRUCT_UNNAMED;
+#[cfg(any(feature = "bincode", feature = "postcard"))]
+use crate::EncoderError;
+use crate::{Encoder, Packer, Result};
+use bytes::{Bytes, BytesMut};
+use std::fmt;
+
+/// How the `encode` and `pack` output of one value relate.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PackEncodeReport {
+    /// `Encoder::encode` output (no magic number).
+    pub encoded: Bytes,
+    /// `Packer::pack` output (no magic number).
+    pub packed: Bytes,
+}
+
+impl PackEncodeReport {
+    /// Returns `true` if both bodies are byte-for-byte identical.
+    pub fn is_identical(&self) -> bool {
+        self.encoded == self.packed
+    }
+
+    /// Returns `true` if the encoded body is the packed body behind a `TAG_STRUCT_UNNAMED`
+    /// byte, as for tuple structs whose fields have identical encodings.
+    pub fn differs_only_by_header(&self) -> bool {
+        self.encoded.first() == Some(&TAG_STRUCT_UNNAMED) && self.encoded[1..] == self.packed[..]
+    }
+
+    /// Returns `true` if the values are written identically apart from the tuple struct header.
+    pub fn is_equivalent(&self) -> bool {
+        self.is_identical() || self.differs_only_by_header()
+    }
+
+    /// Offset of the first differing byte, or `None` if the bodies are identical.
+    pub fn first_difference(&self) -> Option<usize> {
+        let common = self
+            .encoded
+            .iter()
+            .zip(self.packed.iter())
+            .position(|(a, b)| a != b);
+        match common {
+            Some(offset) => Some(offset),
+            None if self.encoded.len() != self.packed.len() => {
+                Some(self.encoded.len().min(self.packed.len()))
+            }
+            None => None,
+        }
+    }
+}
+
+impl fmt::Display for PackEncodeReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_identical() {
+            return write!(
+                f,
+                "encode and pack bodies are identical ({} bytes)",
+                self.encoded.len()
+            );
+        }
+        if self.differs_only_by_header() {
+            return write!(
+                f,
+                "encode body is the pack body behind a TAG_STRUCT_UNNAMED header ({} + 1 bytes)",
+                self.packed.len()
+            );
+        }
+        let offset = self.first_difference().unwrap_or(0);
+        let byte = |bytes: &Bytes| match bytes.get(offset) {
+            Some(b) => format!("0x{:02X}", b),
+            None => "end of data".to_string(),
+        };
+        write!(
+            f,
+            "encode and pack bodies differ at byte {}: encode has {}, pack has {} (encode {}, pack {})",
+            offset,
+            byte(&self.encoded),
+            byte(&self.packed),
+            hex(&self.encoded),
+            hex(&self.packed)
+        )
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Encodes and packs `value` and reports how the two bodies relate.
+///
+/// # Example
+/// ```rust
+/// use senax_encoder::compat::compare_pack_encode;
+/// use senax_encoder::{Encode, Pack};
+///
+/// #[derive(Encode, Pack)]
+/// struct Point(u32, i64);
+///
+/// let report = compare_pack_encode(&Point(300, -5)).unwrap();
+/// assert!(report.differs_only_by_header());
+///
+/// let report = compare_pack_encode(&1.5f64).unwrap();
+/// assert!(!report.is_equivalent());
+/// println!("{}", report); // encode and pack bodies differ at byte 0: ...
+/// ```
+pub fn compare_pack_encode<T: Encoder + Packer>(value: &T) -> Result<PackEncodeReport> {
+    let mut encoded = BytesMut::new();
+    value.encode(&mut encoded)?;
+    let mut packed = BytesMut::new();
+    value.pack(&mut packed)?;
+    Ok(PackEncodeReport {
+        encoded: encoded.freeze(),
+        packed: packed.freeze(),
+    })
+}
+
+/// Panics unless `T::default()` is written identically by `encode` and `pack`, apart from the
+/// tuple struct header (see [`PackEncodeReport::is_equivalent`]).
+///
+/// Meant for tests of attribute-free, scalar-only tuple structs. The panic message describes
+/// the computed difference. The magic numbers always differ and are not compared.
+///
+/// # Example
+/// ```rust
+/// use senax_encoder::compat::assert_pack_encode_equivalent;
+/// use senax_encoder::{Encode, Pack};
+///
+/// #[derive(Encode, Pack, Default)]
+/// struct Reading(u16, i32, bool, String);
+///
+/// assert_pack_encode_equivalent::<Reading>();
+/// ```
+#[track_caller]
+#[allow(clippy::panic)]
+pub fn assert_pack_encode_equivalent<T: Encoder + Packer + Default>() {
+    let report = compare_pack_encode(&T::default())
+        .unwrap_or_else(|e| panic!("{}: failed to write: {}", std::any::type_name::<T>(), e));
+    assert!(
+        report.is_equivalent(),
+        "{}: {}",
+        std::any::type_name::<T>(),
+        report
+    );
+}
+
+/// Reads a payload written by `bincode::serialize` (bincode 1.x default options) and encodes
+/// the value with [`encode`](crate::encode).
+///
+/// `T` must have the serde layout the payload was written with; derive `Encode`/`Decode` on
+/// it next to `Serialize`/`Deserialize` to migrate stored data.
+///
+/// # Example
+/// ```rust
+/// use senax_encoder::compat::from_bincode;
+/// use senax_encoder::{decode, Decode, Encode};
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Serialize, Deserialize, Encode, Decode, Debug, PartialEq)]
+/// struct Order { id: u64, items: Vec<String> }
+///
+/// let order = Order { id: 1, items: vec!["apple".into()] };
+/// let legacy = bincode::serialize(&order).unwrap();
+///
+/// let mut migrated = from_bincode::<Order>(&legacy).unwrap();
+/// assert_eq!(decode::<Order>(&mut migrated).unwrap(), order);
+/// ```
+#[cfg(feature = "bincode")]
+pub fn from_bincode<T>(bytes: &[u8]) -> Result<Bytes>
+where
+    T: serde::de::DeserializeOwned + Encoder,
+{
+    let value: T = bincode::deserialize(bytes)
+        .map_err(|e| EncoderError::Decode(format!("Invalid bincode payload: {}", e)))?;
+    crate::encode(&value)
+}
+
+/// Reads a payload written by `postcard::to_allocvec` (or `to_slice`) and encodes the value
+/// with [`encode`](crate::encode).
+///
+/// `T` must have the serde layout the payload was written with, as for [`from_bincode`].
+///
+/// # Example
+/// ```rust
+/// use senax_encoder::compat::from_postcard;
+/// use senax_encoder::{decode, Decode, Encode};
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Serialize, Deserialize, Encode, Decode, Debug, PartialEq)]
+/// enum Event { Started, Moved { x: i32, y: i32 } }
+///
+/// let legacy = postcard::to_allocvec(&Event::Moved { x: 3, y: -1 }).unwrap();
+/// let mut migrated = from_postcard::<Event>(&legacy).unwrap();
+/// assert_eq!(decode::<Event>(&mut migrated).unwrap(), Event::Moved { x: 3, y: -1 });
+/// ```
+#[cfg(feature = "postcard")]
+pub fn from_postcard<T>(bytes: &[u8]) -> Result<Bytes>
+where
+    T: serde::de::DeserializeOwned + Encoder,
+{
+    let value: T = postcard::from_bytes(bytes)
+        .map_err(|e| EncoderError::Decode(format!("Invalid postcard payload: {}", e)))?;
+    crate::encode(&value)
+}
@@ -0,0 +1,187 @@
+//! Compressed payloads, for large values sent over slow links or kept in storage.
+//!
+//! [`encode_compressed`] compresses the [`encode`] output behind a small header:
+//!
+//! ```text
+//! [magic: 0xA55C] [algorithm: u8] [uncompressed_len] [compressed payload]
+//! ```
+//!
+//! The algorithm byte is 0 for uncompressed payloads, 1 for zstd and 2 for lz4 (frame format),
+//! so [`decode_compressed`] needs no hint from the caller. Each algorithm is behind the cargo
+//! feature of the same name; payloads using an algorithm this build lacks fail to decode.
+
+use crate::{
+    current_decode_config, decode_exact, encode, DecodeLimit, Decoder, Encoder, EncoderError,
+    Result,
+};
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+use std::io::Read;
+
+/// Magic number for compressed payloads (0xA55C in little-endian).
+pub const COMPRESSED_MAGIC: u16 = 0xA55C;
+
+const ALGORITHM_NONE: u8 = 0;
+const ALGORITHM_ZSTD: u8 = 1;
+const ALGORITHM_LZ4: u8 = 2;
+
+/// Compression algorithm of an [`encode_compressed`] payload.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Compression {
+    /// Stores the payload as is, keeping the header so readers need not special-case it.
+    None,
+    /// zstd at the given level (1 to 22; 0 selects zstd's default). Requires the `zstd`
+    /// feature.
+    #[cfg(feature = "zstd")]
+    Zstd(i32),
+    /// lz4 frame format, faster than zstd but with lower ratios. Requires the `lz4` feature.
+    #[cfg(feature = "lz4")]
+    Lz4,
+}
+
+impl Compression {
+    fn id(self) -> u8 {
+        match self {
+            Compression::None => ALGORITHM_NONE,
+            #[cfg(feature = "zstd")]
+            Compression::Zstd(_) => ALGORITHM_ZSTD,
+            #[cfg(feature = "lz4")]
+            Compression::Lz4 => ALGORITHM_LZ4,
+        }
+    }
+
+    fn compress(self, payload: &[u8], writer: &mut BytesMut) -> Result<()> {
+        match self {
+            Compression::None => {
+                writer.put_slice(payload);
+                Ok(())
+            }
+            #[cfg(feature = "zstd")]
+            Compression::Zstd(level) => {
+                let compressed = zstd::bulk::compress(payload, level)
+                    .map_err(|e| EncoderError::Encode(format!("zstd compression failed: {e}")))?;
+                writer.put_slice(&compressed);
+                Ok(())
+            }
+            #[cfg(feature = "lz4")]
+            Compression::Lz4 => {
+                use std::io::Write;
+                let mut encoder = lz4_flex::frame::FrameEncoder::new(writer.writer());
+                encoder
+                    .write_all(payload)
+                    .map_err(|e| EncoderError::Encode(format!("lz4 compression failed: {e}")))?;
+                encoder
+                    .finish()
+                    .map_err(|e| EncoderError::Encode(format!("lz4 compression failed: {e}")))?;
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Encodes a value like [`encode`] and compresses the result.
+///
+/// # Example
+/// ```rust
+/// use senax_encoder::{decode_compressed, encode_compressed, Compression};
+///
+/// let log = "GET /index.html 200\n".repeat(100);
+/// # #[cfg(feature = "zstd")]
+/// let compression = Compression::Zstd(3);
+/// # #[cfg(not(feature = "zstd"))]
+/// # let compression = Compression::None;
+/// let payload = encode_compressed(&log, compression).unwrap();
+/// # #[cfg(feature = "zstd")]
+/// assert!(payload.len() < log.len() / 10);
+/// let text: String = decode_compressed(&mut payload.clone()).unwrap();
+/// assert_eq!(text, log);
+/// ```
+pub fn encode_compressed<T: Encoder>(value: &T, compression: Compression) -> Result<Bytes> {
+    let payload = encode(value)?;
+    let mut writer = BytesMut::new();
+    writer.put_u16_le(COMPRESSED_MAGIC);
+    writer.put_u8(compression.id());
+    payload.len().encode(&mut writer)?;
+    compression.compress(&payload, &mut writer)?;
+    Ok(writer.freeze())
+}
+
+/// Decompresses a payload written by [`encode_compressed`] and decodes it.
+///
+/// The payload must make up all of `reader`, which is consumed. The uncompressed length in
+/// the header is checked against [`DecodeConfig::max_alloc_bytes`](crate::DecodeConfig) before
+/// anything is decompressed, and decompression stops as soon as it produces more bytes than
+/// the header announced, so small inputs cannot expand without bound.
+pub fn decode_compressed<T: Decoder>(reader: &mut Bytes) -> Result<T> {
+    if reader.remaining() < 3 {
+        return Err(EncoderError::eof(3, reader.remaining()));
+    }
+    let magic = reader.get_u16_le();
+    if magic != COMPRESSED_MAGIC {
+        return Err(EncoderError::Decode(format!(
+            "Invalid compressed magic number: expected 0x{:04X}, got 0x{:04X}",
+            COMPRESSED_MAGIC, magic
+        )));
+    }
+    let algorithm = reader.get_u8();
+    let len = usize::decode(reader)?;
+    if let Some(limit) = current_decode_config().max_alloc_bytes {
+        if len > limit {
+            return Err(EncoderError::LimitExceeded {
+                kind: DecodeLimit::AllocatedBytes,
+                len,
+                limit,
+            });
+        }
+    }
+    let body = std::mem::take(reader);
+    let mut payload = match algorithm {
+        ALGORITHM_NONE => body,
+        #[cfg(feature = "zstd")]
+        ALGORITHM_ZSTD => {
+            let decoder = zstd::stream::read::Decoder::new(body.reader())
+                .map_err(|e| EncoderError::Decode(format!("zstd decompression failed: {e}")))?;
+            decompress(decoder, len, "zstd")?
+        }
+        #[cfg(feature = "lz4")]
+        ALGORITHM_LZ4 => decompress(
+            lz4_flex::frame::FrameDecoder::new(body.reader()),
+            len,
+            "lz4",
+        )?,
+        #[cfg(not(feature = "zstd"))]
+        ALGORITHM_ZSTD => return Err(unsupported("zstd")),
+        #[cfg(not(feature = "lz4"))]
+        ALGORITHM_LZ4 => return Err(unsupported("lz4")),
+        other => {
+            return Err(EncoderError::Decode(format!(
+                "Unknown compression algorithm {other}"
+            )))
+        }
+    };
+    if payload.len() != len {
+        return Err(EncoderError::Decode(format!(
+            "Decompressed payload is {} bytes, header announced {}",
+            payload.len(),
+            len
+        )));
+    }
+    decode_exact(&mut payload)
+}
+
+/// Reads at most one byte more than `len` from `decoder`, so a lying header is noticed
+/// without decompressing the rest.
+fn decompress(decoder: impl Read, len: usize, name: &str) -> Result<Bytes> {
+    let mut out = Vec::new();
+    decoder
+        .take(len as u64 + 1)
+        .read_to_end(&mut out)
+        .map_err(|e| EncoderError::Decode(format!("{name} decompression failed: {e}")))?;
+    Ok(Bytes::from(out))
+}
+
+#[cfg(not(all(feature = "zstd", feature = "lz4")))]
+fn unsupported(name: &str) -> EncoderError {
+    EncoderError::Decode(format!(
+        "Payload is compressed with {name}, which needs the `{name}` feature"
+    ))
+}
@@ -0,0 +1,444 @@
+//! Per-call encode and decode configuration.
+//!
+//! The `Encoder` and `Decoder` traits have no room for extra parameters, so options are
+//! installed in a thread-local for the duration of a call and read by the implementations that
+//! care. Use [`crate::encode_with_config`] / [`crate::decode_with_config`] for the common case,
+//! or [`with_encode_config`] / [`with_decode_config`] to wrap direct trait calls.
+//!
+//! Without the `std` feature there is no thread-local storage: the configuration cannot be
+//! changed, every call uses the defaults, and the nesting depth checked by [`decode_nested`] is
+//! counted for the whole program, so recursive types should be decoded from one context (task
+//! or interrupt priority) at a time.
+
+use crate::{EncoderError, Result};
+#[cfg(not(feature = "std"))]
+use core::sync::atomic::{AtomicUsize, Ordering};
+#[cfg(feature = "std")]
+use std::cell::Cell;
+
+/// Options that change how values are decoded.
+///
+/// The default configuration matches the behavior of [`crate::decode`].
+///
+/// # Example
+/// ```rust
+/// use senax_encoder::DecodeConfig;
+///
+/// let config = DecodeConfig {
+///     strict_sets: true,
+///     ..Default::default()
+/// };
+/// assert!(config.strict_sets);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DecodeConfig {
+    /// Reject duplicate elements when decoding set types.
+    ///
+    /// Sets share their wire tags with `Vec<T>`, so a payload produced from a `Vec` may contain
+    /// the same element more than once. By default (lenient mode) duplicates are merged and the
+    /// first occurrence is kept. In strict mode decoding fails with
+    /// [`EncoderError::DuplicateSetElement`](crate::EncoderError::DuplicateSetElement).
+    pub strict_sets: bool,
+    /// Reject fields whose ID the derived struct (or named enum variant) does not know.
+    ///
+    /// Unknown fields are normally skipped so that older readers accept payloads from newer
+    /// writers. Closed-world consumers, where an extra field means a misrouted or tampered
+    /// message, can fail with [`StructDecodeError::UnknownField`](crate::StructDecodeError::UnknownField)
+    /// or [`EnumDecodeError::UnknownField`](crate::EnumDecodeError::UnknownField) instead.
+    /// Fields marked `#[senax(skip_decode)]` are known and still skipped.
+    pub deny_unknown_fields: bool,
+    /// Copy `Bytes` values out of the input instead of slicing it.
+    ///
+    /// Decoded `Bytes` (and `Value::Binary`) normally share the input's allocation, which stays
+    /// alive as long as any of them does: a 16-byte token decoded from a 10 MiB message keeps
+    /// all 10 MiB in memory. With this option each of them gets its own allocation, so the input
+    /// is freed once it is dropped. Other types always copy.
+    pub copy_out: bool,
+    /// Accept a tuple where a fixed-size array is expected, and the other way round.
+    ///
+    /// Tuples and arrays write their elements the same way but under different tags, so
+    /// changing a field from `(f64, f64)` to `[f64; 2]` normally makes stored payloads
+    /// unreadable. With this option `[T; N]` also decodes from an `N`-tuple, and tuples (and
+    /// [`impl_senax_tuple!`](crate::impl_senax_tuple) types) also decode from an array or `Vec`
+    /// with as many elements. The element types still have to match, and `Vec<T>` is unaffected.
+    pub lenient_tuples: bool,
+    /// Maximum nesting depth of recursive types, [`DEFAULT_MAX_DEPTH`] by default.
+    ///
+    /// Decoders of recursive types (derived ones, or hand-written ones that call
+    /// [`decode_nested`]) count one level per nested value and fail with
+    /// [`EncoderError::DepthLimitExceeded`] beyond this depth, so a hostile payload nesting
+    /// `Box<Expr>` a million times deep returns an error instead of overflowing the stack.
+    /// `serde_json::Value` is decoded without recursion but counts each open array or object
+    /// against this limit as well.
+    /// Raise it for legitimately deep data decoded on threads with large stacks.
+    pub max_depth: usize,
+    /// Maximum length in bytes of a decoded owned string, byte string or string map key.
+    ///
+    /// `None` (the default) accepts any length the input actually contains. Borrowed `&str`
+    /// and `&[u8]` values are slices of the input and are not checked.
+    pub max_string_len: Option<usize>,
+    /// Maximum number of elements of a decoded list, set, array, tuple or map.
+    ///
+    /// The limit is checked when the length prefix is read, before any element is decoded.
+    pub max_collection_len: Option<usize>,
+    /// Maximum number of bytes the decoder may allocate for strings and collections during one
+    /// [`with_decode_config`] call.
+    ///
+    /// Each collection is charged its length times the size of its element type when its length
+    /// prefix is read, and each string or byte string its length. Since elements
+    /// can take far more memory than their encoding (an empty `Vec<u8>` is one byte on the wire
+    /// and 24 in memory), this bounds what a payload can make the decoder allocate in total.
+    /// Memory held by other types (`Box`, hash table overhead) is not counted.
+    pub max_alloc_bytes: Option<usize>,
+    /// Convert decoded strings to Unicode Normalization Form C (NFC).
+    ///
+    /// The same identifier can arrive as different byte sequences (`"é"` precomposed or as `e`
+    /// plus a combining accent) depending on the client that produced it. With this option,
+    /// `String`, `SmolStr`, `heapless::String`, string map keys and `Value::String` come out of
+    /// the decoder in NFC, so they compare equal. Strings already in NFC are not copied again.
+    #[cfg(feature = "unicode-normalization")]
+    pub normalize_nfc: bool,
+}
+
+/// Default for [`DecodeConfig::max_depth`].
+///
+/// Leaves ample headroom on the 2 MiB stacks of spawned threads, even in debug builds.
+pub const DEFAULT_MAX_DEPTH: usize = 128;
+
+impl Default for DecodeConfig {
+    fn default() -> Self {
+        Self {
+            strict_sets: false,
+            deny_unknown_fields: false,
+            copy_out: false,
+            lenient_tuples: false,
+            max_depth: DEFAULT_MAX_DEPTH,
+            max_string_len: None,
+            max_collection_len: None,
+            max_alloc_bytes: None,
+            #[cfg(feature = "unicode-normalization")]
+            normalize_nfc: false,
+        }
+    }
+}
+
+/// Options for decoding untrusted input, under the name used by [`crate::decode_with_options`].
+pub type DecodeOptions = DecodeConfig;
+
+/// The limit reported by [`EncoderError::LimitExceeded`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DecodeLimit {
+    /// [`DecodeConfig::max_string_len`]
+    StringLength,
+    /// [`DecodeConfig::max_collection_len`]
+    CollectionLength,
+    /// [`DecodeConfig::max_alloc_bytes`]
+    AllocatedBytes,
+}
+
+impl core::fmt::Display for DecodeLimit {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(match self {
+            DecodeLimit::StringLength => "string length",
+            DecodeLimit::CollectionLength => "collection length",
+            DecodeLimit::AllocatedBytes => "allocated bytes",
+        })
+    }
+}
+
+/// Options that change how values are encoded.
+///
+/// The default configuration matches the behavior of [`crate::encode`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EncodeConfig {
+    /// Write the entries of hash-based maps (`HashMap`, `FxHashMap`, `AHashMap`) ordered by
+    /// their encoded key bytes.
+    ///
+    /// Hash map iteration order varies between runs, which makes snapshot tests of encoded
+    /// payloads flaky. This option only reorders map entries; it does not affect sets or any
+    /// other part of the output. Ordered maps (`BTreeMap`, `IndexMap`) are unaffected.
+    pub sort_maps: bool,
+    /// Replace fields marked `#[senax(redact)]` with their default value.
+    ///
+    /// Redacted `Option` and `skip_default` fields are omitted entirely. Use this to produce
+    /// copies of records with PII removed, e.g. for logs or analytics exports.
+    pub redact: bool,
+    /// Write `f64` values that `f32` represents exactly as `TAG_F32` and 4 bytes, both when
+    /// encoding and packing.
+    ///
+    /// Packed floats otherwise take 8 bytes and encoded ones a decimal string, so this halves
+    /// the size of metrics that were measured as `f32` or hold small integers and binary
+    /// fractions. Other values are written as usual. Readers built before this option existed
+    /// cannot decode the shrunk values.
+    pub shrink_floats: bool,
+}
+
+#[cfg(feature = "std")]
+thread_local! {
+    static DECODE_CONFIG: Cell<DecodeConfig> = Cell::new(DecodeConfig::default());
+    static ENCODE_CONFIG: Cell<EncodeConfig> = Cell::new(EncodeConfig::default());
+    static ENCODE_LIMIT: Cell<Option<usize>> = const { Cell::new(None) };
+    static DECODE_DEPTH: Cell<usize> = const { Cell::new(0) };
+    static DECODE_ALLOCATED: Cell<usize> = const { Cell::new(0) };
+}
+
+#[cfg(not(feature = "std"))]
+static DECODE_DEPTH: AtomicUsize = AtomicUsize::new(0);
+
+#[cfg(feature = "std")]
+fn decode_depth() -> usize {
+    DECODE_DEPTH.with(|c| c.get())
+}
+
+#[cfg(feature = "std")]
+fn set_decode_depth(depth: usize) {
+    DECODE_DEPTH.with(|c| c.set(depth));
+}
+
+// Plain loads and stores, which every target with atomics supports
+#[cfg(not(feature = "std"))]
+fn decode_depth() -> usize {
+    DECODE_DEPTH.load(Ordering::Relaxed)
+}
+
+#[cfg(not(feature = "std"))]
+fn set_decode_depth(depth: usize) {
+    DECODE_DEPTH.store(depth, Ordering::Relaxed);
+}
+
+/// Restores the previous decode configuration and allocation count when dropped, even if
+/// decoding panics.
+#[cfg(feature = "std")]
+struct DecodeConfigGuard(DecodeConfig, usize);
+
+#[cfg(feature = "std")]
+impl Drop for DecodeConfigGuard {
+    fn drop(&mut self) {
+        DECODE_CONFIG.with(|c| c.set(self.0));
+        DECODE_ALLOCATED.with(|c| c.set(self.1));
+    }
+}
+
+/// Runs `f` with `config` installed as the current decode configuration.
+///
+/// Calls may be nested; the previous configuration is restored when `f` returns. Each call
+/// starts a new [`DecodeConfig::max_alloc_bytes`] budget.
+///
+/// # Example
+/// ```rust
+/// use senax_encoder::{with_decode_config, DecodeConfig, Decoder, Encoder};
+/// use bytes::BytesMut;
+/// use std::collections::HashSet;
+///
+/// let mut buf = BytesMut::new();
+/// vec![1u32, 2, 2].encode(&mut buf).unwrap();
+/// let strict = DecodeConfig { strict_sets: true, ..Default::default() };
+/// let result = with_decode_config(&strict, || HashSet::<u32>::decode(&mut buf.freeze()));
+/// assert!(result.is_err());
+/// ```
+#[cfg(feature = "std")]
+pub fn with_decode_config<R>(config: &DecodeConfig, f: impl FnOnce() -> R) -> R {
+    let previous = DECODE_CONFIG.with(|c| c.replace(*config));
+    let allocated = DECODE_ALLOCATED.with(|c| c.replace(0));
+    let _guard = DecodeConfigGuard(previous, allocated);
+    f()
+}
+
+/// Returns the decode configuration currently in effect on this thread.
+#[cfg(feature = "std")]
+#[inline]
+pub fn current_decode_config() -> DecodeConfig {
+    DECODE_CONFIG.with(|c| c.get())
+}
+
+/// Returns the default decode configuration, the only one available without `std`.
+#[cfg(not(feature = "std"))]
+#[inline]
+pub fn current_decode_config() -> DecodeConfig {
+    DecodeConfig::default()
+}
+
+/// Leaves the nesting level entered by [`decode_nested`] when dropped.
+struct DecodeDepthGuard;
+
+impl Drop for DecodeDepthGuard {
+    fn drop(&mut self) {
+        set_decode_depth(decode_depth().saturating_sub(1));
+    }
+}
+
+/// Runs `f` one nesting level deeper, failing with [`EncoderError::DepthLimitExceeded`] once
+/// the depth exceeds [`DecodeConfig::max_depth`].
+///
+/// Derived decoders of recursive types wrap themselves in this call. Hand-written `Decoder` or
+/// `Unpacker` impls of recursive types should do the same.
+///
+/// # Example
+/// ```rust
+/// use senax_encoder::{decode_nested, with_decode_config, DecodeConfig, EncoderError};
+///
+/// fn descend(levels: usize) -> senax_encoder::Result<()> {
+///     if levels == 0 {
+///         return Ok(());
+///     }
+///     decode_nested(|| descend(levels - 1))
+/// }
+///
+/// let config = DecodeConfig { max_depth: 8, ..Default::default() };
+/// assert!(with_decode_config(&config, || descend(8)).is_ok());
+/// let err = with_decode_config(&config, || descend(9)).unwrap_err();
+/// assert!(matches!(err, EncoderError::DepthLimitExceeded { limit: 8 }));
+/// ```
+pub fn decode_nested<R>(f: impl FnOnce() -> Result<R>) -> Result<R> {
+    let limit = current_decode_config().max_depth;
+    let depth = decode_depth() + 1;
+    if depth > limit {
+        return Err(EncoderError::DepthLimitExceeded { limit });
+    }
+    set_decode_depth(depth);
+    let _guard = DecodeDepthGuard;
+    f()
+}
+
+/// Fails if a string of `len` bytes exceeds [`DecodeConfig::max_string_len`] or the
+/// allocation budget, and charges it to the budget otherwise.
+#[inline]
+pub(crate) fn check_string_len(len: usize) -> Result<()> {
+    let config = current_decode_config();
+    if let Some(limit) = config.max_string_len.filter(|&limit| len > limit) {
+        return Err(EncoderError::LimitExceeded {
+            kind: DecodeLimit::StringLength,
+            len,
+            limit,
+        });
+    }
+    charge(config, len)
+}
+
+/// Fails if a collection of `len` elements exceeds [`DecodeConfig::max_collection_len`].
+#[inline]
+pub(crate) fn check_collection_len(len: usize) -> Result<()> {
+    match current_decode_config().max_collection_len {
+        Some(limit) if len > limit => Err(EncoderError::LimitExceeded {
+            kind: DecodeLimit::CollectionLength,
+            len,
+            limit,
+        }),
+        _ => Ok(()),
+    }
+}
+
+/// Charges `bytes` to the [`DecodeConfig::max_alloc_bytes`] budget of the current call.
+#[inline]
+pub(crate) fn charge_alloc(bytes: usize) -> Result<()> {
+    charge(current_decode_config(), bytes)
+}
+
+/// The default configuration sets no allocation budget, so there is nothing to charge.
+#[cfg(not(feature = "std"))]
+fn charge(_config: DecodeConfig, _bytes: usize) -> Result<()> {
+    Ok(())
+}
+
+#[cfg(feature = "std")]
+fn charge(config: DecodeConfig, bytes: usize) -> Result<()> {
+    let Some(limit) = config.max_alloc_bytes else {
+        return Ok(());
+    };
+    let total = DECODE_ALLOCATED.with(|c| c.get()).saturating_add(bytes);
+    if total > limit {
+        return Err(EncoderError::LimitExceeded {
+            kind: DecodeLimit::AllocatedBytes,
+            len: total,
+            limit,
+        });
+    }
+    DECODE_ALLOCATED.with(|c| c.set(total));
+    Ok(())
+}
+
+/// Restores the previous encode configuration when dropped.
+#[cfg(feature = "std")]
+struct EncodeConfigGuard(EncodeConfig);
+
+#[cfg(feature = "std")]
+impl Drop for EncodeConfigGuard {
+    fn drop(&mut self) {
+        ENCODE_CONFIG.with(|c| c.set(self.0));
+    }
+}
+
+/// Runs `f` with `config` installed as the current encode configuration.
+///
+/// Calls may be nested; the previous configuration is restored when `f` returns.
+///
+/// # Example
+/// ```rust
+/// use senax_encoder::{with_encode_config, EncodeConfig, Packer};
+/// use bytes::BytesMut;
+/// use std::collections::HashMap;
+///
+/// let map: HashMap<u32, u32> = (0..16).map(|i| (i, i)).collect();
+/// let sorted = EncodeConfig { sort_maps: true, ..Default::default() };
+/// let mut buf = BytesMut::new();
+/// with_encode_config(&sorted, || map.pack(&mut buf)).unwrap();
+/// ```
+#[cfg(feature = "std")]
+pub fn with_encode_config<R>(config: &EncodeConfig, f: impl FnOnce() -> R) -> R {
+    let previous = ENCODE_CONFIG.with(|c| c.replace(*config));
+    let _guard = EncodeConfigGuard(previous);
+    f()
+}
+
+/// Returns the encode configuration currently in effect on this thread.
+#[cfg(feature = "std")]
+#[inline]
+pub fn current_encode_config() -> EncodeConfig {
+    ENCODE_CONFIG.with(|c| c.get())
+}
+
+/// Returns the default encode configuration, the only one available without `std`.
+#[cfg(not(feature = "std"))]
+#[inline]
+pub fn current_encode_config() -> EncodeConfig {
+    EncodeConfig::default()
+}
+
+/// Restores the previous encode size limit when dropped.
+#[cfg(feature = "std")]
+struct EncodeLimitGuard(Option<usize>);
+
+#[cfg(feature = "std")]
+impl Drop for EncodeLimitGuard {
+    fn drop(&mut self) {
+        ENCODE_LIMIT.with(|c| c.set(self.0));
+    }
+}
+
+/// Runs `f` with `limit` installed as the maximum encoded length, used by [`crate::encode_bounded`].
+#[cfg(feature = "std")]
+pub(crate) fn with_encode_limit<R>(limit: usize, f: impl FnOnce() -> R) -> R {
+    let previous = ENCODE_LIMIT.with(|c| c.replace(Some(limit)));
+    let _guard = EncodeLimitGuard(previous);
+    f()
+}
+
+/// Fails with [`EncoderError::TooLarge`] if `len` exceeds the encode size limit in effect.
+///
+/// Encoders call this at string, binary and collection element boundaries so that an oversized
+/// value is rejected before the rest of it is written.
+#[cfg(feature = "std")]
+#[inline]
+pub(crate) fn check_encode_limit(len: usize) -> Result<()> {
+    match ENCODE_LIMIT.with(|c| c.get()) {
+        Some(limit) if len > limit => Err(EncoderError::TooLarge { actual: len, limit }),
+        _ => Ok(()),
+    }
+}
+
+/// No size limit can be installed without `std`.
+#[cfg(not(feature = "std"))]
+#[inline]
+pub(crate) fn check_encode_limit(_len: usize) -> Result<()> {
+    Ok(())
+}
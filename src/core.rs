@@ -7,6 +7,9 @@
 //! - Tags are stable and part of the wire format.
 
 use crate::*;
+use alloc::borrow::Cow;
+use alloc::rc::Rc;
+use alloc::vec;
 
 ///< 0 for numbers, false for bool
 pub const TAG_ZERO: u8 = 0;
@@ -50,13 +53,13 @@ pub const TAG_ARRAY_VEC_SET_LONG: u8 = 194;
 pub const TAG_TUPLE: u8 = 195;
 ///< Map (HashMap, BTreeMap, IndexMap, FxHashMap, AHashMap)
 pub const TAG_MAP: u8 = 196;
-///< chrono::DateTime
+///< chrono::DateTime, time::OffsetDateTime
 pub const TAG_CHRONO_DATETIME: u8 = 197;
-///< chrono::NaiveDate
+///< chrono::NaiveDate, time::Date
 pub const TAG_CHRONO_NAIVE_DATE: u8 = 198;
-///< chrono::NaiveTime
+///< chrono::NaiveTime, time::Time
 pub const TAG_CHRONO_NAIVE_TIME: u8 = 199;
-///< chrono::NaiveDateTime
+///< chrono::NaiveDateTime, time::PrimitiveDateTime
 pub const TAG_CHRONO_NAIVE_DATETIME: u8 = 208;
 ///< rust_decimal::Decimal
 pub const TAG_DECIMAL: u8 = 200;
@@ -68,6 +71,30 @@ pub const TAG_JSON_NUMBER: u8 = 204;
 pub const TAG_JSON_STRING: u8 = 205; // Uses existing string encoding
 pub const TAG_JSON_ARRAY: u8 = 206;
 pub const TAG_JSON_OBJECT: u8 = 207;
+///< Map with `String` keys (`StringKeyMap`); keys are written as length + UTF-8 bytes without a tag
+pub const TAG_MAP_STRING_KEYS: u8 = 209;
+///< Map with tuple keys written without per-key tuple headers (`#[senax(flat_keys)]`)
+pub const TAG_MAP_FLAT_KEYS: u8 = 210;
+///< Map with `u64` keys (`IdMap`); keys are written like field IDs instead of tagged integers
+pub const TAG_MAP_ID_KEYS: u8 = 211;
+///< core::time::Duration
+pub const TAG_DURATION: u8 = 212;
+///< std::time::SystemTime
+pub const TAG_SYSTEM_TIME: u8 = 213;
+
+// --- Format limits ---
+/// Longest string written inline in its tag byte (`TAG_STRING_BASE + len`).
+pub const MAX_SHORT_STRING_LEN: usize = (TAG_STRING_LONG - TAG_STRING_BASE - 1) as usize;
+/// Longest array, `Vec` or set whose length is written inline in its tag byte.
+pub const MAX_SHORT_ARRAY_LEN: usize =
+    (TAG_ARRAY_VEC_SET_LONG - TAG_ARRAY_VEC_SET_BASE - 1) as usize;
+/// Largest field or variant ID written as one byte; larger IDs take a marker byte plus 8 bytes.
+pub const MAX_SHORT_FIELD_ID: u64 = 250;
+/// Field header byte introducing a field or variant written by name instead of by ID, as
+/// [`encode_named`](crate::self_describing::encode_named) does; the name follows as a string.
+pub const FIELD_NAME_MARKER: u8 = 251;
+/// Largest tuple arity that implements the encoding traits.
+pub const MAX_TUPLE_ARITY: usize = 16;
 
 // --- bool ---
 /// Encodes a `bool` as a single tag byte: `TAG_ZERO` for `false`, `TAG_ONE` for `true`.
@@ -99,7 +126,7 @@ impl Packer for bool {
 impl Decoder for bool {
     fn decode(reader: &mut Bytes) -> Result<Self> {
         if reader.remaining() == 0 {
-            return Err(EncoderError::InsufficientData);
+            return Err(EncoderError::eof(1, 0));
         }
         let tag = reader.get_u8();
         match tag {
@@ -120,7 +147,7 @@ impl Decoder for bool {
 impl Unpacker for bool {
     fn unpack(reader: &mut Bytes) -> Result<Self> {
         if reader.remaining() == 0 {
-            return Err(EncoderError::InsufficientData);
+            return Err(EncoderError::eof(1, 0));
         }
         let value = reader.get_u8();
         Ok(value != TAG_ZERO)
@@ -139,7 +166,7 @@ fn decode_u8_from_tag(tag: u8, reader: &mut Bytes) -> Result<u8> {
         Ok(tag - TAG_ZERO)
     } else if tag == TAG_U8 {
         if reader.remaining() == 0 {
-            return Err(EncoderError::InsufficientData);
+            return Err(EncoderError::eof(1, 0));
         }
         let stored_val = reader.get_u8();
         stored_val.checked_add(128).ok_or_else(|| {
@@ -159,12 +186,12 @@ fn decode_u16_from_tag(tag: u8, reader: &mut Bytes) -> Result<u16> {
         Ok((tag - TAG_ZERO) as u16)
     } else if tag == TAG_U8 {
         if reader.remaining() == 0 {
-            return Err(EncoderError::InsufficientData);
+            return Err(EncoderError::eof(1, 0));
         }
         Ok(reader.get_u8() as u16 + 128)
     } else if tag == TAG_U16 {
         if reader.remaining() < 2 {
-            return Err(EncoderError::InsufficientData);
+            return Err(EncoderError::eof(2, reader.remaining()));
         }
         Ok(reader.get_u16_le())
     } else {
@@ -182,17 +209,17 @@ fn decode_u32_from_tag(tag: u8, reader: &mut Bytes) -> Result<u32> {
         Ok((tag - TAG_ZERO) as u32)
     } else if tag == TAG_U8 {
         if reader.remaining() == 0 {
-            return Err(EncoderError::InsufficientData);
+            return Err(EncoderError::eof(1, 0));
         }
         Ok(reader.get_u8() as u32 + 128)
     } else if tag == TAG_U16 {
         if reader.remaining() < 2 {
-            return Err(EncoderError::InsufficientData);
+            return Err(EncoderError::eof(2, reader.remaining()));
         }
         Ok(reader.get_u16_le() as u32)
     } else if tag == TAG_U32 {
         if reader.remaining() < 4 {
-            return Err(EncoderError::InsufficientData);
+            return Err(EncoderError::eof(4, reader.remaining()));
         }
         Ok(reader.get_u32_le())
     } else {
@@ -210,22 +237,22 @@ fn decode_u64_from_tag(tag: u8, reader: &mut Bytes) -> Result<u64> {
         Ok((tag - TAG_ZERO) as u64)
     } else if tag == TAG_U8 {
         if reader.remaining() == 0 {
-            return Err(EncoderError::InsufficientData);
+            return Err(EncoderError::eof(1, 0));
         }
         Ok(reader.get_u8() as u64 + 128)
     } else if tag == TAG_U16 {
         if reader.remaining() < 2 {
-            return Err(EncoderError::InsufficientData);
+            return Err(EncoderError::eof(2, reader.remaining()));
         }
         Ok(reader.get_u16_le() as u64)
     } else if tag == TAG_U32 {
         if reader.remaining() < 4 {
-            return Err(EncoderError::InsufficientData);
+            return Err(EncoderError::eof(4, reader.remaining()));
         }
         Ok(reader.get_u32_le() as u64)
     } else if tag == TAG_U64 {
         if reader.remaining() < 8 {
-            return Err(EncoderError::InsufficientData);
+            return Err(EncoderError::eof(8, reader.remaining()));
         }
         Ok(reader.get_u64_le())
     } else {
@@ -237,32 +264,32 @@ fn decode_u64_from_tag(tag: u8, reader: &mut Bytes) -> Result<u64> {
 }
 /// Decodes a `u128` value from a tag and buffer.
 /// Used internally for compact integer decoding.
-fn decode_u128_from_tag(tag: u8, reader: &mut Bytes) -> Result<u128> {
+pub(crate) fn decode_u128_from_tag(tag: u8, reader: &mut Bytes) -> Result<u128> {
     if (TAG_ZERO..=TAG_U8_127).contains(&tag) {
         Ok((tag - TAG_ZERO) as u128)
     } else if tag == TAG_U8 {
         if reader.remaining() == 0 {
-            return Err(EncoderError::InsufficientData);
+            return Err(EncoderError::eof(1, 0));
         }
         Ok(reader.get_u8() as u128 + 128)
     } else if tag == TAG_U16 {
         if reader.remaining() < 2 {
-            return Err(EncoderError::InsufficientData);
+            return Err(EncoderError::eof(2, reader.remaining()));
         }
         Ok(reader.get_u16_le() as u128)
     } else if tag == TAG_U32 {
         if reader.remaining() < 4 {
-            return Err(EncoderError::InsufficientData);
+            return Err(EncoderError::eof(4, reader.remaining()));
         }
         Ok(reader.get_u32_le() as u128)
     } else if tag == TAG_U64 {
         if reader.remaining() < 8 {
-            return Err(EncoderError::InsufficientData);
+            return Err(EncoderError::eof(8, reader.remaining()));
         }
         Ok(reader.get_u64_le() as u128)
     } else if tag == TAG_U128 {
         if reader.remaining() < 16 {
-            return Err(EncoderError::InsufficientData);
+            return Err(EncoderError::eof(16, reader.remaining()));
         }
         Ok(reader.get_u128_le())
     } else {
@@ -273,6 +300,98 @@ fn decode_u128_from_tag(tag: u8, reader: &mut Bytes) -> Result<u128> {
     }
 }
 
+/// Longest decimal integer string that can fit an `i128`: a sign and 39 digits.
+const MAX_DECIMAL_INTEGER_LEN: usize = 40;
+
+/// Decodes a scale-0 decimal as an integer, so a field changed from an integer type to
+/// `rust_decimal::Decimal` (e.g. `u64` cents to a `Decimal` amount) stays readable as the
+/// integer type.
+///
+/// Accepts the string form written by decimal encoders (`"-1234"`) and the legacy
+/// `TAG_DECIMAL` form with scale 0. `tag` has already been read. Decimals with a fractional
+/// part, including integral ones such as `"5.00"`, and values outside `T`'s range fail.
+#[cold]
+fn decode_decimal_integer<T: TryFrom<i128>>(
+    tag: u8,
+    reader: &mut Bytes,
+    type_name: &str,
+) -> Result<T> {
+    let value = if (TAG_STRING_BASE..=TAG_STRING_LONG).contains(&tag) {
+        let len = if tag == TAG_STRING_LONG {
+            if reader.remaining() == 0 {
+                return Err(EncoderError::eof(1, 0));
+            }
+            let len_tag = reader.get_u8();
+            decode_u64_from_tag(len_tag, reader)?
+        } else {
+            (tag - TAG_STRING_BASE) as u64
+        };
+        if len > MAX_DECIMAL_INTEGER_LEN as u64 {
+            return Err(EncoderError::Decode(format!(
+                "String of {} bytes is not a {} decimal",
+                len, type_name
+            )));
+        }
+        let len = len as usize;
+        if reader.remaining() < len {
+            return Err(EncoderError::eof(len, reader.remaining()));
+        }
+        let text = reader.split_to(len);
+        let text =
+            ::core::str::from_utf8(&text).map_err(|e| EncoderError::Decode(e.to_string()))?;
+        text.parse::<i128>().map_err(|_| {
+            EncoderError::Decode(format!(
+                "Expected a scale-0 decimal for {}, got '{}'",
+                type_name, text
+            ))
+        })?
+    } else if tag == TAG_DECIMAL {
+        // Read without decimal fallbacks, so nested decimals cannot recurse
+        if reader.remaining() == 0 {
+            return Err(EncoderError::eof(1, 0));
+        }
+        let mantissa_tag = reader.get_u8();
+        let mantissa = if mantissa_tag == TAG_NEGATIVE {
+            if reader.remaining() == 0 {
+                return Err(EncoderError::eof(1, 0));
+            }
+            let inv_tag = reader.get_u8();
+            !decode_u128_from_tag(inv_tag, reader)? as i128
+        } else {
+            i128::try_from(decode_u128_from_tag(mantissa_tag, reader)?).map_err(|_| {
+                EncoderError::Decode(format!("Decimal mantissa too large for {}", type_name))
+            })?
+        };
+        if reader.remaining() == 0 {
+            return Err(EncoderError::eof(1, 0));
+        }
+        let scale_tag = reader.get_u8();
+        let scale = decode_u32_from_tag(scale_tag, reader)?;
+        if scale != 0 {
+            return Err(EncoderError::Decode(format!(
+                "Expected a scale-0 decimal for {}, got scale {}",
+                type_name, scale
+            )));
+        }
+        mantissa
+    } else {
+        return Err(EncoderError::Decode(format!(
+            "Unexpected tag for {}: {}",
+            type_name, tag
+        )));
+    };
+    T::try_from(value).map_err(|_| {
+        EncoderError::Decode(format!("Decimal {} out of range for {}", value, type_name))
+    })
+}
+
+/// Returns `true` for the tags [`decode_decimal_integer`] reads. Integer decoders only check
+/// it after the integer tags failed to match.
+#[inline]
+fn is_decimal_tag(tag: u8) -> bool {
+    (TAG_STRING_BASE..=TAG_STRING_LONG).contains(&tag) || tag == TAG_DECIMAL
+}
+
 // --- Unsigned integer types ---
 /// Encodes unsigned integers using a compact variable-length format.
 ///
@@ -307,17 +426,20 @@ impl Packer for u8 {
 impl Decoder for u8 {
     fn decode(reader: &mut Bytes) -> Result<Self> {
         if reader.remaining() == 0 {
-            return Err(EncoderError::InsufficientData);
+            return Err(EncoderError::eof(1, 0));
         }
         let tag = reader.get_u8();
-        decode_u8_from_tag(tag, reader)
+        match decode_u8_from_tag(tag, reader) {
+            Err(_) if is_decimal_tag(tag) => decode_decimal_integer(tag, reader, "u8"),
+            result => result,
+        }
     }
 }
 
 impl Unpacker for u8 {
     fn unpack(reader: &mut Bytes) -> Result<Self> {
         if reader.remaining() == 0 {
-            return Err(EncoderError::InsufficientData);
+            return Err(EncoderError::eof(1, 0));
         }
         Ok(reader.get_u8())
     }
@@ -352,10 +474,13 @@ impl Packer for u16 {
 impl Decoder for u16 {
     fn decode(reader: &mut Bytes) -> Result<Self> {
         if reader.remaining() == 0 {
-            return Err(EncoderError::InsufficientData);
+            return Err(EncoderError::eof(1, 0));
         }
         let tag = reader.get_u8();
-        decode_u16_from_tag(tag, reader)
+        match decode_u16_from_tag(tag, reader) {
+            Err(_) if is_decimal_tag(tag) => decode_decimal_integer(tag, reader, "u16"),
+            result => result,
+        }
     }
 }
 
@@ -397,10 +522,13 @@ impl Packer for u32 {
 impl Decoder for u32 {
     fn decode(reader: &mut Bytes) -> Result<Self> {
         if reader.remaining() == 0 {
-            return Err(EncoderError::InsufficientData);
+            return Err(EncoderError::eof(1, 0));
         }
         let tag = reader.get_u8();
-        decode_u32_from_tag(tag, reader)
+        match decode_u32_from_tag(tag, reader) {
+            Err(_) if is_decimal_tag(tag) => decode_decimal_integer(tag, reader, "u32"),
+            result => result,
+        }
     }
 }
 
@@ -446,10 +574,13 @@ impl Packer for u64 {
 impl Decoder for u64 {
     fn decode(reader: &mut Bytes) -> Result<Self> {
         if reader.remaining() == 0 {
-            return Err(EncoderError::InsufficientData);
+            return Err(EncoderError::eof(1, 0));
         }
         let tag = reader.get_u8();
-        decode_u64_from_tag(tag, reader)
+        match decode_u64_from_tag(tag, reader) {
+            Err(_) if is_decimal_tag(tag) => decode_decimal_integer(tag, reader, "u64"),
+            result => result,
+        }
     }
 }
 
@@ -497,10 +628,13 @@ impl Packer for u128 {
 impl Decoder for u128 {
     fn decode(reader: &mut Bytes) -> Result<Self> {
         if reader.remaining() == 0 {
-            return Err(EncoderError::InsufficientData);
+            return Err(EncoderError::eof(1, 0));
         }
         let tag = reader.get_u8();
-        decode_u128_from_tag(tag, reader)
+        match decode_u128_from_tag(tag, reader) {
+            Err(_) if is_decimal_tag(tag) => decode_decimal_integer(tag, reader, "u128"),
+            result => result,
+        }
     }
 }
 
@@ -544,7 +678,7 @@ impl Packer for usize {
 impl Decoder for usize {
     fn decode(reader: &mut Bytes) -> Result<Self> {
         if reader.remaining() == 0 {
-            return Err(EncoderError::InsufficientData);
+            return Err(EncoderError::eof(1, 0));
         }
         let tag = reader.get_u8();
         if usize::BITS == u64::BITS {
@@ -600,7 +734,7 @@ impl Packer for i8 {
 impl Decoder for i8 {
     fn decode(reader: &mut Bytes) -> Result<Self> {
         if reader.remaining() == 0 {
-            return Err(EncoderError::InsufficientData);
+            return Err(EncoderError::eof(1, 0));
         }
         let tag = reader.get_u8();
         match tag {
@@ -609,7 +743,12 @@ impl Decoder for i8 {
                 Ok(!inv as i8)
             }
             t => {
-                let v = decode_u8_from_tag(t, reader)?;
+                let v = match decode_u8_from_tag(t, reader) {
+                    Err(_) if is_decimal_tag(t) => {
+                        return decode_decimal_integer(t, reader, "i8");
+                    }
+                    result => result?,
+                };
                 if v > i8::MAX as u8 {
                     return Err(EncoderError::Decode(format!(
                         "Value {} too large for i8",
@@ -625,7 +764,7 @@ impl Decoder for i8 {
 impl Unpacker for i8 {
     fn unpack(reader: &mut Bytes) -> Result<Self> {
         if reader.remaining() == 0 {
-            return Err(EncoderError::InsufficientData);
+            return Err(EncoderError::eof(1, 0));
         }
         Ok(reader.get_i8())
     }
@@ -656,7 +795,7 @@ impl Packer for i16 {
 impl Decoder for i16 {
     fn decode(reader: &mut Bytes) -> Result<Self> {
         if reader.remaining() == 0 {
-            return Err(EncoderError::InsufficientData);
+            return Err(EncoderError::eof(1, 0));
         }
         let tag = reader.get_u8();
         match tag {
@@ -665,7 +804,12 @@ impl Decoder for i16 {
                 Ok(!inv as i16)
             }
             t => {
-                let v = decode_u16_from_tag(t, reader)?;
+                let v = match decode_u16_from_tag(t, reader) {
+                    Err(_) if is_decimal_tag(t) => {
+                        return decode_decimal_integer(t, reader, "i16");
+                    }
+                    result => result?,
+                };
                 if v > i16::MAX as u16 {
                     return Err(EncoderError::Decode(format!(
                         "Value {} too large for i16",
@@ -709,7 +853,7 @@ impl Packer for i32 {
 impl Decoder for i32 {
     fn decode(reader: &mut Bytes) -> Result<Self> {
         if reader.remaining() == 0 {
-            return Err(EncoderError::InsufficientData);
+            return Err(EncoderError::eof(1, 0));
         }
         let tag = reader.get_u8();
         match tag {
@@ -718,7 +862,12 @@ impl Decoder for i32 {
                 Ok(!inv as i32)
             }
             t => {
-                let v = decode_u32_from_tag(t, reader)?;
+                let v = match decode_u32_from_tag(t, reader) {
+                    Err(_) if is_decimal_tag(t) => {
+                        return decode_decimal_integer(t, reader, "i32");
+                    }
+                    result => result?,
+                };
                 if v > i32::MAX as u32 {
                     return Err(EncoderError::Decode(format!(
                         "Value {} too large for i32",
@@ -763,7 +912,7 @@ impl Packer for i64 {
 impl Decoder for i64 {
     fn decode(reader: &mut Bytes) -> Result<Self> {
         if reader.remaining() == 0 {
-            return Err(EncoderError::InsufficientData);
+            return Err(EncoderError::eof(1, 0));
         }
         let tag = reader.get_u8();
         match tag {
@@ -772,7 +921,12 @@ impl Decoder for i64 {
                 Ok(!inv as i64)
             }
             t => {
-                let v = decode_u64_from_tag(t, reader)?;
+                let v = match decode_u64_from_tag(t, reader) {
+                    Err(_) if is_decimal_tag(t) => {
+                        return decode_decimal_integer(t, reader, "i64");
+                    }
+                    result => result?,
+                };
                 if v > i64::MAX as u64 {
                     return Err(EncoderError::Decode(format!(
                         "Value {} too large for i64",
@@ -817,7 +971,7 @@ impl Packer for i128 {
 impl Decoder for i128 {
     fn decode(reader: &mut Bytes) -> Result<Self> {
         if reader.remaining() == 0 {
-            return Err(EncoderError::InsufficientData);
+            return Err(EncoderError::eof(1, 0));
         }
         let tag = reader.get_u8();
         match tag {
@@ -826,7 +980,12 @@ impl Decoder for i128 {
                 Ok(!inv as i128)
             }
             t => {
-                let v = decode_u128_from_tag(t, reader)?;
+                let v = match decode_u128_from_tag(t, reader) {
+                    Err(_) if is_decimal_tag(t) => {
+                        return decode_decimal_integer(t, reader, "i128");
+                    }
+                    result => result?,
+                };
                 if v > i128::MAX as u128 {
                     return Err(EncoderError::Decode(format!(
                         "Value {} too large for i128",
@@ -877,7 +1036,7 @@ impl Packer for isize {
 impl Decoder for isize {
     fn decode(reader: &mut Bytes) -> Result<Self> {
         if reader.remaining() == 0 {
-            return Err(EncoderError::InsufficientData);
+            return Err(EncoderError::eof(1, 0));
         }
         if usize::BITS == u64::BITS {
             Ok(i64::decode(reader)? as isize)
@@ -935,6 +1094,140 @@ impl Unpacker for char {
     }
 }
 
+// --- Duration / SystemTime ---
+/// Encodes a `Duration` as `TAG_DURATION`, whole seconds (`u64`) and nanoseconds (`u32`).
+impl Encoder for ::core::time::Duration {
+    fn encode(&self, writer: &mut BytesMut) -> Result<()> {
+        writer.put_u8(TAG_DURATION);
+        self.as_secs().encode(writer)?;
+        self.subsec_nanos().encode(writer)
+    }
+
+    fn is_default(&self) -> bool {
+        self.is_zero()
+    }
+}
+
+impl Packer for ::core::time::Duration {
+    fn pack(&self, writer: &mut BytesMut) -> Result<()> {
+        self.encode(writer)
+    }
+}
+
+/// Decodes a `Duration`, rejecting nanosecond parts of a second or more.
+impl Decoder for ::core::time::Duration {
+    fn decode(reader: &mut Bytes) -> Result<Self> {
+        if reader.remaining() == 0 {
+            return Err(EncoderError::eof(1, 0));
+        }
+        let tag = reader.get_u8();
+        if tag != TAG_DURATION {
+            return Err(EncoderError::Decode(format!(
+                "Expected Duration tag ({}), got {}",
+                TAG_DURATION, tag
+            )));
+        }
+        let seconds = u64::decode(reader)?;
+        let nanos = read_subsec_nanos(reader)?;
+        Ok(::core::time::Duration::new(seconds, nanos))
+    }
+}
+
+impl Unpacker for ::core::time::Duration {
+    fn unpack(reader: &mut Bytes) -> Result<Self> {
+        Self::decode(reader)
+    }
+}
+
+/// Reads the nanosecond part of a `Duration` or timestamp, which must be below one second.
+pub(crate) fn read_subsec_nanos(reader: &mut Bytes) -> Result<u32> {
+    let nanos = u32::decode(reader)?;
+    if nanos >= 1_000_000_000 {
+        return Err(EncoderError::Decode(format!(
+            "Invalid nanoseconds {}: must be less than one second",
+            nanos
+        )));
+    }
+    Ok(nanos)
+}
+
+/// Encodes a `SystemTime` as `TAG_SYSTEM_TIME`, seconds since the Unix epoch (`i64`, negative
+/// before 1970) and nanoseconds (`u32`) added to them, like `chrono::DateTime<Utc>`.
+#[cfg(feature = "std")]
+impl Encoder for std::time::SystemTime {
+    fn encode(&self, writer: &mut BytesMut) -> Result<()> {
+        let (seconds, nanos) = match self.duration_since(std::time::UNIX_EPOCH) {
+            Ok(after) => (i64::try_from(after.as_secs()).ok(), after.subsec_nanos()),
+            Err(e) => {
+                // Round down to whole seconds so the nanoseconds stay positive
+                let before = e.duration();
+                let seconds = i64::try_from(before.as_secs()).ok();
+                match before.subsec_nanos() {
+                    0 => (seconds.map(|s| -s), 0),
+                    n => (seconds.map(|s| -s - 1), 1_000_000_000 - n),
+                }
+            }
+        };
+        let seconds = seconds.ok_or_else(|| {
+            EncoderError::Encode("SystemTime is too far from the Unix epoch".to_string())
+        })?;
+        writer.put_u8(TAG_SYSTEM_TIME);
+        seconds.encode(writer)?;
+        nanos.encode(writer)
+    }
+
+    fn is_default(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(feature = "std")]
+impl Packer for std::time::SystemTime {
+    fn pack(&self, writer: &mut BytesMut) -> Result<()> {
+        self.encode(writer)
+    }
+}
+
+/// Decodes a `SystemTime`. Values written by `chrono::DateTime<Utc>` (`TAG_CHRONO_DATETIME`)
+/// are accepted too, since they share the format.
+#[cfg(feature = "std")]
+impl Decoder for std::time::SystemTime {
+    fn decode(reader: &mut Bytes) -> Result<Self> {
+        if reader.remaining() == 0 {
+            return Err(EncoderError::eof(1, 0));
+        }
+        let tag = reader.get_u8();
+        if tag != TAG_SYSTEM_TIME && tag != TAG_CHRONO_DATETIME {
+            return Err(EncoderError::Decode(format!(
+                "Expected SystemTime tag ({}), got {}",
+                TAG_SYSTEM_TIME, tag
+            )));
+        }
+        let seconds = i64::decode(reader)?;
+        let nanos = read_subsec_nanos(reader)?;
+        let whole = ::core::time::Duration::from_secs(seconds.unsigned_abs());
+        let time = if seconds >= 0 {
+            std::time::UNIX_EPOCH.checked_add(whole)
+        } else {
+            std::time::UNIX_EPOCH.checked_sub(whole)
+        };
+        time.and_then(|t| t.checked_add(::core::time::Duration::from_nanos(nanos as u64)))
+            .ok_or_else(|| {
+                EncoderError::Decode(format!(
+                    "Timestamp {} seconds, {} nanos is out of range for SystemTime",
+                    seconds, nanos
+                ))
+            })
+    }
+}
+
+#[cfg(feature = "std")]
+impl Unpacker for std::time::SystemTime {
+    fn unpack(reader: &mut Bytes) -> Result<Self> {
+        Self::decode(reader)
+    }
+}
+
 // --- f32/f64 ---
 /// Encodes an `f32` as a scientific notation string.
 ///
@@ -973,7 +1266,7 @@ impl Packer for f32 {
 impl Decoder for f32 {
     fn decode(reader: &mut Bytes) -> Result<Self> {
         if reader.remaining() == 0 {
-            return Err(EncoderError::InsufficientData);
+            return Err(EncoderError::eof(1, 0));
         }
 
         // Peek at the tag to determine format
@@ -997,14 +1290,14 @@ impl Decoder for f32 {
         reader.advance(1); // consume the tag
         if tag == TAG_F32 {
             if reader.remaining() < 4 {
-                return Err(EncoderError::InsufficientData);
+                return Err(EncoderError::eof(4, reader.remaining()));
             }
             let mut bytes = [0u8; 4];
             reader.copy_to_slice(&mut bytes);
             Ok(f32::from_le_bytes(bytes))
         } else if tag == TAG_F64 {
             if reader.remaining() < 8 {
-                return Err(EncoderError::InsufficientData);
+                return Err(EncoderError::eof(8, reader.remaining()));
             }
             let mut bytes = [0u8; 8];
             reader.copy_to_slice(&mut bytes);
@@ -1022,14 +1315,14 @@ impl Unpacker for f32 {
     /// Unpacks an `f32` from either TAG_NONE (0.0) or TAG_F32 + 4 bytes (little-endian IEEE 754).
     fn unpack(reader: &mut Bytes) -> Result<Self> {
         if reader.remaining() == 0 {
-            return Err(EncoderError::InsufficientData);
+            return Err(EncoderError::eof(1, 0));
         }
         let tag = reader.get_u8();
         if tag == TAG_NONE {
             Ok(0.0)
         } else if tag == TAG_F32 {
             if reader.remaining() < 4 {
-                return Err(EncoderError::InsufficientData);
+                return Err(EncoderError::eof(4, reader.remaining()));
             }
             let mut bytes = [0u8; 4];
             reader.copy_to_slice(&mut bytes);
@@ -1043,12 +1336,16 @@ impl Unpacker for f32 {
     }
 }
 
-/// Encodes an `f64` as a scientific notation string.
+/// Encodes an `f64` as a scientific notation string, or as TAG_F32 + 4 bytes when
+/// `EncodeConfig::shrink_floats` is enabled and `f32` holds the value exactly.
 ///
 /// Note: Pack/Unpack still uses binary format for efficiency.
 /// The string format provides better compatibility and readability for Encode/Decode.
 impl Encoder for f64 {
     fn encode(&self, writer: &mut BytesMut) -> Result<()> {
+        if write_shrunk_f64(*self, writer) {
+            return Ok(());
+        }
         let s = format!("{:e}", self);
         s.encode(writer)
     }
@@ -1059,11 +1356,12 @@ impl Encoder for f64 {
 }
 
 impl Packer for f64 {
-    /// Packs an `f64` as TAG_NONE (for 0.0) or TAG_F64 + 8 bytes (little-endian IEEE 754).
+    /// Packs an `f64` as TAG_NONE (for 0.0) or TAG_F64 + 8 bytes (little-endian IEEE 754),
+    /// or TAG_F32 + 4 bytes when shrunk by `EncodeConfig::shrink_floats`.
     fn pack(&self, writer: &mut BytesMut) -> Result<()> {
         if *self == 0.0 {
             writer.put_u8(TAG_NONE);
-        } else {
+        } else if !write_shrunk_f64(*self, writer) {
             writer.put_u8(TAG_F64);
             writer.put_f64_le(*self);
         }
@@ -1071,16 +1369,31 @@ impl Packer for f64 {
     }
 }
 
+/// Writes `value` as TAG_F32 + 4 bytes if [`EncodeConfig::shrink_floats`](crate::EncodeConfig::shrink_floats)
+/// is enabled and the value survives the round trip through `f32` unchanged.
+///
+/// Returns `false`, writing nothing, otherwise.
+fn write_shrunk_f64(value: f64, writer: &mut BytesMut) -> bool {
+    let narrow = value as f32;
+    // NaN never compares equal, so NaN payloads keep their full width
+    if !crate::current_encode_config().shrink_floats || narrow as f64 != value {
+        return false;
+    }
+    writer.put_u8(TAG_F32);
+    writer.put_f32_le(narrow);
+    true
+}
+
 /// Decodes an `f64` from a scientific notation string, legacy binary format, or i128.
 ///
 /// This decoder supports:
 /// - New string format (TAG_STRING_BASE..TAG_STRING_LONG)
-/// - Legacy binary format (TAG_F64)
+/// - Binary format (TAG_F64, or TAG_F32 written by `EncodeConfig::shrink_floats`)
 /// - i128 cross-decode (TAG_ZERO..TAG_U128, TAG_NEGATIVE)
 impl Decoder for f64 {
     fn decode(reader: &mut Bytes) -> Result<Self> {
         if reader.remaining() == 0 {
-            return Err(EncoderError::InsufficientData);
+            return Err(EncoderError::eof(1, 0));
         }
 
         // Peek at the tag to determine format
@@ -1104,61 +1417,80 @@ impl Decoder for f64 {
         reader.advance(1); // consume the tag
         if tag == TAG_F64 {
             if reader.remaining() < 8 {
-                return Err(EncoderError::InsufficientData);
+                return Err(EncoderError::eof(8, reader.remaining()));
             }
             let mut bytes = [0u8; 8];
             reader.copy_to_slice(&mut bytes);
             Ok(f64::from_le_bytes(bytes))
+        } else if tag == TAG_F32 {
+            // Widening is exact, so this returns the value that was shrunk
+            if reader.remaining() < 4 {
+                return Err(EncoderError::eof(4, reader.remaining()));
+            }
+            Ok(reader.get_f32_le() as f64)
         } else {
             Err(EncoderError::Decode(format!(
-                "Expected f64 string ({}..={}), binary tag ({}), or integer tag, got {}. f32 to f64 cross-decoding is not supported due to precision concerns.",
-                TAG_STRING_BASE, TAG_STRING_LONG, TAG_F64, tag
+                "Expected f64 string ({}..={}), binary tag ({}, {}), or integer tag, got {}",
+                TAG_STRING_BASE, TAG_STRING_LONG, TAG_F64, TAG_F32, tag
             )))
         }
     }
 }
 
 impl Unpacker for f64 {
-    /// Unpacks an `f64` from either TAG_NONE (0.0) or TAG_F64 + 8 bytes (little-endian IEEE 754).
+    /// Unpacks an `f64` from TAG_NONE (0.0), TAG_F64 + 8 bytes or, as written by
+    /// `EncodeConfig::shrink_floats`, TAG_F32 + 4 bytes (little-endian IEEE 754).
     fn unpack(reader: &mut Bytes) -> Result<Self> {
         if reader.remaining() == 0 {
-            return Err(EncoderError::InsufficientData);
+            return Err(EncoderError::eof(1, 0));
         }
         let tag = reader.get_u8();
         if tag == TAG_NONE {
             Ok(0.0)
         } else if tag == TAG_F64 {
             if reader.remaining() < 8 {
-                return Err(EncoderError::InsufficientData);
+                return Err(EncoderError::eof(8, reader.remaining()));
             }
             let mut bytes = [0u8; 8];
             reader.copy_to_slice(&mut bytes);
             Ok(f64::from_le_bytes(bytes))
+        } else if tag == TAG_F32 {
+            if reader.remaining() < 4 {
+                return Err(EncoderError::eof(4, reader.remaining()));
+            }
+            Ok(reader.get_f32_le() as f64)
         } else {
             Err(EncoderError::Decode(format!(
-                "Expected f64 tag ({} or {}), got {}",
-                TAG_NONE, TAG_F64, tag
+                "Expected f64 tag ({}, {} or {}), got {}",
+                TAG_NONE, TAG_F64, TAG_F32, tag
             )))
         }
     }
 }
 
 // --- String ---
+/// Writes a string slice using the `String` encoding.
+///
+/// This is used by derived code to write names without allocating a `String`.
+pub fn write_str(value: &str, writer: &mut BytesMut) -> Result<()> {
+    let len = value.len();
+    crate::config::check_encode_limit(writer.len() + len)?;
+    if len <= MAX_SHORT_STRING_LEN {
+        let tag = TAG_STRING_BASE + len as u8;
+        writer.put_u8(tag);
+        writer.put_slice(value.as_bytes());
+    } else {
+        writer.put_u8(TAG_STRING_LONG);
+        len.encode(writer)?;
+        writer.put_slice(value.as_bytes());
+    }
+    Ok(())
+}
+
 /// Encodes a `String` as UTF-8 with a length prefix (short strings use a single tag byte).
 impl Encoder for String {
     fn encode(&self, writer: &mut BytesMut) -> Result<()> {
-        let len = self.len();
-        let max_short = (TAG_STRING_LONG - TAG_STRING_BASE - 1) as usize;
-        if len <= max_short {
-            let tag = TAG_STRING_BASE + len as u8; // 9..=29
-            writer.put_u8(tag);
-            writer.put_slice(self.as_bytes());
-        } else {
-            writer.put_u8(TAG_STRING_LONG);
-            len.encode(writer)?;
-            writer.put_slice(self.as_bytes());
-        }
-        Ok(())
+        write_str(self, writer)
     }
 
     fn is_default(&self) -> bool {
@@ -1176,7 +1508,7 @@ impl Packer for String {
 impl Decoder for String {
     fn decode(reader: &mut Bytes) -> Result<Self> {
         if reader.remaining() == 0 {
-            return Err(EncoderError::InsufficientData);
+            return Err(EncoderError::eof(1, 0));
         }
         let tag = reader.get_u8();
         let len = if (TAG_STRING_BASE..TAG_STRING_LONG).contains(&tag) {
@@ -1190,14 +1522,41 @@ impl Decoder for String {
             )));
         };
         if reader.remaining() < len {
-            return Err(EncoderError::InsufficientData);
+            return Err(EncoderError::eof(len, reader.remaining()));
         }
+        crate::config::check_string_len(len)?;
         let mut bytes = vec![0u8; len];
         if len > 0 {
             reader.copy_to_slice(&mut bytes);
         }
-        String::from_utf8(bytes).map_err(|e| EncoderError::Decode(e.to_string()))
+        let string = String::from_utf8(bytes).map_err(|e| EncoderError::Decode(e.to_string()))?;
+        Ok(normalize_decoded(string))
+    }
+}
+
+/// Applies the normalization selected by the decode configuration to a decoded string.
+#[inline]
+pub(crate) fn normalize_decoded(string: String) -> String {
+    if let Cow::Owned(normalized) = normalize_str(&string) {
+        return normalized;
+    }
+    string
+}
+
+/// Like [`normalize_decoded`], borrowing `text` when it is already normalized.
+#[inline]
+pub(crate) fn normalize_str(text: &str) -> Cow<'_, str> {
+    #[cfg(feature = "unicode-normalization")]
+    if crate::current_decode_config().normalize_nfc {
+        use unicode_normalization::{is_nfc_quick, IsNormalized, UnicodeNormalization};
+        if is_nfc_quick(text.chars()) != IsNormalized::Yes {
+            let normalized: String = text.nfc().collect();
+            if normalized != text {
+                return Cow::Owned(normalized);
+            }
+        }
     }
+    Cow::Borrowed(text)
 }
 
 impl Unpacker for String {
@@ -1246,7 +1605,7 @@ impl<T: Packer> Packer for Option<T> {
 impl<T: Decoder> Decoder for Option<T> {
     fn decode(reader: &mut Bytes) -> Result<Self> {
         if reader.remaining() == 0 {
-            return Err(EncoderError::InsufficientData); // Not even a tag
+            return Err(EncoderError::eof(1, 0)); // Not even a tag
         }
         let tag = reader.get_u8();
         match tag {
@@ -1263,7 +1622,7 @@ impl<T: Decoder> Decoder for Option<T> {
 impl<T: Unpacker> Unpacker for Option<T> {
     fn unpack(reader: &mut Bytes) -> Result<Self> {
         if reader.remaining() == 0 {
-            return Err(EncoderError::InsufficientData); // Not even a tag
+            return Err(EncoderError::eof(1, 0)); // Not even a tag
         }
         let tag = reader.get_u8();
         match tag {
@@ -1284,6 +1643,7 @@ impl<T: Encoder + 'static> Encoder for Vec<T> {
         encode_vec_length(self.len(), writer)?;
         for item in self {
             item.encode(writer)?;
+            crate::config::check_encode_limit(writer.len())?;
         }
         Ok(())
     }
@@ -1307,20 +1667,47 @@ impl<T: Packer + 'static> Packer for Vec<T> {
 /// Decodes a `Vec<T>` from the senax binary format.
 impl<T: Decoder + 'static> Decoder for Vec<T> {
     fn decode(reader: &mut Bytes) -> Result<Self> {
-        let len = decode_vec_length(reader)?;
-        let mut vec = Vec::with_capacity(len);
-        for _ in 0..len {
-            vec.push(T::decode(reader)?);
-        }
+        let mut vec = Vec::new();
+        decode_vec_into(reader, &mut vec)?;
         Ok(vec)
     }
 }
 
+/// Decodes an encoded `Vec<T>` into `vec`, reusing its allocation.
+///
+/// `vec` is cleared first, so a buffer refilled for every message in a loop stops allocating
+/// once it has grown to the largest message. If decoding fails, `vec` holds the elements
+/// decoded so far.
+///
+/// # Example
+/// ```rust
+/// use bytes::BytesMut;
+/// use senax_encoder::{core::decode_vec_into, Encoder};
+///
+/// let mut buffer: Vec<u32> = Vec::with_capacity(16);
+/// for message in [vec![1u32, 2, 3], vec![4]] {
+///     let mut writer = BytesMut::new();
+///     message.encode(&mut writer).unwrap();
+///     decode_vec_into(&mut writer.freeze(), &mut buffer).unwrap();
+///     assert_eq!(buffer, message);
+/// }
+/// assert!(buffer.capacity() >= 16);
+/// ```
+pub fn decode_vec_into<T: Decoder + 'static>(reader: &mut Bytes, vec: &mut Vec<T>) -> Result<()> {
+    vec.clear();
+    let len = decode_vec_length(reader)?;
+    vec.reserve(collection_capacity::<T>(len, reader)?);
+    for _ in 0..len {
+        vec.push(T::decode(reader)?);
+    }
+    Ok(())
+}
+
 impl<T: Unpacker + 'static> Unpacker for Vec<T> {
     /// Unpacks a `Vec<T>` from the compact format.
     fn unpack(reader: &mut Bytes) -> Result<Self> {
         let len = decode_vec_length(reader)?;
-        let mut vec = Vec::with_capacity(len);
+        let mut vec = Vec::with_capacity(collection_capacity::<T>(len, reader)?);
         for _ in 0..len {
             vec.push(T::unpack(reader)?);
         }
@@ -1335,6 +1722,7 @@ impl<T: Encoder, const N: usize> Encoder for [T; N] {
         encode_vec_length(N, writer)?;
         for item in self {
             item.encode(writer)?;
+            crate::config::check_encode_limit(writer.len())?;
         }
         Ok(())
     }
@@ -1357,13 +1745,7 @@ impl<T: Packer, const N: usize> Packer for [T; N] {
 /// Decodes a fixed-size array from the senax binary format.
 impl<T: Decoder, const N: usize> Decoder for [T; N] {
     fn decode(reader: &mut Bytes) -> Result<Self> {
-        let len = decode_vec_length(reader)?;
-        if len != N {
-            return Err(EncoderError::Decode(format!(
-                "Array length mismatch: expected {}, got {}",
-                N, len
-            )));
-        }
+        read_array_header(reader, N)?;
         let mut array = Vec::with_capacity(N);
         for _ in 0..N {
             array.push(T::decode(reader)?);
@@ -1376,13 +1758,7 @@ impl<T: Decoder, const N: usize> Decoder for [T; N] {
 
 impl<T: Unpacker, const N: usize> Unpacker for [T; N] {
     fn unpack(reader: &mut Bytes) -> Result<Self> {
-        let len = decode_vec_length(reader)?;
-        if len != N {
-            return Err(EncoderError::Decode(format!(
-                "Array length mismatch: expected {}, got {}",
-                N, len
-            )));
-        }
+        read_array_header(reader, N)?;
         let mut array = Vec::with_capacity(N);
         for _ in 0..N {
             array.push(T::unpack(reader)?);
@@ -1394,7 +1770,7 @@ impl<T: Unpacker, const N: usize> Unpacker for [T; N] {
 }
 
 // --- Tuple ---
-/// Implements encoding/decoding for tuples up to 10 elements.
+/// Implements encoding/decoding for tuples up to [`MAX_TUPLE_ARITY`] elements.
 ///
 /// Each tuple is encoded as a length-prefixed sequence of its elements.
 macro_rules! impl_tuple {
@@ -1425,17 +1801,7 @@ macro_rules! impl_tuple {
         impl Decoder for () {
 
             fn decode(reader: &mut Bytes) -> Result<Self> {
-                if reader.remaining() == 0 {
-                    return Err(EncoderError::InsufficientData);
-                }
-                let tag = reader.get_u8();
-                if tag != TAG_TUPLE {
-                    return Err(EncoderError::Decode(format!("Expected Tuple tag ({}), got {}", TAG_TUPLE, tag)));
-                }
-                let len = usize::decode(reader)?;
-                if len != 0 {
-                    return Err(EncoderError::Decode(format!("Expected 0-tuple but got {}-tuple", len)));
-                }
+                read_tuple_header(reader, 0)?;
                 Ok(())
             }
         }
@@ -1443,17 +1809,7 @@ macro_rules! impl_tuple {
         impl Unpacker for () {
 
             fn unpack(reader: &mut Bytes) -> Result<Self> {
-                if reader.remaining() == 0 {
-                    return Err(EncoderError::InsufficientData);
-                }
-                let tag = reader.get_u8();
-                if tag != TAG_TUPLE {
-                    return Err(EncoderError::Decode(format!("Expected Tuple tag ({}), got {}", TAG_TUPLE, tag)));
-                }
-                let len = usize::decode(reader)?;
-                if len != 0 {
-                    return Err(EncoderError::Decode(format!("Expected 0-tuple but got {}-tuple", len)));
-                }
+                read_tuple_header(reader, 0)?;
                 Ok(())
             }
         }
@@ -1477,6 +1833,23 @@ macro_rules! impl_tuple {
             }
         }
 
+        impl<$($T: Encoder + Decoder),+> FlatTuple for ($($T,)+) {
+            const ARITY: usize = count_args!($($T),+);
+
+            fn encode_elements(&self, writer: &mut BytesMut) -> Result<()> {
+                $(
+                    self.$idx.encode(writer)?;
+                )+
+                Ok(())
+            }
+
+            fn decode_elements(reader: &mut Bytes) -> Result<Self> {
+                Ok(($(
+                    $T::decode(reader)?,
+                )+))
+            }
+        }
+
         impl<$($T: Packer),+> Packer for ($($T,)+) {
 
             fn pack(&self, writer: &mut BytesMut) -> Result<()> {
@@ -1493,39 +1866,27 @@ macro_rules! impl_tuple {
         impl<$($T: Decoder),+> Decoder for ($($T,)+) {
 
             fn decode(reader: &mut Bytes) -> Result<Self> {
-                if reader.remaining() == 0 {
-                    return Err(EncoderError::InsufficientData);
-                }
-                let tag = reader.get_u8();
-                if tag != TAG_TUPLE {
-                    return Err(EncoderError::Decode(format!("Expected Tuple tag ({}), got {}", TAG_TUPLE, tag)));
-                }
-                let len = usize::decode(reader)?;
-                let expected_len = count_args!($($T),+);
-                if len != expected_len {
-                    return Err(EncoderError::Decode(format!("Expected {}-tuple but got {}-tuple", expected_len, len)));
-                }
+                read_tuple_header(reader, count_args!($($T),+))?;
                 Ok(($(
                     $T::decode(reader)?,
                 )+))
             }
         }
 
+        impl<'a, $($T: BorrowDecoder<'a>),+> BorrowDecoder<'a> for ($($T,)+) {
+
+            fn decode_borrowed(reader: &mut BorrowReader<'a>) -> Result<Self> {
+                read_tuple_header(&mut reader.rest, count_args!($($T),+))?;
+                Ok(($(
+                    $T::decode_borrowed(reader)?,
+                )+))
+            }
+        }
+
         impl<$($T: Unpacker),+> Unpacker for ($($T,)+) {
 
             fn unpack(reader: &mut Bytes) -> Result<Self> {
-                if reader.remaining() == 0 {
-                    return Err(EncoderError::InsufficientData);
-                }
-                let tag = reader.get_u8();
-                if tag != TAG_TUPLE {
-                    return Err(EncoderError::Decode(format!("Expected Tuple tag ({}), got {}", TAG_TUPLE, tag)));
-                }
-                let len = usize::decode(reader)?;
-                let expected_len = count_args!($($T),+);
-                if len != expected_len {
-                    return Err(EncoderError::Decode(format!("Expected {}-tuple but got {}-tuple", expected_len, len)));
-                }
+                read_tuple_header(reader, count_args!($($T),+))?;
                 Ok(($(
                     $T::unpack(reader)?,
                 )+))
@@ -1539,7 +1900,7 @@ macro_rules! count_args {
     ($head:ident $(, $tail:ident)*) => { 1 + count_args!($($tail),*) };
 }
 
-// Generate tuple implementations for 0 to 12 elements
+// Generate tuple implementations for 0 to 16 elements
 impl_tuple!();
 impl_tuple!(T0: 0);
 impl_tuple!(T0: 0, T1: 1);
@@ -1553,19 +1914,190 @@ impl_tuple!(T0: 0, T1: 1, T2: 2, T3: 3, T4: 4, T5: 5, T6: 6, T7: 7, T8: 8);
 impl_tuple!(T0: 0, T1: 1, T2: 2, T3: 3, T4: 4, T5: 5, T6: 6, T7: 7, T8: 8, T9: 9);
 impl_tuple!(T0: 0, T1: 1, T2: 2, T3: 3, T4: 4, T5: 5, T6: 6, T7: 7, T8: 8, T9: 9, T10: 10);
 impl_tuple!(T0: 0, T1: 1, T2: 2, T3: 3, T4: 4, T5: 5, T6: 6, T7: 7, T8: 8, T9: 9, T10: 10, T11: 11);
+impl_tuple!(T0: 0, T1: 1, T2: 2, T3: 3, T4: 4, T5: 5, T6: 6, T7: 7, T8: 8, T9: 9, T10: 10, T11: 11, T12: 12);
+impl_tuple!(T0: 0, T1: 1, T2: 2, T3: 3, T4: 4, T5: 5, T6: 6, T7: 7, T8: 8, T9: 9, T10: 10, T11: 11, T12: 12, T13: 13);
+impl_tuple!(T0: 0, T1: 1, T2: 2, T3: 3, T4: 4, T5: 5, T6: 6, T7: 7, T8: 8, T9: 9, T10: 10, T11: 11, T12: 12, T13: 13, T14: 14);
+impl_tuple!(T0: 0, T1: 1, T2: 2, T3: 3, T4: 4, T5: 5, T6: 6, T7: 7, T8: 8, T9: 9, T10: 10, T11: 11, T12: 12, T13: 13, T14: 14, T15: 15);
+
+/// Implements the encoding traits for a tuple struct, writing it exactly like a tuple.
+///
+/// Tuples of up to [`MAX_TUPLE_ARITY`] elements are supported directly. Rust's orphan rule
+/// keeps other crates from implementing senax traits for wider tuples, so wrap them in a tuple
+/// struct and list its fields as `index: type`. The struct is encoded and packed as `TAG_TUPLE`,
+/// the element count and the elements, so the payload is the one the tuple itself would have
+/// (and decodes as [`Value::Tuple`](crate::Value::Tuple)), rather than a tuple struct payload.
+///
+/// # Example
+/// ```rust
+/// use senax_encoder::{decode, encode, impl_senax_tuple};
+///
+/// #[derive(Debug, PartialEq)]
+/// struct Wide(u8, u8, u8, u8, u8, u8, u8, u8, u8, u8, u8, u8, u8, u8, u8, u8, String);
+///
+/// impl_senax_tuple!(Wide {
+///     0: u8, 1: u8, 2: u8, 3: u8, 4: u8, 5: u8, 6: u8, 7: u8,
+///     8: u8, 9: u8, 10: u8, 11: u8, 12: u8, 13: u8, 14: u8, 15: u8, 16: String,
+/// });
+///
+/// let wide = Wide(0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, "end".into());
+/// let bytes = encode(&wide).unwrap();
+/// assert_eq!(decode::<Wide>(&mut bytes.clone()).unwrap(), wide);
+/// ```
+#[macro_export]
+macro_rules! impl_senax_tuple {
+    ($name:ident { $($idx:tt: $T:ty),+ $(,)? }) => {
+        impl $crate::Encoder for $name {
+            fn encode(&self, writer: &mut $crate::bytes::BytesMut) -> $crate::Result<()> {
+                $crate::core::write_tuple_header(writer, [$(stringify!($idx)),+].len())?;
+                $( $crate::Encoder::encode(&self.$idx, writer)?; )+
+                Ok(())
+            }
+
+            fn is_default(&self) -> bool {
+                $( $crate::Encoder::is_default(&self.$idx) )&&+
+            }
+        }
+
+        impl $crate::Decoder for $name {
+            fn decode(reader: &mut $crate::bytes::Bytes) -> $crate::Result<Self> {
+                $crate::core::read_tuple_header(reader, [$(stringify!($idx)),+].len())?;
+                Ok($name($( <$T as $crate::Decoder>::decode(reader)?, )+))
+            }
+        }
+
+        impl $crate::Packer for $name {
+            fn pack(&self, writer: &mut $crate::bytes::BytesMut) -> $crate::Result<()> {
+                $crate::core::write_tuple_header(writer, [$(stringify!($idx)),+].len())?;
+                $( $crate::Packer::pack(&self.$idx, writer)?; )+
+                Ok(())
+            }
+        }
+
+        impl $crate::Unpacker for $name {
+            fn unpack(reader: &mut $crate::bytes::Bytes) -> $crate::Result<Self> {
+                $crate::core::read_tuple_header(reader, [$(stringify!($idx)),+].len())?;
+                Ok($name($( <$T as $crate::Unpacker>::unpack(reader)?, )+))
+            }
+        }
+    };
+}
+
+/// Writes `TAG_TUPLE` and the element count. Used by [`impl_senax_tuple!`].
+pub fn write_tuple_header(writer: &mut BytesMut, len: usize) -> Result<()> {
+    writer.put_u8(TAG_TUPLE);
+    len.encode(writer)
+}
+
+/// Reads `TAG_TUPLE` and checks the element count. Used by [`impl_senax_tuple!`].
+///
+/// With [`DecodeConfig::lenient_tuples`](crate::DecodeConfig::lenient_tuples), an array or
+/// `Vec` header with the same number of elements is accepted as well.
+pub fn read_tuple_header(reader: &mut Bytes, expected_len: usize) -> Result<()> {
+    if reader.remaining() == 0 {
+        return Err(EncoderError::eof(1, 0));
+    }
+    let tag = reader.get_u8();
+    let len = if tag == TAG_TUPLE {
+        usize::decode(reader)?
+    } else if (TAG_ARRAY_VEC_SET_BASE..=TAG_ARRAY_VEC_SET_LONG).contains(&tag)
+        && crate::current_decode_config().lenient_tuples
+    {
+        array_length_after_tag(tag, reader)?
+    } else {
+        return Err(EncoderError::Decode(format!(
+            "Expected Tuple tag ({}), got {}",
+            TAG_TUPLE, tag
+        )));
+    };
+    if len != expected_len {
+        return Err(EncoderError::Decode(format!(
+            "Expected {}-tuple but got {}-tuple",
+            expected_len, len
+        )));
+    }
+    Ok(())
+}
+
+/// A tuple whose elements can be written back to back without the `TAG_TUPLE` header.
+///
+/// Used for the keys of `#[senax(flat_keys)]` maps, where the arity is written once per map.
+pub trait FlatTuple: Sized {
+    /// Number of elements.
+    const ARITY: usize;
+
+    /// Encodes the elements without the tuple tag and length.
+    fn encode_elements(&self, writer: &mut BytesMut) -> Result<()>;
+
+    /// Decodes elements written by [`encode_elements`](FlatTuple::encode_elements).
+    fn decode_elements(reader: &mut Bytes) -> Result<Self>;
+}
 
 // --- Map (HashMap) ---
+/// Writes a key of a [`TAG_MAP_STRING_KEYS`] map: its length and UTF-8 bytes without a tag.
+#[cfg(feature = "std")]
+pub(crate) fn write_string_key(key: &str, writer: &mut BytesMut) -> Result<()> {
+    crate::config::check_encode_limit(writer.len() + key.len())?;
+    key.len().encode(writer)?;
+    writer.put_slice(key.as_bytes());
+    Ok(())
+}
+
+/// Reads a key written by [`write_string_key`], which must be valid UTF-8.
+#[cfg(feature = "std")]
+pub(crate) fn read_string_key(reader: &mut Bytes) -> Result<String> {
+    let len = usize::decode(reader)?;
+    if reader.remaining() < len {
+        return Err(EncoderError::eof(len, reader.remaining()));
+    }
+    crate::config::check_string_len(len)?;
+    let bytes = reader.split_to(len);
+    let key = ::core::str::from_utf8(&bytes)
+        .map_err(|e| EncoderError::Decode(format!("Invalid UTF-8 in map key: {}", e)))?;
+    Ok(normalize_decoded(key.to_string()))
+}
+
+/// Writes the header (the tag and any fixed bytes that follow it), the entry count and the
+/// entries of a hash-based map.
+///
+/// When [`EncodeConfig::sort_maps`](crate::EncodeConfig::sort_maps) is enabled, entries are
+/// written in the order of their encoded key bytes so the output does not depend on hash order.
+pub(crate) fn write_hash_map_entries<'a, K: 'a, V: 'a>(
+    header: &[u8],
+    entries: impl ExactSizeIterator<Item = (&'a K, &'a V)>,
+    writer: &mut BytesMut,
+    write_key: impl Fn(&K, &mut BytesMut) -> Result<()>,
+    write_value: impl Fn(&V, &mut BytesMut) -> Result<()>,
+) -> Result<()> {
+    writer.put_slice(header);
+    entries.len().encode(writer)?;
+    if !crate::current_encode_config().sort_maps {
+        for (k, v) in entries {
+            write_key(k, writer)?;
+            write_value(v, writer)?;
+            crate::config::check_encode_limit(writer.len())?;
+        }
+        return Ok(());
+    }
+    let mut keyed = Vec::with_capacity(entries.len());
+    for (k, v) in entries {
+        let mut key = BytesMut::new();
+        write_key(k, &mut key)?;
+        keyed.push((key, v));
+    }
+    keyed.sort_unstable_by(|a, b| a.0.cmp(&b.0));
+    for (key, v) in keyed {
+        writer.put_slice(&key);
+        write_value(v, writer)?;
+        crate::config::check_encode_limit(writer.len())?;
+    }
+    Ok(())
+}
+
 /// Encodes a map as a length-prefixed sequence of key-value pairs.
+#[cfg(feature = "std")]
 impl<K: Encoder, V: Encoder> Encoder for HashMap<K, V> {
     fn encode(&self, writer: &mut BytesMut) -> Result<()> {
-        writer.put_u8(TAG_MAP);
-        let len = self.len();
-        len.encode(writer)?;
-        for (k, v) in self {
-            k.encode(writer)?;
-            v.encode(writer)?;
-        }
-        Ok(())
+        write_hash_map_entries(&[TAG_MAP], self.iter(), writer, K::encode, V::encode)
     }
 
     fn is_default(&self) -> bool {
@@ -1573,37 +2105,51 @@ impl<K: Encoder, V: Encoder> Encoder for HashMap<K, V> {
     }
 }
 
+#[cfg(feature = "std")]
 impl<K: Packer, V: Packer> Packer for HashMap<K, V> {
     fn pack(&self, writer: &mut BytesMut) -> Result<()> {
-        writer.put_u8(TAG_MAP);
-        let len = self.len();
-        len.encode(writer)?;
-        for (k, v) in self {
-            k.pack(writer)?;
-            v.pack(writer)?;
-        }
-        Ok(())
+        write_hash_map_entries(&[TAG_MAP], self.iter(), writer, K::pack, V::pack)
     }
 }
 
 /// Decodes a map from the senax binary format.
+#[cfg(feature = "std")]
 impl<K: Decoder + Eq + std::hash::Hash, V: Decoder> Decoder for HashMap<K, V> {
     fn decode(reader: &mut Bytes) -> Result<Self> {
-        let len = read_map_header(reader)?;
-        let mut map = HashMap::with_capacity(len);
-        for _ in 0..len {
-            let k = K::decode(reader)?;
-            let v = V::decode(reader)?;
-            map.insert(k, v);
-        }
+        let mut map = HashMap::new();
+        decode_hash_map_into(reader, &mut map)?;
         Ok(map)
     }
 }
 
+/// Decodes an encoded map into `map`, reusing its allocation.
+///
+/// `map` is cleared first and keeps its capacity. Any hasher is accepted, so `FxHashMap` works
+/// directly and `AHashMap` through `&mut *map`. If decoding fails, `map` holds the entries
+/// decoded so far. See [`decode_vec_into`].
+#[cfg(feature = "std")]
+pub fn decode_hash_map_into<K, V, S>(reader: &mut Bytes, map: &mut HashMap<K, V, S>) -> Result<()>
+where
+    K: Decoder + Eq + std::hash::Hash,
+    V: Decoder,
+    S: std::hash::BuildHasher,
+{
+    map.clear();
+    let len = read_map_header(reader)?;
+    map.reserve(collection_capacity::<(K, V)>(len, reader)?);
+    for _ in 0..len {
+        let k = K::decode(reader)?;
+        let v = V::decode(reader)?;
+        map.insert(k, v);
+    }
+    Ok(())
+}
+
+#[cfg(feature = "std")]
 impl<K: Unpacker + Eq + std::hash::Hash, V: Unpacker> Unpacker for HashMap<K, V> {
     fn unpack(reader: &mut Bytes) -> Result<Self> {
         let len = read_map_header(reader)?;
-        let mut map = HashMap::with_capacity(len);
+        let mut map = HashMap::with_capacity(collection_capacity::<(K, V)>(len, reader)?);
         for _ in 0..len {
             let k = K::unpack(reader)?;
             let v = V::unpack(reader)?;
@@ -1613,6 +2159,144 @@ impl<K: Unpacker + Eq + std::hash::Hash, V: Unpacker> Unpacker for HashMap<K, V>
     }
 }
 
+/// Encodes a map with tuple keys for a `#[senax(flat_keys)]` field.
+///
+/// Writes `TAG_MAP_FLAT_KEYS`, the key arity, the entry count and the entries, with each key's
+/// elements written back to back instead of as a tagged tuple. Works with any map whose
+/// reference iterates over `(&K, &V)` (`HashMap`, `BTreeMap`, `IndexMap`, ...).
+pub fn encode_flat_key_map<'a, M, K, V>(map: &'a M, writer: &mut BytesMut) -> Result<()>
+where
+    &'a M: IntoIterator<Item = (&'a K, &'a V)>,
+    <&'a M as IntoIterator>::IntoIter: ExactSizeIterator,
+    K: FlatTuple + 'a,
+    V: Encoder + 'a,
+{
+    write_hash_map_entries(
+        &[TAG_MAP_FLAT_KEYS, K::ARITY as u8],
+        map.into_iter(),
+        writer,
+        K::encode_elements,
+        V::encode,
+    )
+}
+
+/// Decodes a map written by [`encode_flat_key_map`] for a `#[senax(flat_keys)]` field.
+///
+/// Maps written with tagged tuple keys (`TAG_MAP`) are accepted too, so the attribute can be
+/// added to an existing field without breaking old payloads.
+pub fn decode_flat_key_map<M, K, V>(reader: &mut Bytes) -> Result<M>
+where
+    M: FromIterator<(K, V)>,
+    K: FlatTuple + Decoder,
+    V: Decoder,
+{
+    let flat = reader.chunk().first() == Some(&TAG_MAP_FLAT_KEYS);
+    if !flat {
+        return decode_map_entries::<K, V>(reader).map(|entries| entries.into_iter().collect());
+    }
+    reader.advance(1);
+    if reader.remaining() == 0 {
+        return Err(EncoderError::eof(1, 0));
+    }
+    let arity = reader.get_u8() as usize;
+    if arity != K::ARITY {
+        return Err(EncoderError::Decode(format!(
+            "Expected map keys of {} elements, got {}",
+            K::ARITY,
+            arity
+        )));
+    }
+    let len = usize::decode(reader)?;
+    // Every entry takes at least one byte per key element and one for the value
+    if reader.remaining() < len.saturating_mul(arity + 1) {
+        return Err(EncoderError::eof(
+            len.saturating_mul(arity + 1),
+            reader.remaining(),
+        ));
+    }
+    collection_capacity::<(K, V)>(len, reader)?;
+    (0..len)
+        .map(|_| Ok((K::decode_elements(reader)?, V::decode(reader)?)))
+        .collect()
+}
+
+/// Encodes a sequence or set in ascending order for a `#[senax(sort)]` field.
+///
+/// Writes the same format as `Vec<T>`, so readers need no changes; only the element order is
+/// fixed. Works with any collection whose reference iterates over `&T` (`Vec`, `HashSet`,
+/// `IndexSet`, ...).
+pub fn encode_sorted<'a, C, T>(collection: &'a C, writer: &mut BytesMut) -> Result<()>
+where
+    &'a C: IntoIterator<Item = &'a T>,
+    T: Encoder + Ord + 'a,
+{
+    let mut items: Vec<&T> = collection.into_iter().collect();
+    items.sort();
+    encode_vec_length(items.len(), writer)?;
+    for v in items {
+        v.encode(writer)?;
+        crate::config::check_encode_limit(writer.len())?;
+    }
+    Ok(())
+}
+
+/// Packs a sequence or set in ascending order for a `#[senax(sort)]` field.
+pub fn pack_sorted<'a, C, T>(collection: &'a C, writer: &mut BytesMut) -> Result<()>
+where
+    &'a C: IntoIterator<Item = &'a T>,
+    T: Packer + Ord + 'a,
+{
+    let mut items: Vec<&T> = collection.into_iter().collect();
+    items.sort();
+    encode_vec_length(items.len(), writer)?;
+    for v in items {
+        v.pack(writer)?;
+    }
+    Ok(())
+}
+
+/// Decodes an encoded map into a `Vec` of key/value pairs, preserving wire order.
+///
+/// Unlike decoding into `HashMap` or `BTreeMap`, entries are neither reordered nor
+/// collapsed, so duplicate keys written by foreign producers are kept.
+///
+/// # Example
+/// ```rust
+/// use senax_encoder::{core::decode_map_entries, Encoder};
+/// use bytes::BytesMut;
+/// use std::collections::BTreeMap;
+///
+/// let map = BTreeMap::from([(1u32, "a".to_string()), (2, "b".to_string())]);
+/// let mut buf = BytesMut::new();
+/// map.encode(&mut buf).unwrap();
+/// let entries: Vec<(u32, String)> = decode_map_entries(&mut buf.freeze()).unwrap();
+/// assert_eq!(entries, vec![(1, "a".to_string()), (2, "b".to_string())]);
+/// ```
+pub fn decode_map_entries<K: Decoder, V: Decoder>(reader: &mut Bytes) -> Result<Vec<(K, V)>> {
+    let len = read_map_header(reader)?;
+    let mut entries = Vec::with_capacity(collection_capacity::<(K, V)>(len, reader)?);
+    for _ in 0..len {
+        let k = K::decode(reader)?;
+        let v = V::decode(reader)?;
+        entries.push((k, v));
+    }
+    Ok(entries)
+}
+
+/// Unpacks a packed map into a `Vec` of key/value pairs, preserving wire order.
+///
+/// This is the pack-format counterpart of [`decode_map_entries`].
+pub fn unpack_map_entries<K: Unpacker, V: Unpacker>(reader: &mut Bytes) -> Result<Vec<(K, V)>> {
+    let len = read_map_header(reader)?;
+    let mut entries = Vec::with_capacity(collection_capacity::<(K, V)>(len, reader)?);
+    for _ in 0..len {
+        let k = K::unpack(reader)?;
+        let v = V::unpack(reader)?;
+        entries.push((k, v));
+    }
+    Ok(entries)
+}
+
 /// Writes a `u32` in little-endian format without a tag.
 ///
 /// This is used internally for struct/enum field/variant IDs.
@@ -1626,7 +2310,7 @@ pub fn write_u32_le(writer: &mut BytesMut, value: u32) -> Result<()> {
 /// This is used internally for struct/enum field/variant IDs.
 pub fn read_u32_le(reader: &mut Bytes) -> Result<u32> {
     if reader.remaining() < 4 {
-        return Err(EncoderError::InsufficientData);
+        return Err(EncoderError::eof(4, reader.remaining()));
     }
     Ok(reader.get_u32_le())
 }
@@ -1644,92 +2328,111 @@ pub fn write_u64_le(writer: &mut BytesMut, value: u64) -> Result<()> {
 /// This is used internally for struct/enum field/variant IDs.
 pub fn read_u64_le(reader: &mut Bytes) -> Result<u64> {
     if reader.remaining() < 8 {
-        return Err(EncoderError::InsufficientData);
+        return Err(EncoderError::eof(8, reader.remaining()));
     }
     Ok(reader.get_u64_le())
 }
 
+/// Advances `reader` past `len` bytes.
+#[inline]
+fn skip_bytes(reader: &mut Bytes, len: usize) -> Result<()> {
+    if reader.remaining() < len {
+        return Err(EncoderError::eof(len, reader.remaining()));
+    }
+    reader.advance(len);
+    Ok(())
+}
+
+/// Skips a string value without allocating or checking UTF-8.
+fn skip_string(reader: &mut Bytes) -> Result<()> {
+    if reader.remaining() == 0 {
+        return Err(EncoderError::eof(1, 0));
+    }
+    let tag = reader.get_u8();
+    let len = match tag {
+        _ if (TAG_STRING_BASE..TAG_STRING_LONG).contains(&tag) => (tag - TAG_STRING_BASE) as usize,
+        TAG_STRING_LONG => usize::decode(reader)?,
+        _ => {
+            return Err(EncoderError::Decode(format!(
+                "Expected String tag ({}..={}), got {}",
+                TAG_STRING_BASE, TAG_STRING_LONG, tag
+            )))
+        }
+    };
+    skip_bytes(reader, len)
+}
+
+/// Skips `count` consecutive values of any type, e.g. the remaining elements of a list or the
+/// fields of a tuple.
+///
+/// Equivalent to calling [`skip_value`] `count` times, but small integers and short strings,
+/// the common case in lists and records, are skipped inline. Like [`skip_value`], it never
+/// allocates.
+///
+/// # Example
+/// ```rust
+/// use senax_encoder::core::skip_n_values;
+/// use senax_encoder::{Decoder, Encoder};
+/// use bytes::{Buf, BytesMut};
+///
+/// let mut buf = BytesMut::new();
+/// (1u8, "two".to_string(), vec![3u32], 4u64).encode(&mut buf).unwrap();
+/// let mut reader = buf.freeze();
+/// reader.advance(2); // tuple tag and length
+/// skip_n_values(&mut reader, 3).unwrap();
+/// assert_eq!(u64::decode(&mut reader).unwrap(), 4);
+/// ```
+pub fn skip_n_values(reader: &mut Bytes, count: usize) -> Result<()> {
+    for _ in 0..count {
+        match reader.first() {
+            Some(&(TAG_ZERO..=TAG_U8_127)) => reader.advance(1),
+            Some(&tag) if (TAG_STRING_BASE..TAG_STRING_LONG).contains(&tag) => {
+                skip_bytes(reader, 1 + (tag - TAG_STRING_BASE) as usize)?
+            }
+            _ => skip_value(reader)?,
+        }
+    }
+    Ok(())
+}
+
 /// Skips a value of any type in the senax binary format.
 ///
 /// This is used for forward/backward compatibility when unknown fields/variants are encountered.
+/// Only the structure is checked: strings are not validated as UTF-8, and nothing is allocated
+/// except for error messages.
 ///
 /// # Errors
 /// Returns an error if the value cannot be skipped (e.g., insufficient data).
 pub fn skip_value(reader: &mut Bytes) -> Result<()> {
     if reader.remaining() == 0 {
-        return Err(EncoderError::InsufficientData);
+        return Err(EncoderError::eof(1, 0));
     }
     let tag = reader.get_u8();
     match tag {
         TAG_ZERO..=TAG_U8_127 => Ok(()),
-        TAG_U8 => {
-            if reader.remaining() == 0 {
-                return Err(EncoderError::InsufficientData);
-            }
-            reader.advance(1);
-            Ok(())
-        }
-        TAG_U16 => {
-            if reader.remaining() < 2 {
-                return Err(EncoderError::InsufficientData);
-            }
-            reader.advance(2);
-            Ok(())
-        }
-        TAG_U32 => {
-            if reader.remaining() < 4 {
-                return Err(EncoderError::InsufficientData);
-            }
-            reader.advance(4);
-            Ok(())
-        }
-        TAG_U64 => {
-            if reader.remaining() < 8 {
-                return Err(EncoderError::InsufficientData);
-            }
-            reader.advance(8);
-            Ok(())
-        }
-        TAG_U128 => {
-            if reader.remaining() < 16 {
-                return Err(EncoderError::InsufficientData);
-            }
-            reader.advance(16);
-            Ok(())
-        }
-        TAG_F32 => {
-            if reader.remaining() < 4 {
-                return Err(EncoderError::InsufficientData);
-            }
-            reader.advance(4);
-            Ok(())
-        }
-        TAG_F64 => {
-            if reader.remaining() < 8 {
-                return Err(EncoderError::InsufficientData);
-            }
-            reader.advance(8);
+        TAG_U8 => skip_bytes(reader, 1),
+        TAG_U16 => skip_bytes(reader, 2),
+        TAG_U32 => skip_bytes(reader, 4),
+        TAG_U64 => skip_bytes(reader, 8),
+        TAG_U128 => skip_bytes(reader, 16),
+        TAG_NEGATIVE => {
+            // Followed by the bit-inverted magnitude as an unsigned integer
+            u128::decode(reader)?;
             Ok(())
         }
+        TAG_F32 => skip_bytes(reader, 4),
+        TAG_F64 => skip_bytes(reader, 8),
         TAG_STRING_BASE..=TAG_STRING_LONG => {
             let len = if tag < TAG_STRING_LONG {
                 (tag - TAG_STRING_BASE) as usize
             } else {
                 usize::decode(reader)?
             };
-            if reader.remaining() < len {
-                return Err(EncoderError::InsufficientData);
-            }
-            reader.advance(len);
-            Ok(())
+            skip_bytes(reader, len)
         }
         TAG_BINARY => {
             let len = usize::decode(reader)?;
-            if reader.remaining() < len {
-                return Err(EncoderError::InsufficientData);
-            }
-            reader.advance(len);
-            Ok(())
+            skip_bytes(reader, len)
         }
         TAG_ARRAY_VEC_SET_BASE..=TAG_ARRAY_VEC_SET_LONG => {
             let len = if tag < TAG_ARRAY_VEC_SET_LONG {
@@ -1737,10 +2440,7 @@ pub fn skip_value(reader: &mut Bytes) -> Result<()> {
             } else {
                 usize::decode(reader)?
             };
-            for _ in 0..len {
-                skip_value(reader)?;
-            }
-            Ok(())
+            skip_n_values(reader, len)
         }
         TAG_STRUCT_UNIT => Ok(()),
         TAG_STRUCT_NAMED => {
@@ -1755,10 +2455,7 @@ pub fn skip_value(reader: &mut Bytes) -> Result<()> {
         }
         TAG_STRUCT_UNNAMED => {
             let field_count = usize::decode(reader)?;
-            for _ in 0..field_count {
-                skip_value(reader)?;
-            }
-            Ok(())
+            skip_n_values(reader, field_count)
         }
         TAG_ENUM => {
             let _variant_id = read_field_id_optimized(reader)?;
@@ -1778,79 +2475,91 @@ pub fn skip_value(reader: &mut Bytes) -> Result<()> {
         TAG_ENUM_UNNAMED => {
             let _variant_id = read_field_id_optimized(reader)?;
             let field_count = usize::decode(reader)?;
-            for _ in 0..field_count {
-                skip_value(reader)?;
-            }
-            Ok(())
+            skip_n_values(reader, field_count)
         }
         TAG_TUPLE => {
+            let len = usize::decode(reader)?;
+            skip_n_values(reader, len)
+        }
+        TAG_MAP => {
+            // Keys and values alternate
+            let len = usize::decode(reader)?;
+            let values = len.saturating_mul(2);
+            skip_n_values(reader, values)
+        }
+        TAG_MAP_FLAT_KEYS => {
+            if reader.remaining() == 0 {
+                return Err(EncoderError::eof(1, 0));
+            }
+            let arity = reader.get_u8() as usize;
+            let len = usize::decode(reader)?;
+            // Key elements, then the value
+            let values = len.saturating_mul(arity + 1);
+            skip_n_values(reader, values)
+        }
+        TAG_MAP_STRING_KEYS => {
             let len = usize::decode(reader)?;
             for _ in 0..len {
+                let key_len = usize::decode(reader)?;
+                skip_bytes(reader, key_len)?;
                 skip_value(reader)?;
             }
             Ok(())
         }
-        TAG_MAP => {
+        TAG_MAP_ID_KEYS => {
             let len = usize::decode(reader)?;
             for _ in 0..len {
-                skip_value(reader)?; // key
-                skip_value(reader)?; // value
+                read_field_id_optimized(reader)?;
+                skip_value(reader)?;
             }
             Ok(())
         }
-        TAG_CHRONO_DATETIME => {
-            if reader.remaining() < 12 {
-                return Err(EncoderError::InsufficientData);
-            } // Approximation for i64 + u32, could be more precise
-            let _timestamp_seconds = i64::decode(reader)?;
-            let _timestamp_nanos = u32::decode(reader)?;
+        TAG_DURATION => {
+            u64::decode(reader)?;
+            u32::decode(reader)?;
             Ok(())
         }
-        TAG_CHRONO_NAIVE_DATE => {
-            if reader.remaining() < 8 {
-                return Err(EncoderError::InsufficientData);
-            } // Approximation for i64
-            let _days_from_epoch = i64::decode(reader)?;
+        TAG_SYSTEM_TIME => {
+            i64::decode(reader)?;
+            u32::decode(reader)?;
             Ok(())
         }
-        TAG_CHRONO_NAIVE_TIME => {
-            if reader.remaining() < 8 {
-                return Err(EncoderError::InsufficientData);
-            } // Approximation for u32 + u32
-            let _seconds_from_midnight = u32::decode(reader)?;
-            let _nanoseconds = u32::decode(reader)?;
+        TAG_CHRONO_DATETIME | TAG_CHRONO_NAIVE_DATETIME => {
+            // Seconds and nanoseconds since the Unix epoch
+            i64::decode(reader)?;
+            u32::decode(reader)?;
+            Ok(())
+        }
+        TAG_CHRONO_NAIVE_DATE => {
+            // Days since 1970-01-01
+            i64::decode(reader)?;
             Ok(())
         }
-        TAG_CHRONO_NAIVE_DATETIME => {
-            if reader.remaining() < 12 {
-                return Err(EncoderError::InsufficientData);
-            } // Approximation for i64 + u32
-            let _timestamp_seconds = i64::decode(reader)?;
-            let _timestamp_nanos = u32::decode(reader)?;
+        TAG_CHRONO_NAIVE_TIME => {
+            // Seconds and nanoseconds since midnight
+            u32::decode(reader)?;
+            u32::decode(reader)?;
             Ok(())
         }
         TAG_DECIMAL => {
-            if reader.remaining() < 20 {
-                return Err(EncoderError::InsufficientData);
-            } // Approximation for i128 + u32
-            let _mantissa = i128::decode(reader)?;
-            let _scale = u32::decode(reader)?;
+            // Mantissa and scale
+            i128::decode(reader)?;
+            u32::decode(reader)?;
             Ok(())
         }
         TAG_UUID => {
             // Covers ULID as well
-            if reader.remaining() < 16 {
-                return Err(EncoderError::InsufficientData);
-            }
-            reader.advance(16);
-            Ok(())
+            skip_bytes(reader, 16)
         }
         TAG_JSON_NULL => Ok(()),
-        TAG_JSON_BOOL => Ok(()),
+        TAG_JSON_BOOL => {
+            // Followed by an encoded bool
+            skip_value(reader)
+        }
         TAG_JSON_NUMBER => {
             // Number has type marker + actual number
             if reader.remaining() == 0 {
-                return Err(EncoderError::InsufficientData);
+                return Err(EncoderError::eof(1, 0));
             }
             let number_type = reader.get_u8();
             match number_type {
@@ -1863,6 +2572,9 @@ pub fn skip_value(reader: &mut Bytes) -> Result<()> {
                 2 => {
                     f64::decode(reader)?;
                 }
+                3 => {
+                    skip_string(reader)?;
+                }
                 _ => {
                     return Err(EncoderError::Decode(format!(
                         "Invalid JSON Number type marker: {}",
@@ -1874,20 +2586,16 @@ pub fn skip_value(reader: &mut Bytes) -> Result<()> {
         }
         TAG_JSON_STRING => {
             // String uses regular string encoding
-            String::decode(reader)?;
-            Ok(())
+            skip_string(reader)
         }
         TAG_JSON_ARRAY => {
             let len = usize::decode(reader)?;
-            for _ in 0..len {
-                skip_value(reader)?;
-            }
-            Ok(())
+            skip_n_values(reader, len)
         }
         TAG_JSON_OBJECT => {
             let len = usize::decode(reader)?;
             for _ in 0..len {
-                String::decode(reader)?; // key
+                skip_string(reader)?; // key
                 skip_value(reader)?; // value
             }
             Ok(())
@@ -1908,12 +2616,33 @@ pub fn skip_value(reader: &mut Bytes) -> Result<()> {
 }
 
 // --- HashSet, BTreeSet, IndexSet ---
+/// Collects decoded elements into a set, honoring [`DecodeConfig::strict_sets`](crate::DecodeConfig::strict_sets).
+///
+/// `insert` must return `false` when the element was already present. In lenient mode the
+/// first occurrence is kept; in strict mode the index of the first duplicate is reported.
+pub(crate) fn collect_set<T, S>(
+    elements: Vec<T>,
+    with_capacity: impl FnOnce(usize) -> S,
+    mut insert: impl FnMut(&mut S, T) -> bool,
+) -> Result<S> {
+    let strict = crate::current_decode_config().strict_sets;
+    let mut set = with_capacity(elements.len());
+    for (index, element) in elements.into_iter().enumerate() {
+        if !insert(&mut set, element) && strict {
+            return Err(EncoderError::DuplicateSetElement { index });
+        }
+    }
+    Ok(set)
+}
+
 /// Encodes a set as a length-prefixed sequence of elements.
+#[cfg(feature = "std")]
 impl<T: Encoder + Eq + std::hash::Hash> Encoder for HashSet<T> {
     fn encode(&self, writer: &mut BytesMut) -> Result<()> {
         encode_vec_length(self.len(), writer)?;
         for v in self {
             v.encode(writer)?;
+            crate::config::check_encode_limit(writer.len())?;
         }
         Ok(())
     }
@@ -1923,6 +2652,7 @@ impl<T: Encoder + Eq + std::hash::Hash> Encoder for HashSet<T> {
     }
 }
 
+#[cfg(feature = "std")]
 impl<T: Packer + Eq + std::hash::Hash> Packer for HashSet<T> {
     fn pack(&self, writer: &mut BytesMut) -> Result<()> {
         encode_vec_length(self.len(), writer)?;
@@ -1934,17 +2664,19 @@ impl<T: Packer + Eq + std::hash::Hash> Packer for HashSet<T> {
 }
 
 /// Decodes a set from the senax binary format.
+#[cfg(feature = "std")]
 impl<T: Decoder + Eq + std::hash::Hash + 'static> Decoder for HashSet<T> {
     fn decode(reader: &mut Bytes) -> Result<Self> {
         let vec: Vec<T> = Vec::decode(reader)?;
-        Ok(vec.into_iter().collect())
+        collect_set(vec, HashSet::with_capacity, |set, v| set.insert(v))
     }
 }
 
+#[cfg(feature = "std")]
 impl<T: Unpacker + Eq + std::hash::Hash + 'static> Unpacker for HashSet<T> {
     fn unpack(reader: &mut Bytes) -> Result<Self> {
         let vec: Vec<T> = Vec::unpack(reader)?;
-        Ok(vec.into_iter().collect())
+        collect_set(vec, HashSet::with_capacity, |set, v| set.insert(v))
     }
 }
 // --- BTreeSet ---
@@ -1953,6 +2685,7 @@ impl<T: Encoder + Ord> Encoder for BTreeSet<T> {
         encode_vec_length(self.len(), writer)?;
         for v in self {
             v.encode(writer)?;
+            crate::config::check_encode_limit(writer.len())?;
         }
         Ok(())
     }
@@ -1975,14 +2708,14 @@ impl<T: Packer + Ord> Packer for BTreeSet<T> {
 impl<T: Decoder + Ord + 'static> Decoder for BTreeSet<T> {
     fn decode(reader: &mut Bytes) -> Result<Self> {
         let vec: Vec<T> = Vec::decode(reader)?;
-        Ok(vec.into_iter().collect())
+        collect_set(vec, |_| BTreeSet::new(), |set, v| set.insert(v))
     }
 }
 
 impl<T: Unpacker + Ord + 'static> Unpacker for BTreeSet<T> {
     fn unpack(reader: &mut Bytes) -> Result<Self> {
         let vec: Vec<T> = Vec::unpack(reader)?;
-        Ok(vec.into_iter().collect())
+        collect_set(vec, |_| BTreeSet::new(), |set, v| set.insert(v))
     }
 }
 // --- BTreeMap ---
@@ -1994,6 +2727,7 @@ impl<K: Encoder + Ord, V: Encoder> Encoder for BTreeMap<K, V> {
         for (k, v) in self {
             k.encode(writer)?;
             v.encode(writer)?;
+            crate::config::check_encode_limit(writer.len())?;
         }
         Ok(())
     }
@@ -2018,20 +2752,36 @@ impl<K: Packer + Ord, V: Packer> Packer for BTreeMap<K, V> {
 
 impl<K: Decoder + Ord, V: Decoder> Decoder for BTreeMap<K, V> {
     fn decode(reader: &mut Bytes) -> Result<Self> {
-        let len = read_map_header(reader)?;
         let mut map = BTreeMap::new();
-        for _ in 0..len {
-            let k = K::decode(reader)?;
-            let v = V::decode(reader)?;
-            map.insert(k, v);
-        }
+        decode_btree_map_into(reader, &mut map)?;
         Ok(map)
     }
 }
 
+/// Decodes an encoded map into `map` after clearing it.
+///
+/// `BTreeMap` has no capacity to keep, but this lets code that refills a map per message use
+/// the same pattern as [`decode_hash_map_into`]. If decoding fails, `map` holds the entries
+/// decoded so far.
+pub fn decode_btree_map_into<K: Decoder + Ord, V: Decoder>(
+    reader: &mut Bytes,
+    map: &mut BTreeMap<K, V>,
+) -> Result<()> {
+    map.clear();
+    let len = read_map_header(reader)?;
+    collection_capacity::<(K, V)>(len, reader)?;
+    for _ in 0..len {
+        let k = K::decode(reader)?;
+        let v = V::decode(reader)?;
+        map.insert(k, v);
+    }
+    Ok(())
+}
+
 impl<K: Unpacker + Ord, V: Unpacker> Unpacker for BTreeMap<K, V> {
     fn unpack(reader: &mut Bytes) -> Result<Self> {
         let len = read_map_header(reader)?;
+        collection_capacity::<(K, V)>(len, reader)?;
         let mut map = BTreeMap::new();
         for _ in 0..len {
             let k = K::unpack(reader)?;
@@ -2047,6 +2797,7 @@ impl Encoder for Bytes {
     fn encode(&self, writer: &mut BytesMut) -> Result<()> {
         writer.put_u8(TAG_BINARY);
         let len = self.len();
+        crate::config::check_encode_limit(writer.len() + len)?;
         len.encode(writer)?;
         writer.put_slice(self);
         Ok(())
@@ -2067,10 +2818,26 @@ impl Packer for Bytes {
     }
 }
 
+/// Takes the next `len` bytes of `reader` as a `Bytes` value, sharing the input's allocation
+/// unless [`DecodeConfig::copy_out`](crate::DecodeConfig::copy_out) is set.
+pub(crate) fn split_bytes(reader: &mut Bytes, len: usize) -> Result<Bytes> {
+    if reader.remaining() < len {
+        return Err(EncoderError::eof(len, reader.remaining()));
+    }
+    crate::config::check_string_len(len)?;
+    if crate::current_decode_config().copy_out {
+        let bytes = Bytes::copy_from_slice(&reader[..len]);
+        reader.advance(len);
+        Ok(bytes)
+    } else {
+        Ok(reader.split_to(len))
+    }
+}
+
 impl Decoder for Bytes {
     fn decode(reader: &mut Bytes) -> Result<Self> {
         if reader.remaining() == 0 {
-            return Err(EncoderError::InsufficientData);
+            return Err(EncoderError::eof(1, 0));
         }
         let tag = reader.get_u8();
         let len = if tag == TAG_BINARY {
@@ -2086,18 +2853,14 @@ impl Decoder for Bytes {
             )));
         };
 
-        if reader.remaining() < len {
-            return Err(EncoderError::InsufficientData);
-        }
-
-        Ok(reader.split_to(len))
+        split_bytes(reader, len)
     }
 }
 
 impl Unpacker for Bytes {
     fn unpack(reader: &mut Bytes) -> Result<Self> {
         if reader.remaining() == 0 {
-            return Err(EncoderError::InsufficientData);
+            return Err(EncoderError::eof(1, 0));
         }
         let tag = reader.get_u8();
         let len = if tag == TAG_BINARY {
@@ -2113,11 +2876,285 @@ impl Unpacker for Bytes {
             )));
         };
 
-        if reader.remaining() < len {
-            return Err(EncoderError::InsufficientData);
+        split_bytes(reader, len)
+    }
+}
+
+// --- Borrowed str, [u8] and Cow ---
+/// Encodes a `str` like a `String`.
+impl Encoder for str {
+    fn encode(&self, writer: &mut BytesMut) -> Result<()> {
+        write_str(self, writer)
+    }
+
+    fn is_default(&self) -> bool {
+        self.is_empty()
+    }
+}
+
+impl Packer for str {
+    fn pack(&self, writer: &mut BytesMut) -> Result<()> {
+        self.encode(writer)
+    }
+}
+
+/// Encodes a byte slice like `Bytes`.
+impl Encoder for [u8] {
+    fn encode(&self, writer: &mut BytesMut) -> Result<()> {
+        writer.put_u8(TAG_BINARY);
+        let len = self.len();
+        crate::config::check_encode_limit(writer.len() + len)?;
+        len.encode(writer)?;
+        writer.put_slice(self);
+        Ok(())
+    }
+
+    fn is_default(&self) -> bool {
+        self.is_empty()
+    }
+}
+
+impl Packer for [u8] {
+    fn pack(&self, writer: &mut BytesMut) -> Result<()> {
+        self.encode(writer)
+    }
+}
+
+impl Encoder for Cow<'_, str> {
+    fn encode(&self, writer: &mut BytesMut) -> Result<()> {
+        write_str(self, writer)
+    }
+
+    fn is_default(&self) -> bool {
+        self.is_empty()
+    }
+}
+
+impl Packer for Cow<'_, str> {
+    fn pack(&self, writer: &mut BytesMut) -> Result<()> {
+        write_str(self, writer)
+    }
+}
+
+impl Encoder for Cow<'_, [u8]> {
+    fn encode(&self, writer: &mut BytesMut) -> Result<()> {
+        (**self).encode(writer)
+    }
+
+    fn is_default(&self) -> bool {
+        self.is_empty()
+    }
+}
+
+impl Packer for Cow<'_, [u8]> {
+    fn pack(&self, writer: &mut BytesMut) -> Result<()> {
+        (**self).encode(writer)
+    }
+}
+
+/// Reads a string written by `write_str`, borrowing it from the input.
+fn borrow_str<'a>(reader: &mut BorrowReader<'a>) -> Result<&'a str> {
+    let tag = reader.read_u8()?;
+    let len = if (TAG_STRING_BASE..TAG_STRING_LONG).contains(&tag) {
+        (tag - TAG_STRING_BASE) as usize
+    } else if tag == TAG_STRING_LONG {
+        reader.decode::<usize>()?
+    } else {
+        return Err(EncoderError::Decode(format!(
+            "Expected String tag ({}..={}), got {}",
+            TAG_STRING_BASE, TAG_STRING_LONG, tag
+        )));
+    };
+    ::core::str::from_utf8(reader.read_slice(len)?).map_err(|e| EncoderError::Decode(e.to_string()))
+}
+
+/// Returns `text` as is, failing if the decode configuration asks for a normalization that a
+/// borrowed `&str` cannot hold.
+fn unnormalized_str(text: &str) -> Result<&str> {
+    match normalize_str(text) {
+        Cow::Borrowed(_) => Ok(text),
+        Cow::Owned(_) => Err(EncoderError::Decode(
+            "String is not normalized; decode it as Cow<str> or String instead of &str".to_string(),
+        )),
+    }
+}
+
+/// Decodes a string as a slice of the input.
+impl<'a> BorrowDecoder<'a> for &'a str {
+    fn decode_borrowed(reader: &mut BorrowReader<'a>) -> Result<Self> {
+        unnormalized_str(borrow_str(reader)?)
+    }
+
+    fn from_string_key(key: &'a str) -> Result<Self> {
+        unnormalized_str(key)
+    }
+}
+
+/// Decodes a string as a slice of the input, or as an owned string if
+/// [`DecodeConfig::normalize_nfc`](crate::DecodeConfig) changes it.
+impl<'a> BorrowDecoder<'a> for Cow<'a, str> {
+    fn decode_borrowed(reader: &mut BorrowReader<'a>) -> Result<Self> {
+        Ok(normalize_str(borrow_str(reader)?))
+    }
+
+    fn from_string_key(key: &'a str) -> Result<Self> {
+        Ok(normalize_str(key))
+    }
+}
+
+/// Decodes bytes (or a string, as `Bytes` does) as a slice of the input.
+impl<'a> BorrowDecoder<'a> for &'a [u8] {
+    fn decode_borrowed(reader: &mut BorrowReader<'a>) -> Result<Self> {
+        let tag = reader.read_u8()?;
+        let len = if tag == TAG_BINARY {
+            reader.decode::<usize>()?
+        } else if (TAG_STRING_BASE..TAG_STRING_LONG).contains(&tag) {
+            (tag - TAG_STRING_BASE) as usize
+        } else if tag == TAG_STRING_LONG {
+            reader.decode::<usize>()?
+        } else {
+            return Err(EncoderError::Decode(format!(
+                "Expected Bytes tag ({} or {}..={}), got {}",
+                TAG_BINARY, TAG_STRING_BASE, TAG_STRING_LONG, tag
+            )));
+        };
+        reader.read_slice(len)
+    }
+}
+
+/// Decodes bytes as a slice of the input, or as a copy if
+/// [`DecodeConfig::copy_out`](crate::DecodeConfig::copy_out) is set.
+impl<'a> BorrowDecoder<'a> for Cow<'a, [u8]> {
+    fn decode_borrowed(reader: &mut BorrowReader<'a>) -> Result<Self> {
+        let bytes = <&[u8]>::decode_borrowed(reader)?;
+        if crate::current_decode_config().copy_out {
+            Ok(Cow::Owned(bytes.to_vec()))
+        } else {
+            Ok(Cow::Borrowed(bytes))
         }
+    }
+}
 
-        Ok(reader.split_to(len))
+impl<'a> BorrowDecoder<'a> for String {
+    fn decode_borrowed(reader: &mut BorrowReader<'a>) -> Result<Self> {
+        reader.decode()
+    }
+
+    fn from_string_key(key: &'a str) -> Result<Self> {
+        Ok(normalize_decoded(key.to_string()))
+    }
+}
+
+/// Implements [`BorrowDecoder`] for types that own their data through their [`Decoder`].
+macro_rules! impl_borrow_decoder_owned {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl<'a> BorrowDecoder<'a> for $ty {
+                fn decode_borrowed(reader: &mut BorrowReader<'a>) -> Result<Self> {
+                    reader.decode()
+                }
+            }
+        )*
+    };
+}
+
+impl_borrow_decoder_owned!(
+    (),
+    bool,
+    u8,
+    u16,
+    u32,
+    u64,
+    u128,
+    usize,
+    i8,
+    i16,
+    i32,
+    i64,
+    i128,
+    isize,
+    char,
+    f32,
+    f64,
+    Bytes,
+);
+
+impl<'a, T: BorrowDecoder<'a>> BorrowDecoder<'a> for Option<T> {
+    fn decode_borrowed(reader: &mut BorrowReader<'a>) -> Result<Self> {
+        match reader.read_u8()? {
+            TAG_NONE => Ok(None),
+            TAG_SOME => Ok(Some(T::decode_borrowed(reader)?)),
+            other => Err(EncoderError::Decode(format!(
+                "Expected Option tag ({} or {}), got {}",
+                TAG_NONE, TAG_SOME, other
+            ))),
+        }
+    }
+}
+
+impl<'a, T: BorrowDecoder<'a>> BorrowDecoder<'a> for Vec<T> {
+    fn decode_borrowed(reader: &mut BorrowReader<'a>) -> Result<Self> {
+        let len = decode_vec_length(&mut reader.rest)?;
+        let mut vec = Vec::with_capacity(collection_capacity::<T>(len, &reader.rest)?);
+        for _ in 0..len {
+            vec.push(T::decode_borrowed(reader)?);
+        }
+        Ok(vec)
+    }
+}
+
+/// Decodes the entries of an encoded map, reading untagged string keys with
+/// [`BorrowDecoder::from_string_key`].
+fn decode_borrowed_map<'a, K, V>(
+    reader: &mut BorrowReader<'a>,
+    mut insert: impl FnMut(K, V),
+) -> Result<()>
+where
+    K: BorrowDecoder<'a>,
+    V: BorrowDecoder<'a>,
+{
+    let (len, string_keys) = read_encoded_map_header(&mut reader.rest)?;
+    collection_capacity::<(K, V)>(len, &reader.rest)?;
+    for _ in 0..len {
+        let key = if string_keys {
+            let len = reader.decode::<usize>()?;
+            let key = ::core::str::from_utf8(reader.read_slice(len)?)
+                .map_err(|e| EncoderError::Decode(format!("Invalid UTF-8 in map key: {}", e)))?;
+            K::from_string_key(key)?
+        } else {
+            K::decode_borrowed(reader)?
+        };
+        insert(key, V::decode_borrowed(reader)?);
+    }
+    Ok(())
+}
+
+#[cfg(feature = "std")]
+impl<'a, K, V> BorrowDecoder<'a> for HashMap<K, V>
+where
+    K: BorrowDecoder<'a> + Eq + std::hash::Hash,
+    V: BorrowDecoder<'a>,
+{
+    fn decode_borrowed(reader: &mut BorrowReader<'a>) -> Result<Self> {
+        let mut map = HashMap::new();
+        decode_borrowed_map(reader, |k, v| {
+            map.insert(k, v);
+        })?;
+        Ok(map)
+    }
+}
+
+impl<'a, K, V> BorrowDecoder<'a> for BTreeMap<K, V>
+where
+    K: BorrowDecoder<'a> + Ord,
+    V: BorrowDecoder<'a>,
+{
+    fn decode_borrowed(reader: &mut BorrowReader<'a>) -> Result<Self> {
+        let mut map = BTreeMap::new();
+        decode_borrowed_map(reader, |k, v| {
+            map.insert(k, v);
+        })?;
+        Ok(map)
     }
 }
 
@@ -2152,6 +3189,91 @@ impl<T: Unpacker> Unpacker for Arc<T> {
     }
 }
 
+// --- Rc<T> ---
+/// Encodes an `Rc<T>` by encoding the inner value.
+impl<T: Encoder> Encoder for Rc<T> {
+    fn encode(&self, writer: &mut BytesMut) -> Result<()> {
+        (**self).encode(writer)
+    }
+
+    fn is_default(&self) -> bool {
+        T::is_default(self)
+    }
+}
+
+impl<T: Packer> Packer for Rc<T> {
+    fn pack(&self, writer: &mut BytesMut) -> Result<()> {
+        (**self).pack(writer)
+    }
+}
+
+/// Decodes an `Rc<T>` by decoding the inner value and wrapping it in an Rc.
+impl<T: Decoder> Decoder for Rc<T> {
+    fn decode(reader: &mut Bytes) -> Result<Self> {
+        Ok(Rc::new(T::decode(reader)?))
+    }
+}
+
+impl<T: Unpacker> Unpacker for Rc<T> {
+    fn unpack(reader: &mut Bytes) -> Result<Self> {
+        Ok(Rc::new(T::unpack(reader)?))
+    }
+}
+
+/// Returns the field or variant ID derived from `name`, as computed by the derive macros for
+/// fields without `#[senax(id = N)]`.
+///
+/// The ID is the CRC-64/ECMA-182 checksum of the name, with 0 (the terminator) mapped to
+/// `u64::MAX`.
+pub const fn field_id_from_name(name: &str) -> u64 {
+    nonzero_id(crc64_update(0, name.as_bytes()))
+}
+
+/// Returns the ID derived from `name` in a type marked `#[senax(id_salt = "...")]`.
+///
+/// The checksum covers the salt, a NUL byte and then the name.
+///
+/// # Example
+/// ```rust
+/// use senax_encoder::core::{field_id_from_name, field_id_from_salted_name};
+///
+/// assert_ne!(field_id_from_salted_name("v2", "id"), field_id_from_name("id"));
+/// ```
+pub const fn field_id_from_salted_name(salt: &str, name: &str) -> u64 {
+    let crc = crc64_update(0, salt.as_bytes());
+    let crc = crc64_update(crc, &[0]);
+    nonzero_id(crc64_update(crc, name.as_bytes()))
+}
+
+/// Feeds `bytes` into a CRC-64/ECMA-182 checksum.
+pub(crate) const fn crc64_update(mut crc: u64, bytes: &[u8]) -> u64 {
+    const POLY: u64 = 0x42F0_E1EB_A9EA_3693;
+    let mut i = 0;
+    while i < bytes.len() {
+        crc ^= (bytes[i] as u64) << 56;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & (1 << 63) != 0 {
+                (crc << 1) ^ POLY
+            } else {
+                crc << 1
+            };
+            bit += 1;
+        }
+        i += 1;
+    }
+    crc
+}
+
+/// Maps the reserved terminator ID 0 to `u64::MAX`.
+const fn nonzero_id(crc: u64) -> u64 {
+    if crc == 0 {
+        u64::MAX
+    } else {
+        crc
+    }
+}
+
 /// Writes a `u64` in little-endian format without a tag.
 ///
 /// This is used internally for struct/enum field/variant IDs.
@@ -2159,7 +3281,7 @@ pub fn write_field_id_optimized(writer: &mut BytesMut, field_id: u64) -> Result<
     if field_id == 0 {
         // Terminator
         writer.put_u8(0);
-    } else if (1..=250).contains(&field_id) {
+    } else if (1..=MAX_SHORT_FIELD_ID).contains(&field_id) {
         // Small field ID: write as u8
         writer.put_u8(field_id as u8);
     } else {
@@ -2175,7 +3297,7 @@ pub fn write_field_id_optimized(writer: &mut BytesMut, field_id: u64) -> Result<
 /// Returns Ok(0) for terminator, Ok(field_id) for valid field ID.
 pub fn read_field_id_optimized(reader: &mut Bytes) -> Result<u64> {
     if reader.remaining() == 0 {
-        return Err(EncoderError::InsufficientData);
+        return Err(EncoderError::eof(1, 0));
     }
 
     let first_byte = reader.get_u8();
@@ -2186,17 +3308,82 @@ pub fn read_field_id_optimized(reader: &mut Bytes) -> Result<u64> {
     } else if first_byte == 255 {
         // Large field ID follows
         if reader.remaining() < 8 {
-            return Err(EncoderError::InsufficientData);
+            return Err(EncoderError::eof(8, reader.remaining()));
         }
         Ok(reader.get_u64_le())
+    } else if first_byte == FIELD_NAME_MARKER {
+        // Field name follows; its ID is the hash of the name
+        read_field_name(reader).map(|name| field_id_from_name(&name))
     } else {
         // Small field ID
         Ok(first_byte as u64)
     }
 }
 
+/// Reads a field ID like [`read_field_id_optimized`], resolving a field name through
+/// `aliases` before hashing it.
+///
+/// Used by derived decoders for fields and variants whose ID is not the hash of their name
+/// (explicit `id`, `id_salt`, pinned IDs), so payloads written by
+/// [`encode_named`](crate::self_describing::encode_named) still match them.
+pub fn read_field_id_aliased(reader: &mut Bytes, aliases: &[(&str, u64)]) -> Result<u64> {
+    if reader.first() != Some(&FIELD_NAME_MARKER) {
+        return read_field_id_optimized(reader);
+    }
+    reader.advance(1);
+    let name = read_field_name(reader)?;
+    Ok(aliases
+        .iter()
+        .find(|(alias, _)| *alias == name)
+        .map_or_else(|| field_id_from_name(&name), |(_, id)| *id))
+}
+
+/// Reads the name following a [`FIELD_NAME_MARKER`].
+#[cold]
+#[inline(never)]
+pub(crate) fn read_field_name(reader: &mut Bytes) -> Result<String> {
+    String::decode(reader)
+}
+
+/// Fails with [`StructDecodeError::OutOfRange`] if a decoded field lies outside `min..=max`.
+///
+/// Called by derived `Decode` implementations for `#[senax(min = X, max = Y)]` fields. Values
+/// that do not compare with a bound (NaN) are rejected.
+pub fn check_field_range<T: PartialOrd + ::core::fmt::Display>(
+    value: &T,
+    min: Option<T>,
+    max: Option<T>,
+    field: &'static str,
+    struct_name: &'static str,
+) -> Result<()> {
+    use ::core::cmp::Ordering;
+    let below = min
+        .as_ref()
+        .is_some_and(|min| matches!(value.partial_cmp(min), None | Some(Ordering::Less)));
+    let above = max
+        .as_ref()
+        .is_some_and(|max| matches!(value.partial_cmp(max), None | Some(Ordering::Greater)));
+    if below || above {
+        let range = format!(
+            "{}..{}",
+            min.as_ref().map(ToString::to_string).unwrap_or_default(),
+            max.as_ref()
+                .map(|max| format!("={}", max))
+                .unwrap_or_default()
+        );
+        return Err(StructDecodeError::OutOfRange {
+            field,
+            struct_name,
+            value: value.to_string().into(),
+            range: range.into(),
+        }
+        .into());
+    }
+    Ok(())
+}
+
 /// Implementation for references - delegates to the referenced value
-impl<T: Encoder> Encoder for &T {
+impl<T: Encoder + ?Sized> Encoder for &T {
     fn encode(&self, writer: &mut BytesMut) -> Result<()> {
         (*self).encode(writer)
     }
@@ -2206,7 +3393,7 @@ impl<T: Encoder> Encoder for &T {
     }
 }
 
-impl<T: Packer> Packer for &T {
+impl<T: Packer + ?Sized> Packer for &T {
     fn pack(&self, writer: &mut BytesMut) -> Result<()> {
         (*self).pack(writer)
     }
@@ -2246,8 +3433,7 @@ impl<T: Unpacker> Unpacker for Box<T> {
 /// Encodes the length for array/vec/set format.
 #[inline(never)]
 pub(crate) fn encode_vec_length(len: usize, writer: &mut BytesMut) -> Result<()> {
-    let max_short = (TAG_ARRAY_VEC_SET_LONG - TAG_ARRAY_VEC_SET_BASE - 1) as usize;
-    if len <= max_short {
+    if len <= MAX_SHORT_ARRAY_LEN {
         let tag = TAG_ARRAY_VEC_SET_BASE + len as u8;
         writer.put_u8(tag);
     } else {
@@ -2257,17 +3443,36 @@ pub(crate) fn encode_vec_length(len: usize, writer: &mut BytesMut) -> Result<()>
     Ok(())
 }
 
+/// Largest preallocation, in bytes, made for a collection before its elements are decoded.
+const MAX_PREALLOC_BYTES: usize = 1 << 20;
+
+/// Checks a decoded collection length against the decode configuration and returns how many
+/// elements of `T` to preallocate.
+///
+/// The length comes from the input and is not trusted: fails past
+/// [`DecodeConfig::max_collection_len`](crate::DecodeConfig::max_collection_len), charges
+/// `len` elements to [`DecodeConfig::max_alloc_bytes`](crate::DecodeConfig::max_alloc_bytes),
+/// and caps the preallocation at one element per remaining input byte (every element takes at
+/// least one) and at `MAX_PREALLOC_BYTES`. The collection grows past that as elements decode.
+#[inline]
+pub(crate) fn collection_capacity<T>(len: usize, reader: &Bytes) -> Result<usize> {
+    crate::config::check_collection_len(len)?;
+    let size = ::core::mem::size_of::<T>();
+    crate::config::charge_alloc(len.saturating_mul(size))?;
+    Ok(len
+        .min(reader.remaining())
+        .min(MAX_PREALLOC_BYTES / size.max(1)))
+}
+
 /// Decodes the length for array/vec/set format.
 #[inline(never)]
 pub(crate) fn decode_vec_length(reader: &mut Bytes) -> Result<usize> {
     if reader.remaining() == 0 {
-        return Err(EncoderError::InsufficientData);
+        return Err(EncoderError::eof(1, 0));
     }
     let tag = reader.get_u8();
-    if (TAG_ARRAY_VEC_SET_BASE..TAG_ARRAY_VEC_SET_LONG).contains(&tag) {
-        Ok((tag - TAG_ARRAY_VEC_SET_BASE) as usize)
-    } else if tag == TAG_ARRAY_VEC_SET_LONG {
-        usize::decode(reader)
+    if (TAG_ARRAY_VEC_SET_BASE..=TAG_ARRAY_VEC_SET_LONG).contains(&tag) {
+        array_length_after_tag(tag, reader)
     } else {
         Err(EncoderError::Decode(format!(
             "Expected Vec tag ({}..={}), got {}",
@@ -2276,6 +3481,57 @@ pub(crate) fn decode_vec_length(reader: &mut Bytes) -> Result<usize> {
     }
 }
 
+/// Returns the length encoded by an array/`Vec` tag, reading the long form if needed.
+#[inline]
+fn array_length_after_tag(tag: u8, reader: &mut Bytes) -> Result<usize> {
+    if tag == TAG_ARRAY_VEC_SET_LONG {
+        usize::decode(reader)
+    } else {
+        Ok((tag - TAG_ARRAY_VEC_SET_BASE) as usize)
+    }
+}
+
+/// Reads the header of a fixed-size array and checks that it has `expected_len` elements.
+///
+/// With [`DecodeConfig::lenient_tuples`](crate::DecodeConfig::lenient_tuples), a tuple header
+/// with the same arity is accepted as well.
+pub(crate) fn read_array_header(reader: &mut Bytes, expected_len: usize) -> Result<()> {
+    let len = if reader.first() == Some(&TAG_TUPLE) && crate::current_decode_config().lenient_tuples
+    {
+        reader.advance(1);
+        usize::decode(reader)?
+    } else {
+        decode_vec_length(reader)?
+    };
+    if len != expected_len {
+        return Err(EncoderError::Decode(format!(
+            "Array length mismatch: expected {}, got {}",
+            expected_len, len
+        )));
+    }
+    Ok(())
+}
+
+/// Reads and validates the tag of an encoded map (`TAG_MAP` or `TAG_MAP_STRING_KEYS`), then
+/// returns the map length and whether the keys are untagged strings.
+#[inline(never)]
+pub(crate) fn read_encoded_map_header(reader: &mut Bytes) -> Result<(usize, bool)> {
+    if reader.remaining() == 0 {
+        return Err(EncoderError::eof(1, 0));
+    }
+    let string_keys = match reader.get_u8() {
+        TAG_MAP => false,
+        TAG_MAP_STRING_KEYS => true,
+        tag => {
+            return Err(EncoderError::Decode(format!(
+                "Expected Map tag ({} or {}), got {}",
+                TAG_MAP, TAG_MAP_STRING_KEYS, tag
+            )))
+        }
+    };
+    Ok((usize::decode(reader)?, string_keys))
+}
+
 /// Reads and validates TAG_MAP, then returns the map length.
 ///
 /// This helper function is used by all map-like types (HashMap, BTreeMap, etc.)
@@ -2283,7 +3539,7 @@ pub(crate) fn decode_vec_length(reader: &mut Bytes) -> Result<usize> {
 #[inline(never)]
 pub(crate) fn read_map_header(reader: &mut Bytes) -> Result<usize> {
     if reader.remaining() == 0 {
-        return Err(EncoderError::InsufficientData);
+        return Err(EncoderError::eof(1, 0));
     }
     let tag = reader.get_u8();
     if tag != TAG_MAP {
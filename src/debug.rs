@@ -0,0 +1,872 @@
+//! Debugging helpers for encoded payloads.
+//!
+//! [`diff`] compares two payloads structurally, without their Rust types, and lists where they
+//! differ: fields present in only one of them, values that changed and values whose wire type
+//! changed. It is meant for comparing a message that works with one that does not.
+//!
+//! Both payloads are decoded into [`Value`]s and walked side by side:
+//!
+//! * struct fields (and fields of struct-like enum variants) are matched by field ID;
+//! * list, tuple and tuple struct elements are matched by position;
+//! * map entries are matched by key;
+//! * `Some` is unwrapped, so a changed field inside an optional struct is reported as such;
+//! * different variants of an enum are reported as one changed value.
+//!
+//! Paths use the [`query`](crate::query) syntax: a field name where either payload recorded it
+//! (self-describing payloads and named documents), otherwise the field ID, and `[i]` for
+//! elements. Map entries are written as `[key]`. The root is the empty path.
+//!
+//! [`inspect`] dumps a single payload instead, one line per value with its offset, its bytes,
+//! its wire type and the field or variant ID it was written with, so a payload can be read by
+//! hand without its Rust types. [`inspect_tree`] returns the same information as a tree of
+//! [`InspectNode`]s. Reading stops at the first malformed value, which is reported in place.
+
+use crate::core::{
+    decode_vec_length, field_id_from_name, read_field_id_optimized, read_string_key,
+    TAG_ARRAY_VEC_SET_BASE, TAG_ARRAY_VEC_SET_LONG, TAG_BINARY, TAG_CHRONO_DATETIME,
+    TAG_CHRONO_NAIVE_DATE, TAG_CHRONO_NAIVE_DATETIME, TAG_CHRONO_NAIVE_TIME, TAG_DECIMAL,
+    TAG_DURATION, TAG_ENUM, TAG_ENUM_NAMED, TAG_ENUM_UNNAMED, TAG_F32, TAG_F64, TAG_JSON_ARRAY,
+    TAG_JSON_BOOL, TAG_JSON_NULL, TAG_JSON_NUMBER, TAG_JSON_OBJECT, TAG_JSON_STRING, TAG_MAP,
+    TAG_MAP_FLAT_KEYS, TAG_MAP_ID_KEYS, TAG_MAP_STRING_KEYS, TAG_NEGATIVE, TAG_NONE, TAG_SOME,
+    TAG_STRING_BASE, TAG_STRING_LONG, TAG_STRUCT_NAMED, TAG_STRUCT_UNIT, TAG_STRUCT_UNNAMED,
+    TAG_SYSTEM_TIME, TAG_TUPLE, TAG_U128, TAG_U8, TAG_U8_127, TAG_UUID, TAG_ZERO,
+};
+use crate::self_describing::{decode_self_describing, NAMED_MAGIC, SELF_DESCRIBING_MAGIC};
+use crate::value::{read_id, read_value, Field, Value, VariantFields, MAX_VALUE_DEPTH};
+use crate::{decode, Decoder, EncoderError, Result, ENCODE_MAGIC};
+use bytes::{Buf, Bytes};
+use std::fmt;
+
+/// How a value differs between the two payloads.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DifferenceKind {
+    /// The value is only in the second payload.
+    Added(Value),
+    /// The value is only in the first payload.
+    Removed(Value),
+    /// The value has the same wire type in both payloads but a different content.
+    Changed {
+        /// The value in the first payload.
+        a: Value,
+        /// The value in the second payload.
+        b: Value,
+    },
+    /// The value was written with a different wire type, e.g. a string instead of an integer.
+    TypeChanged {
+        /// The value in the first payload.
+        a: Value,
+        /// The value in the second payload.
+        b: Value,
+    },
+}
+
+/// One difference found by [`diff`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Difference {
+    /// Where the values differ, e.g. `items[2].name`; empty for the root.
+    pub path: String,
+    /// What differs.
+    pub kind: DifferenceKind,
+}
+
+/// The differences between two payloads, in the order of the first payload.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PayloadDiff {
+    differences: Vec<Difference>,
+}
+
+impl PayloadDiff {
+    /// Returns `true` if the payloads hold the same values.
+    pub fn is_empty(&self) -> bool {
+        self.differences.is_empty()
+    }
+
+    /// Number of differences.
+    pub fn len(&self) -> usize {
+        self.differences.len()
+    }
+
+    /// Iterates over the differences.
+    pub fn iter(&self) -> impl Iterator<Item = &Difference> {
+        self.differences.iter()
+    }
+
+    /// Returns the difference at `path`, if any.
+    pub fn get(&self, path: &str) -> Option<&DifferenceKind> {
+        self.differences
+            .iter()
+            .find(|d| d.path == path)
+            .map(|d| &d.kind)
+    }
+}
+
+/// One line per difference: `+` added, `-` removed, `~` changed and `!` type changed.
+impl fmt::Display for PayloadDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for difference in &self.differences {
+            let path = if difference.path.is_empty() {
+                "(root)"
+            } else {
+                &difference.path
+            };
+            match &difference.kind {
+                DifferenceKind::Added(value) => writeln!(f, "+ {}: {:?}", path, value)?,
+                DifferenceKind::Removed(value) => writeln!(f, "- {}: {:?}", path, value)?,
+                DifferenceKind::Changed { a, b } => writeln!(f, "~ {}: {:?} -> {:?}", path, a, b)?,
+                DifferenceKind::TypeChanged { a, b } => writeln!(
+                    f,
+                    "! {}: {} {:?} -> {} {:?}",
+                    path,
+                    wire_type(a),
+                    a,
+                    wire_type(b),
+                    b
+                )?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Compares two payloads (with magic number) structurally.
+///
+/// Accepts payloads written by [`encode`](crate::encode),
+/// [`encode_self_describing`](crate::self_describing::encode_self_describing) and
+/// [`encode_named`](crate::self_describing::encode_named), in any combination. Fails if either
+/// payload cannot be decoded.
+///
+/// # Example
+/// ```rust
+/// use senax_encoder::debug::{diff, DifferenceKind};
+/// use senax_encoder::self_describing::encode_self_describing;
+/// use senax_encoder::{encode, Encode, Value};
+///
+/// #[derive(Encode)]
+/// struct Item { name: String, quantity: u32 }
+///
+/// #[derive(Encode)]
+/// struct Order { id: u64, items: Vec<Item>, note: Option<String> }
+///
+/// let item = |quantity| Item { name: "apple".into(), quantity };
+/// let good = Order { id: 9, items: vec![item(3)], note: None };
+/// let bad = Order { id: 9, items: vec![item(0)], note: Some("x".into()) };
+///
+/// let good = encode_self_describing(&good).unwrap();
+/// let changes = diff(&good, &encode_self_describing(&bad).unwrap()).unwrap();
+/// assert_eq!(changes.len(), 2);
+/// assert_eq!(
+///     changes.get("items[0].quantity"),
+///     Some(&DifferenceKind::Changed { a: Value::UInt(3), b: Value::UInt(0) })
+/// );
+/// assert_eq!(changes.get("note"), Some(&DifferenceKind::Added(Value::String("x".into()))));
+/// println!("{}", changes);
+/// // ~ items[0].quantity: UInt(3) -> UInt(0)
+/// // + note: String("x")
+///
+/// // Names recorded in either payload are used in paths
+/// let changes = diff(&good, &encode(&bad).unwrap()).unwrap();
+/// assert!(changes.get("items[0].quantity").is_some());
+/// ```
+pub fn diff(bytes_a: &[u8], bytes_b: &[u8]) -> Result<PayloadDiff> {
+    let a = read_payload(bytes_a)?;
+    let b = read_payload(bytes_b)?;
+    let mut differ = Differ::default();
+    differ.compare(&mut String::new(), &a, &b);
+    Ok(PayloadDiff {
+        differences: differ.differences,
+    })
+}
+
+fn read_payload(bytes: &[u8]) -> Result<Value> {
+    if bytes.len() < 2 {
+        return Err(EncoderError::eof(2, bytes.len()));
+    }
+    let mut reader = Bytes::copy_from_slice(bytes);
+    match u16::from_le_bytes([bytes[0], bytes[1]]) {
+        ENCODE_MAGIC => decode(&mut reader),
+        SELF_DESCRIBING_MAGIC | NAMED_MAGIC => decode_self_describing(&mut reader),
+        magic => Err(EncoderError::Decode(format!(
+            "Invalid encode magic number: expected 0x{:04X}, 0x{:04X} or 0x{:04X}, got 0x{:04X}",
+            ENCODE_MAGIC, SELF_DESCRIBING_MAGIC, NAMED_MAGIC, magic
+        ))),
+    }
+}
+
+#[derive(Default)]
+struct Differ {
+    differences: Vec<Difference>,
+}
+
+impl Differ {
+    fn push(&mut self, path: &str, kind: DifferenceKind) {
+        self.differences.push(Difference {
+            path: path.to_string(),
+            kind,
+        });
+    }
+
+    fn compare(&mut self, path: &mut String, a: &Value, b: &Value) {
+        if a == b {
+            return;
+        }
+        match (a, b) {
+            (Value::Some(a), Value::Some(b)) => self.compare(path, a, b),
+            (Value::Struct(a), Value::Struct(b)) => self.compare_fields(path, a, b),
+            (Value::List(a), Value::List(b))
+            | (Value::Tuple(a), Value::Tuple(b))
+            | (Value::TupleStruct(a), Value::TupleStruct(b)) => self.compare_elements(path, a, b),
+            (Value::Map(a), Value::Map(b)) => self.compare_entries(path, a, b),
+            (Value::Enum(va), Value::Enum(vb)) if va.id == vb.id => {
+                match (&va.fields, &vb.fields) {
+                    (VariantFields::Named(a), VariantFields::Named(b)) => {
+                        self.compare_fields(path, a, b)
+                    }
+                    (VariantFields::Unnamed(a), VariantFields::Unnamed(b)) => {
+                        self.compare_elements(path, a, b)
+                    }
+                    // Equal apart from recorded names
+                    (VariantFields::Unit, VariantFields::Unit) => {}
+                    _ => self.push(path, changed(a, b)),
+                }
+            }
+            _ => self.push(path, changed(a, b)),
+        }
+    }
+
+    fn compare_fields(&mut self, path: &mut String, a: &[Field], b: &[Field]) {
+        for field in a {
+            let other = b.iter().find(|other| other.id == field.id);
+            let segment = field_segment(path, field, other);
+            self.nested(path, &segment, |differ, path| match other {
+                Some(other) => differ.compare(path, &field.value, &other.value),
+                None => differ.push(path, DifferenceKind::Removed(field.value.clone())),
+            });
+        }
+        for field in b.iter().filter(|f| !a.iter().any(|other| other.id == f.id)) {
+            let segment = field_segment(path, field, None);
+            self.nested(path, &segment, |differ, path| {
+                differ.push(path, DifferenceKind::Added(field.value.clone()))
+            });
+        }
+    }
+
+    fn compare_elements(&mut self, path: &mut String, a: &[Value], b: &[Value]) {
+        for i in 0..a.len().max(b.len()) {
+            self.nested(path, &format!("[{}]", i), |differ, path| {
+                match (a.get(i), b.get(i)) {
+                    (Some(a), Some(b)) => differ.compare(path, a, b),
+                    (Some(a), None) => differ.push(path, DifferenceKind::Removed(a.clone())),
+                    (None, Some(b)) => differ.push(path, DifferenceKind::Added(b.clone())),
+                    (None, None) => {}
+                }
+            });
+        }
+    }
+
+    fn compare_entries(&mut self, path: &mut String, a: &[(Value, Value)], b: &[(Value, Value)]) {
+        for (key, value) in a {
+            self.nested(path, &key_segment(key), |differ, path| {
+                match b.iter().find(|(other, _)| other == key) {
+                    Some((_, other)) => differ.compare(path, value, other),
+                    None => differ.push(path, DifferenceKind::Removed(value.clone())),
+                }
+            });
+        }
+        for (key, value) in b
+            .iter()
+            .filter(|(k, _)| !a.iter().any(|(other, _)| other == k))
+        {
+            self.nested(path, &key_segment(key), |differ, path| {
+                differ.push(path, DifferenceKind::Added(value.clone()))
+            });
+        }
+    }
+
+    /// Runs `f` with `segment` appended to `path`.
+    fn nested(&mut self, path: &mut String, segment: &str, f: impl FnOnce(&mut Self, &mut String)) {
+        let len = path.len();
+        path.push_str(segment);
+        f(self, path);
+        path.truncate(len);
+    }
+}
+
+/// The field name recorded by either payload, or the field ID.
+fn field_segment(path: &str, field: &Field, other: Option<&Field>) -> String {
+    let separator = if path.is_empty() { "" } else { "." };
+    match field.name.as_ref().or(other.and_then(|f| f.name.as_ref())) {
+        Some(name) => format!("{}{}", separator, name),
+        None => format!("{}{}", separator, field.id),
+    }
+}
+
+fn key_segment(key: &Value) -> String {
+    match key {
+        Value::String(key) => format!("[{:?}]", key),
+        Value::UInt(key) => format!("[{}]", key),
+        Value::Int(key) => format!("[{}]", key),
+        key => format!("[{:?}]", key),
+    }
+}
+
+fn changed(a: &Value, b: &Value) -> DifferenceKind {
+    let (a, b) = (a.clone(), b.clone());
+    if wire_type(&a) == wire_type(&b) {
+        DifferenceKind::Changed { a, b }
+    } else {
+        DifferenceKind::TypeChanged { a, b }
+    }
+}
+
+/// The wire type of a value; `Some` and `None` count as the same type.
+fn wire_type(value: &Value) -> &'static str {
+    match value {
+        Value::None | Value::Some(_) => "option",
+        Value::UInt(_) | Value::Int(_) => "integer",
+        Value::Float(_) => "float",
+        Value::String(_) => "string",
+        Value::Binary(_) => "binary",
+        Value::List(_) => "list",
+        Value::Tuple(_) => "tuple",
+        Value::Map(_) => "map",
+        Value::UnitStruct => "unit struct",
+        Value::Struct(_) => "struct",
+        Value::TupleStruct(_) => "tuple struct",
+        Value::Enum(_) => "enum",
+        Value::Uuid(_) => "uuid",
+        Value::Extension { .. } => "extension",
+    }
+}
+
+/// A field or variant ID, with the name the payload recorded for it, if any.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InspectId {
+    /// The ID; the hash of the name for fields written by name.
+    pub id: u64,
+    /// The recorded name (self-describing payloads and named documents).
+    pub name: Option<String>,
+}
+
+/// One value of a payload read by [`inspect_tree`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct InspectNode {
+    /// Offset of the first byte, which is the field ID for struct fields.
+    pub offset: usize,
+    /// Number of bytes, including the field ID and the children.
+    pub len: usize,
+    /// The bytes before the first child: field ID, tag and lengths for containers, the whole
+    /// encoding for scalars.
+    pub header: Bytes,
+    /// The type tag; `None` for untagged parts such as the magic number and string map keys.
+    pub tag: Option<u8>,
+    /// The wire type, e.g. `"struct"`, `"string"` or `"datetime"`.
+    pub kind: &'static str,
+    /// The field the value was written as, inside structs and struct-like variants.
+    pub field: Option<InspectId>,
+    /// The variant, for enums.
+    pub variant: Option<InspectId>,
+    /// The number of elements (entries for maps), or the byte length of strings and binaries.
+    pub count: Option<usize>,
+    /// The value of scalars.
+    pub value: Option<Value>,
+    /// The nested values in wire order. Map entries are a key node followed by a value node.
+    pub children: Vec<InspectNode>,
+    /// Why reading stopped at this node.
+    pub error: Option<String>,
+}
+
+impl InspectNode {
+    fn new(offset: usize, tag: Option<u8>, kind: &'static str) -> Self {
+        Self {
+            offset,
+            len: 0,
+            header: Bytes::new(),
+            tag,
+            kind,
+            field: None,
+            variant: None,
+            count: None,
+            value: None,
+            children: Vec::new(),
+            error: None,
+        }
+    }
+
+    /// Returns the node where reading stopped, if the payload is malformed.
+    pub fn first_error(&self) -> Option<&InspectNode> {
+        self.children
+            .iter()
+            .find_map(Self::first_error)
+            .or(self.error.as_ref().map(|_| self))
+    }
+
+    /// Returns `true` if the whole payload was read.
+    pub fn is_complete(&self) -> bool {
+        self.first_error().is_none()
+    }
+
+    fn render(&self, f: &mut fmt::Formatter<'_>, depth: usize, label: &str) -> fmt::Result {
+        let indent = "  ".repeat(depth);
+        let mut note = format!("{}{}", label, self.kind);
+        if let Some(variant) = &self.variant {
+            note.push(' ');
+            note.push_str(&id_label(variant));
+        }
+        if let Some(count) = self.count {
+            note.push_str(&format!(" ({})", count));
+        }
+        if let Some(value) = &self.value {
+            note.push_str(&format!(" = {:?}", value));
+        }
+        writeln!(
+            f,
+            "{:06x}  {:<30} {}{}",
+            self.offset,
+            hex(&self.header),
+            indent,
+            note
+        )?;
+
+        let is_map = matches!(
+            self.tag,
+            Some(
+                TAG_MAP
+                    | TAG_MAP_FLAT_KEYS
+                    | TAG_MAP_STRING_KEYS
+                    | TAG_MAP_ID_KEYS
+                    | TAG_JSON_OBJECT
+            )
+        );
+        let is_list = matches!(
+            self.tag,
+            Some(
+                TAG_ARRAY_VEC_SET_BASE
+                    ..=TAG_ARRAY_VEC_SET_LONG
+                        | TAG_TUPLE
+                        | TAG_STRUCT_UNNAMED
+                        | TAG_ENUM_UNNAMED
+                        | TAG_JSON_ARRAY
+            )
+        );
+        for (i, child) in self.children.iter().enumerate() {
+            let label = match &child.field {
+                Some(field) => format!("{}: ", id_label(field)),
+                None if is_map && i % 2 == 0 => "key: ".to_string(),
+                None if is_map => "value: ".to_string(),
+                None if is_list => format!("[{}] ", i),
+                None => String::new(),
+            };
+            child.render(f, depth + 1, &label)?;
+        }
+
+        let end = self.offset + self.len;
+        match &self.error {
+            Some(error) => writeln!(f, "{:06x}  {:<30} {}  error: {}", end, "", indent, error),
+            None if matches!(self.tag, Some(TAG_STRUCT_NAMED | TAG_ENUM_NAMED))
+                && self.is_complete() =>
+            {
+                writeln!(f, "{:06x}  {:<30} {}end", end - 1, "00", indent)
+            }
+            None => Ok(()),
+        }
+    }
+}
+
+/// One line per value: offset, bytes in hex and meaning, indented by nesting depth.
+impl fmt::Display for InspectNode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.render(f, 0, "")
+    }
+}
+
+/// The name and ID; the ID is left out when it is the hash of the name.
+fn id_label(id: &InspectId) -> String {
+    match &id.name {
+        Some(name) if id.id == field_id_from_name(name) => format!("`{}`", name),
+        Some(name) => format!("`{}` (id {})", name, id.id),
+        None => format!("id {}", id.id),
+    }
+}
+
+/// Hex bytes, shortened to the first eight.
+fn hex(bytes: &[u8]) -> String {
+    let mut hex = bytes
+        .iter()
+        .take(8)
+        .map(|b| format!("{:02x}", b))
+        .collect::<Vec<_>>()
+        .join(" ");
+    if bytes.len() > 8 {
+        hex.push_str(" ..");
+    }
+    hex
+}
+
+/// Dumps a payload as an annotated tree, one line per value.
+///
+/// Accepts payloads with the magic number of [`encode`](crate::encode),
+/// [`encode_self_describing`](crate::self_describing::encode_self_describing) or
+/// [`encode_named`](crate::self_describing::encode_named); anything else is read as a single
+/// value written by [`Encoder::encode`](crate::Encoder::encode). Never fails: if the payload is
+/// malformed, the dump ends with an `error:` line where reading stopped.
+///
+/// # Example
+/// ```rust
+/// use senax_encoder::{encode, inspect, Encode};
+///
+/// #[derive(Encode)]
+/// struct Item { #[senax(id = 1)] name: String, #[senax(id = 2)] tags: Vec<u8> }
+///
+/// let bytes = encode(&Item { name: "apple".into(), tags: vec![7] }).unwrap();
+/// println!("{}", inspect(&bytes));
+/// // 000000  5a a5                          encode payload
+/// // 000002  b7                               struct
+/// // 000003  01 90 61 70 70 6c 65               id 1: string (5) = String("apple")
+/// // 00000a  02 bd                              id 2: list (1)
+/// // 00000c  07                                   [0] uint = UInt(7)
+/// // 00000d  00                               end
+/// assert!(inspect(&bytes).contains("id 1: string (5) = String(\"apple\")"));
+/// ```
+pub fn inspect(bytes: &[u8]) -> String {
+    inspect_tree(bytes).to_string()
+}
+
+/// Reads a payload into a tree of [`InspectNode`]s, as dumped by [`inspect`].
+///
+/// For payloads with a magic number, the root spans the whole payload and its children are the
+/// name table of self-describing payloads and the value. Otherwise the root is the value.
+///
+/// # Example
+/// ```rust
+/// use senax_encoder::{encode, inspect_tree, Encode, Value};
+///
+/// #[derive(Encode)]
+/// struct Point { #[senax(id = 1)] x: i32, #[senax(id = 2)] y: i32 }
+///
+/// let bytes = encode(&Point { x: 3, y: -4 }).unwrap();
+/// let root = inspect_tree(&bytes);
+/// let point = &root.children[0];
+/// assert_eq!(point.kind, "struct");
+/// assert_eq!(point.children[1].field.as_ref().unwrap().id, 2);
+/// assert_eq!(point.children[1].value, Some(Value::Int(-4)));
+///
+/// assert!(!inspect_tree(&bytes[..bytes.len() - 1]).is_complete());
+/// ```
+pub fn inspect_tree(bytes: &[u8]) -> InspectNode {
+    let mut inspector = Inspector {
+        data: Bytes::copy_from_slice(bytes),
+        names: None,
+    };
+    let mut reader = inspector.data.clone();
+    let magic = (bytes.len() >= 2).then(|| u16::from_le_bytes([bytes[0], bytes[1]]));
+    let kind = match magic {
+        Some(ENCODE_MAGIC) => "encode payload",
+        Some(SELF_DESCRIBING_MAGIC) => "self-describing payload",
+        Some(NAMED_MAGIC) => "named document",
+        _ => {
+            let (mut root, complete) = inspector.value(&mut reader, 0, 1);
+            if complete {
+                inspector.check_end(&mut root, &reader);
+            }
+            return root;
+        }
+    };
+
+    let mut root = InspectNode::new(0, None, kind);
+    reader.advance(2);
+    let step = inspector.payload(&mut root, &mut reader);
+    let complete = step.is_ok();
+    inspector.finish(&mut root, &reader, step);
+    if complete {
+        inspector.check_end(&mut root, &reader);
+    }
+    root
+}
+
+/// Where reading failed: at the node being read, or in one of its children, which already
+/// recorded the error.
+enum Failure {
+    Here(EncoderError),
+    InChild,
+}
+
+impl From<EncoderError> for Failure {
+    fn from(error: EncoderError) -> Self {
+        Failure::Here(error)
+    }
+}
+
+type Step = std::result::Result<(), Failure>;
+
+struct Inspector {
+    data: Bytes,
+    names: Option<Vec<String>>,
+}
+
+impl Inspector {
+    fn offset(&self, reader: &Bytes) -> usize {
+        self.data.len() - reader.remaining()
+    }
+
+    /// Records the error, if any, and the extent of a node read up to `reader`.
+    fn finish(&self, node: &mut InspectNode, reader: &Bytes, step: Step) {
+        if let Err(Failure::Here(error)) = step {
+            node.error = Some(error.to_string());
+        }
+        let end = self.offset(reader);
+        let header_end = node.children.first().map_or(end, |child| child.offset);
+        node.header = self.data.slice(node.offset..header_end);
+        node.len = end - node.offset;
+    }
+
+    fn check_end(&self, root: &mut InspectNode, reader: &Bytes) {
+        if reader.has_remaining() {
+            root.error = Some(format!("{} trailing bytes", reader.remaining()));
+        }
+    }
+
+    /// Reads the name table of a self-describing payload, then the value.
+    fn payload(&mut self, root: &mut InspectNode, reader: &mut Bytes) -> Step {
+        if root.kind == "self-describing payload" {
+            let mut table = InspectNode::new(self.offset(reader), None, "name table");
+            let step = self.name_table(&mut table, reader);
+            let complete = step.is_ok();
+            self.finish(&mut table, reader, step);
+            push(root, table, complete)?;
+        }
+        let (value, complete) = self.value(reader, self.offset(reader), 1);
+        push(root, value, complete)
+    }
+
+    fn name_table(&mut self, table: &mut InspectNode, reader: &mut Bytes) -> Step {
+        let count = usize::decode(reader)?;
+        table.count = Some(count);
+        self.children(table, reader, count, 1)?;
+        let names = table
+            .children
+            .iter()
+            .map(|name| match &name.value {
+                Some(Value::String(name)) => Ok(name.clone()),
+                _ => Err(EncoderError::Decode(
+                    "Name table entries must be strings".to_string(),
+                )),
+            })
+            .collect::<Result<_>>()?;
+        self.names = Some(names);
+        Ok(())
+    }
+
+    /// Reads one value. `start` is before the field ID for struct fields. Returns the node and
+    /// whether it was read completely.
+    fn value(&self, reader: &mut Bytes, start: usize, depth: usize) -> (InspectNode, bool) {
+        let mut node = InspectNode::new(start, reader.first().copied(), "value");
+        let step = self.read(&mut node, reader, depth);
+        let complete = step.is_ok();
+        self.finish(&mut node, reader, step);
+        (node, complete)
+    }
+
+    fn read(&self, node: &mut InspectNode, reader: &mut Bytes, depth: usize) -> Step {
+        if depth > MAX_VALUE_DEPTH {
+            return Err(
+                EncoderError::Decode(format!("Nesting depth exceeds {}", MAX_VALUE_DEPTH)).into(),
+            );
+        }
+        let tag = *reader.first().ok_or(EncoderError::eof(1, 0))?;
+        node.kind = kind_of(tag);
+        match tag {
+            TAG_ARRAY_VEC_SET_BASE..=TAG_ARRAY_VEC_SET_LONG => {
+                let len = decode_vec_length(reader)?;
+                node.count = Some(len);
+                self.children(node, reader, len, depth)
+            }
+            TAG_TUPLE | TAG_STRUCT_UNNAMED | TAG_JSON_ARRAY => {
+                reader.advance(1);
+                let len = usize::decode(reader)?;
+                node.count = Some(len);
+                self.children(node, reader, len, depth)
+            }
+            TAG_MAP | TAG_JSON_OBJECT => {
+                reader.advance(1);
+                let len = usize::decode(reader)?;
+                node.count = Some(len);
+                let values = len.saturating_mul(2);
+                self.children(node, reader, values, depth)
+            }
+            TAG_MAP_FLAT_KEYS | TAG_MAP_STRING_KEYS | TAG_MAP_ID_KEYS => {
+                reader.advance(1);
+                let arity = if tag == TAG_MAP_FLAT_KEYS {
+                    let arity = reader.first().copied().ok_or(EncoderError::eof(1, 0))?;
+                    reader.advance(1);
+                    arity as usize
+                } else {
+                    0
+                };
+                let len = usize::decode(reader)?;
+                node.count = Some(len);
+                for _ in 0..len {
+                    let (key, complete) = self.key(tag, arity, reader, depth + 1);
+                    push(node, key, complete)?;
+                    let (value, complete) = self.value(reader, self.offset(reader), depth + 1);
+                    push(node, value, complete)?;
+                }
+                Ok(())
+            }
+            TAG_STRUCT_NAMED => {
+                reader.advance(1);
+                self.fields(node, reader, depth)
+            }
+            TAG_ENUM | TAG_ENUM_NAMED | TAG_ENUM_UNNAMED => {
+                reader.advance(1);
+                let (id, name) = read_id(reader, self.names.as_deref())?;
+                node.variant = Some(InspectId { id, name });
+                match tag {
+                    TAG_ENUM_NAMED => self.fields(node, reader, depth),
+                    TAG_ENUM_UNNAMED => {
+                        let len = usize::decode(reader)?;
+                        node.count = Some(len);
+                        self.children(node, reader, len, depth)
+                    }
+                    _ => Ok(()),
+                }
+            }
+            TAG_SOME | TAG_CHRONO_NAIVE_DATE | TAG_JSON_BOOL | TAG_JSON_STRING => {
+                reader.advance(1);
+                self.children(node, reader, 1, depth)
+            }
+            TAG_CHRONO_DATETIME
+            | TAG_CHRONO_NAIVE_TIME
+            | TAG_CHRONO_NAIVE_DATETIME
+            | TAG_DECIMAL
+            | TAG_DURATION
+            | TAG_SYSTEM_TIME => {
+                reader.advance(1);
+                self.children(node, reader, 2, depth)
+            }
+            TAG_JSON_NUMBER => {
+                reader.advance(1);
+                let marker = *reader.first().ok_or(EncoderError::eof(1, 0))?;
+                reader.advance(1);
+                node.value = Some(Value::UInt(marker as u128));
+                self.children(node, reader, 1, depth)
+            }
+            TAG_NONE | TAG_STRUCT_UNIT | TAG_JSON_NULL => {
+                reader.advance(1);
+                Ok(())
+            }
+            _ => {
+                let value = read_value(reader, None, depth)?;
+                node.count = match &value {
+                    Value::String(s) => Some(s.len()),
+                    Value::Binary(b) => Some(b.len()),
+                    _ => None,
+                };
+                node.value = Some(value);
+                Ok(())
+            }
+        }
+    }
+
+    /// Reads `count` values into `node`.
+    fn children(
+        &self,
+        node: &mut InspectNode,
+        reader: &mut Bytes,
+        count: usize,
+        depth: usize,
+    ) -> Step {
+        for _ in 0..count {
+            let (child, complete) = self.value(reader, self.offset(reader), depth + 1);
+            push(node, child, complete)?;
+        }
+        Ok(())
+    }
+
+    /// Reads `[field_id] [value]` pairs up to the zero terminator.
+    fn fields(&self, node: &mut InspectNode, reader: &mut Bytes, depth: usize) -> Step {
+        loop {
+            let start = self.offset(reader);
+            let (id, name) = read_id(reader, self.names.as_deref())?;
+            if id == 0 {
+                return Ok(());
+            }
+            let (mut child, complete) = self.value(reader, start, depth + 1);
+            child.field = Some(InspectId { id, name });
+            push(node, child, complete)?;
+        }
+    }
+
+    /// Reads the key of an entry of a map with untagged keys.
+    fn key(&self, tag: u8, arity: usize, reader: &mut Bytes, depth: usize) -> (InspectNode, bool) {
+        let mut key = InspectNode::new(self.offset(reader), None, "key");
+        let step = match tag {
+            TAG_MAP_FLAT_KEYS => {
+                key.kind = "tuple";
+                key.count = Some(arity);
+                self.children(&mut key, reader, arity, depth)
+            }
+            TAG_MAP_STRING_KEYS => {
+                key.kind = "string";
+                read_string_key(reader)
+                    .map(|s| {
+                        key.count = Some(s.len());
+                        key.value = Some(Value::String(s));
+                    })
+                    .map_err(Failure::from)
+            }
+            _ => {
+                key.kind = "id";
+                read_field_id_optimized(reader)
+                    .map(|id| key.value = Some(Value::UInt(id as u128)))
+                    .map_err(Failure::from)
+            }
+        };
+        let complete = step.is_ok();
+        self.finish(&mut key, reader, step);
+        (key, complete)
+    }
+}
+
+/// Adds a child, stopping the parent if the child could not be read.
+fn push(node: &mut InspectNode, child: InspectNode, complete: bool) -> Step {
+    node.children.push(child);
+    if complete {
+        Ok(())
+    } else {
+        Err(Failure::InChild)
+    }
+}
+
+pub(crate) fn kind_of(tag: u8) -> &'static str {
+    match tag {
+        TAG_ZERO..=TAG_U8_127 | TAG_U8..=TAG_U128 => "uint",
+        TAG_NONE => "none",
+        TAG_SOME => "some",
+        TAG_NEGATIVE => "int",
+        TAG_F32 | TAG_F64 => "float",
+        TAG_STRING_BASE..=TAG_STRING_LONG => "string",
+        TAG_BINARY => "binary",
+        TAG_STRUCT_UNIT => "unit struct",
+        TAG_STRUCT_NAMED => "struct",
+        TAG_STRUCT_UNNAMED => "tuple struct",
+        TAG_ENUM | TAG_ENUM_NAMED | TAG_ENUM_UNNAMED => "enum",
+        TAG_ARRAY_VEC_SET_BASE..=TAG_ARRAY_VEC_SET_LONG => "list",
+        TAG_TUPLE => "tuple",
+        TAG_MAP | TAG_MAP_FLAT_KEYS | TAG_MAP_STRING_KEYS | TAG_MAP_ID_KEYS => "map",
+        TAG_CHRONO_DATETIME => "datetime",
+        TAG_CHRONO_NAIVE_DATE => "naive date",
+        TAG_CHRONO_NAIVE_TIME => "naive time",
+        TAG_CHRONO_NAIVE_DATETIME => "naive datetime",
+        TAG_DECIMAL => "decimal",
+        TAG_UUID => "uuid",
+        TAG_DURATION => "duration",
+        TAG_SYSTEM_TIME => "system time",
+        TAG_JSON_NULL => "json null",
+        TAG_JSON_BOOL => "json bool",
+        TAG_JSON_NUMBER => "json number",
+        TAG_JSON_STRING => "json string",
+        TAG_JSON_ARRAY => "json array",
+        TAG_JSON_OBJECT => "json object",
+        _ => "unknown",
+    }
+}
@@ -0,0 +1,191 @@
+//! Observability for fields marked `#[senax(deprecated)]`.
+//!
+//! A deprecated field is no longer written by the derived encoder but is still decoded (falling
+//! back to its default when absent), so a field can be removed in phases: stop writing it, wait
+//! until no producer sends it any more, then delete it. Every time a derived decoder reads a
+//! deprecated field from a payload, the occurrence is reported to the hook installed with
+//! [`set_deprecated_field_hook`] (to log it or bump a metrics counter) and counted by any
+//! enclosing [`collect_deprecated_fields`].
+//!
+//! Payloads that do not contain deprecated fields cost nothing extra. Without the `std`
+//! feature there is no hook or collector, and reads are not reported.
+
+use alloc::collections::BTreeMap;
+#[cfg(feature = "std")]
+use std::cell::RefCell;
+#[cfg(feature = "std")]
+use std::sync::RwLock;
+
+/// Function called for each deprecated field read, with the type name (`Enum::Variant` for enum
+/// variants) and the field name.
+pub type DeprecatedFieldHook = fn(type_name: &'static str, field_name: &'static str);
+
+/// One deprecated field seen while collecting, and how many times it was read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DeprecatedField {
+    /// The struct, or `Enum::Variant`, whose decoder read the field.
+    pub type_name: &'static str,
+    /// The Rust name of the field.
+    pub field_name: &'static str,
+    /// Number of times the field was read.
+    pub count: u64,
+}
+
+/// Deprecated fields read during [`collect_deprecated_fields`], keyed by type and field name.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DeprecatedFieldStats {
+    counts: BTreeMap<(&'static str, &'static str), u64>,
+}
+
+impl DeprecatedFieldStats {
+    /// Returns `true` if no deprecated field was read.
+    pub fn is_empty(&self) -> bool {
+        self.counts.is_empty()
+    }
+
+    /// Total number of deprecated fields read.
+    pub fn total(&self) -> u64 {
+        self.counts.values().sum()
+    }
+
+    /// Number of times `field_name` was read by the decoder of `type_name`.
+    pub fn count(&self, type_name: &str, field_name: &str) -> u64 {
+        self.counts
+            .get(&(type_name, field_name))
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// Iterates over the fields read, ordered by type name and field name.
+    pub fn iter(&self) -> impl Iterator<Item = DeprecatedField> + '_ {
+        self.counts
+            .iter()
+            .map(|(&(type_name, field_name), &count)| DeprecatedField {
+                type_name,
+                field_name,
+                count,
+            })
+    }
+
+    /// Adds the counts of `other` to these statistics.
+    pub fn merge(&mut self, other: &DeprecatedFieldStats) {
+        for (key, count) in &other.counts {
+            *self.counts.entry(*key).or_default() += count;
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+static HOOK: RwLock<Option<DeprecatedFieldHook>> = RwLock::new(None);
+
+#[cfg(feature = "std")]
+thread_local! {
+    static COLLECTOR: RefCell<Option<DeprecatedFieldStats>> = const { RefCell::new(None) };
+}
+
+/// Installs `hook` for all threads, replacing the previous one; `None` removes it.
+///
+/// # Example
+/// ```rust
+/// use senax_encoder::deprecated_fields::set_deprecated_field_hook;
+///
+/// set_deprecated_field_hook(Some(|type_name, field_name| {
+///     eprintln!("deprecated field {}.{} is still being sent", type_name, field_name);
+/// }));
+/// ```
+#[cfg(feature = "std")]
+pub fn set_deprecated_field_hook(hook: Option<DeprecatedFieldHook>) {
+    *HOOK.write().unwrap_or_else(|e| e.into_inner()) = hook;
+}
+
+/// Uninstalls the collector when dropped, even if decoding panics, and folds its counts into
+/// the enclosing collector.
+#[cfg(feature = "std")]
+struct CollectorGuard(Option<Option<DeprecatedFieldStats>>);
+
+#[cfg(feature = "std")]
+impl CollectorGuard {
+    fn restore(&mut self) -> DeprecatedFieldStats {
+        let previous = self.0.take().flatten();
+        COLLECTOR.with(|c| {
+            let mut c = c.borrow_mut();
+            let stats = c.take().unwrap_or_default();
+            *c = previous.map(|mut outer| {
+                outer.merge(&stats);
+                outer
+            });
+            stats
+        })
+    }
+}
+
+#[cfg(feature = "std")]
+impl Drop for CollectorGuard {
+    fn drop(&mut self) {
+        if self.0.is_some() {
+            self.restore();
+        }
+    }
+}
+
+/// Runs `f` and returns its result together with the deprecated fields read by derived decoders
+/// on this thread while it ran.
+///
+/// Calls may be nested; the counts of an inner call are also added to the enclosing one.
+///
+/// # Example
+/// ```rust
+/// use senax_encoder::deprecated_fields::collect_deprecated_fields;
+/// use senax_encoder::{decode, encode, Decode, Encode};
+///
+/// #[derive(Encode)]
+/// struct OldUser { id: u64, fax: String }
+///
+/// #[derive(Encode, Decode, Debug)]
+/// struct User {
+///     id: u64,
+///     #[senax(deprecated)]
+///     fax: String,
+/// }
+///
+/// let bytes = encode(&OldUser { id: 1, fax: "555-0100".into() }).unwrap();
+/// let (user, stats) = collect_deprecated_fields(|| decode::<User>(&mut bytes.clone()));
+/// let user = user.unwrap();
+/// assert_eq!(user.fax, "555-0100");
+/// assert_eq!(stats.count("User", "fax"), 1);
+///
+/// // New payloads no longer carry the field
+/// let bytes = encode(&user).unwrap();
+/// let (user, stats) = collect_deprecated_fields(|| decode::<User>(&mut bytes.clone()));
+/// assert_eq!(user.unwrap().fax, "");
+/// assert!(stats.is_empty());
+/// ```
+#[cfg(feature = "std")]
+pub fn collect_deprecated_fields<R>(f: impl FnOnce() -> R) -> (R, DeprecatedFieldStats) {
+    let previous = COLLECTOR.with(|c| c.replace(Some(DeprecatedFieldStats::default())));
+    let mut guard = CollectorGuard(Some(previous));
+    let result = f();
+    let stats = guard.restore();
+    (result, stats)
+}
+
+/// Reports that a deprecated field was read.
+///
+/// Called by derived decoders; not part of the public API.
+#[doc(hidden)]
+#[cfg(feature = "std")]
+pub fn record_deprecated_field(type_name: &'static str, field_name: &'static str) {
+    let hook = *HOOK.read().unwrap_or_else(|e| e.into_inner());
+    if let Some(hook) = hook {
+        hook(type_name, field_name);
+    }
+    COLLECTOR.with(|c| {
+        if let Some(stats) = c.borrow_mut().as_mut() {
+            *stats.counts.entry((type_name, field_name)).or_default() += 1;
+        }
+    });
+}
+
+#[doc(hidden)]
+#[cfg(not(feature = "std"))]
+pub fn record_deprecated_field(_type_name: &'static str, _field_name: &'static str) {}
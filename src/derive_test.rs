@@ -0,0 +1,79 @@
+//! Helpers for testing the derive macros.
+//!
+//! [`expand_derives!`] runs the senax derives listed in an item's `#[derive(...)]` attribute
+//! and returns the generated code as a `&'static str`, without compiling it.
+//! [`assert_expansion`] pretty-prints an expansion and compares it with a snapshot file, so
+//! changes to the generated code show up as reviewable diffs. Set `SENAX_BLESS=1` to write
+//! new or changed snapshots instead of failing.
+//!
+//! The crate's own tests use this together with `trybuild` UI tests: `tests/expand/` holds the
+//! expansion snapshots and `tests/ui/` the shapes that must (or must not) compile. Add a file
+//! to either directory to cover a new shape.
+//!
+//! # Example
+//! ```rust,no_run
+//! use senax_encoder::derive_test::{assert_expansion, expand_derives};
+//!
+//! let expansion = expand_derives! {
+//!     #[derive(Encode, Decode)]
+//!     struct Point { x: i32, y: i32 }
+//! };
+//! assert_expansion("tests/expand/point.expanded.rs", expansion);
+//! ```
+
+// Assertion helpers report failures by panicking, like `assert!`
+#![allow(clippy::panic)]
+
+use std::path::Path;
+
+pub use senax_encoder_derive::expand_derives;
+
+/// Environment variable that makes [`assert_expansion`] write snapshots instead of comparing.
+pub const BLESS_ENV: &str = "SENAX_BLESS";
+
+/// Formats generated code as it appears in snapshot files.
+///
+/// Panics if `expansion` is not valid Rust, which points at a bug in the derive.
+pub fn pretty(expansion: &str) -> String {
+    let file = syn::parse_file(expansion)
+        .unwrap_or_else(|e| panic!("expansion is not valid Rust: {}\n{}", e, expansion));
+    prettyplease::unparse(&file)
+}
+
+/// Panics unless the pretty-printed `expansion` matches the snapshot at `path`.
+///
+/// A missing snapshot is written and the assertion passes. With `SENAX_BLESS=1`, a different
+/// snapshot is overwritten instead of failing.
+#[track_caller]
+pub fn assert_expansion(path: impl AsRef<Path>, expansion: &str) {
+    let path = path.as_ref();
+    let actual = pretty(expansion);
+    let bless = std::env::var_os(BLESS_ENV).is_some_and(|value| value != "0");
+    match std::fs::read_to_string(path) {
+        Ok(expected) if expected == actual => {}
+        Ok(_) if !bless => {
+            let expected = std::fs::read_to_string(path).unwrap_or_default();
+            let line = expected
+                .lines()
+                .zip(actual.lines())
+                .position(|(expected, actual)| expected != actual)
+                .unwrap_or_else(|| expected.lines().count().min(actual.lines().count()));
+            panic!(
+                "expansion differs from {} at line {}:\n  snapshot: {}\n  actual:   {}\nrerun with {}=1 to update the snapshot",
+                path.display(),
+                line + 1,
+                expected.lines().nth(line).unwrap_or("<end of file>"),
+                actual.lines().nth(line).unwrap_or("<end of file>"),
+                BLESS_ENV
+            );
+        }
+        _ => {
+            if let Some(dir) = path.parent() {
+                std::fs::create_dir_all(dir)
+                    .unwrap_or_else(|e| panic!("cannot create {}: {}", dir.display(), e));
+            }
+            std::fs::write(path, &actual)
+                .unwrap_or_else(|e| panic!("cannot write {}: {}", path.display(), e));
+        }
+    }
+}
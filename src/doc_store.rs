@@ -0,0 +1,263 @@
+//! A small embeddable document store built on the encoded format.
+//!
+//! A [`DocStore`] holds named [`Collection`]s of `encode()` records. Documents are numbered in
+//! insertion order and are never decoded to be indexed or filtered: field values are read
+//! straight from the encoded bytes with [`query`], so a collection can hold documents of
+//! several versions of a type, or of types this program does not know.
+//!
+//! * [`Collection::create_index`] builds a secondary index on a field ID, kept up to date on
+//!   insert. [`Collection::find`] uses it when present and scans all documents otherwise.
+//! * [`Collection::project`] reads one value (any [`query`] path) from a document.
+//! * [`Collection::to_container`] / [`Collection::from_container`] persist a collection in the
+//!   [indexed container](crate::indexed) format.
+//!
+//! Field values are compared by their encoded bytes, so `5u8` finds a field written as
+//! `5u64`, but `5i32` does not find `5.0f64`.
+//!
+//! # Example
+//! ```rust
+//! use senax_encoder::core::field_id_from_name;
+//! use senax_encoder::doc_store::DocStore;
+//! use senax_encoder::{Decode, Encode};
+//!
+//! #[derive(Encode, Decode, Debug, PartialEq)]
+//! struct User { name: String, country: String, age: u32 }
+//!
+//! let mut store = DocStore::new();
+//! let users = store.collection_mut("users");
+//! let country = field_id_from_name("country");
+//! users.create_index(country).unwrap();
+//! users.insert(&User { name: "alice".into(), country: "jp".into(), age: 30 }).unwrap();
+//! users.insert(&User { name: "bob".into(), country: "fr".into(), age: 25 }).unwrap();
+//!
+//! let ids = users.find(country, &"fr".to_string()).unwrap();
+//! assert_eq!(ids, vec![1]);
+//! assert_eq!(users.get::<User>(1).unwrap().unwrap().name, "bob");
+//! ```
+
+use crate::indexed::{IndexedReader, IndexedWriter};
+use crate::query::{query, QueryMatch};
+use crate::value::{write_value, Value};
+use crate::{decode, encode, validate_payload, Decoder, Encoder, EncoderError, Result};
+use bytes::{Bytes, BytesMut};
+use std::collections::{BTreeMap, HashMap};
+
+/// Named collections of encoded documents.
+#[derive(Debug, Clone, Default)]
+pub struct DocStore {
+    collections: BTreeMap<String, Collection>,
+}
+
+impl DocStore {
+    /// Creates an empty store.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the collection called `name`, if it exists.
+    pub fn collection(&self, name: &str) -> Option<&Collection> {
+        self.collections.get(name)
+    }
+
+    /// Returns the collection called `name`, creating an empty one if needed.
+    pub fn collection_mut(&mut self, name: &str) -> &mut Collection {
+        self.collections.entry(name.to_string()).or_default()
+    }
+
+    /// Removes the collection called `name` and returns it.
+    pub fn drop_collection(&mut self, name: &str) -> Option<Collection> {
+        self.collections.remove(name)
+    }
+
+    /// Returns the collection names in sorted order.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.collections.keys().map(String::as_str)
+    }
+}
+
+/// An append-only list of encoded documents with optional secondary indexes.
+#[derive(Debug, Clone, Default)]
+pub struct Collection {
+    documents: Vec<Bytes>,
+    /// Field ID to encoded field value to the documents holding it, in insertion order.
+    indexes: HashMap<u64, BTreeMap<Bytes, Vec<usize>>>,
+}
+
+impl Collection {
+    /// Creates an empty collection.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the number of documents.
+    pub fn len(&self) -> usize {
+        self.documents.len()
+    }
+
+    /// Returns `true` if the collection holds no documents.
+    pub fn is_empty(&self) -> bool {
+        self.documents.is_empty()
+    }
+
+    /// Encodes `document` and appends it, returning its document number.
+    pub fn insert<T: Encoder>(&mut self, document: &T) -> Result<usize> {
+        self.push(encode(document)?)
+    }
+
+    /// Appends an `encode()` payload (with magic number), returning its document number.
+    ///
+    /// The payload is validated first, so a malformed record cannot poison later scans.
+    pub fn insert_raw(&mut self, payload: Bytes) -> Result<usize> {
+        let report = validate_payload::<Value>(&payload);
+        if let Some(issue) = report.error {
+            return Err(EncoderError::Decode(format!(
+                "Invalid document at offset {}: {}",
+                issue.offset, issue.message
+            )));
+        }
+        if report.trailing_bytes > 0 {
+            return Err(EncoderError::Decode(format!(
+                "Invalid document: {} trailing bytes",
+                report.trailing_bytes
+            )));
+        }
+        self.push(payload)
+    }
+
+    fn push(&mut self, payload: Bytes) -> Result<usize> {
+        let id = self.documents.len();
+        let mut keys = Vec::with_capacity(self.indexes.len());
+        for &field_id in self.indexes.keys() {
+            keys.push((field_id, field_key(&payload, field_id)?));
+        }
+        for (field_id, key) in keys {
+            if let (Some(key), Some(index)) = (key, self.indexes.get_mut(&field_id)) {
+                index.entry(key).or_default().push(id);
+            }
+        }
+        self.documents.push(payload);
+        Ok(id)
+    }
+
+    /// Returns the encoded payload of document `id`.
+    pub fn get_raw(&self, id: usize) -> Option<&Bytes> {
+        self.documents.get(id)
+    }
+
+    /// Decodes document `id` as `T`.
+    pub fn get<T: Decoder>(&self, id: usize) -> Result<Option<T>> {
+        self.documents
+            .get(id)
+            .map(|payload| decode(&mut payload.clone()))
+            .transpose()
+    }
+
+    /// Reads the value at a [`query`] path (e.g. `items[0].name`) from document `id`.
+    ///
+    /// Returns `Ok(None)` if the document or the path does not exist.
+    pub fn project(&self, id: usize, path: &str) -> Result<Option<QueryMatch>> {
+        match self.documents.get(id) {
+            Some(payload) => query(payload, path),
+            None => Ok(None),
+        }
+    }
+
+    /// Indexes the field with `field_id` in every document, current and future.
+    ///
+    /// Documents without the field are not indexed. Creating an existing index is a no-op.
+    pub fn create_index(&mut self, field_id: u64) -> Result<()> {
+        if self.indexes.contains_key(&field_id) {
+            return Ok(());
+        }
+        let mut index: BTreeMap<Bytes, Vec<usize>> = BTreeMap::new();
+        for (id, payload) in self.documents.iter().enumerate() {
+            if let Some(key) = field_key(payload, field_id)? {
+                index.entry(key).or_default().push(id);
+            }
+        }
+        self.indexes.insert(field_id, index);
+        Ok(())
+    }
+
+    /// Removes the index on `field_id`, returning `true` if it existed.
+    pub fn drop_index(&mut self, field_id: u64) -> bool {
+        self.indexes.remove(&field_id).is_some()
+    }
+
+    /// Returns `true` if `field_id` is indexed.
+    pub fn has_index(&self, field_id: u64) -> bool {
+        self.indexes.contains_key(&field_id)
+    }
+
+    /// Returns the numbers of the documents whose field `field_id` equals `value`, in
+    /// insertion order.
+    ///
+    /// Uses the index on `field_id` if there is one and scans every document otherwise.
+    pub fn find<V: Encoder + ?Sized>(&self, field_id: u64, value: &V) -> Result<Vec<usize>> {
+        let mut key = BytesMut::new();
+        value.encode(&mut key)?;
+        let key = key.freeze();
+        if let Some(index) = self.indexes.get(&field_id) {
+            return Ok(index.get(&key).cloned().unwrap_or_default());
+        }
+        let mut ids = Vec::new();
+        for (id, payload) in self.documents.iter().enumerate() {
+            if field_key(payload, field_id)?.as_ref() == Some(&key) {
+                ids.push(id);
+            }
+        }
+        Ok(ids)
+    }
+
+    /// Calls `f` with every document number and the value of its field `field_id`.
+    ///
+    /// Documents without the field are skipped. Nothing is decoded beyond the field itself.
+    pub fn scan(
+        &self,
+        field_id: u64,
+        mut f: impl FnMut(usize, QueryMatch) -> Result<()>,
+    ) -> Result<()> {
+        let path = field_id.to_string();
+        for (id, payload) in self.documents.iter().enumerate() {
+            if let Some(value) = query(payload, &path)? {
+                f(id, value)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Writes the documents as an [indexed container](crate::indexed).
+    ///
+    /// Secondary indexes are not stored; recreate them after
+    /// [`from_container`](Self::from_container).
+    pub fn to_container(&self) -> Result<Vec<u8>> {
+        let mut writer = IndexedWriter::new(Vec::new());
+        for payload in &self.documents {
+            writer.append(payload)?;
+        }
+        writer.finish()
+    }
+
+    /// Reads a collection written by [`to_container`](Self::to_container).
+    pub fn from_container(data: Bytes) -> Result<Self> {
+        let reader = IndexedReader::new(data)?;
+        let mut collection = Self::new();
+        for id in 0..reader.len() {
+            collection.insert_raw(reader.record(id)?)?;
+        }
+        Ok(collection)
+    }
+}
+
+/// Returns the encoded bytes of field `field_id` in `payload`, the key used by indexes.
+fn field_key(payload: &Bytes, field_id: u64) -> Result<Option<Bytes>> {
+    Ok(match query(payload, &field_id.to_string())? {
+        Some(QueryMatch::Scalar(value)) => {
+            let mut writer = BytesMut::new();
+            write_value(&value, &mut writer)?;
+            Some(writer.freeze())
+        }
+        Some(QueryMatch::Raw(bytes)) => Some(bytes),
+        None => None,
+    })
+}
@@ -0,0 +1,134 @@
+//! Encrypted payloads, for senax data embedded in tokens or stored outside a trusted boundary.
+//!
+//! [`encode_encrypted`] seals the [`encode`] output with an AEAD cipher:
+//!
+//! ```text
+//! [magic: 0xA55F] [algorithm: u8] [nonce] [ciphertext + tag]
+//! ```
+//!
+//! The only algorithm so far is XChaCha20-Poly1305 (algorithm byte 1, 24-byte nonce, 16-byte
+//! tag). The magic number and algorithm byte are authenticated as associated data, so a
+//! tampered header fails like a tampered ciphertext. Keys are [`FieldKey`]s, the same keys used
+//! for `#[senax(encrypt)]` fields.
+
+use crate::field_crypto::{FieldKey, FIELD_NONCE_LEN};
+use crate::{decode_exact, encode, Decoder, Encoder, EncoderError, Result};
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+use chacha20poly1305::aead::{Aead, AeadCore, OsRng, Payload};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+
+/// Magic number for encrypted payloads (0xA55F in little-endian).
+pub const ENCRYPTED_MAGIC: u16 = 0xA55F;
+
+/// Algorithm byte of XChaCha20-Poly1305 envelopes.
+const ALGORITHM_XCHACHA20_POLY1305: u8 = 1;
+
+/// Length of the magic number and algorithm byte.
+const HEADER_LEN: usize = 3;
+
+/// Length of the authentication tag after the ciphertext.
+const TAG_LEN: usize = 16;
+
+/// Returns a random nonce for [`encode_encrypted`].
+///
+/// Nonces are 24 bytes, long enough that random ones never repeat in practice.
+pub fn random_nonce() -> [u8; FIELD_NONCE_LEN] {
+    XChaCha20Poly1305::generate_nonce(&mut OsRng).into()
+}
+
+/// Encodes a value like [`encode`] and seals it with `key`.
+///
+/// Never seal two payloads with the same key and nonce: that reveals both plaintexts. Use
+/// [`random_nonce`] unless the protocol derives nonces itself.
+///
+/// # Example
+/// ```rust
+/// use senax_encoder::field_crypto::FieldKey;
+/// use senax_encoder::{decode_encrypted, encode_encrypted, random_nonce};
+///
+/// let key = FieldKey::new([7; 32]);
+/// let token = encode_encrypted(&("alice".to_string(), 1_700_000_000u64), &key, &random_nonce())
+///     .unwrap();
+/// let (user, expires): (String, u64) = decode_encrypted(&mut token.clone(), &key).unwrap();
+/// assert_eq!(user, "alice");
+///
+/// // Any other key is rejected
+/// assert!(decode_encrypted::<(String, u64)>(&mut token.clone(), &FieldKey::new([8; 32])).is_err());
+/// ```
+pub fn encode_encrypted<T: Encoder>(
+    value: &T,
+    key: &FieldKey,
+    nonce: &[u8; FIELD_NONCE_LEN],
+) -> Result<Bytes> {
+    let plain = BytesMut::from(encode(value)?);
+    let mut writer = BytesMut::with_capacity(HEADER_LEN + FIELD_NONCE_LEN + plain.len() + TAG_LEN);
+    writer.put_u16_le(ENCRYPTED_MAGIC);
+    writer.put_u8(ALGORITHM_XCHACHA20_POLY1305);
+    let sealed = key
+        .cipher()
+        .encrypt(
+            XNonce::from_slice(nonce),
+            Payload {
+                msg: &plain,
+                aad: &writer[..HEADER_LEN],
+            },
+        )
+        .map_err(|_| EncoderError::Encode("Payload encryption failed".to_string()));
+    #[cfg(feature = "zeroize")]
+    {
+        let mut plain = plain;
+        zeroize::Zeroize::zeroize(&mut plain[..]);
+    }
+    writer.put_slice(nonce);
+    writer.put_slice(&sealed?);
+    Ok(writer.freeze())
+}
+
+/// Opens a payload sealed by [`encode_encrypted`] and decodes it.
+///
+/// The payload must make up all of `reader`, which is consumed. Fails with
+/// [`EncoderError::Decode`] if the key is wrong, any byte was changed or the payload is too
+/// short to hold the header, nonce and tag.
+pub fn decode_encrypted<T: Decoder>(reader: &mut Bytes, key: &FieldKey) -> Result<T> {
+    if reader.remaining() < HEADER_LEN + FIELD_NONCE_LEN + TAG_LEN {
+        return Err(EncoderError::Decode(format!(
+            "Encrypted payload of {} bytes is shorter than its envelope",
+            reader.remaining()
+        )));
+    }
+    let header = reader.split_to(HEADER_LEN);
+    let magic = u16::from_le_bytes([header[0], header[1]]);
+    if magic != ENCRYPTED_MAGIC {
+        return Err(EncoderError::Decode(format!(
+            "Invalid encrypted magic number: expected 0x{:04X}, got 0x{:04X}",
+            ENCRYPTED_MAGIC, magic
+        )));
+    }
+    if header[2] != ALGORITHM_XCHACHA20_POLY1305 {
+        return Err(EncoderError::Decode(format!(
+            "Unknown encryption algorithm {}",
+            header[2]
+        )));
+    }
+    let nonce = reader.split_to(FIELD_NONCE_LEN);
+    let sealed = std::mem::take(reader);
+    let plain = key
+        .cipher()
+        .decrypt(
+            XNonce::from_slice(&nonce),
+            Payload {
+                msg: &sealed,
+                aad: &header,
+            },
+        )
+        .map_err(|_| {
+            EncoderError::Decode(
+                "Failed to decrypt payload: wrong key or corrupted data".to_string(),
+            )
+        })?;
+    #[cfg(feature = "zeroize")]
+    let mut plain = crate::sensitive::zeroizing_bytes(plain);
+    #[cfg(not(feature = "zeroize"))]
+    let mut plain = Bytes::from(plain);
+    decode_exact(&mut plain)
+}
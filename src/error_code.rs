@@ -0,0 +1,252 @@
+//! Compact error codes for constrained targets.
+//!
+//! [`EncoderError`] carries `String` messages, which are expensive to log and ship on embedded
+//! targets. [`EncoderError::code`] adds an [`ErrorCode`] classification: a fieldless enum that
+//! can be logged or sent as a number. With the `defmt` feature, `ErrorCode` implements
+//! `defmt::Format`, and `EncoderError` implements it by logging the code plus the numeric
+//! details of the error, never its strings.
+//!
+//! The codes do not change how errors are built: `Encode` and `Decode` errors still format and
+//! allocate their message when they are created.
+
+use crate::{EncoderError, EnumDecodeError, StructDecodeError};
+
+/// Identifies the kind of an [`EncoderError`] without any payload.
+///
+/// The numeric values are stable and may be stored or sent over the wire.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[repr(u16)]
+pub enum ErrorCode {
+    /// [`EncoderError::Encode`]
+    Encode = 1,
+    /// [`EncoderError::Decode`]
+    Decode = 2,
+    /// [`EncoderError::UnexpectedEof`]
+    UnexpectedEof = 3,
+    /// [`EncoderError::TooLarge`]
+    TooLarge = 4,
+    /// [`EncoderError::CapacityExceeded`]
+    CapacityExceeded = 5,
+    /// [`EncoderError::DuplicateSetElement`]
+    DuplicateSetElement = 6,
+    /// `EncoderError::Io`, which exists with the `std` feature
+    Io = 7,
+    /// [`EncoderError::DepthLimitExceeded`]
+    DepthLimitExceeded = 8,
+    /// [`EncoderError::LimitExceeded`]
+    LimitExceeded = 9,
+    /// [`EncoderError::TrailingData`]
+    TrailingData = 10,
+    /// [`EncoderError::ChecksumMismatch`]
+    ChecksumMismatch = 11,
+    /// [`StructDecodeError::InvalidTag`]
+    StructInvalidTag = 100,
+    /// [`StructDecodeError::MissingRequiredField`]
+    StructMissingRequiredField = 101,
+    /// [`StructDecodeError::FieldCountMismatch`]
+    StructFieldCountMismatch = 102,
+    /// [`StructDecodeError::StructureHashMismatch`]
+    StructStructureHashMismatch = 103,
+    /// [`StructDecodeError::OutOfRange`]
+    StructOutOfRange = 104,
+    /// [`StructDecodeError::UnknownField`]
+    StructUnknownField = 105,
+    /// [`EnumDecodeError::UnknownTag`]
+    EnumUnknownTag = 200,
+    /// [`EnumDecodeError::UnknownVariantId`]
+    EnumUnknownVariantId = 201,
+    /// [`EnumDecodeError::UnknownVariantName`]
+    EnumUnknownVariantName = 202,
+    /// [`EnumDecodeError::UnknownUnitVariantId`]
+    EnumUnknownUnitVariantId = 203,
+    /// [`EnumDecodeError::UnknownNamedVariantId`]
+    EnumUnknownNamedVariantId = 204,
+    /// [`EnumDecodeError::UnknownUnnamedVariantId`]
+    EnumUnknownUnnamedVariantId = 205,
+    /// [`EnumDecodeError::MissingRequiredField`]
+    EnumMissingRequiredField = 206,
+    /// [`EnumDecodeError::FieldCountMismatch`]
+    EnumFieldCountMismatch = 207,
+    /// [`EnumDecodeError::StructureHashMismatch`]
+    EnumStructureHashMismatch = 208,
+    /// [`EnumDecodeError::UnknownField`]
+    EnumUnknownField = 209,
+}
+
+impl ErrorCode {
+    /// Returns the numeric value of the code.
+    pub const fn as_u16(self) -> u16 {
+        self as u16
+    }
+
+    /// Returns a short static description of the code.
+    pub const fn description(self) -> &'static str {
+        match self {
+            ErrorCode::Encode => "encode error",
+            ErrorCode::Decode => "decode error",
+            ErrorCode::UnexpectedEof => "unexpected end of input",
+            ErrorCode::TooLarge => "payload too large",
+            ErrorCode::CapacityExceeded => "capacity exceeded",
+            ErrorCode::DuplicateSetElement => "duplicate set element",
+            ErrorCode::Io => "I/O error",
+            ErrorCode::DepthLimitExceeded => "nesting too deep",
+            ErrorCode::LimitExceeded => "decode limit exceeded",
+            ErrorCode::TrailingData => "trailing data",
+            ErrorCode::ChecksumMismatch => "checksum mismatch",
+            ErrorCode::StructInvalidTag => "invalid struct tag",
+            ErrorCode::StructMissingRequiredField => "missing struct field",
+            ErrorCode::StructFieldCountMismatch => "struct field count mismatch",
+            ErrorCode::StructStructureHashMismatch => "struct hash mismatch",
+            ErrorCode::StructOutOfRange => "field out of range",
+            ErrorCode::StructUnknownField => "unknown struct field",
+            ErrorCode::EnumUnknownTag => "unknown enum tag",
+            ErrorCode::EnumUnknownVariantId => "unknown variant ID",
+            ErrorCode::EnumUnknownVariantName => "unknown variant name",
+            ErrorCode::EnumUnknownUnitVariantId => "unknown unit variant ID",
+            ErrorCode::EnumUnknownNamedVariantId => "unknown named variant ID",
+            ErrorCode::EnumUnknownUnnamedVariantId => "unknown unnamed variant ID",
+            ErrorCode::EnumMissingRequiredField => "missing variant field",
+            ErrorCode::EnumFieldCountMismatch => "variant field count mismatch",
+            ErrorCode::EnumStructureHashMismatch => "variant hash mismatch",
+            ErrorCode::EnumUnknownField => "unknown variant field",
+        }
+    }
+}
+
+impl EncoderError {
+    /// Returns the compact code identifying this error.
+    ///
+    /// # Example
+    /// ```rust
+    /// use senax_encoder::{decode, ErrorCode};
+    ///
+    /// let err = decode::<u32>(&mut bytes::Bytes::new()).unwrap_err();
+    /// assert_eq!(err.code(), ErrorCode::UnexpectedEof);
+    /// assert_eq!(err.code().as_u16(), 3);
+    /// ```
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            EncoderError::Encode(_) => ErrorCode::Encode,
+            EncoderError::Decode(_) => ErrorCode::Decode,
+            EncoderError::UnexpectedEof { .. } => ErrorCode::UnexpectedEof,
+            EncoderError::TooLarge { .. } => ErrorCode::TooLarge,
+            EncoderError::CapacityExceeded { .. } => ErrorCode::CapacityExceeded,
+            EncoderError::DuplicateSetElement { .. } => ErrorCode::DuplicateSetElement,
+            #[cfg(feature = "std")]
+            EncoderError::Io(_) => ErrorCode::Io,
+            EncoderError::DepthLimitExceeded { .. } => ErrorCode::DepthLimitExceeded,
+            EncoderError::LimitExceeded { .. } => ErrorCode::LimitExceeded,
+            EncoderError::TrailingData { .. } => ErrorCode::TrailingData,
+            EncoderError::ChecksumMismatch { .. } => ErrorCode::ChecksumMismatch,
+            EncoderError::StructDecode(e) => match e {
+                StructDecodeError::InvalidTag { .. } => ErrorCode::StructInvalidTag,
+                StructDecodeError::MissingRequiredField { .. } => {
+                    ErrorCode::StructMissingRequiredField
+                }
+                StructDecodeError::FieldCountMismatch { .. } => ErrorCode::StructFieldCountMismatch,
+                StructDecodeError::StructureHashMismatch { .. } => {
+                    ErrorCode::StructStructureHashMismatch
+                }
+                StructDecodeError::OutOfRange { .. } => ErrorCode::StructOutOfRange,
+                StructDecodeError::UnknownField { .. } => ErrorCode::StructUnknownField,
+            },
+            EncoderError::EnumDecode(e) => match e {
+                EnumDecodeError::UnknownTag { .. } => ErrorCode::EnumUnknownTag,
+                EnumDecodeError::UnknownVariantId { .. } => ErrorCode::EnumUnknownVariantId,
+                EnumDecodeError::UnknownVariantName { .. } => ErrorCode::EnumUnknownVariantName,
+                EnumDecodeError::UnknownUnitVariantId { .. } => ErrorCode::EnumUnknownUnitVariantId,
+                EnumDecodeError::UnknownNamedVariantId { .. } => {
+                    ErrorCode::EnumUnknownNamedVariantId
+                }
+                EnumDecodeError::UnknownUnnamedVariantId { .. } => {
+                    ErrorCode::EnumUnknownUnnamedVariantId
+                }
+                EnumDecodeError::MissingRequiredField { .. } => ErrorCode::EnumMissingRequiredField,
+                EnumDecodeError::FieldCountMismatch { .. } => ErrorCode::EnumFieldCountMismatch,
+                EnumDecodeError::StructureHashMismatch { .. } => {
+                    ErrorCode::EnumStructureHashMismatch
+                }
+                EnumDecodeError::UnknownField { .. } => ErrorCode::EnumUnknownField,
+            },
+        }
+    }
+}
+
+/// Logs the error code and numeric details; string payloads are never formatted.
+#[cfg(feature = "defmt")]
+impl defmt::Format for EncoderError {
+    fn format(&self, f: defmt::Formatter) {
+        let code = self.code();
+        match self {
+            EncoderError::UnexpectedEof { needed, available } => defmt::write!(
+                f,
+                "EncoderError({}): needed={=usize} available={=usize}",
+                code,
+                needed,
+                available
+            ),
+            EncoderError::TooLarge { actual, limit } => defmt::write!(
+                f,
+                "EncoderError({}): actual={=usize} limit={=usize}",
+                code,
+                actual,
+                limit
+            ),
+            EncoderError::CapacityExceeded { len, capacity } => defmt::write!(
+                f,
+                "EncoderError({}): len={=usize} capacity={=usize}",
+                code,
+                len,
+                capacity
+            ),
+            EncoderError::DuplicateSetElement { index } => {
+                defmt::write!(f, "EncoderError({}): index={=usize}", code, index)
+            }
+            EncoderError::DepthLimitExceeded { limit } => {
+                defmt::write!(f, "EncoderError({}): limit={=usize}", code, limit)
+            }
+            EncoderError::LimitExceeded { len, limit, .. } => defmt::write!(
+                f,
+                "EncoderError({}): len={=usize} limit={=usize}",
+                code,
+                len,
+                limit
+            ),
+            EncoderError::TrailingData { remaining } => {
+                defmt::write!(f, "EncoderError({}): remaining={=usize}", code, remaining)
+            }
+            EncoderError::ChecksumMismatch { expected, actual } => defmt::write!(
+                f,
+                "EncoderError({}): expected={=u64:#x} actual={=u64:#x}",
+                code,
+                expected,
+                actual
+            ),
+            EncoderError::StructDecode(StructDecodeError::InvalidTag { expected, actual }) => {
+                defmt::write!(
+                    f,
+                    "EncoderError({}): expected={=u8} actual={=u8}",
+                    code,
+                    expected,
+                    actual
+                )
+            }
+            EncoderError::EnumDecode(EnumDecodeError::UnknownTag { tag, .. }) => {
+                defmt::write!(f, "EncoderError({}): tag={=u8}", code, tag)
+            }
+            EncoderError::EnumDecode(
+                EnumDecodeError::UnknownVariantId { variant_id, .. }
+                | EnumDecodeError::UnknownUnitVariantId { variant_id, .. }
+                | EnumDecodeError::UnknownNamedVariantId { variant_id, .. }
+                | EnumDecodeError::UnknownUnnamedVariantId { variant_id, .. },
+            ) => defmt::write!(
+                f,
+                "EncoderError({}): variant_id={=u64:#x}",
+                code,
+                variant_id
+            ),
+            _ => defmt::write!(f, "EncoderError({})", code),
+        }
+    }
+}
@@ -0,0 +1,191 @@
+//! Opaque extension blocks that third parties can extend without changing the host type.
+//!
+//! An [`ExtBlock`] field (marked `#[senax(ext_block)]`) holds a length-prefixed region of
+//! vendor extensions, each identified by an [`Extension::EXT_ID`]:
+//!
+//! ```text
+//! [TAG_BINARY] [region_len] ([ext_id] [len] [extension: Encoder::encode output])...
+//! ```
+//!
+//! On the wire the block is a byte string, so readers whose field is plain `Bytes` (or that
+//! skip the field) still accept it. Decoding keeps the region verbatim: extensions the reader
+//! does not know, and their order, survive a decode and re-encode unchanged. Only
+//! [`ExtBlock::insert`] and [`ExtBlock::remove`] rewrite the entry they touch.
+
+use crate::core::{read_field_id_optimized, write_field_id_optimized};
+use crate::{Decoder, Encoder, EncoderError, Packer, Result, Unpacker};
+use alloc::string::ToString;
+use alloc::vec::Vec;
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+
+/// A value stored in an [`ExtBlock`].
+///
+/// # Example
+/// ```rust
+/// use senax_encoder::core::field_id_from_name;
+/// use senax_encoder::ext_block::{ExtBlock, Extension};
+/// use senax_encoder::{decode, encode, Decode, Encode};
+///
+/// #[derive(Encode, Decode, Debug, PartialEq)]
+/// struct AuditInfo {
+///     approved_by: String,
+/// }
+///
+/// impl Extension for AuditInfo {
+///     const EXT_ID: u64 = field_id_from_name("com.example.audit");
+/// }
+///
+/// #[derive(Encode, Decode, Debug, PartialEq)]
+/// struct Invoice {
+///     total: u64,
+///     #[senax(ext_block)]
+///     ext: ExtBlock,
+/// }
+///
+/// let mut invoice = Invoice { total: 120, ext: ExtBlock::new() };
+/// invoice.ext.insert(&AuditInfo { approved_by: "kim".into() }).unwrap();
+///
+/// let decoded: Invoice = decode(&mut encode(&invoice).unwrap()).unwrap();
+/// let audit = decoded.ext.get::<AuditInfo>().unwrap().unwrap();
+/// assert_eq!(audit.approved_by, "kim");
+/// ```
+pub trait Extension: Encoder + Decoder {
+    /// Identifies the extension within a block. Pick IDs that other vendors will not, e.g.
+    /// [`field_id_from_name`](crate::core::field_id_from_name) of a reverse domain name.
+    /// ID 0 is reserved.
+    const EXT_ID: u64;
+}
+
+/// A length-prefixed region of [`Extension`]s, preserved verbatim across decode and encode.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct ExtBlock {
+    raw: Bytes,
+}
+
+impl ExtBlock {
+    /// Creates an empty block.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Wraps an encoded region, e.g. one read from a `Bytes` field by an older type.
+    ///
+    /// The region is checked when extensions are read, not here.
+    pub fn from_raw(raw: Bytes) -> Self {
+        Self { raw }
+    }
+
+    /// Returns the encoded region.
+    pub fn as_raw(&self) -> &Bytes {
+        &self.raw
+    }
+
+    /// Returns `true` if the block holds no extensions.
+    pub fn is_empty(&self) -> bool {
+        self.raw.is_empty()
+    }
+
+    /// Returns the ID and encoded payload of every extension, in block order.
+    pub fn entries(&self) -> Result<Vec<(u64, Bytes)>> {
+        let mut reader = self.raw.clone();
+        let mut entries = Vec::new();
+        while reader.has_remaining() {
+            let id = read_field_id_optimized(&mut reader)?;
+            let len = usize::decode(&mut reader)?;
+            if reader.remaining() < len {
+                return Err(EncoderError::eof(len, reader.remaining()));
+            }
+            entries.push((id, reader.split_to(len)));
+        }
+        Ok(entries)
+    }
+
+    /// Returns `true` if the block holds an extension with `E`'s ID.
+    pub fn contains<E: Extension>(&self) -> Result<bool> {
+        Ok(self.entries()?.iter().any(|(id, _)| *id == E::EXT_ID))
+    }
+
+    /// Decodes the extension `E`, or returns `None` if the block does not hold it.
+    pub fn get<E: Extension>(&self) -> Result<Option<E>> {
+        match self.entries()?.into_iter().find(|(id, _)| *id == E::EXT_ID) {
+            Some((_, mut payload)) => E::decode(&mut payload).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Stores `extension`, replacing an earlier value with the same ID in place or appending
+    /// it at the end.
+    pub fn insert<E: Extension>(&mut self, extension: &E) -> Result<()> {
+        if E::EXT_ID == 0 {
+            return Err(EncoderError::Encode(
+                "Extension ID 0 is reserved".to_string(),
+            ));
+        }
+        let mut payload = BytesMut::new();
+        extension.encode(&mut payload)?;
+        let mut entries = self.entries()?;
+        match entries.iter_mut().find(|(id, _)| *id == E::EXT_ID) {
+            Some(entry) => entry.1 = payload.freeze(),
+            None => entries.push((E::EXT_ID, payload.freeze())),
+        }
+        self.raw = write_entries(&entries)?;
+        Ok(())
+    }
+
+    /// Removes the extension `E`, returning `true` if the block held it.
+    pub fn remove<E: Extension>(&mut self) -> Result<bool> {
+        let mut entries = self.entries()?;
+        let before = entries.len();
+        entries.retain(|(id, _)| *id != E::EXT_ID);
+        if entries.len() == before {
+            return Ok(false);
+        }
+        self.raw = write_entries(&entries)?;
+        Ok(true)
+    }
+}
+
+fn write_entries(entries: &[(u64, Bytes)]) -> Result<Bytes> {
+    let mut writer = BytesMut::new();
+    for (id, payload) in entries {
+        write_field_id_optimized(&mut writer, *id)?;
+        payload.len().encode(&mut writer)?;
+        writer.put_slice(payload);
+    }
+    Ok(writer.freeze())
+}
+
+impl Encoder for ExtBlock {
+    fn encode(&self, writer: &mut BytesMut) -> Result<()> {
+        self.raw.encode(writer)
+    }
+
+    fn is_default(&self) -> bool {
+        self.is_empty()
+    }
+}
+
+impl Decoder for ExtBlock {
+    fn decode(reader: &mut Bytes) -> Result<Self> {
+        Bytes::decode(reader).map(Self::from_raw)
+    }
+}
+
+impl Packer for ExtBlock {
+    fn pack(&self, writer: &mut BytesMut) -> Result<()> {
+        self.raw.pack(writer)
+    }
+}
+
+impl Unpacker for ExtBlock {
+    fn unpack(reader: &mut Bytes) -> Result<Self> {
+        Bytes::unpack(reader).map(Self::from_raw)
+    }
+}
+
+#[cfg(feature = "std")]
+impl crate::schema::HasSchema for ExtBlock {
+    fn schema() -> crate::schema::TypeSchema {
+        crate::schema::TypeSchema::Bytes
+    }
+}
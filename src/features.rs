@@ -18,6 +18,8 @@ use serde_json::value::RawValue;
 use serde_json::{Map, Number, Value};
 #[cfg(feature = "smol_str")]
 use smol_str::SmolStr;
+#[cfg(feature = "time")]
+use time::{Date, OffsetDateTime, PrimitiveDateTime, Time};
 #[cfg(feature = "ulid")]
 use ulid::Ulid;
 #[cfg(feature = "uuid")]
@@ -35,6 +37,7 @@ impl<T: Encoder + Eq + std::hash::Hash> Encoder for IndexSet<T> {
         encode_vec_length(self.len(), writer)?;
         for v in self {
             v.encode(writer)?;
+            crate::config::check_encode_limit(writer.len())?;
         }
         Ok(())
     }
@@ -47,7 +50,7 @@ impl<T: Encoder + Eq + std::hash::Hash> Encoder for IndexSet<T> {
 impl<T: Decoder + Eq + std::hash::Hash + 'static> Decoder for IndexSet<T> {
     fn decode(reader: &mut Bytes) -> Result<Self> {
         let vec: Vec<T> = Vec::decode(reader)?;
-        Ok(vec.into_iter().collect())
+        collect_set(vec, IndexSet::with_capacity, |set, v| set.insert(v))
     }
 }
 #[cfg(feature = "indexmap")]
@@ -64,7 +67,7 @@ impl<T: Packer + Eq + std::hash::Hash> Packer for IndexSet<T> {
 impl<T: Unpacker + Eq + std::hash::Hash + 'static> Unpacker for IndexSet<T> {
     fn unpack(reader: &mut Bytes) -> Result<Self> {
         let vec: Vec<T> = Vec::unpack(reader)?;
-        Ok(vec.into_iter().collect())
+        collect_set(vec, IndexSet::with_capacity, |set, v| set.insert(v))
     }
 }
 
@@ -78,6 +81,7 @@ impl<K: Encoder + Eq + std::hash::Hash, V: Encoder> Encoder for IndexMap<K, V> {
         for (k, v) in self {
             k.encode(writer)?;
             v.encode(writer)?;
+            crate::config::check_encode_limit(writer.len())?;
         }
         Ok(())
     }
@@ -90,7 +94,7 @@ impl<K: Encoder + Eq + std::hash::Hash, V: Encoder> Encoder for IndexMap<K, V> {
 impl<K: Decoder + Eq + std::hash::Hash, V: Decoder> Decoder for IndexMap<K, V> {
     fn decode(reader: &mut Bytes) -> Result<Self> {
         let len = read_map_header(reader)?;
-        let mut map = IndexMap::with_capacity(len);
+        let mut map = IndexMap::with_capacity(collection_capacity::<(K, V)>(len, reader)?);
         for _ in 0..len {
             let k = K::decode(reader)?;
             let v = V::decode(reader)?;
@@ -116,7 +120,7 @@ impl<K: Packer + Eq + std::hash::Hash, V: Packer> Packer for IndexMap<K, V> {
 impl<K: Unpacker + Eq + std::hash::Hash, V: Unpacker> Unpacker for IndexMap<K, V> {
     fn unpack(reader: &mut Bytes) -> Result<Self> {
         let len = read_map_header(reader)?;
-        let mut map = IndexMap::with_capacity(len);
+        let mut map = IndexMap::with_capacity(collection_capacity::<(K, V)>(len, reader)?);
         for _ in 0..len {
             let k = K::unpack(reader)?;
             let v = V::unpack(reader)?;
@@ -143,15 +147,17 @@ impl Encoder for DateTime<Utc> {
         *self == DateTime::<Utc>::default()
     }
 }
-/// Decodes a `chrono::DateTime<Utc>` from the senax binary format.
+/// Decodes a `chrono::DateTime<Utc>` from the senax binary format, or from an encoded
+/// `SystemTime`.
 #[cfg(feature = "chrono")]
 impl Decoder for DateTime<Utc> {
     fn decode(reader: &mut Bytes) -> Result<Self> {
         if reader.remaining() == 0 {
-            return Err(EncoderError::InsufficientData);
+            return Err(EncoderError::eof(1, 0));
         }
         let tag = reader.get_u8();
-        if tag != TAG_CHRONO_DATETIME {
+        // `SystemTime` values share the format
+        if tag != TAG_CHRONO_DATETIME && tag != TAG_SYSTEM_TIME {
             return Err(EncoderError::Decode(format!(
                 "Expected DateTime<Utc> tag ({}), got {}",
                 TAG_CHRONO_DATETIME, tag
@@ -190,7 +196,7 @@ impl Packer for DateTime<Utc> {
 impl Unpacker for DateTime<Utc> {
     fn unpack(reader: &mut Bytes) -> Result<Self> {
         if reader.remaining() == 0 {
-            return Err(EncoderError::InsufficientData);
+            return Err(EncoderError::eof(1, 0));
         }
         let tag = reader.get_u8();
         match tag {
@@ -233,7 +239,7 @@ impl Encoder for DateTime<Local> {
 impl Decoder for DateTime<Local> {
     fn decode(reader: &mut Bytes) -> Result<Self> {
         if reader.remaining() == 0 {
-            return Err(EncoderError::InsufficientData);
+            return Err(EncoderError::eof(1, 0));
         }
         let tag = reader.get_u8();
         if tag != TAG_CHRONO_DATETIME {
@@ -277,7 +283,7 @@ impl Packer for DateTime<Local> {
 impl Unpacker for DateTime<Local> {
     fn unpack(reader: &mut Bytes) -> Result<Self> {
         if reader.remaining() == 0 {
-            return Err(EncoderError::InsufficientData);
+            return Err(EncoderError::eof(1, 0));
         }
         let tag = reader.get_u8();
         match tag {
@@ -303,14 +309,21 @@ impl Unpacker for DateTime<Local> {
 }
 
 // --- NaiveDate ---
+/// Converts the stored days since 1970-01-01 (`NaiveDate::default()`) back to a date,
+/// failing for counts outside the range `NaiveDate` can represent.
+#[cfg(feature = "chrono")]
+fn naive_date_from_days(days: i64) -> Result<NaiveDate> {
+    chrono::TimeDelta::try_days(days)
+        .and_then(|delta| NaiveDate::default().checked_add_signed(delta))
+        .ok_or_else(|| EncoderError::Decode(format!("Invalid days from epoch: {}", days)))
+}
+
 #[cfg(feature = "chrono")]
 impl Encoder for NaiveDate {
     fn encode(&self, writer: &mut BytesMut) -> Result<()> {
         writer.put_u8(TAG_CHRONO_NAIVE_DATE);
         // Store as days since 1970-01-01
-        let days_from_epoch = self
-            .signed_duration_since(NaiveDate::from_ymd_opt(1970, 1, 1).unwrap())
-            .num_days();
+        let days_from_epoch = self.signed_duration_since(NaiveDate::default()).num_days();
         days_from_epoch.encode(writer)?;
         Ok(())
     }
@@ -323,7 +336,7 @@ impl Encoder for NaiveDate {
 impl Decoder for NaiveDate {
     fn decode(reader: &mut Bytes) -> Result<Self> {
         if reader.remaining() == 0 {
-            return Err(EncoderError::InsufficientData);
+            return Err(EncoderError::eof(1, 0));
         }
         let tag = reader.get_u8();
         if tag != TAG_CHRONO_NAIVE_DATE {
@@ -332,20 +345,14 @@ impl Decoder for NaiveDate {
                 TAG_CHRONO_NAIVE_DATE, tag
             )));
         }
-        let days_from_epoch = i64::decode(reader)?;
-        let epoch_date = NaiveDate::from_ymd_opt(1970, 1, 1).unwrap();
-        epoch_date
-            .checked_add_signed(chrono::TimeDelta::try_days(days_from_epoch).unwrap())
-            .ok_or_else(|| {
-                EncoderError::Decode(format!("Invalid days from epoch: {}", days_from_epoch))
-            })
+        naive_date_from_days(i64::decode(reader)?)
     }
 }
 #[cfg(feature = "chrono")]
 impl Unpacker for NaiveDate {
     fn unpack(reader: &mut Bytes) -> Result<Self> {
         if reader.remaining() == 0 {
-            return Err(EncoderError::InsufficientData);
+            return Err(EncoderError::eof(1, 0));
         }
         let tag = reader.get_u8();
         if tag != TAG_CHRONO_NAIVE_DATE {
@@ -354,13 +361,7 @@ impl Unpacker for NaiveDate {
                 TAG_CHRONO_NAIVE_DATE, tag
             )));
         }
-        let days_from_epoch = i64::unpack(reader)?;
-        let epoch_date = NaiveDate::from_ymd_opt(1970, 1, 1).unwrap();
-        epoch_date
-            .checked_add_signed(chrono::TimeDelta::try_days(days_from_epoch).unwrap())
-            .ok_or_else(|| {
-                EncoderError::Decode(format!("Invalid days from epoch: {}", days_from_epoch))
-            })
+        naive_date_from_days(i64::unpack(reader)?)
     }
 }
 #[cfg(feature = "chrono")]
@@ -368,9 +369,7 @@ impl Packer for NaiveDate {
     fn pack(&self, writer: &mut BytesMut) -> Result<()> {
         writer.put_u8(TAG_CHRONO_NAIVE_DATE);
         // Store as days since 1970-01-01
-        let days_from_epoch = self
-            .signed_duration_since(NaiveDate::from_ymd_opt(1970, 1, 1).unwrap())
-            .num_days();
+        let days_from_epoch = self.signed_duration_since(NaiveDate::default()).num_days();
         days_from_epoch.pack(writer)?;
         Ok(())
     }
@@ -397,7 +396,7 @@ impl Encoder for NaiveTime {
 impl Decoder for NaiveTime {
     fn decode(reader: &mut Bytes) -> Result<Self> {
         if reader.remaining() == 0 {
-            return Err(EncoderError::InsufficientData);
+            return Err(EncoderError::eof(1, 0));
         }
         let tag = reader.get_u8();
         if tag != TAG_CHRONO_NAIVE_TIME {
@@ -421,7 +420,7 @@ impl Decoder for NaiveTime {
 impl Unpacker for NaiveTime {
     fn unpack(reader: &mut Bytes) -> Result<Self> {
         if reader.remaining() == 0 {
-            return Err(EncoderError::InsufficientData);
+            return Err(EncoderError::eof(1, 0));
         }
         let tag = reader.get_u8();
         if tag != TAG_CHRONO_NAIVE_TIME {
@@ -476,7 +475,7 @@ impl Encoder for NaiveDateTime {
 impl Decoder for NaiveDateTime {
     fn decode(reader: &mut Bytes) -> Result<Self> {
         if reader.remaining() == 0 {
-            return Err(EncoderError::InsufficientData);
+            return Err(EncoderError::eof(1, 0));
         }
         let tag = reader.get_u8();
         if tag != TAG_CHRONO_NAIVE_DATETIME {
@@ -518,7 +517,7 @@ impl Packer for NaiveDateTime {
 impl Unpacker for NaiveDateTime {
     fn unpack(reader: &mut Bytes) -> Result<Self> {
         if reader.remaining() == 0 {
-            return Err(EncoderError::InsufficientData);
+            return Err(EncoderError::eof(1, 0));
         }
         let tag = reader.get_u8();
         match tag {
@@ -543,6 +542,341 @@ impl Unpacker for NaiveDateTime {
     }
 }
 
+// --- time::OffsetDateTime ---
+/// Converts seconds and nanoseconds since the Unix epoch to a UTC `OffsetDateTime`.
+#[cfg(feature = "time")]
+fn offset_date_time_from_timestamp(seconds: i64, nanos: u32) -> Result<OffsetDateTime> {
+    OffsetDateTime::from_unix_timestamp(seconds)
+        .ok()
+        .and_then(|dt| dt.replace_nanosecond(nanos).ok())
+        .ok_or_else(|| {
+            EncoderError::Decode(format!(
+                "Invalid timestamp: {} seconds, {} nanos",
+                seconds, nanos
+            ))
+        })
+}
+
+/// Encodes a `time::OffsetDateTime` as the instant it names, in the same format as
+/// `chrono::DateTime<Utc>`. The offset is not stored; values decode in UTC.
+#[cfg(feature = "time")]
+impl Encoder for OffsetDateTime {
+    fn encode(&self, writer: &mut BytesMut) -> Result<()> {
+        writer.put_u8(TAG_CHRONO_DATETIME);
+        self.unix_timestamp().encode(writer)?;
+        self.nanosecond().encode(writer)?;
+        Ok(())
+    }
+
+    fn is_default(&self) -> bool {
+        *self == OffsetDateTime::UNIX_EPOCH
+    }
+}
+/// Decodes a `time::OffsetDateTime` in UTC from an encoded `chrono::DateTime` or `SystemTime`.
+#[cfg(feature = "time")]
+impl Decoder for OffsetDateTime {
+    fn decode(reader: &mut Bytes) -> Result<Self> {
+        if reader.remaining() == 0 {
+            return Err(EncoderError::eof(1, 0));
+        }
+        let tag = reader.get_u8();
+        if tag != TAG_CHRONO_DATETIME && tag != TAG_SYSTEM_TIME {
+            return Err(EncoderError::Decode(format!(
+                "Expected OffsetDateTime tag ({}), got {}",
+                TAG_CHRONO_DATETIME, tag
+            )));
+        }
+        let timestamp_seconds = i64::decode(reader)?;
+        let timestamp_nanos = u32::decode(reader)?;
+        offset_date_time_from_timestamp(timestamp_seconds, timestamp_nanos)
+    }
+}
+/// Packs a `time::OffsetDateTime` like `chrono::DateTime<Utc>`, writing the Unix epoch as `TAG_NONE`.
+#[cfg(feature = "time")]
+impl Packer for OffsetDateTime {
+    fn pack(&self, writer: &mut BytesMut) -> Result<()> {
+        if *self == OffsetDateTime::UNIX_EPOCH {
+            writer.put_u8(TAG_NONE);
+        } else {
+            writer.put_u8(TAG_CHRONO_DATETIME);
+            self.unix_timestamp().pack(writer)?;
+            self.nanosecond().pack(writer)?;
+        }
+        Ok(())
+    }
+}
+#[cfg(feature = "time")]
+impl Unpacker for OffsetDateTime {
+    fn unpack(reader: &mut Bytes) -> Result<Self> {
+        if reader.remaining() == 0 {
+            return Err(EncoderError::eof(1, 0));
+        }
+        let tag = reader.get_u8();
+        match tag {
+            TAG_NONE => Ok(OffsetDateTime::UNIX_EPOCH),
+            TAG_CHRONO_DATETIME => {
+                let timestamp_seconds = i64::unpack(reader)?;
+                let timestamp_nanos = u32::unpack(reader)?;
+                offset_date_time_from_timestamp(timestamp_seconds, timestamp_nanos)
+            }
+            _ => Err(EncoderError::Decode(format!(
+                "Expected OffsetDateTime tag ({} or {}), got {}",
+                TAG_NONE, TAG_CHRONO_DATETIME, tag
+            ))),
+        }
+    }
+}
+
+// --- time::PrimitiveDateTime ---
+/// 1970-01-01 00:00:00, the default `chrono::NaiveDateTime`.
+#[cfg(feature = "time")]
+const PRIMITIVE_EPOCH: PrimitiveDateTime = PrimitiveDateTime::new(
+    OffsetDateTime::UNIX_EPOCH.date(),
+    OffsetDateTime::UNIX_EPOCH.time(),
+);
+
+#[cfg(feature = "time")]
+fn primitive_date_time_from_timestamp(seconds: i64, nanos: u32) -> Result<PrimitiveDateTime> {
+    let dt = offset_date_time_from_timestamp(seconds, nanos)?;
+    Ok(PrimitiveDateTime::new(dt.date(), dt.time()))
+}
+
+/// Encodes a `time::PrimitiveDateTime` in the same format as `chrono::NaiveDateTime`.
+#[cfg(feature = "time")]
+impl Encoder for PrimitiveDateTime {
+    fn encode(&self, writer: &mut BytesMut) -> Result<()> {
+        writer.put_u8(TAG_CHRONO_NAIVE_DATETIME);
+        // Store as seconds and nanoseconds since Unix epoch (1970-01-01 00:00:00)
+        let utc = self.assume_utc();
+        utc.unix_timestamp().encode(writer)?;
+        utc.nanosecond().encode(writer)?;
+        Ok(())
+    }
+
+    fn is_default(&self) -> bool {
+        *self == PRIMITIVE_EPOCH
+    }
+}
+#[cfg(feature = "time")]
+impl Decoder for PrimitiveDateTime {
+    fn decode(reader: &mut Bytes) -> Result<Self> {
+        if reader.remaining() == 0 {
+            return Err(EncoderError::eof(1, 0));
+        }
+        let tag = reader.get_u8();
+        if tag != TAG_CHRONO_NAIVE_DATETIME {
+            return Err(EncoderError::Decode(format!(
+                "Expected PrimitiveDateTime tag ({}), got {}",
+                TAG_CHRONO_NAIVE_DATETIME, tag
+            )));
+        }
+        let timestamp_seconds = i64::decode(reader)?;
+        let timestamp_nanos = u32::decode(reader)?;
+        primitive_date_time_from_timestamp(timestamp_seconds, timestamp_nanos)
+    }
+}
+#[cfg(feature = "time")]
+impl Packer for PrimitiveDateTime {
+    fn pack(&self, writer: &mut BytesMut) -> Result<()> {
+        if *self == PRIMITIVE_EPOCH {
+            writer.put_u8(TAG_NONE);
+        } else {
+            writer.put_u8(TAG_CHRONO_NAIVE_DATETIME);
+            let utc = self.assume_utc();
+            utc.unix_timestamp().pack(writer)?;
+            utc.nanosecond().pack(writer)?;
+        }
+        Ok(())
+    }
+}
+#[cfg(feature = "time")]
+impl Unpacker for PrimitiveDateTime {
+    fn unpack(reader: &mut Bytes) -> Result<Self> {
+        if reader.remaining() == 0 {
+            return Err(EncoderError::eof(1, 0));
+        }
+        let tag = reader.get_u8();
+        match tag {
+            TAG_NONE => Ok(PRIMITIVE_EPOCH),
+            TAG_CHRONO_NAIVE_DATETIME => {
+                let timestamp_seconds = i64::unpack(reader)?;
+                let timestamp_nanos = u32::unpack(reader)?;
+                primitive_date_time_from_timestamp(timestamp_seconds, timestamp_nanos)
+            }
+            _ => Err(EncoderError::Decode(format!(
+                "Expected PrimitiveDateTime tag ({} or {}), got {}",
+                TAG_NONE, TAG_CHRONO_NAIVE_DATETIME, tag
+            ))),
+        }
+    }
+}
+
+// --- time::Date ---
+/// The Julian day number of 1970-01-01, from which dates are stored as a day count.
+#[cfg(feature = "time")]
+const UNIX_EPOCH_JULIAN_DAY: i32 = OffsetDateTime::UNIX_EPOCH.date().to_julian_day();
+
+#[cfg(feature = "time")]
+fn date_to_days(date: &Date) -> i64 {
+    (date.to_julian_day() - UNIX_EPOCH_JULIAN_DAY) as i64
+}
+
+/// Converts the stored days since 1970-01-01 back to a date, failing for counts outside the
+/// range `Date` can represent.
+#[cfg(feature = "time")]
+fn date_from_days(days: i64) -> Result<Date> {
+    i32::try_from(days)
+        .ok()
+        .and_then(|days| days.checked_add(UNIX_EPOCH_JULIAN_DAY))
+        .and_then(|julian_day| Date::from_julian_day(julian_day).ok())
+        .ok_or_else(|| EncoderError::Decode(format!("Invalid days from epoch: {}", days)))
+}
+
+/// Encodes a `time::Date` in the same format as `chrono::NaiveDate`.
+#[cfg(feature = "time")]
+impl Encoder for Date {
+    fn encode(&self, writer: &mut BytesMut) -> Result<()> {
+        writer.put_u8(TAG_CHRONO_NAIVE_DATE);
+        // Store as days since 1970-01-01
+        date_to_days(self).encode(writer)?;
+        Ok(())
+    }
+
+    fn is_default(&self) -> bool {
+        date_to_days(self) == 0
+    }
+}
+#[cfg(feature = "time")]
+impl Decoder for Date {
+    fn decode(reader: &mut Bytes) -> Result<Self> {
+        if reader.remaining() == 0 {
+            return Err(EncoderError::eof(1, 0));
+        }
+        let tag = reader.get_u8();
+        if tag != TAG_CHRONO_NAIVE_DATE {
+            return Err(EncoderError::Decode(format!(
+                "Expected Date tag ({}), got {}",
+                TAG_CHRONO_NAIVE_DATE, tag
+            )));
+        }
+        date_from_days(i64::decode(reader)?)
+    }
+}
+#[cfg(feature = "time")]
+impl Packer for Date {
+    fn pack(&self, writer: &mut BytesMut) -> Result<()> {
+        writer.put_u8(TAG_CHRONO_NAIVE_DATE);
+        date_to_days(self).pack(writer)?;
+        Ok(())
+    }
+}
+#[cfg(feature = "time")]
+impl Unpacker for Date {
+    fn unpack(reader: &mut Bytes) -> Result<Self> {
+        if reader.remaining() == 0 {
+            return Err(EncoderError::eof(1, 0));
+        }
+        let tag = reader.get_u8();
+        if tag != TAG_CHRONO_NAIVE_DATE {
+            return Err(EncoderError::Decode(format!(
+                "Expected Date tag ({}), got {}",
+                TAG_CHRONO_NAIVE_DATE, tag
+            )));
+        }
+        date_from_days(i64::unpack(reader)?)
+    }
+}
+
+// --- time::Time ---
+#[cfg(feature = "time")]
+fn time_to_seconds(time: &Time) -> u32 {
+    time.hour() as u32 * 3600 + time.minute() as u32 * 60 + time.second() as u32
+}
+
+/// Converts the stored seconds from midnight and nanoseconds back to a time. Leap seconds,
+/// which `chrono::NaiveTime` writes as a nanosecond count of a second or more, are rejected.
+#[cfg(feature = "time")]
+fn time_from_seconds(seconds: u32, nanos: u32) -> Result<Time> {
+    let time = if seconds < 86_400 {
+        Time::from_hms_nano(
+            (seconds / 3600) as u8,
+            (seconds / 60 % 60) as u8,
+            (seconds % 60) as u8,
+            nanos,
+        )
+        .ok()
+    } else {
+        None
+    };
+    time.ok_or_else(|| {
+        EncoderError::Decode(format!(
+            "Invalid seconds from midnight: {}, nanoseconds: {}",
+            seconds, nanos
+        ))
+    })
+}
+
+/// Encodes a `time::Time` in the same format as `chrono::NaiveTime`.
+#[cfg(feature = "time")]
+impl Encoder for Time {
+    fn encode(&self, writer: &mut BytesMut) -> Result<()> {
+        writer.put_u8(TAG_CHRONO_NAIVE_TIME);
+        // Store seconds and nanoseconds from 00:00:00 separately
+        time_to_seconds(self).encode(writer)?;
+        self.nanosecond().encode(writer)?;
+        Ok(())
+    }
+
+    fn is_default(&self) -> bool {
+        *self == Time::MIDNIGHT
+    }
+}
+#[cfg(feature = "time")]
+impl Decoder for Time {
+    fn decode(reader: &mut Bytes) -> Result<Self> {
+        if reader.remaining() == 0 {
+            return Err(EncoderError::eof(1, 0));
+        }
+        let tag = reader.get_u8();
+        if tag != TAG_CHRONO_NAIVE_TIME {
+            return Err(EncoderError::Decode(format!(
+                "Expected Time tag ({}), got {}",
+                TAG_CHRONO_NAIVE_TIME, tag
+            )));
+        }
+        let seconds_from_midnight = u32::decode(reader)?;
+        let nanoseconds = u32::decode(reader)?;
+        time_from_seconds(seconds_from_midnight, nanoseconds)
+    }
+}
+#[cfg(feature = "time")]
+impl Packer for Time {
+    fn pack(&self, writer: &mut BytesMut) -> Result<()> {
+        writer.put_u8(TAG_CHRONO_NAIVE_TIME);
+        time_to_seconds(self).pack(writer)?;
+        self.nanosecond().pack(writer)?;
+        Ok(())
+    }
+}
+#[cfg(feature = "time")]
+impl Unpacker for Time {
+    fn unpack(reader: &mut Bytes) -> Result<Self> {
+        if reader.remaining() == 0 {
+            return Err(EncoderError::eof(1, 0));
+        }
+        let tag = reader.get_u8();
+        if tag != TAG_CHRONO_NAIVE_TIME {
+            return Err(EncoderError::Decode(format!(
+                "Expected Time tag ({}), got {}",
+                TAG_CHRONO_NAIVE_TIME, tag
+            )));
+        }
+        let seconds_from_midnight = u32::unpack(reader)?;
+        let nanoseconds = u32::unpack(reader)?;
+        time_from_seconds(seconds_from_midnight, nanoseconds)
+    }
+}
+
 // --- Decimal ---
 #[cfg(feature = "rust_decimal")]
 impl Encoder for Decimal {
@@ -571,10 +905,11 @@ impl Decoder for Decimal {
     /// This decoder supports:
     /// - New string format (TAG_STRING_BASE..TAG_STRING_LONG)
     /// - Legacy binary format (TAG_DECIMAL + mantissa + scale)
-    /// - i128 cross-decode (TAG_ZERO..TAG_U128, TAG_NEGATIVE)
+    /// - i128 cross-decode (TAG_ZERO..TAG_U128, TAG_NEGATIVE), failing for integers beyond
+    ///   the 96-bit mantissa
     fn decode(reader: &mut Bytes) -> Result<Self> {
         if reader.remaining() == 0 {
-            return Err(EncoderError::InsufficientData);
+            return Err(EncoderError::eof(1, 0));
         }
 
         // Peek at the tag to determine format
@@ -588,10 +923,12 @@ impl Decoder for Decimal {
             });
         }
 
-        // Try i128 cross-decode
+        // Try i128 cross-decode; Decimal holds 96-bit mantissas, so larger integers fail
         if tag == TAG_NEGATIVE || (TAG_ZERO..=TAG_U128).contains(&tag) {
             let i128_val = i128::decode(reader)?;
-            return Ok(Decimal::from(i128_val));
+            return Decimal::try_from_i128_with_scale(i128_val, 0).map_err(|_| {
+                EncoderError::Decode(format!("Integer {} out of range for Decimal", i128_val))
+            });
         }
 
         // Fall back to legacy binary format for backward compatibility
@@ -648,7 +985,7 @@ impl Decoder for BigDecimal {
     /// - i128 cross-decode (TAG_ZERO..TAG_U128, TAG_NEGATIVE)
     fn decode(reader: &mut Bytes) -> Result<Self> {
         if reader.remaining() == 0 {
-            return Err(EncoderError::InsufficientData);
+            return Err(EncoderError::eof(1, 0));
         }
 
         // Peek at the tag to determine format
@@ -706,7 +1043,7 @@ impl Packer for Uuid {
 impl Decoder for Uuid {
     fn decode(reader: &mut Bytes) -> Result<Self> {
         if reader.remaining() == 0 {
-            return Err(EncoderError::InsufficientData);
+            return Err(EncoderError::eof(1, 0));
         }
         let tag = reader.get_u8();
         if tag != TAG_UUID {
@@ -716,7 +1053,7 @@ impl Decoder for Uuid {
             )));
         }
         if reader.remaining() < 16 {
-            return Err(EncoderError::InsufficientData);
+            return Err(EncoderError::eof(16, reader.remaining()));
         }
         let uuid_u128 = reader.get_u128_le();
         Ok(Uuid::from_u128(uuid_u128))
@@ -726,14 +1063,14 @@ impl Decoder for Uuid {
 impl Unpacker for Uuid {
     fn unpack(reader: &mut Bytes) -> Result<Self> {
         if reader.remaining() == 0 {
-            return Err(EncoderError::InsufficientData);
+            return Err(EncoderError::eof(1, 0));
         }
         let tag = reader.get_u8();
         match tag {
             TAG_NONE => Ok(Uuid::default()),
             TAG_UUID => {
                 if reader.remaining() < 16 {
-                    return Err(EncoderError::InsufficientData);
+                    return Err(EncoderError::eof(16, reader.remaining()));
                 }
                 let uuid_u128 = reader.get_u128_le();
                 Ok(Uuid::from_u128(uuid_u128))
@@ -779,7 +1116,7 @@ impl Packer for Ulid {
 impl Decoder for Ulid {
     fn decode(reader: &mut Bytes) -> Result<Self> {
         if reader.remaining() == 0 {
-            return Err(EncoderError::InsufficientData);
+            return Err(EncoderError::eof(1, 0));
         }
         let tag = reader.get_u8();
         if tag != TAG_UUID {
@@ -789,7 +1126,7 @@ impl Decoder for Ulid {
             )));
         }
         if reader.remaining() < 16 {
-            return Err(EncoderError::InsufficientData);
+            return Err(EncoderError::eof(16, reader.remaining()));
         }
         let ulid_u128 = reader.get_u128_le();
         Ok(Ulid(ulid_u128))
@@ -799,14 +1136,14 @@ impl Decoder for Ulid {
 impl Unpacker for Ulid {
     fn unpack(reader: &mut Bytes) -> Result<Self> {
         if reader.remaining() == 0 {
-            return Err(EncoderError::InsufficientData);
+            return Err(EncoderError::eof(1, 0));
         }
         let tag = reader.get_u8();
         match tag {
             TAG_NONE => Ok(Ulid::default()),
             TAG_UUID => {
                 if reader.remaining() < 16 {
-                    return Err(EncoderError::InsufficientData);
+                    return Err(EncoderError::eof(16, reader.remaining()));
                 }
                 let ulid_u128 = reader.get_u128_le();
                 Ok(Ulid(ulid_u128))
@@ -820,61 +1157,62 @@ impl Unpacker for Ulid {
 }
 
 // --- serde_json::Value ---
+/// An array or object whose elements are still being written by the `Value` encoder.
+#[cfg(feature = "serde_json")]
+enum JsonEncodeFrame<'a> {
+    Array(::core::slice::Iter<'a, Value>),
+    Object(serde_json::map::Iter<'a>),
+}
+
+/// Encodes a JSON value with an explicit stack instead of recursion, so deeply nested values
+/// cannot overflow the call stack.
 #[cfg(feature = "serde_json")]
 impl Encoder for Value {
     fn encode(&self, writer: &mut BytesMut) -> Result<()> {
-        match self {
-            Value::Null => {
-                writer.put_u8(TAG_JSON_NULL);
-                Ok(())
-            }
-            Value::Bool(b) => {
-                writer.put_u8(TAG_JSON_BOOL);
-                b.encode(writer)?;
-                Ok(())
-            }
-            Value::Number(n) => {
-                writer.put_u8(TAG_JSON_NUMBER);
-                // Preserve integer/float distinction where possible
-                if let Some(u) = n.as_u64() {
-                    // Encode as tagged unsigned integer
-                    writer.put_u8(0); // Unsigned integer (u64) marker
-                    u.encode(writer)?;
-                } else if let Some(i) = n.as_i64() {
-                    // Encode as tagged signed integer
-                    writer.put_u8(1); // Signed integer (i64) marker
-                    i.encode(writer)?;
-                } else {
-                    // Encode as float
-                    writer.put_u8(2); // Float marker
-                    let float_val = n.as_f64().unwrap_or(0.0);
-                    float_val.encode(writer)?;
-                }
-                Ok(())
-            }
-            Value::String(s) => {
-                writer.put_u8(TAG_JSON_STRING);
-                s.encode(writer)?;
-                Ok(())
-            }
-            Value::Array(arr) => {
-                writer.put_u8(TAG_JSON_ARRAY);
-                let len = arr.len();
-                len.encode(writer)?;
-                for item in arr {
-                    item.encode(writer)?;
+        let mut stack: Vec<JsonEncodeFrame> = Vec::new();
+        let mut next = Some(self);
+        loop {
+            if let Some(value) = next.take() {
+                match value {
+                    Value::Null => writer.put_u8(TAG_JSON_NULL),
+                    Value::Bool(b) => {
+                        writer.put_u8(TAG_JSON_BOOL);
+                        b.encode(writer)?;
+                    }
+                    Value::Number(n) => encode_json_number(n, writer)?,
+                    Value::String(s) => {
+                        writer.put_u8(TAG_JSON_STRING);
+                        s.encode(writer)?;
+                    }
+                    Value::Array(arr) => {
+                        writer.put_u8(TAG_JSON_ARRAY);
+                        arr.len().encode(writer)?;
+                        stack.push(JsonEncodeFrame::Array(arr.iter()));
+                    }
+                    Value::Object(obj) => {
+                        writer.put_u8(TAG_JSON_OBJECT);
+                        obj.len().encode(writer)?;
+                        stack.push(JsonEncodeFrame::Object(obj.iter()));
+                    }
                 }
-                Ok(())
             }
-            Value::Object(obj) => {
-                writer.put_u8(TAG_JSON_OBJECT);
-                let len = obj.len();
-                len.encode(writer)?;
-                for (key, value) in obj {
-                    key.encode(writer)?;
-                    value.encode(writer)?;
-                }
-                Ok(())
+            match stack.last_mut() {
+                None => return Ok(()),
+                Some(JsonEncodeFrame::Array(items)) => match items.next() {
+                    Some(item) => next = Some(item),
+                    None => {
+                        stack.pop();
+                    }
+                },
+                Some(JsonEncodeFrame::Object(entries)) => match entries.next() {
+                    Some((key, value)) => {
+                        key.encode(writer)?;
+                        next = Some(value);
+                    }
+                    None => {
+                        stack.pop();
+                    }
+                },
             }
         }
     }
@@ -884,6 +1222,31 @@ impl Encoder for Value {
     }
 }
 
+#[cfg(feature = "serde_json")]
+fn encode_json_number(n: &Number, writer: &mut BytesMut) -> Result<()> {
+    writer.put_u8(TAG_JSON_NUMBER);
+    // Preserve integer/float distinction where possible
+    if let Some(u) = n.as_u64() {
+        // Encode as tagged unsigned integer
+        writer.put_u8(0); // Unsigned integer (u64) marker
+        u.encode(writer)?;
+    } else if let Some(i) = n.as_i64() {
+        // Encode as tagged signed integer
+        writer.put_u8(1); // Signed integer (i64) marker
+        i.encode(writer)?;
+    } else if cfg!(feature = "arbitrary_precision") {
+        // Keep the exact decimal text instead of rounding to f64
+        writer.put_u8(3); // Decimal string marker
+        write_str(&n.to_string(), writer)?;
+    } else {
+        // Encode as float
+        writer.put_u8(2); // Float marker
+        let float_val = n.as_f64().unwrap_or(0.0);
+        float_val.encode(writer)?;
+    }
+    Ok(())
+}
+
 #[cfg(feature = "serde_json")]
 impl Packer for Value {
     fn pack(&self, writer: &mut BytesMut) -> Result<()> {
@@ -891,69 +1254,453 @@ impl Packer for Value {
     }
 }
 
+/// An array or object whose elements are still being read by the `Value` decoder.
+#[cfg(feature = "serde_json")]
+enum JsonDecodeFrame {
+    Array {
+        items: Vec<Value>,
+        remaining: usize,
+    },
+    Object {
+        entries: Map<String, Value>,
+        remaining: usize,
+        key: String,
+    },
+}
+
+/// Decodes a JSON value with an explicit stack instead of recursion.
+///
+/// Each open array or object counts as one nesting level; payloads nested deeper than
+/// [`DecodeConfig::max_depth`](crate::DecodeConfig::max_depth) fail with
+/// `EncoderError::DepthLimitExceeded`, so attacker-controlled JSON can neither overflow the
+/// stack while decoding nor produce a value too deep to drop.
 #[cfg(feature = "serde_json")]
 impl Decoder for Value {
     fn decode(reader: &mut Bytes) -> Result<Self> {
-        if reader.remaining() == 0 {
-            return Err(EncoderError::InsufficientData);
-        }
-        let tag = reader.get_u8();
-        match tag {
-            TAG_JSON_NULL => Ok(Value::Null),
-            TAG_JSON_BOOL => {
-                let b = bool::decode(reader)?;
-                Ok(Value::Bool(b))
+        let max_depth = current_decode_config().max_depth;
+        let mut stack: Vec<JsonDecodeFrame> = Vec::new();
+        loop {
+            if let Some(JsonDecodeFrame::Object { key, .. }) = stack.last_mut() {
+                *key = String::decode(reader)?;
             }
-            TAG_JSON_NUMBER => {
-                if reader.remaining() == 0 {
-                    return Err(EncoderError::InsufficientData);
-                }
-                let number_type = reader.get_u8();
-                match number_type {
-                    0 => {
-                        // Unsigned integer
-                        let u = u64::decode(reader)?;
-                        Ok(Value::Number(Number::from(u)))
+            if reader.remaining() == 0 {
+                return Err(EncoderError::eof(1, 0));
+            }
+            let tag = reader.get_u8();
+            let mut value = match tag {
+                TAG_JSON_ARRAY | TAG_JSON_OBJECT => {
+                    let len = usize::decode(reader)?;
+                    if len > 0 {
+                        if stack.len() >= max_depth {
+                            return Err(EncoderError::DepthLimitExceeded { limit: max_depth });
+                        }
+                        stack.push(if tag == TAG_JSON_ARRAY {
+                            JsonDecodeFrame::Array {
+                                items: Vec::with_capacity(collection_capacity::<Value>(
+                                    len, reader,
+                                )?),
+                                remaining: len,
+                            }
+                        } else {
+                            JsonDecodeFrame::Object {
+                                entries: Map::with_capacity(
+                                    collection_capacity::<(String, Value)>(len, reader)?,
+                                ),
+                                remaining: len,
+                                key: String::new(),
+                            }
+                        });
+                        continue;
                     }
-                    1 => {
-                        // Signed integer
-                        let i = i64::decode(reader)?;
-                        Ok(Value::Number(Number::from(i)))
+                    if tag == TAG_JSON_ARRAY {
+                        Value::Array(Vec::new())
+                    } else {
+                        Value::Object(Map::new())
                     }
-                    2 => {
-                        // Float
-                        let f = f64::decode(reader)?;
-                        Ok(Value::Number(
-                            Number::from_f64(f).unwrap_or(Number::from(0)),
-                        ))
+                }
+                _ => decode_json_scalar(tag, reader)?,
+            };
+            // Hand the value to its parent, closing every container it completes
+            loop {
+                match stack.last_mut() {
+                    None => return Ok(value),
+                    Some(JsonDecodeFrame::Array { items, remaining }) => {
+                        items.push(value);
+                        *remaining -= 1;
+                        if *remaining > 0 {
+                            break;
+                        }
+                        value = Value::Array(::core::mem::take(items));
+                    }
+                    Some(JsonDecodeFrame::Object {
+                        entries,
+                        remaining,
+                        key,
+                    }) => {
+                        entries.insert(::core::mem::take(key), value);
+                        *remaining -= 1;
+                        if *remaining > 0 {
+                            break;
+                        }
+                        value = Value::Object(::core::mem::take(entries));
                     }
-                    _ => Err(EncoderError::Decode(format!(
-                        "Invalid JSON Number type marker: {}",
-                        number_type
-                    ))),
                 }
+                stack.pop();
             }
-            TAG_JSON_STRING => {
-                let s = String::decode(reader)?;
-                Ok(Value::String(s))
+        }
+    }
+}
+
+/// Decodes a JSON value that is not an array or object, after its tag.
+#[cfg(feature = "serde_json")]
+fn decode_json_scalar(tag: u8, reader: &mut Bytes) -> Result<Value> {
+    match tag {
+        TAG_JSON_NULL => Ok(Value::Null),
+        TAG_JSON_BOOL => {
+            let b = bool::decode(reader)?;
+            Ok(Value::Bool(b))
+        }
+        TAG_JSON_NUMBER => {
+            if reader.remaining() == 0 {
+                return Err(EncoderError::eof(1, 0));
             }
-            TAG_JSON_ARRAY => {
-                let len = usize::decode(reader)?;
-                let mut arr = Vec::with_capacity(len);
-                for _ in 0..len {
-                    arr.push(Value::decode(reader)?);
+            let number_type = reader.get_u8();
+            match number_type {
+                0 => {
+                    // Unsigned integer
+                    let u = u64::decode(reader)?;
+                    Ok(Value::Number(Number::from(u)))
                 }
-                Ok(Value::Array(arr))
-            }
+                1 => {
+                    // Signed integer
+                    let i = i64::decode(reader)?;
+                    Ok(Value::Number(Number::from(i)))
+                }
+                2 => {
+                    // Float
+                    let f = f64::decode(reader)?;
+                    Ok(Value::Number(
+                        Number::from_f64(f).unwrap_or(Number::from(0)),
+                    ))
+                }
+                3 => {
+                    // Decimal string (exact with serde_json's arbitrary_precision)
+                    let s = String::decode(reader)?;
+                    s.parse::<Number>().map(Value::Number).map_err(|e| {
+                        EncoderError::Decode(format!("Invalid JSON number '{}': {}", s, e))
+                    })
+                }
+                _ => Err(EncoderError::Decode(format!(
+                    "Invalid JSON Number type marker: {}",
+                    number_type
+                ))),
+            }
+        }
+        TAG_JSON_STRING => {
+            let s = String::decode(reader)?;
+            Ok(Value::String(s))
+        }
+        _ => Err(EncoderError::Decode(format!(
+            "Expected JSON Value tag (202-207), got {}",
+            tag
+        ))),
+    }
+}
+
+#[cfg(feature = "serde_json")]
+impl Unpacker for Value {
+    fn unpack(reader: &mut Bytes) -> Result<Self> {
+        Self::decode(reader)
+    }
+}
+
+// --- serde_yaml::Value / toml::Value ---
+/// A `TAG_JSON_NUMBER` payload, read by the YAML and TOML decoders.
+#[cfg(any(feature = "serde_yaml", feature = "toml"))]
+enum JsonNumber {
+    UInt(u64),
+    Int(i64),
+    Float(f64),
+    Decimal(String),
+}
+
+#[cfg(any(feature = "serde_yaml", feature = "toml"))]
+fn read_json_number(reader: &mut Bytes) -> Result<JsonNumber> {
+    if reader.remaining() == 0 {
+        return Err(EncoderError::eof(1, 0));
+    }
+    match reader.get_u8() {
+        0 => Ok(JsonNumber::UInt(u64::decode(reader)?)),
+        1 => Ok(JsonNumber::Int(i64::decode(reader)?)),
+        2 => Ok(JsonNumber::Float(f64::decode(reader)?)),
+        3 => Ok(JsonNumber::Decimal(String::decode(reader)?)),
+        number_type => Err(EncoderError::Decode(format!(
+            "Invalid JSON Number type marker: {}",
+            number_type
+        ))),
+    }
+}
+
+/// Encodes a YAML value with the `serde_json::Value` tags.
+///
+/// Mapping keys that are strings are written like JSON object keys; other keys are written as
+/// YAML values. A tagged value is written as a single-entry mapping from `!Tag` to the value (as
+/// serde represents it) and decodes as that mapping.
+#[cfg(feature = "serde_yaml")]
+impl Encoder for serde_yaml::Value {
+    fn encode(&self, writer: &mut BytesMut) -> Result<()> {
+        use serde_yaml::Value as Yaml;
+        match self {
+            Yaml::Null => writer.put_u8(TAG_JSON_NULL),
+            Yaml::Bool(b) => {
+                writer.put_u8(TAG_JSON_BOOL);
+                b.encode(writer)?;
+            }
+            Yaml::Number(n) => {
+                writer.put_u8(TAG_JSON_NUMBER);
+                if let Some(u) = n.as_u64() {
+                    writer.put_u8(0); // Unsigned integer (u64) marker
+                    u.encode(writer)?;
+                } else if let Some(i) = n.as_i64() {
+                    writer.put_u8(1); // Signed integer (i64) marker
+                    i.encode(writer)?;
+                } else {
+                    writer.put_u8(2); // Float marker
+                    n.as_f64().unwrap_or(0.0).encode(writer)?;
+                }
+            }
+            Yaml::String(s) => {
+                writer.put_u8(TAG_JSON_STRING);
+                s.encode(writer)?;
+            }
+            Yaml::Sequence(seq) => {
+                writer.put_u8(TAG_JSON_ARRAY);
+                seq.len().encode(writer)?;
+                for item in seq {
+                    item.encode(writer)?;
+                }
+            }
+            Yaml::Mapping(map) => {
+                writer.put_u8(TAG_JSON_OBJECT);
+                map.len().encode(writer)?;
+                for (key, value) in map {
+                    match key {
+                        // Plain string, as for JSON object keys
+                        Yaml::String(key) => key.encode(writer)?,
+                        key => key.encode(writer)?,
+                    }
+                    value.encode(writer)?;
+                }
+            }
+            Yaml::Tagged(tagged) => {
+                writer.put_u8(TAG_JSON_OBJECT);
+                1usize.encode(writer)?;
+                tagged.tag.to_string().encode(writer)?;
+                tagged.value.encode(writer)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn is_default(&self) -> bool {
+        self.is_null()
+    }
+}
+
+#[cfg(feature = "serde_yaml")]
+impl Packer for serde_yaml::Value {
+    fn pack(&self, writer: &mut BytesMut) -> Result<()> {
+        self.encode(writer)
+    }
+}
+
+#[cfg(feature = "serde_yaml")]
+impl Decoder for serde_yaml::Value {
+    fn decode(reader: &mut Bytes) -> Result<Self> {
+        use serde_yaml::{Mapping, Number, Value as Yaml};
+        if reader.remaining() == 0 {
+            return Err(EncoderError::eof(1, 0));
+        }
+        let tag = reader.get_u8();
+        match tag {
+            TAG_JSON_NULL => Ok(Yaml::Null),
+            TAG_JSON_BOOL => Ok(Yaml::Bool(bool::decode(reader)?)),
+            TAG_JSON_NUMBER => {
+                let number = match read_json_number(reader)? {
+                    JsonNumber::UInt(u) => Number::from(u),
+                    JsonNumber::Int(i) => Number::from(i),
+                    JsonNumber::Float(f) => Number::from(f),
+                    JsonNumber::Decimal(s) => s.parse().map_err(|e| {
+                        EncoderError::Decode(format!("Invalid YAML number '{}': {}", s, e))
+                    })?,
+                };
+                Ok(Yaml::Number(number))
+            }
+            TAG_JSON_STRING => Ok(Yaml::String(String::decode(reader)?)),
+            TAG_JSON_ARRAY => {
+                let len = usize::decode(reader)?;
+                let mut seq = Vec::with_capacity(collection_capacity::<Yaml>(len, reader)?);
+                for _ in 0..len {
+                    seq.push(Yaml::decode(reader)?);
+                }
+                Ok(Yaml::Sequence(seq))
+            }
+            TAG_JSON_OBJECT => {
+                let len = usize::decode(reader)?;
+                let mut map =
+                    Mapping::with_capacity(collection_capacity::<(Yaml, Yaml)>(len, reader)?);
+                for _ in 0..len {
+                    let key = match reader.chunk().first() {
+                        Some(TAG_STRING_BASE..=TAG_STRING_LONG) => {
+                            Yaml::String(String::decode(reader)?)
+                        }
+                        _ => Yaml::decode(reader)?,
+                    };
+                    let value = Yaml::decode(reader)?;
+                    map.insert(key, value);
+                }
+                Ok(Yaml::Mapping(map))
+            }
+            _ => Err(EncoderError::Decode(format!(
+                "Expected JSON Value tag (202-207), got {}",
+                tag
+            ))),
+        }
+    }
+}
+
+#[cfg(feature = "serde_yaml")]
+impl Unpacker for serde_yaml::Value {
+    fn unpack(reader: &mut Bytes) -> Result<Self> {
+        Self::decode(reader)
+    }
+}
+
+/// Key of the single-entry table that stands for a TOML datetime (as in toml's serde support).
+#[cfg(feature = "toml")]
+const TOML_DATETIME_FIELD: &str = "$__toml_private_datetime";
+
+/// Encodes a TOML value with the `serde_json::Value` tags.
+///
+/// A datetime is written as a single-entry table from `$__toml_private_datetime` to its RFC 3339
+/// text, as toml's serde support represents it, and decodes back to a datetime.
+#[cfg(feature = "toml")]
+impl Encoder for toml::Value {
+    fn encode(&self, writer: &mut BytesMut) -> Result<()> {
+        use toml::Value as Toml;
+        match self {
+            Toml::String(s) => {
+                writer.put_u8(TAG_JSON_STRING);
+                s.encode(writer)?;
+            }
+            Toml::Integer(i) => {
+                writer.put_u8(TAG_JSON_NUMBER);
+                if *i >= 0 {
+                    writer.put_u8(0); // Unsigned integer (u64) marker
+                    (*i as u64).encode(writer)?;
+                } else {
+                    writer.put_u8(1); // Signed integer (i64) marker
+                    i.encode(writer)?;
+                }
+            }
+            Toml::Float(f) => {
+                writer.put_u8(TAG_JSON_NUMBER);
+                writer.put_u8(2); // Float marker
+                f.encode(writer)?;
+            }
+            Toml::Boolean(b) => {
+                writer.put_u8(TAG_JSON_BOOL);
+                b.encode(writer)?;
+            }
+            Toml::Datetime(dt) => {
+                writer.put_u8(TAG_JSON_OBJECT);
+                1usize.encode(writer)?;
+                TOML_DATETIME_FIELD.to_string().encode(writer)?;
+                writer.put_u8(TAG_JSON_STRING);
+                dt.to_string().encode(writer)?;
+            }
+            Toml::Array(array) => {
+                writer.put_u8(TAG_JSON_ARRAY);
+                array.len().encode(writer)?;
+                for item in array {
+                    item.encode(writer)?;
+                }
+            }
+            Toml::Table(table) => {
+                writer.put_u8(TAG_JSON_OBJECT);
+                table.len().encode(writer)?;
+                for (key, value) in table {
+                    key.encode(writer)?;
+                    value.encode(writer)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// `toml::Value` has no default value, so this is always `false`.
+    fn is_default(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(feature = "toml")]
+impl Packer for toml::Value {
+    fn pack(&self, writer: &mut BytesMut) -> Result<()> {
+        self.encode(writer)
+    }
+}
+
+#[cfg(feature = "toml")]
+impl Decoder for toml::Value {
+    fn decode(reader: &mut Bytes) -> Result<Self> {
+        use toml::Value as Toml;
+        if reader.remaining() == 0 {
+            return Err(EncoderError::eof(1, 0));
+        }
+        let tag = reader.get_u8();
+        match tag {
+            TAG_JSON_NULL => Err(EncoderError::Decode("TOML has no null value".to_string())),
+            TAG_JSON_BOOL => Ok(Toml::Boolean(bool::decode(reader)?)),
+            TAG_JSON_NUMBER => match read_json_number(reader)? {
+                JsonNumber::UInt(u) => i64::try_from(u).map(Toml::Integer).map_err(|_| {
+                    EncoderError::Decode(format!("Integer {} out of range for TOML", u))
+                }),
+                JsonNumber::Int(i) => Ok(Toml::Integer(i)),
+                JsonNumber::Float(f) => Ok(Toml::Float(f)),
+                JsonNumber::Decimal(s) => s
+                    .parse()
+                    .map(Toml::Integer)
+                    .or_else(|_| s.parse().map(Toml::Float))
+                    .map_err(|_| EncoderError::Decode(format!("Invalid TOML number '{}'", s))),
+            },
+            TAG_JSON_STRING => Ok(Toml::String(String::decode(reader)?)),
+            TAG_JSON_ARRAY => {
+                let len = usize::decode(reader)?;
+                let mut array = Vec::with_capacity(collection_capacity::<Toml>(len, reader)?);
+                for _ in 0..len {
+                    array.push(Toml::decode(reader)?);
+                }
+                Ok(Toml::Array(array))
+            }
             TAG_JSON_OBJECT => {
                 let len = usize::decode(reader)?;
-                let mut obj = Map::with_capacity(len);
+                collection_capacity::<(String, Toml)>(len, reader)?;
+                let mut table = toml::Table::new();
                 for _ in 0..len {
                     let key = String::decode(reader)?;
-                    let value = Value::decode(reader)?;
-                    obj.insert(key, value);
+                    let value = Toml::decode(reader)?;
+                    table.insert(key, value);
                 }
-                Ok(Value::Object(obj))
+                if let Some(Toml::String(s)) = table.get(TOML_DATETIME_FIELD) {
+                    if table.len() == 1 {
+                        let dt = s.parse().map_err(|e| {
+                            EncoderError::Decode(format!("Invalid TOML datetime '{}': {}", s, e))
+                        })?;
+                        return Ok(Toml::Datetime(dt));
+                    }
+                }
+                Ok(Toml::Table(table))
             }
             _ => Err(EncoderError::Decode(format!(
                 "Expected JSON Value tag (202-207), got {}",
@@ -963,8 +1710,8 @@ impl Decoder for Value {
     }
 }
 
-#[cfg(feature = "serde_json")]
-impl Unpacker for Value {
+#[cfg(feature = "toml")]
+impl Unpacker for toml::Value {
     fn unpack(reader: &mut Bytes) -> Result<Self> {
         Self::decode(reader)
     }
@@ -974,14 +1721,7 @@ impl Unpacker for Value {
 #[cfg(feature = "fxhash")]
 impl<K: Encoder + Eq + std::hash::Hash, V: Encoder> Encoder for FxHashMap<K, V> {
     fn encode(&self, writer: &mut BytesMut) -> Result<()> {
-        writer.put_u8(TAG_MAP);
-        let len = self.len();
-        len.encode(writer)?;
-        for (k, v) in self {
-            k.encode(writer)?;
-            v.encode(writer)?;
-        }
-        Ok(())
+        write_hash_map_entries(&[TAG_MAP], self.iter(), writer, K::encode, V::encode)
     }
 
     fn is_default(&self) -> bool {
@@ -991,21 +1731,15 @@ impl<K: Encoder + Eq + std::hash::Hash, V: Encoder> Encoder for FxHashMap<K, V>
 #[cfg(feature = "fxhash")]
 impl<K: Packer + Eq + std::hash::Hash, V: Packer> Packer for FxHashMap<K, V> {
     fn pack(&self, writer: &mut BytesMut) -> Result<()> {
-        writer.put_u8(TAG_MAP);
-        let len = self.len();
-        len.encode(writer)?;
-        for (k, v) in self {
-            k.pack(writer)?;
-            v.pack(writer)?;
-        }
-        Ok(())
+        write_hash_map_entries(&[TAG_MAP], self.iter(), writer, K::pack, V::pack)
     }
 }
 #[cfg(feature = "fxhash")]
 impl<K: Decoder + Eq + std::hash::Hash, V: Decoder> Decoder for FxHashMap<K, V> {
     fn decode(reader: &mut Bytes) -> Result<Self> {
         let len = read_map_header(reader)?;
-        let mut map = FxHashMap::with_capacity_and_hasher(len, Default::default());
+        let capacity = collection_capacity::<(K, V)>(len, reader)?;
+        let mut map = FxHashMap::with_capacity_and_hasher(capacity, Default::default());
         for _ in 0..len {
             let k = K::decode(reader)?;
             let v = V::decode(reader)?;
@@ -1018,7 +1752,8 @@ impl<K: Decoder + Eq + std::hash::Hash, V: Decoder> Decoder for FxHashMap<K, V>
 impl<K: Unpacker + Eq + std::hash::Hash, V: Unpacker> Unpacker for FxHashMap<K, V> {
     fn unpack(reader: &mut Bytes) -> Result<Self> {
         let len = read_map_header(reader)?;
-        let mut map = FxHashMap::with_capacity_and_hasher(len, Default::default());
+        let capacity = collection_capacity::<(K, V)>(len, reader)?;
+        let mut map = FxHashMap::with_capacity_and_hasher(capacity, Default::default());
         for _ in 0..len {
             let k = K::unpack(reader)?;
             let v = V::unpack(reader)?;
@@ -1032,14 +1767,7 @@ impl<K: Unpacker + Eq + std::hash::Hash, V: Unpacker> Unpacker for FxHashMap<K,
 #[cfg(feature = "ahash")]
 impl<K: Encoder + Eq + std::hash::Hash, V: Encoder> Encoder for AHashMap<K, V> {
     fn encode(&self, writer: &mut BytesMut) -> Result<()> {
-        writer.put_u8(TAG_MAP);
-        let len = self.len();
-        len.encode(writer)?;
-        for (k, v) in self {
-            k.encode(writer)?;
-            v.encode(writer)?;
-        }
-        Ok(())
+        write_hash_map_entries(&[TAG_MAP], self.iter(), writer, K::encode, V::encode)
     }
 
     fn is_default(&self) -> bool {
@@ -1049,21 +1777,14 @@ impl<K: Encoder + Eq + std::hash::Hash, V: Encoder> Encoder for AHashMap<K, V> {
 #[cfg(feature = "ahash")]
 impl<K: Packer + Eq + std::hash::Hash, V: Packer> Packer for AHashMap<K, V> {
     fn pack(&self, writer: &mut BytesMut) -> Result<()> {
-        writer.put_u8(TAG_MAP);
-        let len = self.len();
-        len.encode(writer)?;
-        for (k, v) in self {
-            k.pack(writer)?;
-            v.pack(writer)?;
-        }
-        Ok(())
+        write_hash_map_entries(&[TAG_MAP], self.iter(), writer, K::pack, V::pack)
     }
 }
 #[cfg(feature = "ahash")]
 impl<K: Decoder + Eq + std::hash::Hash, V: Decoder> Decoder for AHashMap<K, V> {
     fn decode(reader: &mut Bytes) -> Result<Self> {
         let len = read_map_header(reader)?;
-        let mut map = AHashMap::with_capacity(len);
+        let mut map = AHashMap::with_capacity(collection_capacity::<(K, V)>(len, reader)?);
         for _ in 0..len {
             let k = K::decode(reader)?;
             let v = V::decode(reader)?;
@@ -1076,7 +1797,7 @@ impl<K: Decoder + Eq + std::hash::Hash, V: Decoder> Decoder for AHashMap<K, V> {
 impl<K: Unpacker + Eq + std::hash::Hash, V: Unpacker> Unpacker for AHashMap<K, V> {
     fn unpack(reader: &mut Bytes) -> Result<Self> {
         let len = read_map_header(reader)?;
-        let mut map = AHashMap::with_capacity(len);
+        let mut map = AHashMap::with_capacity(collection_capacity::<(K, V)>(len, reader)?);
         for _ in 0..len {
             let k = K::unpack(reader)?;
             let v = V::unpack(reader)?;
@@ -1093,6 +1814,7 @@ impl<T: Encoder + Eq + std::hash::Hash> Encoder for FxHashSet<T> {
         encode_vec_length(self.len(), writer)?;
         for v in self {
             v.encode(writer)?;
+            crate::config::check_encode_limit(writer.len())?;
         }
         Ok(())
     }
@@ -1115,14 +1837,22 @@ impl<T: Packer + Eq + std::hash::Hash> Packer for FxHashSet<T> {
 impl<T: Decoder + Eq + std::hash::Hash + 'static> Decoder for FxHashSet<T> {
     fn decode(reader: &mut Bytes) -> Result<Self> {
         let vec: Vec<T> = Vec::decode(reader)?;
-        Ok(vec.into_iter().collect())
+        collect_set(
+            vec,
+            |n| FxHashSet::with_capacity_and_hasher(n, Default::default()),
+            |set, v| set.insert(v),
+        )
     }
 }
 #[cfg(feature = "fxhash")]
 impl<T: Unpacker + Eq + std::hash::Hash + 'static> Unpacker for FxHashSet<T> {
     fn unpack(reader: &mut Bytes) -> Result<Self> {
         let vec: Vec<T> = Vec::unpack(reader)?;
-        Ok(vec.into_iter().collect())
+        collect_set(
+            vec,
+            |n| FxHashSet::with_capacity_and_hasher(n, Default::default()),
+            |set, v| set.insert(v),
+        )
     }
 }
 
@@ -1133,6 +1863,7 @@ impl<T: Encoder + Eq + std::hash::Hash> Encoder for AHashSet<T> {
         encode_vec_length(self.len(), writer)?;
         for v in self {
             v.encode(writer)?;
+            crate::config::check_encode_limit(writer.len())?;
         }
         Ok(())
     }
@@ -1155,14 +1886,14 @@ impl<T: Packer + Eq + std::hash::Hash> Packer for AHashSet<T> {
 impl<T: Decoder + Eq + std::hash::Hash + 'static> Decoder for AHashSet<T> {
     fn decode(reader: &mut Bytes) -> Result<Self> {
         let vec: Vec<T> = Vec::decode(reader)?;
-        Ok(vec.into_iter().collect())
+        collect_set(vec, AHashSet::with_capacity, |set, v| set.insert(v))
     }
 }
 #[cfg(feature = "ahash")]
 impl<T: Unpacker + Eq + std::hash::Hash + 'static> Unpacker for AHashSet<T> {
     fn unpack(reader: &mut Bytes) -> Result<Self> {
         let vec: Vec<T> = Vec::unpack(reader)?;
-        Ok(vec.into_iter().collect())
+        collect_set(vec, AHashSet::with_capacity, |set, v| set.insert(v))
     }
 }
 
@@ -1198,7 +1929,7 @@ impl Packer for SmolStr {
 impl Decoder for SmolStr {
     fn decode(reader: &mut Bytes) -> Result<Self> {
         if reader.remaining() == 0 {
-            return Err(EncoderError::InsufficientData);
+            return Err(EncoderError::eof(1, 0));
         }
         let tag = reader.get_u8();
         let len = if (TAG_STRING_BASE..TAG_STRING_LONG).contains(&tag) {
@@ -1212,14 +1943,15 @@ impl Decoder for SmolStr {
             )));
         };
         if reader.remaining() < len {
-            return Err(EncoderError::InsufficientData);
+            return Err(EncoderError::eof(len, reader.remaining()));
         }
+        crate::config::check_string_len(len)?;
         let mut bytes = vec![0u8; len];
         if len > 0 {
             reader.copy_to_slice(&mut bytes);
         }
         let string = String::from_utf8(bytes).map_err(|e| EncoderError::Decode(e.to_string()))?;
-        Ok(SmolStr::new(string))
+        Ok(SmolStr::new(crate::core::normalize_decoded(string)))
     }
 }
 #[cfg(feature = "smol_str")]
@@ -1262,7 +1994,7 @@ impl Packer for Box<RawValue> {
 impl Decoder for Box<RawValue> {
     fn decode(reader: &mut Bytes) -> Result<Self> {
         if reader.remaining() == 0 {
-            return Err(EncoderError::InsufficientData);
+            return Err(EncoderError::eof(1, 0));
         }
         let tag = reader.get_u8();
         let len = if (TAG_STRING_BASE..TAG_STRING_LONG).contains(&tag) {
@@ -1276,8 +2008,9 @@ impl Decoder for Box<RawValue> {
             )));
         };
         if reader.remaining() < len {
-            return Err(EncoderError::InsufficientData);
+            return Err(EncoderError::eof(len, reader.remaining()));
         }
+        crate::config::check_string_len(len)?;
         let mut bytes = vec![0u8; len];
         if len > 0 {
             reader.copy_to_slice(&mut bytes);
@@ -1293,3 +2026,280 @@ impl Unpacker for Box<RawValue> {
         Self::decode(reader)
     }
 }
+
+// --- heapless ---
+#[cfg(feature = "heapless")]
+impl<T: Encoder, const N: usize> Encoder for heapless::Vec<T, N> {
+    fn encode(&self, writer: &mut BytesMut) -> Result<()> {
+        encode_vec_length(self.len(), writer)?;
+        for v in self {
+            v.encode(writer)?;
+            crate::config::check_encode_limit(writer.len())?;
+        }
+        Ok(())
+    }
+
+    fn is_default(&self) -> bool {
+        self.is_empty()
+    }
+}
+#[cfg(feature = "heapless")]
+impl<T: Packer, const N: usize> Packer for heapless::Vec<T, N> {
+    fn pack(&self, writer: &mut BytesMut) -> Result<()> {
+        encode_vec_length(self.len(), writer)?;
+        for v in self {
+            v.pack(writer)?;
+        }
+        Ok(())
+    }
+}
+/// Decodes a `heapless::Vec`, failing with `CapacityExceeded` if the sequence is longer than `N`.
+#[cfg(feature = "heapless")]
+impl<T: Decoder, const N: usize> Decoder for heapless::Vec<T, N> {
+    fn decode(reader: &mut Bytes) -> Result<Self> {
+        let len = decode_vec_length(reader)?;
+        if len > N {
+            return Err(EncoderError::CapacityExceeded { len, capacity: N });
+        }
+        crate::config::check_collection_len(len)?;
+        let mut vec = heapless::Vec::new();
+        for _ in 0..len {
+            // Cannot fail: len <= N was checked above
+            let _ = vec.push(T::decode(reader)?);
+        }
+        Ok(vec)
+    }
+}
+#[cfg(feature = "heapless")]
+impl<T: Unpacker, const N: usize> Unpacker for heapless::Vec<T, N> {
+    fn unpack(reader: &mut Bytes) -> Result<Self> {
+        let len = decode_vec_length(reader)?;
+        if len > N {
+            return Err(EncoderError::CapacityExceeded { len, capacity: N });
+        }
+        crate::config::check_collection_len(len)?;
+        let mut vec = heapless::Vec::new();
+        for _ in 0..len {
+            let _ = vec.push(T::unpack(reader)?);
+        }
+        Ok(vec)
+    }
+}
+
+#[cfg(feature = "heapless")]
+impl<const N: usize> Encoder for heapless::String<N> {
+    fn encode(&self, writer: &mut BytesMut) -> Result<()> {
+        write_str(self.as_str(), writer)
+    }
+
+    fn is_default(&self) -> bool {
+        self.is_empty()
+    }
+}
+#[cfg(feature = "heapless")]
+impl<const N: usize> Packer for heapless::String<N> {
+    fn pack(&self, writer: &mut BytesMut) -> Result<()> {
+        self.encode(writer)
+    }
+}
+/// Decodes a string into a fixed-capacity string type through `push_str`, failing with
+/// `CapacityExceeded` if it is longer than `capacity` bytes.
+#[cfg(any(feature = "heapless", feature = "arrayvec"))]
+fn decode_bounded_str<S: Default>(
+    reader: &mut Bytes,
+    capacity: usize,
+    push_str: impl FnOnce(&mut S, &str) -> bool,
+) -> Result<S> {
+    if reader.remaining() == 0 {
+        return Err(EncoderError::eof(1, 0));
+    }
+    let tag = reader.get_u8();
+    let len = if (TAG_STRING_BASE..TAG_STRING_LONG).contains(&tag) {
+        (tag - TAG_STRING_BASE) as usize
+    } else if tag == TAG_STRING_LONG {
+        usize::decode(reader)?
+    } else {
+        return Err(EncoderError::Decode(format!(
+            "Expected String tag ({}..={}), got {}",
+            TAG_STRING_BASE, TAG_STRING_LONG, tag
+        )));
+    };
+    if len > capacity {
+        return Err(EncoderError::CapacityExceeded { len, capacity });
+    }
+    if reader.remaining() < len {
+        return Err(EncoderError::eof(len, reader.remaining()));
+    }
+    let text =
+        ::core::str::from_utf8(&reader[..len]).map_err(|e| EncoderError::Decode(e.to_string()))?;
+    let text = crate::core::normalize_str(text);
+    let mut string = S::default();
+    // Normalization may lengthen the string
+    if !push_str(&mut string, &text) {
+        return Err(EncoderError::CapacityExceeded {
+            len: text.len(),
+            capacity,
+        });
+    }
+    reader.advance(len);
+    Ok(string)
+}
+
+/// Decodes a `heapless::String`, failing with `CapacityExceeded` if the string is longer than
+/// `N` bytes.
+#[cfg(feature = "heapless")]
+impl<const N: usize> Decoder for heapless::String<N> {
+    fn decode(reader: &mut Bytes) -> Result<Self> {
+        decode_bounded_str(reader, N, |string: &mut Self, text| {
+            string.push_str(text).is_ok()
+        })
+    }
+}
+#[cfg(feature = "heapless")]
+impl<const N: usize> Unpacker for heapless::String<N> {
+    fn unpack(reader: &mut Bytes) -> Result<Self> {
+        Self::decode(reader)
+    }
+}
+
+// --- smallvec ---
+#[cfg(feature = "smallvec")]
+impl<T: Encoder, const N: usize> Encoder for smallvec::SmallVec<[T; N]> {
+    fn encode(&self, writer: &mut BytesMut) -> Result<()> {
+        encode_vec_length(self.len(), writer)?;
+        for v in self {
+            v.encode(writer)?;
+            crate::config::check_encode_limit(writer.len())?;
+        }
+        Ok(())
+    }
+
+    fn is_default(&self) -> bool {
+        self.is_empty()
+    }
+}
+#[cfg(feature = "smallvec")]
+impl<T: Packer, const N: usize> Packer for smallvec::SmallVec<[T; N]> {
+    fn pack(&self, writer: &mut BytesMut) -> Result<()> {
+        encode_vec_length(self.len(), writer)?;
+        for v in self {
+            v.pack(writer)?;
+        }
+        Ok(())
+    }
+}
+/// Decodes a `SmallVec` from the `Vec<T>` format, spilling to the heap beyond `N` elements.
+#[cfg(feature = "smallvec")]
+impl<T: Decoder, const N: usize> Decoder for smallvec::SmallVec<[T; N]> {
+    fn decode(reader: &mut Bytes) -> Result<Self> {
+        let len = decode_vec_length(reader)?;
+        let mut vec = smallvec::SmallVec::with_capacity(collection_capacity::<T>(len, reader)?);
+        for _ in 0..len {
+            vec.push(T::decode(reader)?);
+        }
+        Ok(vec)
+    }
+}
+#[cfg(feature = "smallvec")]
+impl<T: Unpacker, const N: usize> Unpacker for smallvec::SmallVec<[T; N]> {
+    fn unpack(reader: &mut Bytes) -> Result<Self> {
+        let len = decode_vec_length(reader)?;
+        let mut vec = smallvec::SmallVec::with_capacity(collection_capacity::<T>(len, reader)?);
+        for _ in 0..len {
+            vec.push(T::unpack(reader)?);
+        }
+        Ok(vec)
+    }
+}
+
+// --- arrayvec ---
+#[cfg(feature = "arrayvec")]
+impl<T: Encoder, const N: usize> Encoder for arrayvec::ArrayVec<T, N> {
+    fn encode(&self, writer: &mut BytesMut) -> Result<()> {
+        encode_vec_length(self.len(), writer)?;
+        for v in self {
+            v.encode(writer)?;
+            crate::config::check_encode_limit(writer.len())?;
+        }
+        Ok(())
+    }
+
+    fn is_default(&self) -> bool {
+        self.is_empty()
+    }
+}
+#[cfg(feature = "arrayvec")]
+impl<T: Packer, const N: usize> Packer for arrayvec::ArrayVec<T, N> {
+    fn pack(&self, writer: &mut BytesMut) -> Result<()> {
+        encode_vec_length(self.len(), writer)?;
+        for v in self {
+            v.pack(writer)?;
+        }
+        Ok(())
+    }
+}
+/// Decodes an `ArrayVec`, failing with `CapacityExceeded` if the sequence is longer than `N`.
+#[cfg(feature = "arrayvec")]
+impl<T: Decoder, const N: usize> Decoder for arrayvec::ArrayVec<T, N> {
+    fn decode(reader: &mut Bytes) -> Result<Self> {
+        let len = decode_vec_length(reader)?;
+        if len > N {
+            return Err(EncoderError::CapacityExceeded { len, capacity: N });
+        }
+        crate::config::check_collection_len(len)?;
+        let mut vec = arrayvec::ArrayVec::new();
+        for _ in 0..len {
+            // Cannot fail: len <= N was checked above
+            let _ = vec.try_push(T::decode(reader)?);
+        }
+        Ok(vec)
+    }
+}
+#[cfg(feature = "arrayvec")]
+impl<T: Unpacker, const N: usize> Unpacker for arrayvec::ArrayVec<T, N> {
+    fn unpack(reader: &mut Bytes) -> Result<Self> {
+        let len = decode_vec_length(reader)?;
+        if len > N {
+            return Err(EncoderError::CapacityExceeded { len, capacity: N });
+        }
+        crate::config::check_collection_len(len)?;
+        let mut vec = arrayvec::ArrayVec::new();
+        for _ in 0..len {
+            let _ = vec.try_push(T::unpack(reader)?);
+        }
+        Ok(vec)
+    }
+}
+
+#[cfg(feature = "arrayvec")]
+impl<const N: usize> Encoder for arrayvec::ArrayString<N> {
+    fn encode(&self, writer: &mut BytesMut) -> Result<()> {
+        write_str(self.as_str(), writer)
+    }
+
+    fn is_default(&self) -> bool {
+        self.is_empty()
+    }
+}
+#[cfg(feature = "arrayvec")]
+impl<const N: usize> Packer for arrayvec::ArrayString<N> {
+    fn pack(&self, writer: &mut BytesMut) -> Result<()> {
+        self.encode(writer)
+    }
+}
+/// Decodes an `ArrayString`, failing with `CapacityExceeded` if the string is longer than `N`
+/// bytes.
+#[cfg(feature = "arrayvec")]
+impl<const N: usize> Decoder for arrayvec::ArrayString<N> {
+    fn decode(reader: &mut Bytes) -> Result<Self> {
+        decode_bounded_str(reader, N, |string: &mut Self, text| {
+            string.try_push_str(text).is_ok()
+        })
+    }
+}
+#[cfg(feature = "arrayvec")]
+impl<const N: usize> Unpacker for arrayvec::ArrayString<N> {
+    fn unpack(reader: &mut Bytes) -> Result<Self> {
+        Self::decode(reader)
+    }
+}
@@ -0,0 +1,175 @@
+//! Field-level encryption for `#[senax(encrypt)]` fields.
+//!
+//! Derived `Encode`/`Decode` implementations seal the encoded bytes of each `#[senax(encrypt)]`
+//! field with XChaCha20-Poly1305 using the key installed by [`with_field_key`]. An encrypted
+//! field is written as a binary value
+//!
+//! ```text
+//! [nonce: 24 bytes] [ciphertext + tag]
+//! ```
+//!
+//! with a random nonce per field and the field ID as associated data, so a ciphertext cannot be
+//! moved to a different field. Encoding or decoding an encrypted field without a key installed
+//! fails.
+
+use crate::{Decoder, Encoder, EncoderError, Result};
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use std::cell::Cell;
+
+/// Length of the nonce at the start of every encrypted field.
+pub const FIELD_NONCE_LEN: usize = 24;
+
+/// A 256-bit key for `#[senax(encrypt)]` fields and [`encode_encrypted`](crate::encode_encrypted)
+/// envelopes.
+#[derive(Clone)]
+pub struct FieldKey([u8; 32]);
+
+impl FieldKey {
+    /// Creates a key from raw bytes.
+    pub fn new(bytes: [u8; 32]) -> Self {
+        Self(bytes)
+    }
+
+    pub(crate) fn cipher(&self) -> XChaCha20Poly1305 {
+        XChaCha20Poly1305::new(&self.0.into())
+    }
+}
+
+impl std::fmt::Debug for FieldKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("FieldKey(..)")
+    }
+}
+
+thread_local! {
+    static FIELD_KEY: Cell<Option<[u8; 32]>> = const { Cell::new(None) };
+}
+
+/// Restores the previous field key when dropped.
+struct FieldKeyGuard(Option<[u8; 32]>);
+
+impl Drop for FieldKeyGuard {
+    fn drop(&mut self) {
+        FIELD_KEY.with(|c| c.set(self.0));
+    }
+}
+
+/// Runs `f` with `key` installed for encrypting and decrypting `#[senax(encrypt)]` fields.
+///
+/// Calls may be nested; the previous key is restored when `f` returns.
+///
+/// # Example
+/// ```rust
+/// use senax_encoder::field_crypto::{with_field_key, FieldKey};
+/// use senax_encoder::{decode, encode, Decode, Encode};
+///
+/// #[derive(Encode, Decode, Debug, PartialEq)]
+/// struct Customer {
+///     id: u64,
+///     #[senax(encrypt)]
+///     email: String,
+/// }
+///
+/// let key = FieldKey::new([7; 32]);
+/// let value = Customer { id: 1, email: "alice@example.com".into() };
+/// let bytes = with_field_key(&key, || encode(&value)).unwrap();
+/// let decoded: Customer = with_field_key(&key, || decode(&mut bytes.clone())).unwrap();
+/// assert_eq!(decoded, value);
+///
+/// // Without the key the field cannot be read
+/// assert!(decode::<Customer>(&mut bytes.clone()).is_err());
+/// ```
+pub fn with_field_key<R>(key: &FieldKey, f: impl FnOnce() -> R) -> R {
+    let previous = FIELD_KEY.with(|c| c.replace(Some(key.0)));
+    let _guard = FieldKeyGuard(previous);
+    f()
+}
+
+fn current_cipher() -> Option<XChaCha20Poly1305> {
+    FIELD_KEY
+        .with(|c| c.get())
+        .map(|key| XChaCha20Poly1305::new(&key.into()))
+}
+
+/// Encodes `value` and writes it as an encrypted binary value. Used by derived code.
+pub fn encode_encrypted_field<T: Encoder + ?Sized>(
+    value: &T,
+    field_id: u64,
+    writer: &mut BytesMut,
+) -> Result<()> {
+    let cipher = current_cipher().ok_or_else(|| {
+        EncoderError::Encode(
+            "No field encryption key installed; wrap the call in with_field_key".to_string(),
+        )
+    })?;
+    let mut plain = BytesMut::new();
+    // The plaintext is read back with Decoder, so it never carries self-describing names
+    crate::self_describing::without_names(|| value.encode(&mut plain))?;
+    let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+    let aad = field_id.to_le_bytes();
+    let sealed = cipher
+        .encrypt(
+            &nonce,
+            Payload {
+                msg: &plain,
+                aad: &aad,
+            },
+        )
+        .map_err(|_| EncoderError::Encode("Field encryption failed".to_string()));
+    #[cfg(feature = "zeroize")]
+    zeroize::Zeroize::zeroize(&mut plain[..]);
+    let sealed = sealed?;
+
+    let mut envelope = BytesMut::with_capacity(FIELD_NONCE_LEN + sealed.len());
+    envelope.put_slice(&nonce);
+    envelope.put_slice(&sealed);
+    envelope.freeze().encode(writer)
+}
+
+/// Reads an encrypted binary value, decrypts it and decodes `T`. Used by derived code.
+pub fn decode_encrypted_field<T: Decoder>(reader: &mut Bytes, field_id: u64) -> Result<T> {
+    let cipher = current_cipher().ok_or_else(|| {
+        EncoderError::Decode(
+            "No field encryption key installed; wrap the call in with_field_key".to_string(),
+        )
+    })?;
+    let envelope = Bytes::decode(reader)?;
+    // The binary value was read whole, so a short envelope is corrupt rather than truncated
+    if envelope.len() < FIELD_NONCE_LEN {
+        return Err(EncoderError::Decode(format!(
+            "Encrypted field 0x{:016X} of {} bytes is shorter than its nonce",
+            field_id,
+            envelope.len()
+        )));
+    }
+    let nonce = XNonce::from_slice(&envelope[..FIELD_NONCE_LEN]);
+    let aad = field_id.to_le_bytes();
+    let plain = cipher
+        .decrypt(
+            nonce,
+            Payload {
+                msg: &envelope[FIELD_NONCE_LEN..],
+                aad: &aad,
+            },
+        )
+        .map_err(|_| {
+            EncoderError::Decode(format!(
+                "Failed to decrypt field 0x{:016X}: wrong key or corrupted data",
+                field_id
+            ))
+        })?;
+    #[cfg(feature = "zeroize")]
+    let mut plain = crate::sensitive::zeroizing_bytes(plain);
+    #[cfg(not(feature = "zeroize"))]
+    let mut plain = Bytes::from(plain);
+    let value = T::decode(&mut plain)?;
+    if plain.has_remaining() {
+        return Err(EncoderError::Decode(format!(
+            "Trailing bytes in encrypted field 0x{:016X}",
+            field_id
+        )));
+    }
+    Ok(value)
+}
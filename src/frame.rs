@@ -0,0 +1,43 @@
+//! The length header shared by every length-delimited frame format.
+//!
+//! [`encode_framed`](crate::encode_framed), the record log, the `tokio` codec and the
+//! `futures` sink and stream all write frames as
+//!
+//! ```text
+//! [payload_len: u32 little-endian] [payload] ...
+//! ```
+//!
+//! so a byte stream written by any of them can be read by the others.
+
+use crate::{EncoderError, Result};
+use alloc::format;
+use bytes::Buf;
+
+/// Length of the frame header.
+pub(crate) const HEADER_LEN: usize = 4;
+
+/// Largest payload a frame can hold; longer lengths are reserved for the record log's
+/// transaction markers.
+pub(crate) const MAX_PAYLOAD_LEN: u32 = u32::MAX - 2;
+
+/// Returns the header of a frame holding `len` payload bytes.
+pub(crate) fn header(len: usize) -> Result<[u8; HEADER_LEN]> {
+    u32::try_from(len)
+        .ok()
+        .filter(|&len| len <= MAX_PAYLOAD_LEN)
+        .map(u32::to_le_bytes)
+        .ok_or_else(|| {
+            EncoderError::Encode(format!(
+                "Message of {} bytes exceeds the frame limit of {} bytes",
+                len, MAX_PAYLOAD_LEN
+            ))
+        })
+}
+
+/// Reads the length field of the header at the start of `src`.
+pub(crate) fn read_header(src: &[u8]) -> Result<u32> {
+    let mut header = src
+        .get(..HEADER_LEN)
+        .ok_or(EncoderError::eof(HEADER_LEN, src.len()))?;
+    Ok(header.get_u32_le())
+}
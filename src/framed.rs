@@ -0,0 +1,259 @@
+//! `futures` sinks and streams of encoded messages.
+//!
+//! [`SenaxSink`] and [`SenaxStream`] carry values over any `AsyncWrite` / `AsyncRead` (sockets,
+//! pipes, files) in the [record log](crate::record_log) frame format, so the byte streams they
+//! produce can also be read with `RecordLogReader`:
+//!
+//! ```text
+//! [payload_len: u32 little-endian] [payload: encode() output] ...
+//! ```
+//!
+//! They implement `futures::Sink` and `futures::Stream` and plug into `futures` combinators and
+//! `tower`-style pipelines. The sink applies backpressure: `poll_ready` writes buffered frames
+//! out before accepting more once [`SenaxSink::backpressure_boundary`] bytes are pending.
+
+use crate::frame::{self, HEADER_LEN};
+use crate::{decode, encode, Decoder, Encoder, EncoderError, Result};
+use bytes::{Buf, BufMut, BytesMut};
+use futures_core::Stream;
+use futures_io::{AsyncRead, AsyncWrite};
+use futures_sink::Sink;
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::task::{ready, Context, Poll};
+
+/// Default number of buffered bytes above which [`SenaxSink`] stops accepting messages.
+pub const DEFAULT_BACKPRESSURE_BOUNDARY: usize = 8 * 1024;
+
+/// Default limit on the payload length accepted by [`SenaxStream`].
+pub const DEFAULT_MAX_FRAME_LEN: usize = crate::DEFAULT_MAX_FRAME_LEN;
+
+/// A `Sink` that encodes values of type `T` into frames written to `W`.
+///
+/// # Example
+/// ```rust
+/// use futures::{executor::block_on, SinkExt, StreamExt};
+/// use senax_encoder::framed::{SenaxSink, SenaxStream};
+///
+/// let mut sink = SenaxSink::<_, String>::new(Vec::new());
+/// block_on(sink.send("hello".to_string())).unwrap();
+///
+/// let bytes = sink.into_inner();
+/// let mut stream = SenaxStream::<_, String>::new(futures::io::Cursor::new(bytes));
+/// assert_eq!(block_on(stream.next()).unwrap().unwrap(), "hello");
+/// ```
+pub struct SenaxSink<W, T> {
+    inner: W,
+    buffer: BytesMut,
+    backpressure_boundary: usize,
+    marker: PhantomData<fn(&T)>,
+}
+
+impl<W, T> SenaxSink<W, T> {
+    /// Creates a sink writing to `inner`.
+    pub fn new(inner: W) -> Self {
+        Self {
+            inner,
+            buffer: BytesMut::new(),
+            backpressure_boundary: DEFAULT_BACKPRESSURE_BOUNDARY,
+            marker: PhantomData,
+        }
+    }
+
+    /// Sets the number of buffered bytes above which `poll_ready` first writes the buffer out.
+    pub fn with_backpressure_boundary(mut self, boundary: usize) -> Self {
+        self.backpressure_boundary = boundary;
+        self
+    }
+
+    /// The number of buffered bytes above which `poll_ready` first writes the buffer out.
+    pub fn backpressure_boundary(&self) -> usize {
+        self.backpressure_boundary
+    }
+
+    /// Bytes encoded but not yet written to the underlying writer.
+    pub fn buffered_len(&self) -> usize {
+        self.buffer.len()
+    }
+
+    /// Returns a reference to the underlying writer.
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Returns the underlying writer. Frames still in the buffer are dropped; flush first.
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+impl<W: AsyncWrite + Unpin, T> SenaxSink<W, T> {
+    /// Writes buffered bytes to the underlying writer until the buffer is empty.
+    fn poll_write_buffer(&mut self, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        while !self.buffer.is_empty() {
+            let n = ready!(Pin::new(&mut self.inner).poll_write(cx, &self.buffer))?;
+            if n == 0 {
+                return Poll::Ready(Err(
+                    std::io::Error::from(std::io::ErrorKind::WriteZero).into()
+                ));
+            }
+            self.buffer.advance(n);
+        }
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<W: AsyncWrite + Unpin, T: Encoder> Sink<T> for SenaxSink<W, T> {
+    type Error = EncoderError;
+
+    fn poll_ready(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        let this = self.get_mut();
+        if this.buffer.len() >= this.backpressure_boundary {
+            ready!(this.poll_write_buffer(cx))?;
+        }
+        Poll::Ready(Ok(()))
+    }
+
+    fn start_send(self: Pin<&mut Self>, item: T) -> Result<()> {
+        let this = self.get_mut();
+        let payload = encode(&item)?;
+        let header = frame::header(payload.len())?;
+        this.buffer.reserve(HEADER_LEN + payload.len());
+        this.buffer.put_slice(&header);
+        this.buffer.put_slice(&payload);
+        Ok(())
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        let this = self.get_mut();
+        ready!(this.poll_write_buffer(cx))?;
+        ready!(Pin::new(&mut this.inner).poll_flush(cx))?;
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        let this = self.get_mut();
+        ready!(this.poll_write_buffer(cx))?;
+        ready!(Pin::new(&mut this.inner).poll_close(cx))?;
+        Poll::Ready(Ok(()))
+    }
+}
+
+/// A `Stream` that reads frames from `R` and decodes them as `T`.
+///
+/// The stream ends when the reader does at a frame boundary. A reader that ends inside a frame
+/// yields [`EncoderError::UnexpectedEof`] once. A frame that fails to decode yields its
+/// error and the stream continues with the next frame.
+pub struct SenaxStream<R, T> {
+    inner: R,
+    buffer: BytesMut,
+    max_frame_len: usize,
+    eof: bool,
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<R, T> SenaxStream<R, T> {
+    /// Creates a stream reading from `inner`.
+    pub fn new(inner: R) -> Self {
+        Self {
+            inner,
+            buffer: BytesMut::new(),
+            max_frame_len: DEFAULT_MAX_FRAME_LEN,
+            eof: false,
+            marker: PhantomData,
+        }
+    }
+
+    /// Sets the longest payload accepted; longer frames end the stream with an error.
+    pub fn with_max_frame_len(mut self, max_frame_len: usize) -> Self {
+        self.max_frame_len = max_frame_len;
+        self
+    }
+
+    /// Returns a reference to the underlying reader.
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// Returns the underlying reader. Bytes already read into the buffer are dropped.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    /// Splits the next complete frame off the buffer, if there is one.
+    fn next_frame(&mut self) -> Result<Option<BytesMut>> {
+        if self.buffer.len() < HEADER_LEN {
+            return Ok(None);
+        }
+        let len = frame::read_header(&self.buffer)? as usize;
+        if len > self.max_frame_len {
+            return Err(EncoderError::Decode(format!(
+                "Frame of {} bytes exceeds the limit of {} bytes",
+                len, self.max_frame_len
+            )));
+        }
+        if self.buffer.len() < HEADER_LEN + len {
+            self.buffer.reserve(HEADER_LEN + len - self.buffer.len());
+            return Ok(None);
+        }
+        self.buffer.advance(HEADER_LEN);
+        Ok(Some(self.buffer.split_to(len)))
+    }
+
+    /// Length of the incomplete frame at the start of the buffer, header included, as far as
+    /// it is known.
+    fn pending_frame_len(&self) -> usize {
+        frame::read_header(&self.buffer).map_or(HEADER_LEN, |len| HEADER_LEN + len as usize)
+    }
+}
+
+impl<R: AsyncRead + Unpin, T: Decoder> Stream for SenaxStream<R, T> {
+    type Item = Result<T>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Result<T>>> {
+        let this = self.get_mut();
+        loop {
+            match this.next_frame() {
+                Ok(Some(frame)) => return Poll::Ready(Some(decode(&mut frame.freeze()))),
+                Ok(None) => {}
+                Err(e) => {
+                    this.eof = true;
+                    this.buffer.clear();
+                    return Poll::Ready(Some(Err(e)));
+                }
+            }
+            if this.eof {
+                if this.buffer.is_empty() {
+                    return Poll::Ready(None);
+                }
+                let err = EncoderError::UnexpectedEof {
+                    needed: this.pending_frame_len(),
+                    available: this.buffer.len(),
+                };
+                this.buffer.clear();
+                return Poll::Ready(Some(Err(err)));
+            }
+
+            let start = this.buffer.len();
+            let want = this.buffer.capacity().max(start + 4096);
+            this.buffer.resize(want, 0);
+            let result = Pin::new(&mut this.inner).poll_read(cx, &mut this.buffer[start..]);
+            match result {
+                Poll::Ready(Ok(n)) => {
+                    this.buffer.truncate(start + n);
+                    this.eof = n == 0;
+                }
+                Poll::Ready(Err(e)) => {
+                    this.buffer.truncate(start);
+                    if e.kind() != std::io::ErrorKind::Interrupted {
+                        return Poll::Ready(Some(Err(e.into())));
+                    }
+                }
+                Poll::Pending => {
+                    this.buffer.truncate(start);
+                    return Poll::Pending;
+                }
+            }
+        }
+    }
+}
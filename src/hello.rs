@@ -0,0 +1,256 @@
+//! Connection handshake for negotiating the wire mode and checking schemas.
+//!
+//! Before exchanging application messages, both peers of a connection send a [`Hello`]
+//! frame describing their build and what they accept:
+//!
+//! ```text
+//! [magic: 0xA55B] [Hello]
+//! ```
+//!
+//! where `Hello` is written with [`Encoder::encode`], so fields added in later versions are
+//! skipped by older peers. Each side then calls [`Hello::negotiate`] with the peer's hello.
+//! Negotiation is symmetric: both sides arrive at the same [`WireMode`] without another round
+//! trip, and apply its [`decode_config`](Negotiated::decode_config) and
+//! [`encode_config`](Negotiated::encode_config) to the messages that follow.
+//!
+//! Schema fingerprints ([`TypeSchema::fingerprint`]) registered with [`Hello::with_schema`]
+//! are compared by name, so a peer running an incompatible version of a message type is
+//! noticed before its first message fails to decode.
+
+use crate::schema::{HasSchema, TypeSchema};
+use crate::{
+    capabilities, Capabilities, Decode, DecodeConfig, Decoder, Encode, EncodeConfig, Encoder,
+    EncoderError, Result,
+};
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+use std::collections::BTreeMap;
+
+/// Magic number for hello frames (0xA55B in little-endian).
+pub const HELLO_MAGIC: u16 = 0xA55B;
+
+/// Version of the encode format spoken by this build.
+///
+/// Peers with different format versions cannot exchange messages; [`Hello::negotiate`] fails.
+pub const FORMAT_VERSION: u32 = 1;
+
+/// How peers read and write the messages that follow the handshake.
+///
+/// Listed from the strictest to the most lenient; negotiation picks the strictest mode both
+/// peers accept.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum WireMode {
+    /// Strict decoding, and encoding that always produces the same bytes for the same value
+    /// (hash map entries are sorted), for signing or deduplicating messages.
+    Canonical,
+    /// Rejects unknown fields and duplicate set elements.
+    Strict,
+    /// The default decoding rules, plus tuples and arrays read as each other.
+    Lenient,
+}
+
+impl WireMode {
+    /// Every mode, from the strictest to the most lenient.
+    pub const ALL: [WireMode; 3] = [WireMode::Canonical, WireMode::Strict, WireMode::Lenient];
+
+    /// Returns the name the mode is sent under in a [`Hello`].
+    pub fn name(self) -> &'static str {
+        match self {
+            WireMode::Canonical => "canonical",
+            WireMode::Strict => "strict",
+            WireMode::Lenient => "lenient",
+        }
+    }
+
+    /// Returns the mode called `name`, or `None` for modes this build does not know.
+    pub fn from_name(name: &str) -> Option<WireMode> {
+        WireMode::ALL.into_iter().find(|mode| mode.name() == name)
+    }
+
+    /// Returns the decode configuration of the mode.
+    pub fn decode_config(self) -> DecodeConfig {
+        match self {
+            WireMode::Canonical | WireMode::Strict => DecodeConfig {
+                strict_sets: true,
+                deny_unknown_fields: true,
+                ..Default::default()
+            },
+            WireMode::Lenient => DecodeConfig {
+                lenient_tuples: true,
+                ..Default::default()
+            },
+        }
+    }
+
+    /// Returns the encode configuration of the mode.
+    pub fn encode_config(self) -> EncodeConfig {
+        EncodeConfig {
+            sort_maps: self == WireMode::Canonical,
+            ..Default::default()
+        }
+    }
+}
+
+/// The handshake message a peer sends when a connection opens.
+#[derive(Encode, Decode, Debug, Clone, PartialEq, Eq)]
+#[senax(crate = "crate")]
+pub struct Hello {
+    /// Version of the encode format, [`FORMAT_VERSION`] for this build.
+    pub format_version: u32,
+    /// What the build can read, including the crate version.
+    pub capabilities: Capabilities,
+    /// Names of the accepted [`WireMode`]s. Names unknown to the receiver are ignored.
+    pub modes: Vec<String>,
+    /// Schema fingerprints of the message types the peer sends or expects, by name.
+    #[senax(default)]
+    pub schemas: BTreeMap<String, u64>,
+}
+
+impl Default for Hello {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Hello {
+    /// Describes this build, accepting every mode and listing no schemas.
+    pub fn new() -> Self {
+        Self {
+            format_version: FORMAT_VERSION,
+            capabilities: capabilities(),
+            modes: WireMode::ALL
+                .iter()
+                .map(|mode| mode.name().to_string())
+                .collect(),
+            schemas: BTreeMap::new(),
+        }
+    }
+
+    /// Returns the peer's senax-encoder version.
+    pub fn crate_version(&self) -> &str {
+        &self.capabilities.crate_version
+    }
+
+    /// Accepts only `modes`.
+    pub fn with_modes(mut self, modes: &[WireMode]) -> Self {
+        self.modes = modes.iter().map(|mode| mode.name().to_string()).collect();
+        self
+    }
+
+    /// Adds the fingerprint of `T`'s schema under `name`.
+    pub fn with_schema<T: HasSchema>(self, name: impl Into<String>) -> Self {
+        self.with_fingerprint(name, &T::schema())
+    }
+
+    /// Adds the fingerprint of `schema` under `name`.
+    pub fn with_fingerprint(mut self, name: impl Into<String>, schema: &TypeSchema) -> Self {
+        self.schemas.insert(name.into(), schema.fingerprint());
+        self
+    }
+
+    /// Returns `true` if the mode is accepted.
+    pub fn accepts(&self, mode: WireMode) -> bool {
+        self.modes.iter().any(|name| name == mode.name())
+    }
+
+    /// Writes the hello frame.
+    pub fn encode_frame(&self) -> Result<Bytes> {
+        let mut writer = BytesMut::new();
+        writer.put_u16_le(HELLO_MAGIC);
+        self.encode(&mut writer)?;
+        Ok(writer.freeze())
+    }
+
+    /// Reads a hello frame written by [`encode_frame`](Self::encode_frame).
+    pub fn decode_frame(reader: &mut Bytes) -> Result<Hello> {
+        if reader.remaining() < 2 {
+            return Err(EncoderError::eof(2, reader.remaining()));
+        }
+        let magic = reader.get_u16_le();
+        if magic != HELLO_MAGIC {
+            return Err(EncoderError::Decode(format!(
+                "Invalid hello magic number: expected 0x{:04X}, got 0x{:04X}",
+                HELLO_MAGIC, magic
+            )));
+        }
+        Hello::decode(reader)
+    }
+
+    /// Agrees with `peer` on how to exchange messages.
+    ///
+    /// Fails if the format versions differ or no mode is accepted by both sides.
+    ///
+    /// # Example
+    /// ```rust
+    /// use senax_encoder::hello::{Hello, WireMode};
+    ///
+    /// let client = Hello::new().with_modes(&[WireMode::Strict, WireMode::Lenient]);
+    /// let server = Hello::new();
+    ///
+    /// let frame = client.encode_frame().unwrap();
+    /// let received = Hello::decode_frame(&mut frame.clone()).unwrap();
+    /// let agreed = server.negotiate(&received).unwrap();
+    /// assert_eq!(agreed.mode, WireMode::Strict);
+    /// assert!(agreed.decode_config().deny_unknown_fields);
+    /// assert_eq!(agreed, client.negotiate(&server).unwrap());
+    /// ```
+    pub fn negotiate(&self, peer: &Hello) -> Result<Negotiated> {
+        if peer.format_version != self.format_version {
+            return Err(EncoderError::Decode(format!(
+                "Peer speaks format version {}, expected {}",
+                peer.format_version, self.format_version
+            )));
+        }
+        let mode = WireMode::ALL
+            .into_iter()
+            .find(|mode| self.accepts(*mode) && peer.accepts(*mode))
+            .ok_or_else(|| {
+                EncoderError::Decode(format!(
+                    "No wire mode accepted by both peers: {:?} and {:?}",
+                    self.modes, peer.modes
+                ))
+            })?;
+        let schema_mismatches = self
+            .schemas
+            .iter()
+            .filter(|(name, fingerprint)| {
+                peer.schemas
+                    .get(*name)
+                    .is_some_and(|theirs| theirs != *fingerprint)
+            })
+            .map(|(name, _)| name.clone())
+            .collect();
+        Ok(Negotiated {
+            mode,
+            capabilities: self.capabilities.common(&peer.capabilities),
+            schema_mismatches,
+        })
+    }
+}
+
+/// The outcome of [`Hello::negotiate`], the same on both peers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Negotiated {
+    /// The strictest mode both peers accept.
+    pub mode: WireMode,
+    /// What both builds support ([`Capabilities::common`]).
+    pub capabilities: Capabilities,
+    /// Names of the schemas both peers listed with different fingerprints, in name order.
+    pub schema_mismatches: Vec<String>,
+}
+
+impl Negotiated {
+    /// Returns `true` if every schema listed by both peers has the same fingerprint.
+    pub fn schemas_match(&self) -> bool {
+        self.schema_mismatches.is_empty()
+    }
+
+    /// Returns the decode configuration of the negotiated mode.
+    pub fn decode_config(&self) -> DecodeConfig {
+        self.mode.decode_config()
+    }
+
+    /// Returns the encode configuration of the negotiated mode.
+    pub fn encode_config(&self) -> EncodeConfig {
+        self.mode.encode_config()
+    }
+}
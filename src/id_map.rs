@@ -0,0 +1,176 @@
+//! A map keyed by `u64` IDs with compact keys.
+//!
+//! [`IdMap`] stores entity-ID keyed data (game objects, replicated records) in a `BTreeMap`
+//! and encodes it under [`TAG_MAP_ID_KEYS`]: the entry count, then each key written like a
+//! field ID (one byte for IDs `1..=250`, `255` plus eight bytes otherwise) followed by the
+//! value. Small IDs take one byte instead of the one to nine bytes of a tagged integer, and the
+//! `BTreeMap` keeps the output ordered by ID.
+//!
+//! Decoding also accepts a plain `TAG_MAP` with integer keys, so an `IdMap` field can replace
+//! a `HashMap<u64, V>` or `BTreeMap<u64, V>` field and still read payloads written before the
+//! change.
+
+use crate::core::{read_field_id_optimized, write_field_id_optimized, TAG_MAP, TAG_MAP_ID_KEYS};
+use crate::{Decoder, Encoder, EncoderError, Packer, Result, Unpacker};
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+use std::collections::BTreeMap;
+use std::ops::{Deref, DerefMut};
+
+/// A `BTreeMap<u64, V>` whose keys are encoded like field IDs.
+///
+/// `IdMap` dereferences to the inner `BTreeMap`, so the usual map methods are available.
+///
+/// # Example
+/// ```rust
+/// use senax_encoder::{decode, encode, IdMap};
+///
+/// let mut players: IdMap<String> = IdMap::new();
+/// players.insert(7, "alice".into());
+/// players.insert(12, "bob".into());
+///
+/// let bytes = encode(&players).unwrap();
+/// let decoded: IdMap<String> = decode(&mut bytes.clone()).unwrap();
+/// assert_eq!(decoded, players);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct IdMap<V>(pub BTreeMap<u64, V>);
+
+impl<V> IdMap<V> {
+    /// Creates an empty map.
+    pub fn new() -> Self {
+        Self(BTreeMap::new())
+    }
+
+    /// Returns the inner `BTreeMap`.
+    pub fn into_inner(self) -> BTreeMap<u64, V> {
+        self.0
+    }
+}
+
+impl<V> Default for IdMap<V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<V> Deref for IdMap<V> {
+    type Target = BTreeMap<u64, V>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<V> DerefMut for IdMap<V> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<V> From<BTreeMap<u64, V>> for IdMap<V> {
+    fn from(map: BTreeMap<u64, V>) -> Self {
+        Self(map)
+    }
+}
+
+impl<V> FromIterator<(u64, V)> for IdMap<V> {
+    fn from_iter<I: IntoIterator<Item = (u64, V)>>(iter: I) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+
+impl<V> Extend<(u64, V)> for IdMap<V> {
+    fn extend<I: IntoIterator<Item = (u64, V)>>(&mut self, iter: I) {
+        self.0.extend(iter)
+    }
+}
+
+impl<V> IntoIterator for IdMap<V> {
+    type Item = (u64, V);
+    type IntoIter = std::collections::btree_map::IntoIter<u64, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl<'a, V> IntoIterator for &'a IdMap<V> {
+    type Item = (&'a u64, &'a V);
+    type IntoIter = std::collections::btree_map::Iter<'a, u64, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
+
+/// Writes the tag, the entry count and the entries with field-ID keys.
+fn write_entries<V>(
+    map: &IdMap<V>,
+    writer: &mut BytesMut,
+    write_value: impl Fn(&V, &mut BytesMut) -> Result<()>,
+) -> Result<()> {
+    writer.put_u8(TAG_MAP_ID_KEYS);
+    map.len().encode(writer)?;
+    for (id, value) in map {
+        write_field_id_optimized(writer, *id)?;
+        write_value(value, writer)?;
+        crate::config::check_encode_limit(writer.len())?;
+    }
+    Ok(())
+}
+
+/// Reads a map written by [`write_entries`], or a `TAG_MAP` with integer keys.
+fn read_entries<V>(
+    reader: &mut Bytes,
+    read_value: impl Fn(&mut Bytes) -> Result<V>,
+) -> Result<IdMap<V>> {
+    if reader.remaining() == 0 {
+        return Err(EncoderError::eof(1, 0));
+    }
+    let tag = reader.get_u8();
+    if tag != TAG_MAP_ID_KEYS && tag != TAG_MAP {
+        return Err(EncoderError::Decode(format!(
+            "Expected IdMap tag ({} or {}), got {}",
+            TAG_MAP_ID_KEYS, TAG_MAP, tag
+        )));
+    }
+    let len = usize::decode(reader)?;
+    let mut map = BTreeMap::new();
+    for _ in 0..len {
+        let id = if tag == TAG_MAP_ID_KEYS {
+            read_field_id_optimized(reader)?
+        } else {
+            u64::decode(reader)?
+        };
+        map.insert(id, read_value(reader)?);
+    }
+    Ok(IdMap(map))
+}
+
+impl<V: Encoder> Encoder for IdMap<V> {
+    fn encode(&self, writer: &mut BytesMut) -> Result<()> {
+        write_entries(self, writer, V::encode)
+    }
+
+    fn is_default(&self) -> bool {
+        self.is_empty()
+    }
+}
+
+impl<V: Decoder> Decoder for IdMap<V> {
+    fn decode(reader: &mut Bytes) -> Result<Self> {
+        read_entries(reader, V::decode)
+    }
+}
+
+impl<V: Packer> Packer for IdMap<V> {
+    fn pack(&self, writer: &mut BytesMut) -> Result<()> {
+        write_entries(self, writer, V::pack)
+    }
+}
+
+impl<V: Unpacker> Unpacker for IdMap<V> {
+    fn unpack(reader: &mut Bytes) -> Result<Self> {
+        read_entries(reader, V::unpack)
+    }
+}
@@ -0,0 +1,219 @@
+//! Indexed container of encoded records.
+//!
+//! An indexed container stores records back to back, followed by a footer that holds the
+//! offset of every record:
+//!
+//! ```text
+//! [record]... [offset: u64 little-endian]... [count: u64 little-endian] [magic: u32 little-endian]
+//! ```
+//!
+//! Records written by [`IndexedWriter::append_value`] are full `encode()` outputs, including the
+//! encode magic number. [`IndexedReader`] reads the footer once and then gives O(1) access to
+//! any record by its number, and binary search over records sorted by a key.
+
+use crate::{decode, encode, Decoder, Encoder, EncoderError, Result};
+use bytes::{Buf, Bytes};
+use std::cmp::Ordering;
+use std::io::Write;
+
+/// Magic number at the very end of an indexed container ("SNIX" in little-endian).
+pub const INDEXED_FOOTER_MAGIC: u32 = 0x5849_4E53;
+
+/// Length of the fixed part of the footer (record count and magic number).
+const FOOTER_TAIL_LEN: usize = 12;
+
+/// Writes records followed by an offset index.
+///
+/// The container is only readable after [`finish`](IndexedWriter::finish) has written the
+/// index.
+pub struct IndexedWriter<W> {
+    inner: W,
+    position: u64,
+    offsets: Vec<u64>,
+}
+
+impl<W: Write> IndexedWriter<W> {
+    /// Creates a writer that writes a new container to `inner`.
+    pub fn new(inner: W) -> Self {
+        Self {
+            inner,
+            position: 0,
+            offsets: Vec::new(),
+        }
+    }
+
+    /// Returns the number of records written so far.
+    pub fn len(&self) -> usize {
+        self.offsets.len()
+    }
+
+    /// Returns `true` if no record has been written.
+    pub fn is_empty(&self) -> bool {
+        self.offsets.is_empty()
+    }
+
+    /// Appends a raw record and returns its record number.
+    pub fn append(&mut self, record: &[u8]) -> Result<usize> {
+        self.inner.write_all(record)?;
+        self.offsets.push(self.position);
+        self.position += record.len() as u64;
+        Ok(self.offsets.len() - 1)
+    }
+
+    /// Encodes `value` with [`encode`] and appends it as one record.
+    pub fn append_value<T: Encoder>(&mut self, value: &T) -> Result<usize> {
+        let record = encode(value)?;
+        self.append(&record)
+    }
+
+    /// Writes the offset index and returns the underlying writer.
+    pub fn finish(mut self) -> Result<W> {
+        for offset in &self.offsets {
+            self.inner.write_all(&offset.to_le_bytes())?;
+        }
+        self.inner
+            .write_all(&(self.offsets.len() as u64).to_le_bytes())?;
+        self.inner.write_all(&INDEXED_FOOTER_MAGIC.to_le_bytes())?;
+        self.inner.flush()?;
+        Ok(self.inner)
+    }
+}
+
+/// Random access to the records of an indexed container held in memory.
+///
+/// # Example
+/// ```rust
+/// use senax_encoder::indexed::{IndexedReader, IndexedWriter};
+/// use senax_encoder::{Decode, Encode};
+///
+/// #[derive(Encode, Decode, Debug, PartialEq)]
+/// struct Entry { key: u32, name: String }
+///
+/// let mut writer = IndexedWriter::new(Vec::new());
+/// for key in [10, 20, 30] {
+///     writer.append_value(&Entry { key, name: format!("entry-{}", key) }).unwrap();
+/// }
+/// let data = writer.finish().unwrap();
+///
+/// let reader = IndexedReader::new(data.into()).unwrap();
+/// assert_eq!(reader.len(), 3);
+/// assert_eq!(reader.get::<Entry>(1).unwrap().name, "entry-20");
+/// assert_eq!(reader.binary_search_by_key(&30, |e: &Entry| e.key).unwrap(), Ok(2));
+/// assert_eq!(reader.binary_search_by_key(&15, |e: &Entry| e.key).unwrap(), Err(1));
+/// ```
+#[derive(Debug, Clone)]
+pub struct IndexedReader {
+    data: Bytes,
+    offsets: Vec<u64>,
+    /// Start of the offset index, which is also the end of the last record.
+    index_start: u64,
+}
+
+impl IndexedReader {
+    /// Opens a container, reading and checking its offset index.
+    pub fn new(data: Bytes) -> Result<Self> {
+        if data.len() < FOOTER_TAIL_LEN {
+            return Err(EncoderError::eof(FOOTER_TAIL_LEN, data.len()));
+        }
+        let mut tail = data.slice(data.len() - FOOTER_TAIL_LEN..);
+        let count = tail.get_u64_le();
+        let magic = tail.get_u32_le();
+        if magic != INDEXED_FOOTER_MAGIC {
+            return Err(EncoderError::Decode(format!(
+                "Invalid indexed container magic number: expected 0x{:08X}, got 0x{:08X}",
+                INDEXED_FOOTER_MAGIC, magic
+            )));
+        }
+        let index_len = usize::try_from(count)
+            .unwrap_or(usize::MAX)
+            .saturating_mul(8);
+        if index_len > data.len() - FOOTER_TAIL_LEN {
+            return Err(EncoderError::eof(
+                index_len.saturating_add(FOOTER_TAIL_LEN),
+                data.len(),
+            ));
+        }
+        let index_start = data.len() - FOOTER_TAIL_LEN - index_len;
+
+        let mut index = data.slice(index_start..index_start + index_len);
+        let mut offsets = Vec::with_capacity(count as usize);
+        let mut previous = 0;
+        for _ in 0..count {
+            let offset = index.get_u64_le();
+            if offset < previous || offset > index_start as u64 {
+                return Err(EncoderError::Decode(format!(
+                    "Invalid record offset {} in indexed container",
+                    offset
+                )));
+            }
+            offsets.push(offset);
+            previous = offset;
+        }
+        Ok(Self {
+            data,
+            offsets,
+            index_start: index_start as u64,
+        })
+    }
+
+    /// Returns the number of records.
+    pub fn len(&self) -> usize {
+        self.offsets.len()
+    }
+
+    /// Returns `true` if the container holds no records.
+    pub fn is_empty(&self) -> bool {
+        self.offsets.is_empty()
+    }
+
+    /// Returns the raw bytes of record `index`.
+    pub fn record(&self, index: usize) -> Result<Bytes> {
+        let start = *self.offsets.get(index).ok_or_else(|| {
+            EncoderError::Decode(format!(
+                "Record {} out of range for a container of {} records",
+                index,
+                self.offsets.len()
+            ))
+        })?;
+        let end = self
+            .offsets
+            .get(index + 1)
+            .copied()
+            .unwrap_or(self.index_start);
+        Ok(self.data.slice(start as usize..end as usize))
+    }
+
+    /// Decodes record `index` with [`decode`].
+    pub fn get<T: Decoder>(&self, index: usize) -> Result<T> {
+        decode(&mut self.record(index)?)
+    }
+
+    /// Binary searches records sorted by the key that `extract` returns.
+    ///
+    /// Like [`slice::binary_search_by_key`], returns `Ok(index)` of a matching record or
+    /// `Err(index)` where a record with that key would be inserted. Only O(log n) records are
+    /// decoded.
+    pub fn binary_search_by_key<K: Ord, T: Decoder>(
+        &self,
+        key: &K,
+        extract: impl Fn(&T) -> K,
+    ) -> Result<std::result::Result<usize, usize>> {
+        let mut low = 0;
+        let mut high = self.offsets.len();
+        while low < high {
+            let mid = low + (high - low) / 2;
+            let record: T = self.get(mid)?;
+            match extract(&record).cmp(key) {
+                Ordering::Less => low = mid + 1,
+                Ordering::Greater => high = mid,
+                Ordering::Equal => return Ok(Ok(mid)),
+            }
+        }
+        Ok(Err(low))
+    }
+
+    /// Decodes every record in order.
+    pub fn iter<T: Decoder>(&self) -> impl Iterator<Item = Result<T>> + '_ {
+        (0..self.offsets.len()).map(move |index| self.get(index))
+    }
+}
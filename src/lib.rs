@@ -3,7 +3,7 @@
 //! A fast, compact, and schema-evolution-friendly binary serialization library for Rust.
 //!
 //! - Supports struct/enum encoding with field/variant IDs for forward/backward compatibility
-//! - Efficient encoding for primitives, collections, Option, String, bytes, and popular crates (chrono, uuid, ulid, rust_decimal, bigdecimal, indexmap, fxhash, ahash, smol_str, serde_json)
+//! - Efficient encoding for primitives, collections, Option, String, bytes, and popular crates (chrono, time, uuid, ulid, rust_decimal, bigdecimal, indexmap, fxhash, ahash, smol_str, serde_json)
 //! - Custom derive macros for ergonomic usage
 //! - Feature-gated support for optional dependencies
 //!
@@ -28,15 +28,47 @@
 //! - `#[senax(default)]` — If a field is missing during decoding, its value is set to `Default::default()` instead of causing an error. For `Option<T>`, this means `None`.
 //! - `#[senax(skip_encode)]` — This field is not written during encoding. On decode, it is set to `Default::default()`.
 //! - `#[senax(skip_decode)]` — This field is ignored during decoding and always set to `Default::default()`. It is still encoded if present.
+//! - `#[senax(skip)]` — Shorthand for `skip_encode` and `skip_decode`: the field is neither written nor read, and is always `Default::default()` after decoding.
+//! - `#[senax(skip_encode_if = "path::to::fn")]` — This field is not written during encoding when `fn(&T) -> bool` returns `true` for its value (e.g. `Vec::is_empty`). On decode, missing fields are set to `Default::default()`. Applies to named fields.
 //! - `#[senax(skip_default)]` — This field is not written during encoding if its value equals the default value. On decode, missing fields are set to `Default::default()`.
+//! - `#[senax(with = "Proxy")]` — Encodes the field through a proxy declared with [`senax_remote!`], which converts a third-party type that implements no codec traits to and from a representation type (e.g. `chrono::Weekday` as `u8`). On an `Option` field the proxy converts the inner value. Applies to named struct fields.
 //! - `#[senax(rename = "name")]` — Use the given string as the logical field/variant name for ID calculation. Useful for renaming fields/variants while keeping the same wire format.
+//! - `#[senax(tolerant)]` — On a tuple enum variant, accept payloads with more fields than expected and skip the extra trailing fields. Without it, adding a field to a tuple variant makes older readers fail with `FieldCountMismatch`. Payloads with fewer fields are still rejected.
+//! - `#[senax(encrypt)]` — Seals the field's encoded bytes with XChaCha20-Poly1305 using the key installed by `field_crypto::with_field_key`. Requires the `chacha20poly1305` feature. Applies to named struct fields and `Encode`/`Decode` only.
+//! - `#[senax(redact)]` — When `EncodeConfig::redact` is set, the field is encoded as its default value (`Option` and `skip_default` fields are omitted). Applies to named struct fields and `Encode` only.
+//! - `#[senax(flat_keys)]` — On a map field with tuple keys, writes the key arity once per map instead of a tuple header on every key. Decoding also accepts maps written without the attribute. Applies to named struct fields and `Encode`/`Decode` only.
+//! - `#[senax(ext_block)]` — Marks an [`ext_block::ExtBlock`] field: a length-prefixed region of vendor extensions (types implementing [`ext_block::Extension`]) that third parties can extend without changing the struct. Decoding keeps the region verbatim, so extensions the reader does not know survive a re-encode. The field is omitted when empty and empty when missing. Applies to named struct fields.
+//! - `#[senax(sensitive)]` — Holds the decoded value in `zeroize::Zeroizing` storage until the struct is built, so it is wiped if decoding fails. The field type must implement `zeroize::Zeroize`. Requires the `zeroize` feature. Applies to named fields and `Decode` only; not supported with `builder`.
+//! - `#[senax(max_len = N)]` — Declares that a string or collection field holds at most `N` elements (bytes for strings), for use in `#[senax(max_size)]` types. Named struct fields longer than `N` fail to encode and decode.
+//! - `#[senax(sort)]` — Writes a sequence or set field's elements in ascending order (requires `Ord`), for deterministic payloads when callers build collections in varying order. The wire format is unchanged. Applies to named struct fields and `Encode`/`Pack`.
+//! - `#[senax(min = X, max = Y)]` — Inclusive bounds on a numeric field, checked during decode ([`StructDecodeError::OutOfRange`]).
+//! - `#[senax(deprecated)]` — Stops encoding the field but keeps decoding it, using `Default::default()` when it is missing. Each time a derived decoder reads the field from a payload, the read is reported to the hook set with [`deprecated_fields::set_deprecated_field_hook`] and counted by [`collect_deprecated_fields`], so the field can be deleted once producers stop sending it.
+//! - `#[senax(prefix)]` — Marks a leading field of a named struct as part of its header. The derive generates a `<Name>Prefix` struct holding these fields and an inherent `<Name>::decode_prefix(&Bytes)` that reads only them from an `encode`d payload (without magic) and returns the prefix plus the unread remaining bytes, so routers can inspect headers without decoding bodies. Prefix fields must come first in declaration order. Not supported on generic structs.
+//! - `#[senax(accept_tuple)]` — (container) Lets a named struct decode payloads written by a tuple struct, assigning values to fields by position.
+//! - `#[senax(accept_named)]` — (container) Lets a tuple struct decode payloads written by a named struct. Every field must carry an `id` or `rename` naming the field ID it is read from.
+//! - `#[senax(crate = "path")]` — (container) Path to senax-encoder used by the generated code, for when it is re-exported from a facade crate or renamed in `Cargo.toml`. Generated code reaches `bytes` through this path too.
+//! - `#[senax(discriminant_ids)]` — (container) On an enum, use explicit discriminants (`Variant = 3`) as variant IDs instead of CRC64, aligning wire IDs with existing FFI/protocol numbers. Variants without `= N` take the previous value plus one, as in Rust. `#[senax(id = N)]` on a variant still takes precedence. Discriminants must be non-negative integer literals.
+//! - `#[senax(string_repr)]` — (container) On an enum, encode unit variants as their name string (the `rename` value if set) instead of a variant ID, for interop with systems that match on names and for human-auditable payloads. Decoding accepts both the string and the ID form. Variants with fields keep the ID form. Applies to `Encode`/`Decode` only; `Pack` is unchanged.
+//! - `#[senax(rename_all = "camelCase")]` — (container) Converts struct field names or enum variant names to the given convention (serde's spellings, e.g. `camelCase`, `snake_case`, `kebab-case`) before hashing them into IDs, so renaming fields to a new naming convention keeps the wire format. A field's own `rename` or `id` takes precedence.
+//! - `#[senax(id_salt = "v2")]` — (container) Mixes the salt into every field and variant ID hashed from a name (including fields of enum variants and the pack structure hash), so bumping it deliberately breaks wire compatibility when a type's meaning changes. Explicit `id`s are kept. `core::field_id_from_salted_name` computes the salted IDs.
+//! - `#[senax(ids_file = "senax-ids.toml")]` — (container) Takes every named field and variant ID from a mapping file (path relative to the crate root), a reviewable single source of truth for wire IDs. The derive fails if a field or variant is not pinned, or if its `#[senax(id = N)]` disagrees with the file.
+//! - `#[senax(bound = "T: Encoder + Default + 'static")]` — (container) On a generic type, replaces the bounds the derives add to type parameters. By default each `Encode`/`Decode`/`Pack`/`Unpack` impl requires `T: Encoder + 'static` (or `Decoder`, `Packer`, `Unpacker`) for every type parameter used by a field it writes or reads; parameters only used in skipped fields get no bound. Use `bound` when a parameter needs more (e.g. `Default` for a `#[senax(default)] value: T` field) or less, as with serde's `bound`. `bound = ""` removes the bounds.
+//! - `#[senax(recursive)]` — (container) Applies the decode depth limit (`DecodeConfig::max_depth`) to a type that recurses only through other types, e.g. `A { b: Box<B> }` with `B { a: Vec<A> }`. Types with a field naming the type itself (`Box<Expr>`, `Vec<Node>`, `Option<Box<Self>>`) get the limit automatically.
+//! - `#[senax(max_size)]` — (container) Also implements [`MaxEncodedSize`], a compile-time upper bound on the encoded size. Every encoded field must have a bounded type or a `max_len` bound.
+//! - `#[senax(wire_info)]` — (container) Also implements [`wire_info::WireInfo`], which lists the field and variant IDs the derived encoder writes, dumps the annotated encoding of `Default::default()` and renders any value's encoding with `fmt_wire()`.
+//! - `#[senax(schema)]` — (container) Also implements [`schema::HasSchema`], whose `schema()` returns a [`schema::TypeSchema`] with the field names, IDs and types the derived encoder writes, nested through the field types. Field doc comments are kept in the schema (not on the wire) for generated documentation. Use it to check wire compatibility between services at startup or to document the format.
+//! - `#[senax(builder)]` — (container) On a named struct, generates `<Name>Builder` with chained setters and a `try_build` that reports missing required fields. Derived `Decode` fills the struct through the builder.
+//! - `#[senax(union_as = "EnumName")]` — (container) On a union, encodes and decodes through a tagged view type, using `From<&Union> for EnumName` and `From<EnumName> for Union`.
 //!
 //! ## Feature Flags
 //!
 //! The following optional features enable support for popular crates and types:
 //!
+//! - `std` (default) — Enables everything that needs the standard library: `HashMap`/`HashSet` support, `EncoderError::Io`, per-call configuration (`with_decode_config`, `encode_with_config`, ...) and the modules built on them. Without it the crate is `#![no_std]` and only needs `alloc`; the derives, the encode/pack functions and the `alloc` and `heapless` types keep working, and decoding uses the default `DecodeConfig`. Features for other crates enable `std`, except `heapless`, `smallvec`, `arrayvec`, `time`, `defmt` and `zeroize`.
+//!
 //! ### External Crate Support
 //! - `chrono` — Enables encoding/decoding of `chrono::DateTime`, `NaiveDate`, and `NaiveTime` types.
+//! - `time` — Enables encoding/decoding of `time::OffsetDateTime`, `PrimitiveDateTime`, `Date`, and `Time`, in the same formats as their chrono counterparts.
 //! - `uuid` — Enables encoding/decoding of `uuid::Uuid`.
 //! - `ulid` — Enables encoding/decoding of `ulid::Ulid` (shares the same tag as UUID for binary compatibility).
 //! - `rust_decimal` — Enables encoding/decoding of `rust_decimal::Decimal`.
@@ -45,17 +77,184 @@
 //! - `fxhash` — Enables encoding/decoding of `fxhash::FxHashMap` and `fxhash::FxHashSet` (fast hash collections).
 //! - `ahash` — Enables encoding/decoding of `ahash::AHashMap` and `ahash::AHashSet` (high-performance hash collections).
 //! - `smol_str` — Enables encoding/decoding of `smol_str::SmolStr` (small string optimization).
+//! - `heapless` — Enables encoding/decoding of `heapless::Vec<T, N>` and `heapless::String<N>` (fixed-capacity collections for embedded use). Decoding a value longer than `N` fails with `EncoderError::CapacityExceeded`.
+//! - `smallvec` — Enables encoding/decoding of `smallvec::SmallVec<[T; N]>`, wire-compatible with `Vec<T>` so fields can switch between the two without breaking payloads.
+//! - `arrayvec` — Enables encoding/decoding of `arrayvec::ArrayVec<T, N>` and `arrayvec::ArrayString<N>`, wire-compatible with `Vec<T>`/`String`. Decoding a value longer than `N` fails with `EncoderError::CapacityExceeded`.
 //! - `serde_json` — Enables encoding/decoding of `serde_json::Value` (JSON values as dynamic type).
 //! - `raw_value` — Enables encoding/decoding of `Box<serde_json::value::RawValue>` (raw JSON strings). Requires `serde_json` feature.
+//! - `arbitrary_precision` — Enables serde_json's `arbitrary_precision` and encodes JSON numbers that do not fit `u64`/`i64` as their exact decimal text instead of `f64`. Requires `serde_json` feature.
+//! - `serde_yaml` — Enables encoding/decoding of `serde_yaml::Value` with the `serde_json::Value` tags.
+//! - `toml` — Enables encoding/decoding of `toml::Value` with the `serde_json::Value` tags.
+//!
+//! ### Utilities
+//! - `textenc` — Enables `encode_base64`/`decode_base64` and `encode_hex`/`decode_hex` for embedding payloads in text (JSON/YAML config, HTTP headers).
+//! - `defmt` — Implements `defmt::Format` for `EncoderError` and `ErrorCode`, logging compact error codes instead of strings. Error messages are still built when errors are created.
+//! - `chacha20poly1305` — Enables the `field_crypto` module used by `#[senax(encrypt)]` fields, and `encode_encrypted`/`decode_encrypted`, which seal a whole payload in an authenticated envelope.
+//! - `derive-test` — Enables the `derive_test` module: `expand_derives!` and expansion snapshot assertions for testing the derive macros.
+//! - `doc-store` — Enables the `doc_store` module: collections of encoded documents with field-ID indexes and query-by-field scans.
+//! - `futures` — Enables the `framed` module: `SenaxSink` and `SenaxStream`, which implement `futures::Sink`/`Stream` over `AsyncWrite`/`AsyncRead` with record log framing.
+//! - `senax_serde` — Enables the `senax_serde` module: `SenaxSerde<T>` encodes and decodes any `serde::Serialize`/`Deserialize` type in the same format as the derive macros.
+//! - `bincode` / `postcard` — Enable `compat::from_bincode` / `compat::from_postcard`, which read payloads written by those crates through serde and encode them with senax, for migrating stored data.
+//! - `zstd` / `lz4` — Enable `encode_compressed` / `decode_compressed` with `Compression::Zstd(level)` / `Compression::Lz4`, which compress the encoded bytes behind a header naming the algorithm.
+//! - `zeroize` — Enables the `sensitive` module used by `#[senax(sensitive)]` fields, implements the codec traits for `zeroize::Zeroizing<T>` and wipes the plaintext of `#[senax(encrypt)]` fields.
+//! - `tokio` — Enables the `codec` module: `SenaxCodec` for `tokio_util::codec::Framed` and the `encode_async`/`decode_async` helpers, with record log framing.
+//! - `unicode-normalization` — Adds `DecodeConfig::normalize_nfc`, which converts decoded strings to Unicode Normalization Form C.
+//! - `panic-free` — Denies clippy's `unwrap_used`, `expect_used`, `panic`, `unreachable`, `todo` and `unimplemented` lints in this crate, so `cargo clippy --features panic-free` checks that no library code path panics. The few APIs that panic by contract (`Value::set_field`, the `assert_*` test helpers and the `derive_test` harness) are the only exceptions. Impls generated by the derives deny the same lints in the crates that use them.
 
+#![cfg_attr(not(feature = "std"), no_std)]
+#![cfg_attr(
+    feature = "panic-free",
+    deny(
+        clippy::unwrap_used,
+        clippy::expect_used,
+        clippy::panic,
+        clippy::unreachable,
+        clippy::todo,
+        clippy::unimplemented
+    )
+)]
+
+#[cfg(feature = "std")]
+mod capabilities;
+#[cfg(feature = "std")]
+pub mod changelog;
+#[cfg(feature = "std")]
+mod checksum;
+#[cfg(feature = "tokio")]
+pub mod codec;
+#[cfg(feature = "std")]
+pub mod compat;
+#[cfg(any(feature = "zstd", feature = "lz4"))]
+mod compression;
+mod config;
 pub mod core;
+#[cfg(feature = "std")]
+pub mod debug;
+pub mod deprecated_fields;
+#[cfg(feature = "derive-test")]
+pub mod derive_test;
+#[cfg(feature = "doc-store")]
+pub mod doc_store;
+#[cfg(feature = "chacha20poly1305")]
+mod encrypted;
+mod error_code;
+pub mod ext_block;
 mod features;
+#[cfg(feature = "chacha20poly1305")]
+pub mod field_crypto;
+mod frame;
+#[cfg(feature = "futures")]
+pub mod framed;
+#[cfg(feature = "std")]
+pub mod hello;
+#[cfg(feature = "std")]
+pub mod id_map;
+#[cfg(feature = "std")]
+pub mod indexed;
+pub mod max_size;
+#[cfg(feature = "std")]
+pub mod migrate;
+#[cfg(feature = "std")]
+pub mod minimize;
+#[cfg(feature = "std")]
+pub mod multipart;
+#[cfg(feature = "std")]
+pub mod nullable_vec;
+#[cfg(feature = "std")]
+pub mod patch;
+#[cfg(feature = "std")]
+pub mod peek;
+#[cfg(feature = "std")]
+pub mod query;
+#[cfg(feature = "std")]
+pub mod record_log;
+#[cfg(feature = "std")]
+pub mod registry;
+pub mod remote;
+#[cfg(feature = "std")]
+pub mod schema;
+pub mod self_describing;
+#[cfg(feature = "senax_serde")]
+pub mod senax_serde;
+#[cfg(feature = "zeroize")]
+pub mod sensitive;
+#[cfg(feature = "std")]
+pub mod seq;
+#[cfg(feature = "std")]
+pub mod service;
+#[cfg(feature = "std")]
+pub mod session;
+#[cfg(feature = "std")]
+pub mod string_key_map;
+#[cfg(feature = "std")]
+pub mod testvectors;
+#[cfg(feature = "textenc")]
+mod textenc;
+#[cfg(feature = "std")]
+pub mod timeseries;
+pub mod unknown_fields;
+#[cfg(feature = "std")]
+pub mod validate;
+#[cfg(feature = "std")]
+pub mod value;
+#[cfg(feature = "std")]
+pub mod wire_info;
+
+#[cfg(feature = "std")]
+pub use capabilities::{capabilities, Capabilities};
+#[cfg(feature = "std")]
+pub use checksum::{decode_checked, encode_checked, Checksum};
+#[cfg(any(feature = "zstd", feature = "lz4"))]
+pub use compression::{decode_compressed, encode_compressed, Compression, COMPRESSED_MAGIC};
+pub use config::{
+    current_decode_config, current_encode_config, decode_nested, DecodeConfig, DecodeLimit,
+    DecodeOptions, EncodeConfig, DEFAULT_MAX_DEPTH,
+};
+#[cfg(feature = "std")]
+pub use config::{with_decode_config, with_encode_config};
+#[cfg(feature = "std")]
+pub use debug::{inspect, inspect_tree, InspectNode};
+#[cfg(feature = "std")]
+pub use deprecated_fields::{collect_deprecated_fields, DeprecatedFieldStats};
+#[cfg(feature = "chacha20poly1305")]
+pub use encrypted::{decode_encrypted, encode_encrypted, random_nonce, ENCRYPTED_MAGIC};
+pub use error_code::ErrorCode;
+#[cfg(feature = "std")]
+pub use id_map::IdMap;
+pub use max_size::MaxEncodedSize;
+#[cfg(feature = "std")]
+pub use nullable_vec::NullableVec;
+#[cfg(feature = "std")]
+pub use peek::{peek_info, PayloadInfo, StructKind};
+#[cfg(feature = "std")]
+pub use query::{query, QueryMatch};
+#[cfg(feature = "std")]
+pub use string_key_map::StringKeyMap;
+#[cfg(feature = "textenc")]
+pub use textenc::{decode_base64, decode_hex, encode_base64, encode_hex};
+#[cfg(feature = "std")]
+pub use unknown_fields::{collect_unknown_fields, UnknownFieldStats};
+#[cfg(feature = "std")]
+pub use validate::{validate_payload, ValidationReport};
+#[cfg(feature = "std")]
+pub use value::Value;
 
 use bytes::{Buf, BufMut, Bytes, BytesMut};
 pub use senax_encoder_derive::{Decode, Encode, Pack, Unpack};
-use std::collections::HashMap;
-use std::collections::{BTreeMap, BTreeSet, HashSet};
-use std::sync::Arc;
+
+/// Re-export of the `bytes` crate used by the traits and by derived code.
+pub use bytes;
+
+extern crate alloc;
+
+use alloc::boxed::Box;
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use std::collections::{HashMap, HashSet};
 
 /// Errors that can occur during encoding or decoding operations.
 #[derive(Debug, thiserror::Error)]
@@ -66,9 +265,50 @@ pub enum EncoderError {
     /// The value could not be decoded (e.g., invalid data, type mismatch, or schema evolution error).
     #[error("Decode error: {0}")]
     Decode(String),
-    /// The buffer did not contain enough data to complete the operation.
-    #[error("Insufficient data in buffer")]
-    InsufficientData,
+    /// The input ended before the value was complete.
+    ///
+    /// At the point where decoding stopped, `needed` bytes were required but only `available`
+    /// were left (`needed > available`). Both count from that point, not from the start of the
+    /// input, and `needed` is a lower bound: more may be required once they arrive. Unlike the
+    /// other decode errors, this one means the data seen so far may be a valid prefix, so a
+    /// streaming reader should wait for more bytes and retry instead of treating the input as
+    /// corrupt.
+    #[error("Unexpected end of input: needed {needed} bytes, {available} available")]
+    UnexpectedEof { needed: usize, available: usize },
+    /// The encoded payload exceeded the limit given to [`encode_bounded`].
+    ///
+    /// Encoding stops as soon as the limit is crossed, so `actual` is the length reached at that
+    /// point and may be smaller than the full encoded size.
+    #[error("Encoded payload of {actual} bytes exceeds the limit of {limit} bytes")]
+    TooLarge { actual: usize, limit: usize },
+    /// A decoded length exceeded the capacity of a fixed-capacity container.
+    #[error("Length {len} exceeds capacity {capacity}")]
+    CapacityExceeded { len: usize, capacity: usize },
+    /// A set contained the same element more than once while strict set decoding was enabled.
+    #[error("Duplicate set element at index {index}")]
+    DuplicateSetElement { index: usize },
+    /// Recursive values were nested deeper than [`DecodeConfig::max_depth`].
+    #[error("Nesting depth exceeds the limit of {limit}")]
+    DepthLimitExceeded { limit: usize },
+    /// A decoded length exceeded one of the limits set in [`DecodeConfig`].
+    ///
+    /// For [`DecodeLimit::AllocatedBytes`], `len` is the total that would have been allocated.
+    #[error("Decoded {kind} of {len} exceeds the limit of {limit}")]
+    LimitExceeded {
+        kind: DecodeLimit,
+        len: usize,
+        limit: usize,
+    },
+    /// The checksum of an [`encode_checked`] envelope does not match its payload.
+    #[error("Checksum mismatch: expected 0x{expected:X}, computed 0x{actual:X}")]
+    ChecksumMismatch { expected: u64, actual: u64 },
+    /// Bytes were left over after decoding with [`decode_exact`].
+    #[error("{remaining} trailing bytes after the decoded value")]
+    TrailingData { remaining: usize },
+    /// An I/O error occurred while reading or writing a record log.
+    #[cfg(feature = "std")]
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
     /// Struct-specific decode error
     #[error(transparent)]
     StructDecode(#[from] StructDecodeError),
@@ -77,10 +317,18 @@ pub enum EncoderError {
     EnumDecode(#[from] EnumDecodeError),
 }
 
+impl EncoderError {
+    /// An [`EncoderError::UnexpectedEof`] for a read of `needed` bytes with `available` left.
+    #[inline]
+    pub(crate) fn eof(needed: usize, available: usize) -> Self {
+        EncoderError::UnexpectedEof { needed, available }
+    }
+}
+
 /// The result type used throughout this crate for encode/decode operations.
 ///
 /// All `Encode` and `Decode` trait methods return this type.
-pub type Result<T> = std::result::Result<T, EncoderError>;
+pub type Result<T> = ::core::result::Result<T, EncoderError>;
 
 /// Derive-specific error types for struct operations
 #[derive(Debug, thiserror::Error)]
@@ -104,6 +352,20 @@ pub enum StructDecodeError {
         expected: u64,
         actual: u64,
     },
+    #[error("Unknown field ID 0x{field_id:016X} for struct {struct_name}")]
+    UnknownField {
+        field_id: u64,
+        struct_name: &'static str,
+    },
+    #[error(
+        "Field '{field}' of struct {struct_name} is {value}, outside the allowed range {range}"
+    )]
+    OutOfRange {
+        field: &'static str,
+        struct_name: &'static str,
+        value: Box<str>,
+        range: Box<str>,
+    },
 }
 
 /// Derive-specific error types for enum operations
@@ -116,6 +378,11 @@ pub enum EnumDecodeError {
         variant_id: u64,
         enum_name: &'static str,
     },
+    #[error("Unknown variant name '{name}' for enum {enum_name}")]
+    UnknownVariantName {
+        name: String,
+        enum_name: &'static str,
+    },
     #[error("Unknown unit variant ID: 0x{variant_id:016X} for enum {enum_name}")]
     UnknownUnitVariantId {
         variant_id: u64,
@@ -151,6 +418,12 @@ pub enum EnumDecodeError {
         expected: u64,
         actual: u64,
     },
+    #[error("Unknown field ID 0x{field_id:016X} for variant {enum_name}::{variant_name}")]
+    UnknownField {
+        field_id: u64,
+        enum_name: &'static str,
+        variant_name: &'static str,
+    },
 }
 
 /// Magic number for encoded format (0xA55A in little-endian)
@@ -185,7 +458,7 @@ const PACK_MAGIC: u16 = 0xDADA;
 /// ```
 pub fn decode<T: Decoder>(reader: &mut Bytes) -> Result<T> {
     if reader.remaining() < 2 {
-        return Err(EncoderError::InsufficientData);
+        return Err(EncoderError::eof(2, reader.remaining()));
     }
     let magic = reader.get_u16_le();
     if magic != ENCODE_MAGIC {
@@ -197,6 +470,240 @@ pub fn decode<T: Decoder>(reader: &mut Bytes) -> Result<T> {
     T::decode(reader)
 }
 
+/// Convenience function to decode a value from bytes using the given configuration.
+///
+/// Behaves like [`decode`], but applies `config` for the duration of the call.
+///
+/// # Example
+/// ```rust
+/// use senax_encoder::{encode, decode_with_config, DecodeConfig, EncoderError};
+/// use std::collections::BTreeSet;
+///
+/// let mut buf = encode(&vec![1u32, 2, 1]).unwrap();
+/// let config = DecodeConfig { strict_sets: true, ..Default::default() };
+/// let result = decode_with_config::<BTreeSet<u32>>(&mut buf, &config);
+/// assert!(matches!(result, Err(EncoderError::DuplicateSetElement { index: 2 })));
+/// ```
+#[cfg(feature = "std")]
+pub fn decode_with_config<T: Decoder>(reader: &mut Bytes, config: &DecodeConfig) -> Result<T> {
+    with_decode_config(config, || decode(reader))
+}
+
+/// Decodes a value from untrusted input, enforcing the limits set in `options`.
+///
+/// This is [`decode_with_config`]; [`DecodeOptions`] is [`DecodeConfig`]. Set
+/// `max_string_len`, `max_collection_len`, `max_depth` and `max_alloc_bytes` to bound the memory
+/// and stack a hostile payload can make the decoder use. Even without limits, the decoder never
+/// reserves room for more elements than the input has bytes left.
+///
+/// # Example
+/// ```rust
+/// use senax_encoder::{decode_with_options, encode, DecodeLimit, DecodeOptions, EncoderError};
+///
+/// let options = DecodeOptions {
+///     max_string_len: Some(64),
+///     max_collection_len: Some(1_000),
+///     max_alloc_bytes: Some(1 << 20),
+///     ..Default::default()
+/// };
+/// let mut buf = encode(&"x".repeat(65)).unwrap();
+/// let err = decode_with_options::<String>(&mut buf, &options).unwrap_err();
+/// assert!(matches!(
+///     err,
+///     EncoderError::LimitExceeded { kind: DecodeLimit::StringLength, len: 65, limit: 64 }
+/// ));
+/// ```
+#[cfg(feature = "std")]
+pub fn decode_with_options<T: Decoder>(reader: &mut Bytes, options: &DecodeOptions) -> Result<T> {
+    decode_with_config(reader, options)
+}
+
+/// Decodes a value like [`decode`] and requires it to consume the whole buffer.
+///
+/// Fails with [`EncoderError::TrailingData`] if any bytes are left after the value, which in a
+/// protocol that carries one message per frame means the frame is corrupt. `reader` is left
+/// positioned after the decoded value either way.
+///
+/// # Example
+/// ```rust
+/// use senax_encoder::{decode_exact, encode, EncoderError};
+/// use bytes::{BufMut, BytesMut};
+///
+/// let frame = encode(&42u32).unwrap();
+/// assert_eq!(decode_exact::<u32>(&mut frame.clone()).unwrap(), 42);
+///
+/// let mut corrupt = BytesMut::from(&frame[..]);
+/// corrupt.put_slice(&[0xde, 0xad]);
+/// let err = decode_exact::<u32>(&mut corrupt.freeze()).unwrap_err();
+/// assert!(matches!(err, EncoderError::TrailingData { remaining: 2 }));
+/// ```
+pub fn decode_exact<T: Decoder>(reader: &mut Bytes) -> Result<T> {
+    let value = decode(reader)?;
+    if reader.has_remaining() {
+        return Err(EncoderError::TrailingData {
+            remaining: reader.remaining(),
+        });
+    }
+    Ok(value)
+}
+
+/// Encodes a value like [`encode`] and prepends the payload length, for stream transports.
+///
+/// Frames use the same header as the record log and the `codec` and `framed` modules, so
+/// they can be mixed on one stream:
+///
+/// ```text
+/// [payload_len: u32 little-endian] [payload: encode() output] ...
+/// ```
+///
+/// Read frames with [`decode_framed`] or, in a socket read loop, [`try_decode_framed`].
+///
+/// # Example
+/// ```rust
+/// use senax_encoder::{decode_framed, encode_framed};
+/// use bytes::BytesMut;
+///
+/// let mut stream = BytesMut::new();
+/// stream.extend_from_slice(&encode_framed(&"first".to_string()).unwrap());
+/// stream.extend_from_slice(&encode_framed(&"second".to_string()).unwrap());
+///
+/// let mut stream = stream.freeze();
+/// assert_eq!(decode_framed::<String>(&mut stream).unwrap(), "first");
+/// assert_eq!(decode_framed::<String>(&mut stream).unwrap(), "second");
+/// assert!(stream.is_empty());
+/// ```
+pub fn encode_framed<T: Encoder>(value: &T) -> Result<Bytes> {
+    let payload = encode(value)?;
+    let mut writer = BytesMut::with_capacity(frame::HEADER_LEN + payload.len());
+    writer.put_slice(&frame::header(payload.len())?);
+    writer.put_slice(&payload);
+    Ok(writer.freeze())
+}
+
+/// Default limit on the payload length accepted by [`try_decode_framed`].
+pub const DEFAULT_MAX_FRAME_LEN: usize = 16 * 1024 * 1024;
+
+/// Decodes one frame written by [`encode_framed`], leaving `reader` at the next frame.
+///
+/// The value must fill its frame exactly; leftover bytes inside the frame fail with
+/// [`EncoderError::TrailingData`]. A frame cut short fails with
+/// [`EncoderError::UnexpectedEof`] and leaves `reader` at the start of the frame.
+pub fn decode_framed<T: Decoder>(reader: &mut Bytes) -> Result<T> {
+    let len = frame::read_header(reader)? as usize;
+    let available = reader.remaining() - frame::HEADER_LEN;
+    if available < len {
+        return Err(EncoderError::eof(len, available));
+    }
+    reader.advance(frame::HEADER_LEN);
+    decode_exact(&mut reader.split_to(len))
+}
+
+/// Decodes the first frame in `buf` if it has arrived completely, or returns `None`.
+///
+/// Complete frames are removed from `buf`, including frames that fail to decode, so a read
+/// loop can keep appending received bytes to the same buffer. An incomplete frame is left in
+/// place. Frames longer than [`DEFAULT_MAX_FRAME_LEN`] fail with [`EncoderError::Decode`] as
+/// soon as their header arrives; see [`try_decode_framed_with_max_len`] for another limit.
+///
+/// # Example
+/// ```rust
+/// use senax_encoder::{encode_framed, try_decode_framed};
+/// use bytes::BytesMut;
+///
+/// let frame = encode_framed(&vec![1u32, 2, 3]).unwrap();
+/// let mut buf = BytesMut::new();
+///
+/// // The first half of the frame arrives
+/// buf.extend_from_slice(&frame[..6]);
+/// assert_eq!(try_decode_framed::<Vec<u32>>(&mut buf).unwrap(), None);
+///
+/// // The rest arrives
+/// buf.extend_from_slice(&frame[6..]);
+/// assert_eq!(try_decode_framed::<Vec<u32>>(&mut buf).unwrap(), Some(vec![1, 2, 3]));
+/// assert!(buf.is_empty());
+/// ```
+pub fn try_decode_framed<T: Decoder>(buf: &mut BytesMut) -> Result<Option<T>> {
+    try_decode_framed_with_max_len(buf, DEFAULT_MAX_FRAME_LEN)
+}
+
+/// Decodes the first frame in `buf` like [`try_decode_framed`], accepting payloads of up to
+/// `max_len` bytes.
+///
+/// A header announcing a longer payload, or a length reserved for record log markers, fails
+/// with [`EncoderError::Decode`] and leaves `buf` untouched; the stream cannot be resynchronized
+/// after that, so the connection should be closed.
+pub fn try_decode_framed_with_max_len<T: Decoder>(
+    buf: &mut BytesMut,
+    max_len: usize,
+) -> Result<Option<T>> {
+    if buf.len() < frame::HEADER_LEN {
+        return Ok(None);
+    }
+    let len = frame::read_header(buf)?;
+    if len > frame::MAX_PAYLOAD_LEN {
+        return Err(EncoderError::Decode(format!(
+            "Invalid frame length {:#x}",
+            len
+        )));
+    }
+    let len = len as usize;
+    if len > max_len {
+        return Err(EncoderError::Decode(format!(
+            "Frame of {} bytes exceeds the limit of {} bytes",
+            len, max_len
+        )));
+    }
+    let frame_len = frame::HEADER_LEN
+        .checked_add(len)
+        .ok_or_else(|| EncoderError::Decode(format!("Frame length {} overflows", len)))?;
+    if buf.len() < frame_len {
+        return Ok(None);
+    }
+    let mut payload = buf.split_to(frame_len).freeze();
+    payload.advance(frame::HEADER_LEN);
+    decode_exact(&mut payload).map(Some)
+}
+
+/// Convenience function to decode a value from an owned `Vec<u8>`, as [`decode`] does.
+///
+/// The vector is turned into `Bytes` without copying. `Bytes` values in the result (and
+/// `Value::Binary`) are slices of the vector, so its whole allocation stays alive until the last
+/// of them is dropped; decode with [`DecodeConfig::copy_out`] (e.g. through
+/// [`with_decode_config`]) to copy them out instead. Strings, `Vec<u8>` and all other types are
+/// always copied.
+///
+/// # Example
+/// ```rust
+/// use senax_encoder::{decode_from_vec, encode};
+/// use bytes::Bytes;
+///
+/// let input = encode(&Bytes::from_static(b"token")).unwrap().to_vec();
+/// let token: Bytes = decode_from_vec(input).unwrap();
+/// assert_eq!(token, "token");
+/// ```
+pub fn decode_from_vec<T: Decoder>(bytes: Vec<u8>) -> Result<T> {
+    decode(&mut Bytes::from(bytes))
+}
+
+/// Convenience function to decode a value from a shared `Arc<[u8]>`, as [`decode`] does.
+///
+/// The input is not copied: `Bytes` values in the result (and `Value::Binary`) hold a reference
+/// to the `Arc`, keeping the whole buffer alive until the last of them is dropped. Decode with
+/// [`DecodeConfig::copy_out`] to copy them out instead. All other types are always copied.
+///
+/// # Example
+/// ```rust
+/// use senax_encoder::{decode_from_arc_bytes, encode};
+/// use std::sync::Arc;
+///
+/// let input: Arc<[u8]> = encode(&vec![1u32, 2, 3]).unwrap().to_vec().into();
+/// let values: Vec<u32> = decode_from_arc_bytes(input.clone()).unwrap();
+/// assert_eq!(values, vec![1, 2, 3]);
+/// ```
+pub fn decode_from_arc_bytes<T: Decoder>(bytes: Arc<[u8]>) -> Result<T> {
+    decode(&mut Bytes::from_owner(bytes))
+}
+
 /// Convenience function to encode a value to bytes with magic number.
 ///
 /// This function adds the encode magic number (0xA55A) at the beginning of the data
@@ -260,6 +767,55 @@ pub fn encode_to<T: Encoder>(value: &T, writer: &mut BytesMut) -> Result<()> {
     value.encode(writer)
 }
 
+/// Convenience function to encode a value to bytes with magic number using the given configuration.
+///
+/// Behaves like [`encode`], but applies `config` for the duration of the call.
+///
+/// # Example
+/// ```rust
+/// use senax_encoder::{encode_with_config, EncodeConfig};
+/// use std::collections::HashMap;
+///
+/// let map: HashMap<String, u32> = (0..32).map(|i| (i.to_string(), i)).collect();
+/// let config = EncodeConfig { sort_maps: true, ..Default::default() };
+/// let a = encode_with_config(&map, &config).unwrap();
+/// let b = encode_with_config(&map.clone(), &config).unwrap();
+/// assert_eq!(a, b);
+/// ```
+#[cfg(feature = "std")]
+pub fn encode_with_config<T: Encoder>(value: &T, config: &EncodeConfig) -> Result<Bytes> {
+    with_encode_config(config, || encode(value))
+}
+
+/// Convenience function to encode a value to bytes with magic number, failing if the payload
+/// would be longer than `max_len` bytes (magic number included).
+///
+/// The running length is checked while encoding, at string, binary and collection element
+/// boundaries, so an oversized value fails with [`EncoderError::TooLarge`] without building the
+/// whole buffer. Useful for producers constrained by an MTU or a queue message limit.
+///
+/// # Example
+/// ```rust
+/// use senax_encoder::{encode_bounded, EncoderError};
+///
+/// let small = encode_bounded(&vec![1u8; 16], 64).unwrap();
+/// assert!(small.len() <= 64);
+///
+/// let result = encode_bounded(&vec![0u64; 10_000], 64);
+/// assert!(matches!(result, Err(EncoderError::TooLarge { limit: 64, .. })));
+/// ```
+#[cfg(feature = "std")]
+pub fn encode_bounded<T: Encoder>(value: &T, max_len: usize) -> Result<Bytes> {
+    let bytes = config::with_encode_limit(max_len, || encode(value))?;
+    if bytes.len() > max_len {
+        return Err(EncoderError::TooLarge {
+            actual: bytes.len(),
+            limit: max_len,
+        });
+    }
+    Ok(bytes)
+}
+
 /// Trait for types that can be encoded into the senax binary format.
 ///
 /// Implement this trait for your type to enable serialization.
@@ -316,6 +872,140 @@ pub trait Decoder: Sized {
     /// # Arguments
     /// * `reader` - The buffer to read the encoded bytes from.
     fn decode(reader: &mut Bytes) -> Result<Self>;
+
+    /// Decodes a value and returns it together with the number of bytes it occupied.
+    ///
+    /// Useful when values are embedded in custom containers and the caller needs to track
+    /// where one ends. The count is the drop in `reader.remaining()`.
+    ///
+    /// # Example
+    /// ```rust
+    /// use senax_encoder::{Decoder, Encoder};
+    /// use bytes::BytesMut;
+    ///
+    /// let mut writer = BytesMut::new();
+    /// "hello".to_string().encode(&mut writer).unwrap();
+    /// 7u32.encode(&mut writer).unwrap();
+    /// let mut reader = writer.freeze();
+    /// let (text, used) = String::decode_counted(&mut reader).unwrap();
+    /// assert_eq!((text.as_str(), used), ("hello", 6));
+    /// assert_eq!(u32::decode_counted(&mut reader).unwrap(), (7, 1));
+    /// ```
+    fn decode_counted(reader: &mut Bytes) -> Result<(Self, usize)> {
+        let before = reader.remaining();
+        let value = Self::decode(reader)?;
+        Ok((value, before - reader.remaining()))
+    }
+}
+
+/// Trait for types that can be decoded from the senax binary format while borrowing from the
+/// input.
+///
+/// `&'a str` and `&'a [u8]` are decoded as slices of the input instead of being copied into a
+/// `String` or `Vec<u8>`; `Cow<'a, str>` and `Cow<'a, [u8]>` borrow too, unless the decode
+/// configuration asks for a copy. `Option`, `Vec`, tuples, `HashMap` and `BTreeMap` decode
+/// borrowed elements, and primitives, `String` and `Bytes` decode as with [`Decoder`]. The wire
+/// format is the same as for the owned types, so `&str` reads what `String` wrote and `&[u8]`
+/// reads what `Bytes` wrote.
+///
+/// For your own types, implement the trait by decoding owned values with
+/// [`BorrowReader::decode`]. Use [`decode_borrowed`] as the entry point.
+///
+/// # Errors
+/// Returns `EncoderError` if the value cannot be decoded or the data is invalid.
+pub trait BorrowDecoder<'a>: Sized {
+    /// Decodes the value from `reader`, borrowing from its input where possible.
+    fn decode_borrowed(reader: &mut BorrowReader<'a>) -> Result<Self>;
+
+    /// Converts the untagged key of a string-keyed map (how [`StringKeyMap`] is encoded) to
+    /// `Self`.
+    ///
+    /// The default rejects the key; string types override it.
+    fn from_string_key(key: &'a str) -> Result<Self> {
+        let _ = key;
+        Err(EncoderError::Decode(format!(
+            "{} cannot be decoded from a string map key",
+            ::core::any::type_name::<Self>()
+        )))
+    }
+}
+
+/// The input of a [`BorrowDecoder`]: a position in a `Bytes` buffer that decoded values may
+/// borrow from for `'a`.
+#[derive(Debug, Clone)]
+pub struct BorrowReader<'a> {
+    input: &'a [u8],
+    rest: Bytes,
+}
+
+impl<'a> BorrowReader<'a> {
+    /// Creates a reader positioned at the start of `input`.
+    pub fn new(input: &'a Bytes) -> Self {
+        Self {
+            input,
+            rest: input.clone(),
+        }
+    }
+
+    /// Number of bytes left to read.
+    pub fn remaining(&self) -> usize {
+        self.rest.len()
+    }
+
+    /// The unread part of the input.
+    pub fn as_bytes(&self) -> &'a [u8] {
+        &self.input[self.input.len() - self.rest.len()..]
+    }
+
+    /// Reads the next `len` bytes as a slice of the input.
+    pub fn read_slice(&mut self, len: usize) -> Result<&'a [u8]> {
+        let slice = self
+            .as_bytes()
+            .get(..len)
+            .ok_or(EncoderError::eof(len, self.rest.len()))?;
+        self.rest.advance(len);
+        Ok(slice)
+    }
+
+    /// Reads one byte.
+    pub fn read_u8(&mut self) -> Result<u8> {
+        Ok(self.read_slice(1)?[0])
+    }
+
+    /// Decodes an owned value with its [`Decoder`] implementation.
+    ///
+    /// `Bytes` values share the input's allocation, as with [`decode`].
+    pub fn decode<T: Decoder>(&mut self) -> Result<T> {
+        T::decode(&mut self.rest)
+    }
+}
+
+/// Convenience function to decode a value that borrows from `input`, as [`decode`] does for
+/// owned values.
+///
+/// # Example
+/// ```rust
+/// use senax_encoder::{decode_borrowed, encode};
+/// use std::borrow::Cow;
+///
+/// let input = encode(&("Ana".to_string(), vec!["x".to_string(), "y".to_string()], 7u32)).unwrap();
+/// let (name, tags, id): (&str, Vec<Cow<str>>, u32) = decode_borrowed(&input).unwrap();
+/// assert_eq!((name, id), ("Ana", 7));
+/// assert!(matches!(tags[0], Cow::Borrowed("x")));
+/// // `name` points into `input`
+/// assert!(input.as_ptr_range().contains(&name.as_ptr()));
+/// ```
+pub fn decode_borrowed<'a, T: BorrowDecoder<'a>>(input: &'a Bytes) -> Result<T> {
+    let mut reader = BorrowReader::new(input);
+    let magic = reader.read_slice(2)?;
+    let magic = u16::from_le_bytes([magic[0], magic[1]]);
+    if magic != ENCODE_MAGIC {
+        return Err(EncoderError::Decode(format!(
+            "Invalid encode magic number: expected 0x{:04X}, got 0x{:04X}",
+            ENCODE_MAGIC, magic
+        )));
+    }
+    T::decode_borrowed(&mut reader)
 }
 
 /// Trait for types that can be unpacked from a compact binary format.
@@ -425,7 +1115,7 @@ pub fn pack_to<T: Packer>(value: &T, writer: &mut BytesMut) -> Result<()> {
 /// ```
 pub fn unpack<T: Unpacker>(reader: &mut Bytes) -> Result<T> {
     if reader.remaining() < 2 {
-        return Err(EncoderError::InsufficientData);
+        return Err(EncoderError::eof(2, reader.remaining()));
     }
     let magic = reader.get_u16_le();
     if magic != PACK_MAGIC {
@@ -436,3 +1126,61 @@ pub fn unpack<T: Unpacker>(reader: &mut Bytes) -> Result<T> {
     }
     T::unpack(reader)
 }
+
+/// Transcodes an encoded payload (magic 0xA55A) into the pack format (magic 0xDADA).
+///
+/// The value is decoded as `T` and packed again, so fields unknown to `T` are dropped.
+/// Useful for archiving data into the more compact pack format.
+///
+/// # Arguments
+/// * `reader` - The buffer to read the encoded bytes from.
+///
+/// # Example
+/// ```rust
+/// use senax_encoder::{encode, repack, unpack, Encode, Decode, Pack, Unpack};
+///
+/// #[derive(Encode, Decode, Pack, Unpack, PartialEq, Debug)]
+/// struct MyStruct {
+///     id: u32,
+///     name: String,
+/// }
+///
+/// let value = MyStruct { id: 42, name: "hello".to_string() };
+/// let mut encoded = encode(&value).unwrap();
+/// let mut packed = repack::<MyStruct>(&mut encoded).unwrap();
+/// let decoded: MyStruct = unpack(&mut packed).unwrap();
+/// assert_eq!(value, decoded);
+/// ```
+pub fn repack<T: Decoder + Packer>(reader: &mut Bytes) -> Result<Bytes> {
+    let value: T = decode(reader)?;
+    pack(&value)
+}
+
+/// Transcodes a packed payload (magic 0xDADA) into the encode format (magic 0xA55A).
+///
+/// This is the inverse of [`repack`]. The value is unpacked as `T`, so `T` must match the
+/// type the data was packed with.
+///
+/// # Arguments
+/// * `reader` - The buffer to read the packed bytes from.
+///
+/// # Example
+/// ```rust
+/// use senax_encoder::{decode, pack, unpack_to_encode, Encode, Decode, Pack, Unpack};
+///
+/// #[derive(Encode, Decode, Pack, Unpack, PartialEq, Debug)]
+/// struct MyStruct {
+///     id: u32,
+///     name: String,
+/// }
+///
+/// let value = MyStruct { id: 42, name: "hello".to_string() };
+/// let mut packed = pack(&value).unwrap();
+/// let mut encoded = unpack_to_encode::<MyStruct>(&mut packed).unwrap();
+/// let decoded: MyStruct = decode(&mut encoded).unwrap();
+/// assert_eq!(value, decoded);
+/// ```
+pub fn unpack_to_encode<T: Unpacker + Encoder>(reader: &mut Bytes) -> Result<Bytes> {
+    let value: T = unpack(reader)?;
+    encode(&value)
+}
@@ -0,0 +1,346 @@
+//! Compile-time upper bounds on the `Encoder` output size.
+//!
+//! [`MaxEncodedSize`] is implemented for fixed-size types (scalars, arrays, tuples, `Option`)
+//! and can be derived for structs and enums with `#[senax(max_size)]`. Variable-length fields
+//! (strings, vectors, sets, maps) need a `#[senax(max_len = N)]` bound, which uses
+//! [`MaxElementSize`] to size `N` elements. Derived `Encode` and `Decode` also reject named
+//! struct fields longer than their `max_len` through [`check_max_len`].
+//!
+//! Sizes are upper bounds for [`Encoder::encode`](crate::Encoder::encode) output and do not
+//! include the 2-byte magic number written by [`encode`](crate::encode).
+
+use crate::{EncoderError, Result};
+use alloc::boxed::Box;
+use alloc::collections::{BTreeMap, BTreeSet, VecDeque};
+use alloc::rc::Rc;
+use alloc::string::String;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use bytes::Bytes;
+#[cfg(feature = "std")]
+use std::collections::{HashMap, HashSet};
+
+/// Types whose encoded size has a compile-time upper bound.
+///
+/// # Example
+/// ```rust
+/// use senax_encoder::{Encode, Encoder, MaxEncodedSize};
+/// use bytes::BytesMut;
+///
+/// #[derive(Encode)]
+/// #[senax(max_size)]
+/// struct Reading {
+///     sensor: u16,
+///     value: f32,
+///     #[senax(max_len = 8)]
+///     unit: String,
+/// }
+///
+/// const BUF_LEN: usize = <Reading as MaxEncodedSize>::MAX_ENCODED_SIZE;
+/// let reading = Reading { sensor: 3, value: 21.5, unit: "celsius".into() };
+/// let mut buf = BytesMut::with_capacity(BUF_LEN);
+/// reading.encode(&mut buf).unwrap();
+/// assert!(buf.len() <= BUF_LEN);
+/// ```
+pub trait MaxEncodedSize {
+    /// Maximum number of bytes written by `Encoder::encode` for any value of this type.
+    const MAX_ENCODED_SIZE: usize;
+}
+
+/// Length-prefixed types whose encoded size is bounded once their length is limited.
+pub trait MaxElementSize {
+    /// Maximum encoded size of one element (one byte for strings and binary data).
+    const MAX_ELEMENT_SIZE: usize;
+}
+
+/// Types with a length that `#[senax(max_len = N)]` can bound.
+pub trait BoundedLen {
+    /// The number of elements (bytes for strings and binary data).
+    fn bounded_len(&self) -> usize;
+}
+
+/// Fails with [`EncoderError::CapacityExceeded`] if `value` holds more than `max_len` elements.
+///
+/// Called by derived `Encode` and `Decode` implementations for `#[senax(max_len = N)]` fields.
+pub fn check_max_len<T: BoundedLen + ?Sized>(value: &T, max_len: usize) -> Result<()> {
+    let len = value.bounded_len();
+    if len > max_len {
+        return Err(EncoderError::CapacityExceeded {
+            len,
+            capacity: max_len,
+        });
+    }
+    Ok(())
+}
+
+macro_rules! impl_bounded_len {
+    ($([$($g:tt)*] $t:ty),* $(,)?) => {
+        $(
+            impl<$($g)*> BoundedLen for $t {
+                fn bounded_len(&self) -> usize {
+                    self.len()
+                }
+            }
+        )*
+    };
+}
+
+impl_bounded_len! {
+    [] String,
+    [] str,
+    [] Bytes,
+    [T] [T],
+    [T] Vec<T>,
+    [T] VecDeque<T>,
+    [T] BTreeSet<T>,
+    [K, V] BTreeMap<K, V>,
+}
+
+#[cfg(feature = "std")]
+impl_bounded_len! {
+    [T, S] HashSet<T, S>,
+    [K, V, S] HashMap<K, V, S>,
+}
+
+#[cfg(feature = "indexmap")]
+impl_bounded_len! {
+    [T, S] indexmap::IndexSet<T, S>,
+    [K, V, S] indexmap::IndexMap<K, V, S>,
+}
+
+#[cfg(feature = "ahash")]
+impl_bounded_len! {
+    [T] ahash::AHashSet<T>,
+    [K, V] ahash::AHashMap<K, V>,
+}
+
+#[cfg(feature = "smol_str")]
+impl_bounded_len! {
+    [] smol_str::SmolStr,
+}
+
+#[cfg(feature = "heapless")]
+impl_bounded_len! {
+    [T, const N: usize] heapless::Vec<T, N>,
+    [const N: usize] heapless::String<N>,
+}
+
+#[cfg(feature = "smallvec")]
+impl_bounded_len! {
+    [T, const N: usize] smallvec::SmallVec<[T; N]>,
+}
+
+#[cfg(feature = "arrayvec")]
+impl_bounded_len! {
+    [T, const N: usize] arrayvec::ArrayVec<T, N>,
+    [const N: usize] arrayvec::ArrayString<N>,
+}
+
+impl<T: BoundedLen + ?Sized> BoundedLen for Box<T> {
+    fn bounded_len(&self) -> usize {
+        (**self).bounded_len()
+    }
+}
+
+impl<T: BoundedLen + ?Sized> BoundedLen for Arc<T> {
+    fn bounded_len(&self) -> usize {
+        (**self).bounded_len()
+    }
+}
+
+impl<T: BoundedLen + ?Sized> BoundedLen for Rc<T> {
+    fn bounded_len(&self) -> usize {
+        (**self).bounded_len()
+    }
+}
+
+/// Maximum encoded size of an unsigned integer equal to `value`.
+pub const fn uint_size(value: u128) -> usize {
+    if value <= 127 {
+        1
+    } else if value <= 255 + 128 {
+        2
+    } else if value <= u16::MAX as u128 {
+        3
+    } else if value <= u32::MAX as u128 {
+        5
+    } else if value <= u64::MAX as u128 {
+        9
+    } else {
+        17
+    }
+}
+
+/// Encoded size of a field or variant ID written by
+/// [`write_field_id_optimized`](crate::core::write_field_id_optimized).
+pub const fn field_id_size(id: u64) -> usize {
+    if id <= 250 {
+        1
+    } else {
+        9
+    }
+}
+
+/// Maximum encoded size of a length-prefixed value holding at most `max_len` elements.
+///
+/// The prefix is one tag byte followed, for long values, by the length as an integer.
+pub const fn max_len_size(max_len: usize, element_size: usize) -> usize {
+    1 + uint_size(max_len as u128) + max_len * element_size
+}
+
+/// Returns the largest of `sizes` (0 for an empty slice).
+pub const fn max_of(sizes: &[usize]) -> usize {
+    let mut max = 0;
+    let mut i = 0;
+    while i < sizes.len() {
+        if sizes[i] > max {
+            max = sizes[i];
+        }
+        i += 1;
+    }
+    max
+}
+
+macro_rules! impl_max_encoded_size {
+    ($($t:ty => $size:expr),* $(,)?) => {
+        $(
+            impl MaxEncodedSize for $t {
+                const MAX_ENCODED_SIZE: usize = $size;
+            }
+        )*
+    };
+}
+
+impl_max_encoded_size! {
+    bool => 1,
+    u8 => uint_size(u8::MAX as u128),
+    u16 => uint_size(u16::MAX as u128),
+    u32 => uint_size(u32::MAX as u128),
+    u64 => uint_size(u64::MAX as u128),
+    u128 => uint_size(u128::MAX),
+    usize => uint_size(usize::MAX as u128),
+    // TAG_NEGATIVE followed by the inverted magnitude
+    i8 => 1 + uint_size(u8::MAX as u128),
+    i16 => 1 + uint_size(u16::MAX as u128),
+    i32 => 1 + uint_size(u32::MAX as u128),
+    i64 => 1 + uint_size(u64::MAX as u128),
+    i128 => 1 + uint_size(u128::MAX),
+    isize => 1 + uint_size(usize::MAX as u128),
+    char => uint_size(char::MAX as u128),
+    // Scientific notation strings, e.g. "-1.17549435e-38"
+    f32 => 1 + 15,
+    // e.g. "-2.2250738585072014e-308"
+    f64 => 1 + 24,
+    // TAG_TUPLE followed by a zero length
+    () => 2,
+    // The tag, seconds and nanoseconds
+    ::core::time::Duration => 1 + uint_size(u64::MAX as u128) + uint_size(u32::MAX as u128),
+}
+
+#[cfg(feature = "std")]
+impl MaxEncodedSize for std::time::SystemTime {
+    const MAX_ENCODED_SIZE: usize = 1 + i64::MAX_ENCODED_SIZE + u32::MAX_ENCODED_SIZE;
+}
+
+#[cfg(feature = "uuid")]
+impl MaxEncodedSize for uuid::Uuid {
+    const MAX_ENCODED_SIZE: usize = 1 + 16;
+}
+
+#[cfg(feature = "ulid")]
+impl MaxEncodedSize for ulid::Ulid {
+    const MAX_ENCODED_SIZE: usize = 1 + 16;
+}
+
+#[cfg(feature = "heapless")]
+impl<T: MaxEncodedSize, const N: usize> MaxEncodedSize for heapless::Vec<T, N> {
+    const MAX_ENCODED_SIZE: usize = max_len_size(N, T::MAX_ENCODED_SIZE);
+}
+
+#[cfg(feature = "heapless")]
+impl<const N: usize> MaxEncodedSize for heapless::String<N> {
+    const MAX_ENCODED_SIZE: usize = max_len_size(N, 1);
+}
+
+#[cfg(feature = "arrayvec")]
+impl<T: MaxEncodedSize, const N: usize> MaxEncodedSize for arrayvec::ArrayVec<T, N> {
+    const MAX_ENCODED_SIZE: usize = max_len_size(N, T::MAX_ENCODED_SIZE);
+}
+
+#[cfg(feature = "arrayvec")]
+impl<const N: usize> MaxEncodedSize for arrayvec::ArrayString<N> {
+    const MAX_ENCODED_SIZE: usize = max_len_size(N, 1);
+}
+
+impl<T: MaxEncodedSize> MaxEncodedSize for Option<T> {
+    const MAX_ENCODED_SIZE: usize = 1 + T::MAX_ENCODED_SIZE;
+}
+
+impl<T: MaxEncodedSize, const N: usize> MaxEncodedSize for [T; N] {
+    const MAX_ENCODED_SIZE: usize = max_len_size(N, T::MAX_ENCODED_SIZE);
+}
+
+impl<T: MaxEncodedSize + ?Sized> MaxEncodedSize for Box<T> {
+    const MAX_ENCODED_SIZE: usize = T::MAX_ENCODED_SIZE;
+}
+
+impl<T: MaxEncodedSize + ?Sized> MaxEncodedSize for Arc<T> {
+    const MAX_ENCODED_SIZE: usize = T::MAX_ENCODED_SIZE;
+}
+
+impl<T: MaxEncodedSize + ?Sized> MaxEncodedSize for Rc<T> {
+    const MAX_ENCODED_SIZE: usize = T::MAX_ENCODED_SIZE;
+}
+
+macro_rules! impl_max_encoded_size_tuple {
+    ($len:expr; $($T:ident),+) => {
+        impl<$($T: MaxEncodedSize),+> MaxEncodedSize for ($($T,)+) {
+            // TAG_TUPLE, the element count, then the elements
+            const MAX_ENCODED_SIZE: usize = 1 + uint_size($len) $(+ $T::MAX_ENCODED_SIZE)+;
+        }
+    };
+}
+
+impl_max_encoded_size_tuple!(1; T0);
+impl_max_encoded_size_tuple!(2; T0, T1);
+impl_max_encoded_size_tuple!(3; T0, T1, T2);
+impl_max_encoded_size_tuple!(4; T0, T1, T2, T3);
+impl_max_encoded_size_tuple!(5; T0, T1, T2, T3, T4);
+impl_max_encoded_size_tuple!(6; T0, T1, T2, T3, T4, T5);
+impl_max_encoded_size_tuple!(7; T0, T1, T2, T3, T4, T5, T6);
+impl_max_encoded_size_tuple!(8; T0, T1, T2, T3, T4, T5, T6, T7);
+
+impl MaxElementSize for String {
+    const MAX_ELEMENT_SIZE: usize = 1;
+}
+
+impl MaxElementSize for Bytes {
+    const MAX_ELEMENT_SIZE: usize = 1;
+}
+
+impl<T: MaxEncodedSize> MaxElementSize for Vec<T> {
+    const MAX_ELEMENT_SIZE: usize = T::MAX_ENCODED_SIZE;
+}
+
+#[cfg(feature = "smallvec")]
+impl<T: MaxEncodedSize, const N: usize> MaxElementSize for smallvec::SmallVec<[T; N]> {
+    const MAX_ELEMENT_SIZE: usize = T::MAX_ENCODED_SIZE;
+}
+
+#[cfg(feature = "std")]
+impl<T: MaxEncodedSize> MaxElementSize for HashSet<T> {
+    const MAX_ELEMENT_SIZE: usize = T::MAX_ENCODED_SIZE;
+}
+
+impl<T: MaxEncodedSize> MaxElementSize for BTreeSet<T> {
+    const MAX_ELEMENT_SIZE: usize = T::MAX_ENCODED_SIZE;
+}
+
+#[cfg(feature = "std")]
+impl<K: MaxEncodedSize, V: MaxEncodedSize> MaxElementSize for HashMap<K, V> {
+    const MAX_ELEMENT_SIZE: usize = K::MAX_ENCODED_SIZE + V::MAX_ENCODED_SIZE;
+}
+
+impl<K: MaxEncodedSize, V: MaxEncodedSize> MaxElementSize for BTreeMap<K, V> {
+    const MAX_ELEMENT_SIZE: usize = K::MAX_ENCODED_SIZE + V::MAX_ENCODED_SIZE;
+}
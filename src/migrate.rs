@@ -0,0 +1,242 @@
+//! Storage migration driver for record logs.
+//!
+//! [`migrate_log`] reads a [record log](crate::record_log) of `Old` values, converts each one
+//! with `From<Old> for New` and appends the result to a new log. Migration is resumable: if the
+//! target log already exists, its complete records are kept (a torn trailing frame is truncated)
+//! and the same number of source records is skipped. Delete the target to start over.
+//!
+//! During a migration period, services that write both forms use [`encode_dual`], and tests
+//! check with [`verify_dual`] / [`assert_dual_consistent`] that the new form is what
+//! `From<Old> for New` would produce.
+
+use crate::record_log::{RecordLogReader, RecordLogWriter};
+use crate::{decode, encode, Decoder, Encoder, EncoderError, Result};
+use bytes::Bytes;
+use std::fmt::Debug;
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufReader, BufWriter};
+use std::path::Path;
+
+/// Progress of a migration, reported after every record.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MigrateProgress {
+    /// Records converted and written during this run.
+    pub migrated: u64,
+    /// Records already present in the target and skipped when resuming.
+    pub resumed: u64,
+    /// Bytes consumed from the source log(s).
+    pub bytes_read: u64,
+}
+
+/// Migrates the record log at `source` into `target`, converting `Old` values into `New`.
+///
+/// `on_progress` is called after each migrated record. Returns the final progress.
+///
+/// # Example
+/// ```rust
+/// use senax_encoder::migrate::migrate_log;
+/// use senax_encoder::record_log::RecordLogWriter;
+/// use senax_encoder::{Decode, Encode};
+/// use std::fs::File;
+///
+/// #[derive(Encode, Decode)]
+/// struct UserV1 { name: String }
+///
+/// #[derive(Encode, Decode)]
+/// struct UserV2 { name: String, active: bool }
+///
+/// impl From<UserV1> for UserV2 {
+///     fn from(old: UserV1) -> Self {
+///         UserV2 { name: old.name, active: true }
+///     }
+/// }
+///
+/// let dir = std::env::temp_dir().join(format!("senax-migrate-doc-{}", std::process::id()));
+/// std::fs::create_dir_all(&dir).unwrap();
+/// let source = dir.join("users.log");
+/// let target = dir.join("users.v2.log");
+/// let _ = std::fs::remove_file(&target);
+///
+/// let mut writer = RecordLogWriter::new(File::create(&source).unwrap());
+/// writer.append_value(&UserV1 { name: "alice".into() }).unwrap();
+/// writer.flush().unwrap();
+///
+/// let progress = migrate_log::<UserV1, UserV2>(&source, &target, |_| {}).unwrap();
+/// assert_eq!(progress.migrated, 1);
+/// # std::fs::remove_dir_all(&dir).unwrap();
+/// ```
+pub fn migrate_log<Old, New>(
+    source: &Path,
+    target: &Path,
+    mut on_progress: impl FnMut(&MigrateProgress),
+) -> Result<MigrateProgress>
+where
+    Old: Decoder,
+    New: From<Old> + Encoder,
+{
+    let existing = count_complete_records(target)?;
+
+    let mut reader = RecordLogReader::new(BufReader::new(File::open(source)?));
+    let mut progress = MigrateProgress::default();
+    while progress.resumed < existing {
+        if reader.read_record()?.is_none() {
+            return Err(EncoderError::Decode(format!(
+                "Cannot resume migration: target {} has {} records but source {} has only {}",
+                target.display(),
+                existing,
+                source.display(),
+                progress.resumed
+            )));
+        }
+        progress.resumed += 1;
+    }
+    progress.bytes_read = reader.offset();
+
+    let file = OpenOptions::new().create(true).append(true).open(target)?;
+    let mut writer = RecordLogWriter::new(BufWriter::new(file));
+    while let Some(old) = reader.read_value::<Old>()? {
+        writer.append_value(&New::from(old))?;
+        progress.migrated += 1;
+        progress.bytes_read = reader.offset();
+        on_progress(&progress);
+    }
+    writer.flush()?;
+    writer
+        .into_inner()
+        .into_inner()
+        .map_err(|e| e.into_error())?
+        .sync_all()?;
+    Ok(progress)
+}
+
+/// Migrates every record log file in `source_dir` into a file of the same name in `target_dir`.
+///
+/// Files are processed in name order and subdirectories are ignored. `on_progress` receives the
+/// source file being migrated together with that file's progress. Each file resumes
+/// independently, so an interrupted directory migration can simply be run again.
+/// Returns the totals over all files.
+pub fn migrate_dir<Old, New>(
+    source_dir: &Path,
+    target_dir: &Path,
+    mut on_progress: impl FnMut(&Path, &MigrateProgress),
+) -> Result<MigrateProgress>
+where
+    Old: Decoder,
+    New: From<Old> + Encoder,
+{
+    fs::create_dir_all(target_dir)?;
+    let mut sources = Vec::new();
+    for entry in fs::read_dir(source_dir)? {
+        let entry = entry?;
+        if entry.file_type()?.is_file() {
+            sources.push(entry.path());
+        }
+    }
+    sources.sort();
+
+    let mut total = MigrateProgress::default();
+    for source in sources {
+        let target = target_dir.join(source.file_name().unwrap_or_default());
+        let progress = migrate_log::<Old, New>(&source, &target, |p| on_progress(&source, p))?;
+        total.migrated += progress.migrated;
+        total.resumed += progress.resumed;
+        total.bytes_read += progress.bytes_read;
+    }
+    Ok(total)
+}
+
+/// Counts the complete records in an existing target log, truncating a torn trailing frame.
+fn count_complete_records(target: &Path) -> Result<u64> {
+    let file = match File::open(target) {
+        Ok(file) => file,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+        Err(e) => return Err(e.into()),
+    };
+    let mut reader = RecordLogReader::new(BufReader::new(file));
+    let mut count = 0;
+    loop {
+        match reader.read_record() {
+            Ok(Some(_)) => count += 1,
+            Ok(None) => break,
+            Err(EncoderError::UnexpectedEof { .. }) => {
+                OpenOptions::new()
+                    .write(true)
+                    .open(target)?
+                    .set_len(reader.offset())?;
+                break;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(count)
+}
+
+/// Encodes the old and new schema forms of one record, for services that write both during a
+/// migration.
+///
+/// Returns the `encode()` payloads of `old` and `new`, in that order.
+pub fn encode_dual<Old: Encoder, New: Encoder>(old: &Old, new: &New) -> Result<(Bytes, Bytes)> {
+    Ok((encode(old)?, encode(new)?))
+}
+
+/// Checks that a pair written by [`encode_dual`] is consistent: the new payload must decode to
+/// the same value as the old payload converted with `From<Old> for New`.
+///
+/// Fails with [`EncoderError::Decode`] if either payload does not decode or the values differ.
+pub fn verify_dual<Old, New>(old: &Bytes, new: &Bytes) -> Result<()>
+where
+    Old: Decoder,
+    New: From<Old> + Decoder + PartialEq + Debug,
+{
+    let converted = New::from(decode::<Old>(&mut old.clone())?);
+    let written = decode::<New>(&mut new.clone())?;
+    if converted != written {
+        return Err(EncoderError::Decode(format!(
+            "Dual-write mismatch: old form converts to {:?} but new form is {:?}",
+            converted, written
+        )));
+    }
+    Ok(())
+}
+
+/// Panics unless `old` and `new` are consistent forms of the same record (see [`verify_dual`]).
+///
+/// Both values go through [`encode_dual`] and are decoded again, so this also catches fields
+/// that do not round-trip.
+///
+/// # Example
+/// ```rust
+/// use senax_encoder::migrate::assert_dual_consistent;
+/// use senax_encoder::{Decode, Encode};
+///
+/// #[derive(Encode, Decode)]
+/// struct PriceV1 { cents: u64 }
+///
+/// #[derive(Encode, Decode, Debug, PartialEq)]
+/// struct PriceV2 { cents: u64, currency: String }
+///
+/// impl From<PriceV1> for PriceV2 {
+///     fn from(old: PriceV1) -> Self {
+///         PriceV2 { cents: old.cents, currency: "USD".into() }
+///     }
+/// }
+///
+/// assert_dual_consistent(&PriceV1 { cents: 250 }, &PriceV2 { cents: 250, currency: "USD".into() });
+/// ```
+#[track_caller]
+#[allow(clippy::panic)]
+pub fn assert_dual_consistent<Old, New>(old: &Old, new: &New)
+where
+    Old: Encoder + Decoder,
+    New: From<Old> + Encoder + Decoder + PartialEq + Debug,
+{
+    let result = encode_dual(old, new).and_then(|(old, new)| verify_dual::<Old, New>(&old, &new));
+    if let Err(e) = result {
+        panic!(
+            "{} -> {}: {}",
+            std::any::type_name::<Old>(),
+            std::any::type_name::<New>(),
+            e
+        );
+    }
+}
@@ -0,0 +1,491 @@
+//! Shrinking failing payloads to minimal reproducers.
+//!
+//! [`minimize_payload`] takes an `encode()` payload that triggers a bug (a decode error, a panic,
+//! a wrong value) and a predicate that recognizes the failure, and repeatedly tries smaller
+//! variants of the payload, keeping each one the predicate still accepts. The payload is split
+//! into values with the same walk as [`skip_value`], so every candidate stays well-formed:
+//!
+//! - struct and enum fields, list elements and map entries are removed, and length prefixes are
+//!   rewritten to match;
+//! - `Some(x)` is replaced by `None`;
+//! - strings and binaries are emptied or halved;
+//! - integers are replaced by `0`;
+//! - bytes after the top-level value are dropped.
+//!
+//! Values inside tuples, unnamed structs and variants are shrunk, but never removed, since
+//! their arity is part of the type.
+
+use crate::core::*;
+use crate::validate::MAX_VALIDATION_DEPTH;
+use crate::{Decoder, Encoder, EncoderError, Result, ENCODE_MAGIC};
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+
+/// Result of [`minimize_payload`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MinimizeReport {
+    /// The smallest payload found that still fails, including the magic number.
+    pub payload: Bytes,
+    /// Length of the payload passed in.
+    pub original_len: usize,
+    /// Number of candidates passed to the predicate.
+    pub attempts: usize,
+    /// Number of candidates kept.
+    pub reductions: usize,
+}
+
+/// Shrinks `payload` while `still_fails` returns `true` for it.
+///
+/// `still_fails` receives complete payloads (with magic number), so it can call
+/// [`decode`](crate::decode) directly. It should check for the specific failure being
+/// triaged (an error message, a panic) rather than any error, or the payload shrinks to one
+/// that merely fails differently. Wrap the call in `std::panic::catch_unwind` to minimize
+/// panics.
+///
+/// The result is 1-minimal for the reductions listed in the [module docs](self): applying any
+/// single one of them to it no longer fails.
+///
+/// # Errors
+/// Returns an error if the payload has no magic number, its top-level value is not
+/// well-formed (see [`validate_payload`](crate::validate_payload)), or `still_fails` returns
+/// `false` for the payload itself.
+///
+/// # Example
+/// ```rust
+/// use senax_encoder::minimize::minimize_payload;
+/// use senax_encoder::{decode, encode, Decode, Encode};
+///
+/// #[derive(Encode, Decode)]
+/// struct Order { id: u64, note: String, lines: Vec<u32> }
+///
+/// // A decoder that rejects orders with a zero line
+/// fn check(order: &Order) -> Result<(), String> {
+///     if order.lines.contains(&0) {
+///         return Err("zero quantity".into());
+///     }
+///     Ok(())
+/// }
+///
+/// let order = Order { id: 12345, note: "leave at door".into(), lines: vec![3, 0, 7, 9] };
+/// let report = minimize_payload(&encode(&order).unwrap(), |payload| {
+///     match decode::<Order>(&mut payload.clone()) {
+///         Ok(order) => check(&order).is_err(),
+///         Err(_) => false,
+///     }
+/// })
+/// .unwrap();
+///
+/// let minimal: Order = decode(&mut report.payload.clone()).unwrap();
+/// assert_eq!((minimal.id, minimal.note.as_str()), (0, ""));
+/// assert_eq!(minimal.lines, vec![0]);
+/// ```
+pub fn minimize_payload<F>(payload: &[u8], mut still_fails: F) -> Result<MinimizeReport>
+where
+    F: FnMut(&Bytes) -> bool,
+{
+    let mut reader = Bytes::copy_from_slice(payload);
+    if reader.remaining() < 2 {
+        return Err(EncoderError::eof(2, reader.remaining()));
+    }
+    let magic = reader.get_u16_le();
+    if magic != ENCODE_MAGIC {
+        return Err(EncoderError::Decode(format!(
+            "Invalid magic number: expected 0x{:04X}, got 0x{:04X}",
+            ENCODE_MAGIC, magic
+        )));
+    }
+    let mut current = Payload {
+        root: Node::parse(&mut reader, 1)?,
+        trailing: reader,
+    };
+    if !still_fails(&Bytes::copy_from_slice(payload)) {
+        return Err(EncoderError::Decode(
+            "The payload does not reproduce the failure".to_string(),
+        ));
+    }
+
+    let mut attempts = 0;
+    let mut reductions = 0;
+    // Candidates are numbered in a fixed order; after a reduction is kept, the same number
+    // names the next candidate of the smaller payload.
+    let mut next = 0;
+    let mut reduced_this_pass = false;
+    loop {
+        let Some(candidate) = current.reduce(next) else {
+            if !reduced_this_pass {
+                break;
+            }
+            next = 0;
+            reduced_this_pass = false;
+            continue;
+        };
+        attempts += 1;
+        if still_fails(&candidate.to_bytes()?) {
+            current = candidate;
+            reductions += 1;
+            reduced_this_pass = true;
+        } else {
+            next += 1;
+        }
+    }
+
+    Ok(MinimizeReport {
+        payload: current.to_bytes()?,
+        original_len: payload.len(),
+        attempts,
+        reductions,
+    })
+}
+
+/// A parsed payload.
+#[derive(Clone)]
+struct Payload {
+    root: Node,
+    trailing: Bytes,
+}
+
+impl Payload {
+    /// Returns a copy with the `index`-th reduction applied, if there are that many.
+    fn reduce(&self, mut index: usize) -> Option<Payload> {
+        if !self.trailing.is_empty() {
+            if index == 0 {
+                return Some(Payload {
+                    root: self.root.clone(),
+                    trailing: Bytes::new(),
+                });
+            }
+            index -= 1;
+        }
+        self.root.reduce(&mut index).map(|root| Payload {
+            root,
+            trailing: self.trailing.clone(),
+        })
+    }
+
+    fn to_bytes(&self) -> Result<Bytes> {
+        let mut writer = BytesMut::new();
+        writer.put_u16_le(ENCODE_MAGIC);
+        self.root.write(&mut writer)?;
+        writer.put_slice(&self.trailing);
+        Ok(writer.freeze())
+    }
+}
+
+/// One value of the payload.
+#[derive(Clone)]
+enum Node {
+    /// A value kept byte for byte, including its tag.
+    Scalar(Bytes),
+    /// A string or binary value.
+    Blob { binary: bool, data: Bytes },
+    /// A value made of entries.
+    Container { layout: Layout, entries: Vec<Entry> },
+}
+
+/// How a container writes its header and which of its entries may be removed.
+#[derive(Clone)]
+enum Layout {
+    /// A list or set with a length in its tag.
+    List,
+    /// A fixed header followed by a fixed number of entries (tuples, unnamed structs and
+    /// variants, `Some`).
+    Fixed(Bytes),
+    /// A named struct or variant: header, fields, then the terminator.
+    Fields(Bytes),
+    /// A tag and a `usize` count of entries (maps and JSON arrays/objects).
+    Counted(u8),
+    /// A map with flattened tuple keys of the given arity.
+    FlatKeys(u8),
+}
+
+/// One removable part of a container: a field, a list element, a map entry.
+#[derive(Clone)]
+struct Entry {
+    /// Raw bytes written before the values (a field ID or an untagged key).
+    prefix: Bytes,
+    values: Vec<Node>,
+}
+
+impl Entry {
+    fn parse(prefix: Bytes, reader: &mut Bytes, count: usize, depth: usize) -> Result<Entry> {
+        let values = (0..count)
+            .map(|_| Node::parse(reader, depth + 1))
+            .collect::<Result<_>>()?;
+        Ok(Entry { prefix, values })
+    }
+}
+
+/// Returns the bytes `read` consumed from `reader`.
+fn take(reader: &mut Bytes, read: impl FnOnce(&mut Bytes) -> Result<()>) -> Result<Bytes> {
+    let start = reader.clone();
+    read(reader)?;
+    Ok(start.slice(..start.len() - reader.len()))
+}
+
+impl Node {
+    fn parse(reader: &mut Bytes, depth: usize) -> Result<Node> {
+        if depth > MAX_VALIDATION_DEPTH {
+            return Err(EncoderError::Decode(format!(
+                "Nesting deeper than {} levels",
+                MAX_VALIDATION_DEPTH
+            )));
+        }
+        let Some(&tag) = reader.first() else {
+            return Err(EncoderError::eof(1, 0));
+        };
+        let node = match tag {
+            _ if (TAG_STRING_BASE..=TAG_STRING_LONG).contains(&tag) || tag == TAG_BINARY => {
+                reader.advance(1);
+                let len = if tag < TAG_STRING_LONG {
+                    (tag - TAG_STRING_BASE) as usize
+                } else {
+                    usize::decode(reader)?
+                };
+                if reader.remaining() < len {
+                    return Err(EncoderError::eof(len, reader.remaining()));
+                }
+                Node::Blob {
+                    binary: tag == TAG_BINARY,
+                    data: reader.split_to(len),
+                }
+            }
+            _ if (TAG_ARRAY_VEC_SET_BASE..=TAG_ARRAY_VEC_SET_LONG).contains(&tag) => {
+                let len = decode_vec_length(reader)?;
+                let entries = (0..len)
+                    .map(|_| Entry::parse(Bytes::new(), reader, 1, depth))
+                    .collect::<Result<_>>()?;
+                Node::Container {
+                    layout: Layout::List,
+                    entries,
+                }
+            }
+            TAG_SOME => {
+                let header = reader.split_to(1);
+                Node::Container {
+                    layout: Layout::Fixed(header),
+                    entries: vec![Entry::parse(Bytes::new(), reader, 1, depth)?],
+                }
+            }
+            TAG_STRUCT_UNNAMED | TAG_ENUM_UNNAMED | TAG_TUPLE => {
+                let mut len = 0;
+                let header = take(reader, |r| {
+                    r.advance(1);
+                    if tag == TAG_ENUM_UNNAMED {
+                        read_field_id_optimized(r)?;
+                    }
+                    len = usize::decode(r)?;
+                    Ok(())
+                })?;
+                let entries = (0..len)
+                    .map(|_| Entry::parse(Bytes::new(), reader, 1, depth))
+                    .collect::<Result<_>>()?;
+                Node::Container {
+                    layout: Layout::Fixed(header),
+                    entries,
+                }
+            }
+            TAG_STRUCT_NAMED | TAG_ENUM_NAMED => {
+                let header = take(reader, |r| {
+                    r.advance(1);
+                    if tag == TAG_ENUM_NAMED {
+                        read_field_id_optimized(r)?;
+                    }
+                    Ok(())
+                })?;
+                let mut entries = Vec::new();
+                loop {
+                    let mut field_id = 0;
+                    let prefix = take(reader, |r| {
+                        field_id = read_field_id_optimized(r)?;
+                        Ok(())
+                    })?;
+                    if field_id == 0 {
+                        break;
+                    }
+                    entries.push(Entry::parse(prefix, reader, 1, depth)?);
+                }
+                Node::Container {
+                    layout: Layout::Fields(header),
+                    entries,
+                }
+            }
+            TAG_MAP | TAG_JSON_ARRAY | TAG_MAP_STRING_KEYS | TAG_MAP_ID_KEYS | TAG_JSON_OBJECT => {
+                reader.advance(1);
+                let len = usize::decode(reader)?;
+                let mut entries = Vec::new();
+                for _ in 0..len {
+                    let entry = match tag {
+                        TAG_MAP => Entry::parse(Bytes::new(), reader, 2, depth)?,
+                        TAG_JSON_ARRAY => Entry::parse(Bytes::new(), reader, 1, depth)?,
+                        TAG_MAP_STRING_KEYS => {
+                            let key = take(reader, |r| {
+                                let key_len = usize::decode(r)?;
+                                if r.remaining() < key_len {
+                                    return Err(EncoderError::eof(key_len, r.remaining()));
+                                }
+                                r.advance(key_len);
+                                Ok(())
+                            })?;
+                            Entry::parse(key, reader, 1, depth)?
+                        }
+                        TAG_MAP_ID_KEYS => {
+                            let key = take(reader, |r| read_field_id_optimized(r).map(|_| ()))?;
+                            Entry::parse(key, reader, 1, depth)?
+                        }
+                        // JSON object keys are tagged strings, kept as they are
+                        _ => Entry::parse(Bytes::new(), reader, 2, depth)?,
+                    };
+                    entries.push(entry);
+                }
+                Node::Container {
+                    layout: Layout::Counted(tag),
+                    entries,
+                }
+            }
+            TAG_MAP_FLAT_KEYS => {
+                reader.advance(1);
+                if reader.remaining() == 0 {
+                    return Err(EncoderError::eof(1, 0));
+                }
+                let arity = reader.get_u8();
+                let len = usize::decode(reader)?;
+                let entries = (0..len)
+                    .map(|_| Entry::parse(Bytes::new(), reader, arity as usize + 1, depth))
+                    .collect::<Result<_>>()?;
+                Node::Container {
+                    layout: Layout::FlatKeys(arity),
+                    entries,
+                }
+            }
+            _ => Node::Scalar(take(reader, skip_value)?),
+        };
+        Ok(node)
+    }
+
+    fn write(&self, writer: &mut BytesMut) -> Result<()> {
+        match self {
+            Node::Scalar(bytes) => writer.put_slice(bytes),
+            Node::Blob { binary: true, data } => {
+                writer.put_u8(TAG_BINARY);
+                data.len().encode(writer)?;
+                writer.put_slice(data);
+            }
+            Node::Blob {
+                binary: false,
+                data,
+            } => {
+                if data.len() <= MAX_SHORT_STRING_LEN {
+                    writer.put_u8(TAG_STRING_BASE + data.len() as u8);
+                } else {
+                    writer.put_u8(TAG_STRING_LONG);
+                    data.len().encode(writer)?;
+                }
+                writer.put_slice(data);
+            }
+            Node::Container { layout, entries } => {
+                match layout {
+                    Layout::List => encode_vec_length(entries.len(), writer)?,
+                    Layout::Fixed(header) | Layout::Fields(header) => writer.put_slice(header),
+                    Layout::Counted(tag) => {
+                        writer.put_u8(*tag);
+                        entries.len().encode(writer)?;
+                    }
+                    Layout::FlatKeys(arity) => {
+                        writer.put_u8(TAG_MAP_FLAT_KEYS);
+                        writer.put_u8(*arity);
+                        entries.len().encode(writer)?;
+                    }
+                }
+                for entry in entries {
+                    writer.put_slice(&entry.prefix);
+                    for value in &entry.values {
+                        value.write(writer)?;
+                    }
+                }
+                if let Layout::Fields(_) = layout {
+                    write_field_id_optimized(writer, 0)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns a copy with the `index`-th reduction of this subtree applied, or subtracts the
+    /// number of reductions in the subtree from `index`.
+    ///
+    /// A node's own reductions come before those of its children, largest first.
+    fn reduce(&self, index: &mut usize) -> Option<Node> {
+        match self {
+            Node::Scalar(bytes) => {
+                let is_integer =
+                    matches!(bytes[0], TAG_ONE..=TAG_U8_127 | TAG_U8..=TAG_U128 | TAG_NEGATIVE);
+                if is_integer {
+                    if *index == 0 {
+                        return Some(Node::Scalar(Bytes::from_static(&[TAG_ZERO])));
+                    }
+                    *index -= 1;
+                }
+                None
+            }
+            Node::Blob { binary, data } => {
+                let lens = [0, data.len() / 2];
+                let shorter = if data.len() > 1 {
+                    &lens[..]
+                } else if data.len() == 1 {
+                    &lens[..1]
+                } else {
+                    &[]
+                };
+                if let Some(&len) = shorter.get(*index) {
+                    return Some(Node::Blob {
+                        binary: *binary,
+                        data: data.slice(..len),
+                    });
+                }
+                *index -= shorter.len();
+                None
+            }
+            Node::Container { layout, entries } => {
+                match layout {
+                    Layout::Fixed(header) => {
+                        if header[..] == [TAG_SOME] {
+                            if *index == 0 {
+                                return Some(Node::Scalar(Bytes::from_static(&[TAG_NONE])));
+                            }
+                            *index -= 1;
+                        }
+                    }
+                    _ => {
+                        // Removing every entry, then each entry on its own
+                        let clear = usize::from(entries.len() > 1);
+                        if *index < clear + entries.len() {
+                            let mut entries = entries.clone();
+                            if *index < clear {
+                                entries.clear();
+                            } else {
+                                entries.remove(*index - clear);
+                            }
+                            return Some(Node::Container {
+                                layout: layout.clone(),
+                                entries,
+                            });
+                        }
+                        *index -= clear + entries.len();
+                    }
+                }
+                for (i, entry) in entries.iter().enumerate() {
+                    for (j, value) in entry.values.iter().enumerate() {
+                        if let Some(reduced) = value.reduce(index) {
+                            let mut entries = entries.clone();
+                            entries[i].values[j] = reduced;
+                            return Some(Node::Container {
+                                layout: layout.clone(),
+                                entries,
+                            });
+                        }
+                    }
+                }
+                None
+            }
+        }
+    }
+}
@@ -0,0 +1,208 @@
+//! Splitting encoded values into size-limited frames.
+//!
+//! [`encode_multipart`] encodes a value with [`encode`] and cuts the payload into frames no
+//! longer than a given frame size, for transports with a hard message size limit. Each frame is
+//!
+//! ```text
+//! [index: u32 little-endian] [count: u32 little-endian] [chunk]
+//! ```
+//!
+//! where `index` is the position of the frame and `count` the number of frames in the message.
+//! [`MultipartAssembler`] collects the frames of one message, in any order, and returns the
+//! payload once all of them have arrived. Messages longer than
+//! [`MultipartAssembler::with_max_total_len`] (16 MiB by default) are rejected as frames arrive,
+//! so a peer cannot make the assembler allocate for a frame count it announces.
+
+use crate::{decode, encode, Decoder, Encoder, EncoderError, Result};
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+use std::collections::BTreeMap;
+
+/// Length of the header at the start of every frame.
+pub const MULTIPART_HEADER_LEN: usize = 8;
+
+/// Default limit on the payload length accepted by [`MultipartAssembler`].
+pub const DEFAULT_MAX_TOTAL_LEN: usize = 16 * 1024 * 1024;
+
+/// Encodes `value` and splits the payload into frames of at most `frame_size` bytes.
+///
+/// A value that fits in one frame still produces a single frame with a header.
+///
+/// # Example
+/// ```rust
+/// use senax_encoder::multipart::{encode_multipart, MultipartAssembler};
+///
+/// let blob = vec![7u8; 1000];
+/// let frames = encode_multipart(&blob, 128).unwrap();
+/// assert!(frames.iter().all(|frame| frame.len() <= 128));
+///
+/// let mut assembler = MultipartAssembler::new();
+/// let mut decoded = None;
+/// for frame in frames {
+///     decoded = assembler.push_value::<Vec<u8>>(frame).unwrap();
+/// }
+/// assert_eq!(decoded, Some(blob));
+/// ```
+pub fn encode_multipart<T: Encoder>(value: &T, frame_size: usize) -> Result<Vec<Bytes>> {
+    if frame_size <= MULTIPART_HEADER_LEN {
+        return Err(EncoderError::Encode(format!(
+            "Frame size {} must be larger than the {}-byte multipart header",
+            frame_size, MULTIPART_HEADER_LEN
+        )));
+    }
+    let payload = encode(value)?;
+    let chunk_size = frame_size - MULTIPART_HEADER_LEN;
+    let count = payload.len().div_ceil(chunk_size).max(1);
+    let count = u32::try_from(count).map_err(|_| {
+        EncoderError::Encode(format!(
+            "Payload of {} bytes needs more than {} frames",
+            payload.len(),
+            u32::MAX
+        ))
+    })?;
+
+    let mut frames = Vec::with_capacity(count as usize);
+    for index in 0..count {
+        let start = index as usize * chunk_size;
+        let end = (start + chunk_size).min(payload.len());
+        let mut frame = BytesMut::with_capacity(MULTIPART_HEADER_LEN + end - start);
+        frame.put_u32_le(index);
+        frame.put_u32_le(count);
+        frame.put_slice(&payload[start..end]);
+        frames.push(frame.freeze());
+    }
+    Ok(frames)
+}
+
+/// Reassembles the frames of one message produced by [`encode_multipart`].
+///
+/// Frames may be pushed in any order. After the message is complete the assembler is reset and
+/// can be reused for the next message.
+#[derive(Debug)]
+pub struct MultipartAssembler {
+    /// Received chunks by frame index
+    parts: BTreeMap<usize, Bytes>,
+    /// Frame count of the current message, 0 before the first frame
+    count: usize,
+    /// Bytes received for the current message
+    total_len: usize,
+    max_total_len: usize,
+}
+
+impl Default for MultipartAssembler {
+    fn default() -> Self {
+        Self {
+            parts: BTreeMap::new(),
+            count: 0,
+            total_len: 0,
+            max_total_len: DEFAULT_MAX_TOTAL_LEN,
+        }
+    }
+}
+
+impl MultipartAssembler {
+    /// Creates an empty assembler accepting messages up to [`DEFAULT_MAX_TOTAL_LEN`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the longest payload accepted; frames beyond it fail to push.
+    pub fn with_max_total_len(mut self, max_total_len: usize) -> Self {
+        self.max_total_len = max_total_len;
+        self
+    }
+
+    /// The longest payload accepted.
+    pub fn max_total_len(&self) -> usize {
+        self.max_total_len
+    }
+
+    /// Returns the number of frames received for the current message.
+    pub fn received(&self) -> usize {
+        self.parts.len()
+    }
+
+    /// Returns the number of frames in the current message, or `None` before the first frame.
+    pub fn expected(&self) -> Option<usize> {
+        if self.count == 0 {
+            None
+        } else {
+            Some(self.count)
+        }
+    }
+
+    /// Adds a frame and returns the full payload (with magic number) once every frame has been
+    /// received.
+    ///
+    /// Fails on a malformed header, a frame count that disagrees with earlier frames, a frame
+    /// index that was already received, or a message longer than
+    /// [`max_total_len`](Self::max_total_len). Every frame but the last carries at least one
+    /// byte, so a frame count above the limit is rejected with the first frame.
+    pub fn push(&mut self, mut frame: Bytes) -> Result<Option<Bytes>> {
+        if frame.remaining() < MULTIPART_HEADER_LEN {
+            return Err(EncoderError::eof(MULTIPART_HEADER_LEN, frame.remaining()));
+        }
+        let index = frame.get_u32_le() as usize;
+        let count = frame.get_u32_le() as usize;
+        if count == 0 || index >= count {
+            return Err(EncoderError::Decode(format!(
+                "Invalid multipart frame {} of {}",
+                index, count
+            )));
+        }
+        if self.count == 0 {
+            if count > self.max_total_len {
+                return Err(EncoderError::Decode(format!(
+                    "Multipart message of {} frames exceeds the limit of {} bytes",
+                    count, self.max_total_len
+                )));
+            }
+            self.count = count;
+        } else if self.count != count {
+            return Err(EncoderError::Decode(format!(
+                "Multipart frame count mismatch: expected {}, got {}",
+                self.count, count
+            )));
+        }
+        if self.parts.contains_key(&index) {
+            return Err(EncoderError::Decode(format!(
+                "Duplicate multipart frame {}",
+                index
+            )));
+        }
+        let total_len = self.total_len + frame.len();
+        if total_len > self.max_total_len {
+            return Err(EncoderError::Decode(format!(
+                "Multipart message exceeds the limit of {} bytes",
+                self.max_total_len
+            )));
+        }
+        self.total_len = total_len;
+        self.parts.insert(index, frame);
+        if self.parts.len() < count {
+            return Ok(None);
+        }
+
+        let parts = std::mem::take(&mut self.parts);
+        let mut payload = BytesMut::with_capacity(self.total_len);
+        self.reset();
+        for part in parts.into_values() {
+            payload.put_slice(&part);
+        }
+        Ok(Some(payload.freeze()))
+    }
+
+    /// Adds a frame and decodes the value once every frame has been received.
+    pub fn push_value<T: Decoder>(&mut self, frame: Bytes) -> Result<Option<T>> {
+        match self.push(frame)? {
+            Some(mut payload) => decode(&mut payload).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Discards the frames received so far.
+    pub fn reset(&mut self) {
+        self.parts.clear();
+        self.count = 0;
+        self.total_len = 0;
+    }
+}
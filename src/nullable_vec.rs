@@ -0,0 +1,227 @@
+//! A list of optional values stored as a nullable column.
+//!
+//! A `Vec<Option<T>>` writes one tag per element (`TAG_NONE`, or `TAG_SOME` before the value),
+//! so a sparse column of mostly missing values costs a byte per gap. [`NullableVec`] holds
+//! the same data but encodes it column-wise, as a three-element tuple:
+//!
+//! 1. the number of elements;
+//! 2. a presence bitmap as binary, one bit per element (least significant bit first), set
+//!    where the element is `Some`;
+//! 3. a list of the present values only, without option tags.
+//!
+//! Missing values then cost one bit each. The layout only uses existing tags, so payloads
+//! containing a `NullableVec` can still be read as [`Value`](crate::Value)s, validated and
+//! queried without knowing its type.
+//!
+//! Decoding also accepts a plain list of options, so a `NullableVec` field can replace a
+//! `Vec<Option<T>>` field and still read payloads written before the change.
+
+use crate::core::{
+    collection_capacity, decode_vec_length, encode_vec_length, read_tuple_header,
+    write_tuple_header, TAG_ARRAY_VEC_SET_BASE, TAG_ARRAY_VEC_SET_LONG,
+};
+use crate::{Decoder, Encoder, EncoderError, Packer, Result, Unpacker};
+use bytes::{Bytes, BytesMut};
+use std::ops::{Deref, DerefMut};
+
+/// A `Vec<Option<T>>` encoded as a presence bitmap followed by the present values.
+///
+/// `NullableVec` dereferences to the inner `Vec`, so the usual vector methods are available.
+///
+/// # Example
+/// ```rust
+/// use senax_encoder::{decode, encode, NullableVec};
+///
+/// let mut readings = vec![None; 100];
+/// readings[10] = Some(21.5f64);
+/// readings[70] = Some(22.0);
+///
+/// let column = NullableVec::from(readings.clone());
+/// let bytes = encode(&column).unwrap();
+/// assert!(bytes.len() < encode(&readings).unwrap().len() / 2);
+///
+/// let decoded: NullableVec<f64> = decode(&mut bytes.clone()).unwrap();
+/// assert_eq!(decoded.into_inner(), readings);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct NullableVec<T>(pub Vec<Option<T>>);
+
+impl<T> NullableVec<T> {
+    /// Creates an empty list.
+    pub fn new() -> Self {
+        Self(Vec::new())
+    }
+
+    /// Returns the inner `Vec`.
+    pub fn into_inner(self) -> Vec<Option<T>> {
+        self.0
+    }
+
+    /// Returns the number of `Some` elements.
+    pub fn count_present(&self) -> usize {
+        self.0.iter().filter(|v| v.is_some()).count()
+    }
+}
+
+impl<T> Default for NullableVec<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Deref for NullableVec<T> {
+    type Target = Vec<Option<T>>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for NullableVec<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<T> From<Vec<Option<T>>> for NullableVec<T> {
+    fn from(vec: Vec<Option<T>>) -> Self {
+        Self(vec)
+    }
+}
+
+impl<T> From<NullableVec<T>> for Vec<Option<T>> {
+    fn from(vec: NullableVec<T>) -> Self {
+        vec.0
+    }
+}
+
+impl<T> FromIterator<Option<T>> for NullableVec<T> {
+    fn from_iter<I: IntoIterator<Item = Option<T>>>(iter: I) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+
+impl<T> Extend<Option<T>> for NullableVec<T> {
+    fn extend<I: IntoIterator<Item = Option<T>>>(&mut self, iter: I) {
+        self.0.extend(iter)
+    }
+}
+
+impl<T> IntoIterator for NullableVec<T> {
+    type Item = Option<T>;
+    type IntoIter = std::vec::IntoIter<Option<T>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a NullableVec<T> {
+    type Item = &'a Option<T>;
+    type IntoIter = std::slice::Iter<'a, Option<T>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
+
+/// Writes the element count, the presence bitmap and the present values.
+fn write_column<T>(
+    vec: &NullableVec<T>,
+    writer: &mut BytesMut,
+    write_value: impl Fn(&T, &mut BytesMut) -> Result<()>,
+) -> Result<()> {
+    let mut bitmap = vec![0u8; vec.len().div_ceil(8)];
+    for (i, value) in vec.iter().enumerate() {
+        if value.is_some() {
+            bitmap[i / 8] |= 1 << (i % 8);
+        }
+    }
+    write_tuple_header(writer, 3)?;
+    vec.len().encode(writer)?;
+    Bytes::from(bitmap).encode(writer)?;
+    encode_vec_length(vec.count_present(), writer)?;
+    for value in vec.iter().flatten() {
+        write_value(value, writer)?;
+        crate::config::check_encode_limit(writer.len())?;
+    }
+    Ok(())
+}
+
+/// Reads a column written by [`write_column`].
+fn read_column<T>(
+    reader: &mut Bytes,
+    read_value: impl Fn(&mut Bytes) -> Result<T>,
+) -> Result<NullableVec<T>> {
+    read_tuple_header(reader, 3)?;
+    let len = usize::decode(reader)?;
+    let bitmap = Bytes::decode(reader)?;
+    if bitmap.len() != len.div_ceil(8) {
+        return Err(EncoderError::Decode(format!(
+            "NullableVec bitmap has {} bytes for {} elements",
+            bitmap.len(),
+            len
+        )));
+    }
+    let present = bitmap
+        .iter()
+        .map(|b| b.count_ones() as usize)
+        .sum::<usize>();
+    let count = decode_vec_length(reader)?;
+    if count != present {
+        return Err(EncoderError::Decode(format!(
+            "NullableVec bitmap marks {} values present, but {} were written",
+            present, count
+        )));
+    }
+    let mut vec = Vec::with_capacity(collection_capacity::<Option<T>>(len, reader)?);
+    for i in 0..len {
+        if bitmap[i / 8] & (1 << (i % 8)) != 0 {
+            vec.push(Some(read_value(reader)?));
+        } else {
+            vec.push(None);
+        }
+    }
+    Ok(NullableVec(vec))
+}
+
+/// Returns `true` if the next value is a plain list, i.e. a `Vec<Option<T>>`.
+fn is_list(reader: &Bytes) -> bool {
+    reader
+        .first()
+        .is_some_and(|tag| (TAG_ARRAY_VEC_SET_BASE..=TAG_ARRAY_VEC_SET_LONG).contains(tag))
+}
+
+impl<T: Encoder> Encoder for NullableVec<T> {
+    fn encode(&self, writer: &mut BytesMut) -> Result<()> {
+        write_column(self, writer, T::encode)
+    }
+
+    fn is_default(&self) -> bool {
+        self.is_empty()
+    }
+}
+
+impl<T: Decoder + 'static> Decoder for NullableVec<T> {
+    fn decode(reader: &mut Bytes) -> Result<Self> {
+        if is_list(reader) {
+            return Vec::<Option<T>>::decode(reader).map(Self);
+        }
+        read_column(reader, T::decode)
+    }
+}
+
+impl<T: Packer> Packer for NullableVec<T> {
+    fn pack(&self, writer: &mut BytesMut) -> Result<()> {
+        write_column(self, writer, T::pack)
+    }
+}
+
+impl<T: Unpacker + 'static> Unpacker for NullableVec<T> {
+    fn unpack(reader: &mut Bytes) -> Result<Self> {
+        if is_list(reader) {
+            return Vec::<Option<T>>::unpack(reader).map(Self);
+        }
+        read_column(reader, T::unpack)
+    }
+}
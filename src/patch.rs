@@ -0,0 +1,217 @@
+//! Field-level diffs between encoded values.
+//!
+//! [`diff`] compares the `encode()` output of two values and records which struct fields
+//! changed, so a state update can be shipped as the changed fields instead of the whole value.
+//! [`Patch::apply`] rebuilds the new value from the old one.
+//!
+//! Fields are compared by their encoded bytes and matched by field ID, so a patch made with
+//! one version of a struct applies to payloads of another. Values that are not named structs
+//! (tuples, enums, collections, scalars) are replaced whole. Nested structs are not diffed
+//! recursively: a changed nested struct is sent as one field. `HashMap`/`HashSet` fields encode
+//! in iteration order and may show up as changed even when equal; the result of applying the
+//! patch is still correct.
+//!
+//! # Example
+//! ```rust
+//! use senax_encoder::patch::diff;
+//! use senax_encoder::{Decode, Encode};
+//!
+//! #[derive(Encode, Decode, Debug, PartialEq, Clone)]
+//! struct Player { name: String, score: u64, level: u32 }
+//!
+//! let old = Player { name: "ann".into(), score: 10, level: 1 };
+//! let new = Player { score: 25, ..old.clone() };
+//!
+//! let patch = diff(&old, &new).unwrap();
+//! assert_eq!(patch.len(), 1);
+//! assert_eq!(patch.apply(&old).unwrap(), new);
+//! ```
+
+use crate::core::{
+    read_field_id_optimized, skip_value, write_field_id_optimized, TAG_STRUCT_NAMED,
+};
+use crate::{decode, encode, Decoder, Encoder, EncoderError, Result, ENCODE_MAGIC};
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+
+/// The difference between two encoded values.
+///
+/// Encodes as an ordinary value, so patches can be stored and sent like any other message.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Patch {
+    /// Encoded value (without magic) replacing the old one, for values that are not named structs.
+    replace: Option<Bytes>,
+    /// Fields to add or overwrite, as field ID and encoded value.
+    set: Vec<(u64, Bytes)>,
+    /// IDs of fields to remove, so they decode as their default.
+    removed: Vec<u64>,
+}
+
+impl Patch {
+    /// Returns `true` if applying the patch changes nothing.
+    pub fn is_empty(&self) -> bool {
+        self.replace.is_none() && self.set.is_empty() && self.removed.is_empty()
+    }
+
+    /// Returns the number of changed fields, counting a whole-value replacement as one.
+    pub fn len(&self) -> usize {
+        self.replace.is_some() as usize + self.set.len() + self.removed.len()
+    }
+
+    /// Returns `true` if the patch replaces the whole value rather than individual fields.
+    pub fn is_replacement(&self) -> bool {
+        self.replace.is_some()
+    }
+
+    /// Returns the IDs of the fields the patch sets or removes.
+    pub fn field_ids(&self) -> impl Iterator<Item = u64> + '_ {
+        self.set
+            .iter()
+            .map(|(id, _)| *id)
+            .chain(self.removed.iter().copied())
+    }
+
+    /// Applies the patch to `base` and decodes the result.
+    pub fn apply<T: Encoder + Decoder>(&self, base: &T) -> Result<T> {
+        let payload = self.apply_payload(&encode(base)?)?;
+        decode(&mut payload.clone())
+    }
+
+    /// Applies the patch to an `encode()` payload (with magic number), returning the new payload.
+    ///
+    /// Fields of `base` keep their order; fields the patch adds are appended.
+    pub fn apply_payload(&self, base: &Bytes) -> Result<Bytes> {
+        let body = strip_magic(base)?;
+        let mut writer = BytesMut::new();
+        writer.put_u16_le(ENCODE_MAGIC);
+        if let Some(replace) = &self.replace {
+            writer.put_slice(replace);
+            return Ok(writer.freeze());
+        }
+        if self.set.is_empty() && self.removed.is_empty() {
+            return Ok(base.clone());
+        }
+        let fields = split_fields(&body)?.ok_or_else(|| {
+            EncoderError::Decode("Field patch applied to a value that is not a named struct".into())
+        })?;
+        writer.put_u8(TAG_STRUCT_NAMED);
+        for (id, value) in &fields {
+            if self.removed.contains(id) {
+                continue;
+            }
+            let value = self
+                .set
+                .iter()
+                .find(|(set_id, _)| set_id == id)
+                .map_or(value, |(_, set)| set);
+            write_field_id_optimized(&mut writer, *id)?;
+            writer.put_slice(value);
+        }
+        for (id, value) in &self.set {
+            if !fields.iter().any(|(field_id, _)| field_id == id) {
+                write_field_id_optimized(&mut writer, *id)?;
+                writer.put_slice(value);
+            }
+        }
+        write_field_id_optimized(&mut writer, 0)?;
+        Ok(writer.freeze())
+    }
+}
+
+impl Encoder for Patch {
+    fn encode(&self, writer: &mut BytesMut) -> Result<()> {
+        (&self.replace, &self.set, &self.removed).encode(writer)
+    }
+
+    fn is_default(&self) -> bool {
+        self.is_empty()
+    }
+}
+
+impl Decoder for Patch {
+    fn decode(reader: &mut Bytes) -> Result<Self> {
+        let (replace, set, removed) = Decoder::decode(reader)?;
+        Ok(Self {
+            replace,
+            set,
+            removed,
+        })
+    }
+}
+
+/// Computes the patch that turns `old` into `new`.
+pub fn diff<T: Encoder>(old: &T, new: &T) -> Result<Patch> {
+    diff_payloads(&encode(old)?, &encode(new)?)
+}
+
+/// Computes the patch that turns one `encode()` payload (with magic number) into another.
+///
+/// The payloads may come from different types, e.g. two versions of a struct.
+pub fn diff_payloads(old: &Bytes, new: &Bytes) -> Result<Patch> {
+    let old = strip_magic(old)?;
+    let new = strip_magic(new)?;
+    if old == new {
+        return Ok(Patch::default());
+    }
+    let (old_fields, new_fields) = match (split_fields(&old)?, split_fields(&new)?) {
+        (Some(old_fields), Some(new_fields)) => (old_fields, new_fields),
+        _ => {
+            return Ok(Patch {
+                replace: Some(new),
+                ..Patch::default()
+            })
+        }
+    };
+    let set = new_fields
+        .iter()
+        .filter(|(id, value)| !old_fields.iter().any(|old| old.0 == *id && old.1 == value))
+        .cloned()
+        .collect();
+    let removed = old_fields
+        .iter()
+        .filter(|(id, _)| !new_fields.iter().any(|(new_id, _)| new_id == id))
+        .map(|(id, _)| *id)
+        .collect();
+    Ok(Patch {
+        replace: None,
+        set,
+        removed,
+    })
+}
+
+fn strip_magic(payload: &Bytes) -> Result<Bytes> {
+    let mut reader = payload.clone();
+    if reader.remaining() < 2 {
+        return Err(EncoderError::eof(2, reader.remaining()));
+    }
+    let magic = reader.get_u16_le();
+    if magic != ENCODE_MAGIC {
+        return Err(EncoderError::Decode(format!(
+            "Invalid encode magic number: expected 0x{:04X}, got 0x{:04X}",
+            ENCODE_MAGIC, magic
+        )));
+    }
+    Ok(reader)
+}
+
+/// Splits a named struct into its field IDs and encoded values, or returns `None` for any
+/// other value.
+fn split_fields(body: &Bytes) -> Result<Option<Vec<(u64, Bytes)>>> {
+    if body.first() != Some(&TAG_STRUCT_NAMED) {
+        return Ok(None);
+    }
+    let mut reader = body.slice(1..);
+    let mut fields = Vec::new();
+    loop {
+        let id = read_field_id_optimized(&mut reader)?;
+        if id == 0 {
+            break;
+        }
+        let start = reader.clone();
+        skip_value(&mut reader)?;
+        fields.push((id, start.slice(..start.len() - reader.len())));
+    }
+    if reader.has_remaining() {
+        return Ok(None);
+    }
+    Ok(Some(fields))
+}
@@ -0,0 +1,167 @@
+//! Peeking at the header of a payload without decoding it.
+//!
+//! [`peek_info`] reads the magic number and the first tag of a payload and returns what they
+//! tell about the value: its wire type, whether it is a struct or an enum variant (and which),
+//! the element count the header announces and how many bytes the value takes. It reads a few
+//! bytes (plus the name table of self-describing payloads), does not copy the payload and
+//! leaves the caller's buffer untouched, so routers can pick a queue or reject oversized
+//! messages before paying for a full decode.
+
+use crate::core::{
+    read_field_id_optimized, skip_value, TAG_ARRAY_VEC_SET_BASE, TAG_ARRAY_VEC_SET_LONG,
+    TAG_BINARY, TAG_ENUM, TAG_ENUM_NAMED, TAG_ENUM_UNNAMED, TAG_JSON_ARRAY, TAG_JSON_OBJECT,
+    TAG_MAP, TAG_MAP_FLAT_KEYS, TAG_MAP_ID_KEYS, TAG_MAP_STRING_KEYS, TAG_STRING_BASE,
+    TAG_STRING_LONG, TAG_STRUCT_NAMED, TAG_STRUCT_UNIT, TAG_STRUCT_UNNAMED, TAG_TUPLE,
+};
+use crate::self_describing::{NAMED_MAGIC, SELF_DESCRIBING_MAGIC};
+use crate::{Decoder, EncoderError, Result, ENCODE_MAGIC};
+use bytes::{Buf, Bytes};
+
+/// The shape of a struct or enum variant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StructKind {
+    /// No fields.
+    Unit,
+    /// Named fields, written with their field IDs.
+    Named,
+    /// Positional fields.
+    Unnamed,
+}
+
+/// What the header of a payload tells about its value, as returned by [`peek_info`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PayloadInfo {
+    /// The type tag of the value (see [`crate::core`]).
+    pub tag: u8,
+    /// The number of bytes the value takes in the payload, after the magic number (and the name
+    /// table of self-describing payloads). Trailing bytes are counted too, so this is an upper
+    /// bound on the encoded size, and a rough measure of the decoded size.
+    pub approx_len: usize,
+    /// Set for structs and enums: the shape of the struct or of the variant.
+    pub struct_kind: Option<StructKind>,
+    /// The variant ID, for enums. For named documents, the hash of the variant name.
+    pub variant_id: Option<u64>,
+    /// The count the header announces: elements of lists, tuples and tuple structs or variants,
+    /// entries of maps and bytes of strings and binary data. `None` for other values.
+    pub count: Option<usize>,
+}
+
+/// Reads the header of a payload (with magic number) without consuming it.
+///
+/// Accepts payloads written by [`encode`](crate::encode),
+/// [`encode_self_describing`](crate::self_describing::encode_self_describing) and
+/// [`encode_named`](crate::self_describing::encode_named). Only the magic number, the first
+/// tag and the lengths or variant ID right after it are read; the rest of the payload is not
+/// validated, so a successful peek does not mean the payload decodes.
+///
+/// # Example
+/// ```rust
+/// use senax_encoder::{encode, peek_info, Encode, StructKind};
+/// use senax_encoder::core::field_id_from_name;
+///
+/// #[derive(Encode)]
+/// enum Command {
+///     Ping,
+///     Upload { name: String, data: Vec<u8> },
+/// }
+///
+/// let bytes = encode(&Command::Upload { name: "a.bin".into(), data: vec![0; 4096] }).unwrap();
+/// let info = peek_info(&bytes).unwrap();
+/// assert_eq!(info.variant_id, Some(field_id_from_name("Upload")));
+/// assert_eq!(info.struct_kind, Some(StructKind::Named));
+/// assert!(info.approx_len > 4096);
+///
+/// // Route uploads to the bulk queue, reject anything over 1 MiB
+/// let queue = match info.variant_id {
+///     _ if info.approx_len > 1 << 20 => "rejected",
+///     Some(id) if id == field_id_from_name("Upload") => "bulk",
+///     _ => "default",
+/// };
+/// assert_eq!(queue, "bulk");
+/// ```
+pub fn peek_info(bytes: &Bytes) -> Result<PayloadInfo> {
+    // Cloning `Bytes` only bumps a reference count; the caller's buffer is not advanced
+    let mut reader = bytes.clone();
+    if reader.remaining() < 2 {
+        return Err(EncoderError::eof(2, reader.remaining()));
+    }
+    let magic = reader.get_u16_le();
+    match magic {
+        ENCODE_MAGIC | NAMED_MAGIC => {}
+        SELF_DESCRIBING_MAGIC => {
+            for _ in 0..usize::decode(&mut reader)? {
+                skip_value(&mut reader)?;
+            }
+        }
+        magic => {
+            return Err(EncoderError::Decode(format!(
+                "Invalid encode magic number: expected 0x{:04X}, 0x{:04X} or 0x{:04X}, got 0x{:04X}",
+                ENCODE_MAGIC, SELF_DESCRIBING_MAGIC, NAMED_MAGIC, magic
+            )));
+        }
+    }
+    let approx_len = reader.remaining();
+    if !reader.has_remaining() {
+        return Err(EncoderError::eof(1, 0));
+    }
+    let tag = reader.get_u8();
+
+    let mut info = PayloadInfo {
+        tag,
+        approx_len,
+        struct_kind: None,
+        variant_id: None,
+        count: None,
+    };
+    match tag {
+        TAG_STRING_BASE..=TAG_STRING_LONG => {
+            info.count = Some(if tag < TAG_STRING_LONG {
+                (tag - TAG_STRING_BASE) as usize
+            } else {
+                usize::decode(&mut reader)?
+            });
+        }
+        TAG_ARRAY_VEC_SET_BASE..=TAG_ARRAY_VEC_SET_LONG => {
+            info.count = Some(if tag < TAG_ARRAY_VEC_SET_LONG {
+                (tag - TAG_ARRAY_VEC_SET_BASE) as usize
+            } else {
+                usize::decode(&mut reader)?
+            });
+        }
+        TAG_BINARY | TAG_TUPLE | TAG_MAP | TAG_MAP_STRING_KEYS | TAG_MAP_ID_KEYS
+        | TAG_JSON_ARRAY | TAG_JSON_OBJECT => {
+            info.count = Some(usize::decode(&mut reader)?);
+        }
+        TAG_MAP_FLAT_KEYS => {
+            // The key arity comes before the entry count
+            if !reader.has_remaining() {
+                return Err(EncoderError::eof(1, 0));
+            }
+            reader.advance(1);
+            info.count = Some(usize::decode(&mut reader)?);
+        }
+        TAG_STRUCT_UNIT => info.struct_kind = Some(StructKind::Unit),
+        TAG_STRUCT_NAMED => info.struct_kind = Some(StructKind::Named),
+        TAG_STRUCT_UNNAMED => {
+            info.struct_kind = Some(StructKind::Unnamed);
+            info.count = Some(usize::decode(&mut reader)?);
+        }
+        TAG_ENUM | TAG_ENUM_NAMED | TAG_ENUM_UNNAMED => {
+            info.variant_id = Some(read_field_id_optimized(&mut reader)?);
+            info.struct_kind = Some(match tag {
+                TAG_ENUM => StructKind::Unit,
+                TAG_ENUM_NAMED => StructKind::Named,
+                _ => StructKind::Unnamed,
+            });
+            if tag == TAG_ENUM_UNNAMED {
+                // Self-describing payloads put a name index between the ID and the count
+                if magic == SELF_DESCRIBING_MAGIC {
+                    usize::decode(&mut reader)?;
+                }
+                info.count = Some(usize::decode(&mut reader)?);
+            }
+        }
+        _ => {}
+    }
+    Ok(info)
+}
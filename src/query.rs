@@ -0,0 +1,308 @@
+//! Path queries over encoded payloads.
+//!
+//! [`query`] follows a path such as `items[2].name` through an encoded payload, skipping every
+//! value that is not on the path, and returns only the selected value. This lets log pipelines
+//! filter on a few fields without decoding whole messages.
+//!
+//! A path is a sequence of segments separated by `.`:
+//!
+//! * `name` selects a struct field (or a field of a struct-like enum variant) by name, or the
+//!   entry of a map with that string key. In [`encode`](crate::encode) payloads the name is
+//!   resolved to its field ID with [`field_id_from_name`]; in
+//!   [self-describing](crate::self_describing) payloads it is matched against the recorded
+//!   names. Use the `rename` value for renamed fields.
+//! * `123` (all digits) selects a struct field by ID, for fields with `#[senax(id = N)]`, or
+//!   the entry of an [`IdMap`](crate::IdMap) with that key.
+//! * `[2]` selects an element of a list, tuple, tuple struct or tuple variant. Indexes may
+//!   follow a name (`items[2]`) or each other (`grid[1][0]`).
+//!
+//! `Option` values are transparent: `Some` is unwrapped and `None` matches nothing.
+
+use crate::core::*;
+use crate::self_describing::{read_name_table, NAMED_MAGIC, SELF_DESCRIBING_MAGIC};
+use crate::value::{read_value, Value};
+use crate::{Decoder, EncoderError, Result, ENCODE_MAGIC};
+use bytes::{Buf, Bytes};
+
+/// The value selected by [`query`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum QueryMatch {
+    /// A scalar (integer, float, string, binary, UUID or unit enum variant), decoded.
+    Scalar(Value),
+    /// A struct, collection or other composite value, as its encoded bytes (without magic).
+    ///
+    /// For self-describing payloads the bytes still contain name indexes, so decode them with
+    /// the surrounding payload's name table rather than with `Decoder`.
+    Raw(Bytes),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Segment<'a> {
+    Name(&'a str),
+    Id(u64),
+    Index(usize),
+}
+
+/// Selects the value at `path` in an encoded payload (with magic number).
+///
+/// Accepts payloads written by [`encode`](crate::encode) and by
+/// [`encode_self_describing`](crate::self_describing::encode_self_describing). Returns
+/// `Ok(None)` if the path does not exist in the payload, and an error if the path cannot be
+/// parsed or the payload is malformed along the way.
+///
+/// # Example
+/// ```rust
+/// use senax_encoder::{encode, query, Encode, QueryMatch, Value};
+///
+/// #[derive(Encode)]
+/// struct Item { name: String, quantity: u32 }
+///
+/// #[derive(Encode)]
+/// struct Order { id: u64, items: Vec<Item> }
+///
+/// let order = Order {
+///     id: 9,
+///     items: vec![
+///         Item { name: "apple".into(), quantity: 3 },
+///         Item { name: "pear".into(), quantity: 1 },
+///     ],
+/// };
+/// let bytes = encode(&order).unwrap();
+/// assert_eq!(
+///     query(&bytes, "items[1].name").unwrap(),
+///     Some(QueryMatch::Scalar(Value::String("pear".into())))
+/// );
+/// assert_eq!(query(&bytes, "items[5].name").unwrap(), None);
+/// ```
+pub fn query(bytes: &Bytes, path: &str) -> Result<Option<QueryMatch>> {
+    let segments = parse_path(path)?;
+    let mut reader = bytes.clone();
+    if reader.remaining() < 2 {
+        return Err(EncoderError::eof(2, reader.remaining()));
+    }
+    let names = match reader.get_u16_le() {
+        // Names in named documents hash to the IDs that plain payloads carry
+        ENCODE_MAGIC | NAMED_MAGIC => None,
+        SELF_DESCRIBING_MAGIC => Some(read_name_table(&mut reader)?),
+        magic => {
+            return Err(EncoderError::Decode(format!(
+            "Invalid encode magic number: expected 0x{:04X}, 0x{:04X} or 0x{:04X}, got 0x{:04X}",
+            ENCODE_MAGIC, SELF_DESCRIBING_MAGIC, NAMED_MAGIC, magic
+        )))
+        }
+    };
+    let navigator = Navigator {
+        names: names.as_deref(),
+    };
+
+    for segment in segments {
+        if !navigator.unwrap_some(&mut reader)? || !navigator.select(&mut reader, segment)? {
+            return Ok(None);
+        }
+    }
+    if !navigator.unwrap_some(&mut reader)? {
+        return Ok(None);
+    }
+    navigator.take(&mut reader).map(Some)
+}
+
+fn parse_path(path: &str) -> Result<Vec<Segment<'_>>> {
+    let invalid = || EncoderError::Decode(format!("Invalid query path '{}'", path));
+    let mut segments = Vec::new();
+    for (i, part) in path.split('.').enumerate() {
+        let (head, mut rest) = match part.find('[') {
+            Some(pos) => part.split_at(pos),
+            None => (part, ""),
+        };
+        if head.is_empty() {
+            // Only the first segment may start with an index, e.g. "[0].name"
+            if i > 0 || rest.is_empty() {
+                return Err(invalid());
+            }
+        } else if head.bytes().all(|b| b.is_ascii_digit()) {
+            segments.push(Segment::Id(head.parse().map_err(|_| invalid())?));
+        } else {
+            segments.push(Segment::Name(head));
+        }
+        while !rest.is_empty() {
+            let end = rest.find(']').ok_or_else(invalid)?;
+            if !rest.starts_with('[') {
+                return Err(invalid());
+            }
+            let index = rest[1..end].parse().map_err(|_| invalid())?;
+            segments.push(Segment::Index(index));
+            rest = &rest[end + 1..];
+        }
+    }
+    Ok(segments)
+}
+
+struct Navigator<'a> {
+    /// The name table of a self-describing payload.
+    names: Option<&'a [String]>,
+}
+
+impl Navigator<'_> {
+    /// Steps into `Some` values. Returns `false` at a `None`.
+    fn unwrap_some(&self, reader: &mut Bytes) -> Result<bool> {
+        loop {
+            match reader.chunk().first() {
+                Some(&TAG_SOME) => reader.advance(1),
+                Some(&TAG_NONE) => return Ok(false),
+                Some(_) => return Ok(true),
+                None => return Err(EncoderError::eof(1, 0)),
+            }
+        }
+    }
+
+    /// Moves `reader` to the start of the child selected by `segment`.
+    ///
+    /// Returns `false` if the current value has no such child.
+    fn select(&self, reader: &mut Bytes, segment: Segment<'_>) -> Result<bool> {
+        let tag = reader.get_u8();
+        match (segment, tag) {
+            (Segment::Name(_) | Segment::Id(_), TAG_STRUCT_NAMED) => {
+                self.select_field(reader, segment)
+            }
+            (Segment::Name(_) | Segment::Id(_), TAG_ENUM_NAMED) => {
+                self.read_id(reader)?;
+                self.select_field(reader, segment)
+            }
+            (Segment::Name(name), TAG_MAP) => {
+                let len = usize::decode(reader)?;
+                for _ in 0..len {
+                    let key = read_value(reader, self.names, 1)?;
+                    if matches!(&key, Value::String(key) if key == name) {
+                        return Ok(true);
+                    }
+                    self.skip(reader)?;
+                }
+                Ok(false)
+            }
+            (Segment::Name(name), TAG_MAP_STRING_KEYS) => {
+                let len = usize::decode(reader)?;
+                for _ in 0..len {
+                    let key = read_string_key(reader)?;
+                    if key == name {
+                        return Ok(true);
+                    }
+                    self.skip(reader)?;
+                }
+                Ok(false)
+            }
+            (Segment::Id(wanted), TAG_MAP_ID_KEYS) => {
+                let len = usize::decode(reader)?;
+                for _ in 0..len {
+                    if read_field_id_optimized(reader)? == wanted {
+                        return Ok(true);
+                    }
+                    self.skip(reader)?;
+                }
+                Ok(false)
+            }
+            (Segment::Index(index), _) => {
+                let len = match tag {
+                    TAG_ARRAY_VEC_SET_BASE..=TAG_ARRAY_VEC_SET_LONG => {
+                        if tag < TAG_ARRAY_VEC_SET_LONG {
+                            (tag - TAG_ARRAY_VEC_SET_BASE) as usize
+                        } else {
+                            usize::decode(reader)?
+                        }
+                    }
+                    TAG_TUPLE | TAG_STRUCT_UNNAMED => usize::decode(reader)?,
+                    TAG_ENUM_UNNAMED => {
+                        self.read_id(reader)?;
+                        usize::decode(reader)?
+                    }
+                    _ => return Ok(false),
+                };
+                if index >= len {
+                    return Ok(false);
+                }
+                self.skip_n(reader, index)?;
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+
+    /// Scans `[field_id] [value]` pairs for the field selected by `segment`.
+    fn select_field(&self, reader: &mut Bytes, segment: Segment<'_>) -> Result<bool> {
+        loop {
+            let (id, name) = self.read_id(reader)?;
+            if id == 0 {
+                return Ok(false);
+            }
+            let found = match segment {
+                Segment::Id(wanted) => id == wanted,
+                Segment::Name(wanted) => match name {
+                    Some(name) => name == wanted,
+                    None => id == field_id_from_name(wanted),
+                },
+                Segment::Index(_) => false,
+            };
+            if found {
+                return Ok(true);
+            }
+            self.skip(reader)?;
+        }
+    }
+
+    /// Reads a field or variant ID and, in self-describing payloads, its name.
+    fn read_id(&self, reader: &mut Bytes) -> Result<(u64, Option<&str>)> {
+        let id = read_field_id_optimized(reader)?;
+        let names = match self.names {
+            Some(names) if id != 0 => names,
+            _ => return Ok((id, None)),
+        };
+        let index = usize::decode(reader)?;
+        let name = names.get(index).ok_or_else(|| {
+            EncoderError::Decode(format!(
+                "Name index {} out of range for a table of {} names",
+                index,
+                names.len()
+            ))
+        })?;
+        Ok((id, Some(name)))
+    }
+
+    fn skip(&self, reader: &mut Bytes) -> Result<()> {
+        match self.names {
+            // Name indexes are not understood by skip_value
+            Some(names) => read_value(reader, Some(names), 1).map(drop),
+            None => skip_value(reader),
+        }
+    }
+
+    fn skip_n(&self, reader: &mut Bytes, count: usize) -> Result<()> {
+        if self.names.is_none() {
+            return skip_n_values(reader, count);
+        }
+        for _ in 0..count {
+            self.skip(reader)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the value at the start of `reader`.
+    fn take(&self, reader: &mut Bytes) -> Result<QueryMatch> {
+        let tag = reader.chunk()[0];
+        let scalar = matches!(
+            tag,
+            TAG_ZERO..=TAG_U128
+                | TAG_NEGATIVE
+                | TAG_F32
+                | TAG_F64
+                | TAG_STRING_BASE..=TAG_BINARY
+                | TAG_ENUM
+                | TAG_UUID
+        );
+        if scalar {
+            return read_value(reader, self.names, 1).map(QueryMatch::Scalar);
+        }
+        let mut rest = reader.clone();
+        self.skip(&mut rest)?;
+        let len = reader.remaining() - rest.remaining();
+        Ok(QueryMatch::Raw(reader.split_to(len)))
+    }
+}
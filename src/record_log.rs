@@ -0,0 +1,347 @@
+//! Append-only record log.
+//!
+//! A record log is a plain sequence of frames:
+//!
+//! ```text
+//! [payload_len: u32 little-endian] [payload: payload_len bytes] ...
+//! ```
+//!
+//! Payloads written by [`RecordLogWriter::append_value`] are full `encode()` outputs,
+//! including the encode magic number. A frame cut off by a crash mid-write is reported as
+//! [`EncoderError::UnexpectedEof`] so callers can truncate the log at [`RecordLogReader::offset`].
+//! A length field above [`RecordLogReader::with_max_record_len`] (16 MiB by default) is
+//! reported as [`EncoderError::Decode`] before any payload buffer is allocated.
+//!
+//! Records written through a [`TransactionWriter`] are enclosed in marker frames, whose length
+//! field holds a reserved value instead of a payload length:
+//!
+//! ```text
+//! [TXN_BEGIN] [record frame] ... [TXN_COMMIT] [record_count: u32 little-endian]
+//! ```
+//!
+//! The reader only returns the records of a transaction once it has read the commit marker.
+//! A transaction cut off before its commit marker (the writer crashed mid-commit) ends the log
+//! without an error, and [`RecordLogReader::offset`] stays at its start, so truncating the log
+//! there drops the uncommitted tail.
+
+use crate::frame::{self, HEADER_LEN};
+use crate::{decode, encode, Decoder, Encoder, EncoderError, Result};
+use bytes::Bytes;
+use std::collections::VecDeque;
+use std::io::{Read, Write};
+
+/// Length field value that starts a transaction.
+pub const TXN_BEGIN: u32 = u32::MAX;
+
+/// Length field value that commits a transaction; followed by the number of records in it.
+pub const TXN_COMMIT: u32 = u32::MAX - 1;
+
+/// Largest payload a frame can hold; longer lengths are reserved for the transaction markers.
+pub const MAX_RECORD_LEN: u32 = frame::MAX_PAYLOAD_LEN;
+
+/// Default limit on the payload length accepted by [`RecordLogReader`].
+pub const DEFAULT_MAX_RECORD_LEN: usize = 16 * 1024 * 1024;
+
+/// Whether a frame read from the log holds a record or ended the input.
+enum Frame {
+    Record(Bytes),
+    Begin,
+    Commit(u32),
+    End,
+}
+
+/// Reads frames from a record log.
+pub struct RecordLogReader<R> {
+    inner: R,
+    offset: u64,
+    max_record_len: usize,
+    /// Records of the last committed transaction not returned yet
+    pending: VecDeque<Bytes>,
+}
+
+impl<R: Read> RecordLogReader<R> {
+    /// Creates a reader positioned at the start of the log, accepting records up to
+    /// [`DEFAULT_MAX_RECORD_LEN`].
+    pub fn new(inner: R) -> Self {
+        Self {
+            inner,
+            offset: 0,
+            max_record_len: DEFAULT_MAX_RECORD_LEN,
+            pending: VecDeque::new(),
+        }
+    }
+
+    /// Sets the longest payload accepted; longer records fail to read.
+    pub fn with_max_record_len(mut self, max_record_len: usize) -> Self {
+        self.max_record_len = max_record_len;
+        self
+    }
+
+    /// The longest payload accepted.
+    pub fn max_record_len(&self) -> usize {
+        self.max_record_len
+    }
+
+    /// Returns the number of bytes consumed by complete frames and committed transactions so far.
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    /// Reads the next frame payload, or `None` at the end of the log.
+    ///
+    /// Records of a transaction are returned once its commit marker has been read; an
+    /// uncommitted transaction at the end of the log is skipped.
+    pub fn read_record(&mut self) -> Result<Option<Bytes>> {
+        loop {
+            if let Some(record) = self.pending.pop_front() {
+                return Ok(Some(record));
+            }
+            match self.read_frame()? {
+                (Frame::Record(payload), size) => {
+                    self.offset += size;
+                    return Ok(Some(payload));
+                }
+                (Frame::Begin, size) => {
+                    if !self.read_transaction(size)? {
+                        return Ok(None);
+                    }
+                }
+                (Frame::Commit(_), _) => {
+                    return Err(EncoderError::Decode(
+                        "Record log has a commit marker without a transaction".to_string(),
+                    ))
+                }
+                (Frame::End, _) => return Ok(None),
+            }
+        }
+    }
+
+    /// Reads the records of a transaction up to its commit marker into `pending`.
+    ///
+    /// Returns `false`, leaving the offset at the transaction's start, if the log ends first.
+    fn read_transaction(&mut self, begin_size: u64) -> Result<bool> {
+        let mut records = VecDeque::new();
+        let mut size = begin_size;
+        loop {
+            let frame = match self.read_frame() {
+                Ok(frame) => frame,
+                Err(EncoderError::UnexpectedEof { .. }) => return Ok(false),
+                Err(e) => return Err(e),
+            };
+            size += frame.1;
+            match frame.0 {
+                Frame::Record(payload) => records.push_back(payload),
+                Frame::Commit(count) if count as usize == records.len() => {
+                    self.offset += size;
+                    self.pending = records;
+                    return Ok(true);
+                }
+                Frame::Commit(count) => {
+                    return Err(EncoderError::Decode(format!(
+                        "Record log transaction commits {} records but holds {}",
+                        count,
+                        records.len()
+                    )))
+                }
+                Frame::Begin => {
+                    return Err(EncoderError::Decode(
+                        "Record log transaction starts before the previous one is committed"
+                            .to_string(),
+                    ))
+                }
+                Frame::End => return Ok(false),
+            }
+        }
+    }
+
+    /// Reads one frame and returns it with its size in bytes.
+    fn read_frame(&mut self) -> Result<(Frame, u64)> {
+        let mut len_buf = [0u8; HEADER_LEN];
+        let n = read_full(&mut self.inner, &mut len_buf)?;
+        if n == 0 {
+            return Ok((Frame::End, 0));
+        }
+        match frame::read_header(&len_buf[..n])? {
+            TXN_BEGIN => Ok((Frame::Begin, HEADER_LEN as u64)),
+            TXN_COMMIT => {
+                let mut count_buf = [0u8; 4];
+                let n = read_full(&mut self.inner, &mut count_buf)?;
+                if n < count_buf.len() {
+                    return Err(EncoderError::eof(count_buf.len(), n));
+                }
+                Ok((
+                    Frame::Commit(u32::from_le_bytes(count_buf)),
+                    2 * HEADER_LEN as u64,
+                ))
+            }
+            len => {
+                let len = len as usize;
+                if len > self.max_record_len {
+                    return Err(EncoderError::Decode(format!(
+                        "Record of {} bytes exceeds the limit of {} bytes",
+                        len, self.max_record_len
+                    )));
+                }
+                let mut payload = vec![0u8; len];
+                let n = read_full(&mut self.inner, &mut payload)?;
+                if n < len {
+                    return Err(EncoderError::eof(len, n));
+                }
+                Ok((
+                    Frame::Record(Bytes::from(payload)),
+                    (HEADER_LEN + len) as u64,
+                ))
+            }
+        }
+    }
+
+    /// Reads and decodes the next record, or `None` at the end of the log.
+    pub fn read_value<T: Decoder>(&mut self) -> Result<Option<T>> {
+        match self.read_record()? {
+            Some(mut payload) => decode(&mut payload).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Returns the underlying reader.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+/// Appends frames to a record log.
+pub struct RecordLogWriter<W> {
+    inner: W,
+}
+
+impl<W: Write> RecordLogWriter<W> {
+    /// Creates a writer that appends to `inner`.
+    pub fn new(inner: W) -> Self {
+        Self { inner }
+    }
+
+    /// Appends a raw payload as one frame.
+    pub fn append(&mut self, payload: &[u8]) -> Result<()> {
+        self.inner.write_all(&frame::header(payload.len())?)?;
+        self.inner.write_all(payload)?;
+        Ok(())
+    }
+
+    /// Starts a transaction whose records are written together by [`TransactionWriter::commit`].
+    ///
+    /// # Example
+    /// ```rust
+    /// use senax_encoder::record_log::{RecordLogReader, RecordLogWriter};
+    ///
+    /// let mut log = Vec::new();
+    /// let mut writer = RecordLogWriter::new(&mut log);
+    /// let mut txn = writer.transaction();
+    /// txn.append_value(&"debit".to_string()).unwrap();
+    /// txn.append_value(&"credit".to_string()).unwrap();
+    /// txn.commit().unwrap();
+    ///
+    /// let mut reader = RecordLogReader::new(log.as_slice());
+    /// assert_eq!(reader.read_value::<String>().unwrap().as_deref(), Some("debit"));
+    /// assert_eq!(reader.read_value::<String>().unwrap().as_deref(), Some("credit"));
+    /// assert_eq!(reader.read_value::<String>().unwrap(), None);
+    /// ```
+    pub fn transaction(&mut self) -> TransactionWriter<'_, W> {
+        TransactionWriter {
+            log: self,
+            buffer: Vec::new(),
+            count: 0,
+        }
+    }
+
+    /// Encodes `value` with [`encode`] and appends it as one frame.
+    pub fn append_value<T: Encoder>(&mut self, value: &T) -> Result<()> {
+        let payload = encode(value)?;
+        self.append(&payload)
+    }
+
+    /// Flushes buffered frames to the underlying writer.
+    pub fn flush(&mut self) -> Result<()> {
+        self.inner.flush()?;
+        Ok(())
+    }
+
+    /// Returns the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+/// Buffers records and appends them to a record log atomically.
+///
+/// Created by [`RecordLogWriter::transaction`]. Nothing reaches the log until
+/// [`commit`](Self::commit), which writes the begin marker, the records and the commit marker
+/// with one `write_all`. Dropping the transaction without committing discards its records.
+/// Readers skip a transaction whose commit marker never made it to disk.
+pub struct TransactionWriter<'a, W: Write> {
+    log: &'a mut RecordLogWriter<W>,
+    buffer: Vec<u8>,
+    count: u32,
+}
+
+impl<W: Write> TransactionWriter<'_, W> {
+    /// Buffers a raw payload as one record of the transaction.
+    pub fn append(&mut self, payload: &[u8]) -> Result<()> {
+        let header = frame::header(payload.len())?;
+        self.count = self.count.checked_add(1).ok_or_else(|| {
+            EncoderError::Encode("Too many records in one record log transaction".to_string())
+        })?;
+        if self.buffer.is_empty() {
+            self.buffer.extend_from_slice(&TXN_BEGIN.to_le_bytes());
+        }
+        self.buffer.extend_from_slice(&header);
+        self.buffer.extend_from_slice(payload);
+        Ok(())
+    }
+
+    /// Encodes `value` with [`encode`] and buffers it as one record of the transaction.
+    pub fn append_value<T: Encoder>(&mut self, value: &T) -> Result<()> {
+        let payload = encode(value)?;
+        self.append(&payload)
+    }
+
+    /// Returns the number of records buffered so far.
+    pub fn len(&self) -> usize {
+        self.count as usize
+    }
+
+    /// Returns `true` if no record has been buffered.
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    /// Writes the buffered records and the commit marker to the log.
+    ///
+    /// An empty transaction writes nothing. Call [`RecordLogWriter::flush`] (and sync the file)
+    /// afterwards to make the commit durable.
+    pub fn commit(mut self) -> Result<()> {
+        if self.count == 0 {
+            return Ok(());
+        }
+        self.buffer.extend_from_slice(&TXN_COMMIT.to_le_bytes());
+        self.buffer.extend_from_slice(&self.count.to_le_bytes());
+        self.log.inner.write_all(&self.buffer)?;
+        Ok(())
+    }
+
+    /// Discards the buffered records; same as dropping the transaction.
+    pub fn rollback(self) {}
+}
+
+/// Reads until `buf` is full or the reader is exhausted, returning the number of bytes read.
+fn read_full<R: Read>(reader: &mut R, buf: &mut [u8]) -> Result<usize> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        match reader.read(&mut buf[filled..]) {
+            Ok(0) => break,
+            Ok(n) => filled += n,
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e.into()),
+        }
+    }
+    Ok(filled)
+}
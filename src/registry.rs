@@ -0,0 +1,142 @@
+//! Compile-time message registries.
+//!
+//! [`message_registry!`](crate::message_registry) declares a closed set of message types, each
+//! with a fixed type ID, and generates an enum with a `match`-based dispatcher. A message is
+//! framed as
+//!
+//! ```text
+//! [type_id] [payload]
+//! ```
+//!
+//! where `type_id` uses the field ID encoding and `payload` is the message's
+//! [`Encoder::encode`](crate::Encoder::encode) output. Decoding needs no `HashMap` lookup or
+//! boxed decoder: the type ID is matched directly against the registered IDs.
+
+/// Returns `true` if every ID is non-zero and no ID appears twice. Used by `message_registry!`.
+#[doc(hidden)]
+pub const fn type_ids_are_valid(ids: &[u64]) -> bool {
+    let mut i = 0;
+    while i < ids.len() {
+        if ids[i] == 0 {
+            return false;
+        }
+        let mut j = i + 1;
+        while j < ids.len() {
+            if ids[i] == ids[j] {
+                return false;
+            }
+            j += 1;
+        }
+        i += 1;
+    }
+    true
+}
+
+/// Declares an enum over a fixed set of message types with static decode dispatch.
+///
+/// Each variant wraps one message type and is assigned a non-zero integer literal type ID.
+/// Duplicate or zero IDs are rejected at compile time. The generated enum provides:
+///
+/// * `TYPE_IDS` - the registered type IDs in declaration order
+/// * `message_type_id(&self)` - the type ID of the contained message
+/// * `encode_message(&self, writer)` - writes `[type_id] [payload]`
+/// * `decode_message(reader)` - reads the type ID and decodes the matching message
+/// * `decode_payload(type_id, reader)` - decodes a payload whose type ID was read elsewhere
+/// * `From<T>` for each message type
+///
+/// # Example
+/// ```rust
+/// use senax_encoder::{message_registry, Decode, Encode};
+/// use bytes::BytesMut;
+///
+/// #[derive(Encode, Decode, Debug, PartialEq)]
+/// struct Login { user: String }
+///
+/// #[derive(Encode, Decode, Debug, PartialEq)]
+/// struct Logout { session: u64 }
+///
+/// message_registry! {
+///     #[derive(Debug, PartialEq)]
+///     pub enum Message {
+///         Login(Login) = 1,
+///         Logout(Logout) = 2,
+///     }
+/// }
+///
+/// let mut buf = BytesMut::new();
+/// Message::from(Logout { session: 9 }).encode_message(&mut buf).unwrap();
+/// let decoded = Message::decode_message(&mut buf.freeze()).unwrap();
+/// assert_eq!(decoded, Message::Logout(Logout { session: 9 }));
+/// ```
+#[macro_export]
+macro_rules! message_registry {
+    (
+        $(#[$meta:meta])*
+        $vis:vis enum $name:ident {
+            $( $variant:ident ( $ty:ty ) = $id:literal ),+ $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        $vis enum $name {
+            $( $variant($ty), )+
+        }
+
+        const _: () = assert!(
+            $crate::registry::type_ids_are_valid(&[$($id),+]),
+            "message_registry!: type IDs must be non-zero and unique"
+        );
+
+        impl $name {
+            /// Type IDs of the registered messages, in declaration order.
+            pub const TYPE_IDS: &'static [u64] = &[$($id),+];
+
+            /// Returns the type ID of the contained message.
+            pub fn message_type_id(&self) -> u64 {
+                match self {
+                    $( $name::$variant(_) => $id, )+
+                }
+            }
+
+            /// Writes the type ID followed by the encoded message.
+            pub fn encode_message(
+                &self,
+                writer: &mut $crate::bytes::BytesMut,
+            ) -> $crate::Result<()> {
+                $crate::core::write_field_id_optimized(writer, self.message_type_id())?;
+                match self {
+                    $( $name::$variant(message) => $crate::Encoder::encode(message, writer), )+
+                }
+            }
+
+            /// Reads a type ID and decodes the message registered under it.
+            pub fn decode_message(reader: &mut $crate::bytes::Bytes) -> $crate::Result<Self> {
+                let type_id = $crate::core::read_field_id_optimized(reader)?;
+                Self::decode_payload(type_id, reader)
+            }
+
+            /// Decodes the message registered under `type_id` from `reader`.
+            pub fn decode_payload(
+                type_id: u64,
+                reader: &mut $crate::bytes::Bytes,
+            ) -> $crate::Result<Self> {
+                match type_id {
+                    $( $id => Ok($name::$variant(<$ty as $crate::Decoder>::decode(reader)?)), )+
+                    _ => Err($crate::EncoderError::EnumDecode(
+                        $crate::EnumDecodeError::UnknownVariantId {
+                            variant_id: type_id,
+                            enum_name: stringify!($name),
+                        },
+                    )),
+                }
+            }
+        }
+
+        $(
+            impl From<$ty> for $name {
+                fn from(message: $ty) -> Self {
+                    $name::$variant(message)
+                }
+            }
+        )+
+    };
+}
@@ -0,0 +1,183 @@
+//! Encoding third-party types that implement none of the codec traits.
+//!
+//! The orphan rule keeps a crate from implementing [`Encoder`] and [`Decoder`] for a type it
+//! does not own, such as `chrono::Weekday`. Instead of wrapping such fields in a newtype,
+//! declare a proxy with [`senax_remote!`](crate::senax_remote) that converts the type to and
+//! from a representation which does implement them, and mark the fields with
+//! `#[senax(with = "Proxy")]`:
+//!
+//! ```rust
+//! use senax_encoder::{decode, encode, senax_remote, Decode, Encode, EncoderError};
+//!
+//! // A type from another crate
+//! mod calendar {
+//!     #[derive(Debug, Clone, Copy, PartialEq)]
+//!     pub enum Weekday { Mon, Tue, Wed, Thu, Fri, Sat, Sun }
+//! }
+//! use calendar::Weekday;
+//!
+//! senax_remote! {
+//!     /// Writes a `Weekday` as its number from Monday.
+//!     pub struct WeekdayDef(Weekday) as u8 {
+//!         into: |day| *day as u8,
+//!         from: |n| match n {
+//!             0 => Ok(Weekday::Mon),
+//!             1 => Ok(Weekday::Tue),
+//!             2 => Ok(Weekday::Wed),
+//!             3 => Ok(Weekday::Thu),
+//!             4 => Ok(Weekday::Fri),
+//!             5 => Ok(Weekday::Sat),
+//!             6 => Ok(Weekday::Sun),
+//!             n => Err(EncoderError::Decode(format!("invalid weekday {}", n))),
+//!         },
+//!     }
+//! }
+//!
+//! #[derive(Encode, Decode, Debug, PartialEq)]
+//! struct Shift {
+//!     #[senax(with = "WeekdayDef")]
+//!     day: Weekday,
+//!     #[senax(with = "WeekdayDef")]
+//!     swap_day: Option<Weekday>,
+//! }
+//!
+//! let shift = Shift { day: Weekday::Fri, swap_day: None };
+//! let decoded: Shift = decode(&mut encode(&shift).unwrap()).unwrap();
+//! assert_eq!(decoded, shift);
+//! ```
+//!
+//! The field is written exactly like its representation, so a `u8` field can later become a
+//! `#[senax(with)]` field without changing the payloads. On an `Option` field the proxy
+//! converts the inner value. `with` applies to named struct fields, for `Encode`, `Decode`,
+//! `Pack` and `Unpack`, and `HasSchema` and `MaxEncodedSize` describe the representation.
+//! Fields that fall back to their default (`default`, `skip_default`, `skip_decode`) still
+//! need the remote type to implement `Default`.
+
+use crate::{Decoder, Encoder, Packer, Result, Unpacker};
+use bytes::{Bytes, BytesMut};
+
+/// A proxy that encodes `Target` as `Repr`, declared with [`senax_remote!`](crate::senax_remote).
+pub trait Remote {
+    /// The type the proxy stands in for.
+    type Target;
+    /// The type written to the payload.
+    type Repr;
+
+    /// Converts a value to its representation.
+    fn to_repr(value: &Self::Target) -> Self::Repr;
+
+    /// Converts a decoded representation back, failing for representations with no value.
+    fn from_repr(repr: Self::Repr) -> Result<Self::Target>;
+}
+
+/// Encodes `value` as its representation.
+pub fn encode<R: Remote>(value: &R::Target, writer: &mut BytesMut) -> Result<()>
+where
+    R::Repr: Encoder,
+{
+    R::to_repr(value).encode(writer)
+}
+
+/// Decodes a representation and converts it back.
+pub fn decode<R: Remote>(reader: &mut Bytes) -> Result<R::Target>
+where
+    R::Repr: Decoder,
+{
+    R::from_repr(R::Repr::decode(reader)?)
+}
+
+/// Returns `true` if the representation of `value` is its default, for `skip_default` fields.
+pub fn is_default<R: Remote>(value: &R::Target) -> bool
+where
+    R::Repr: Encoder,
+{
+    R::to_repr(value).is_default()
+}
+
+/// Packs `value` as its representation.
+pub fn pack<R: Remote>(value: &R::Target, writer: &mut BytesMut) -> Result<()>
+where
+    R::Repr: Packer,
+{
+    R::to_repr(value).pack(writer)
+}
+
+/// Unpacks a representation and converts it back.
+pub fn unpack<R: Remote>(reader: &mut Bytes) -> Result<R::Target>
+where
+    R::Repr: Unpacker,
+{
+    R::from_repr(R::Repr::unpack(reader)?)
+}
+
+/// Packs an optional value, converting the inner value.
+pub fn pack_option<R: Remote>(value: &Option<R::Target>, writer: &mut BytesMut) -> Result<()>
+where
+    R::Repr: Packer,
+{
+    value.as_ref().map(R::to_repr).pack(writer)
+}
+
+/// Unpacks an optional value, converting the inner value.
+pub fn unpack_option<R: Remote>(reader: &mut Bytes) -> Result<Option<R::Target>>
+where
+    R::Repr: Unpacker,
+{
+    Option::<R::Repr>::unpack(reader)?
+        .map(R::from_repr)
+        .transpose()
+}
+
+/// Declares a [`Remote`] proxy that encodes a type through a conversion to another type.
+///
+/// `into` takes a `&Target` and returns the representation; `from` takes the representation
+/// and returns `senax_encoder::Result<Target>`. Both accept closures or paths to functions.
+/// Fields use the proxy with `#[senax(with = "Proxy")]`, and its functions can be called
+/// directly through [`remote::encode`](crate::remote::encode) and friends.
+///
+/// # Example
+/// ```rust
+/// use senax_encoder::remote::{decode, encode};
+/// use senax_encoder::senax_remote;
+/// use std::net::Ipv4Addr;
+///
+/// senax_remote! {
+///     pub struct Ipv4Def(Ipv4Addr) as u32 {
+///         into: |addr| u32::from(*addr),
+///         from: |bits| Ok(Ipv4Addr::from(bits)),
+///     }
+/// }
+///
+/// let mut buf = bytes::BytesMut::new();
+/// encode::<Ipv4Def>(&Ipv4Addr::LOCALHOST, &mut buf).unwrap();
+/// let addr = decode::<Ipv4Def>(&mut buf.freeze()).unwrap();
+/// assert_eq!(addr, Ipv4Addr::LOCALHOST);
+/// ```
+#[macro_export]
+macro_rules! senax_remote {
+    (
+        $(#[$meta:meta])*
+        $vis:vis struct $name:ident ( $target:ty ) as $repr:ty {
+            into: $into:expr,
+            from: $from:expr $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        $vis struct $name;
+
+        impl $crate::remote::Remote for $name {
+            type Target = $target;
+            type Repr = $repr;
+
+            fn to_repr(value: &$target) -> $repr {
+                let into: fn(&$target) -> $repr = $into;
+                into(value)
+            }
+
+            fn from_repr(repr: $repr) -> $crate::Result<$target> {
+                let from: fn($repr) -> $crate::Result<$target> = $from;
+                from(repr)
+            }
+        }
+    };
+}
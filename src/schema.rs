@@ -0,0 +1,822 @@
+//! Schema descriptors and writer-schema-driven decoding.
+//!
+//! A [`TypeSchema`] describes the wire shape of a type: its field names, IDs and types. Schemas
+//! implement `Encode`/`Decode`, so the schema a payload was written with can be stored next to
+//! the data or in a registry.
+//!
+//! [`SchemaResolver`] reads payloads written with an older (writer) schema as the current
+//! (reader) type, in the spirit of Avro schema resolution:
+//!
+//! * writer fields are matched to reader fields by name, then by the reader's aliases, then by
+//!   field ID, and rewritten to the reader's field ID (so renames survive ID changes);
+//! * writer fields without a reader counterpart are dropped;
+//! * reader fields missing from the writer schema are filled with the default value of their
+//!   type (`Option` fields are left empty);
+//! * integers may be widened (`UInt` to `Int` or `Float`, `Int` to `Float`); other type changes
+//!   are rejected when the resolver is built.
+//!
+//! [`check_compat`] classifies the changes between two versions of a schema as backward
+//! compatible, forward compatible or breaking, for compatibility gates in CI.
+//!
+//! [`codegen`] turns a schema into decoder stubs for TypeScript and Go.
+
+use crate::core::field_id_from_name;
+use crate::value::{write_value, Field, Value};
+use crate::{decode, Decode, Decoder, Encode, Encoder, EncoderError, Result};
+use bytes::{Bytes, BytesMut};
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::rc::Rc;
+use std::sync::Arc;
+
+pub mod codegen;
+mod compat;
+
+pub use compat::{check_compat, CompatChange, CompatReport, Compatibility, SchemaChange};
+
+/// The wire shape of a type.
+#[derive(Encode, Decode, Debug, Clone, PartialEq)]
+#[senax(crate = "crate")]
+pub enum TypeSchema {
+    /// `bool`.
+    Bool,
+    /// Unsigned integers.
+    UInt,
+    /// Signed integers.
+    Int,
+    /// `f32` and `f64`.
+    Float,
+    /// Strings.
+    String,
+    /// Binary data.
+    Bytes,
+    /// `Option<T>`.
+    Option(Box<TypeSchema>),
+    /// Vectors, arrays and sets.
+    List(Box<TypeSchema>),
+    /// Maps.
+    Map(Box<TypeSchema>, Box<TypeSchema>),
+    /// A struct with named fields.
+    Struct(StructSchema),
+    /// Any other type, passed through unchanged.
+    Any,
+    /// Tuples and `()`.
+    Tuple(Vec<TypeSchema>),
+    /// A tuple struct, or a unit struct when it has no fields.
+    TupleStruct(TupleStructSchema),
+    /// An enum.
+    Enum(EnumSchema),
+}
+
+/// The fields of a struct.
+#[derive(Encode, Decode, Debug, Clone, PartialEq)]
+#[senax(crate = "crate")]
+pub struct StructSchema {
+    /// The type name.
+    pub name: String,
+    /// The fields in declaration order.
+    pub fields: Vec<FieldSchema>,
+}
+
+/// The positional fields of a tuple struct.
+#[derive(Encode, Decode, Debug, Clone, PartialEq)]
+#[senax(crate = "crate")]
+pub struct TupleStructSchema {
+    /// The type name.
+    pub name: String,
+    /// The field types in declaration order.
+    pub fields: Vec<TypeSchema>,
+}
+
+/// The variants of an enum.
+#[derive(Encode, Decode, Debug, Clone, PartialEq)]
+#[senax(crate = "crate")]
+pub struct EnumSchema {
+    /// The type name.
+    pub name: String,
+    /// The variants in declaration order.
+    pub variants: Vec<VariantSchema>,
+    /// Whether unit variants are written as their name (`#[senax(string_repr)]`) rather than
+    /// their ID.
+    #[senax(default)]
+    pub string_repr: bool,
+}
+
+/// An enum variant.
+#[derive(Encode, Decode, Debug, Clone, PartialEq)]
+#[senax(crate = "crate")]
+pub struct VariantSchema {
+    /// The variant name (the `rename` value, if any).
+    pub name: String,
+    /// The variant ID.
+    pub id: u64,
+    /// The fields: a [`TypeSchema::Struct`] for struct-like variants, a [`TypeSchema::Tuple`]
+    /// for tuple-like variants and `None` for unit variants.
+    pub fields: Option<TypeSchema>,
+}
+
+/// A named struct field.
+#[derive(Encode, Decode, Debug, Clone, PartialEq)]
+#[senax(crate = "crate")]
+pub struct FieldSchema {
+    /// The field name (the `rename` value, if any).
+    pub name: String,
+    /// The field ID.
+    pub id: u64,
+    /// The field type.
+    pub ty: TypeSchema,
+    /// Former names of the field, used to match writer fields after a rename.
+    #[senax(default)]
+    pub aliases: Vec<String>,
+    /// Whether the decoder falls back to a default value when the field is missing
+    /// (`default`, `skip_default` or `skip_decode`).
+    #[senax(default)]
+    pub default: bool,
+    /// The `///` doc comment of the field, for generated documentation. Not part of the wire
+    /// format.
+    pub doc: Option<String>,
+}
+
+impl TypeSchema {
+    /// Returns a 64-bit fingerprint of the wire shape, for comparing schemas with a peer
+    /// without sending them whole.
+    ///
+    /// The fingerprint is the CRC-64/ECMA-182 checksum of the encoded schema with field doc
+    /// comments removed, so it changes with names, IDs, types and defaults but not with
+    /// documentation.
+    ///
+    /// # Example
+    /// ```rust
+    /// use senax_encoder::schema::{FieldSchema, StructSchema, TypeSchema};
+    ///
+    /// let v1 = TypeSchema::Struct(StructSchema::new(
+    ///     "User",
+    ///     vec![FieldSchema::new("name", TypeSchema::String)],
+    /// ));
+    /// let documented = TypeSchema::Struct(StructSchema::new(
+    ///     "User",
+    ///     vec![FieldSchema::new("name", TypeSchema::String).with_doc("Display name")],
+    /// ));
+    /// let v2 = TypeSchema::Struct(StructSchema::new(
+    ///     "User",
+    ///     vec![FieldSchema::new("name", TypeSchema::Bytes)],
+    /// ));
+    /// assert_eq!(v1.fingerprint(), documented.fingerprint());
+    /// assert_ne!(v1.fingerprint(), v2.fingerprint());
+    /// ```
+    pub fn fingerprint(&self) -> u64 {
+        let mut schema = self.clone();
+        schema.strip_docs();
+        let mut writer = BytesMut::new();
+        // Schemas contain only strings, integers and collections, which always encode
+        match schema.encode(&mut writer) {
+            Ok(()) => crate::core::crc64_update(0, &writer),
+            Err(_) => 0,
+        }
+    }
+
+    fn strip_docs(&mut self) {
+        match self {
+            TypeSchema::Option(inner) | TypeSchema::List(inner) => inner.strip_docs(),
+            TypeSchema::Map(key, value) => {
+                key.strip_docs();
+                value.strip_docs();
+            }
+            TypeSchema::Struct(schema) => {
+                for field in &mut schema.fields {
+                    field.doc = None;
+                    field.ty.strip_docs();
+                }
+            }
+            TypeSchema::Tuple(fields) => fields.iter_mut().for_each(TypeSchema::strip_docs),
+            TypeSchema::TupleStruct(schema) => {
+                schema.fields.iter_mut().for_each(TypeSchema::strip_docs)
+            }
+            TypeSchema::Enum(schema) => {
+                for variant in &mut schema.variants {
+                    if let Some(fields) = &mut variant.fields {
+                        fields.strip_docs();
+                    }
+                }
+            }
+            TypeSchema::Bool
+            | TypeSchema::UInt
+            | TypeSchema::Int
+            | TypeSchema::Float
+            | TypeSchema::String
+            | TypeSchema::Bytes
+            | TypeSchema::Any => {}
+        }
+    }
+}
+
+impl StructSchema {
+    /// Creates a struct schema.
+    pub fn new(name: impl Into<String>, fields: Vec<FieldSchema>) -> Self {
+        Self {
+            name: name.into(),
+            fields,
+        }
+    }
+}
+
+impl FieldSchema {
+    /// Creates a field whose ID is derived from its name, as the derive macros do.
+    pub fn new(name: impl Into<String>, ty: TypeSchema) -> Self {
+        let name = name.into();
+        Self {
+            id: field_id_from_name(&name),
+            name,
+            ty,
+            aliases: Vec::new(),
+            default: false,
+            doc: None,
+        }
+    }
+
+    /// Sets an explicit field ID, as given with `#[senax(id = N)]`.
+    pub fn with_id(mut self, id: u64) -> Self {
+        self.id = id;
+        self
+    }
+
+    /// Marks the field as falling back to a default value when missing.
+    pub fn with_default(mut self) -> Self {
+        self.default = true;
+        self
+    }
+
+    /// Sets the documentation of the field.
+    pub fn with_doc(mut self, doc: impl Into<String>) -> Self {
+        self.doc = Some(doc.into());
+        self
+    }
+
+    /// Returns `true` if payloads must contain the field to be decoded: it is neither an
+    /// `Option` nor has a default.
+    pub fn is_required(&self) -> bool {
+        !self.default && !matches!(self.ty, TypeSchema::Option(_))
+    }
+
+    /// Adds former names of the field.
+    pub fn with_aliases<I, S>(mut self, aliases: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.aliases.extend(aliases.into_iter().map(Into::into));
+        self
+    }
+}
+
+/// Types that describe their wire shape as a [`TypeSchema`].
+///
+/// Implemented for the built-in types and derived for structs and enums with
+/// `#[senax(schema)]` next to `#[derive(Encode)]`. The derived schema lists the fields the
+/// encoder writes (fields marked `skip_encode` or `deprecated` are left out) with their names
+/// and IDs, and nests the schemas of the field types. A type that refers back to itself is
+/// described as [`TypeSchema::Any`] where it recurses.
+///
+/// Comparing the schema of a type with the one a peer publishes (or with a stored copy) checks
+/// wire compatibility at startup, and the schema itself documents the binary format.
+///
+/// # Example
+/// ```rust
+/// use senax_encoder::schema::{HasSchema, SchemaResolver, TypeSchema};
+/// use senax_encoder::Encode;
+///
+/// #[derive(Encode)]
+/// #[senax(schema)]
+/// struct User {
+///     #[senax(id = 1)]
+///     name: String,
+///     tags: Vec<String>,
+///     manager: Option<Box<User>>,
+/// }
+///
+/// let TypeSchema::Struct(schema) = User::schema() else { unreachable!() };
+/// assert_eq!(schema.fields[0].id, 1);
+/// assert_eq!(schema.fields[1].ty, TypeSchema::List(Box::new(TypeSchema::String)));
+///
+/// // Fails if a field changed to an incompatible type
+/// SchemaResolver::new(&User::schema(), &User::schema()).unwrap();
+/// ```
+pub trait HasSchema {
+    /// The wire shape of values of this type.
+    fn schema() -> TypeSchema;
+}
+
+thread_local! {
+    static DESCRIBING: RefCell<Vec<&'static str>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Runs `f` to build the schema of the type `type_name`, or returns [`TypeSchema::Any`] if the
+/// schema of that type is already being built on this thread.
+///
+/// Called by derived `HasSchema` implementations; not part of the public API.
+#[doc(hidden)]
+pub fn describe(type_name: &'static str, f: impl FnOnce() -> TypeSchema) -> TypeSchema {
+    struct Pop;
+
+    impl Drop for Pop {
+        fn drop(&mut self) {
+            DESCRIBING.with(|d| d.borrow_mut().pop());
+        }
+    }
+
+    let recursive = DESCRIBING.with(|d| {
+        let mut d = d.borrow_mut();
+        if d.contains(&type_name) {
+            return true;
+        }
+        d.push(type_name);
+        false
+    });
+    if recursive {
+        return TypeSchema::Any;
+    }
+    let _pop = Pop;
+    f()
+}
+
+macro_rules! impl_has_schema {
+    ($($schema:ident: $($t:ty),+;)*) => {
+        $($(
+            impl HasSchema for $t {
+                fn schema() -> TypeSchema {
+                    TypeSchema::$schema
+                }
+            }
+        )+)*
+    };
+}
+
+impl_has_schema! {
+    Bool: bool;
+    UInt: u8, u16, u32, u64, u128, usize, char;
+    Int: i8, i16, i32, i64, i128, isize;
+    Float: f32, f64;
+    String: String, str, Cow<'_, str>;
+    Bytes: Bytes, [u8], Cow<'_, [u8]>;
+    Any: Value, ::core::time::Duration, std::time::SystemTime;
+}
+
+#[cfg(feature = "chrono")]
+impl_has_schema! {
+    Any: chrono::DateTime<chrono::Utc>, chrono::DateTime<chrono::Local>, chrono::NaiveDate,
+        chrono::NaiveTime, chrono::NaiveDateTime;
+}
+
+#[cfg(feature = "time")]
+impl_has_schema! {
+    Any: time::OffsetDateTime, time::PrimitiveDateTime, time::Date, time::Time;
+}
+
+#[cfg(feature = "rust_decimal")]
+impl_has_schema! {
+    String: rust_decimal::Decimal;
+}
+
+#[cfg(feature = "bigdecimal")]
+impl_has_schema! {
+    String: bigdecimal::BigDecimal;
+}
+
+#[cfg(feature = "uuid")]
+impl_has_schema! {
+    Any: uuid::Uuid;
+}
+
+#[cfg(feature = "ulid")]
+impl_has_schema! {
+    Any: ulid::Ulid;
+}
+
+#[cfg(feature = "smol_str")]
+impl_has_schema! {
+    String: smol_str::SmolStr;
+}
+
+#[cfg(feature = "serde_json")]
+impl_has_schema! {
+    Any: serde_json::Value;
+}
+
+impl<T: HasSchema> HasSchema for Option<T> {
+    fn schema() -> TypeSchema {
+        TypeSchema::Option(Box::new(T::schema()))
+    }
+}
+
+macro_rules! impl_has_schema_list {
+    ($($t:ty),+ $(,)?) => {
+        $(
+            impl<T: HasSchema> HasSchema for $t {
+                fn schema() -> TypeSchema {
+                    TypeSchema::List(Box::new(T::schema()))
+                }
+            }
+        )+
+    };
+}
+
+impl_has_schema_list!(Vec<T>, HashSet<T>, BTreeSet<T>);
+
+impl<T: HasSchema, const N: usize> HasSchema for [T; N] {
+    fn schema() -> TypeSchema {
+        TypeSchema::List(Box::new(T::schema()))
+    }
+}
+
+macro_rules! impl_has_schema_map {
+    ($($t:ty),+ $(,)?) => {
+        $(
+            impl<K: HasSchema, V: HasSchema> HasSchema for $t {
+                fn schema() -> TypeSchema {
+                    TypeSchema::Map(Box::new(K::schema()), Box::new(V::schema()))
+                }
+            }
+        )+
+    };
+}
+
+impl_has_schema_map!(HashMap<K, V>, BTreeMap<K, V>);
+
+#[cfg(feature = "indexmap")]
+impl_has_schema_list!(indexmap::IndexSet<T>);
+#[cfg(feature = "indexmap")]
+impl_has_schema_map!(indexmap::IndexMap<K, V>);
+#[cfg(feature = "fxhash")]
+impl_has_schema_list!(fxhash::FxHashSet<T>);
+#[cfg(feature = "fxhash")]
+impl_has_schema_map!(fxhash::FxHashMap<K, V>);
+#[cfg(feature = "ahash")]
+impl_has_schema_list!(ahash::AHashSet<T>);
+#[cfg(feature = "ahash")]
+impl_has_schema_map!(ahash::AHashMap<K, V>);
+
+#[cfg(feature = "heapless")]
+impl<T: HasSchema, const N: usize> HasSchema for heapless::Vec<T, N> {
+    fn schema() -> TypeSchema {
+        TypeSchema::List(Box::new(T::schema()))
+    }
+}
+
+#[cfg(feature = "heapless")]
+impl<const N: usize> HasSchema for heapless::String<N> {
+    fn schema() -> TypeSchema {
+        TypeSchema::String
+    }
+}
+
+#[cfg(feature = "smallvec")]
+impl<T: HasSchema, const N: usize> HasSchema for smallvec::SmallVec<[T; N]> {
+    fn schema() -> TypeSchema {
+        TypeSchema::List(Box::new(T::schema()))
+    }
+}
+
+#[cfg(feature = "arrayvec")]
+impl<T: HasSchema, const N: usize> HasSchema for arrayvec::ArrayVec<T, N> {
+    fn schema() -> TypeSchema {
+        TypeSchema::List(Box::new(T::schema()))
+    }
+}
+
+#[cfg(feature = "arrayvec")]
+impl<const N: usize> HasSchema for arrayvec::ArrayString<N> {
+    fn schema() -> TypeSchema {
+        TypeSchema::String
+    }
+}
+
+impl<T: HasSchema + ?Sized> HasSchema for Box<T> {
+    fn schema() -> TypeSchema {
+        T::schema()
+    }
+}
+
+impl<T: HasSchema + ?Sized> HasSchema for Arc<T> {
+    fn schema() -> TypeSchema {
+        T::schema()
+    }
+}
+
+impl<T: HasSchema + ?Sized> HasSchema for Rc<T> {
+    fn schema() -> TypeSchema {
+        T::schema()
+    }
+}
+
+impl<T: HasSchema + ?Sized> HasSchema for &T {
+    fn schema() -> TypeSchema {
+        T::schema()
+    }
+}
+
+macro_rules! impl_has_schema_tuple {
+    ($($T:ident),*) => {
+        impl<$($T: HasSchema),*> HasSchema for ($($T,)*) {
+            fn schema() -> TypeSchema {
+                TypeSchema::Tuple(vec![$($T::schema()),*])
+            }
+        }
+    };
+}
+
+impl_has_schema_tuple!();
+impl_has_schema_tuple!(T0);
+impl_has_schema_tuple!(T0, T1);
+impl_has_schema_tuple!(T0, T1, T2);
+impl_has_schema_tuple!(T0, T1, T2, T3);
+impl_has_schema_tuple!(T0, T1, T2, T3, T4);
+impl_has_schema_tuple!(T0, T1, T2, T3, T4, T5);
+impl_has_schema_tuple!(T0, T1, T2, T3, T4, T5, T6);
+impl_has_schema_tuple!(T0, T1, T2, T3, T4, T5, T6, T7);
+impl_has_schema_tuple!(T0, T1, T2, T3, T4, T5, T6, T7, T8);
+
+/// How to rewrite a value written with the writer schema.
+#[derive(Debug, Clone)]
+enum Plan {
+    Keep,
+    Struct(StructPlan),
+    List(Box<Plan>),
+    Map(Box<Plan>),
+    Option(Box<Plan>),
+}
+
+#[derive(Debug, Clone)]
+struct StructPlan {
+    /// Writer field ID to reader field ID and the plan for its value.
+    fields: HashMap<u64, (u64, Plan)>,
+    /// Reader fields absent from the writer schema, with their default values.
+    defaults: Vec<(u64, Value)>,
+}
+
+/// Decodes payloads written with one schema as a type described by another.
+///
+/// # Example
+/// ```rust
+/// use senax_encoder::schema::{FieldSchema, SchemaResolver, StructSchema, TypeSchema};
+/// use senax_encoder::{encode, Decode, Encode};
+///
+/// #[derive(Encode)]
+/// struct UserV1 { user_name: String, age: u32, legacy_flag: bool }
+///
+/// #[derive(Decode, Debug, PartialEq)]
+/// struct UserV2 { name: String, age: u32, email: String }
+///
+/// let writer = TypeSchema::Struct(StructSchema::new("User", vec![
+///     FieldSchema::new("user_name", TypeSchema::String),
+///     FieldSchema::new("age", TypeSchema::UInt),
+///     FieldSchema::new("legacy_flag", TypeSchema::Bool),
+/// ]));
+/// let reader = TypeSchema::Struct(StructSchema::new("User", vec![
+///     FieldSchema::new("name", TypeSchema::String).with_aliases(["user_name"]),
+///     FieldSchema::new("age", TypeSchema::UInt),
+///     FieldSchema::new("email", TypeSchema::String),
+/// ]));
+///
+/// let resolver = SchemaResolver::new(&writer, &reader).unwrap();
+/// let old = encode(&UserV1 { user_name: "alice".into(), age: 30, legacy_flag: true }).unwrap();
+/// let user: UserV2 = resolver.decode(&mut old.clone()).unwrap();
+/// assert_eq!(user, UserV2 { name: "alice".into(), age: 30, email: String::new() });
+/// ```
+#[derive(Debug, Clone)]
+pub struct SchemaResolver {
+    plan: Plan,
+}
+
+impl SchemaResolver {
+    /// Builds a resolver from the schema the data was written with to the schema of the type
+    /// it is read as.
+    ///
+    /// Fails if a matched field changed to an incompatible type.
+    pub fn new(writer: &TypeSchema, reader: &TypeSchema) -> Result<Self> {
+        Ok(Self {
+            plan: plan(writer, reader, "value")?,
+        })
+    }
+
+    /// Decodes an encoded payload (with magic number) written with the writer schema as `T`.
+    pub fn decode<T: Decoder>(&self, reader: &mut Bytes) -> Result<T> {
+        let value: Value = decode(reader)?;
+        let value = self.resolve(value);
+        let mut writer = BytesMut::new();
+        write_value(&value, &mut writer)?;
+        T::decode(&mut writer.freeze())
+    }
+
+    /// Rewrites a value written with the writer schema into the shape of the reader schema.
+    pub fn resolve(&self, value: Value) -> Value {
+        apply(&self.plan, value)
+    }
+}
+
+fn plan(writer: &TypeSchema, reader: &TypeSchema, path: &str) -> Result<Plan> {
+    use TypeSchema::*;
+    let plan = match (writer, reader) {
+        (Any, _) | (_, Any) => Plan::Keep,
+        (writer, reader) if widens(writer, reader) => Plan::Keep,
+        (Option(w), Option(r)) => wrap(plan(w, r, path)?, Plan::Option),
+        (List(w), List(r)) => wrap(plan(w, r, path)?, Plan::List),
+        (Map(wk, wv), Map(rk, rv)) => {
+            if !matches!(plan(wk, rk, path)?, Plan::Keep) {
+                return Err(incompatible(path, writer, reader));
+            }
+            wrap(plan(wv, rv, path)?, Plan::Map)
+        }
+        (Struct(w), Struct(r)) => Plan::Struct(plan_struct(w, r, path)?),
+        (Tuple(w), Tuple(r)) => plan_positional(w, r, path, writer, reader)?,
+        (TupleStruct(w), TupleStruct(r)) => {
+            plan_positional(&w.fields, &r.fields, path, writer, reader)?
+        }
+        // Variants are matched by ID when decoding
+        (Enum(_), Enum(_)) => Plan::Keep,
+        _ => return Err(incompatible(path, writer, reader)),
+    };
+    Ok(plan)
+}
+
+/// Whether a scalar written as `writer` can be read as `reader`.
+fn widens(writer: &TypeSchema, reader: &TypeSchema) -> bool {
+    use TypeSchema::*;
+    matches!(
+        (writer, reader),
+        (Bool, Bool)
+            | (UInt, UInt | Int | Float)
+            | (Int, Int | Float)
+            | (Float, Float)
+            | (String, String)
+            | (Bytes, Bytes)
+    )
+}
+
+/// Tuples are kept as written; their elements must not need rewriting.
+fn plan_positional(
+    writer_fields: &[TypeSchema],
+    reader_fields: &[TypeSchema],
+    path: &str,
+    writer: &TypeSchema,
+    reader: &TypeSchema,
+) -> Result<Plan> {
+    if writer_fields.len() != reader_fields.len() {
+        return Err(incompatible(path, writer, reader));
+    }
+    for (i, (w, r)) in writer_fields.iter().zip(reader_fields).enumerate() {
+        if !matches!(plan(w, r, &format!("{}.{}", path, i))?, Plan::Keep) {
+            return Err(incompatible(path, writer, reader));
+        }
+    }
+    Ok(Plan::Keep)
+}
+
+fn wrap(inner: Plan, f: impl FnOnce(Box<Plan>) -> Plan) -> Plan {
+    match inner {
+        Plan::Keep => Plan::Keep,
+        inner => f(Box::new(inner)),
+    }
+}
+
+fn plan_struct(writer: &StructSchema, reader: &StructSchema, path: &str) -> Result<StructPlan> {
+    let mut fields = HashMap::new();
+    let mut defaults = Vec::new();
+    for field in &reader.fields {
+        let source = writer
+            .fields
+            .iter()
+            .find(|w| w.name == field.name)
+            .or_else(|| {
+                writer
+                    .fields
+                    .iter()
+                    .find(|w| field.aliases.contains(&w.name))
+            })
+            .or_else(|| writer.fields.iter().find(|w| w.id == field.id));
+        let field_path = format!("{}.{}", path, field.name);
+        match source {
+            Some(source) => {
+                // Present Option fields are written without the Some tag
+                let plan = plan(
+                    unwrap_option(&source.ty),
+                    unwrap_option(&field.ty),
+                    &field_path,
+                )?;
+                fields.insert(source.id, (field.id, plan));
+            }
+            None => {
+                if let Some(value) = default_value(&field.ty) {
+                    defaults.push((field.id, value));
+                }
+            }
+        }
+    }
+    Ok(StructPlan { fields, defaults })
+}
+
+fn unwrap_option(ty: &TypeSchema) -> &TypeSchema {
+    match ty {
+        TypeSchema::Option(inner) => inner,
+        ty => ty,
+    }
+}
+
+/// Returns the value a derived `Decode` would read as the type's default, or `None` for
+/// `Option` fields (left out) and types without a known default.
+fn default_value(ty: &TypeSchema) -> Option<Value> {
+    let value = match ty {
+        TypeSchema::Bool | TypeSchema::UInt | TypeSchema::Int => Value::UInt(0),
+        TypeSchema::Float => Value::String(format!("{:e}", 0.0f64)),
+        TypeSchema::String => Value::String(String::new()),
+        TypeSchema::Bytes => Value::Binary(Bytes::new()),
+        TypeSchema::List(_) => Value::List(Vec::new()),
+        TypeSchema::Map(_, _) => Value::Map(Vec::new()),
+        TypeSchema::Struct(schema) => Value::Struct(
+            schema
+                .fields
+                .iter()
+                .filter_map(|field| {
+                    default_value(&field.ty).map(|value| Field {
+                        id: field.id,
+                        name: None,
+                        value,
+                    })
+                })
+                .collect(),
+        ),
+        TypeSchema::Option(_)
+        | TypeSchema::Any
+        | TypeSchema::Tuple(_)
+        | TypeSchema::TupleStruct(_)
+        | TypeSchema::Enum(_) => return None,
+    };
+    Some(value)
+}
+
+fn incompatible(path: &str, writer: &TypeSchema, reader: &TypeSchema) -> EncoderError {
+    EncoderError::Decode(format!(
+        "Schema mismatch at {}: written as {}, cannot be read as {}",
+        path,
+        kind(writer),
+        kind(reader)
+    ))
+}
+
+fn kind(ty: &TypeSchema) -> &'static str {
+    match ty {
+        TypeSchema::Bool => "bool",
+        TypeSchema::UInt => "unsigned integer",
+        TypeSchema::Int => "signed integer",
+        TypeSchema::Float => "float",
+        TypeSchema::String => "string",
+        TypeSchema::Bytes => "bytes",
+        TypeSchema::Option(_) => "option",
+        TypeSchema::List(_) => "list",
+        TypeSchema::Map(_, _) => "map",
+        TypeSchema::Struct(_) => "struct",
+        TypeSchema::Any => "any",
+        TypeSchema::Tuple(_) => "tuple",
+        TypeSchema::TupleStruct(_) => "tuple struct",
+        TypeSchema::Enum(_) => "enum",
+    }
+}
+
+fn apply(plan: &Plan, value: Value) -> Value {
+    match (plan, value) {
+        (Plan::Keep, value) => value,
+        (Plan::Option(inner), Value::Some(value)) => Value::Some(Box::new(apply(inner, *value))),
+        (Plan::List(inner), Value::List(values)) => {
+            Value::List(values.into_iter().map(|v| apply(inner, v)).collect())
+        }
+        (Plan::Map(inner), Value::Map(entries)) => Value::Map(
+            entries
+                .into_iter()
+                .map(|(k, v)| (k, apply(inner, v)))
+                .collect(),
+        ),
+        (Plan::Struct(plan), Value::Struct(fields)) => {
+            let mut resolved: Vec<Field> = fields
+                .into_iter()
+                .filter_map(|field| {
+                    let (id, plan) = plan.fields.get(&field.id)?;
+                    Some(Field {
+                        id: *id,
+                        name: field.name,
+                        value: apply(plan, field.value),
+                    })
+                })
+                .collect();
+            for (id, default) in &plan.defaults {
+                resolved.push(Field {
+                    id: *id,
+                    name: None,
+                    value: default.clone(),
+                });
+            }
+            Value::Struct(resolved)
+        }
+        // Values that do not match the writer schema are left for the decoder to reject
+        (_, value) => value,
+    }
+}
@@ -0,0 +1,1071 @@
+//! Decoder stubs for other languages, generated from a [`TypeSchema`].
+//!
+//! [`typescript`] and [`golang`] emit a self-contained source file with a small reader for the
+//! dynamic (`encode`) format, a type per struct in the schema and a decode function for each of
+//! them. Unknown fields are skipped and missing ones keep their default, as the Rust decoder
+//! does. The output is a starting point: integers are read as `bigint`/`uint64`/`int64`,
+//! tuples, enums and types described as [`TypeSchema::Any`] are skipped, and the Go file
+//! declares `package senax`.
+//!
+//! [`markdown`] documents the wire format instead: a table per struct listing the field IDs,
+//! types and whether each field is required, and a table per enum listing the variant IDs.
+//! Field doc comments captured by `#[senax(schema)]` are carried into all three outputs.
+//!
+//! # Example
+//! ```rust
+//! use senax_encoder::schema::codegen;
+//! use senax_encoder::schema::{FieldSchema, StructSchema, TypeSchema};
+//!
+//! let schema = TypeSchema::Struct(StructSchema::new("User", vec![
+//!     FieldSchema::new("name", TypeSchema::String),
+//!     FieldSchema::new("age", TypeSchema::UInt),
+//! ]));
+//! assert!(codegen::typescript(&schema).contains("export interface User {"));
+//! assert!(codegen::golang(&schema).contains("type User struct {"));
+//! assert!(codegen::markdown(&schema).contains("| `age` | "));
+//! ```
+
+use super::{EnumSchema, StructSchema, TypeSchema};
+use std::fmt::Write;
+
+/// Generates a TypeScript module decoding values of `schema`.
+///
+/// The module exports `SenaxReader`, an interface and a `read<Name>` function per struct, and
+/// `decode<Name>(bytes)` (or `decodeValue` for non-struct schemas) for the root type.
+pub fn typescript(schema: &TypeSchema) -> String {
+    let mut out = String::from(TS_RUNTIME);
+    for s in structs(schema) {
+        let name = type_name(&s.name);
+        let _ = writeln!(out, "\nexport interface {} {{", name);
+        for field in &s.fields {
+            let (ty, optional) = match &field.ty {
+                TypeSchema::Option(inner) => (ts_type(inner), true),
+                TypeSchema::Any
+                | TypeSchema::Tuple(_)
+                | TypeSchema::TupleStruct(_)
+                | TypeSchema::Enum(_) => (ts_type(&field.ty), true),
+                ty => (ts_type(ty), false),
+            };
+            let marker = if optional { "?" } else { "" };
+            if let Some(doc) = &field.doc {
+                let doc = doc.replace("*/", "*\\/");
+                let _ = writeln!(out, "  /**{} */", comment_lines(&doc, "\n   *"));
+            }
+            let _ = writeln!(out, "  {}{}: {};", ts_key(&field.name), marker, ty);
+        }
+        out.push_str("}\n");
+
+        let _ = writeln!(out, "\nexport function default{0}(): {0} {{", name);
+        out.push_str("  return {\n");
+        for field in &s.fields {
+            if let Some(default) = ts_default(&field.ty) {
+                let _ = writeln!(out, "    {}: {},", ts_key(&field.name), default);
+            }
+        }
+        out.push_str("  };\n}\n");
+
+        let _ = writeln!(
+            out,
+            "\nexport function read{0}(r: SenaxReader): {0} {{",
+            name
+        );
+        let _ = writeln!(out, "  const out = default{}();", name);
+        out.push_str("  r.expectTag(TAG_STRUCT_NAMED);\n");
+        out.push_str("  for (let id = r.readFieldId(); id !== 0n; id = r.readFieldId()) {\n");
+        out.push_str("    switch (id) {\n");
+        for field in &s.fields {
+            // Present Option fields are written without the Some tag
+            let ty = match &field.ty {
+                TypeSchema::Option(inner) => inner,
+                ty => ty,
+            };
+            let _ = writeln!(
+                out,
+                "      case {}n: out{} = {}; break;",
+                field.id,
+                ts_access(&field.name),
+                ts_read(ty)
+            );
+        }
+        out.push_str("      default: r.skip();\n    }\n  }\n  return out;\n}\n");
+    }
+
+    let (name, ty) = match schema {
+        TypeSchema::Struct(s) => (type_name(&s.name), type_name(&s.name)),
+        ty => ("Value".to_string(), ts_type(ty)),
+    };
+    let _ = writeln!(
+        out,
+        "\nexport function decode{}(bytes: Uint8Array): {} {{",
+        name, ty
+    );
+    out.push_str("  const r = new SenaxReader(bytes);\n  r.readMagic();\n");
+    let _ = writeln!(out, "  return {};\n}}", ts_read(schema));
+    out
+}
+
+/// Generates a Go file (`package senax`) decoding values of `schema`.
+///
+/// The file declares `Reader`, a struct type and a `read<Name>` function per struct, and
+/// `Decode<Name>(buf)` (or `DecodeValue` for non-struct schemas) for the root type. It needs
+/// Go 1.18 or later.
+pub fn golang(schema: &TypeSchema) -> String {
+    let mut out = String::from(GO_RUNTIME);
+    for s in structs(schema) {
+        let name = type_name(&s.name);
+        let _ = writeln!(out, "\ntype {} struct {{", name);
+        for field in &s.fields {
+            if let Some(doc) = &field.doc {
+                let _ = writeln!(out, "\t//{}", comment_lines(doc, "\n\t//"));
+            }
+            let _ = writeln!(out, "\t{} {}", go_field(&field.name), go_type(&field.ty));
+        }
+        out.push_str("}\n");
+
+        let _ = writeln!(out, "\nfunc read{0}(r *Reader) {0} {{", name);
+        let _ = writeln!(out, "\tvar out {}", name);
+        out.push_str("\tr.expectTag(tagStructNamed)\n");
+        out.push_str("\tfor id := r.ReadFieldID(); id != 0; id = r.ReadFieldID() {\n");
+        out.push_str("\t\tswitch id {\n");
+        for field in &s.fields {
+            let value = match &field.ty {
+                TypeSchema::Option(inner) => format!("ptr({})", go_read(inner)),
+                ty => go_read(ty),
+            };
+            let _ = writeln!(
+                out,
+                "\t\tcase {}:\n\t\t\tout.{} = {}",
+                field.id,
+                go_field(&field.name),
+                value
+            );
+        }
+        out.push_str("\t\tdefault:\n\t\t\tr.Skip()\n\t\t}\n\t}\n\treturn out\n}\n");
+    }
+
+    let (name, ty) = match schema {
+        TypeSchema::Struct(s) => (type_name(&s.name), type_name(&s.name)),
+        ty => ("Value".to_string(), go_type(ty)),
+    };
+    let _ = writeln!(
+        out,
+        "\n// Decode{0} decodes an encoded payload (with magic number).\nfunc Decode{0}(buf []byte) ({1}, error) {{",
+        name, ty
+    );
+    out.push_str("\tr := NewReader(buf)\n\tr.ReadMagic()\n");
+    let _ = writeln!(out, "\tv := {}\n\treturn v, r.Err()\n}}", go_read(schema));
+    out
+}
+
+/// Generates Markdown documenting the wire format of `schema`.
+///
+/// Every struct and enum reachable from `schema` gets a section: a table of the fields with
+/// their IDs, types, whether payloads must contain them and their doc comments, or a table of
+/// the variants with their IDs and fields.
+pub fn markdown(schema: &TypeSchema) -> String {
+    let mut out = String::new();
+    for item in documented(schema) {
+        match item {
+            Documented::Struct(s) => {
+                let _ = writeln!(out, "## {}\n", s.name);
+                md_fields(&mut out, s);
+            }
+            Documented::Enum(e) => {
+                let _ = writeln!(out, "## {}\n", e.name);
+                if e.string_repr {
+                    out.push_str("Unit variants are written as their name.\n\n");
+                }
+                out.push_str("| Variant | ID | Fields |\n|---|---|---|\n");
+                for variant in &e.variants {
+                    let fields = variant.fields.as_ref().map(md_type).unwrap_or_default();
+                    let _ = writeln!(out, "| `{}` | {} | {} |", variant.name, variant.id, fields);
+                }
+                out.push('\n');
+            }
+        }
+    }
+    out
+}
+
+fn md_fields(out: &mut String, s: &StructSchema) {
+    out.push_str("| Field | ID | Type | Required | Description |\n|---|---|---|---|---|\n");
+    for field in &s.fields {
+        let doc = field
+            .doc
+            .as_deref()
+            .unwrap_or("")
+            .replace('|', "\\|")
+            .replace('\n', "<br>");
+        let required = if field.is_required() { "yes" } else { "no" };
+        let _ = writeln!(
+            out,
+            "| `{}` | {} | {} | {} | {} |",
+            field.name,
+            field.id,
+            md_type(&field.ty),
+            required,
+            doc
+        );
+    }
+    out.push('\n');
+}
+
+fn md_type(ty: &TypeSchema) -> String {
+    match ty {
+        TypeSchema::Bool => "bool".to_string(),
+        TypeSchema::UInt => "uint".to_string(),
+        TypeSchema::Int => "int".to_string(),
+        TypeSchema::Float => "float".to_string(),
+        TypeSchema::String => "string".to_string(),
+        TypeSchema::Bytes => "bytes".to_string(),
+        TypeSchema::Option(inner) => format!("option&lt;{}&gt;", md_type(inner)),
+        TypeSchema::List(inner) => format!("list&lt;{}&gt;", md_type(inner)),
+        TypeSchema::Map(key, value) => {
+            format!("map&lt;{}, {}&gt;", md_type(key), md_type(value))
+        }
+        TypeSchema::Struct(s) => format!("[{}](#{})", s.name, md_anchor(&s.name)),
+        TypeSchema::Enum(e) => format!("[{}](#{})", e.name, md_anchor(&e.name)),
+        TypeSchema::Any => "any".to_string(),
+        TypeSchema::Tuple(types) => format!(
+            "({})",
+            types.iter().map(md_type).collect::<Vec<_>>().join(", ")
+        ),
+        TypeSchema::TupleStruct(s) => format!(
+            "{}({})",
+            s.name,
+            s.fields.iter().map(md_type).collect::<Vec<_>>().join(", ")
+        ),
+    }
+}
+
+/// The anchor GitHub generates for a heading.
+fn md_anchor(heading: &str) -> String {
+    heading
+        .chars()
+        .filter(|c| c.is_alphanumeric() || *c == '-' || *c == '_' || *c == ' ')
+        .map(|c| {
+            if c == ' ' {
+                '-'
+            } else {
+                c.to_ascii_lowercase()
+            }
+        })
+        .collect()
+}
+
+enum Documented<'a> {
+    Struct(&'a StructSchema),
+    Enum(&'a EnumSchema),
+}
+
+/// The struct and enum schemas reachable from `schema`, first occurrence of each name only.
+fn documented(schema: &TypeSchema) -> Vec<Documented<'_>> {
+    fn walk<'a>(ty: &'a TypeSchema, out: &mut Vec<Documented<'a>>) {
+        let name = match ty {
+            TypeSchema::Struct(s) => &s.name,
+            TypeSchema::Enum(e) => &e.name,
+            _ => "",
+        };
+        let seen = out.iter().any(|item| match item {
+            Documented::Struct(s) => s.name == name,
+            Documented::Enum(e) => e.name == name,
+        });
+        match ty {
+            TypeSchema::Option(inner) | TypeSchema::List(inner) => walk(inner, out),
+            TypeSchema::Map(key, value) => {
+                walk(key, out);
+                walk(value, out);
+            }
+            TypeSchema::Tuple(types) => types.iter().for_each(|ty| walk(ty, out)),
+            TypeSchema::TupleStruct(s) => s.fields.iter().for_each(|ty| walk(ty, out)),
+            TypeSchema::Struct(s) if !seen => {
+                out.push(Documented::Struct(s));
+                for field in &s.fields {
+                    walk(&field.ty, out);
+                }
+            }
+            TypeSchema::Enum(e) if !seen => {
+                out.push(Documented::Enum(e));
+                for variant in &e.variants {
+                    match &variant.fields {
+                        // Struct-like variants are documented as structs named after the variant
+                        Some(TypeSchema::Struct(s)) => {
+                            out.push(Documented::Struct(s));
+                            for field in &s.fields {
+                                walk(&field.ty, out);
+                            }
+                        }
+                        Some(ty) => walk(ty, out),
+                        None => {}
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    let mut out = Vec::new();
+    walk(schema, &mut out);
+    out
+}
+
+/// The lines of `doc`, each prefixed with a space unless empty, joined with `separator`.
+fn comment_lines(doc: &str, separator: &str) -> String {
+    let lines: Vec<_> = doc
+        .lines()
+        .map(|line| {
+            if line.is_empty() {
+                String::new()
+            } else {
+                format!(" {}", line)
+            }
+        })
+        .collect();
+    lines.join(separator)
+}
+
+/// The struct schemas reachable from `schema`, first occurrence of each name only.
+fn structs(schema: &TypeSchema) -> Vec<&StructSchema> {
+    fn walk<'a>(ty: &'a TypeSchema, out: &mut Vec<&'a StructSchema>) {
+        match ty {
+            TypeSchema::Option(inner) | TypeSchema::List(inner) => walk(inner, out),
+            TypeSchema::Map(key, value) => {
+                walk(key, out);
+                walk(value, out);
+            }
+            TypeSchema::Struct(s) => {
+                if out.iter().any(|seen| seen.name == s.name) {
+                    return;
+                }
+                out.push(s);
+                for field in &s.fields {
+                    walk(&field.ty, out);
+                }
+            }
+            _ => {}
+        }
+    }
+    let mut out = Vec::new();
+    walk(schema, &mut out);
+    out
+}
+
+fn type_name(name: &str) -> String {
+    let name: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    if name.starts_with(|c: char| c.is_ascii_digit()) || name.is_empty() {
+        format!("T{}", name)
+    } else {
+        name
+    }
+}
+
+fn is_identifier(name: &str) -> bool {
+    !name.is_empty()
+        && !name.starts_with(|c: char| c.is_ascii_digit())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$')
+}
+
+fn ts_key(name: &str) -> String {
+    if is_identifier(name) {
+        name.to_string()
+    } else {
+        format!("{:?}", name)
+    }
+}
+
+fn ts_access(name: &str) -> String {
+    if is_identifier(name) {
+        format!(".{}", name)
+    } else {
+        format!("[{:?}]", name)
+    }
+}
+
+fn ts_type(ty: &TypeSchema) -> String {
+    match ty {
+        TypeSchema::Bool => "boolean".to_string(),
+        TypeSchema::UInt | TypeSchema::Int => "bigint".to_string(),
+        TypeSchema::Float => "number".to_string(),
+        TypeSchema::String => "string".to_string(),
+        TypeSchema::Bytes => "Uint8Array".to_string(),
+        TypeSchema::Option(inner) => format!("{} | undefined", ts_type(inner)),
+        TypeSchema::List(inner) => format!("Array<{}>", ts_type(inner)),
+        TypeSchema::Map(key, value) => format!("Map<{}, {}>", ts_type(key), ts_type(value)),
+        TypeSchema::Struct(s) => type_name(&s.name),
+        TypeSchema::Any
+        | TypeSchema::Tuple(_)
+        | TypeSchema::TupleStruct(_)
+        | TypeSchema::Enum(_) => "unknown".to_string(),
+    }
+}
+
+fn ts_default(ty: &TypeSchema) -> Option<String> {
+    let default = match ty {
+        TypeSchema::Bool => "false".to_string(),
+        TypeSchema::UInt | TypeSchema::Int => "0n".to_string(),
+        TypeSchema::Float => "0".to_string(),
+        TypeSchema::String => "\"\"".to_string(),
+        TypeSchema::Bytes => "new Uint8Array()".to_string(),
+        TypeSchema::List(_) => "[]".to_string(),
+        TypeSchema::Map(_, _) => "new Map()".to_string(),
+        TypeSchema::Struct(s) => format!("default{}()", type_name(&s.name)),
+        TypeSchema::Option(_)
+        | TypeSchema::Any
+        | TypeSchema::Tuple(_)
+        | TypeSchema::TupleStruct(_)
+        | TypeSchema::Enum(_) => return None,
+    };
+    Some(default)
+}
+
+fn ts_read(ty: &TypeSchema) -> String {
+    match ty {
+        TypeSchema::Bool => "r.readBool()".to_string(),
+        TypeSchema::UInt => "r.readUInt()".to_string(),
+        TypeSchema::Int => "r.readInt()".to_string(),
+        TypeSchema::Float => "r.readFloat()".to_string(),
+        TypeSchema::String => "r.readString()".to_string(),
+        TypeSchema::Bytes => "r.readBytes()".to_string(),
+        TypeSchema::Option(inner) => format!("r.readOption(() => {})", ts_read(inner)),
+        TypeSchema::List(inner) => format!("r.readList(() => {})", ts_read(inner)),
+        TypeSchema::Map(key, value) => {
+            format!(
+                "r.readMap(() => {}, () => {})",
+                ts_read(key),
+                ts_read(value)
+            )
+        }
+        TypeSchema::Struct(s) => format!("read{}(r)", type_name(&s.name)),
+        TypeSchema::Any
+        | TypeSchema::Tuple(_)
+        | TypeSchema::TupleStruct(_)
+        | TypeSchema::Enum(_) => "r.readAny()".to_string(),
+    }
+}
+
+fn go_field(name: &str) -> String {
+    let mut out = String::new();
+    for part in name.split(|c: char| !c.is_ascii_alphanumeric()) {
+        let mut chars = part.chars();
+        if let Some(first) = chars.next() {
+            out.push(first.to_ascii_uppercase());
+            out.extend(chars);
+        }
+    }
+    if out.is_empty() || out.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("F{}", out)
+    } else {
+        out
+    }
+}
+
+fn go_type(ty: &TypeSchema) -> String {
+    match ty {
+        TypeSchema::Bool => "bool".to_string(),
+        TypeSchema::UInt => "uint64".to_string(),
+        TypeSchema::Int => "int64".to_string(),
+        TypeSchema::Float => "float64".to_string(),
+        TypeSchema::String => "string".to_string(),
+        TypeSchema::Bytes => "[]byte".to_string(),
+        TypeSchema::Option(inner) => format!("*{}", go_type(inner)),
+        TypeSchema::List(inner) => format!("[]{}", go_type(inner)),
+        TypeSchema::Map(key, value) => format!("map[{}]{}", go_type(key), go_type(value)),
+        TypeSchema::Struct(s) => type_name(&s.name),
+        TypeSchema::Any
+        | TypeSchema::Tuple(_)
+        | TypeSchema::TupleStruct(_)
+        | TypeSchema::Enum(_) => "any".to_string(),
+    }
+}
+
+fn go_read(ty: &TypeSchema) -> String {
+    match ty {
+        TypeSchema::Bool => "r.ReadBool()".to_string(),
+        TypeSchema::UInt => "r.ReadUInt()".to_string(),
+        TypeSchema::Int => "r.ReadInt()".to_string(),
+        TypeSchema::Float => "r.ReadFloat()".to_string(),
+        TypeSchema::String => "r.ReadString()".to_string(),
+        TypeSchema::Bytes => "r.ReadBytes()".to_string(),
+        TypeSchema::Option(inner) => format!(
+            "readOption(r, func() {} {{ return {} }})",
+            go_type(inner),
+            go_read(inner)
+        ),
+        TypeSchema::List(inner) => format!(
+            "readList(r, func() {} {{ return {} }})",
+            go_type(inner),
+            go_read(inner)
+        ),
+        TypeSchema::Map(key, value) => format!(
+            "readMap(r, func() {} {{ return {} }}, func() {} {{ return {} }})",
+            go_type(key),
+            go_read(key),
+            go_type(value),
+            go_read(value)
+        ),
+        TypeSchema::Struct(s) => format!("read{}(r)", type_name(&s.name)),
+        TypeSchema::Any
+        | TypeSchema::Tuple(_)
+        | TypeSchema::TupleStruct(_)
+        | TypeSchema::Enum(_) => "r.ReadAny()".to_string(),
+    }
+}
+
+const TS_RUNTIME: &str = r#"// Generated by senax-encoder from a type schema. Decoder stubs for the dynamic format.
+
+const TAG_NONE = 128;
+const TAG_SOME = 129;
+const TAG_NEGATIVE = 136;
+const TAG_STRING_BASE = 139;
+const TAG_STRING_LONG = 180;
+const TAG_BINARY = 181;
+const TAG_STRUCT_NAMED = 183;
+const TAG_LIST_BASE = 188;
+const TAG_LIST_LONG = 194;
+const TAG_MAP = 196;
+const TAG_MAP_STRING_KEYS = 209;
+
+export class SenaxReader {
+  private pos = 0;
+  private readonly view: DataView;
+  private readonly utf8 = new TextDecoder("utf-8", { fatal: true });
+
+  constructor(private readonly buf: Uint8Array) {
+    this.view = new DataView(buf.buffer, buf.byteOffset, buf.byteLength);
+  }
+
+  private take(n: number): number {
+    if (n < 0 || this.pos + n > this.buf.length) throw new Error("senax: insufficient data");
+    const at = this.pos;
+    this.pos += n;
+    return at;
+  }
+
+  readTag(): number {
+    return this.buf[this.take(1)];
+  }
+
+  expectTag(tag: number): void {
+    const got = this.readTag();
+    if (got !== tag) throw new Error(`senax: expected tag ${tag}, got ${got}`);
+  }
+
+  readMagic(): void {
+    if (this.view.getUint16(this.take(2), true) !== 0xa55a) throw new Error("senax: bad magic number");
+  }
+
+  private uintFromTag(tag: number): bigint {
+    if (tag <= 127) return BigInt(tag);
+    switch (tag) {
+      case 131: return BigInt(this.buf[this.take(1)] + 128);
+      case 132: return BigInt(this.view.getUint16(this.take(2), true));
+      case 133: return BigInt(this.view.getUint32(this.take(4), true));
+      case 134: return this.view.getBigUint64(this.take(8), true);
+      case 135: {
+        const at = this.take(16);
+        return (this.view.getBigUint64(at + 8, true) << 64n) | this.view.getBigUint64(at, true);
+      }
+    }
+    throw new Error(`senax: unexpected tag ${tag} for an integer`);
+  }
+
+  readUInt(): bigint {
+    return this.uintFromTag(this.readTag());
+  }
+
+  readInt(): bigint {
+    const tag = this.readTag();
+    return tag === TAG_NEGATIVE ? -this.readUInt() - 1n : this.uintFromTag(tag);
+  }
+
+  readLength(): number {
+    return Number(this.readUInt());
+  }
+
+  readBool(): boolean {
+    const tag = this.readTag();
+    if (tag > 1) throw new Error(`senax: unexpected tag ${tag} for a bool`);
+    return tag === 1;
+  }
+
+  readFloat(): number {
+    const tag = this.buf[this.pos];
+    if (tag >= TAG_STRING_BASE && tag <= TAG_STRING_LONG) return Number(this.readString());
+    if (tag === 137) return this.view.getFloat32(this.take(5) + 1, true);
+    if (tag === 138) return this.view.getFloat64(this.take(9) + 1, true);
+    return Number(this.readInt());
+  }
+
+  private readUtf8(len: number): string {
+    const at = this.take(len);
+    return this.utf8.decode(this.buf.subarray(at, at + len));
+  }
+
+  readString(): string {
+    const tag = this.readTag();
+    if (tag >= TAG_STRING_BASE && tag < TAG_STRING_LONG) return this.readUtf8(tag - TAG_STRING_BASE);
+    if (tag === TAG_STRING_LONG) return this.readUtf8(this.readLength());
+    throw new Error(`senax: unexpected tag ${tag} for a string`);
+  }
+
+  readBytes(): Uint8Array {
+    this.expectTag(TAG_BINARY);
+    const len = this.readLength();
+    const at = this.take(len);
+    return this.buf.slice(at, at + len);
+  }
+
+  readOption<T>(read: () => T): T | undefined {
+    const tag = this.readTag();
+    if (tag === TAG_NONE) return undefined;
+    if (tag === TAG_SOME) return read();
+    throw new Error(`senax: unexpected tag ${tag} for an option`);
+  }
+
+  private listLength(tag: number): number {
+    if (tag >= TAG_LIST_BASE && tag < TAG_LIST_LONG) return tag - TAG_LIST_BASE;
+    if (tag === TAG_LIST_LONG) return this.readLength();
+    throw new Error(`senax: unexpected tag ${tag} for a list`);
+  }
+
+  readList<T>(read: () => T): Array<T> {
+    const len = this.listLength(this.readTag());
+    const out: Array<T> = [];
+    for (let i = 0; i < len; i++) out.push(read());
+    return out;
+  }
+
+  readMap<K, V>(readKey: () => K, readValue: () => V): Map<K, V> {
+    const tag = this.readTag();
+    if (tag !== TAG_MAP && tag !== TAG_MAP_STRING_KEYS) {
+      throw new Error(`senax: unexpected tag ${tag} for a map`);
+    }
+    const len = this.readLength();
+    const out = new Map<K, V>();
+    for (let i = 0; i < len; i++) {
+      const key = tag === TAG_MAP ? readKey() : (this.readUtf8(this.readLength()) as unknown as K);
+      out.set(key, readValue());
+    }
+    return out;
+  }
+
+  readFieldId(): bigint {
+    const first = this.readTag();
+    return first === 255 ? this.view.getBigUint64(this.take(8), true) : BigInt(first);
+  }
+
+  readAny(): unknown {
+    this.skip();
+    return undefined;
+  }
+
+  skip(): void {
+    const tag = this.readTag();
+    if (tag <= 135 && tag !== TAG_NONE && tag !== TAG_SOME && tag !== 130) {
+      this.uintFromTag(tag);
+    } else if (tag >= TAG_STRING_BASE && tag <= TAG_STRING_LONG) {
+      this.take(tag === TAG_STRING_LONG ? this.readLength() : tag - TAG_STRING_BASE);
+    } else if (tag >= TAG_LIST_BASE && tag <= TAG_LIST_LONG) {
+      for (let n = this.listLength(tag); n > 0; n--) this.skip();
+    } else {
+      switch (tag) {
+        case TAG_NONE: case 182: case 202: break;
+        case TAG_SOME: case 203: case 205: this.skip(); break;
+        case TAG_NEGATIVE: this.readUInt(); break;
+        case 137: this.take(4); break;
+        case 138: this.take(8); break;
+        case TAG_BINARY: this.take(this.readLength()); break;
+        case TAG_STRUCT_NAMED: while (this.readFieldId() !== 0n) this.skip(); break;
+        case 184: case 195: case 206: for (let n = this.readLength(); n > 0; n--) this.skip(); break;
+        case 185: this.readFieldId(); break;
+        case 186: this.readFieldId(); while (this.readFieldId() !== 0n) this.skip(); break;
+        case 187: this.readFieldId(); for (let n = this.readLength(); n > 0; n--) this.skip(); break;
+        case TAG_MAP: for (let n = this.readLength() * 2; n > 0; n--) this.skip(); break;
+        case TAG_MAP_STRING_KEYS: case 207:
+          for (let n = this.readLength(); n > 0; n--) {
+            if (tag === 207) this.skip(); else this.take(this.readLength());
+            this.skip();
+          }
+          break;
+        case 210: {
+          const arity = this.readTag();
+          for (let n = this.readLength() * (arity + 1); n > 0; n--) this.skip();
+          break;
+        }
+        case 197: case 208: this.readInt(); this.readUInt(); break;
+        case 198: this.readInt(); break;
+        case 199: this.readUInt(); this.readUInt(); break;
+        case 200: this.readInt(); this.readUInt(); break;
+        case 201: this.take(16); break;
+        case 204: {
+          const marker = this.readTag();
+          if (marker > 3) throw new Error(`senax: invalid JSON number marker ${marker}`);
+          this.skip();
+          break;
+        }
+        default: throw new Error(`senax: cannot skip tag ${tag}`);
+      }
+    }
+  }
+}
+"#;
+
+const GO_RUNTIME: &str = r#"// Code generated by senax-encoder from a type schema. Decoder stubs for the dynamic format.
+
+package senax
+
+import (
+	"encoding/binary"
+	"fmt"
+	"math"
+	"strconv"
+	"unicode/utf8"
+)
+
+const (
+	tagNone          = 128
+	tagSome          = 129
+	tagNegative      = 136
+	tagStringBase    = 139
+	tagStringLong    = 180
+	tagBinary        = 181
+	tagStructNamed   = 183
+	tagListBase      = 188
+	tagListLong      = 194
+	tagMap           = 196
+	tagMapStringKeys = 209
+)
+
+// Reader decodes values in the senax dynamic format. The first error is kept and later reads
+// return zero values.
+type Reader struct {
+	buf []byte
+	pos int
+	err error
+}
+
+// NewReader returns a reader over buf.
+func NewReader(buf []byte) *Reader { return &Reader{buf: buf} }
+
+// Err returns the first error encountered.
+func (r *Reader) Err() error { return r.err }
+
+func (r *Reader) fail(format string, args ...any) {
+	if r.err == nil {
+		r.err = fmt.Errorf("senax: "+format, args...)
+	}
+}
+
+func (r *Reader) take(n int) []byte {
+	if r.err != nil {
+		return nil
+	}
+	if n < 0 || len(r.buf)-r.pos < n {
+		r.fail("insufficient data")
+		return nil
+	}
+	b := r.buf[r.pos : r.pos+n]
+	r.pos += n
+	return b
+}
+
+func (r *Reader) readTag() byte {
+	if b := r.take(1); b != nil {
+		return b[0]
+	}
+	return 0
+}
+
+func (r *Reader) expectTag(tag byte) {
+	if got := r.readTag(); r.err == nil && got != tag {
+		r.fail("expected tag %d, got %d", tag, got)
+	}
+}
+
+// ReadMagic checks the magic number at the start of a payload.
+func (r *Reader) ReadMagic() {
+	if b := r.take(2); b != nil && binary.LittleEndian.Uint16(b) != 0xA55A {
+		r.fail("bad magic number")
+	}
+}
+
+func (r *Reader) uintFromTag(tag byte) uint64 {
+	switch {
+	case tag <= 127:
+		return uint64(tag)
+	case tag == 131:
+		return uint64(r.readTag()) + 128
+	case tag == 132:
+		if b := r.take(2); b != nil {
+			return uint64(binary.LittleEndian.Uint16(b))
+		}
+	case tag == 133:
+		if b := r.take(4); b != nil {
+			return uint64(binary.LittleEndian.Uint32(b))
+		}
+	case tag == 134:
+		if b := r.take(8); b != nil {
+			return binary.LittleEndian.Uint64(b)
+		}
+	case tag == 135:
+		if b := r.take(16); b != nil {
+			if binary.LittleEndian.Uint64(b[8:]) != 0 {
+				r.fail("integer overflows 64 bits")
+			}
+			return binary.LittleEndian.Uint64(b)
+		}
+	default:
+		r.fail("unexpected tag %d for an integer", tag)
+	}
+	return 0
+}
+
+// ReadUInt reads an unsigned integer.
+func (r *Reader) ReadUInt() uint64 { return r.uintFromTag(r.readTag()) }
+
+// ReadInt reads a signed integer.
+func (r *Reader) ReadInt() int64 {
+	tag := r.readTag()
+	if tag == tagNegative {
+		m := r.ReadUInt()
+		if m > math.MaxInt64 {
+			r.fail("integer overflows 64 bits")
+		}
+		return -int64(m) - 1
+	}
+	v := r.uintFromTag(tag)
+	if v > math.MaxInt64 {
+		r.fail("integer overflows 64 bits")
+	}
+	return int64(v)
+}
+
+func (r *Reader) readLength() int {
+	n := r.ReadUInt()
+	if n > uint64(len(r.buf)) {
+		r.fail("length %d exceeds the payload", n)
+		return 0
+	}
+	return int(n)
+}
+
+// ReadBool reads a bool.
+func (r *Reader) ReadBool() bool {
+	tag := r.readTag()
+	if tag > 1 {
+		r.fail("unexpected tag %d for a bool", tag)
+	}
+	return tag == 1
+}
+
+// ReadFloat reads a float written as a string, a legacy binary float or an integer.
+func (r *Reader) ReadFloat() float64 {
+	if r.err != nil || r.pos >= len(r.buf) {
+		r.take(1)
+		return 0
+	}
+	switch tag := r.buf[r.pos]; {
+	case tag >= tagStringBase && tag <= tagStringLong:
+		s := r.ReadString()
+		f, err := strconv.ParseFloat(s, 64)
+		if err != nil && r.err == nil {
+			r.fail("invalid float %q", s)
+		}
+		return f
+	case tag == 137:
+		if b := r.take(5); b != nil {
+			return float64(math.Float32frombits(binary.LittleEndian.Uint32(b[1:])))
+		}
+		return 0
+	case tag == 138:
+		if b := r.take(9); b != nil {
+			return math.Float64frombits(binary.LittleEndian.Uint64(b[1:]))
+		}
+		return 0
+	default:
+		return float64(r.ReadInt())
+	}
+}
+
+func (r *Reader) readUTF8(n int) string {
+	b := r.take(n)
+	if b != nil && !utf8.Valid(b) {
+		r.fail("invalid UTF-8")
+	}
+	return string(b)
+}
+
+// ReadString reads a UTF-8 string.
+func (r *Reader) ReadString() string {
+	tag := r.readTag()
+	switch {
+	case tag >= tagStringBase && tag < tagStringLong:
+		return r.readUTF8(int(tag - tagStringBase))
+	case tag == tagStringLong:
+		return r.readUTF8(r.readLength())
+	case r.err == nil:
+		r.fail("unexpected tag %d for a string", tag)
+	}
+	return ""
+}
+
+// ReadBytes reads binary data.
+func (r *Reader) ReadBytes() []byte {
+	r.expectTag(tagBinary)
+	return append([]byte(nil), r.take(r.readLength())...)
+}
+
+// ReadFieldID reads a struct field ID; 0 marks the end of the fields.
+func (r *Reader) ReadFieldID() uint64 {
+	first := r.readTag()
+	if first != 255 {
+		return uint64(first)
+	}
+	if b := r.take(8); b != nil {
+		return binary.LittleEndian.Uint64(b)
+	}
+	return 0
+}
+
+// ReadAny skips a value of a type the schema does not describe.
+func (r *Reader) ReadAny() any {
+	r.Skip()
+	return nil
+}
+
+func (r *Reader) listLength(tag byte) int {
+	switch {
+	case tag >= tagListBase && tag < tagListLong:
+		return int(tag - tagListBase)
+	case tag == tagListLong:
+		return r.readLength()
+	case r.err == nil:
+		r.fail("unexpected tag %d for a list", tag)
+	}
+	return 0
+}
+
+func (r *Reader) skipN(n int) {
+	for ; n > 0 && r.err == nil; n-- {
+		r.Skip()
+	}
+}
+
+// Skip skips one value.
+func (r *Reader) Skip() {
+	tag := r.readTag()
+	switch {
+	case r.err != nil:
+	case tag == 135:
+		r.take(16)
+	case tag <= 134 && tag != tagNone && tag != tagSome && tag != 130:
+		r.uintFromTag(tag)
+	case tag >= tagStringBase && tag < tagStringLong:
+		r.take(int(tag - tagStringBase))
+	case tag == tagStringLong || tag == tagBinary:
+		r.take(r.readLength())
+	case tag >= tagListBase && tag <= tagListLong:
+		r.skipN(r.listLength(tag))
+	case tag == tagNone || tag == 182 || tag == 202:
+	case tag == tagSome || tag == 203 || tag == 205:
+		r.Skip()
+	case tag == tagNegative || tag == 198:
+		r.Skip()
+	case tag == 137:
+		r.take(4)
+	case tag == 138:
+		r.take(8)
+	case tag == tagStructNamed:
+		for r.ReadFieldID() != 0 && r.err == nil {
+			r.Skip()
+		}
+	case tag == 184 || tag == 195 || tag == 206:
+		r.skipN(r.readLength())
+	case tag == 185:
+		r.ReadFieldID()
+	case tag == 186:
+		r.ReadFieldID()
+		for r.ReadFieldID() != 0 && r.err == nil {
+			r.Skip()
+		}
+	case tag == 187:
+		r.ReadFieldID()
+		r.skipN(r.readLength())
+	case tag == tagMap:
+		r.skipN(r.readLength() * 2)
+	case tag == tagMapStringKeys || tag == 207:
+		for n := r.readLength(); n > 0 && r.err == nil; n-- {
+			if tag == 207 {
+				r.Skip()
+			} else {
+				r.take(r.readLength())
+			}
+			r.Skip()
+		}
+	case tag == 210:
+		arity := int(r.readTag())
+		r.skipN(r.readLength() * (arity + 1))
+	case tag == 197 || tag == 208 || tag == 199 || tag == 200:
+		r.Skip()
+		r.ReadUInt()
+	case tag == 201:
+		r.take(16)
+	case tag == 204:
+		if marker := r.readTag(); marker > 3 {
+			r.fail("invalid JSON number marker %d", marker)
+		}
+		r.Skip()
+	default:
+		r.fail("cannot skip tag %d", tag)
+	}
+}
+
+func ptr[T any](v T) *T { return &v }
+
+func readOption[T any](r *Reader, read func() T) *T {
+	switch tag := r.readTag(); {
+	case tag == tagSome:
+		return ptr(read())
+	case tag != tagNone && r.err == nil:
+		r.fail("unexpected tag %d for an option", tag)
+	}
+	return nil
+}
+
+func readList[T any](r *Reader, read func() T) []T {
+	n := r.listLength(r.readTag())
+	out := make([]T, 0, n)
+	for i := 0; i < n && r.err == nil; i++ {
+		out = append(out, read())
+	}
+	return out
+}
+
+func readMap[K comparable, V any](r *Reader, readKey func() K, readValue func() V) map[K]V {
+	tag := r.readTag()
+	if tag != tagMap && tag != tagMapStringKeys {
+		if r.err == nil {
+			r.fail("unexpected tag %d for a map", tag)
+		}
+		return nil
+	}
+	n := r.readLength()
+	out := make(map[K]V, n)
+	for i := 0; i < n && r.err == nil; i++ {
+		var key K
+		if tag == tagMap {
+			key = readKey()
+		} else if k, ok := any(r.readUTF8(r.readLength())).(K); ok {
+			key = k
+		} else {
+			r.fail("string map keys for a non-string key type")
+		}
+		out[key] = readValue()
+	}
+	return out
+}
+"#;
@@ -0,0 +1,453 @@
+//! Compatibility classification between two versions of a schema.
+
+use super::{kind, unwrap_option, widens, EnumSchema, FieldSchema, TypeSchema};
+use std::fmt;
+
+/// Which readers can read which data after a change.
+///
+/// *Backward* compatible: readers built with the new schema read data written with the old
+/// one. *Forward* compatible: readers built with the old schema read data written with the new
+/// one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Compatibility {
+    /// Old and new readers read data written with either schema.
+    Full,
+    /// New readers read old data, but old readers may fail on new data.
+    Backward,
+    /// Old readers read new data, but new readers may fail on old data.
+    Forward,
+    /// Neither direction is guaranteed.
+    Breaking,
+}
+
+impl Compatibility {
+    /// The compatibility of two changes applied together.
+    pub fn and(self, other: Compatibility) -> Compatibility {
+        use Compatibility::*;
+        match (self, other) {
+            (Full, other) | (other, Full) => other,
+            (a, b) if a == b => a,
+            _ => Breaking,
+        }
+    }
+
+    /// Returns `true` if new readers read old data.
+    pub fn is_backward(self) -> bool {
+        matches!(self, Compatibility::Full | Compatibility::Backward)
+    }
+
+    /// Returns `true` if old readers read new data.
+    pub fn is_forward(self) -> bool {
+        matches!(self, Compatibility::Full | Compatibility::Forward)
+    }
+}
+
+impl fmt::Display for Compatibility {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Compatibility::Full => "fully compatible",
+            Compatibility::Backward => "backward compatible",
+            Compatibility::Forward => "forward compatible",
+            Compatibility::Breaking => "breaking",
+        })
+    }
+}
+
+/// A difference between the old and the new schema.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SchemaChange {
+    /// A field was added.
+    FieldAdded {
+        /// The field ID.
+        id: u64,
+        /// Whether payloads must contain it (see [`FieldSchema::is_required`]).
+        required: bool,
+    },
+    /// A field was removed.
+    FieldRemoved {
+        /// The field ID.
+        id: u64,
+        /// Whether old readers require it.
+        required: bool,
+    },
+    /// A field became required, or stopped being required.
+    RequiredChanged {
+        /// Whether the new field is required.
+        required: bool,
+    },
+    /// A variant was added to an enum.
+    VariantAdded {
+        /// The variant ID.
+        id: u64,
+    },
+    /// A variant was removed from an enum.
+    VariantRemoved {
+        /// The variant ID.
+        id: u64,
+    },
+    /// A field or variant kept its ID under a new name.
+    Renamed {
+        /// The old name.
+        old: String,
+        /// The new name.
+        new: String,
+    },
+    /// A value changed its type.
+    TypeChanged {
+        /// The old type.
+        old: &'static str,
+        /// The new type.
+        new: &'static str,
+    },
+    /// A tuple, tuple struct or tuple variant changed its number of fields.
+    ArityChanged {
+        /// The old number of fields.
+        old: usize,
+        /// The new number of fields.
+        new: usize,
+    },
+    /// Two fields or variants of the new schema share an ID.
+    DuplicateId {
+        /// The shared ID.
+        id: u64,
+        /// The name of the first one.
+        first: String,
+        /// The name of the second one.
+        second: String,
+    },
+}
+
+impl fmt::Display for SchemaChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let required = |required: bool| if required { "required" } else { "optional" };
+        match self {
+            SchemaChange::FieldAdded { id, required: r } => {
+                write!(f, "{} field added (id {})", required(*r), id)
+            }
+            SchemaChange::FieldRemoved { id, required: r } => {
+                write!(f, "{} field removed (id {})", required(*r), id)
+            }
+            SchemaChange::RequiredChanged { required: r } => {
+                write!(f, "field became {}", required(*r))
+            }
+            SchemaChange::VariantAdded { id } => write!(f, "variant added (id {})", id),
+            SchemaChange::VariantRemoved { id } => write!(f, "variant removed (id {})", id),
+            SchemaChange::Renamed { old, new } => write!(f, "renamed from {} to {}", old, new),
+            SchemaChange::TypeChanged { old, new } => {
+                write!(f, "type changed from {} to {}", old, new)
+            }
+            SchemaChange::ArityChanged { old, new } => {
+                write!(f, "number of fields changed from {} to {}", old, new)
+            }
+            SchemaChange::DuplicateId { id, first, second } => {
+                write!(f, "{} and {} share the id {}", first, second, id)
+            }
+        }
+    }
+}
+
+/// One change found by [`check_compat`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompatChange {
+    /// Where the change is, e.g. `value.address.city`.
+    pub path: String,
+    /// What changed.
+    pub change: SchemaChange,
+    /// What the change is compatible with.
+    pub compatibility: Compatibility,
+}
+
+/// The changes between two schemas, in the order of the old schema.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CompatReport {
+    changes: Vec<CompatChange>,
+}
+
+impl CompatReport {
+    /// All changes, including fully compatible ones such as renames.
+    pub fn changes(&self) -> &[CompatChange] {
+        &self.changes
+    }
+
+    /// The compatibility of all changes together; [`Compatibility::Full`] if there are none.
+    pub fn compatibility(&self) -> Compatibility {
+        self.changes
+            .iter()
+            .fold(Compatibility::Full, |acc, c| acc.and(c.compatibility))
+    }
+
+    /// Returns `true` if new readers read data written with the old schema.
+    pub fn is_backward_compatible(&self) -> bool {
+        self.compatibility().is_backward()
+    }
+
+    /// Returns `true` if old readers read data written with the new schema.
+    pub fn is_forward_compatible(&self) -> bool {
+        self.compatibility().is_forward()
+    }
+
+    /// Returns `true` if neither direction is guaranteed.
+    pub fn is_breaking(&self) -> bool {
+        self.compatibility() == Compatibility::Breaking
+    }
+
+    /// The changes that are not fully compatible.
+    pub fn incompatible_changes(&self) -> impl Iterator<Item = &CompatChange> {
+        self.changes
+            .iter()
+            .filter(|c| c.compatibility != Compatibility::Full)
+    }
+}
+
+/// A summary line, then one line per change.
+impl fmt::Display for CompatReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}", self.compatibility())?;
+        for change in &self.changes {
+            writeln!(
+                f,
+                "  {}: {} ({})",
+                change.path, change.change, change.compatibility
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Classifies the changes from the `old` to the `new` version of a schema.
+///
+/// Fields and variants are matched by ID, as derived decoders do:
+///
+/// * adding an `Option` field or a field with a default, removing a field old readers do not
+///   require, and renaming a field or variant while keeping its ID are fully compatible;
+/// * adding a required field is forward compatible (old readers skip it; new readers fail on
+///   old data without it), removing a required field is backward compatible;
+/// * adding a variant is backward compatible, removing one is forward compatible;
+/// * widening an integer (`UInt` to `Int` or `Float`, `Int` to `Float`) is backward
+///   compatible, narrowing it forward compatible; other type changes, tuple arity changes and
+///   IDs shared by two fields or variants of the new schema are breaking.
+///
+/// [`TypeSchema::Any`] is compatible with every type.
+///
+/// # Example
+/// ```rust
+/// use senax_encoder::schema::{check_compat, Compatibility, FieldSchema, StructSchema, TypeSchema};
+///
+/// let v1 = TypeSchema::Struct(StructSchema::new("User", vec![
+///     FieldSchema::new("name", TypeSchema::String),
+///     FieldSchema::new("age", TypeSchema::UInt),
+/// ]));
+/// let v2 = TypeSchema::Struct(StructSchema::new("User", vec![
+///     FieldSchema::new("name", TypeSchema::String),
+///     FieldSchema::new("age", TypeSchema::Int),
+///     FieldSchema::new("email", TypeSchema::Option(Box::new(TypeSchema::String))),
+/// ]));
+///
+/// let report = check_compat(&v1, &v2);
+/// assert_eq!(report.compatibility(), Compatibility::Backward);
+/// assert!(report.is_backward_compatible());
+/// assert!(!report.is_forward_compatible());
+/// print!("{}", report);
+/// // backward compatible
+/// //   value.age: type changed from unsigned integer to signed integer (backward compatible)
+/// //   value.email: optional field added (id ...) (fully compatible)
+/// ```
+pub fn check_compat(old: &TypeSchema, new: &TypeSchema) -> CompatReport {
+    let mut checker = Checker::default();
+    checker.compare(old, new, "value");
+    CompatReport {
+        changes: checker.changes,
+    }
+}
+
+#[derive(Default)]
+struct Checker {
+    changes: Vec<CompatChange>,
+}
+
+impl Checker {
+    fn push(&mut self, path: &str, change: SchemaChange, compatibility: Compatibility) {
+        self.changes.push(CompatChange {
+            path: path.to_string(),
+            change,
+            compatibility,
+        });
+    }
+
+    fn compare(&mut self, old: &TypeSchema, new: &TypeSchema, path: &str) {
+        use TypeSchema::*;
+        match (old, new) {
+            (Any, _) | (_, Any) => {}
+            (Option(old), Option(new)) | (List(old), List(new)) => self.compare(old, new, path),
+            (Map(old_key, old_value), Map(new_key, new_value)) => {
+                self.compare(old_key, new_key, path);
+                self.compare(old_value, new_value, path);
+            }
+            (Struct(old), Struct(new)) => self.compare_fields(&old.fields, &new.fields, path),
+            (Tuple(old), Tuple(new)) => self.compare_positional(old, new, path),
+            (TupleStruct(old), TupleStruct(new)) => {
+                self.compare_positional(&old.fields, &new.fields, path)
+            }
+            (Enum(old), Enum(new)) => self.compare_variants(old, new, path),
+            (old, new) => {
+                let compatibility = match (widens(old, new), widens(new, old)) {
+                    (true, true) => return,
+                    (true, false) => Compatibility::Backward,
+                    (false, true) => Compatibility::Forward,
+                    (false, false) => Compatibility::Breaking,
+                };
+                self.push(path, type_changed(old, new), compatibility);
+            }
+        }
+    }
+
+    fn compare_positional(&mut self, old: &[TypeSchema], new: &[TypeSchema], path: &str) {
+        if old.len() != new.len() {
+            let change = SchemaChange::ArityChanged {
+                old: old.len(),
+                new: new.len(),
+            };
+            self.push(path, change, Compatibility::Breaking);
+            return;
+        }
+        for (i, (old, new)) in old.iter().zip(new).enumerate() {
+            self.compare(old, new, &format!("{}.{}", path, i));
+        }
+    }
+
+    fn compare_fields(&mut self, old: &[FieldSchema], new: &[FieldSchema], path: &str) {
+        for (i, field) in new.iter().enumerate() {
+            if let Some(first) = new[..i].iter().find(|f| f.id == field.id) {
+                let change = SchemaChange::DuplicateId {
+                    id: field.id,
+                    first: first.name.clone(),
+                    second: field.name.clone(),
+                };
+                self.push(path, change, Compatibility::Breaking);
+            }
+        }
+
+        for old_field in old {
+            let field_path = format!("{}.{}", path, old_field.name);
+            let Some(new_field) = new.iter().find(|f| f.id == old_field.id) else {
+                let required = old_field.is_required();
+                let compatibility = if required {
+                    Compatibility::Backward
+                } else {
+                    Compatibility::Full
+                };
+                let change = SchemaChange::FieldRemoved {
+                    id: old_field.id,
+                    required,
+                };
+                self.push(&field_path, change, compatibility);
+                continue;
+            };
+            if new_field.name != old_field.name {
+                let change = SchemaChange::Renamed {
+                    old: old_field.name.clone(),
+                    new: new_field.name.clone(),
+                };
+                self.push(&field_path, change, Compatibility::Full);
+            }
+            // Required-ness only matters for payloads without the field
+            match (old_field.is_required(), new_field.is_required()) {
+                (false, true) => self.push(
+                    &field_path,
+                    SchemaChange::RequiredChanged { required: true },
+                    Compatibility::Forward,
+                ),
+                (true, false) => self.push(
+                    &field_path,
+                    SchemaChange::RequiredChanged { required: false },
+                    Compatibility::Backward,
+                ),
+                _ => {}
+            }
+            // Present Option fields are written without the Some tag
+            self.compare(
+                unwrap_option(&old_field.ty),
+                unwrap_option(&new_field.ty),
+                &field_path,
+            );
+        }
+
+        for new_field in new.iter().filter(|f| !old.iter().any(|o| o.id == f.id)) {
+            let required = new_field.is_required();
+            let compatibility = if required {
+                Compatibility::Forward
+            } else {
+                Compatibility::Full
+            };
+            let change = SchemaChange::FieldAdded {
+                id: new_field.id,
+                required,
+            };
+            self.push(
+                &format!("{}.{}", path, new_field.name),
+                change,
+                compatibility,
+            );
+        }
+    }
+
+    fn compare_variants(&mut self, old: &EnumSchema, new: &EnumSchema, path: &str) {
+        for (i, variant) in new.variants.iter().enumerate() {
+            if let Some(first) = new.variants[..i].iter().find(|v| v.id == variant.id) {
+                let change = SchemaChange::DuplicateId {
+                    id: variant.id,
+                    first: first.name.clone(),
+                    second: variant.name.clone(),
+                };
+                self.push(path, change, Compatibility::Breaking);
+            }
+        }
+
+        for old_variant in &old.variants {
+            let variant_path = format!("{}::{}", path, old_variant.name);
+            let Some(new_variant) = new.variants.iter().find(|v| v.id == old_variant.id) else {
+                let change = SchemaChange::VariantRemoved { id: old_variant.id };
+                self.push(&variant_path, change, Compatibility::Forward);
+                continue;
+            };
+            if new_variant.name != old_variant.name {
+                let change = SchemaChange::Renamed {
+                    old: old_variant.name.clone(),
+                    new: new_variant.name.clone(),
+                };
+                self.push(&variant_path, change, Compatibility::Full);
+            }
+            match (&old_variant.fields, &new_variant.fields) {
+                (Some(old), Some(new)) => self.compare(old, new, &variant_path),
+                (None, None) => {}
+                (old, new) => {
+                    let change = SchemaChange::TypeChanged {
+                        old: old.as_ref().map_or("unit variant", kind),
+                        new: new.as_ref().map_or("unit variant", kind),
+                    };
+                    self.push(&variant_path, change, Compatibility::Breaking);
+                }
+            }
+        }
+
+        for new_variant in new
+            .variants
+            .iter()
+            .filter(|v| !old.variants.iter().any(|o| o.id == v.id))
+        {
+            let change = SchemaChange::VariantAdded { id: new_variant.id };
+            self.push(
+                &format!("{}::{}", path, new_variant.name),
+                change,
+                Compatibility::Backward,
+            );
+        }
+    }
+}
+
+fn type_changed(old: &TypeSchema, new: &TypeSchema) -> SchemaChange {
+    SchemaChange::TypeChanged {
+        old: kind(old),
+        new: kind(new),
+    }
+}
@@ -0,0 +1,249 @@
+//! Self-describing payloads that carry field and variant names.
+//!
+//! [`encode_self_describing`] writes the usual encode format, except that every field and
+//! variant ID written by derived code is followed by an index into a table of names stored at
+//! the start of the payload:
+//!
+//! ```text
+//! [magic: 0xA55D] [name count] [name]... [value]
+//! ```
+//!
+//! Each name is stored once per payload. [`decode_self_describing`] reads such a payload into a
+//! [`Value`] whose fields and variants carry their names, so archived data stays readable
+//! without the Rust types that wrote it. Self-describing payloads cannot be read by
+//! [`decode`](crate::decode).
+//!
+//! [`encode_named`] writes a "document" instead: every field and variant is written as
+//! [`FIELD_NAME_MARKER`] followed by its name in place of the ID, with no table:
+//!
+//! ```text
+//! [magic: 0xA559] [value]
+//! ```
+//!
+//! Documents are larger, but each value can be read on its own, by tools that only know the
+//! names, and [`decode_named`] reads them back into the Rust types. Derived decoders match a
+//! name to the field with that wire name (after `rename`/`rename_all`), also for fields with
+//! an explicit `id`.
+//!
+//! Both encoders need the `std` feature. Without it, derived encoders always write plain IDs,
+//! and derived decoders still read documents.
+
+use crate::core::write_field_id_optimized;
+#[cfg(feature = "std")]
+use crate::core::FIELD_NAME_MARKER;
+#[cfg(feature = "std")]
+use crate::value::{read_value, Value};
+use crate::Result;
+#[cfg(feature = "std")]
+use crate::{Decoder, Encoder, EncoderError};
+use bytes::BytesMut;
+#[cfg(feature = "std")]
+use bytes::{Buf, BufMut, Bytes};
+#[cfg(feature = "std")]
+use std::cell::RefCell;
+#[cfg(feature = "std")]
+use std::collections::HashMap;
+
+/// Magic number for self-describing payloads (0xA55D in little-endian).
+pub const SELF_DESCRIBING_MAGIC: u16 = 0xA55D;
+
+/// Magic number for documents written by [`encode_named`] (0xA559 in little-endian).
+pub const NAMED_MAGIC: u16 = 0xA559;
+
+#[cfg(feature = "std")]
+#[derive(Default)]
+struct NameTable {
+    names: Vec<&'static str>,
+    index: HashMap<&'static str, usize>,
+}
+
+#[cfg(feature = "std")]
+/// How field and variant headers are written during an encode.
+enum NameMode {
+    /// ID followed by an index into the table ([`encode_self_describing`]).
+    Table(NameTable),
+    /// Name instead of the ID ([`encode_named`]).
+    Named,
+}
+
+#[cfg(feature = "std")]
+thread_local! {
+    static NAME_MODE: RefCell<Option<NameMode>> = const { RefCell::new(None) };
+}
+
+/// Restores the previous name mode when dropped.
+#[cfg(feature = "std")]
+struct NameModeGuard(Option<NameMode>);
+
+#[cfg(feature = "std")]
+impl Drop for NameModeGuard {
+    fn drop(&mut self) {
+        NAME_MODE.with(|t| *t.borrow_mut() = self.0.take());
+    }
+}
+
+/// Runs `f` with `mode` in effect, then returns the mode as `f` left it.
+#[cfg(feature = "std")]
+fn with_mode<R>(mode: NameMode, f: impl FnOnce() -> R) -> (R, Option<NameMode>) {
+    let previous = NAME_MODE.with(|t| t.borrow_mut().replace(mode));
+    let _guard = NameModeGuard(previous);
+    let result = f();
+    (result, NAME_MODE.with(|t| t.borrow_mut().take()))
+}
+
+/// Writes the header of a field or variant: its ID, followed by its name index during a
+/// self-describing encode, or its name instead of the ID during a named encode.
+/// Used by derived code.
+#[doc(hidden)]
+#[cfg(feature = "std")]
+#[inline]
+pub fn write_field_header(writer: &mut BytesMut, id: u64, name: &'static str) -> Result<()> {
+    NAME_MODE.with(|t| match t.borrow_mut().as_mut() {
+        None => write_field_id_optimized(writer, id),
+        Some(NameMode::Table(table)) => {
+            write_field_id_optimized(writer, id)?;
+            let next = table.names.len();
+            let index = *table.index.entry(name).or_insert(next);
+            if index == next {
+                table.names.push(name);
+            }
+            index.encode(writer)
+        }
+        Some(NameMode::Named) => {
+            writer.put_u8(FIELD_NAME_MARKER);
+            crate::core::write_str(name, writer)
+        }
+    })
+}
+
+#[doc(hidden)]
+#[cfg(not(feature = "std"))]
+#[inline]
+pub fn write_field_header(writer: &mut BytesMut, id: u64, _name: &'static str) -> Result<()> {
+    write_field_id_optimized(writer, id)
+}
+
+/// Runs `f` without recording names, for nested payloads that are read with [`Decoder`].
+#[cfg(feature = "chacha20poly1305")]
+pub(crate) fn without_names<R>(f: impl FnOnce() -> R) -> R {
+    let previous = NAME_MODE.with(|t| t.borrow_mut().take());
+    let _guard = NameModeGuard(previous);
+    f()
+}
+
+/// Encodes `value` as a self-describing payload.
+///
+/// # Example
+/// ```rust
+/// use senax_encoder::self_describing::{decode_self_describing, encode_self_describing};
+/// use senax_encoder::{Encode, Value};
+///
+/// #[derive(Encode)]
+/// struct Reading { sensor: String, celsius: i32 }
+///
+/// let bytes = encode_self_describing(&Reading { sensor: "probe-1".into(), celsius: -4 }).unwrap();
+/// let value = decode_self_describing(&mut bytes.clone()).unwrap();
+/// assert_eq!(value.field("sensor"), Some(&Value::String("probe-1".into())));
+/// assert_eq!(value.field("celsius"), Some(&Value::Int(-4)));
+/// ```
+#[cfg(feature = "std")]
+pub fn encode_self_describing<T: Encoder>(value: &T) -> Result<Bytes> {
+    let mut body = BytesMut::new();
+    let (result, mode) = with_mode(NameMode::Table(NameTable::default()), || {
+        value.encode(&mut body)
+    });
+    result?;
+    let table = match mode {
+        Some(NameMode::Table(table)) => table,
+        _ => NameTable::default(),
+    };
+
+    let mut writer = BytesMut::with_capacity(body.len() + 16 * table.names.len());
+    writer.put_u16_le(SELF_DESCRIBING_MAGIC);
+    table.names.len().encode(&mut writer)?;
+    for name in &table.names {
+        crate::core::write_str(name, &mut writer)?;
+    }
+    writer.put_slice(&body);
+    Ok(writer.freeze())
+}
+
+/// Decodes a payload written by [`encode_self_describing`] or [`encode_named`] into a
+/// [`Value`] with names.
+#[cfg(feature = "std")]
+pub fn decode_self_describing(reader: &mut Bytes) -> Result<Value> {
+    if reader.remaining() < 2 {
+        return Err(EncoderError::eof(2, reader.remaining()));
+    }
+    match reader.get_u16_le() {
+        SELF_DESCRIBING_MAGIC => {
+            let names = read_name_table(reader)?;
+            read_value(reader, Some(&names), 1)
+        }
+        NAMED_MAGIC => read_value(reader, None, 1),
+        magic => Err(EncoderError::Decode(format!(
+            "Invalid self-describing magic number: expected 0x{:04X} or 0x{:04X}, got 0x{:04X}",
+            SELF_DESCRIBING_MAGIC, NAMED_MAGIC, magic
+        ))),
+    }
+}
+
+/// Encodes `value` as a document whose fields and variants are written by name.
+///
+/// # Example
+/// ```rust
+/// use senax_encoder::self_describing::{decode_named, decode_self_describing, encode_named};
+/// use senax_encoder::{Decode, Encode, Value};
+///
+/// #[derive(Encode, Decode, Debug, PartialEq)]
+/// struct Reading { sensor: String, #[senax(id = 7)] celsius: i32 }
+///
+/// let reading = Reading { sensor: "probe-1".into(), celsius: -4 };
+/// let bytes = encode_named(&reading).unwrap();
+/// assert_eq!(decode_named::<Reading>(&mut bytes.clone()).unwrap(), reading);
+///
+/// // Readable without the type
+/// let value = decode_self_describing(&mut bytes.clone()).unwrap();
+/// assert_eq!(value.field("celsius"), Some(&Value::Int(-4)));
+/// ```
+#[cfg(feature = "std")]
+pub fn encode_named<T: Encoder>(value: &T) -> Result<Bytes> {
+    let mut writer = BytesMut::new();
+    writer.put_u16_le(NAMED_MAGIC);
+    with_mode(NameMode::Named, || value.encode(&mut writer)).0?;
+    Ok(writer.freeze())
+}
+
+/// Decodes a document written by [`encode_named`].
+///
+/// Fields are matched by wire name; a name the type does not know is treated as an unknown
+/// field, like an unknown ID.
+#[cfg(feature = "std")]
+pub fn decode_named<T: Decoder>(reader: &mut Bytes) -> Result<T> {
+    if reader.remaining() < 2 {
+        return Err(EncoderError::eof(2, reader.remaining()));
+    }
+    let magic = reader.get_u16_le();
+    if magic != NAMED_MAGIC {
+        return Err(EncoderError::Decode(format!(
+            "Invalid named document magic number: expected 0x{:04X}, got 0x{:04X}",
+            NAMED_MAGIC, magic
+        )));
+    }
+    T::decode(reader)
+}
+
+/// Reads the name table that follows the magic number.
+#[cfg(feature = "std")]
+pub(crate) fn read_name_table(reader: &mut Bytes) -> Result<Vec<String>> {
+    let count = usize::decode(reader)?;
+    // Every name takes at least one byte
+    if reader.remaining() < count {
+        return Err(EncoderError::eof(count, reader.remaining()));
+    }
+    let mut names = Vec::with_capacity(count);
+    for _ in 0..count {
+        names.push(String::decode(reader)?);
+    }
+    Ok(names)
+}
@@ -0,0 +1,886 @@
+//! Bridge between serde and the encode format.
+//!
+//! [`SenaxSerde<T>`] implements [`Encoder`] for any `T: Serialize` and [`Decoder`] for any
+//! `T: DeserializeOwned`, so types that only implement serde traits can be used wherever senax
+//! types are expected: as fields of derived structs, in collections, or on their own. The value
+//! goes through [`Value`], written with the same tags, field IDs and variant IDs as the derive
+//! macros, so a serde type and a `#[derive(Encode, Decode)]` type with the same shape read each
+//! other's payloads:
+//!
+//! - Struct fields and enum variants are identified by the ID hashed from their serde name
+//!   (the Rust name unless `#[serde(rename)]` is used). `None` fields are omitted.
+//! - Floats are written as strings, as by the `f32`/`f64` encoders.
+//! - Newtype structs are tuple structs with one field; `()` is an empty tuple.
+//! - `serialize_bytes` (e.g. `serde_bytes`) writes binary data; a plain `Vec<u8>` is a list.
+//!
+//! Unknown fields are skipped on decode. Types that need self-describing input
+//! (`#[serde(flatten)]`, `#[serde(untagged)]`, internally tagged enums) can only see field and
+//! variant IDs, not names, and are not supported.
+
+use crate::core::field_id_from_name;
+use crate::value::{write_value, Field, Value, Variant, VariantFields};
+use crate::{Decoder, Encoder, EncoderError, Result};
+use bytes::{Bytes, BytesMut};
+use serde::de::{self, DeserializeOwned, IntoDeserializer, Visitor};
+use serde::ser::{self, Serialize};
+use std::fmt::Display;
+use std::ops::{Deref, DerefMut};
+
+/// Wraps a serde type so it can be encoded and decoded with senax.
+///
+/// # Example
+/// ```rust
+/// use senax_encoder::senax_serde::SenaxSerde;
+/// use senax_encoder::{decode, encode, Decode, Encode};
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Serialize, Deserialize, Debug, PartialEq)]
+/// struct Point { x: i32, y: i32 }
+///
+/// // The same shape, derived with senax
+/// #[derive(Encode, Decode, Debug, PartialEq)]
+/// struct SenaxPoint { x: i32, y: i32 }
+///
+/// let mut bytes = encode(&SenaxSerde(Point { x: 1, y: -2 })).unwrap();
+/// let decoded: SenaxPoint = decode(&mut bytes.clone()).unwrap();
+/// assert_eq!(decoded, SenaxPoint { x: 1, y: -2 });
+///
+/// let decoded: SenaxSerde<Point> = decode(&mut bytes).unwrap();
+/// assert_eq!(decoded.into_inner(), Point { x: 1, y: -2 });
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct SenaxSerde<T>(pub T);
+
+impl<T> SenaxSerde<T> {
+    /// Returns the wrapped value.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> From<T> for SenaxSerde<T> {
+    fn from(value: T) -> Self {
+        SenaxSerde(value)
+    }
+}
+
+impl<T> Deref for SenaxSerde<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for SenaxSerde<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+/// Encodes the wrapped value through its `Serialize` implementation.
+impl<T: Serialize> Encoder for SenaxSerde<T> {
+    fn encode(&self, writer: &mut BytesMut) -> Result<()> {
+        write_value(&to_value(&self.0)?, writer)
+    }
+
+    /// serde has no notion of a default value, so this is always `false`.
+    fn is_default(&self) -> bool {
+        false
+    }
+}
+
+/// Decodes the wrapped value through its `Deserialize` implementation.
+impl<T: DeserializeOwned> Decoder for SenaxSerde<T> {
+    fn decode(reader: &mut Bytes) -> Result<Self> {
+        from_value(Value::decode(reader)?).map(SenaxSerde)
+    }
+}
+
+/// Converts a serde type to the [`Value`] it is encoded as.
+pub fn to_value<T: Serialize + ?Sized>(value: &T) -> Result<Value> {
+    value.serialize(ValueSerializer)
+}
+
+/// Converts a [`Value`] decoded from the encode format to a serde type.
+///
+/// Struct fields and enum variants are matched by the IDs hashed from their serde names.
+pub fn from_value<T: DeserializeOwned>(value: Value) -> Result<T> {
+    T::deserialize(ValueDeserializer(value))
+}
+
+impl ser::Error for EncoderError {
+    fn custom<T: Display>(msg: T) -> Self {
+        EncoderError::Encode(msg.to_string())
+    }
+}
+
+impl de::Error for EncoderError {
+    fn custom<T: Display>(msg: T) -> Self {
+        EncoderError::Decode(msg.to_string())
+    }
+}
+
+fn decode_error(msg: impl Display) -> EncoderError {
+    <EncoderError as de::Error>::custom(msg)
+}
+
+// --- Serialization ---
+
+struct ValueSerializer;
+
+fn unsigned(v: impl Into<u128>) -> Value {
+    Value::UInt(v.into())
+}
+
+fn signed(v: impl Into<i128>) -> Value {
+    let v = v.into();
+    if v >= 0 {
+        Value::UInt(v as u128)
+    } else {
+        Value::Int(v)
+    }
+}
+
+fn variant(name: &str, fields: VariantFields) -> Value {
+    Value::Enum(Box::new(Variant {
+        id: field_id_from_name(name),
+        name: None,
+        fields,
+    }))
+}
+
+impl ser::Serializer for ValueSerializer {
+    type Ok = Value;
+    type Error = EncoderError;
+    type SerializeSeq = SeqSerializer;
+    type SerializeTuple = SeqSerializer;
+    type SerializeTupleStruct = SeqSerializer;
+    type SerializeTupleVariant = SeqSerializer;
+    type SerializeMap = MapSerializer;
+    type SerializeStruct = StructSerializer;
+    type SerializeStructVariant = StructSerializer;
+
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+
+    fn serialize_bool(self, v: bool) -> Result<Value> {
+        Ok(unsigned(v as u8))
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<Value> {
+        Ok(signed(v))
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<Value> {
+        Ok(signed(v))
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<Value> {
+        Ok(signed(v))
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<Value> {
+        Ok(signed(v))
+    }
+
+    fn serialize_i128(self, v: i128) -> Result<Value> {
+        Ok(signed(v))
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<Value> {
+        Ok(unsigned(v))
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<Value> {
+        Ok(unsigned(v))
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<Value> {
+        Ok(unsigned(v))
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<Value> {
+        Ok(unsigned(v))
+    }
+
+    fn serialize_u128(self, v: u128) -> Result<Value> {
+        Ok(unsigned(v))
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<Value> {
+        Ok(Value::String(format!("{:e}", v)))
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<Value> {
+        Ok(Value::String(format!("{:e}", v)))
+    }
+
+    fn serialize_char(self, v: char) -> Result<Value> {
+        Ok(unsigned(v as u32))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<Value> {
+        Ok(Value::String(v.to_string()))
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<Value> {
+        Ok(Value::Binary(Bytes::copy_from_slice(v)))
+    }
+
+    fn serialize_none(self) -> Result<Value> {
+        Ok(Value::None)
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<Value> {
+        Ok(Value::Some(Box::new(to_value(value)?)))
+    }
+
+    fn serialize_unit(self) -> Result<Value> {
+        Ok(Value::Tuple(Vec::new()))
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<Value> {
+        Ok(Value::UnitStruct)
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant_name: &'static str,
+    ) -> Result<Value> {
+        Ok(variant(variant_name, VariantFields::Unit))
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<Value> {
+        Ok(Value::TupleStruct(vec![to_value(value)?]))
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant_name: &'static str,
+        value: &T,
+    ) -> Result<Value> {
+        Ok(variant(
+            variant_name,
+            VariantFields::Unnamed(vec![to_value(value)?]),
+        ))
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<SeqSerializer> {
+        Ok(SeqSerializer::new(SeqKind::List, len.unwrap_or(0)))
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<SeqSerializer> {
+        Ok(SeqSerializer::new(SeqKind::Tuple, len))
+    }
+
+    fn serialize_tuple_struct(self, _name: &'static str, len: usize) -> Result<SeqSerializer> {
+        Ok(SeqSerializer::new(SeqKind::TupleStruct, len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant_name: &'static str,
+        len: usize,
+    ) -> Result<SeqSerializer> {
+        Ok(SeqSerializer::new(SeqKind::Variant(variant_name), len))
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<MapSerializer> {
+        Ok(MapSerializer {
+            entries: Vec::with_capacity(len.unwrap_or(0)),
+            key: None,
+        })
+    }
+
+    fn serialize_struct(self, _name: &'static str, len: usize) -> Result<StructSerializer> {
+        Ok(StructSerializer {
+            fields: Vec::with_capacity(len),
+            variant: None,
+        })
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant_name: &'static str,
+        len: usize,
+    ) -> Result<StructSerializer> {
+        Ok(StructSerializer {
+            fields: Vec::with_capacity(len),
+            variant: Some(variant_name),
+        })
+    }
+}
+
+enum SeqKind {
+    List,
+    Tuple,
+    TupleStruct,
+    Variant(&'static str),
+}
+
+struct SeqSerializer {
+    kind: SeqKind,
+    values: Vec<Value>,
+}
+
+impl SeqSerializer {
+    fn new(kind: SeqKind, len: usize) -> Self {
+        SeqSerializer {
+            kind,
+            values: Vec::with_capacity(len),
+        }
+    }
+
+    fn push<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        self.values.push(to_value(value)?);
+        Ok(())
+    }
+
+    fn finish(self) -> Value {
+        match self.kind {
+            SeqKind::List => Value::List(self.values),
+            SeqKind::Tuple => Value::Tuple(self.values),
+            SeqKind::TupleStruct => Value::TupleStruct(self.values),
+            SeqKind::Variant(name) => variant(name, VariantFields::Unnamed(self.values)),
+        }
+    }
+}
+
+impl ser::SerializeSeq for SeqSerializer {
+    type Ok = Value;
+    type Error = EncoderError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Value> {
+        Ok(self.finish())
+    }
+}
+
+impl ser::SerializeTuple for SeqSerializer {
+    type Ok = Value;
+    type Error = EncoderError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Value> {
+        Ok(self.finish())
+    }
+}
+
+impl ser::SerializeTupleStruct for SeqSerializer {
+    type Ok = Value;
+    type Error = EncoderError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Value> {
+        Ok(self.finish())
+    }
+}
+
+impl ser::SerializeTupleVariant for SeqSerializer {
+    type Ok = Value;
+    type Error = EncoderError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Value> {
+        Ok(self.finish())
+    }
+}
+
+struct MapSerializer {
+    entries: Vec<(Value, Value)>,
+    key: Option<Value>,
+}
+
+impl ser::SerializeMap for MapSerializer {
+    type Ok = Value;
+    type Error = EncoderError;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<()> {
+        self.key = Some(to_value(key)?);
+        Ok(())
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        let key = self.key.take().ok_or_else(|| {
+            EncoderError::Encode("Map value serialized before its key".to_string())
+        })?;
+        self.entries.push((key, to_value(value)?));
+        Ok(())
+    }
+
+    fn end(self) -> Result<Value> {
+        Ok(Value::Map(self.entries))
+    }
+}
+
+struct StructSerializer {
+    fields: Vec<Field>,
+    variant: Option<&'static str>,
+}
+
+impl StructSerializer {
+    fn push<T: Serialize + ?Sized>(&mut self, name: &'static str, value: &T) -> Result<()> {
+        // Like derived encoders, omit `None` fields and write `Some` fields without the tag
+        let value = match to_value(value)? {
+            Value::None => return Ok(()),
+            Value::Some(inner) => *inner,
+            value => value,
+        };
+        self.fields.push(Field {
+            id: field_id_from_name(name),
+            name: None,
+            value,
+        });
+        Ok(())
+    }
+
+    fn finish(self) -> Value {
+        match self.variant {
+            None => Value::Struct(self.fields),
+            Some(name) => variant(name, VariantFields::Named(self.fields)),
+        }
+    }
+}
+
+impl ser::SerializeStruct for StructSerializer {
+    type Ok = Value;
+    type Error = EncoderError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        name: &'static str,
+        value: &T,
+    ) -> Result<()> {
+        self.push(name, value)
+    }
+
+    fn end(self) -> Result<Value> {
+        Ok(self.finish())
+    }
+}
+
+impl ser::SerializeStructVariant for StructSerializer {
+    type Ok = Value;
+    type Error = EncoderError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        name: &'static str,
+        value: &T,
+    ) -> Result<()> {
+        self.push(name, value)
+    }
+
+    fn end(self) -> Result<Value> {
+        Ok(self.finish())
+    }
+}
+
+// --- Deserialization ---
+
+struct ValueDeserializer(Value);
+
+impl ValueDeserializer {
+    fn invalid_type<E: de::Expected>(&self, expected: &E) -> EncoderError {
+        let unexpected = match &self.0 {
+            Value::None => de::Unexpected::Option,
+            Value::Some(_) => de::Unexpected::Option,
+            Value::UInt(v) => match u64::try_from(*v) {
+                Ok(v) => de::Unexpected::Unsigned(v),
+                Err(_) => de::Unexpected::Other("u128"),
+            },
+            Value::Int(v) => match i64::try_from(*v) {
+                Ok(v) => de::Unexpected::Signed(v),
+                Err(_) => de::Unexpected::Other("i128"),
+            },
+            Value::Float(v) => de::Unexpected::Float(*v),
+            Value::String(s) => de::Unexpected::Str(s),
+            Value::Binary(b) => de::Unexpected::Bytes(b),
+            Value::List(_) | Value::Tuple(_) => de::Unexpected::Seq,
+            Value::Map(_) => de::Unexpected::Map,
+            Value::UnitStruct => de::Unexpected::Unit,
+            Value::Struct(_) => de::Unexpected::Other("struct"),
+            Value::TupleStruct(_) => de::Unexpected::Other("tuple struct"),
+            Value::Enum(_) => de::Unexpected::Enum,
+            Value::Uuid(_) => de::Unexpected::Other("UUID"),
+            Value::Extension { .. } => de::Unexpected::Other("extension value"),
+        };
+        de::Error::invalid_type(unexpected, expected)
+    }
+
+    /// Parses a float written as a string, or converts a legacy binary float or an integer.
+    fn float(self) -> Result<f64> {
+        match self.0 {
+            Value::String(s) => s
+                .parse()
+                .map_err(|e| decode_error(format!("Invalid float string '{}': {}", s, e))),
+            Value::Float(v) => Ok(v),
+            Value::UInt(v) => Ok(v as f64),
+            Value::Int(v) => Ok(v as f64),
+            _ => Err(self.invalid_type(&"a float")),
+        }
+    }
+}
+
+fn visit_seq<'de, V: Visitor<'de>>(values: Vec<Value>, visitor: V) -> Result<V::Value> {
+    let len = values.len();
+    let mut seq = SeqDeserializer(values.into_iter());
+    let value = visitor.visit_seq(&mut seq)?;
+    if !seq.0.as_slice().is_empty() {
+        return Err(de::Error::invalid_length(len, &"fewer elements"));
+    }
+    Ok(value)
+}
+
+/// Pairs each field with the name in `names` that hashes to its ID; other fields are dropped.
+fn visit_fields<'de, V: Visitor<'de>>(
+    fields: Vec<Field>,
+    names: &'static [&'static str],
+    visitor: V,
+) -> Result<V::Value> {
+    let named = fields
+        .into_iter()
+        .filter_map(|field| {
+            names
+                .iter()
+                .find(|name| field_id_from_name(name) == field.id)
+                .map(|name| (*name, field.value))
+        })
+        .collect::<Vec<_>>();
+    visitor.visit_map(StructDeserializer {
+        fields: named.into_iter(),
+        value: None,
+    })
+}
+
+impl<'de> de::Deserializer<'de> for ValueDeserializer {
+    type Error = EncoderError;
+
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        match self.0 {
+            Value::None => visitor.visit_none(),
+            Value::Some(inner) => visitor.visit_some(ValueDeserializer(*inner)),
+            Value::UInt(v) => match u64::try_from(v) {
+                Ok(v) => visitor.visit_u64(v),
+                Err(_) => visitor.visit_u128(v),
+            },
+            Value::Int(v) => match i64::try_from(v) {
+                Ok(v) => visitor.visit_i64(v),
+                Err(_) => visitor.visit_i128(v),
+            },
+            Value::Float(v) => visitor.visit_f64(v),
+            Value::String(s) => visitor.visit_string(s),
+            Value::Binary(b) => visitor.visit_byte_buf(b.to_vec()),
+            Value::List(values) | Value::Tuple(values) | Value::TupleStruct(values) => {
+                visit_seq(values, visitor)
+            }
+            Value::Map(entries) => visitor.visit_map(MapDeserializer {
+                entries: entries.into_iter(),
+                value: None,
+            }),
+            Value::UnitStruct => visitor.visit_unit(),
+            Value::Uuid(bytes) => visitor.visit_bytes(&bytes),
+            value @ (Value::Struct(_) | Value::Enum(_) | Value::Extension { .. }) => {
+                Err(ValueDeserializer(value)
+                    .invalid_type(&"a value whose type is known (field names are not encoded)"))
+            }
+        }
+    }
+
+    fn deserialize_bool<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        match self.0 {
+            Value::UInt(0) => visitor.visit_bool(false),
+            Value::UInt(1) => visitor.visit_bool(true),
+            _ => Err(self.invalid_type(&"a bool")),
+        }
+    }
+
+    fn deserialize_f32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        match self.0 {
+            Value::String(s) => visitor.visit_f32(
+                s.parse()
+                    .map_err(|e| decode_error(format!("Invalid f32 string '{}': {}", s, e)))?,
+            ),
+            value => visitor.visit_f32(ValueDeserializer(value).float()? as f32),
+        }
+    }
+
+    fn deserialize_f64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_f64(self.float()?)
+    }
+
+    fn deserialize_char<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        match self.0 {
+            Value::UInt(v) => match u32::try_from(v).ok().and_then(char::from_u32) {
+                Some(c) => visitor.visit_char(c),
+                None => Err(decode_error(format!("Invalid Unicode code point: {}", v))),
+            },
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        match self.0 {
+            Value::None => visitor.visit_none(),
+            Value::Some(inner) => visitor.visit_some(ValueDeserializer(*inner)),
+            // Struct fields hold the inner value of `Some`
+            value => visitor.visit_some(ValueDeserializer(value)),
+        }
+    }
+
+    fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        match &self.0 {
+            Value::UnitStruct => visitor.visit_unit(),
+            Value::Tuple(values) if values.is_empty() => visitor.visit_unit(),
+            _ => Err(self.invalid_type(&"unit")),
+        }
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value> {
+        self.deserialize_unit(visitor)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value> {
+        match self.0 {
+            Value::TupleStruct(mut values) if values.len() == 1 => {
+                visitor.visit_newtype_struct(ValueDeserializer(values.remove(0)))
+            }
+            value => visitor.visit_newtype_struct(ValueDeserializer(value)),
+        }
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value> {
+        match self.0 {
+            Value::Struct(values) => visit_fields(values, fields, visitor),
+            // Positional payloads, as accepted by `#[senax(accept_tuple)]`
+            Value::TupleStruct(values) | Value::Tuple(values) | Value::List(values) => {
+                visit_seq(values, visitor)
+            }
+            _ => Err(self.invalid_type(&"a struct")),
+        }
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value> {
+        match self.0 {
+            Value::Enum(variant) => {
+                let name = variants
+                    .iter()
+                    .find(|name| field_id_from_name(name) == variant.id)
+                    .ok_or_else(|| {
+                        decode_error(format!("Unknown variant ID 0x{:016X}", variant.id))
+                    })?;
+                visitor.visit_enum(EnumDeserializer {
+                    name,
+                    fields: variant.fields,
+                })
+            }
+            // Unit variants of `#[senax(string_repr)]` enums
+            Value::String(name) => visitor.visit_enum(name.into_deserializer()),
+            _ => Err(self.invalid_type(&"an enum")),
+        }
+    }
+
+    fn deserialize_identifier<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        self.deserialize_any(visitor)
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_unit()
+    }
+
+    serde::forward_to_deserialize_any! {
+        i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 str string bytes byte_buf seq tuple
+        tuple_struct map
+    }
+}
+
+struct SeqDeserializer(std::vec::IntoIter<Value>);
+
+impl<'de> de::SeqAccess<'de> for SeqDeserializer {
+    type Error = EncoderError;
+
+    fn next_element_seed<T: de::DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>> {
+        self.0
+            .next()
+            .map(|value| seed.deserialize(ValueDeserializer(value)))
+            .transpose()
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.0.len())
+    }
+}
+
+struct MapDeserializer {
+    entries: std::vec::IntoIter<(Value, Value)>,
+    value: Option<Value>,
+}
+
+impl<'de> de::MapAccess<'de> for MapDeserializer {
+    type Error = EncoderError;
+
+    fn next_key_seed<K: de::DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>> {
+        match self.entries.next() {
+            Some((key, value)) => {
+                self.value = Some(value);
+                seed.deserialize(ValueDeserializer(key)).map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V: de::DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value> {
+        let value = self
+            .value
+            .take()
+            .ok_or_else(|| decode_error("Map value requested before its key"))?;
+        seed.deserialize(ValueDeserializer(value))
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.entries.len())
+    }
+}
+
+struct StructDeserializer {
+    fields: std::vec::IntoIter<(&'static str, Value)>,
+    value: Option<Value>,
+}
+
+impl<'de> de::MapAccess<'de> for StructDeserializer {
+    type Error = EncoderError;
+
+    fn next_key_seed<K: de::DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>> {
+        match self.fields.next() {
+            Some((name, value)) => {
+                self.value = Some(value);
+                let name: de::value::StrDeserializer<EncoderError> = name.into_deserializer();
+                seed.deserialize(name).map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V: de::DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value> {
+        let value = self
+            .value
+            .take()
+            .ok_or_else(|| decode_error("Field value requested before its name"))?;
+        seed.deserialize(ValueDeserializer(value))
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.fields.len())
+    }
+}
+
+struct EnumDeserializer {
+    name: &'static str,
+    fields: VariantFields,
+}
+
+impl<'de> de::EnumAccess<'de> for EnumDeserializer {
+    type Error = EncoderError;
+    type Variant = VariantDeserializer;
+
+    fn variant_seed<V: de::DeserializeSeed<'de>>(
+        self,
+        seed: V,
+    ) -> Result<(V::Value, Self::Variant)> {
+        let name: de::value::StrDeserializer<EncoderError> = self.name.into_deserializer();
+        let name = seed.deserialize(name)?;
+        Ok((name, VariantDeserializer(self.fields)))
+    }
+}
+
+struct VariantDeserializer(VariantFields);
+
+impl<'de> de::VariantAccess<'de> for VariantDeserializer {
+    type Error = EncoderError;
+
+    fn unit_variant(self) -> Result<()> {
+        match self.0 {
+            VariantFields::Unit => Ok(()),
+            _ => Err(decode_error("Expected a unit variant")),
+        }
+    }
+
+    fn newtype_variant_seed<T: de::DeserializeSeed<'de>>(self, seed: T) -> Result<T::Value> {
+        match self.0 {
+            VariantFields::Unnamed(mut values) if values.len() == 1 => {
+                seed.deserialize(ValueDeserializer(values.remove(0)))
+            }
+            _ => Err(decode_error("Expected a tuple variant with one field")),
+        }
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value> {
+        match self.0 {
+            VariantFields::Unnamed(values) => visit_seq(values, visitor),
+            _ => Err(decode_error("Expected a tuple variant")),
+        }
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value> {
+        match self.0 {
+            VariantFields::Named(values) => visit_fields(values, fields, visitor),
+            VariantFields::Unnamed(values) => visit_seq(values, visitor),
+            VariantFields::Unit => Err(decode_error("Expected a struct variant")),
+        }
+    }
+}
@@ -0,0 +1,91 @@
+//! Wiping decoded secrets, for credential- and token-carrying message types.
+//!
+//! Derived decoders hold each `#[senax(sensitive)]` field in [`Zeroizing`] storage until the
+//! value is built, so a secret decoded before a later field fails (or that fails its own
+//! `max_len`/`min`/`max` check) is overwritten with zeros instead of being left in freed
+//! memory. Once decoding succeeds the value is moved into the struct unchanged; declare the
+//! field as `Zeroizing<T>` to keep wiping it after that.
+//!
+//! With this feature enabled, the plaintext buffers of `#[senax(encrypt)]` fields are wiped
+//! as well.
+//!
+//! The input buffer belongs to the caller and is not wiped, and values decoded as `Bytes`
+//! share it.
+
+use crate::{Decoder, Encoder, Packer, Result, Unpacker};
+use bytes::{Bytes, BytesMut};
+
+pub use zeroize::{Zeroize, Zeroizing};
+
+/// Runs `check` on a decoded sensitive value, wiping the value if the check fails. Used by
+/// derived code.
+pub fn check_sensitive<T: Zeroize>(
+    mut value: T,
+    check: impl FnOnce(&T) -> Result<()>,
+) -> Result<T> {
+    if let Err(e) = check(&value) {
+        value.zeroize();
+        return Err(e);
+    }
+    Ok(value)
+}
+
+/// Wraps a buffer holding plaintext so that it is wiped once the last `Bytes` referring to
+/// it is dropped.
+#[cfg(feature = "chacha20poly1305")]
+pub(crate) fn zeroizing_bytes(buf: Vec<u8>) -> Bytes {
+    Bytes::from_owner(Zeroizing::new(buf))
+}
+
+/// Encodes a `Zeroizing<T>` by encoding the inner value.
+impl<T: Encoder + Zeroize> Encoder for Zeroizing<T> {
+    fn encode(&self, writer: &mut BytesMut) -> Result<()> {
+        (**self).encode(writer)
+    }
+
+    fn is_default(&self) -> bool {
+        T::is_default(self)
+    }
+}
+
+impl<T: Packer + Zeroize> Packer for Zeroizing<T> {
+    fn pack(&self, writer: &mut BytesMut) -> Result<()> {
+        (**self).pack(writer)
+    }
+}
+
+/// Decodes a `Zeroizing<T>` by decoding the inner value and wrapping it.
+impl<T: Decoder + Zeroize> Decoder for Zeroizing<T> {
+    fn decode(reader: &mut Bytes) -> Result<Self> {
+        T::decode(reader).map(Zeroizing::new)
+    }
+}
+
+impl<T: Unpacker + Zeroize> Unpacker for Zeroizing<T> {
+    fn unpack(reader: &mut Bytes) -> Result<Self> {
+        T::unpack(reader).map(Zeroizing::new)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: crate::schema::HasSchema + Zeroize> crate::schema::HasSchema for Zeroizing<T> {
+    fn schema() -> crate::schema::TypeSchema {
+        T::schema()
+    }
+}
+
+impl<T: crate::max_size::BoundedLen + Zeroize> crate::max_size::BoundedLen for Zeroizing<T> {
+    fn bounded_len(&self) -> usize {
+        (**self).bounded_len()
+    }
+}
+
+impl<T: crate::MaxEncodedSize + Zeroize> crate::MaxEncodedSize for Zeroizing<T> {
+    const MAX_ENCODED_SIZE: usize = T::MAX_ENCODED_SIZE;
+}
+
+impl<T: crate::max_size::MaxElementSize + Zeroize> crate::max_size::MaxElementSize
+    for Zeroizing<T>
+{
+    const MAX_ELEMENT_SIZE: usize = T::MAX_ELEMENT_SIZE;
+}
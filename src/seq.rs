@@ -0,0 +1,174 @@
+//! Sequences encoded from fallible sources.
+//!
+//! [`encode_seq_try_from_iter`] writes the items of an iterator of `Result`s (a database
+//! cursor, a paginated API) without collecting them first. The number of items is not known up
+//! front, so the sequence is written as chunks, each a `Vec` length header followed by up to
+//! [`SEQ_CHUNK_LEN`] items, and ended by an empty chunk:
+//!
+//! ```text
+//! [TAG_ARRAY_VEC_SET_LONG, TAG_U16, len: u16 little-endian] [item]... ... [TAG_ARRAY_VEC_SET_BASE]
+//! ```
+//!
+//! Chunk headers have a fixed width, so each one is patched in place once its chunk is full
+//! and no item is copied. If the source or an item fails, everything written for the sequence
+//! is truncated away and the buffer is left as it was. [`decode_seq`] and [`SeqIter`] read the
+//! sequence back.
+
+use crate::core::{decode_vec_length, TAG_ARRAY_VEC_SET_BASE, TAG_ARRAY_VEC_SET_LONG, TAG_U16};
+use crate::{Decoder, Encoder, EncoderError, Result};
+use bytes::{BufMut, Bytes, BytesMut};
+use std::marker::PhantomData;
+
+/// Maximum number of items in one chunk.
+pub const SEQ_CHUNK_LEN: usize = u16::MAX as usize;
+
+/// Length of a chunk header: the `Vec` tag, the `u16` tag and the length.
+const CHUNK_HEADER_LEN: usize = 4;
+
+/// Error from [`encode_seq_try_from_iter`].
+#[derive(Debug, thiserror::Error)]
+pub enum TryEncodeError<E> {
+    /// The source iterator yielded an error.
+    #[error("Sequence source failed: {0}")]
+    Source(E),
+    /// An item could not be encoded.
+    #[error(transparent)]
+    Encode(#[from] EncoderError),
+}
+
+/// Encodes the items of `iter` as a chunked sequence and returns the number of items written.
+///
+/// Stops at the first `Err` from the iterator or from encoding an item. In that case `writer`
+/// is truncated back to its length before the call, so no partial sequence is left behind.
+///
+/// # Example
+/// ```rust
+/// use bytes::BytesMut;
+/// use senax_encoder::seq::{decode_seq, encode_seq_try_from_iter, TryEncodeError};
+///
+/// let rows: Vec<Result<u32, String>> = vec![Ok(1), Ok(2), Ok(3)];
+/// let mut writer = BytesMut::new();
+/// assert_eq!(encode_seq_try_from_iter(rows, &mut writer).unwrap(), 3);
+/// assert_eq!(decode_seq::<u32>(&mut writer.clone().freeze()).unwrap(), vec![1, 2, 3]);
+///
+/// let failing: Vec<Result<u32, String>> = vec![Ok(1), Err("cursor closed".into())];
+/// let before = writer.len();
+/// let err = encode_seq_try_from_iter(failing, &mut writer).unwrap_err();
+/// assert!(matches!(err, TryEncodeError::Source(message) if message == "cursor closed"));
+/// assert_eq!(writer.len(), before);
+/// ```
+pub fn encode_seq_try_from_iter<T, E, I>(
+    iter: I,
+    writer: &mut BytesMut,
+) -> std::result::Result<usize, TryEncodeError<E>>
+where
+    T: Encoder,
+    I: IntoIterator<Item = std::result::Result<T, E>>,
+{
+    let start = writer.len();
+    let result = write_chunks(iter, writer);
+    if result.is_err() {
+        writer.truncate(start);
+    }
+    result
+}
+
+fn write_chunks<T, E, I>(
+    iter: I,
+    writer: &mut BytesMut,
+) -> std::result::Result<usize, TryEncodeError<E>>
+where
+    T: Encoder,
+    I: IntoIterator<Item = std::result::Result<T, E>>,
+{
+    let mut total = 0;
+    let mut header = None;
+    let mut chunk_len = 0;
+    for item in iter {
+        let item = item.map_err(TryEncodeError::Source)?;
+        let position = *header.get_or_insert_with(|| {
+            let position = writer.len();
+            writer.put_slice(&[TAG_ARRAY_VEC_SET_LONG, TAG_U16, 0, 0]);
+            position
+        });
+        item.encode(writer)?;
+        crate::config::check_encode_limit(writer.len())?;
+        chunk_len += 1;
+        total += 1;
+        if chunk_len == SEQ_CHUNK_LEN {
+            patch_chunk_len(writer, position, chunk_len);
+            header = None;
+            chunk_len = 0;
+        }
+    }
+    if let Some(position) = header {
+        patch_chunk_len(writer, position, chunk_len);
+    }
+    writer.put_u8(TAG_ARRAY_VEC_SET_BASE);
+    Ok(total)
+}
+
+fn patch_chunk_len(writer: &mut BytesMut, position: usize, len: usize) {
+    writer[position + 2..position + CHUNK_HEADER_LEN].copy_from_slice(&(len as u16).to_le_bytes());
+}
+
+/// Decodes a whole sequence written by [`encode_seq_try_from_iter`].
+pub fn decode_seq<T: Decoder>(reader: &mut Bytes) -> Result<Vec<T>> {
+    SeqIter::new(reader).collect()
+}
+
+/// Iterates over the items of a sequence written by [`encode_seq_try_from_iter`], decoding one
+/// item at a time.
+///
+/// Iteration ends after the terminating empty chunk, leaving the reader just past it, or at
+/// the first error.
+pub struct SeqIter<'a, T> {
+    reader: &'a mut Bytes,
+    remaining_in_chunk: usize,
+    done: bool,
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<'a, T: Decoder> SeqIter<'a, T> {
+    /// Starts reading a sequence at the current position of `reader`.
+    pub fn new(reader: &'a mut Bytes) -> Self {
+        Self {
+            reader,
+            remaining_in_chunk: 0,
+            done: false,
+            marker: PhantomData,
+        }
+    }
+
+    fn read_item(&mut self) -> Result<Option<T>> {
+        if self.remaining_in_chunk == 0 {
+            self.remaining_in_chunk = decode_vec_length(self.reader)?;
+            if self.remaining_in_chunk == 0 {
+                return Ok(None);
+            }
+        }
+        self.remaining_in_chunk -= 1;
+        T::decode(self.reader).map(Some)
+    }
+}
+
+impl<T: Decoder> Iterator for SeqIter<'_, T> {
+    type Item = Result<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        match self.read_item() {
+            Ok(Some(item)) => Some(Ok(item)),
+            Ok(None) => {
+                self.done = true;
+                None
+            }
+            Err(e) => {
+                self.done = true;
+                Some(Err(e))
+            }
+        }
+    }
+}
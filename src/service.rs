@@ -0,0 +1,342 @@
+//! Request/response services over any framed transport.
+//!
+//! [`senax_service!`](crate::senax_service) declares a service trait with one method per RPC and
+//! generates a client and a server for it. Each method gets an ID derived from its name like a
+//! field ID ([`field_id_from_name`](crate::core::field_id_from_name)), so methods can be added
+//! and reordered without breaking peers. Messages are framed as
+//!
+//! ```text
+//! request:  [method_id] [request]
+//! response: [0] [response]  or  [1] [Status]
+//! ```
+//!
+//! where `method_id` uses the field ID encoding and values are written with
+//! [`Encoder::encode`]. The client sends requests through a [`Transport`]; [`FramedTransport`]
+//! and [`serve`] carry them over any `Read + Write` stream in the
+//! [record log](crate::record_log) frame format.
+
+use crate::record_log::{RecordLogReader, RecordLogWriter};
+use crate::{Decode, Decoder, Encode, Encoder, EncoderError};
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+use std::io::{Read, Write};
+
+/// Response marker of a successful call.
+pub const RESPONSE_OK: u8 = 0;
+/// Response marker of a failed call; a [`Status`] follows.
+pub const RESPONSE_ERROR: u8 = 1;
+
+/// The error returned by a service method, sent back to the client.
+///
+/// Codes follow gRPC's status codes; the ones the generated server produces itself are
+/// provided as constants.
+#[derive(Encode, Decode, Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[senax(crate = "crate")]
+#[error("Service error {code}: {message}")]
+pub struct Status {
+    /// The status code.
+    pub code: u32,
+    /// A description for the caller.
+    pub message: String,
+}
+
+impl Status {
+    /// The request could not be decoded.
+    pub const INVALID_ARGUMENT: u32 = 3;
+    /// The server does not implement the requested method.
+    pub const UNIMPLEMENTED: u32 = 12;
+    /// The server failed to encode its response.
+    pub const INTERNAL: u32 = 13;
+
+    /// Creates a status.
+    pub fn new(code: u32, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+/// Error returned by generated client methods.
+#[derive(Debug, thiserror::Error)]
+pub enum CallError {
+    /// The request or response could not be encoded, decoded or transported.
+    #[error(transparent)]
+    Transport(#[from] EncoderError),
+    /// The server returned an error.
+    #[error(transparent)]
+    Status(#[from] Status),
+}
+
+/// Carries one encoded request to a server and returns the encoded response.
+///
+/// Implemented for closures, which is convenient for in-process servers and tests, and by
+/// [`FramedTransport`] for byte streams.
+pub trait Transport {
+    /// Sends `request` and waits for the response.
+    fn call(&mut self, request: Bytes) -> crate::Result<Bytes>;
+}
+
+impl<F: FnMut(Bytes) -> crate::Result<Bytes>> Transport for F {
+    fn call(&mut self, request: Bytes) -> crate::Result<Bytes> {
+        self(request)
+    }
+}
+
+/// A [`Transport`] that writes each request as one record log frame to a stream and reads
+/// the response frame.
+pub struct FramedTransport<S> {
+    stream: S,
+}
+
+impl<S> FramedTransport<S> {
+    /// Creates a transport over `stream`, e.g. a `TcpStream`.
+    pub fn new(stream: S) -> Self {
+        Self { stream }
+    }
+
+    /// Returns the underlying stream.
+    pub fn into_inner(self) -> S {
+        self.stream
+    }
+}
+
+impl<S: Read + Write> Transport for FramedTransport<S> {
+    fn call(&mut self, request: Bytes) -> crate::Result<Bytes> {
+        let mut writer = RecordLogWriter::new(&mut self.stream);
+        writer.append(&request)?;
+        writer.flush()?;
+        RecordLogReader::new(&mut self.stream)
+            .read_record()?
+            .ok_or(EncoderError::eof(4, 0))
+    }
+}
+
+/// Answers requests read from `stream` with `handler` until the peer closes it.
+///
+/// `handler` is usually a generated server's `handle` method.
+pub fn serve<S: Read + Write>(
+    mut stream: S,
+    mut handler: impl FnMut(Bytes) -> Bytes,
+) -> crate::Result<()> {
+    while let Some(request) = RecordLogReader::new(&mut stream).read_record()? {
+        let response = handler(request);
+        let mut writer = RecordLogWriter::new(&mut stream);
+        writer.append(&response)?;
+        writer.flush()?;
+    }
+    Ok(())
+}
+
+/// Encodes a request for `method_id`. Used by generated clients.
+#[doc(hidden)]
+pub fn encode_request<T: Encoder>(method_id: u64, request: &T) -> crate::Result<Bytes> {
+    let mut writer = BytesMut::new();
+    crate::core::write_field_id_optimized(&mut writer, method_id)?;
+    request.encode(&mut writer)?;
+    Ok(writer.freeze())
+}
+
+/// Decodes a response. Used by generated clients.
+#[doc(hidden)]
+pub fn decode_response<T: Decoder>(mut response: Bytes) -> Result<T, CallError> {
+    if !response.has_remaining() {
+        return Err(EncoderError::eof(1, 0).into());
+    }
+    match response.get_u8() {
+        RESPONSE_OK => Ok(T::decode(&mut response)?),
+        RESPONSE_ERROR => Err(Status::decode(&mut response)?.into()),
+        other => Err(EncoderError::Decode(format!("Invalid response marker: {}", other)).into()),
+    }
+}
+
+/// Decodes the request of a method. Used by generated servers.
+#[doc(hidden)]
+pub fn decode_request<T: Decoder>(request: &mut Bytes) -> Result<T, Status> {
+    T::decode(request).map_err(|e| Status::new(Status::INVALID_ARGUMENT, e.to_string()))
+}
+
+/// Encodes the result of a method. Used by generated servers.
+#[doc(hidden)]
+pub fn encode_response<T: Encoder>(result: Result<T, Status>) -> Bytes {
+    let mut writer = BytesMut::new();
+    let status = match result {
+        Ok(response) => {
+            writer.put_u8(RESPONSE_OK);
+            match response.encode(&mut writer) {
+                Ok(()) => return writer.freeze(),
+                Err(e) => Status::new(Status::INTERNAL, e.to_string()),
+            }
+        }
+        Err(status) => status,
+    };
+    writer.clear();
+    writer.put_u8(RESPONSE_ERROR);
+    // A status is a number and a string, which always encode
+    let _ = status.encode(&mut writer);
+    writer.freeze()
+}
+
+/// Declares a service trait together with a client and a server for it.
+///
+/// Every method takes one request type and returns one response type, both implementing
+/// `Encoder` and `Decoder`. Method IDs are `field_id_from_name` of the method name; duplicate
+/// IDs are rejected at compile time. The macro generates:
+///
+/// * the trait, with `fn method(&self, request: Req) -> Result<Resp, Status>` per method;
+/// * the client struct, generic over a [`Transport`], with `new`, `into_inner` and
+///   `fn method(&mut self, request: &Req) -> Result<Resp, CallError>` per method;
+/// * the server struct, generic over the trait, with `new`, `METHOD_IDS`, `method_name(id)`
+///   and `handle(&self, request: Bytes) -> Bytes`, which answers unknown methods with
+///   [`Status::UNIMPLEMENTED`].
+///
+/// # Example
+/// ```rust
+/// use senax_encoder::service::Status;
+/// use senax_encoder::{senax_service, Decode, Encode};
+///
+/// #[derive(Encode, Decode, Debug, PartialEq)]
+/// pub struct HelloRequest { pub name: String }
+///
+/// #[derive(Encode, Decode, Debug, PartialEq)]
+/// pub struct HelloReply { pub message: String }
+///
+/// senax_service! {
+///     pub service Greeter {
+///         client: GreeterClient,
+///         server: GreeterServer,
+///         fn say_hello(HelloRequest) -> HelloReply;
+///     }
+/// }
+///
+/// struct MyGreeter;
+///
+/// impl Greeter for MyGreeter {
+///     fn say_hello(&self, request: HelloRequest) -> Result<HelloReply, Status> {
+///         Ok(HelloReply { message: format!("Hello, {}!", request.name) })
+///     }
+/// }
+///
+/// let server = GreeterServer::new(MyGreeter);
+/// let mut client = GreeterClient::new(|request| Ok(server.handle(request)));
+/// let reply = client.say_hello(&HelloRequest { name: "Ana".into() }).unwrap();
+/// assert_eq!(reply.message, "Hello, Ana!");
+/// ```
+#[macro_export]
+macro_rules! senax_service {
+    (
+        $(#[$meta:meta])*
+        $vis:vis service $name:ident {
+            client: $client:ident,
+            server: $server:ident,
+            $(
+                $(#[$method_meta:meta])*
+                fn $method:ident ( $req:ty ) -> $resp:ty;
+            )+
+        }
+    ) => {
+        $(#[$meta])*
+        $vis trait $name {
+            $(
+                $(#[$method_meta])*
+                fn $method(&self, request: $req) -> ::core::result::Result<$resp, $crate::service::Status>;
+            )+
+        }
+
+        const _: () = assert!(
+            $crate::registry::type_ids_are_valid(&[
+                $($crate::core::field_id_from_name(stringify!($method))),+
+            ]),
+            "senax_service!: method IDs must be unique"
+        );
+
+        #[doc = concat!("Client for the `", stringify!($name), "` service.")]
+        $vis struct $client<T> {
+            transport: T,
+        }
+
+        impl<T: $crate::service::Transport> $client<T> {
+            /// Creates a client sending requests through `transport`.
+            pub fn new(transport: T) -> Self {
+                Self { transport }
+            }
+
+            /// Returns the underlying transport.
+            pub fn into_inner(self) -> T {
+                self.transport
+            }
+
+            $(
+                $(#[$method_meta])*
+                pub fn $method(
+                    &mut self,
+                    request: &$req,
+                ) -> ::core::result::Result<$resp, $crate::service::CallError> {
+                    const METHOD_ID: u64 = $crate::core::field_id_from_name(stringify!($method));
+                    let request = $crate::service::encode_request(METHOD_ID, request)?;
+                    let response = self.transport.call(request)?;
+                    $crate::service::decode_response(response)
+                }
+            )+
+        }
+
+        #[doc = concat!("Server dispatching requests to a `", stringify!($name), "` implementation.")]
+        $vis struct $server<S> {
+            service: S,
+        }
+
+        impl<S> $server<S> {
+            /// IDs of the service's methods, in declaration order.
+            pub const METHOD_IDS: &'static [u64] = &[
+                $($crate::core::field_id_from_name(stringify!($method))),+
+            ];
+
+            /// Returns the name of the method with ID `method_id`.
+            pub fn method_name(method_id: u64) -> ::core::option::Option<&'static str> {
+                $(
+                    if method_id == $crate::core::field_id_from_name(stringify!($method)) {
+                        return ::core::option::Option::Some(stringify!($method));
+                    }
+                )+
+                ::core::option::Option::None
+            }
+
+            /// Returns the service implementation.
+            pub fn into_inner(self) -> S {
+                self.service
+            }
+        }
+
+        impl<S: $name> $server<S> {
+            /// Creates a server for `service`.
+            pub fn new(service: S) -> Self {
+                Self { service }
+            }
+
+            /// Decodes a request, calls the method it names and returns the encoded response.
+            pub fn handle(&self, mut request: $crate::bytes::Bytes) -> $crate::bytes::Bytes {
+                let method_id = match $crate::core::read_field_id_optimized(&mut request) {
+                    Ok(id) => id,
+                    Err(e) => {
+                        return $crate::service::encode_response::<()>(Err(
+                            $crate::service::Status::new(
+                                $crate::service::Status::INVALID_ARGUMENT,
+                                e.to_string(),
+                            ),
+                        ))
+                    }
+                };
+                $(
+                    if method_id == $crate::core::field_id_from_name(stringify!($method)) {
+                        let result = $crate::service::decode_request::<$req>(&mut request)
+                            .and_then(|request| self.service.$method(request));
+                        return $crate::service::encode_response(result);
+                    }
+                )+
+                $crate::service::encode_response::<()>(Err($crate::service::Status::new(
+                    $crate::service::Status::UNIMPLEMENTED,
+                    format!("Unknown method ID {} for {}", method_id, stringify!($name)),
+                )))
+            }
+        }
+    };
+}
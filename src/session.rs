@@ -0,0 +1,534 @@
+//! Session codec that shares a dictionary across the messages of one connection.
+//!
+//! Chatty protocols send the same strings (enum names, user IDs, status texts) and the same
+//! 64-bit field and variant IDs in every message. [`SessionEncoder`] and [`SessionDecoder`]
+//! keep a dictionary of both for the lifetime of a connection: the first message that contains
+//! a string or long ID defines it, later messages refer to it by index.
+//!
+//! Each call to [`SessionEncoder::encode`] produces one frame:
+//!
+//! ```text
+//! [magic: 0xA55E] [kind] [generation] [sequence] [value]
+//! ```
+//!
+//! The value is the usual encode format, except that inside structs, enums, options, lists,
+//! tuples and maps:
+//!
+//! * a string may be replaced by [`TAG_SESSION_STRING_REF`] and a dictionary index, or
+//!   preceded by [`TAG_SESSION_STRING_DEFINE`] to add it to the dictionary;
+//! * a field or variant ID above [`MAX_SHORT_FIELD_ID`] may be replaced by
+//!   [`SESSION_ID_REF`] and a dictionary index, or written as [`SESSION_ID_DEFINE`] and the
+//!   ID to add it to the dictionary.
+//!
+//! A [`SESSION_FRAME_RESET`] frame clears both dictionaries and starts a new generation. The
+//! encoder sends one first and after every [`SessionEncoder::reset`]. The sequence number
+//! counts frames within a generation, so a decoder notices lost or reordered frames; it then
+//! rejects data frames until the next reset frame ([`SessionDecoder::needs_reset`]), which the
+//! application requests from its peer.
+
+use crate::core::{
+    decode_vec_length, read_field_id_optimized, skip_value, MAX_SHORT_FIELD_ID,
+    TAG_ARRAY_VEC_SET_BASE, TAG_ARRAY_VEC_SET_LONG, TAG_ENUM, TAG_ENUM_NAMED, TAG_ENUM_UNNAMED,
+    TAG_MAP, TAG_SOME, TAG_STRING_BASE, TAG_STRING_LONG, TAG_STRUCT_NAMED, TAG_STRUCT_UNNAMED,
+    TAG_TUPLE,
+};
+use crate::validate::MAX_VALIDATION_DEPTH;
+use crate::{Decoder, Encoder, EncoderError, Result};
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+use std::collections::HashMap;
+
+/// Magic number for session frames (0xA55E in little-endian).
+pub const SESSION_MAGIC: u16 = 0xA55E;
+
+/// Frame kind of a frame that uses the current dictionary.
+pub const SESSION_FRAME_DATA: u8 = 1;
+/// Frame kind of a frame that clears the dictionary and starts a new generation.
+pub const SESSION_FRAME_RESET: u8 = 2;
+
+/// Value tag: a string from the dictionary; the index follows as a `usize`.
+pub const TAG_SESSION_STRING_REF: u8 = 240;
+/// Value tag: the string that follows is added to the dictionary.
+pub const TAG_SESSION_STRING_DEFINE: u8 = 241;
+/// Field ID marker: an ID from the dictionary; the index follows as a `usize`.
+pub const SESSION_ID_REF: u8 = 254;
+/// Field ID marker: the `u64` ID that follows (little-endian) is added to the dictionary.
+pub const SESSION_ID_DEFINE: u8 = 253;
+
+/// Limits of a session dictionary.
+///
+/// Both ends of a connection should use the same configuration; the decoder rejects frames
+/// that would grow its dictionary beyond `max_entries`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SessionConfig {
+    /// Shortest encoded string (tag and length included) worth interning.
+    pub min_string_len: usize,
+    /// Longest encoded string worth interning.
+    pub max_string_len: usize,
+    /// Maximum number of strings, and separately of IDs, in the dictionary. When it is full,
+    /// new strings and IDs are written as usual.
+    pub max_entries: usize,
+}
+
+impl Default for SessionConfig {
+    fn default() -> Self {
+        Self {
+            min_string_len: 4,
+            max_string_len: 256,
+            max_entries: 4096,
+        }
+    }
+}
+
+/// Writing end of a session. See the [module documentation](self).
+///
+/// # Example
+/// ```rust
+/// use senax_encoder::session::{SessionDecoder, SessionEncoder};
+/// use senax_encoder::{Decode, Encode};
+///
+/// #[derive(Encode, Decode, Debug, PartialEq)]
+/// struct Status { service: String, state: String }
+///
+/// let mut encoder = SessionEncoder::new();
+/// let mut decoder = SessionDecoder::new();
+/// let status = Status { service: "billing-api".into(), state: "healthy".into() };
+///
+/// let first = encoder.encode(&status).unwrap();
+/// let second = encoder.encode(&status).unwrap();
+/// assert!(second.len() < first.len());
+///
+/// assert_eq!(decoder.decode::<Status>(&mut first.clone()).unwrap(), status);
+/// assert_eq!(decoder.decode::<Status>(&mut second.clone()).unwrap(), status);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct SessionEncoder {
+    config: SessionConfig,
+    strings: HashMap<Bytes, usize>,
+    ids: HashMap<u64, usize>,
+    generation: u32,
+    sequence: u64,
+    started: bool,
+}
+
+impl SessionEncoder {
+    /// Creates an encoder with the default [`SessionConfig`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates an encoder with the given limits.
+    pub fn with_config(config: SessionConfig) -> Self {
+        Self {
+            config,
+            ..Self::default()
+        }
+    }
+
+    /// The current generation, incremented by every reset frame.
+    pub fn generation(&self) -> u32 {
+        self.generation
+    }
+
+    /// Number of strings and IDs in the dictionary.
+    pub fn dictionary_len(&self) -> usize {
+        self.strings.len() + self.ids.len()
+    }
+
+    /// Clears the dictionary; the next frame is a reset frame.
+    ///
+    /// Call this when the peer reports [`SessionDecoder::needs_reset`] or reconnects.
+    pub fn reset(&mut self) {
+        self.strings.clear();
+        self.ids.clear();
+        self.started = false;
+    }
+
+    /// Encodes `value` as the next frame of the session.
+    ///
+    /// If encoding fails, the dictionary and sequence number are left unchanged.
+    pub fn encode<T: Encoder>(&mut self, value: &T) -> Result<Bytes> {
+        let mut payload = BytesMut::new();
+        value.encode(&mut payload)?;
+
+        let reset = !self.started;
+        let generation = if reset {
+            self.generation.wrapping_add(1)
+        } else {
+            self.generation
+        };
+        let sequence = if reset { 0 } else { self.sequence };
+
+        let mut writer = BytesMut::with_capacity(payload.len() + 8);
+        writer.put_u16_le(SESSION_MAGIC);
+        writer.put_u8(if reset {
+            SESSION_FRAME_RESET
+        } else {
+            SESSION_FRAME_DATA
+        });
+        generation.encode(&mut writer)?;
+        sequence.encode(&mut writer)?;
+
+        let mut interner = Interner {
+            session: self,
+            added_strings: Vec::new(),
+            added_ids: Vec::new(),
+        };
+        let result = copy_value(&mut interner, &mut payload.freeze(), &mut writer, 0);
+        let Interner {
+            added_strings,
+            added_ids,
+            ..
+        } = interner;
+        if let Err(e) = result {
+            for key in added_strings {
+                self.strings.remove(&key);
+            }
+            for id in added_ids {
+                self.ids.remove(&id);
+            }
+            return Err(e);
+        }
+
+        self.started = true;
+        self.generation = generation;
+        self.sequence = sequence + 1;
+        Ok(writer.freeze())
+    }
+}
+
+/// Reading end of a session. See the [module documentation](self).
+#[derive(Debug, Clone, Default)]
+pub struct SessionDecoder {
+    config: SessionConfig,
+    strings: Vec<Bytes>,
+    ids: Vec<u64>,
+    generation: u32,
+    next_sequence: u64,
+    in_sync: bool,
+}
+
+impl SessionDecoder {
+    /// Creates a decoder with the default [`SessionConfig`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a decoder with the given limits.
+    pub fn with_config(config: SessionConfig) -> Self {
+        Self {
+            config,
+            ..Self::default()
+        }
+    }
+
+    /// The generation of the last reset frame.
+    pub fn generation(&self) -> u32 {
+        self.generation
+    }
+
+    /// Number of strings and IDs in the dictionary.
+    pub fn dictionary_len(&self) -> usize {
+        self.strings.len() + self.ids.len()
+    }
+
+    /// Returns `true` until a reset frame has been read, and after a frame was lost,
+    /// reordered or malformed. Data frames are rejected in that state; ask the peer to call
+    /// [`SessionEncoder::reset`].
+    pub fn needs_reset(&self) -> bool {
+        !self.in_sync
+    }
+
+    /// Decodes the next frame of the session into `T`.
+    pub fn decode<T: Decoder>(&mut self, frame: &mut Bytes) -> Result<T> {
+        let mut payload = self.decode_payload(frame)?;
+        T::decode(&mut payload)
+    }
+
+    /// Reads the next frame of the session and returns its value in the usual encode format
+    /// (without magic), with every dictionary reference resolved.
+    pub fn decode_payload(&mut self, frame: &mut Bytes) -> Result<Bytes> {
+        if frame.remaining() < 3 {
+            return Err(EncoderError::eof(3, frame.remaining()));
+        }
+        let magic = frame.get_u16_le();
+        if magic != SESSION_MAGIC {
+            return Err(EncoderError::Decode(format!(
+                "Invalid session magic: expected 0x{:04X}, got 0x{:04X}",
+                SESSION_MAGIC, magic
+            )));
+        }
+        let kind = frame.get_u8();
+        let generation = u32::decode(frame)?;
+        let sequence = u64::decode(frame)?;
+        match kind {
+            SESSION_FRAME_RESET => {
+                self.strings.clear();
+                self.ids.clear();
+                self.generation = generation;
+                self.next_sequence = 0;
+                self.in_sync = true;
+            }
+            SESSION_FRAME_DATA => {}
+            other => {
+                return Err(EncoderError::Decode(format!(
+                    "Unknown session frame kind: {}",
+                    other
+                )))
+            }
+        }
+        if !self.in_sync {
+            return Err(EncoderError::Decode(
+                "Session out of sync: waiting for a reset frame".to_string(),
+            ));
+        }
+        if generation != self.generation || sequence != self.next_sequence {
+            self.in_sync = false;
+            return Err(EncoderError::Decode(format!(
+                "Session out of sync: expected generation {} frame {}, got generation {} frame {}",
+                self.generation, self.next_sequence, generation, sequence
+            )));
+        }
+
+        let mut writer = BytesMut::with_capacity(frame.remaining() * 2);
+        let mut resolver = Resolver { session: self };
+        if let Err(e) = copy_value(&mut resolver, frame, &mut writer, 0) {
+            // Definitions in the rest of the frame are lost
+            self.in_sync = false;
+            return Err(e);
+        }
+        self.next_sequence += 1;
+        Ok(writer.freeze())
+    }
+}
+
+/// Rewrites the strings and field IDs met by [`copy_value`].
+trait Transcoder {
+    /// Copies the string (or session string tag) at the start of `reader`.
+    fn string(&mut self, reader: &mut Bytes, out: &mut BytesMut) -> Result<()>;
+
+    /// Copies the field or variant ID at the start of `reader` and returns it.
+    fn field_id(&mut self, reader: &mut Bytes, out: &mut BytesMut) -> Result<u64>;
+}
+
+struct Interner<'a> {
+    session: &'a mut SessionEncoder,
+    added_strings: Vec<Bytes>,
+    added_ids: Vec<u64>,
+}
+
+impl Transcoder for Interner<'_> {
+    fn string(&mut self, reader: &mut Bytes, out: &mut BytesMut) -> Result<()> {
+        let encoded = take(reader, skip_value)?;
+        let config = &self.session.config;
+        if encoded.len() < config.min_string_len || encoded.len() > config.max_string_len {
+            out.put_slice(&encoded);
+        } else if let Some(index) = self.session.strings.get(&encoded) {
+            out.put_u8(TAG_SESSION_STRING_REF);
+            index.encode(out)?;
+        } else if self.session.strings.len() < config.max_entries {
+            out.put_u8(TAG_SESSION_STRING_DEFINE);
+            out.put_slice(&encoded);
+            let index = self.session.strings.len();
+            self.session.strings.insert(encoded.clone(), index);
+            self.added_strings.push(encoded);
+        } else {
+            out.put_slice(&encoded);
+        }
+        Ok(())
+    }
+
+    fn field_id(&mut self, reader: &mut Bytes, out: &mut BytesMut) -> Result<u64> {
+        let encoded = take(reader, |r| read_field_id_optimized(r).map(drop))?;
+        let id = read_field_id_optimized(&mut encoded.clone())?;
+        if id <= MAX_SHORT_FIELD_ID {
+            out.put_slice(&encoded);
+        } else if let Some(index) = self.session.ids.get(&id) {
+            out.put_u8(SESSION_ID_REF);
+            index.encode(out)?;
+        } else if self.session.ids.len() < self.session.config.max_entries {
+            out.put_u8(SESSION_ID_DEFINE);
+            out.put_u64_le(id);
+            self.session.ids.insert(id, self.session.ids.len());
+            self.added_ids.push(id);
+        } else {
+            out.put_slice(&encoded);
+        }
+        Ok(id)
+    }
+}
+
+struct Resolver<'a> {
+    session: &'a mut SessionDecoder,
+}
+
+impl Resolver<'_> {
+    fn check_room(&self, len: usize) -> Result<()> {
+        if len >= self.session.config.max_entries {
+            return Err(EncoderError::Decode(format!(
+                "Session dictionary exceeds {} entries",
+                self.session.config.max_entries
+            )));
+        }
+        Ok(())
+    }
+}
+
+impl Transcoder for Resolver<'_> {
+    fn string(&mut self, reader: &mut Bytes, out: &mut BytesMut) -> Result<()> {
+        match reader[0] {
+            TAG_SESSION_STRING_REF => {
+                reader.advance(1);
+                let index = usize::decode(reader)?;
+                let encoded = self.session.strings.get(index).ok_or_else(|| {
+                    EncoderError::Decode(format!("Unknown session string index: {}", index))
+                })?;
+                out.put_slice(encoded);
+            }
+            TAG_SESSION_STRING_DEFINE => {
+                reader.advance(1);
+                if !matches!(reader.first(), Some(TAG_STRING_BASE..=TAG_STRING_LONG)) {
+                    return Err(EncoderError::Decode(
+                        "Expected a string after a session string definition".to_string(),
+                    ));
+                }
+                self.check_room(self.session.strings.len())?;
+                let encoded = take(reader, skip_value)?;
+                out.put_slice(&encoded);
+                self.session.strings.push(encoded);
+            }
+            _ => out.put_slice(&take(reader, skip_value)?),
+        }
+        Ok(())
+    }
+
+    fn field_id(&mut self, reader: &mut Bytes, out: &mut BytesMut) -> Result<u64> {
+        let id = match reader.first() {
+            Some(&SESSION_ID_REF) => {
+                reader.advance(1);
+                let index = usize::decode(reader)?;
+                *self.session.ids.get(index).ok_or_else(|| {
+                    EncoderError::Decode(format!("Unknown session ID index: {}", index))
+                })?
+            }
+            Some(&SESSION_ID_DEFINE) => {
+                reader.advance(1);
+                if reader.remaining() < 8 {
+                    return Err(EncoderError::eof(8, reader.remaining()));
+                }
+                self.check_room(self.session.ids.len())?;
+                let id = reader.get_u64_le();
+                self.session.ids.push(id);
+                id
+            }
+            _ => read_field_id_optimized(reader)?,
+        };
+        crate::core::write_field_id_optimized(out, id)?;
+        Ok(id)
+    }
+}
+
+/// Runs `read` and returns the bytes it consumed.
+fn take(reader: &mut Bytes, read: impl FnOnce(&mut Bytes) -> Result<()>) -> Result<Bytes> {
+    let before = reader.clone();
+    read(reader)?;
+    Ok(before.slice(..before.len() - reader.len()))
+}
+
+/// Copies one value from `reader` to `out`, passing strings and field IDs inside containers
+/// through `codec`. Other values are copied unchanged.
+fn copy_value<C: Transcoder>(
+    codec: &mut C,
+    reader: &mut Bytes,
+    out: &mut BytesMut,
+    depth: usize,
+) -> Result<()> {
+    if depth > MAX_VALIDATION_DEPTH {
+        return Err(EncoderError::Decode(format!(
+            "Session frame nesting exceeds {} levels",
+            MAX_VALIDATION_DEPTH
+        )));
+    }
+    let Some(&tag) = reader.first() else {
+        return Err(EncoderError::eof(1, 0));
+    };
+    match tag {
+        TAG_STRING_BASE..=TAG_STRING_LONG | TAG_SESSION_STRING_REF | TAG_SESSION_STRING_DEFINE => {
+            codec.string(reader, out)
+        }
+        TAG_SOME => {
+            out.put_u8(reader.get_u8());
+            copy_value(codec, reader, out, depth + 1)
+        }
+        TAG_ARRAY_VEC_SET_BASE..=TAG_ARRAY_VEC_SET_LONG => {
+            let mut len = 0;
+            out.put_slice(&take(reader, |r| {
+                len = decode_vec_length(r)?;
+                Ok(())
+            })?);
+            copy_many(codec, reader, out, len, depth)
+        }
+        TAG_TUPLE | TAG_STRUCT_UNNAMED | TAG_MAP => {
+            let mut len = 0;
+            out.put_slice(&take(reader, |r| {
+                r.advance(1);
+                len = usize::decode(r)?;
+                Ok(())
+            })?);
+            let values = if tag == TAG_MAP {
+                len.saturating_mul(2)
+            } else {
+                len
+            };
+            copy_many(codec, reader, out, values, depth)
+        }
+        TAG_STRUCT_NAMED => {
+            out.put_u8(reader.get_u8());
+            copy_fields(codec, reader, out, depth)
+        }
+        TAG_ENUM | TAG_ENUM_NAMED | TAG_ENUM_UNNAMED => {
+            out.put_u8(reader.get_u8());
+            codec.field_id(reader, out)?;
+            if tag == TAG_ENUM_NAMED {
+                copy_fields(codec, reader, out, depth)
+            } else if tag == TAG_ENUM_UNNAMED {
+                let mut len = 0;
+                out.put_slice(&take(reader, |r| {
+                    len = usize::decode(r)?;
+                    Ok(())
+                })?);
+                copy_many(codec, reader, out, len, depth)
+            } else {
+                Ok(())
+            }
+        }
+        _ => {
+            out.put_slice(&take(reader, skip_value)?);
+            Ok(())
+        }
+    }
+}
+
+fn copy_many<C: Transcoder>(
+    codec: &mut C,
+    reader: &mut Bytes,
+    out: &mut BytesMut,
+    count: usize,
+    depth: usize,
+) -> Result<()> {
+    for _ in 0..count {
+        copy_value(codec, reader, out, depth + 1)?;
+    }
+    Ok(())
+}
+
+fn copy_fields<C: Transcoder>(
+    codec: &mut C,
+    reader: &mut Bytes,
+    out: &mut BytesMut,
+    depth: usize,
+) -> Result<()> {
+    while codec.field_id(reader, out)? != 0 {
+        copy_value(codec, reader, out, depth + 1)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,162 @@
+//! A map keyed by strings with untagged keys.
+//!
+//! [`StringKeyMap`] stores string-keyed data (labels, headers, JSON-like objects) in a
+//! `BTreeMap` and encodes it under [`TAG_MAP_STRING_KEYS`]: the entry count, then each key as
+//! its length and UTF-8 bytes without a per-key type tag, followed by the value. Readers that
+//! only see the payload know every key is a string, as JSON objects require, and each key is
+//! one byte shorter.
+//!
+//! Plain `HashMap<String, V>` and `BTreeMap<String, V>` keep the general `TAG_MAP` encoding,
+//! so older readers still decode them. Switching a field to `StringKeyMap` is an explicit
+//! wire-format change: its decoder also accepts a `TAG_MAP` with string keys, so it reads
+//! payloads written before the change, but readers built before `TAG_MAP_STRING_KEYS` existed
+//! cannot read what it writes. `Pack` output is the same as for `BTreeMap<String, V>`.
+
+use crate::core::{read_string_key, write_string_key, TAG_MAP, TAG_MAP_STRING_KEYS};
+use crate::{Decoder, Encoder, EncoderError, Packer, Result, Unpacker};
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+use std::collections::BTreeMap;
+use std::ops::{Deref, DerefMut};
+
+/// A `BTreeMap<String, V>` whose keys are encoded without a type tag.
+///
+/// `StringKeyMap` dereferences to the inner `BTreeMap`, so the usual map methods are available.
+///
+/// # Example
+/// ```rust
+/// use senax_encoder::{decode, encode, StringKeyMap};
+///
+/// let mut labels: StringKeyMap<String> = StringKeyMap::new();
+/// labels.insert("env".into(), "prod".into());
+/// labels.insert("region".into(), "eu-west-1".into());
+///
+/// let bytes = encode(&labels).unwrap();
+/// let decoded: StringKeyMap<String> = decode(&mut bytes.clone()).unwrap();
+/// assert_eq!(decoded, labels);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct StringKeyMap<V>(pub BTreeMap<String, V>);
+
+impl<V> StringKeyMap<V> {
+    /// Creates an empty map.
+    pub fn new() -> Self {
+        Self(BTreeMap::new())
+    }
+
+    /// Returns the inner `BTreeMap`.
+    pub fn into_inner(self) -> BTreeMap<String, V> {
+        self.0
+    }
+}
+
+impl<V> Default for StringKeyMap<V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<V> Deref for StringKeyMap<V> {
+    type Target = BTreeMap<String, V>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<V> DerefMut for StringKeyMap<V> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<V> From<BTreeMap<String, V>> for StringKeyMap<V> {
+    fn from(map: BTreeMap<String, V>) -> Self {
+        Self(map)
+    }
+}
+
+impl<V> FromIterator<(String, V)> for StringKeyMap<V> {
+    fn from_iter<I: IntoIterator<Item = (String, V)>>(iter: I) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+
+impl<V> Extend<(String, V)> for StringKeyMap<V> {
+    fn extend<I: IntoIterator<Item = (String, V)>>(&mut self, iter: I) {
+        self.0.extend(iter)
+    }
+}
+
+impl<V> IntoIterator for StringKeyMap<V> {
+    type Item = (String, V);
+    type IntoIter = std::collections::btree_map::IntoIter<String, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl<'a, V> IntoIterator for &'a StringKeyMap<V> {
+    type Item = (&'a String, &'a V);
+    type IntoIter = std::collections::btree_map::Iter<'a, String, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
+
+impl<V: Encoder> Encoder for StringKeyMap<V> {
+    fn encode(&self, writer: &mut BytesMut) -> Result<()> {
+        writer.put_u8(TAG_MAP_STRING_KEYS);
+        self.len().encode(writer)?;
+        for (key, value) in self {
+            write_string_key(key, writer)?;
+            value.encode(writer)?;
+            crate::config::check_encode_limit(writer.len())?;
+        }
+        Ok(())
+    }
+
+    fn is_default(&self) -> bool {
+        self.is_empty()
+    }
+}
+
+/// Reads a map written by [`StringKeyMap`]'s encoder, or a `TAG_MAP` with string keys.
+impl<V: Decoder> Decoder for StringKeyMap<V> {
+    fn decode(reader: &mut Bytes) -> Result<Self> {
+        if reader.remaining() == 0 {
+            return Err(EncoderError::eof(1, 0));
+        }
+        let tag = reader.get_u8();
+        if tag != TAG_MAP_STRING_KEYS && tag != TAG_MAP {
+            return Err(EncoderError::Decode(format!(
+                "Expected StringKeyMap tag ({} or {}), got {}",
+                TAG_MAP_STRING_KEYS, TAG_MAP, tag
+            )));
+        }
+        let len = usize::decode(reader)?;
+        let mut map = BTreeMap::new();
+        for _ in 0..len {
+            let key = if tag == TAG_MAP_STRING_KEYS {
+                read_string_key(reader)?
+            } else {
+                String::decode(reader)?
+            };
+            map.insert(key, V::decode(reader)?);
+        }
+        Ok(Self(map))
+    }
+}
+
+impl<V: Packer> Packer for StringKeyMap<V> {
+    fn pack(&self, writer: &mut BytesMut) -> Result<()> {
+        self.0.pack(writer)
+    }
+}
+
+impl<V: Unpacker> Unpacker for StringKeyMap<V> {
+    fn unpack(reader: &mut Bytes) -> Result<Self> {
+        BTreeMap::unpack(reader).map(Self)
+    }
+}
@@ -0,0 +1,542 @@
+//! Conformance test vectors for the encode format.
+//!
+//! [`test_vectors`] builds a corpus of payloads covering every tag and the boundaries between
+//! their forms (compact and tagged integers, short and long strings and lists, one-byte and
+//! extended field IDs, ...), plus malformed payloads that a decoder must reject. Each vector
+//! carries the bytes and the expected decoded [`Value`], rendered as JSON by [`value_to_json`],
+//! so implementations of the format in other languages can check themselves against this one.
+//!
+//! [`write_test_vectors`] writes the corpus to a directory as `<name>.bin` (the payload) and
+//! `<name>.json`:
+//!
+//! ```text
+//! {"name": "...", "description": "...", "hex": "a55a...", "expected": <value> | null}
+//! ```
+//!
+//! `expected` is `null` for payloads that must fail to decode. Values are written as objects
+//! with a `type` member; integers and IDs are decimal strings so no precision is lost in JSON.
+
+use crate::core::*;
+use crate::value::{Field, Value, VariantFields};
+use crate::{decode, encode, Encode, Encoder, Result, ENCODE_MAGIC};
+use bytes::{BufMut, Bytes, BytesMut};
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::path::Path;
+
+/// One payload of the conformance corpus.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TestVector {
+    /// A short identifier, also used as the file name.
+    pub name: &'static str,
+    /// What the payload exercises.
+    pub description: &'static str,
+    /// The payload, including the magic number.
+    pub bytes: Bytes,
+    /// The decoded value, or `None` if decoding must fail.
+    pub expected: Option<Value>,
+}
+
+impl TestVector {
+    /// Renders the vector as the JSON document written by [`write_test_vectors`].
+    pub fn to_json(&self) -> String {
+        let expected = match &self.expected {
+            Some(value) => value_to_json(value),
+            None => "null".to_string(),
+        };
+        format!(
+            "{{\"name\":{},\"description\":{},\"hex\":{},\"expected\":{}}}",
+            json_string(self.name),
+            json_string(self.description),
+            json_string(&hex(&self.bytes)),
+            expected
+        )
+    }
+}
+
+#[derive(Encode)]
+#[senax(crate = "crate")]
+struct Unit;
+
+#[derive(Encode)]
+#[senax(crate = "crate")]
+struct Point {
+    x: i32,
+    y: i32,
+}
+
+#[derive(Encode)]
+#[senax(crate = "crate")]
+struct Pair(u8, String);
+
+#[derive(Encode)]
+#[senax(crate = "crate")]
+struct WithOptions {
+    present: Option<u32>,
+    absent: Option<u32>,
+    #[senax(id = 1000)]
+    extended_id: bool,
+}
+
+#[derive(Encode)]
+#[senax(crate = "crate")]
+enum Shape {
+    Empty,
+    Circle { radius: u32 },
+    Line(i8, i8),
+}
+
+/// Builds the conformance corpus.
+pub fn test_vectors() -> Result<Vec<TestVector>> {
+    let mut vectors = Vec::new();
+    let mut add = |name, description, bytes: Bytes| {
+        let expected = decode::<Value>(&mut bytes.clone()).ok();
+        vectors.push(TestVector {
+            name,
+            description,
+            bytes,
+            expected,
+        });
+    };
+
+    // Integers
+    add("uint_zero", "0 as TAG_ZERO", encode(&0u32)?);
+    add("bool_true", "true as TAG_ONE", encode(&true)?);
+    add(
+        "uint_compact_max",
+        "127, the largest compact integer",
+        encode(&127u8)?,
+    );
+    add(
+        "uint_u8",
+        "128 as TAG_U8 followed by the value minus 128",
+        encode(&128u8)?,
+    );
+    add("uint_u16", "u16::MAX as TAG_U16", encode(&u16::MAX)?);
+    add("uint_u32", "u32::MAX as TAG_U32", encode(&u32::MAX)?);
+    add("uint_u64", "u64::MAX as TAG_U64", encode(&u64::MAX)?);
+    add("uint_u128", "u128::MAX as TAG_U128", encode(&u128::MAX)?);
+    add("int_minus_one", "-1 as TAG_NEGATIVE and 0", encode(&-1i32)?);
+    add("int_i64_min", "i64::MIN", encode(&i64::MIN)?);
+    add("int_i128_min", "i128::MIN", encode(&i128::MIN)?);
+
+    // Floats
+    add(
+        "float_string",
+        "1.5f64, written as the string \"1.5e0\"",
+        encode(&1.5f64)?,
+    );
+    add(
+        "float_legacy_f32",
+        "TAG_F32 followed by a little-endian IEEE 754 single",
+        raw(|w| {
+            w.put_u8(TAG_F32);
+            w.put_f32_le(-2.5);
+            Ok(())
+        })?,
+    );
+    add(
+        "float_legacy_f64",
+        "TAG_F64 followed by a little-endian IEEE 754 double",
+        raw(|w| {
+            w.put_u8(TAG_F64);
+            w.put_f64_le(0.1);
+            Ok(())
+        })?,
+    );
+
+    // Strings and binary
+    add("string_empty", "Empty string", encode(&String::new())?);
+    add(
+        "string_utf8",
+        "Short string with multi-byte UTF-8",
+        encode(&"héllo, 世界".to_string())?,
+    );
+    add(
+        "string_short_max",
+        "40-byte string, the longest with the length in the tag",
+        encode(&"a".repeat(40))?,
+    );
+    add(
+        "string_long",
+        "41-byte string as TAG_STRING_LONG and a length",
+        encode(&"b".repeat(41))?,
+    );
+    add(
+        "binary",
+        "Bytes as TAG_BINARY and a length",
+        encode(&Bytes::from_static(&[0, 1, 0xFE, 0xFF]))?,
+    );
+
+    // Option
+    add("option_none", "None", encode(&None::<u8>)?);
+    add("option_some", "Some(5)", encode(&Some(5u8))?);
+    add("option_some_none", "Some(None)", encode(&Some(None::<u8>))?);
+
+    // Collections
+    add("vec_empty", "Empty Vec", encode(&Vec::<u8>::new())?);
+    add(
+        "vec_short_max",
+        "5 elements, the most with the length in the tag",
+        encode(&vec![1u8, 2, 3, 4, 5])?,
+    );
+    add(
+        "vec_long",
+        "6 elements as TAG_ARRAY_VEC_SET_LONG and a length",
+        encode(&vec![1u8, 2, 3, 4, 5, 6])?,
+    );
+    add(
+        "tuple",
+        "(1u8, \"a\", -1i8)",
+        encode(&(1u8, "a".to_string(), -1i8))?,
+    );
+    add(
+        "map",
+        "BTreeMap<u8, bool> as TAG_MAP",
+        encode(&BTreeMap::from([(1u8, true), (2, false)]))?,
+    );
+    add(
+        "map_string_keys",
+        "StringKeyMap<u8> with untagged UTF-8 keys",
+        encode(&crate::StringKeyMap::from(BTreeMap::from([
+            ("a".to_string(), 1u8),
+            ("bc".to_string(), 2),
+        ])))?,
+    );
+    add(
+        "map_flat_keys",
+        "Tuple-keyed map written with #[senax(flat_keys)]",
+        raw(|w| encode_flat_key_map(&BTreeMap::from([((1u8, 2u8), 3u8)]), w))?,
+    );
+    add(
+        "map_id_keys",
+        "IdMap<u8> with one-byte and long field-ID keys",
+        encode(&crate::IdMap::from(BTreeMap::from([
+            (7u64, 1u8),
+            (1000, 2),
+        ])))?,
+    );
+
+    // Structs and enums
+    add("struct_unit", "Unit struct", encode(&Unit)?);
+    add(
+        "struct_named",
+        "Named fields by CRC-64 field ID, then the terminator",
+        encode(&Point { x: 3, y: -4 })?,
+    );
+    add(
+        "struct_unnamed",
+        "Tuple struct",
+        encode(&Pair(7, "seven".into()))?,
+    );
+    add(
+        "struct_options_and_extended_id",
+        "Present Option fields without TAG_SOME, absent ones omitted, and a field ID above 250",
+        encode(&WithOptions {
+            present: Some(9),
+            absent: None,
+            extended_id: true,
+        })?,
+    );
+    add("enum_unit", "Unit variant", encode(&Shape::Empty)?);
+    add(
+        "enum_named",
+        "Variant with named fields",
+        encode(&Shape::Circle { radius: 2 })?,
+    );
+    add(
+        "enum_unnamed",
+        "Variant with unnamed fields",
+        encode(&Shape::Line(-1, 1))?,
+    );
+
+    // Types of optional features, built by hand
+    add(
+        "uuid",
+        "TAG_UUID followed by 16 bytes",
+        raw(|w| {
+            w.put_u8(TAG_UUID);
+            w.put_slice(&[0x55; 16]);
+            Ok(())
+        })?,
+    );
+    add(
+        "chrono_datetime",
+        "TAG_CHRONO_DATETIME, seconds (i64) and nanoseconds (u32)",
+        raw(|w| {
+            w.put_u8(TAG_CHRONO_DATETIME);
+            1_700_000_000i64.encode(w)?;
+            500u32.encode(w)
+        })?,
+    );
+    add(
+        "duration",
+        "TAG_DURATION, seconds (u64) and nanoseconds (u32) for 90.25 s",
+        encode(&std::time::Duration::new(90, 250_000_000))?,
+    );
+    add(
+        "system_time",
+        "TAG_SYSTEM_TIME, seconds (i64) and nanoseconds (u32) since the Unix epoch",
+        encode(&(std::time::UNIX_EPOCH + std::time::Duration::new(1_700_000_000, 500)))?,
+    );
+    add(
+        "decimal",
+        "TAG_DECIMAL, mantissa (i128) and scale (u32) for 12.34",
+        raw(|w| {
+            w.put_u8(TAG_DECIMAL);
+            1234i128.encode(w)?;
+            2u32.encode(w)
+        })?,
+    );
+    add(
+        "json_object",
+        "JSON object {\"n\": null, \"b\": true, \"x\": -2} with JSON tags",
+        raw(|w| {
+            w.put_u8(TAG_JSON_OBJECT);
+            3usize.encode(w)?;
+            write_str("n", w)?;
+            w.put_u8(TAG_JSON_NULL);
+            write_str("b", w)?;
+            w.put_u8(TAG_JSON_BOOL);
+            true.encode(w)?;
+            write_str("x", w)?;
+            w.put_u8(TAG_JSON_NUMBER);
+            w.put_u8(1); // Signed integer marker
+            (-2i64).encode(w)
+        })?,
+    );
+
+    // Payloads that must be rejected
+    add(
+        "invalid_magic",
+        "Wrong magic number",
+        Bytes::from_static(&[0x00, 0x00, TAG_ZERO]),
+    );
+    add(
+        "invalid_unknown_tag",
+        "Tag 255 is not assigned",
+        raw(|w| {
+            w.put_u8(255);
+            Ok(())
+        })?,
+    );
+    add(
+        "invalid_truncated_string",
+        "String length beyond the end of the payload",
+        raw(|w| {
+            w.put_u8(TAG_STRING_BASE + 5);
+            w.put_slice(b"ab");
+            Ok(())
+        })?,
+    );
+    add(
+        "invalid_utf8",
+        "String bytes that are not UTF-8",
+        raw(|w| {
+            w.put_u8(TAG_STRING_BASE + 2);
+            w.put_slice(&[0xC3, 0x28]);
+            Ok(())
+        })?,
+    );
+    add(
+        "invalid_unterminated_struct",
+        "Named struct without the zero terminator",
+        raw(|w| {
+            w.put_u8(TAG_STRUCT_NAMED);
+            write_field_id_optimized(w, 1)?;
+            w.put_u8(TAG_ONE);
+            Ok(())
+        })?,
+    );
+
+    Ok(vectors)
+}
+
+/// Builds a payload whose value is written by `f` after the magic number.
+fn raw(f: impl FnOnce(&mut BytesMut) -> Result<()>) -> Result<Bytes> {
+    let mut writer = BytesMut::new();
+    writer.put_u16_le(ENCODE_MAGIC);
+    f(&mut writer)?;
+    Ok(writer.freeze())
+}
+
+/// Writes every vector of [`test_vectors`] to `dir` as `<name>.bin` and `<name>.json`, and
+/// returns the number of vectors. The directory is created if needed.
+pub fn write_test_vectors(dir: impl AsRef<Path>) -> Result<usize> {
+    let dir = dir.as_ref();
+    std::fs::create_dir_all(dir)?;
+    let vectors = test_vectors()?;
+    for vector in &vectors {
+        std::fs::write(dir.join(format!("{}.bin", vector.name)), &vector.bytes)?;
+        std::fs::write(dir.join(format!("{}.json", vector.name)), vector.to_json())?;
+    }
+    Ok(vectors.len())
+}
+
+/// Renders a [`Value`] as JSON.
+///
+/// Every value is an object with a `type` member:
+///
+/// | `type` | Other members |
+/// |---|---|
+/// | `none`, `unit_struct` | — |
+/// | `some` | `value` |
+/// | `uint`, `int` | `value` (decimal string) |
+/// | `float` | `value` (Rust `{:?}` formatting, e.g. `"0.1"`, `"NaN"`) |
+/// | `string` | `value` |
+/// | `binary`, `uuid` | `hex` |
+/// | `list`, `tuple`, `tuple_struct` | `items` |
+/// | `map` | `entries` (array of `[key, value]` pairs) |
+/// | `struct` | `fields` (array of `{"id", "name", "value"}`) |
+/// | `enum` | `id`, `name`, and `fields` (named) or `items` (unnamed) |
+/// | `extension` | `tag` (number), `values` |
+///
+/// IDs are decimal strings and `name` is `null` unless the payload recorded names.
+pub fn value_to_json(value: &Value) -> String {
+    let mut out = String::new();
+    write_json(value, &mut out);
+    out
+}
+
+fn write_json(value: &Value, out: &mut String) {
+    match value {
+        Value::None => out.push_str(r#"{"type":"none"}"#),
+        Value::Some(inner) => {
+            out.push_str(r#"{"type":"some","value":"#);
+            write_json(inner, out);
+            out.push('}');
+        }
+        Value::UInt(n) => {
+            let _ = write!(out, r#"{{"type":"uint","value":"{}"}}"#, n);
+        }
+        Value::Int(n) => {
+            let _ = write!(out, r#"{{"type":"int","value":"{}"}}"#, n);
+        }
+        Value::Float(f) => {
+            let _ = write!(out, r#"{{"type":"float","value":"{:?}"}}"#, f);
+        }
+        Value::String(s) => {
+            let _ = write!(out, r#"{{"type":"string","value":{}}}"#, json_string(s));
+        }
+        Value::Binary(b) => {
+            let _ = write!(out, r#"{{"type":"binary","hex":"{}"}}"#, hex(b));
+        }
+        Value::Uuid(b) => {
+            let _ = write!(out, r#"{{"type":"uuid","hex":"{}"}}"#, hex(b));
+        }
+        Value::List(items) => write_items("list", items, out),
+        Value::Tuple(items) => write_items("tuple", items, out),
+        Value::TupleStruct(items) => write_items("tuple_struct", items, out),
+        Value::Map(entries) => {
+            out.push_str(r#"{"type":"map","entries":["#);
+            for (i, (key, value)) in entries.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                out.push('[');
+                write_json(key, out);
+                out.push(',');
+                write_json(value, out);
+                out.push(']');
+            }
+            out.push_str("]}");
+        }
+        Value::UnitStruct => out.push_str(r#"{"type":"unit_struct"}"#),
+        Value::Struct(fields) => {
+            out.push_str(r#"{"type":"struct","fields":"#);
+            write_fields(fields, out);
+            out.push('}');
+        }
+        Value::Enum(variant) => {
+            let _ = write!(
+                out,
+                r#"{{"type":"enum","id":"{}","name":{}"#,
+                variant.id,
+                json_name(variant.name.as_deref())
+            );
+            match &variant.fields {
+                VariantFields::Unit => {}
+                VariantFields::Named(fields) => {
+                    out.push_str(r#","fields":"#);
+                    write_fields(fields, out);
+                }
+                VariantFields::Unnamed(items) => {
+                    out.push_str(r#","items":"#);
+                    write_array(items, out);
+                }
+            }
+            out.push('}');
+        }
+        Value::Extension { tag, values } => {
+            let _ = write!(out, r#"{{"type":"extension","tag":{},"values":"#, tag);
+            write_array(values, out);
+            out.push('}');
+        }
+    }
+}
+
+fn write_items(kind: &str, items: &[Value], out: &mut String) {
+    let _ = write!(out, r#"{{"type":"{}","items":"#, kind);
+    write_array(items, out);
+    out.push('}');
+}
+
+fn write_array(items: &[Value], out: &mut String) {
+    out.push('[');
+    for (i, item) in items.iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        write_json(item, out);
+    }
+    out.push(']');
+}
+
+fn write_fields(fields: &[Field], out: &mut String) {
+    out.push('[');
+    for (i, field) in fields.iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        let _ = write!(
+            out,
+            r#"{{"id":"{}","name":{},"value":"#,
+            field.id,
+            json_name(field.name.as_deref())
+        );
+        write_json(&field.value, out);
+        out.push('}');
+    }
+    out.push(']');
+}
+
+fn json_name(name: Option<&str>) -> String {
+    name.map_or_else(|| "null".to_string(), json_string)
+}
+
+fn json_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().fold(String::new(), |mut out, b| {
+        let _ = write!(out, "{:02x}", b);
+        out
+    })
+}
@@ -0,0 +1,53 @@
+//! Text adapters for embedding encoded payloads in JSON/YAML config or HTTP headers.
+//!
+//! The text carries the same bytes as [`encode`](crate::encode) (including the magic number),
+//! written as standard base64 (with padding) or lowercase hex.
+
+use crate::{decode, encode, Decoder, Encoder, EncoderError, Result};
+use base64::Engine;
+use bytes::Bytes;
+
+/// Encodes a value and returns the payload as a base64 string.
+///
+/// # Example
+/// ```rust
+/// use senax_encoder::{decode_base64, encode_base64};
+///
+/// let text = encode_base64(&vec![1u32, 2, 3]).unwrap();
+/// let value: Vec<u32> = decode_base64(&text).unwrap();
+/// assert_eq!(value, vec![1, 2, 3]);
+/// ```
+pub fn encode_base64<T: Encoder>(value: &T) -> Result<String> {
+    let bytes = encode(value)?;
+    Ok(base64::engine::general_purpose::STANDARD.encode(bytes))
+}
+
+/// Decodes a value from a base64 string produced by [`encode_base64`].
+pub fn decode_base64<T: Decoder>(text: &str) -> Result<T> {
+    let bytes = base64::engine::general_purpose::STANDARD
+        .decode(text.trim())
+        .map_err(|e| EncoderError::Decode(format!("Invalid base64: {}", e)))?;
+    decode(&mut Bytes::from(bytes))
+}
+
+/// Encodes a value and returns the payload as a lowercase hex string.
+///
+/// # Example
+/// ```rust
+/// use senax_encoder::{decode_hex, encode_hex};
+///
+/// let text = encode_hex(&"hello".to_string()).unwrap();
+/// let value: String = decode_hex(&text).unwrap();
+/// assert_eq!(value, "hello");
+/// ```
+pub fn encode_hex<T: Encoder>(value: &T) -> Result<String> {
+    let bytes = encode(value)?;
+    Ok(hex::encode(bytes))
+}
+
+/// Decodes a value from a hex string produced by [`encode_hex`] (either letter case is accepted).
+pub fn decode_hex<T: Decoder>(text: &str) -> Result<T> {
+    let bytes = hex::decode(text.trim())
+        .map_err(|e| EncoderError::Decode(format!("Invalid hex: {}", e)))?;
+    decode(&mut Bytes::from(bytes))
+}
@@ -0,0 +1,398 @@
+//! Compressed (timestamp, value) streams.
+//!
+//! [`TimeSeriesWriter`] compresses samples Gorilla-style: timestamps are stored as the
+//! difference between consecutive deltas, so a series sampled at a fixed interval costs one
+//! bit per timestamp, and each value is XORed with the previous one so that unchanged or
+//! slowly changing values cost a few bits. A block looks like this:
+//!
+//! ```text
+//! [count: u32 little-endian] [first timestamp: i64 little-endian] [first value: f64 little-endian] [bit stream]
+//! ```
+//!
+//! For every sample after the first, the bit stream holds the delta-of-delta `d` of the
+//! timestamp (zigzag-encoded as `z`) followed by the value:
+//!
+//! | Timestamp bits           | Condition     |
+//! |--------------------------|---------------|
+//! | `0`                      | `d == 0`      |
+//! | `10` + 7 bits of `z`     | `z < 2^7`     |
+//! | `110` + 9 bits of `z`    | `z < 2^9`     |
+//! | `1110` + 12 bits of `z`  | `z < 2^12`    |
+//! | `1111` + 64 bits of `z`  | otherwise     |
+//!
+//! | Value bits                                                         | Condition                                     |
+//! |--------------------------------------------------------------------|-----------------------------------------------|
+//! | `0`                                                                | same bits as the previous value               |
+//! | `10` + meaningful XOR bits                                         | fits the previous leading/trailing zero window |
+//! | `11` + 5 bits leading zeros + 6 bits (length - 1) + meaningful bits | otherwise                                     |
+//!
+//! The stream is padded with zero bits to a whole byte. Timestamp arithmetic wraps, so any
+//! `i64` sequence round-trips, and values round-trip bit for bit (including NaN payloads).
+//!
+//! [`TimeSeries`] implements [`Encoder`]/[`Decoder`] and [`Packer`]/[`Unpacker`] by storing
+//! the block as binary, so a compressed series can be a field of a derived struct.
+//!
+//! # Example
+//! ```rust
+//! use senax_encoder::timeseries::{Sample, TimeSeries};
+//! use senax_encoder::{decode, encode};
+//!
+//! let series = TimeSeries(
+//!     (0..1000)
+//!         .map(|i| Sample { timestamp: 1_700_000_000 + i * 10, value: 20.5 })
+//!         .collect(),
+//! );
+//! let bytes = encode(&series).unwrap();
+//! assert!(bytes.len() < 300); // 16000 bytes as plain (i64, f64) pairs
+//! assert_eq!(decode::<TimeSeries>(&mut bytes.clone()).unwrap(), series);
+//! ```
+
+use crate::core::{read_u32_le, read_u64_le, write_u32_le, write_u64_le};
+use crate::{Decoder, Encoder, EncoderError, Packer, Result, Unpacker};
+use bytes::{Buf, Bytes, BytesMut};
+
+/// Length of the block header (count, first timestamp and first value).
+const HEADER_LEN: usize = 20;
+
+/// Timestamp delta-of-delta buckets: prefix, prefix length and payload bits.
+const TIMESTAMP_BUCKETS: [(u64, u32, u32); 3] = [(0b10, 2, 7), (0b110, 3, 9), (0b1110, 4, 12)];
+
+/// One point of a time series.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Sample {
+    /// Timestamp in a unit chosen by the caller (seconds, milliseconds, ...).
+    pub timestamp: i64,
+    /// Measured value.
+    pub value: f64,
+}
+
+/// Compresses samples into a block, one at a time.
+#[derive(Debug, Default)]
+pub struct TimeSeriesWriter {
+    header: BytesMut,
+    bits: BitWriter,
+    count: u32,
+    prev_timestamp: i64,
+    prev_delta: i64,
+    prev_value: u64,
+    /// Leading and trailing zeros of the last XOR window, once one has been written.
+    window: Option<(u32, u32)>,
+}
+
+impl TimeSeriesWriter {
+    /// Creates a writer for an empty block.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the number of samples written so far.
+    pub fn len(&self) -> usize {
+        self.count as usize
+    }
+
+    /// Returns `true` if no sample has been written.
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    /// Appends one sample.
+    ///
+    /// Fails once the block holds `u32::MAX` samples.
+    pub fn push(&mut self, timestamp: i64, value: f64) -> Result<()> {
+        if self.count == u32::MAX {
+            return Err(EncoderError::Encode(
+                "Time series block cannot hold more than u32::MAX samples".to_string(),
+            ));
+        }
+        let value = value.to_bits();
+        if self.count == 0 {
+            write_u64_le(&mut self.header, timestamp as u64)?;
+            write_u64_le(&mut self.header, value)?;
+        } else {
+            let delta = timestamp.wrapping_sub(self.prev_timestamp);
+            self.write_timestamp(delta.wrapping_sub(self.prev_delta));
+            self.write_value(value);
+            self.prev_delta = delta;
+        }
+        self.prev_timestamp = timestamp;
+        self.prev_value = value;
+        self.count += 1;
+        Ok(())
+    }
+
+    fn write_timestamp(&mut self, delta_of_delta: i64) {
+        if delta_of_delta == 0 {
+            self.bits.write(0, 1);
+            return;
+        }
+        let zigzag = ((delta_of_delta << 1) ^ (delta_of_delta >> 63)) as u64;
+        for (prefix, prefix_len, payload_len) in TIMESTAMP_BUCKETS {
+            if zigzag < 1 << payload_len {
+                self.bits.write(prefix, prefix_len);
+                self.bits.write(zigzag, payload_len);
+                return;
+            }
+        }
+        self.bits.write(0b1111, 4);
+        self.bits.write(zigzag, 64);
+    }
+
+    fn write_value(&mut self, value: u64) {
+        let xor = value ^ self.prev_value;
+        if xor == 0 {
+            self.bits.write(0, 1);
+            return;
+        }
+        let leading = xor.leading_zeros().min(31);
+        let trailing = xor.trailing_zeros();
+        match self.window {
+            Some((prev_leading, prev_trailing))
+                if leading >= prev_leading && trailing >= prev_trailing =>
+            {
+                self.bits.write(0b10, 2);
+                self.bits
+                    .write(xor >> prev_trailing, 64 - prev_leading - prev_trailing);
+            }
+            _ => {
+                let meaningful = 64 - leading - trailing;
+                self.bits.write(0b11, 2);
+                self.bits.write(leading as u64, 5);
+                self.bits.write((meaningful - 1) as u64, 6);
+                self.bits.write(xor >> trailing, meaningful);
+                self.window = Some((leading, trailing));
+            }
+        }
+    }
+
+    /// Returns the finished block.
+    pub fn finish(self) -> Result<Bytes> {
+        let mut block = BytesMut::with_capacity(HEADER_LEN + self.bits.bytes.len());
+        write_u32_le(&mut block, self.count)?;
+        block.extend_from_slice(&self.header);
+        block.extend_from_slice(&self.bits.bytes);
+        Ok(block.freeze())
+    }
+}
+
+/// Iterates over the samples of a block written by [`TimeSeriesWriter`].
+#[derive(Debug)]
+pub struct TimeSeriesReader {
+    bits: BitReader,
+    remaining: u32,
+    started: bool,
+    first: Sample,
+    prev_timestamp: i64,
+    prev_delta: i64,
+    prev_value: u64,
+    window: Option<(u32, u32)>,
+}
+
+impl TimeSeriesReader {
+    /// Reads the block header. Samples are decoded lazily by [`Iterator::next`].
+    pub fn new(mut block: Bytes) -> Result<Self> {
+        if block.remaining() < 4 {
+            return Err(EncoderError::eof(4, block.remaining()));
+        }
+        let count = read_u32_le(&mut block)?;
+        let first = if count > 0 {
+            if block.remaining() < 16 {
+                return Err(EncoderError::eof(16, block.remaining()));
+            }
+            Sample {
+                timestamp: read_u64_le(&mut block)? as i64,
+                value: f64::from_bits(read_u64_le(&mut block)?),
+            }
+        } else {
+            Sample::default()
+        };
+        Ok(Self {
+            bits: BitReader {
+                bytes: block,
+                position: 0,
+            },
+            remaining: count,
+            started: false,
+            first,
+            prev_timestamp: first.timestamp,
+            prev_delta: 0,
+            prev_value: first.value.to_bits(),
+            window: None,
+        })
+    }
+
+    /// Returns the number of samples not yet read.
+    pub fn remaining(&self) -> usize {
+        self.remaining as usize
+    }
+
+    fn read_sample(&mut self) -> Result<Sample> {
+        if !self.started {
+            self.started = true;
+            return Ok(self.first);
+        }
+        let delta = self.prev_delta.wrapping_add(self.read_timestamp()?);
+        let timestamp = self.prev_timestamp.wrapping_add(delta);
+        let value = self.read_value()?;
+        self.prev_delta = delta;
+        self.prev_timestamp = timestamp;
+        self.prev_value = value;
+        Ok(Sample {
+            timestamp,
+            value: f64::from_bits(value),
+        })
+    }
+
+    fn read_timestamp(&mut self) -> Result<i64> {
+        if self.bits.read(1)? == 0 {
+            return Ok(0);
+        }
+        let mut payload_len = 64;
+        for (_, _, bucket_len) in TIMESTAMP_BUCKETS {
+            if self.bits.read(1)? == 0 {
+                payload_len = bucket_len;
+                break;
+            }
+        }
+        let zigzag = self.bits.read(payload_len)?;
+        Ok((zigzag >> 1) as i64 ^ -((zigzag & 1) as i64))
+    }
+
+    fn read_value(&mut self) -> Result<u64> {
+        if self.bits.read(1)? == 0 {
+            return Ok(self.prev_value);
+        }
+        let (leading, trailing) = if self.bits.read(1)? == 0 {
+            self.window.ok_or_else(|| {
+                EncoderError::Decode("Time series value reuses a missing window".to_string())
+            })?
+        } else {
+            let leading = self.bits.read(5)? as u32;
+            let meaningful = self.bits.read(6)? as u32 + 1;
+            if leading + meaningful > 64 {
+                return Err(EncoderError::Decode(format!(
+                    "Invalid time series value window: {} leading zeros and {} bits",
+                    leading, meaningful
+                )));
+            }
+            let window = (leading, 64 - leading - meaningful);
+            self.window = Some(window);
+            window
+        };
+        let xor = self.bits.read(64 - leading - trailing)? << trailing;
+        Ok(self.prev_value ^ xor)
+    }
+}
+
+impl Iterator for TimeSeriesReader {
+    type Item = Result<Sample>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let sample = self.read_sample();
+        self.remaining = if sample.is_ok() {
+            self.remaining - 1
+        } else {
+            0
+        };
+        Some(sample)
+    }
+}
+
+/// Compresses `samples` into one block.
+pub fn compress(samples: &[Sample]) -> Result<Bytes> {
+    let mut writer = TimeSeriesWriter::new();
+    for sample in samples {
+        writer.push(sample.timestamp, sample.value)?;
+    }
+    writer.finish()
+}
+
+/// Decompresses a block written by [`compress`] or [`TimeSeriesWriter`].
+pub fn decompress(block: Bytes) -> Result<Vec<Sample>> {
+    TimeSeriesReader::new(block)?.collect()
+}
+
+/// A series of samples stored as one compressed block.
+///
+/// Encoded and packed as binary holding the block, so it costs the same in either format and
+/// can be read by [`TimeSeriesReader`] straight from a `Bytes` field.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct TimeSeries(pub Vec<Sample>);
+
+impl Encoder for TimeSeries {
+    fn encode(&self, writer: &mut BytesMut) -> Result<()> {
+        compress(&self.0)?.encode(writer)
+    }
+
+    fn is_default(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl Decoder for TimeSeries {
+    fn decode(reader: &mut Bytes) -> Result<Self> {
+        decompress(Bytes::decode(reader)?).map(TimeSeries)
+    }
+}
+
+impl Packer for TimeSeries {
+    fn pack(&self, writer: &mut BytesMut) -> Result<()> {
+        compress(&self.0)?.pack(writer)
+    }
+}
+
+impl Unpacker for TimeSeries {
+    fn unpack(reader: &mut Bytes) -> Result<Self> {
+        decompress(Bytes::unpack(reader)?).map(TimeSeries)
+    }
+}
+
+#[derive(Debug, Default)]
+struct BitWriter {
+    bytes: Vec<u8>,
+    /// Bits used in the last byte (0 means the next bit starts a new byte).
+    used: u32,
+}
+
+impl BitWriter {
+    /// Writes the low `len` bits of `value`, most significant first.
+    fn write(&mut self, value: u64, len: u32) {
+        for shift in (0..len).rev() {
+            if self.used == 0 {
+                self.bytes.push(0);
+            }
+            let bit = ((value >> shift) & 1) as u8;
+            if let Some(last) = self.bytes.last_mut() {
+                *last |= bit << (7 - self.used);
+            }
+            self.used = (self.used + 1) % 8;
+        }
+    }
+}
+
+#[derive(Debug)]
+struct BitReader {
+    bytes: Bytes,
+    /// Position in bits.
+    position: usize,
+}
+
+impl BitReader {
+    /// Reads `len` bits (at most 64), most significant first.
+    fn read(&mut self, len: u32) -> Result<u64> {
+        if self.position + len as usize > self.bytes.len() * 8 {
+            let needed = (self.position + len as usize).div_ceil(8);
+            return Err(EncoderError::eof(needed, self.bytes.len()));
+        }
+        let mut value = 0u64;
+        for _ in 0..len {
+            let byte = self.bytes[self.position / 8];
+            let bit = (byte >> (7 - self.position % 8)) & 1;
+            value = (value << 1) | bit as u64;
+            self.position += 1;
+        }
+        Ok(value)
+    }
+}
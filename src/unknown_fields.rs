@@ -0,0 +1,169 @@
+//! Statistics about unknown fields skipped while decoding.
+//!
+//! Derived decoders skip fields whose ID they do not know, so that older readers accept
+//! payloads from newer writers. [`collect_unknown_fields`] counts those skips, per type and
+//! field ID, for the decodes run inside it. Operators can export the counts as metrics to
+//! notice producers running ahead of their consumers instead of silently dropping data.
+//!
+//! Nothing is recorded outside [`collect_unknown_fields`], and decoding payloads without
+//! unknown fields costs nothing extra. Without the `std` feature there is no collector, and
+//! unknown fields are skipped without being counted.
+
+use crate::Result;
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+use bytes::Bytes;
+#[cfg(feature = "std")]
+use std::cell::RefCell;
+
+/// One unknown field ID seen while collecting, and how many times it was skipped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SkippedField {
+    /// The struct, or `Enum::Variant`, whose decoder skipped the field.
+    pub type_name: &'static str,
+    /// The unknown field ID.
+    pub field_id: u64,
+    /// Number of times the field was skipped.
+    pub count: u64,
+}
+
+/// Unknown fields skipped during [`collect_unknown_fields`], keyed by type name and field ID.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct UnknownFieldStats {
+    counts: BTreeMap<(&'static str, u64), u64>,
+}
+
+impl UnknownFieldStats {
+    /// Returns `true` if no unknown field was skipped.
+    pub fn is_empty(&self) -> bool {
+        self.counts.is_empty()
+    }
+
+    /// Total number of skipped fields.
+    pub fn total(&self) -> u64 {
+        self.counts.values().sum()
+    }
+
+    /// Number of times `field_id` was skipped by the decoder of `type_name`.
+    pub fn count(&self, type_name: &str, field_id: u64) -> u64 {
+        self.counts
+            .get(&(type_name, field_id))
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// The distinct unknown field IDs skipped by the decoder of `type_name`, in ascending order.
+    pub fn field_ids(&self, type_name: &str) -> Vec<u64> {
+        self.counts
+            .keys()
+            .filter(|(name, _)| *name == type_name)
+            .map(|(_, id)| *id)
+            .collect()
+    }
+
+    /// Iterates over the skipped fields ordered by type name and field ID.
+    pub fn iter(&self) -> impl Iterator<Item = SkippedField> + '_ {
+        self.counts
+            .iter()
+            .map(|(&(type_name, field_id), &count)| SkippedField {
+                type_name,
+                field_id,
+                count,
+            })
+    }
+
+    /// Adds the counts of `other` to these statistics.
+    pub fn merge(&mut self, other: &UnknownFieldStats) {
+        for (key, count) in &other.counts {
+            *self.counts.entry(*key).or_default() += count;
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+thread_local! {
+    static COLLECTOR: RefCell<Option<UnknownFieldStats>> = const { RefCell::new(None) };
+}
+
+/// Uninstalls the collector when dropped, even if decoding panics, and folds its counts into
+/// the enclosing collector.
+#[cfg(feature = "std")]
+struct CollectorGuard(Option<Option<UnknownFieldStats>>);
+
+#[cfg(feature = "std")]
+impl CollectorGuard {
+    fn restore(&mut self) -> UnknownFieldStats {
+        let previous = self.0.take().flatten();
+        COLLECTOR.with(|c| {
+            let mut c = c.borrow_mut();
+            let stats = c.take().unwrap_or_default();
+            *c = previous.map(|mut outer| {
+                outer.merge(&stats);
+                outer
+            });
+            stats
+        })
+    }
+}
+
+#[cfg(feature = "std")]
+impl Drop for CollectorGuard {
+    fn drop(&mut self) {
+        if self.0.is_some() {
+            self.restore();
+        }
+    }
+}
+
+/// Runs `f` and returns its result together with the unknown fields skipped by derived
+/// decoders on this thread while it ran.
+///
+/// Calls may be nested; the counts of an inner call are also added to the enclosing one.
+///
+/// # Example
+/// ```rust
+/// use senax_encoder::{collect_unknown_fields, decode, encode, Decode, Encode};
+///
+/// #[derive(Encode)]
+/// struct OrderV2 { id: u64, coupon: String }
+///
+/// #[derive(Decode, Debug)]
+/// struct Order { id: u64 }
+///
+/// let bytes = encode(&OrderV2 { id: 1, coupon: "SPRING".into() }).unwrap();
+/// let (order, stats) = collect_unknown_fields(|| decode::<Order>(&mut bytes.clone()));
+/// assert_eq!(order.unwrap().id, 1);
+/// assert_eq!(stats.total(), 1);
+/// assert_eq!(
+///     stats.field_ids("Order"),
+///     vec![senax_encoder::core::field_id_from_name("coupon")]
+/// );
+/// ```
+#[cfg(feature = "std")]
+pub fn collect_unknown_fields<R>(f: impl FnOnce() -> R) -> (R, UnknownFieldStats) {
+    let previous = COLLECTOR.with(|c| c.replace(Some(UnknownFieldStats::default())));
+    let mut guard = CollectorGuard(Some(previous));
+    let result = f();
+    let stats = guard.restore();
+    (result, stats)
+}
+
+/// Skips the value of an unknown field and records it if a collector is installed.
+///
+/// Called by derived decoders; not part of the public API.
+#[doc(hidden)]
+#[cfg_attr(not(feature = "std"), allow(unused_variables))]
+pub fn skip_unknown_field(
+    reader: &mut Bytes,
+    type_name: &'static str,
+    field_id: u64,
+) -> Result<()> {
+    crate::core::skip_value(reader)?;
+    #[cfg(feature = "std")]
+    COLLECTOR.with(|c| {
+        if let Some(stats) = c.borrow_mut().as_mut() {
+            *stats.counts.entry((type_name, field_id)).or_default() += 1;
+        }
+    });
+    Ok(())
+}
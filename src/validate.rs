@@ -0,0 +1,289 @@
+//! Structural validation of encoded payloads.
+//!
+//! [`validate_payload`] walks an `encode()` payload tag by tag without constructing the target
+//! type. It checks that every tag is known, that length prefixes fit in the remaining bytes,
+//! that strings are valid UTF-8 and that every named struct/enum is closed by its terminator.
+//! This is meant for ingestion gateways that must reject garbage early and cheaply; a payload
+//! that validates can still fail to decode as `T` (e.g. a type mismatch or a missing field).
+
+use crate::core::*;
+use crate::{Decoder, EncoderError, Result};
+use bytes::{Buf, Bytes};
+
+/// Maximum nesting depth accepted by [`validate_payload`].
+pub const MAX_VALIDATION_DEPTH: usize = 256;
+
+/// Result of [`validate_payload`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationReport {
+    /// Name of the type the payload was validated for.
+    pub type_name: &'static str,
+    /// Bytes consumed by the magic number and the top-level value.
+    pub bytes_consumed: usize,
+    /// Bytes left after the top-level value.
+    pub trailing_bytes: usize,
+    /// Number of tagged values visited.
+    pub values: usize,
+    /// Deepest nesting level reached (the top-level value is depth 1).
+    pub max_depth: usize,
+    /// The first structural problem found, if any.
+    pub error: Option<ValidationIssue>,
+}
+
+/// A structural problem found by [`validate_payload`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationIssue {
+    /// Byte offset from the start of the payload where the problem was detected.
+    pub offset: usize,
+    /// Human-readable description of the problem.
+    pub message: String,
+}
+
+impl ValidationReport {
+    /// Returns `true` if the payload is well-formed and has no trailing bytes.
+    pub fn is_valid(&self) -> bool {
+        self.error.is_none() && self.trailing_bytes == 0
+    }
+}
+
+/// Checks the structural well-formedness of an encoded payload (with magic number) for `T`.
+///
+/// The payload is not decoded into `T`; `T` only names the expected type in the report.
+///
+/// # Example
+/// ```rust
+/// use senax_encoder::{encode, validate_payload, Encode, Decode};
+///
+/// #[derive(Encode, Decode)]
+/// struct Event { id: u32, name: String }
+///
+/// let bytes = encode(&Event { id: 1, name: "start".into() }).unwrap();
+/// assert!(validate_payload::<Event>(&bytes).is_valid());
+///
+/// let truncated = bytes.slice(..bytes.len() - 1);
+/// let report = validate_payload::<Event>(&truncated);
+/// assert!(!report.is_valid());
+/// assert!(report.error.is_some());
+/// ```
+pub fn validate_payload<T: Decoder>(bytes: &[u8]) -> ValidationReport {
+    let total = bytes.len();
+    let mut reader = Bytes::copy_from_slice(bytes);
+    let mut walker = Walker {
+        values: 0,
+        max_depth: 0,
+    };
+    let result = if reader.remaining() < 2 {
+        Err(EncoderError::eof(2, reader.remaining()))
+    } else {
+        let magic = reader.get_u16_le();
+        if magic != crate::ENCODE_MAGIC {
+            Err(EncoderError::Decode(format!(
+                "Invalid encode magic number: expected 0x{:04X}, got 0x{:04X}",
+                crate::ENCODE_MAGIC,
+                magic
+            )))
+        } else {
+            walker.walk(&mut reader, 1)
+        }
+    };
+    let offset = total - reader.remaining();
+    ValidationReport {
+        type_name: std::any::type_name::<T>(),
+        bytes_consumed: offset,
+        trailing_bytes: reader.remaining(),
+        values: walker.values,
+        max_depth: walker.max_depth,
+        error: result.err().map(|e| ValidationIssue {
+            offset,
+            message: e.to_string(),
+        }),
+    }
+}
+
+struct Walker {
+    values: usize,
+    max_depth: usize,
+}
+
+impl Walker {
+    fn walk(&mut self, reader: &mut Bytes, depth: usize) -> Result<()> {
+        if depth > MAX_VALIDATION_DEPTH {
+            return Err(EncoderError::Decode(format!(
+                "Nesting depth exceeds {}",
+                MAX_VALIDATION_DEPTH
+            )));
+        }
+        self.values += 1;
+        self.max_depth = self.max_depth.max(depth);
+        if reader.remaining() == 0 {
+            return Err(EncoderError::eof(1, 0));
+        }
+        let tag = reader.get_u8();
+        match tag {
+            TAG_ZERO..=TAG_U8_127 | TAG_NONE | TAG_STRUCT_UNIT | TAG_JSON_NULL => Ok(()),
+            TAG_U8 => advance(reader, 1),
+            TAG_U16 => advance(reader, 2),
+            TAG_U32 | TAG_F32 => advance(reader, 4),
+            TAG_U64 | TAG_F64 => advance(reader, 8),
+            TAG_U128 | TAG_UUID => advance(reader, 16),
+            TAG_NEGATIVE => {
+                // Followed by the bit-inverted magnitude as an unsigned integer
+                if reader.remaining() == 0 {
+                    return Err(EncoderError::eof(1, 0));
+                }
+                match reader.get_u8() {
+                    TAG_ZERO..=TAG_U8_127 => Ok(()),
+                    TAG_U8 => advance(reader, 1),
+                    TAG_U16 => advance(reader, 2),
+                    TAG_U32 => advance(reader, 4),
+                    TAG_U64 => advance(reader, 8),
+                    TAG_U128 => advance(reader, 16),
+                    other => Err(EncoderError::Decode(format!(
+                        "Expected unsigned integer tag after TAG_NEGATIVE, got {}",
+                        other
+                    ))),
+                }
+            }
+            TAG_STRING_BASE..=TAG_STRING_LONG => {
+                let len = if tag < TAG_STRING_LONG {
+                    (tag - TAG_STRING_BASE) as usize
+                } else {
+                    usize::decode(reader)?
+                };
+                check_len(reader, len)?;
+                std::str::from_utf8(&reader[..len])
+                    .map_err(|e| EncoderError::Decode(format!("Invalid UTF-8 string: {}", e)))?;
+                reader.advance(len);
+                Ok(())
+            }
+            TAG_BINARY => {
+                let len = usize::decode(reader)?;
+                advance(reader, len)
+            }
+            TAG_ARRAY_VEC_SET_BASE..=TAG_ARRAY_VEC_SET_LONG => {
+                let len = if tag < TAG_ARRAY_VEC_SET_LONG {
+                    (tag - TAG_ARRAY_VEC_SET_BASE) as usize
+                } else {
+                    usize::decode(reader)?
+                };
+                self.walk_many(reader, len, depth)
+            }
+            TAG_STRUCT_NAMED => self.walk_fields(reader, depth),
+            TAG_STRUCT_UNNAMED | TAG_TUPLE | TAG_JSON_ARRAY => {
+                let len = usize::decode(reader)?;
+                self.walk_many(reader, len, depth)
+            }
+            TAG_ENUM => {
+                read_field_id_optimized(reader)?;
+                Ok(())
+            }
+            TAG_ENUM_NAMED => {
+                read_field_id_optimized(reader)?;
+                self.walk_fields(reader, depth)
+            }
+            TAG_ENUM_UNNAMED => {
+                read_field_id_optimized(reader)?;
+                let len = usize::decode(reader)?;
+                self.walk_many(reader, len, depth)
+            }
+            TAG_MAP | TAG_JSON_OBJECT => {
+                let len = usize::decode(reader)?;
+                let values = len.saturating_mul(2);
+                self.walk_many(reader, values, depth)
+            }
+            TAG_MAP_FLAT_KEYS => {
+                check_len(reader, 1)?;
+                let arity = reader.get_u8() as usize;
+                let len = usize::decode(reader)?;
+                // Key elements and the value of every entry, back to back
+                let values = len.saturating_mul(arity + 1);
+                self.walk_many(reader, values, depth)
+            }
+            TAG_MAP_STRING_KEYS => {
+                let len = usize::decode(reader)?;
+                // Every entry takes at least two bytes
+                check_len(reader, len.saturating_mul(2))?;
+                for _ in 0..len {
+                    let key_len = usize::decode(reader)?;
+                    check_len(reader, key_len)?;
+                    std::str::from_utf8(&reader[..key_len]).map_err(|e| {
+                        EncoderError::Decode(format!("Invalid UTF-8 in map key: {}", e))
+                    })?;
+                    reader.advance(key_len);
+                    self.walk(reader, depth + 1)?;
+                }
+                Ok(())
+            }
+            TAG_MAP_ID_KEYS => self.walk_id_keyed_entries(reader, depth),
+            TAG_SOME | TAG_CHRONO_NAIVE_DATE | TAG_JSON_BOOL | TAG_JSON_STRING => {
+                self.walk(reader, depth + 1)
+            }
+            TAG_CHRONO_DATETIME
+            | TAG_CHRONO_NAIVE_TIME
+            | TAG_CHRONO_NAIVE_DATETIME
+            | TAG_DECIMAL
+            | TAG_DURATION
+            | TAG_SYSTEM_TIME => self.walk_many(reader, 2, depth),
+            TAG_JSON_NUMBER => {
+                if reader.remaining() == 0 {
+                    return Err(EncoderError::eof(1, 0));
+                }
+                let number_type = reader.get_u8();
+                if number_type > 3 {
+                    return Err(EncoderError::Decode(format!(
+                        "Invalid JSON Number type marker: {}",
+                        number_type
+                    )));
+                }
+                self.walk(reader, depth + 1)
+            }
+            _ => Err(EncoderError::Decode(format!("Unknown tag {}", tag))),
+        }
+    }
+
+    /// Walks `count` child values, rejecting counts that cannot fit in the remaining bytes.
+    // Kept out of `walk` so that its stack frame does not grow
+    #[inline(never)]
+    fn walk_id_keyed_entries(&mut self, reader: &mut Bytes, depth: usize) -> Result<()> {
+        let len = usize::decode(reader)?;
+        // Every entry takes at least two bytes
+        check_len(reader, len.saturating_mul(2))?;
+        for _ in 0..len {
+            read_field_id_optimized(reader)?;
+            self.walk(reader, depth + 1)?;
+        }
+        Ok(())
+    }
+
+    fn walk_many(&mut self, reader: &mut Bytes, count: usize, depth: usize) -> Result<()> {
+        // Every value takes at least one byte
+        check_len(reader, count)?;
+        for _ in 0..count {
+            self.walk(reader, depth + 1)?;
+        }
+        Ok(())
+    }
+
+    /// Walks `[field_id] [value]` pairs up to the zero terminator.
+    fn walk_fields(&mut self, reader: &mut Bytes, depth: usize) -> Result<()> {
+        loop {
+            if read_field_id_optimized(reader)? == 0 {
+                return Ok(());
+            }
+            self.walk(reader, depth + 1)?;
+        }
+    }
+}
+
+fn check_len(reader: &Bytes, len: usize) -> Result<()> {
+    if reader.remaining() < len {
+        return Err(EncoderError::eof(len, reader.remaining()));
+    }
+    Ok(())
+}
+
+fn advance(reader: &mut Bytes, len: usize) -> Result<()> {
+    check_len(reader, len)?;
+    reader.advance(len);
+    Ok(())
+}
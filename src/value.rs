@@ -0,0 +1,787 @@
+//! A dynamic representation of encoded values.
+//!
+//! [`Value`] mirrors the tags of the encode format, so any payload written by
+//! [`Encoder::encode`](crate::Encoder::encode) can be decoded without the Rust type that
+//! produced it. Struct fields and enum variants carry their IDs and, when decoded from a
+//! [self-describing](crate::self_describing) payload, their names.
+//!
+//! A `Value` can be edited ([`Value::set_field`], [`Value::field_mut`], ...) and encoded
+//! again, so tools can rewrite payloads of types they do not know. [`to_value`] and
+//! [`from_value`] convert between `Value` and typed data.
+
+use crate::core::*;
+use crate::{Decoder, Encoder, EncoderError, Result};
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+
+/// Maximum nesting depth accepted when decoding a [`Value`].
+pub const MAX_VALUE_DEPTH: usize = 256;
+
+/// A value decoded from the encode format without knowing its Rust type.
+///
+/// Some Rust types share a wire representation, so the mapping is not one-to-one: `bool`
+/// decodes as [`Value::UInt`], floating point numbers (written as strings) as
+/// [`Value::String`], and sets as [`Value::List`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum Value {
+    /// `None`.
+    #[default]
+    None,
+    /// `Some` wrapping a value.
+    Some(Box<Value>),
+    /// A non-negative integer (including `bool`).
+    UInt(u128),
+    /// A negative integer.
+    Int(i128),
+    /// A float in the legacy binary representation.
+    Float(f64),
+    /// A UTF-8 string.
+    String(String),
+    /// Binary data (`Bytes`, `Vec<u8>` written as binary).
+    Binary(Bytes),
+    /// An array, vector or set.
+    List(Vec<Value>),
+    /// A tuple.
+    Tuple(Vec<Value>),
+    /// A map as key/value pairs in wire order.
+    Map(Vec<(Value, Value)>),
+    /// A unit struct.
+    UnitStruct,
+    /// A struct with named fields.
+    Struct(Vec<Field>),
+    /// A tuple struct.
+    TupleStruct(Vec<Value>),
+    /// An enum variant.
+    Enum(Box<Variant>),
+    /// A UUID or ULID.
+    Uuid([u8; 16]),
+    /// Any other tag (chrono, decimal and JSON types) with the values that follow it.
+    ///
+    /// The JSON number type marker is represented as a leading [`Value::UInt`], and the
+    /// entries of a JSON object are flattened into alternating keys and values.
+    Extension {
+        /// The wire tag.
+        tag: u8,
+        /// The values written after the tag.
+        values: Vec<Value>,
+    },
+}
+
+/// A named struct or variant field.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Field {
+    /// The field ID.
+    pub id: u64,
+    /// The field name, if the payload recorded it.
+    pub name: Option<String>,
+    /// The field value.
+    pub value: Value,
+}
+
+/// An enum variant.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Variant {
+    /// The variant ID.
+    pub id: u64,
+    /// The variant name, if the payload recorded it.
+    pub name: Option<String>,
+    /// The variant's fields.
+    pub fields: VariantFields,
+}
+
+/// The fields of an enum [`Variant`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum VariantFields {
+    /// A unit variant.
+    Unit,
+    /// A variant with named fields.
+    Named(Vec<Field>),
+    /// A tuple variant.
+    Unnamed(Vec<Value>),
+}
+
+impl Field {
+    /// Creates a field whose ID is hashed from `name`, as derived code does for a field
+    /// without `id`.
+    pub fn new(name: impl Into<String>, value: impl Into<Value>) -> Self {
+        let name = name.into();
+        Self {
+            id: field_id_from_name(&name),
+            name: Some(name),
+            value: value.into(),
+        }
+    }
+
+    /// Returns `true` if the field recorded `name`, or, without a recorded name, if its ID is
+    /// the one hashed from `name`.
+    pub fn is_named(&self, name: &str) -> bool {
+        match &self.name {
+            Some(recorded) => recorded == name,
+            None => self.id == field_id_from_name(name),
+        }
+    }
+}
+
+impl Value {
+    /// Returns the named fields of a struct or struct-like enum variant.
+    pub fn fields(&self) -> Option<&[Field]> {
+        match self {
+            Value::Struct(fields) => Some(fields),
+            Value::Enum(variant) => match &variant.fields {
+                VariantFields::Named(fields) => Some(fields),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// Returns the named fields of a struct or struct-like enum variant for editing.
+    pub fn fields_mut(&mut self) -> Option<&mut Vec<Field>> {
+        match self {
+            Value::Struct(fields) => Some(fields),
+            Value::Enum(variant) => match &mut variant.fields {
+                VariantFields::Named(fields) => Some(fields),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// Returns the value of the field called `name`.
+    ///
+    /// Fields are matched by recorded name, or, in payloads without names, by the ID hashed
+    /// from `name` (see [`Field::is_named`]).
+    pub fn field(&self, name: &str) -> Option<&Value> {
+        self.fields()?
+            .iter()
+            .find(|field| field.is_named(name))
+            .map(|field| &field.value)
+    }
+
+    /// Returns the value of the field called `name` for editing.
+    pub fn field_mut(&mut self, name: &str) -> Option<&mut Value> {
+        self.fields_mut()?
+            .iter_mut()
+            .find(|field| field.is_named(name))
+            .map(|field| &mut field.value)
+    }
+
+    /// Returns the value of the field with ID `id`.
+    pub fn field_by_id(&self, id: u64) -> Option<&Value> {
+        self.fields()?
+            .iter()
+            .find(|field| field.id == id)
+            .map(|field| &field.value)
+    }
+
+    /// Returns the value of the field with ID `id` for editing.
+    pub fn field_by_id_mut(&mut self, id: u64) -> Option<&mut Value> {
+        self.fields_mut()?
+            .iter_mut()
+            .find(|field| field.id == id)
+            .map(|field| &mut field.value)
+    }
+
+    /// Sets the field called `name`, adding it if missing, and returns the previous value.
+    ///
+    /// # Panics
+    /// Panics if `self` is not a struct or a struct-like enum variant. See
+    /// [`try_set_field`](Self::try_set_field) for a version that does not panic.
+    #[allow(clippy::expect_used)]
+    pub fn set_field(&mut self, name: &str, value: impl Into<Value>) -> Option<Value> {
+        self.try_set_field(name, value)
+            .expect("set_field called on a value without named fields")
+    }
+
+    /// Sets the field called `name` like [`set_field`](Self::set_field), but hands `value`
+    /// back as `Err` if `self` is not a struct or a struct-like enum variant.
+    pub fn try_set_field(
+        &mut self,
+        name: &str,
+        value: impl Into<Value>,
+    ) -> std::result::Result<Option<Value>, Value> {
+        let value = value.into();
+        let Some(fields) = self.fields_mut() else {
+            return Err(value);
+        };
+        match fields.iter_mut().find(|field| field.is_named(name)) {
+            Some(field) => Ok(Some(std::mem::replace(&mut field.value, value))),
+            None => {
+                fields.push(Field::new(name, value));
+                Ok(None)
+            }
+        }
+    }
+
+    /// Removes the field called `name` and returns its value.
+    pub fn remove_field(&mut self, name: &str) -> Option<Value> {
+        let fields = self.fields_mut()?;
+        let index = fields.iter().position(|field| field.is_named(name))?;
+        Some(fields.remove(index).value)
+    }
+
+    /// Returns the elements of a list, tuple or tuple struct.
+    pub fn as_list(&self) -> Option<&[Value]> {
+        match self {
+            Value::List(values) | Value::Tuple(values) | Value::TupleStruct(values) => Some(values),
+            _ => None,
+        }
+    }
+
+    /// Returns the elements of a list, tuple or tuple struct for editing.
+    pub fn as_list_mut(&mut self) -> Option<&mut Vec<Value>> {
+        match self {
+            Value::List(values) | Value::Tuple(values) | Value::TupleStruct(values) => Some(values),
+            _ => None,
+        }
+    }
+
+    /// Returns the string, if the value is one.
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    /// Returns the binary data, if the value is binary.
+    pub fn as_bytes(&self) -> Option<&[u8]> {
+        match self {
+            Value::Binary(b) => Some(b),
+            _ => None,
+        }
+    }
+
+    /// Returns the integer if it fits in a `u64`.
+    pub fn as_u64(&self) -> Option<u64> {
+        match self {
+            Value::UInt(v) => u64::try_from(*v).ok(),
+            _ => None,
+        }
+    }
+
+    /// Returns the integer if it fits in an `i64`.
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            Value::UInt(v) => i64::try_from(*v).ok(),
+            Value::Int(v) => i64::try_from(*v).ok(),
+            _ => None,
+        }
+    }
+
+    /// Returns the number as an `f64`.
+    ///
+    /// Floats are written as strings, so a string that parses as a float is accepted as well.
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Value::Float(v) => Some(*v),
+            Value::String(s) => s.parse().ok(),
+            _ => None,
+        }
+    }
+
+    /// Returns the `bool` written as `0` or `1`.
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Value::UInt(0) => Some(false),
+            Value::UInt(1) => Some(true),
+            _ => None,
+        }
+    }
+
+    /// Returns the value inside `Some`, `None` for `None`, and the value itself otherwise.
+    ///
+    /// Derived structs write present `Option` fields without `Some`, so this reads optional
+    /// values the same way wherever they come from.
+    pub fn as_option(&self) -> Option<&Value> {
+        match self {
+            Value::None => None,
+            Value::Some(inner) => Some(inner),
+            value => Some(value),
+        }
+    }
+}
+
+/// Decodes any value in the encode format. Field and variant names are `None`.
+impl Decoder for Value {
+    fn decode(reader: &mut Bytes) -> Result<Self> {
+        read_value(reader, None, 1)
+    }
+}
+
+/// Writes the value back in the encode format, with field and variant IDs but without names.
+impl Encoder for Value {
+    fn encode(&self, writer: &mut BytesMut) -> Result<()> {
+        write_value(self, writer)
+    }
+
+    fn is_default(&self) -> bool {
+        matches!(self, Value::None)
+    }
+}
+
+macro_rules! impl_from_uint {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Value {
+                fn from(v: $t) -> Self {
+                    Value::UInt(v as u128)
+                }
+            }
+        )*
+    };
+}
+
+impl_from_uint!(u8, u16, u32, u64, u128, usize);
+
+macro_rules! impl_from_int {
+    ($($t:ty),*) => {
+        $(
+            /// Non-negative numbers become [`Value::UInt`], as they are written.
+            impl From<$t> for Value {
+                fn from(v: $t) -> Self {
+                    if v >= 0 {
+                        Value::UInt(v as u128)
+                    } else {
+                        Value::Int(v as i128)
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_from_int!(i8, i16, i32, i64, i128, isize);
+
+impl From<bool> for Value {
+    fn from(v: bool) -> Self {
+        Value::UInt(v as u128)
+    }
+}
+
+/// Floats become strings, as they are written.
+impl From<f64> for Value {
+    fn from(v: f64) -> Self {
+        Value::String(format!("{:e}", v))
+    }
+}
+
+/// Floats become strings, as they are written.
+impl From<f32> for Value {
+    fn from(v: f32) -> Self {
+        Value::String(format!("{:e}", v))
+    }
+}
+
+impl From<&str> for Value {
+    fn from(v: &str) -> Self {
+        Value::String(v.to_string())
+    }
+}
+
+impl From<String> for Value {
+    fn from(v: String) -> Self {
+        Value::String(v)
+    }
+}
+
+impl From<Bytes> for Value {
+    fn from(v: Bytes) -> Self {
+        Value::Binary(v)
+    }
+}
+
+impl<T: Into<Value>> From<Option<T>> for Value {
+    fn from(v: Option<T>) -> Self {
+        match v {
+            Some(v) => Value::Some(Box::new(v.into())),
+            None => Value::None,
+        }
+    }
+}
+
+impl<T: Into<Value>> From<Vec<T>> for Value {
+    fn from(v: Vec<T>) -> Self {
+        Value::List(v.into_iter().map(Into::into).collect())
+    }
+}
+
+/// Converts a value of any encodable type into a [`Value`] by encoding it.
+///
+/// # Example
+/// ```rust
+/// use senax_encoder::value::{from_value, to_value};
+/// use senax_encoder::{Decode, Encode};
+///
+/// #[derive(Encode, Decode, Debug, PartialEq)]
+/// struct Item { name: String, quantity: u32 }
+///
+/// let mut value = to_value(&Item { name: "apple".into(), quantity: 3 }).unwrap();
+/// value.set_field("quantity", 5u32);
+/// let item: Item = from_value(&value).unwrap();
+/// assert_eq!(item, Item { name: "apple".into(), quantity: 5 });
+/// ```
+pub fn to_value<T: Encoder>(value: &T) -> Result<Value> {
+    let mut writer = BytesMut::new();
+    value.encode(&mut writer)?;
+    Value::decode(&mut writer.freeze())
+}
+
+/// Converts a [`Value`] into `T` by encoding it and decoding the result.
+pub fn from_value<T: Decoder>(value: &Value) -> Result<T> {
+    let mut writer = BytesMut::new();
+    value.encode(&mut writer)?;
+    T::decode(&mut writer.freeze())
+}
+
+/// Reads one value. With `names`, every field and variant ID is followed by an index into the
+/// name table, as written by [`crate::self_describing`].
+pub(crate) fn read_value(
+    reader: &mut Bytes,
+    names: Option<&[String]>,
+    depth: usize,
+) -> Result<Value> {
+    if depth > MAX_VALUE_DEPTH {
+        return Err(EncoderError::Decode(format!(
+            "Nesting depth exceeds {}",
+            MAX_VALUE_DEPTH
+        )));
+    }
+    if reader.remaining() == 0 {
+        return Err(EncoderError::eof(1, 0));
+    }
+    let tag = reader.get_u8();
+    let value = match tag {
+        TAG_ZERO..=TAG_U8_127 | TAG_U8 | TAG_U16 | TAG_U32 | TAG_U64 | TAG_U128 => {
+            Value::UInt(decode_u128_from_tag(tag, reader)?)
+        }
+        TAG_NEGATIVE => Value::Int(!u128::decode(reader)? as i128),
+        TAG_NONE => Value::None,
+        TAG_SOME => Value::Some(Box::new(read_value(reader, names, depth + 1)?)),
+        TAG_F32 => {
+            check_len(reader, 4)?;
+            Value::Float(reader.get_f32_le() as f64)
+        }
+        TAG_F64 => {
+            check_len(reader, 8)?;
+            Value::Float(reader.get_f64_le())
+        }
+        TAG_STRING_BASE..=TAG_STRING_LONG => {
+            let len = if tag < TAG_STRING_LONG {
+                (tag - TAG_STRING_BASE) as usize
+            } else {
+                usize::decode(reader)?
+            };
+            check_len(reader, len)?;
+            crate::config::check_string_len(len)?;
+            let s = String::from_utf8(reader.split_to(len).to_vec())
+                .map_err(|e| EncoderError::Decode(format!("Invalid UTF-8 string: {}", e)))?;
+            Value::String(crate::core::normalize_decoded(s))
+        }
+        TAG_BINARY => {
+            let len = usize::decode(reader)?;
+            check_len(reader, len)?;
+            Value::Binary(crate::core::split_bytes(reader, len)?)
+        }
+        TAG_ARRAY_VEC_SET_BASE..=TAG_ARRAY_VEC_SET_LONG => {
+            let len = if tag < TAG_ARRAY_VEC_SET_LONG {
+                (tag - TAG_ARRAY_VEC_SET_BASE) as usize
+            } else {
+                usize::decode(reader)?
+            };
+            Value::List(read_many(reader, len, names, depth)?)
+        }
+        TAG_TUPLE => {
+            let len = usize::decode(reader)?;
+            Value::Tuple(read_many(reader, len, names, depth)?)
+        }
+        TAG_MAP => {
+            let len = usize::decode(reader)?;
+            // Every entry takes at least two bytes
+            check_len(reader, len.saturating_mul(2))?;
+            let mut entries =
+                Vec::with_capacity(collection_capacity::<(Value, Value)>(len, reader)?);
+            for _ in 0..len {
+                let key = read_value(reader, names, depth + 1)?;
+                let value = read_value(reader, names, depth + 1)?;
+                entries.push((key, value));
+            }
+            Value::Map(entries)
+        }
+        TAG_MAP_FLAT_KEYS => {
+            check_len(reader, 1)?;
+            let arity = reader.get_u8() as usize;
+            let len = usize::decode(reader)?;
+            check_len(reader, len.saturating_mul(arity + 1))?;
+            let mut entries =
+                Vec::with_capacity(collection_capacity::<(Value, Value)>(len, reader)?);
+            for _ in 0..len {
+                let key = Value::Tuple(read_many(reader, arity, names, depth + 1)?);
+                let value = read_value(reader, names, depth + 1)?;
+                entries.push((key, value));
+            }
+            Value::Map(entries)
+        }
+        TAG_MAP_STRING_KEYS => {
+            let len = usize::decode(reader)?;
+            check_len(reader, len.saturating_mul(2))?;
+            let mut entries =
+                Vec::with_capacity(collection_capacity::<(Value, Value)>(len, reader)?);
+            for _ in 0..len {
+                let key = read_string_key(reader)?;
+                let value = read_value(reader, names, depth + 1)?;
+                entries.push((Value::String(key), value));
+            }
+            Value::Map(entries)
+        }
+        TAG_MAP_ID_KEYS => {
+            let len = usize::decode(reader)?;
+            check_len(reader, len.saturating_mul(2))?;
+            let mut entries =
+                Vec::with_capacity(collection_capacity::<(Value, Value)>(len, reader)?);
+            for _ in 0..len {
+                let key = read_field_id_optimized(reader)?;
+                let value = read_value(reader, names, depth + 1)?;
+                entries.push((Value::UInt(key as u128), value));
+            }
+            Value::Map(entries)
+        }
+        TAG_STRUCT_UNIT => Value::UnitStruct,
+        TAG_STRUCT_NAMED => Value::Struct(read_fields(reader, names, depth)?),
+        TAG_STRUCT_UNNAMED => {
+            let len = usize::decode(reader)?;
+            Value::TupleStruct(read_many(reader, len, names, depth)?)
+        }
+        TAG_ENUM | TAG_ENUM_NAMED | TAG_ENUM_UNNAMED => {
+            let (id, name) = read_id(reader, names)?;
+            let fields = match tag {
+                TAG_ENUM => VariantFields::Unit,
+                TAG_ENUM_NAMED => VariantFields::Named(read_fields(reader, names, depth)?),
+                _ => {
+                    let len = usize::decode(reader)?;
+                    VariantFields::Unnamed(read_many(reader, len, names, depth)?)
+                }
+            };
+            Value::Enum(Box::new(Variant { id, name, fields }))
+        }
+        TAG_UUID => {
+            check_len(reader, 16)?;
+            let mut bytes = [0u8; 16];
+            reader.copy_to_slice(&mut bytes);
+            Value::Uuid(bytes)
+        }
+        TAG_JSON_NULL => Value::Extension {
+            tag,
+            values: Vec::new(),
+        },
+        TAG_CHRONO_NAIVE_DATE | TAG_JSON_BOOL | TAG_JSON_STRING => Value::Extension {
+            tag,
+            values: read_many(reader, 1, names, depth)?,
+        },
+        TAG_CHRONO_DATETIME
+        | TAG_CHRONO_NAIVE_TIME
+        | TAG_CHRONO_NAIVE_DATETIME
+        | TAG_DECIMAL
+        | TAG_DURATION
+        | TAG_SYSTEM_TIME => Value::Extension {
+            tag,
+            values: read_many(reader, 2, names, depth)?,
+        },
+        TAG_JSON_NUMBER => {
+            check_len(reader, 1)?;
+            let marker = Value::UInt(reader.get_u8() as u128);
+            let mut values = vec![marker];
+            values.push(read_value(reader, names, depth + 1)?);
+            Value::Extension { tag, values }
+        }
+        TAG_JSON_ARRAY => {
+            let len = usize::decode(reader)?;
+            Value::Extension {
+                tag,
+                values: read_many(reader, len, names, depth)?,
+            }
+        }
+        TAG_JSON_OBJECT => {
+            let len = usize::decode(reader)?;
+            let count = len.saturating_mul(2);
+            Value::Extension {
+                tag,
+                values: read_many(reader, count, names, depth)?,
+            }
+        }
+        _ => return Err(EncoderError::Decode(format!("Unknown tag {}", tag))),
+    };
+    Ok(value)
+}
+
+/// Writes `value` in the encode format, without field or variant names.
+pub(crate) fn write_value(value: &Value, writer: &mut BytesMut) -> Result<()> {
+    match value {
+        Value::None => writer.put_u8(TAG_NONE),
+        Value::Some(inner) => {
+            writer.put_u8(TAG_SOME);
+            write_value(inner, writer)?;
+        }
+        Value::UInt(v) => v.encode(writer)?,
+        Value::Int(v) if *v >= 0 => (*v as u128).encode(writer)?,
+        Value::Int(v) => {
+            writer.put_u8(TAG_NEGATIVE);
+            (!*v as u128).encode(writer)?;
+        }
+        Value::Float(v) => {
+            writer.put_u8(TAG_F64);
+            writer.put_f64_le(*v);
+        }
+        Value::String(s) => write_str(s, writer)?,
+        Value::Binary(b) => b.encode(writer)?,
+        Value::List(values) => {
+            encode_vec_length(values.len(), writer)?;
+            write_values(values, writer)?;
+        }
+        Value::Tuple(values) => {
+            writer.put_u8(TAG_TUPLE);
+            values.len().encode(writer)?;
+            write_values(values, writer)?;
+        }
+        Value::Map(entries) => {
+            writer.put_u8(TAG_MAP);
+            entries.len().encode(writer)?;
+            for (key, value) in entries {
+                write_value(key, writer)?;
+                write_value(value, writer)?;
+            }
+        }
+        Value::UnitStruct => writer.put_u8(TAG_STRUCT_UNIT),
+        Value::Struct(fields) => {
+            writer.put_u8(TAG_STRUCT_NAMED);
+            write_fields(fields, writer)?;
+        }
+        Value::TupleStruct(values) => {
+            writer.put_u8(TAG_STRUCT_UNNAMED);
+            values.len().encode(writer)?;
+            write_values(values, writer)?;
+        }
+        Value::Enum(variant) => match &variant.fields {
+            VariantFields::Unit => {
+                writer.put_u8(TAG_ENUM);
+                write_field_id_optimized(writer, variant.id)?;
+            }
+            VariantFields::Named(fields) => {
+                writer.put_u8(TAG_ENUM_NAMED);
+                write_field_id_optimized(writer, variant.id)?;
+                write_fields(fields, writer)?;
+            }
+            VariantFields::Unnamed(values) => {
+                writer.put_u8(TAG_ENUM_UNNAMED);
+                write_field_id_optimized(writer, variant.id)?;
+                values.len().encode(writer)?;
+                write_values(values, writer)?;
+            }
+        },
+        Value::Uuid(bytes) => {
+            writer.put_u8(TAG_UUID);
+            writer.put_slice(bytes);
+        }
+        Value::Extension { tag, values } => {
+            writer.put_u8(*tag);
+            match (*tag, values.split_first()) {
+                (TAG_JSON_NUMBER, Some((Value::UInt(marker), rest))) if *marker <= 3 => {
+                    writer.put_u8(*marker as u8);
+                    write_values(rest, writer)?;
+                }
+                (TAG_JSON_NUMBER, _) => {
+                    return Err(EncoderError::Encode(
+                        "JSON number value must start with its type marker".to_string(),
+                    ))
+                }
+                (TAG_JSON_ARRAY, _) => {
+                    values.len().encode(writer)?;
+                    write_values(values, writer)?;
+                }
+                (TAG_JSON_OBJECT, _) => {
+                    (values.len() / 2).encode(writer)?;
+                    write_values(values, writer)?;
+                }
+                _ => write_values(values, writer)?,
+            }
+        }
+    }
+    Ok(())
+}
+
+fn write_values(values: &[Value], writer: &mut BytesMut) -> Result<()> {
+    for value in values {
+        write_value(value, writer)?;
+    }
+    Ok(())
+}
+
+fn write_fields(fields: &[Field], writer: &mut BytesMut) -> Result<()> {
+    for field in fields {
+        write_field_id_optimized(writer, field.id)?;
+        write_value(&field.value, writer)?;
+    }
+    write_field_id_optimized(writer, 0)
+}
+
+/// Reads `count` child values, rejecting counts that cannot fit in the remaining bytes.
+fn read_many(
+    reader: &mut Bytes,
+    count: usize,
+    names: Option<&[String]>,
+    depth: usize,
+) -> Result<Vec<Value>> {
+    // Every value takes at least one byte
+    check_len(reader, count)?;
+    let mut values = Vec::with_capacity(collection_capacity::<Value>(count, reader)?);
+    for _ in 0..count {
+        values.push(read_value(reader, names, depth + 1)?);
+    }
+    Ok(values)
+}
+
+/// Reads `[field_id] [value]` pairs up to the zero terminator.
+fn read_fields(reader: &mut Bytes, names: Option<&[String]>, depth: usize) -> Result<Vec<Field>> {
+    let mut fields = Vec::new();
+    loop {
+        let (id, name) = read_id(reader, names)?;
+        if id == 0 {
+            return Ok(fields);
+        }
+        let value = read_value(reader, names, depth + 1)?;
+        fields.push(Field { id, name, value });
+    }
+}
+
+/// Reads a field or variant ID and, with a name table, the name index that follows it.
+/// A field written by name gets the hash of its name as ID.
+pub(crate) fn read_id(
+    reader: &mut Bytes,
+    names: Option<&[String]>,
+) -> Result<(u64, Option<String>)> {
+    if reader.first() == Some(&FIELD_NAME_MARKER) {
+        reader.advance(1);
+        let name = read_field_name(reader)?;
+        return Ok((field_id_from_name(&name), Some(name)));
+    }
+    let id = read_field_id_optimized(reader)?;
+    let names = match names {
+        Some(names) if id != 0 => names,
+        _ => return Ok((id, None)),
+    };
+    let index = usize::decode(reader)?;
+    let name = names.get(index).ok_or_else(|| {
+        EncoderError::Decode(format!(
+            "Name index {} out of range for a table of {} names",
+            index,
+            names.len()
+        ))
+    })?;
+    Ok((id, Some(name.clone())))
+}
+
+fn check_len(reader: &Bytes, len: usize) -> Result<()> {
+    if reader.remaining() < len {
+        return Err(EncoderError::eof(len, reader.remaining()));
+    }
+    Ok(())
+}
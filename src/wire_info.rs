@@ -0,0 +1,306 @@
+//! Wire layout descriptions generated by `#[senax(wire_info)]`.
+//!
+//! Types deriving `Encode` with `#[senax(wire_info)]` implement [`WireInfo`], which lists the
+//! field and variant IDs the derived encoder writes and, for types implementing `Default`,
+//! dumps the annotated encoding of the default value. This makes a type's byte layout easy to
+//! inspect from a test or a debugger without reading the derive's output.
+//!
+//! ```rust
+//! use senax_encoder::wire_info::WireInfo;
+//! use senax_encoder::Encode;
+//!
+//! #[derive(Encode, Default)]
+//! #[senax(wire_info)]
+//! struct Point {
+//!     #[senax(id = 1)]
+//!     x: i32,
+//!     #[senax(id = 2)]
+//!     y: i32,
+//!     label: Option<String>,
+//! }
+//!
+//! let fields = Point::wire_fields();
+//! assert_eq!(fields[0].name, "x");
+//! assert_eq!(fields[1].id, 2);
+//! assert_eq!(fields[2].type_name, "Option<String>");
+//!
+//! println!("{}", Point::wire_example().unwrap());
+//! // b7                             struct
+//! // 01 00                          `x`: i32 (id 1) = UInt(0)
+//! // 02 00                          `y`: i32 (id 2) = UInt(0)
+//! // 00                             end
+//!
+//! // Any value, one line per field: name → id → tag → bytes
+//! let point = Point { x: 3, y: -1, label: None };
+//! assert_eq!(
+//!     point.fmt_wire(),
+//!     "struct → b7\n\
+//!      `x` → id 1 → tag 0x03 (uint) → 01 03\n\
+//!      `y` → id 2 → tag 0x88 (int) → 02 88 00\n\
+//!      end → 00\n"
+//! );
+//! ```
+
+use crate::core::{
+    read_field_id_optimized, skip_value, TAG_ENUM, TAG_ENUM_NAMED, TAG_ENUM_UNNAMED,
+    TAG_STRUCT_NAMED, TAG_STRUCT_UNIT, TAG_STRUCT_UNNAMED,
+};
+use crate::debug::kind_of;
+use crate::value::Value;
+use crate::{Decoder, Encoder, EncoderError, Result};
+use bytes::{Buf, Bytes, BytesMut};
+use std::fmt::Write as _;
+
+/// A field written by a derived encoder.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WireField {
+    /// The Rust field name, or its position for tuple fields.
+    pub name: &'static str,
+    /// The field ID; for tuple fields, the position.
+    pub id: u64,
+    /// The field type as written in the source.
+    pub type_name: &'static str,
+}
+
+/// An enum variant written by a derived encoder.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WireVariant {
+    /// The Rust variant name.
+    pub name: &'static str,
+    /// The variant ID.
+    pub id: u64,
+    /// The fields of the variant.
+    pub fields: &'static [WireField],
+}
+
+/// Describes the wire layout of a type; implemented by `#[senax(wire_info)]`.
+pub trait WireInfo {
+    /// The fields of a struct, in encode order. Fields marked `skip_encode` are not listed.
+    fn wire_fields() -> &'static [WireField] {
+        &[]
+    }
+
+    /// The variants of an enum, in declaration order.
+    fn wire_variants() -> &'static [WireVariant] {
+        &[]
+    }
+
+    /// Encodes `Self::default()` and returns its bytes annotated line by line.
+    fn wire_example() -> Result<String>
+    where
+        Self: Default + Encoder + Sized,
+    {
+        let mut writer = BytesMut::new();
+        Self::default().encode(&mut writer)?;
+        annotate(&writer.freeze(), Self::wire_fields(), Self::wire_variants())
+    }
+
+    /// Encodes `self` and renders the encoding one field per line as
+    /// `name → id → tag → bytes`, for test failures and bug reports.
+    ///
+    /// The bytes of all lines together are the encoding of the value. Encode errors are
+    /// rendered in place of the layout.
+    fn fmt_wire(&self) -> String
+    where
+        Self: Encoder,
+    {
+        let mut writer = BytesMut::new();
+        if let Err(e) = self.encode(&mut writer) {
+            return format!("<encode error: {}>", e);
+        }
+        annotate_with(
+            &writer.freeze(),
+            Self::wire_fields(),
+            Self::wire_variants(),
+            Style::Arrows,
+        )
+        .unwrap_or_else(|e| format!("<annotate error: {}>", e))
+    }
+}
+
+/// How each annotated line is laid out.
+#[derive(Clone, Copy)]
+enum Style {
+    /// Hex bytes in a column, then the meaning ([`annotate`]).
+    Columns,
+    /// Meaning first, bytes last ([`WireInfo::fmt_wire`]).
+    Arrows,
+}
+
+/// Annotates the encoding of a value, naming the fields and variants listed in `fields` and
+/// `variants`. Each line shows the bytes in hex followed by their meaning.
+pub fn annotate(bytes: &Bytes, fields: &[WireField], variants: &[WireVariant]) -> Result<String> {
+    annotate_with(bytes, fields, variants, Style::Columns)
+}
+
+fn annotate_with(
+    bytes: &Bytes,
+    fields: &[WireField],
+    variants: &[WireVariant],
+    style: Style,
+) -> Result<String> {
+    let mut out = String::new();
+    let mut reader = bytes.clone();
+    if !reader.has_remaining() {
+        return Err(EncoderError::eof(1, 0));
+    }
+    match reader[0] {
+        TAG_STRUCT_UNIT => {
+            let tag = reader.split_to(1);
+            line(&mut out, style, &tag, "unit struct");
+        }
+        TAG_STRUCT_NAMED => {
+            let tag = reader.split_to(1);
+            line(&mut out, style, &tag, "struct");
+            annotate_named(&mut reader, fields, style, &mut out)?;
+        }
+        TAG_STRUCT_UNNAMED => {
+            let mut count = 0;
+            let header = take(&mut reader, |r| {
+                r.advance(1);
+                count = usize::decode(r)?;
+                Ok(())
+            })?;
+            line(
+                &mut out,
+                style,
+                &header,
+                &format!("tuple struct, {} fields", count),
+            );
+            annotate_unnamed(&mut reader, count, fields, style, &mut out)?;
+        }
+        tag @ (TAG_ENUM | TAG_ENUM_NAMED | TAG_ENUM_UNNAMED) => {
+            let mut id = 0;
+            let header = take(&mut reader, |r| {
+                r.advance(1);
+                id = read_field_id_optimized(r)?;
+                Ok(())
+            })?;
+            let variant = variants.iter().find(|v| v.id == id);
+            let note = match variant {
+                Some(v) => format!("variant `{}` (id {})", v.name, id),
+                None => format!("variant id {}", id),
+            };
+            line(&mut out, style, &header, &note);
+            let fields = variant.map_or(&[][..], |v| v.fields);
+            if tag == TAG_ENUM_NAMED {
+                annotate_named(&mut reader, fields, style, &mut out)?;
+            } else if tag == TAG_ENUM_UNNAMED {
+                let mut count = 0;
+                let header = take(&mut reader, |r| {
+                    count = usize::decode(r)?;
+                    Ok(())
+                })?;
+                line(&mut out, style, &header, &format!("{} fields", count));
+                annotate_unnamed(&mut reader, count, fields, style, &mut out)?;
+            }
+        }
+        _ => {
+            let mut value = Value::None;
+            let bytes = take(&mut reader, |r| {
+                value = Value::decode(r)?;
+                Ok(())
+            })?;
+            let note = match style {
+                Style::Columns => format!("{:?}", value),
+                Style::Arrows => format!("value → {}", tag_note(&bytes)),
+            };
+            line(&mut out, style, &bytes, &note);
+        }
+    }
+    Ok(out)
+}
+
+fn annotate_named(
+    reader: &mut Bytes,
+    fields: &[WireField],
+    style: Style,
+    out: &mut String,
+) -> Result<()> {
+    loop {
+        let mut id = 0;
+        let id_bytes = take(reader, |r| {
+            id = read_field_id_optimized(r)?;
+            Ok(())
+        })?;
+        if id == 0 {
+            line(out, style, &id_bytes, "end");
+            return Ok(());
+        }
+        let (value_bytes, value) = take_value(reader)?;
+        let mut bytes = id_bytes.to_vec();
+        bytes.extend_from_slice(&value_bytes);
+        let field = fields.iter().find(|f| f.id == id);
+        let note = match (style, field) {
+            (Style::Columns, Some(f)) => {
+                format!("`{}`: {} (id {}) = {:?}", f.name, f.type_name, id, value)
+            }
+            (Style::Columns, None) => format!("unknown field (id {}) = {:?}", id, value),
+            (Style::Arrows, Some(f)) => {
+                format!("`{}` → id {} → {}", f.name, id, tag_note(&value_bytes))
+            }
+            (Style::Arrows, None) => {
+                format!("unknown field → id {} → {}", id, tag_note(&value_bytes))
+            }
+        };
+        line(out, style, &bytes, &note);
+    }
+}
+
+fn annotate_unnamed(
+    reader: &mut Bytes,
+    count: usize,
+    fields: &[WireField],
+    style: Style,
+    out: &mut String,
+) -> Result<()> {
+    for position in 0..count {
+        let (bytes, value) = take_value(reader)?;
+        let note = match (style, fields.get(position)) {
+            (Style::Columns, Some(f)) => format!("`{}`: {} = {:?}", f.name, f.type_name, value),
+            (Style::Columns, None) => format!("extra field {} = {:?}", position, value),
+            (Style::Arrows, Some(f)) => {
+                format!("`{}` → id {} → {}", f.name, position, tag_note(&bytes))
+            }
+            (Style::Arrows, None) => {
+                format!("extra field → id {} → {}", position, tag_note(&bytes))
+            }
+        };
+        line(out, style, &bytes, &note);
+    }
+    Ok(())
+}
+
+/// Runs `read` and returns the bytes it consumed.
+fn take(reader: &mut Bytes, read: impl FnOnce(&mut Bytes) -> Result<()>) -> Result<Bytes> {
+    let before = reader.clone();
+    read(reader)?;
+    Ok(before.slice(..before.len() - reader.len()))
+}
+
+/// Reads one value, returning its bytes and its dynamic form.
+fn take_value(reader: &mut Bytes) -> Result<(Bytes, Value)> {
+    let bytes = take(reader, skip_value)?;
+    let value = Value::decode(&mut bytes.clone())?;
+    Ok((bytes, value))
+}
+
+/// Describes the tag that starts `bytes`.
+fn tag_note(bytes: &[u8]) -> String {
+    match bytes.first() {
+        Some(&tag) => format!("tag 0x{:02x} ({})", tag, kind_of(tag)),
+        None => "no tag".to_string(),
+    }
+}
+
+fn line(out: &mut String, style: Style, bytes: &[u8], note: &str) {
+    let hex = bytes
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect::<Vec<_>>()
+        .join(" ");
+    let _ = match style {
+        Style::Columns => writeln!(out, "{:<30} {}", hex, note),
+        Style::Arrows => writeln!(out, "{} → {}", note, hex),
+    };
+}
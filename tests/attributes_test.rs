@@ -767,3 +767,530 @@ fn test_enum_with_u8_id() {
     // bytes[1] should contain variant ID 1 as u8
     assert_eq!(bytes[1], 1);
 }
+
+// =============================================================================
+// #[senax(tolerant)] test
+// =============================================================================
+
+#[derive(Encode, Decode, Debug, PartialEq)]
+enum ShapeV1 {
+    #[senax(id = 1, tolerant)]
+    Point(i32, i32),
+    #[senax(id = 2)]
+    Strict(i32),
+}
+
+#[derive(Encode, Decode, Debug, PartialEq)]
+enum ShapeV2 {
+    #[senax(id = 1, tolerant)]
+    Point(i32, i32, String, Vec<u8>),
+    #[senax(id = 2)]
+    Strict(i32, i32),
+}
+
+#[test]
+fn test_tolerant_variant_skips_extra_fields() {
+    let newer = ShapeV2::Point(1, 2, "label".to_string(), vec![9, 9]);
+    let mut buffer = BytesMut::new();
+    newer.encode(&mut buffer).unwrap();
+    let mut bytes = buffer.freeze();
+
+    let older = ShapeV1::decode(&mut bytes).unwrap();
+    assert_eq!(older, ShapeV1::Point(1, 2));
+    assert!(bytes.is_empty());
+}
+
+#[test]
+fn test_tolerant_variant_rejects_missing_fields() {
+    let mut buffer = BytesMut::new();
+    ShapeV1::Point(1, 2).encode(&mut buffer).unwrap();
+    let result = ShapeV2::decode(&mut buffer.freeze());
+    assert!(matches!(
+        result,
+        Err(senax_encoder::EncoderError::EnumDecode(
+            senax_encoder::EnumDecodeError::FieldCountMismatch {
+                expected: 4,
+                actual: 2,
+                ..
+            }
+        ))
+    ));
+}
+
+#[test]
+fn test_non_tolerant_variant_rejects_extra_fields() {
+    let mut buffer = BytesMut::new();
+    ShapeV2::Strict(1, 2).encode(&mut buffer).unwrap();
+    let result = ShapeV1::decode(&mut buffer.freeze());
+    assert!(matches!(
+        result,
+        Err(senax_encoder::EncoderError::EnumDecode(
+            senax_encoder::EnumDecodeError::FieldCountMismatch { .. }
+        ))
+    ));
+}
+
+// =============================================================================
+// Named struct <-> tuple struct migration test
+// =============================================================================
+
+#[derive(Encode, Decode, Debug, PartialEq)]
+struct NamedPoint {
+    x: i32,
+    y: i32,
+    label: Option<String>,
+}
+
+#[derive(Encode, Decode, Debug, PartialEq)]
+struct TuplePoint(i32, i32, Option<String>);
+
+#[derive(Encode, Decode, Debug, PartialEq)]
+#[senax(accept_named)]
+struct RenamedTuplePoint(
+    #[senax(rename = "y")] i32,
+    #[senax(rename = "x")] i32,
+    #[senax(rename = "label")] Option<String>,
+    #[senax(rename = "z", default)] u8,
+);
+
+#[derive(Encode, Decode, Debug, PartialEq)]
+#[senax(accept_tuple)]
+struct AcceptTuplePoint {
+    x: i32,
+    y: i32,
+    #[senax(default)]
+    z: i32,
+}
+
+#[test]
+fn test_tuple_struct_rejects_named_payload_without_opt_in() {
+    let named = NamedPoint {
+        x: 1,
+        y: 2,
+        label: Some("p".to_string()),
+    };
+    let mut buffer = BytesMut::new();
+    named.encode(&mut buffer).unwrap();
+    assert!(matches!(
+        TuplePoint::decode(&mut buffer.freeze()),
+        Err(senax_encoder::EncoderError::StructDecode(
+            senax_encoder::StructDecodeError::InvalidTag { .. }
+        ))
+    ));
+}
+
+#[test]
+fn test_tuple_struct_decodes_named_payload_by_field_id() {
+    let named = NamedPoint {
+        x: 10,
+        y: 20,
+        label: Some("p".to_string()),
+    };
+    let mut buffer = BytesMut::new();
+    named.encode(&mut buffer).unwrap();
+    let decoded = RenamedTuplePoint::decode(&mut buffer.freeze()).unwrap();
+    assert_eq!(decoded, RenamedTuplePoint(20, 10, Some("p".to_string()), 0));
+
+    let named = NamedPoint {
+        x: 10,
+        y: 20,
+        label: None,
+    };
+    let mut buffer = BytesMut::new();
+    named.encode(&mut buffer).unwrap();
+    let decoded = RenamedTuplePoint::decode(&mut buffer.freeze()).unwrap();
+    assert_eq!(decoded, RenamedTuplePoint(20, 10, None, 0));
+}
+
+#[test]
+fn test_tuple_struct_does_not_shift_past_omitted_fields() {
+    #[derive(Encode)]
+    struct Old {
+        a: Option<i32>,
+        b: Option<i32>,
+    }
+    #[derive(Decode, Debug, PartialEq)]
+    #[senax(accept_named)]
+    struct New(
+        #[senax(rename = "a")] Option<i32>,
+        #[senax(rename = "b")] Option<i32>,
+    );
+
+    let mut buffer = BytesMut::new();
+    Old {
+        a: None,
+        b: Some(7),
+    }
+    .encode(&mut buffer)
+    .unwrap();
+    let decoded = New::decode(&mut buffer.freeze()).unwrap();
+    assert_eq!(decoded, New(None, Some(7)));
+}
+
+#[test]
+fn test_tuple_struct_reports_missing_named_field() {
+    #[derive(Encode)]
+    struct OnlyX {
+        x: i32,
+    }
+    let mut buffer = BytesMut::new();
+    OnlyX { x: 1 }.encode(&mut buffer).unwrap();
+    let result = RenamedTuplePoint::decode(&mut buffer.freeze());
+    assert!(matches!(
+        result,
+        Err(senax_encoder::EncoderError::StructDecode(
+            senax_encoder::StructDecodeError::MissingRequiredField { field: "0", .. }
+        ))
+    ));
+}
+
+#[test]
+fn test_named_struct_accepts_tuple_payload_when_opted_in() {
+    let mut buffer = BytesMut::new();
+    TuplePoint(3, 4, None).encode(&mut buffer).unwrap();
+    let bytes = buffer.freeze();
+
+    // The third tuple value is an Option<String> and does not fit `z: i32`.
+    assert!(AcceptTuplePoint::decode(&mut bytes.clone()).is_err());
+    // Without the opt-in, tuple payloads are rejected by tag.
+    assert!(matches!(
+        NamedPoint::decode(&mut bytes.clone()),
+        Err(senax_encoder::EncoderError::StructDecode(
+            senax_encoder::StructDecodeError::InvalidTag { .. }
+        ))
+    ));
+
+    #[derive(Encode)]
+    struct Pair(i32, i32);
+    let mut buffer = BytesMut::new();
+    Pair(3, 4).encode(&mut buffer).unwrap();
+    let decoded = AcceptTuplePoint::decode(&mut buffer.freeze()).unwrap();
+    assert_eq!(decoded, AcceptTuplePoint { x: 3, y: 4, z: 0 });
+}
+
+// =============================================================================
+// #[senax(discriminant_ids)] test
+// =============================================================================
+
+#[derive(Encode, Decode, Debug, PartialEq, Clone, Copy)]
+#[senax(discriminant_ids)]
+enum Opcode {
+    Nop = 0,
+    Load = 3,
+    Store, // 4
+    #[senax(id = 100)]
+    Halt = 200,
+    Jump = 300,
+}
+
+#[derive(Encode, Decode, Debug, PartialEq)]
+#[senax(discriminant_ids)]
+#[repr(u8)]
+enum Frame {
+    Ping = 1,
+    Data(Vec<u8>) = 2,
+    Error { code: u16 } = 7,
+}
+
+#[test]
+fn test_discriminant_ids_are_variant_ids() {
+    let cases = [
+        (Opcode::Nop, 0u64),
+        (Opcode::Load, 3),
+        (Opcode::Store, 4),
+        (Opcode::Halt, 100),
+        (Opcode::Jump, 300),
+    ];
+    for (op, id) in cases {
+        let mut buffer = BytesMut::new();
+        op.encode(&mut buffer).unwrap();
+        let mut expected = BytesMut::new();
+        expected.extend_from_slice(&[senax_encoder::core::TAG_ENUM]);
+        senax_encoder::core::write_field_id_optimized(&mut expected, id).unwrap();
+        assert_eq!(buffer, expected, "{:?}", op);
+        assert_eq!(Opcode::decode(&mut buffer.freeze()).unwrap(), op);
+    }
+}
+
+#[test]
+fn test_discriminant_ids_with_fields() {
+    for value in [
+        Frame::Ping,
+        Frame::Data(vec![1, 2, 3]),
+        Frame::Error { code: 404 },
+    ] {
+        let mut buffer = BytesMut::new();
+        value.encode(&mut buffer).unwrap();
+        let bytes = buffer.freeze();
+        let expected_id = match value {
+            Frame::Ping => 1,
+            Frame::Data(_) => 2,
+            Frame::Error { .. } => 7,
+        };
+        assert_eq!(bytes[1], expected_id);
+        assert_eq!(Frame::decode(&mut bytes.clone()).unwrap(), value);
+    }
+}
+
+// =============================================================================
+// #[senax(string_repr)] test
+// =============================================================================
+
+#[derive(Encode, Decode, Debug, PartialEq)]
+#[senax(string_repr)]
+enum Status {
+    Active,
+    #[senax(rename = "on-hold")]
+    OnHold,
+    Failed {
+        reason: String,
+    },
+}
+
+#[derive(Encode, Decode, Debug, PartialEq)]
+enum StatusById {
+    Active,
+    #[senax(rename = "on-hold")]
+    OnHold,
+}
+
+#[test]
+fn test_string_repr_writes_variant_name() {
+    let mut buffer = BytesMut::new();
+    Status::Active.encode(&mut buffer).unwrap();
+    let mut expected = BytesMut::new();
+    "Active".to_string().encode(&mut expected).unwrap();
+    assert_eq!(buffer, expected);
+
+    let mut buffer = BytesMut::new();
+    Status::OnHold.encode(&mut buffer).unwrap();
+    let name = String::decode(&mut buffer.clone().freeze()).unwrap();
+    assert_eq!(name, "on-hold");
+    assert_eq!(
+        Status::decode(&mut buffer.freeze()).unwrap(),
+        Status::OnHold
+    );
+}
+
+#[test]
+fn test_string_repr_accepts_id_form_and_data_variants() {
+    // Payloads written before string_repr was enabled use variant IDs
+    let mut buffer = BytesMut::new();
+    StatusById::OnHold.encode(&mut buffer).unwrap();
+    assert_eq!(
+        Status::decode(&mut buffer.freeze()).unwrap(),
+        Status::OnHold
+    );
+
+    let failed = Status::Failed {
+        reason: "timeout".to_string(),
+    };
+    let mut buffer = BytesMut::new();
+    failed.encode(&mut buffer).unwrap();
+    assert_eq!(buffer[0], senax_encoder::core::TAG_ENUM_NAMED);
+    assert_eq!(Status::decode(&mut buffer.freeze()).unwrap(), failed);
+}
+
+#[test]
+fn test_string_repr_unknown_name() {
+    let mut buffer = BytesMut::new();
+    "Paused".to_string().encode(&mut buffer).unwrap();
+    let result = Status::decode(&mut buffer.freeze());
+    assert!(matches!(
+        result,
+        Err(senax_encoder::EncoderError::EnumDecode(
+            senax_encoder::EnumDecodeError::UnknownVariantName { ref name, .. }
+        )) if name == "Paused"
+    ));
+}
+
+// =============================================================================
+// Prefix decode tests
+// =============================================================================
+
+#[derive(Encode, Decode, Debug, PartialEq)]
+pub struct Envelope {
+    #[senax(prefix)]
+    pub route: String,
+    #[senax(prefix)]
+    pub trace_id: Option<u64>,
+    pub body: Vec<u8>,
+}
+
+#[test]
+fn test_decode_prefix_stops_before_body() {
+    let envelope = Envelope {
+        route: "users/get".to_string(),
+        trace_id: Some(42),
+        body: vec![1, 2, 3, 4],
+    };
+    let mut buffer = BytesMut::new();
+    envelope.encode(&mut buffer).unwrap();
+    let bytes = buffer.freeze();
+
+    let (prefix, rest) = Envelope::decode_prefix(&bytes).unwrap();
+    assert_eq!(prefix.route, "users/get");
+    assert_eq!(prefix.trace_id, Some(42));
+    assert!(rest.len() < bytes.len());
+    assert!(rest.ends_with(&[0]));
+
+    // The original bytes are untouched and still decode in full
+    assert_eq!(Envelope::decode(&mut bytes.clone()).unwrap(), envelope);
+}
+
+#[test]
+fn test_decode_prefix_missing_fields() {
+    // `None` fields are not written, so the prefix ends early
+    let envelope = Envelope {
+        route: "ping".to_string(),
+        trace_id: None,
+        body: vec![],
+    };
+    let mut buffer = BytesMut::new();
+    envelope.encode(&mut buffer).unwrap();
+    let (prefix, _) = Envelope::decode_prefix(&buffer.freeze()).unwrap();
+    assert_eq!(prefix.route, "ping");
+    assert_eq!(prefix.trace_id, None);
+
+    let mut buffer = BytesMut::new();
+    EnvelopeBodyOnly { body: vec![9] }
+        .encode(&mut buffer)
+        .unwrap();
+    let result = Envelope::decode_prefix(&buffer.freeze());
+    assert!(matches!(
+        result,
+        Err(senax_encoder::EncoderError::StructDecode(
+            senax_encoder::StructDecodeError::MissingRequiredField { field: "route", .. }
+        ))
+    ));
+}
+
+#[derive(Encode, Decode, Debug, PartialEq)]
+struct EnvelopeBodyOnly {
+    body: Vec<u8>,
+}
+
+// =============================================================================
+// Redaction tests
+// =============================================================================
+
+#[derive(Encode, Decode, Debug, PartialEq, Clone)]
+struct AuditRecord {
+    action: String,
+    #[senax(redact)]
+    email: String,
+    #[senax(redact)]
+    ip_address: Option<String>,
+    #[senax(redact, skip_default)]
+    tags: Vec<String>,
+}
+
+fn audit_record() -> AuditRecord {
+    AuditRecord {
+        action: "login".to_string(),
+        email: "bob@example.com".to_string(),
+        ip_address: Some("10.0.0.1".to_string()),
+        tags: vec!["vip".to_string()],
+    }
+}
+
+#[test]
+fn test_redact_replaces_fields_with_defaults() {
+    let config = senax_encoder::EncodeConfig {
+        redact: true,
+        ..Default::default()
+    };
+    let bytes = senax_encoder::encode_with_config(&audit_record(), &config).unwrap();
+    let decoded: AuditRecord = senax_encoder::decode(&mut bytes.clone()).unwrap();
+    assert_eq!(
+        decoded,
+        AuditRecord {
+            action: "login".to_string(),
+            email: String::new(),
+            ip_address: None,
+            tags: vec![],
+        }
+    );
+}
+
+#[test]
+fn test_redact_has_no_effect_by_default() {
+    let bytes = senax_encoder::encode(&audit_record()).unwrap();
+    let decoded: AuditRecord = senax_encoder::decode(&mut bytes.clone()).unwrap();
+    assert_eq!(decoded, audit_record());
+}
+
+#[derive(Encode, Decode, Debug, PartialEq)]
+#[senax(builder)]
+struct Invoice {
+    number: u64,
+    customer: String,
+    note: Option<String>,
+    #[senax(default)]
+    lines: Vec<String>,
+}
+
+#[derive(Encode, Debug, PartialEq)]
+struct InvoiceWithoutCustomer {
+    number: u64,
+}
+
+#[test]
+fn test_builder_builds_incrementally() {
+    let invoice = Invoice::builder()
+        .number(7)
+        .customer("ACME".to_string())
+        .note("rush".to_string())
+        .try_build()
+        .unwrap();
+    assert_eq!(
+        invoice,
+        Invoice {
+            number: 7,
+            customer: "ACME".to_string(),
+            note: Some("rush".to_string()),
+            lines: vec![],
+        }
+    );
+
+    let mut bytes = senax_encoder::encode(&invoice).unwrap();
+    let decoded: Invoice = senax_encoder::decode(&mut bytes).unwrap();
+    assert_eq!(decoded, invoice);
+}
+
+#[test]
+fn test_builder_shares_missing_field_diagnostics() {
+    let built = Invoice::builder().number(7).try_build();
+    let mut bytes = senax_encoder::encode(&InvoiceWithoutCustomer { number: 7 }).unwrap();
+    let decoded = senax_encoder::decode::<Invoice>(&mut bytes);
+    for result in [built, decoded] {
+        assert!(matches!(
+            result,
+            Err(senax_encoder::EncoderError::StructDecode(
+                senax_encoder::StructDecodeError::MissingRequiredField {
+                    field: "customer",
+                    struct_name: "Invoice",
+                }
+            ))
+        ));
+    }
+}
+
+#[derive(Encode, Decode, Debug, PartialEq)]
+#[senax(builder)]
+struct Tagged<T: Encoder + Decoder> {
+    tag: String,
+    value: T,
+}
+
+#[test]
+fn test_builder_on_generic_struct() {
+    let tagged = Tagged::builder()
+        .tag("count".to_string())
+        .value(3u32)
+        .try_build()
+        .unwrap();
+    let mut bytes = senax_encoder::encode(&tagged).unwrap();
+    let decoded: Tagged<u32> = senax_encoder::decode(&mut bytes).unwrap();
+    assert_eq!(decoded, tagged);
+}
@@ -0,0 +1,85 @@
+#![cfg(feature = "bincode")]
+
+use senax_encoder::compat::from_bincode;
+use senax_encoder::{decode, Decode, Encode, EncoderError};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+#[derive(Serialize, Deserialize, Encode, Decode, Debug, PartialEq, Clone)]
+enum Status {
+    Open,
+    Shipped { carrier: String, days: u8 },
+    Returned(String),
+}
+
+#[derive(Serialize, Deserialize, Encode, Decode, Debug, PartialEq, Clone)]
+struct Order {
+    id: u64,
+    customer: String,
+    total: f64,
+    note: Option<String>,
+    lines: Vec<(String, u32)>,
+    attributes: BTreeMap<String, i64>,
+    status: Status,
+}
+
+/// The order after migration, with a field added
+#[derive(Encode, Decode, Debug, PartialEq)]
+struct OrderV2 {
+    id: u64,
+    customer: String,
+    #[senax(default)]
+    priority: u8,
+}
+
+fn order() -> Order {
+    Order {
+        id: 9,
+        customer: "alice".to_string(),
+        total: 12.5,
+        note: None,
+        lines: vec![("apple".to_string(), 3), ("pear".to_string(), 1)],
+        attributes: [("gift".to_string(), -1)].into(),
+        status: Status::Shipped {
+            carrier: "post".to_string(),
+            days: 2,
+        },
+    }
+}
+
+#[test]
+fn test_migrates_payloads() {
+    for status in [
+        Status::Open,
+        Status::Returned("damaged".to_string()),
+        order().status,
+    ] {
+        let value = Order { status, ..order() };
+        let legacy = bincode::serialize(&value).unwrap();
+        let mut migrated = from_bincode::<Order>(&legacy).unwrap();
+        assert_eq!(decode::<Order>(&mut migrated).unwrap(), value);
+    }
+}
+
+#[test]
+fn test_migrated_payloads_evolve() {
+    let legacy = bincode::serialize(&order()).unwrap();
+    let mut migrated = from_bincode::<Order>(&legacy).unwrap();
+    let evolved: OrderV2 = decode(&mut migrated).unwrap();
+    assert_eq!(
+        evolved,
+        OrderV2 {
+            id: 9,
+            customer: "alice".to_string(),
+            priority: 0,
+        }
+    );
+}
+
+#[test]
+fn test_invalid_payloads() {
+    let legacy = bincode::serialize(&order()).unwrap();
+    let err = from_bincode::<Order>(&legacy[..legacy.len() / 2]).unwrap_err();
+    assert!(matches!(err, EncoderError::Decode(_)), "{:?}", err);
+    assert!(err.to_string().contains("bincode"), "{}", err);
+}
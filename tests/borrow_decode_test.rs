@@ -0,0 +1,188 @@
+use bytes::Bytes;
+use senax_encoder::{
+    decode, decode_borrowed, encode, with_decode_config, BorrowDecoder, BorrowReader, DecodeConfig,
+    Encode, EncoderError,
+};
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap};
+
+fn points_into(slice: &[u8], input: &[u8]) -> bool {
+    input.as_ptr_range().contains(&slice.as_ptr())
+}
+
+#[derive(Encode)]
+struct Message<'a> {
+    title: Cow<'a, str>,
+    body: &'a str,
+    attachment: Cow<'a, [u8]>,
+}
+
+#[test]
+fn test_strings_and_bytes_borrow_from_the_input() {
+    let long = "l".repeat(1000);
+    let input = encode(&(
+        "short".to_string(),
+        long.clone(),
+        Bytes::from_static(b"\x00\x01\x02"),
+        Bytes::from(vec![7u8; 500]),
+    ))
+    .unwrap();
+    let (short, long_str, small, large): (&str, &str, &[u8], Cow<[u8]>) =
+        decode_borrowed(&input).unwrap();
+    assert_eq!((short, long_str), ("short", long.as_str()));
+    assert_eq!(small, b"\x00\x01\x02");
+    assert!(matches!(&large, Cow::Borrowed(b) if b.len() == 500));
+    for slice in [short.as_bytes(), long_str.as_bytes(), small, &large] {
+        assert!(points_into(slice, &input));
+    }
+}
+
+#[test]
+fn test_borrowed_and_owned_encodings_match() {
+    let owned = encode(&(
+        "title".to_string(),
+        "body".to_string(),
+        Bytes::from_static(b"xyz"),
+    ))
+    .unwrap();
+    let message = Message {
+        title: Cow::Borrowed("title"),
+        body: "body",
+        attachment: Cow::Owned(b"xyz".to_vec()),
+    };
+    let (title, body, attachment) = (&message.title, message.body, &message.attachment);
+    assert_eq!(encode(&(title, body, attachment)).unwrap(), owned);
+
+    // Strings decode as `String` and `Bytes` as `&[u8]`, and the other way around
+    let input = encode(&("text", b"raw".as_slice())).unwrap();
+    let (text, raw): (String, Bytes) = decode(&mut input.clone()).unwrap();
+    assert_eq!((text.as_str(), &raw[..]), ("text", &b"raw"[..]));
+
+    let struct_input = encode(&message).unwrap();
+    assert!(decode::<senax_encoder::value::Value>(&mut struct_input.clone()).is_ok());
+}
+
+#[test]
+fn test_containers_of_borrowed_values() {
+    let names = vec!["a".to_string(), "bb".to_string(), "ccc".to_string()];
+    let labels: HashMap<String, u32> = [("x".to_string(), 1), ("y".to_string(), 2)].into();
+    let by_id: BTreeMap<u32, String> = [(1, "one".to_string()), (2, "two".to_string())].into();
+    let keyed = encode(&labels).unwrap();
+    let input = encode(&(names, Some("maybe".to_string()), labels, by_id)).unwrap();
+
+    #[allow(clippy::type_complexity)]
+    let (names, maybe, labels, by_id): (
+        Vec<&str>,
+        Option<&str>,
+        HashMap<&str, u32>,
+        BTreeMap<u32, Cow<str>>,
+    ) = decode_borrowed(&input).unwrap();
+    assert_eq!(names, vec!["a", "bb", "ccc"]);
+    assert_eq!(maybe, Some("maybe"));
+    assert_eq!(labels, [("x", 1), ("y", 2)].into());
+    assert_eq!(by_id[&2], "two");
+    for key in labels.keys() {
+        assert!(points_into(key.as_bytes(), &input));
+    }
+    assert!(points_into(names[2].as_bytes(), &input));
+    assert!(matches!(by_id[&1], Cow::Borrowed(_)));
+
+    // String keys also decode into owned and copy-on-write keys
+    let owned: BTreeMap<String, u32> = decode_borrowed(&keyed).unwrap();
+    assert_eq!(owned.len(), 2);
+    let cow: HashMap<Cow<str>, u32> = decode_borrowed(&keyed).unwrap();
+    assert_eq!(cow.len(), 2);
+    assert!(decode_borrowed::<HashMap<u32, u32>>(&keyed).is_err());
+}
+
+/// A user type decoding one borrowed and one owned field.
+#[derive(Debug, PartialEq)]
+struct Token<'a> {
+    name: &'a str,
+    expires: u64,
+}
+
+impl<'a> BorrowDecoder<'a> for Token<'a> {
+    fn decode_borrowed(reader: &mut BorrowReader<'a>) -> senax_encoder::Result<Self> {
+        let (name, expires) = BorrowDecoder::decode_borrowed(reader)?;
+        Ok(Token { name, expires })
+    }
+}
+
+#[test]
+fn test_user_types() {
+    let input = encode(&vec![("alpha", 10u64), ("beta", 20)]).unwrap();
+    let tokens: Vec<Token> = decode_borrowed(&input).unwrap();
+    assert_eq!(
+        tokens,
+        vec![
+            Token {
+                name: "alpha",
+                expires: 10
+            },
+            Token {
+                name: "beta",
+                expires: 20
+            }
+        ]
+    );
+
+    let mut reader = BorrowReader::new(&input);
+    assert_eq!(reader.remaining(), input.len());
+    assert_eq!(reader.read_slice(2).unwrap(), &input[..2]);
+    assert_eq!(reader.as_bytes(), &input[2..]);
+}
+
+#[test]
+fn test_copy_out_copies_byte_cows() {
+    let input = encode(&Bytes::from(vec![1u8; 32])).unwrap();
+    let config = DecodeConfig {
+        copy_out: true,
+        ..Default::default()
+    };
+    let copied: Cow<[u8]> = with_decode_config(&config, || decode_borrowed(&input)).unwrap();
+    assert!(matches!(copied, Cow::Owned(_)));
+    // A plain slice is always borrowed
+    let slice: &[u8] = with_decode_config(&config, || decode_borrowed(&input)).unwrap();
+    assert!(points_into(slice, &input));
+}
+
+#[cfg(feature = "unicode-normalization")]
+#[test]
+fn test_normalization_of_borrowed_strings() {
+    let config = DecodeConfig {
+        normalize_nfc: true,
+        ..Default::default()
+    };
+    let input = encode(&"Cafe\u{301}".to_string()).unwrap();
+    let cow: Cow<str> = with_decode_config(&config, || decode_borrowed(&input)).unwrap();
+    assert_eq!(cow, "Caf\u{e9}");
+    assert!(with_decode_config(&config, || decode_borrowed::<&str>(&input)).is_err());
+
+    // Already normalized strings stay borrowed
+    let input = encode(&"Caf\u{e9}".to_string()).unwrap();
+    let cow: Cow<str> = with_decode_config(&config, || decode_borrowed(&input)).unwrap();
+    assert!(matches!(cow, Cow::Borrowed(_)));
+    let text: &str = with_decode_config(&config, || decode_borrowed(&input)).unwrap();
+    assert_eq!(text, "Caf\u{e9}");
+}
+
+#[test]
+fn test_invalid_input() {
+    let input = encode(&"hello".to_string()).unwrap();
+    let truncated = input.slice(..input.len() - 1);
+    assert!(matches!(
+        decode_borrowed::<&str>(&truncated),
+        Err(EncoderError::UnexpectedEof { .. })
+    ));
+    assert!(decode_borrowed::<&str>(&input.slice(1..)).is_err());
+    assert!(decode_borrowed::<&str>(&encode(&5u32).unwrap()).is_err());
+    assert!(decode_borrowed::<(&str, u32)>(&encode(&("a", 1u32, 2u32)).unwrap()).is_err());
+
+    let mut bad_utf8 = input.to_vec();
+    bad_utf8[3] = 0xff;
+    assert!(matches!(
+        decode_borrowed::<&str>(&Bytes::from(bad_utf8)),
+        Err(EncoderError::Decode(_))
+    ));
+}
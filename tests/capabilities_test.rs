@@ -0,0 +1,125 @@
+use bytes::BytesMut;
+use senax_encoder::core::*;
+use senax_encoder::{capabilities, decode, encode, Capabilities, Encoder};
+
+#[test]
+fn test_core_tags_are_always_supported() {
+    let local = capabilities();
+    assert_eq!(local.crate_version, env!("CARGO_PKG_VERSION"));
+    for tag in [
+        TAG_ZERO,
+        TAG_U8_127,
+        TAG_NONE,
+        TAG_SOME,
+        TAG_U128,
+        TAG_F64,
+        TAG_STRING_BASE,
+        TAG_STRING_LONG,
+        TAG_BINARY,
+        TAG_STRUCT_NAMED,
+        TAG_ENUM_UNNAMED,
+        TAG_ARRAY_VEC_SET_LONG,
+        TAG_TUPLE,
+        TAG_MAP,
+        TAG_MAP_STRING_KEYS,
+        TAG_MAP_FLAT_KEYS,
+        TAG_MAP_ID_KEYS,
+        TAG_DURATION,
+        TAG_SYSTEM_TIME,
+    ] {
+        assert!(local.supports_tag(tag), "tag {}", tag);
+    }
+    assert!(!local.supports_tag(130));
+    assert!(!local.supports_tag(255));
+    assert!(local.tags.windows(2).all(|pair| pair[0] < pair[1]));
+}
+
+#[test]
+fn test_feature_tags_follow_features() {
+    let local = capabilities();
+    assert_eq!(
+        local.supports_tag(TAG_UUID),
+        cfg!(feature = "uuid") || cfg!(feature = "ulid")
+    );
+    assert_eq!(
+        local.supports_tag(TAG_CHRONO_DATETIME),
+        cfg!(feature = "chrono")
+    );
+    assert_eq!(local.has_feature("chrono"), cfg!(feature = "chrono"));
+    assert_eq!(local.has_feature("doc-store"), cfg!(feature = "doc-store"));
+    assert!(!local.has_feature("no-such-feature"));
+}
+
+#[test]
+fn test_format_limits_match_the_encoding() {
+    let mut writer = BytesMut::new();
+    "x".repeat(MAX_SHORT_STRING_LEN)
+        .encode(&mut writer)
+        .unwrap();
+    assert_eq!(
+        writer[0] as usize,
+        TAG_STRING_BASE as usize + MAX_SHORT_STRING_LEN
+    );
+    writer.clear();
+    "x".repeat(MAX_SHORT_STRING_LEN + 1)
+        .encode(&mut writer)
+        .unwrap();
+    assert_eq!(writer[0], TAG_STRING_LONG);
+
+    writer.clear();
+    vec![0u32; MAX_SHORT_ARRAY_LEN].encode(&mut writer).unwrap();
+    assert_eq!(
+        writer[0] as usize,
+        TAG_ARRAY_VEC_SET_BASE as usize + MAX_SHORT_ARRAY_LEN
+    );
+    writer.clear();
+    vec![0u32; MAX_SHORT_ARRAY_LEN + 1]
+        .encode(&mut writer)
+        .unwrap();
+    assert_eq!(writer[0], TAG_ARRAY_VEC_SET_LONG);
+
+    writer.clear();
+    write_field_id_optimized(&mut writer, MAX_SHORT_FIELD_ID).unwrap();
+    assert_eq!(writer.len(), 1);
+    writer.clear();
+    write_field_id_optimized(&mut writer, MAX_SHORT_FIELD_ID + 1).unwrap();
+    assert_eq!(writer.len(), 9);
+
+    let widest = (
+        0u8, 1u8, 2u8, 3u8, 4u8, 5u8, 6u8, 7u8, 8u8, 9u8, 10u8, 11u8, 12u8, 13u8, 14u8, 15u8,
+    );
+    assert_eq!(MAX_TUPLE_ARITY, 16);
+    assert!(encode(&widest).is_ok());
+}
+
+#[test]
+fn test_negotiation_with_an_older_peer() {
+    let local = capabilities();
+    let bytes = encode(&local).unwrap();
+    let decoded: Capabilities = decode(&mut bytes.clone()).unwrap();
+    assert_eq!(decoded, local);
+
+    let peer = Capabilities {
+        crate_version: "0.1.9".into(),
+        tags: local
+            .tags
+            .iter()
+            .copied()
+            .filter(|tag| *tag != TAG_MAP_FLAT_KEYS)
+            .collect(),
+        max_tuple_arity: 8,
+        features: vec!["serde_json".into()],
+    };
+    assert_eq!(local.tags_unsupported_by(&peer), vec![TAG_MAP_FLAT_KEYS]);
+    assert!(peer.tags_unsupported_by(&local).is_empty());
+
+    let common = local.common(&peer);
+    assert_eq!(common.crate_version, "0.1.9");
+    assert_eq!(common.max_tuple_arity, 8);
+    assert!(!common.supports_tag(TAG_MAP_FLAT_KEYS));
+    assert_eq!(
+        common.has_feature("serde_json"),
+        cfg!(feature = "serde_json")
+    );
+    assert_eq!(peer.common(&local), common);
+}
@@ -0,0 +1,175 @@
+use senax_encoder::changelog::{replay, replay_encoded, ChangeLog, Snapshot};
+use senax_encoder::core::field_id_from_name;
+use senax_encoder::patch::{diff, diff_payloads, Patch};
+use senax_encoder::{decode, encode, Decode, Encode};
+use std::collections::BTreeMap;
+
+#[derive(Encode, Decode, Debug, PartialEq, Clone, Default)]
+struct Account {
+    owner: String,
+    balance: i64,
+    #[senax(skip_default)]
+    frozen: bool,
+    tags: Vec<String>,
+    limits: BTreeMap<String, u32>,
+    note: Option<String>,
+}
+
+#[derive(Encode, Decode, Debug, PartialEq, Clone, Default)]
+struct AccountV2 {
+    owner: String,
+    balance: i64,
+    #[senax(default)]
+    currency: String,
+}
+
+fn account() -> Account {
+    Account {
+        owner: "ann".into(),
+        balance: 100,
+        frozen: false,
+        tags: vec!["vip".into()],
+        limits: BTreeMap::from([("daily".into(), 500)]),
+        note: None,
+    }
+}
+
+#[test]
+fn test_diff_records_only_changed_fields() {
+    let old = account();
+    let mut new = old.clone();
+    new.balance = 80;
+    new.note = Some("overdrawn soon".into());
+    let patch = diff(&old, &new).unwrap();
+    assert!(!patch.is_replacement());
+    let mut ids: Vec<u64> = patch.field_ids().collect();
+    ids.sort();
+    let mut expected = vec![field_id_from_name("balance"), field_id_from_name("note")];
+    expected.sort();
+    assert_eq!(ids, expected);
+    assert_eq!(patch.apply(&old).unwrap(), new);
+
+    assert!(diff(&old, &old).unwrap().is_empty());
+    assert_eq!(diff(&old, &old).unwrap().apply(&old).unwrap(), old);
+}
+
+#[test]
+fn test_removed_fields_fall_back_to_defaults() {
+    let mut old = account();
+    old.frozen = true;
+    old.note = Some("hold".into());
+    let new = account();
+    let patch = diff(&old, &new).unwrap();
+    // `frozen` is skipped when false and `note` when None, so both are removed
+    assert_eq!(patch.len(), 2);
+    assert_eq!(patch.apply(&old).unwrap(), new);
+}
+
+#[test]
+fn test_patch_applies_across_struct_versions() {
+    let old = AccountV2 {
+        owner: "ann".into(),
+        balance: 1,
+        currency: "EUR".into(),
+    };
+    let new = AccountV2 {
+        balance: 2,
+        ..old.clone()
+    };
+    let patch = diff(&old, &new).unwrap();
+    // Apply the V2 patch to a V1 payload
+    let v1 = encode(&account()).unwrap();
+    let patched: Account = decode(&mut patch.apply_payload(&v1).unwrap()).unwrap();
+    assert_eq!(patched.balance, 2);
+    assert_eq!(patched.tags, account().tags);
+}
+
+#[test]
+fn test_non_struct_values_are_replaced() {
+    let patch = diff(&vec![1u32, 2], &vec![1u32, 2, 3]).unwrap();
+    assert!(patch.is_replacement());
+    assert_eq!(patch.apply(&vec![9u32]).unwrap(), vec![1, 2, 3]);
+
+    let to_struct = diff_payloads(&encode(&5u32).unwrap(), &encode(&account()).unwrap()).unwrap();
+    assert!(to_struct.is_replacement());
+}
+
+#[test]
+fn test_field_patch_on_non_struct_is_rejected() {
+    let mut new = account();
+    new.balance = 1;
+    let patch = diff(&account(), &new).unwrap();
+    assert!(patch.apply_payload(&encode(&7u32).unwrap()).is_err());
+}
+
+#[test]
+fn test_patches_encode() {
+    let mut new = account();
+    new.tags.push("new".into());
+    let patch = diff(&account(), &new).unwrap();
+    let decoded: Patch = decode(&mut encode(&patch).unwrap()).unwrap();
+    assert_eq!(decoded, patch);
+    let replacement = diff(&1u8, &2u8).unwrap();
+    assert_eq!(
+        decode::<Patch>(&mut encode(&replacement).unwrap()).unwrap(),
+        replacement
+    );
+}
+
+#[test]
+fn test_changelog_replication() {
+    let mut state = account();
+    let mut log = ChangeLog::new(Snapshot::new(10, &state).unwrap());
+    let early_replica = log.head();
+
+    let mut wire = Vec::new();
+    for i in 0..5 {
+        state.balance += i;
+        if i == 3 {
+            state.tags.clear();
+        }
+        wire.push(encode(log.record(&state).unwrap()).unwrap());
+    }
+    assert_eq!(log.version(), 15);
+    assert_eq!(log.head().value().unwrap(), state);
+
+    let caught_up = replay(&early_replica, log.since(10).unwrap()).unwrap();
+    assert_eq!(caught_up, log.head());
+    let from_wire = replay_encoded(&early_replica, wire).unwrap();
+    assert_eq!(from_wire.value().unwrap(), state);
+
+    let middle = replay(&early_replica, &log.patches()[..2]).unwrap();
+    assert_eq!(middle.version(), 12);
+    assert_eq!(log.since(12).unwrap().len(), 3);
+    assert_eq!(
+        replay(&middle, log.since(12).unwrap())
+            .unwrap()
+            .value()
+            .unwrap(),
+        state
+    );
+    assert!(log.since(15).unwrap().is_empty());
+    assert!(log.since(9).is_none());
+    assert!(log.since(16).is_none());
+}
+
+#[test]
+fn test_changelog_compact_and_encode() {
+    let mut state = account();
+    let mut log = ChangeLog::new(Snapshot::new(0, &state).unwrap());
+    state.owner = "bob".into();
+    log.record(&state).unwrap();
+
+    let decoded: ChangeLog<Account> = decode(&mut encode(&log).unwrap()).unwrap();
+    assert_eq!(decoded.head(), log.head());
+    assert_eq!(decoded.patches(), log.patches());
+
+    log.compact();
+    assert!(log.patches().is_empty());
+    assert_eq!(log.base().version(), 1);
+    assert_eq!(log.base().value().unwrap(), state);
+    assert!(log.since(0).is_none());
+
+    let snapshot: Snapshot<Account> = decode(&mut encode(log.base()).unwrap()).unwrap();
+    assert_eq!(&snapshot, log.base());
+}
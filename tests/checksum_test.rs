@@ -0,0 +1,86 @@
+use bytes::Bytes;
+use senax_encoder::{
+    decode_checked, encode, encode_checked, Checksum, Decode, Encode, EncoderError, ErrorCode,
+};
+
+#[derive(Encode, Decode, Debug, PartialEq)]
+struct Telemetry {
+    device: String,
+    readings: Vec<i32>,
+}
+
+fn telemetry() -> Telemetry {
+    Telemetry {
+        device: "sensor-7".to_string(),
+        readings: vec![12, -4, 1000],
+    }
+}
+
+#[test]
+fn test_roundtrip() {
+    for checksum in [Checksum::Crc32, Checksum::Crc64] {
+        let envelope = encode_checked(&telemetry(), checksum).unwrap();
+        let payload = encode(&telemetry()).unwrap();
+        assert_eq!(envelope.len(), payload.len() + checksum.size());
+        assert_eq!(&envelope[..payload.len()], &payload[..]);
+
+        let mut reader = envelope.clone();
+        assert_eq!(
+            decode_checked::<Telemetry>(&mut reader, checksum).unwrap(),
+            telemetry()
+        );
+        assert!(reader.is_empty());
+    }
+}
+
+#[test]
+fn test_every_bit_flip_is_detected() {
+    for checksum in [Checksum::Crc32, Checksum::Crc64] {
+        let envelope = encode_checked(&telemetry(), checksum).unwrap();
+        for i in 0..envelope.len() * 8 {
+            let mut corrupted = envelope.to_vec();
+            corrupted[i / 8] ^= 1 << (i % 8);
+            let err =
+                decode_checked::<Telemetry>(&mut Bytes::from(corrupted), checksum).unwrap_err();
+            assert!(
+                matches!(err, EncoderError::ChecksumMismatch { .. }),
+                "bit {}: {:?}",
+                i,
+                err
+            );
+            assert_eq!(err.code(), ErrorCode::ChecksumMismatch);
+        }
+    }
+}
+
+#[test]
+fn test_truncation_and_wrong_algorithm() {
+    let envelope = encode_checked(&telemetry(), Checksum::Crc64).unwrap();
+    assert!(decode_checked::<Telemetry>(&mut envelope.clone(), Checksum::Crc32).is_err());
+    assert!(decode_checked::<Telemetry>(
+        &mut envelope.slice(..envelope.len() - 1),
+        Checksum::Crc64
+    )
+    .is_err());
+    assert!(matches!(
+        decode_checked::<Telemetry>(&mut Bytes::from_static(&[1, 2, 3]), Checksum::Crc32),
+        Err(EncoderError::UnexpectedEof { .. })
+    ));
+}
+
+#[test]
+fn test_mismatch_reports_both_checksums() {
+    let mut envelope = encode_checked(&telemetry(), Checksum::Crc32)
+        .unwrap()
+        .to_vec();
+    let len = envelope.len();
+    envelope[len - 4..].copy_from_slice(&0xDEAD_BEEFu32.to_le_bytes());
+    let payload = encode(&telemetry()).unwrap();
+    match decode_checked::<Telemetry>(&mut Bytes::from(envelope), Checksum::Crc32) {
+        Err(EncoderError::ChecksumMismatch { expected, actual }) => {
+            assert_eq!(expected, 0xDEAD_BEEF);
+            assert_eq!(actual, Checksum::Crc32.compute(&payload));
+        }
+        other => panic!("unexpected {:?}", other),
+    }
+}
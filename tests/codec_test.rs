@@ -0,0 +1,154 @@
+#![cfg(feature = "tokio")]
+
+use bytes::{BufMut, BytesMut};
+use futures::{SinkExt, StreamExt};
+use senax_encoder::codec::{decode_async, encode_async, SenaxCodec, DEFAULT_MAX_FRAME_LEN};
+use senax_encoder::record_log::RecordLogReader;
+use senax_encoder::{Decode, Encode, EncoderError};
+use tokio::io::AsyncWriteExt;
+use tokio::net::{TcpListener, TcpStream};
+use tokio_util::codec::{Decoder, Encoder, Framed, FramedRead};
+
+#[derive(Encode, Decode, Debug, PartialEq, Clone)]
+struct Trade {
+    symbol: String,
+    price: i64,
+    quantity: u32,
+}
+
+fn trade(i: u32) -> Trade {
+    Trade {
+        symbol: format!("SYM{}", i),
+        price: i as i64 * 100 - 50,
+        quantity: i,
+    }
+}
+
+#[tokio::test]
+async fn test_framed_roundtrip_over_tcp() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let server = tokio::spawn(async move {
+        let (stream, _) = listener.accept().await.unwrap();
+        let mut framed = Framed::new(stream, SenaxCodec::<Trade>::new());
+        // Echo every trade back with a doubled quantity
+        while let Some(trade) = framed.next().await {
+            let mut trade = trade.unwrap();
+            trade.quantity *= 2;
+            framed.send(trade).await.unwrap();
+        }
+    });
+
+    let stream = TcpStream::connect(addr).await.unwrap();
+    let mut framed = Framed::new(stream, SenaxCodec::<Trade>::new());
+    for i in 0..50 {
+        framed.send(trade(i)).await.unwrap();
+        let echoed = framed.next().await.unwrap().unwrap();
+        assert_eq!(echoed.quantity, i * 2);
+        assert_eq!(echoed.symbol, trade(i).symbol);
+    }
+    drop(framed);
+    server.await.unwrap();
+}
+
+#[tokio::test]
+async fn test_async_helpers() {
+    let (mut client, mut server) = tokio::io::duplex(64);
+    let writer = tokio::spawn(async move {
+        for i in 0..20 {
+            encode_async(&mut client, &trade(i)).await.unwrap();
+        }
+    });
+    for i in 0..20 {
+        let decoded: Option<Trade> = decode_async(&mut server).await.unwrap();
+        assert_eq!(decoded, Some(trade(i)));
+    }
+    writer.await.unwrap();
+    assert_eq!(decode_async::<_, Trade>(&mut server).await.unwrap(), None);
+}
+
+#[tokio::test]
+async fn test_format_matches_record_log() {
+    let mut buf = Vec::new();
+    encode_async(&mut buf, &trade(1)).await.unwrap();
+    let mut encoded = BytesMut::new();
+    SenaxCodec::new().encode(trade(2), &mut encoded).unwrap();
+    buf.extend_from_slice(&encoded);
+
+    let mut reader = RecordLogReader::new(buf.as_slice());
+    assert_eq!(reader.read_value::<Trade>().unwrap(), Some(trade(1)));
+    assert_eq!(reader.read_value::<Trade>().unwrap(), Some(trade(2)));
+    assert_eq!(reader.read_value::<Trade>().unwrap(), None);
+}
+
+#[test]
+fn test_codec_waits_for_complete_frames() {
+    let mut encoded = BytesMut::new();
+    let mut codec = SenaxCodec::<Trade>::new();
+    codec.encode(trade(3), &mut encoded).unwrap();
+    codec.encode(trade(4), &mut encoded).unwrap();
+
+    let mut src = BytesMut::new();
+    let mut decoded = Vec::new();
+    for byte in encoded {
+        src.put_u8(byte);
+        if let Some(trade) = codec.decode(&mut src).unwrap() {
+            decoded.push(trade);
+        }
+    }
+    assert_eq!(decoded, vec![trade(3), trade(4)]);
+    assert!(src.is_empty());
+}
+
+#[tokio::test]
+async fn test_truncated_frames() {
+    let mut buf = Vec::new();
+    encode_async(&mut buf, &trade(5)).await.unwrap();
+
+    for len in 1..buf.len() {
+        let mut reader = &buf[..len];
+        assert!(matches!(
+            decode_async::<_, Trade>(&mut reader).await,
+            Err(EncoderError::UnexpectedEof { .. })
+        ));
+
+        let mut frames = FramedRead::new(&buf[..len], SenaxCodec::<Trade>::new());
+        assert!(matches!(
+            frames.next().await,
+            Some(Err(EncoderError::UnexpectedEof { .. }))
+        ));
+        assert!(frames.next().await.is_none());
+    }
+}
+
+#[tokio::test]
+async fn test_frame_limits() {
+    let mut codec = SenaxCodec::<Vec<u8>>::new().with_max_frame_len(16);
+    assert_eq!(codec.max_frame_len(), 16);
+    let mut dst = BytesMut::new();
+    assert!(codec.encode(vec![0u8; 8], &mut dst).is_ok());
+    let len = dst.len();
+    assert!(matches!(
+        codec.encode(vec![0u8; 64], &mut dst),
+        Err(EncoderError::Encode(_))
+    ));
+    assert_eq!(dst.len(), len);
+
+    let mut src = BytesMut::new();
+    src.put_u32_le(17);
+    assert!(matches!(
+        codec.decode(&mut src),
+        Err(EncoderError::Decode(_))
+    ));
+
+    // The async reader rejects oversized frames without allocating their payload
+    let mut header = Vec::new();
+    header
+        .write_all(&((DEFAULT_MAX_FRAME_LEN + 1) as u32).to_le_bytes())
+        .await
+        .unwrap();
+    assert!(matches!(
+        decode_async::<_, Vec<u8>>(&mut header.as_slice()).await,
+        Err(EncoderError::Decode(_))
+    ));
+}
@@ -0,0 +1,153 @@
+#![cfg(any(feature = "zstd", feature = "lz4"))]
+
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+use senax_encoder::{
+    decode_compressed, encode, encode_compressed, with_decode_config, Compression, Decode,
+    DecodeConfig, DecodeLimit, Decoder, Encode, Encoder, EncoderError, COMPRESSED_MAGIC,
+};
+
+#[derive(Encode, Decode, Debug, PartialEq, Clone)]
+struct Report {
+    title: String,
+    rows: Vec<(u32, String)>,
+}
+
+fn report() -> Report {
+    Report {
+        title: "daily".to_string(),
+        rows: (0..200).map(|i| (i, format!("row {}", i % 7))).collect(),
+    }
+}
+
+fn algorithms() -> Vec<Compression> {
+    vec![
+        Compression::None,
+        #[cfg(feature = "zstd")]
+        Compression::Zstd(0),
+        #[cfg(feature = "zstd")]
+        Compression::Zstd(19),
+        #[cfg(feature = "lz4")]
+        Compression::Lz4,
+    ]
+}
+
+/// A payload with the given header fields and body.
+fn payload(algorithm: u8, len: usize, body: &[u8]) -> Bytes {
+    let mut buf = BytesMut::new();
+    buf.put_u16_le(COMPRESSED_MAGIC);
+    buf.put_u8(algorithm);
+    len.encode(&mut buf).unwrap();
+    buf.put_slice(body);
+    buf.freeze()
+}
+
+/// Splits a compressed payload into its algorithm byte and compressed body.
+fn split(mut compressed: Bytes) -> (u8, Bytes) {
+    let algorithm = compressed[2];
+    compressed.advance(3);
+    usize::decode(&mut compressed).unwrap();
+    (algorithm, compressed)
+}
+
+#[test]
+fn test_round_trip() {
+    let plain = encode(&report()).unwrap();
+    for compression in algorithms() {
+        let compressed = encode_compressed(&report(), compression).unwrap();
+        assert_eq!(&compressed[..2], &COMPRESSED_MAGIC.to_le_bytes());
+        if compression != Compression::None {
+            assert!(compressed.len() < plain.len(), "{compression:?}");
+        }
+        let decoded: Report = decode_compressed(&mut compressed.clone()).unwrap();
+        assert_eq!(decoded, report(), "{compression:?}");
+    }
+}
+
+#[test]
+fn test_none_keeps_payload() {
+    let compressed = encode_compressed(&7u32, Compression::None).unwrap();
+    let plain = encode(&7u32).unwrap();
+    assert_eq!(compressed, payload(0, plain.len(), &plain));
+}
+
+#[test]
+fn test_empty_and_small_values() {
+    for compression in algorithms() {
+        let compressed = encode_compressed(&String::new(), compression).unwrap();
+        assert_eq!(
+            decode_compressed::<String>(&mut compressed.clone()).unwrap(),
+            ""
+        );
+        let compressed = encode_compressed(&1u8, compression).unwrap();
+        assert_eq!(decode_compressed::<u8>(&mut compressed.clone()).unwrap(), 1);
+    }
+}
+
+#[test]
+fn test_rejects_bad_header() {
+    let mut compressed = encode_compressed(&report(), Compression::None)
+        .unwrap()
+        .to_vec();
+    compressed[0] ^= 0xFF;
+    let err = decode_compressed::<Report>(&mut compressed.into()).unwrap_err();
+    assert!(matches!(err, EncoderError::Decode(_)));
+
+    let err = decode_compressed::<u8>(&mut Bytes::from_static(&[0x5C])).unwrap_err();
+    assert!(matches!(err, EncoderError::UnexpectedEof { .. }));
+
+    let err = decode_compressed::<u8>(&mut payload(9, 1, &[0])).unwrap_err();
+    assert!(matches!(err, EncoderError::Decode(msg) if msg.contains("Unknown")));
+}
+
+#[test]
+fn test_length_mismatch() {
+    let plain = encode(&report()).unwrap();
+    for compression in algorithms() {
+        let (algorithm, body) = split(encode_compressed(&report(), compression).unwrap());
+        for len in [plain.len() - 1, plain.len() + 1] {
+            let err = decode_compressed::<Report>(&mut payload(algorithm, len, &body)).unwrap_err();
+            assert!(matches!(err, EncoderError::Decode(_)), "{compression:?}");
+        }
+    }
+}
+
+#[test]
+fn test_announced_length_limited() {
+    let config = DecodeConfig {
+        max_alloc_bytes: Some(64),
+        ..Default::default()
+    };
+    let compressed = encode_compressed(&report(), Compression::None).unwrap();
+    let err = with_decode_config(&config, || {
+        decode_compressed::<Report>(&mut compressed.clone())
+    })
+    .unwrap_err();
+    assert!(matches!(
+        err,
+        EncoderError::LimitExceeded {
+            kind: DecodeLimit::AllocatedBytes,
+            limit: 64,
+            ..
+        }
+    ));
+}
+
+#[cfg(feature = "zstd")]
+#[test]
+fn test_decompression_stops_at_announced_length() {
+    // 64 MiB of zeros compress to a few kilobytes; a header claiming 16 bytes must not
+    // inflate all of them
+    let zeros = Bytes::from(vec![0u8; 64 << 20]);
+    let (algorithm, body) = split(encode_compressed(&zeros, Compression::Zstd(3)).unwrap());
+    assert!(body.len() < 64 << 10);
+    let err = decode_compressed::<Bytes>(&mut payload(algorithm, 16, &body)).unwrap_err();
+    assert!(matches!(err, EncoderError::Decode(_)));
+}
+
+#[cfg(not(all(feature = "zstd", feature = "lz4")))]
+#[test]
+fn test_disabled_algorithm() {
+    let missing = if cfg!(feature = "zstd") { 2 } else { 1 };
+    let err = decode_compressed::<u8>(&mut payload(missing, 1, &[0])).unwrap_err();
+    assert!(matches!(err, EncoderError::Decode(msg) if msg.contains("feature")));
+}
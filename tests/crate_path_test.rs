@@ -0,0 +1,54 @@
+// Derived code must only reach senax-encoder through the `crate` attribute path.
+
+mod facade {
+    pub use senax_encoder as senax;
+}
+
+mod shadowed {
+    // Shadows the extern crate name, so the default `senax_encoder::...` paths would not resolve.
+    #[allow(dead_code)]
+    mod senax_encoder {}
+
+    use ::senax_encoder::{Decode, Encode, Pack, Unpack};
+
+    #[derive(Encode, Decode, Pack, Unpack, Debug, PartialEq)]
+    #[senax(crate = "crate::facade::senax")]
+    pub struct Record {
+        pub id: u32,
+        pub name: Option<String>,
+    }
+
+    #[derive(Encode, Decode, Pack, Unpack, Debug, PartialEq)]
+    #[senax(crate = "crate::facade::senax")]
+    pub enum Message {
+        Ping,
+        Data(Vec<u8>),
+        Named { record: Record },
+    }
+}
+
+use shadowed::{Message, Record};
+
+#[test]
+fn test_derive_with_crate_path() {
+    let value = Message::Named {
+        record: Record {
+            id: 1,
+            name: Some("facade".to_string()),
+        },
+    };
+    let mut bytes = senax_encoder::encode(&value).unwrap();
+    let decoded: Message = senax_encoder::decode(&mut bytes).unwrap();
+    assert_eq!(value, decoded);
+
+    let mut packed = senax_encoder::pack(&Message::Data(vec![1, 2])).unwrap();
+    let unpacked: Message = senax_encoder::unpack(&mut packed).unwrap();
+    assert_eq!(unpacked, Message::Data(vec![1, 2]));
+}
+
+#[test]
+fn test_bytes_reexport() {
+    let mut writer = senax_encoder::bytes::BytesMut::new();
+    senax_encoder::Encoder::encode(&Message::Ping, &mut writer).unwrap();
+    assert!(!writer.is_empty());
+}
@@ -0,0 +1,174 @@
+use senax_encoder::core::field_id_from_name;
+use senax_encoder::debug::{diff, DifferenceKind};
+use senax_encoder::self_describing::encode_self_describing;
+use senax_encoder::{encode, Encode, Value};
+use std::collections::BTreeMap;
+
+#[derive(Encode, Clone)]
+enum Status {
+    Active,
+    Suspended { reason: String, days: u32 },
+}
+
+#[derive(Encode, Clone)]
+struct Customer {
+    id: u64,
+    name: String,
+    tags: Vec<String>,
+    limits: BTreeMap<String, u32>,
+    status: Status,
+    location: Option<(i32, i32)>,
+}
+
+#[derive(Encode)]
+struct CustomerV2 {
+    id: String,
+    name: String,
+    tags: Vec<String>,
+}
+
+fn customer() -> Customer {
+    Customer {
+        id: 1,
+        name: "alice".to_string(),
+        tags: vec!["vip".to_string(), "eu".to_string()],
+        limits: [("daily".to_string(), 100), ("monthly".to_string(), 1000)].into(),
+        status: Status::Suspended {
+            reason: "fraud check".to_string(),
+            days: 3,
+        },
+        location: Some((10, 20)),
+    }
+}
+
+#[test]
+fn test_equal_payloads() {
+    let bytes = encode(&customer()).unwrap();
+    assert!(diff(&bytes, &bytes).unwrap().is_empty());
+
+    // The same value with and without recorded names
+    let named = encode_self_describing(&customer()).unwrap();
+    let changes = diff(&bytes, &named).unwrap();
+    assert!(changes.is_empty(), "{}", changes);
+    assert_eq!(changes.to_string(), "");
+}
+
+#[test]
+fn test_nested_changes() {
+    let a = customer();
+    let mut b = customer();
+    b.tags.push("new".to_string());
+    b.limits.remove("monthly");
+    b.limits.insert("daily".to_string(), 50);
+    b.status = Status::Suspended {
+        reason: "fraud check".to_string(),
+        days: 4,
+    };
+    b.location = Some((10, 21));
+
+    let changes = diff(
+        &encode_self_describing(&a).unwrap(),
+        &encode_self_describing(&b).unwrap(),
+    )
+    .unwrap();
+    let paths: Vec<_> = changes.iter().map(|d| d.path.as_str()).collect();
+    assert_eq!(
+        paths,
+        [
+            "tags[2]",
+            "limits[\"daily\"]",
+            "limits[\"monthly\"]",
+            "status.days",
+            "location[1]",
+        ]
+    );
+    assert_eq!(
+        changes.get("tags[2]"),
+        Some(&DifferenceKind::Added(Value::String("new".to_string())))
+    );
+    assert_eq!(
+        changes.get("limits[\"monthly\"]"),
+        Some(&DifferenceKind::Removed(Value::UInt(1000)))
+    );
+    assert_eq!(
+        changes.get("location[1]"),
+        Some(&DifferenceKind::Changed {
+            a: Value::UInt(20),
+            b: Value::UInt(21),
+        })
+    );
+    assert_eq!(
+        changes.to_string().lines().next(),
+        Some("+ tags[2]: String(\"new\")")
+    );
+}
+
+#[test]
+fn test_variant_and_option_changes() {
+    let a = customer();
+    let mut b = customer();
+    b.status = Status::Active;
+    b.location = None;
+
+    let changes = diff(&encode(&a).unwrap(), &encode(&b).unwrap()).unwrap();
+    assert_eq!(changes.len(), 2);
+    // Without recorded names, paths use field IDs
+    let status = field_id_from_name("status").to_string();
+    assert!(matches!(
+        changes.get(&status),
+        Some(DifferenceKind::Changed {
+            a: Value::Enum(_),
+            b: Value::Enum(_),
+        })
+    ));
+    let location = field_id_from_name("location").to_string();
+    assert!(matches!(
+        changes.get(&location),
+        Some(DifferenceKind::Removed(Value::Tuple(_)))
+    ));
+}
+
+#[test]
+fn test_type_changes() {
+    let a = customer();
+    let b = CustomerV2 {
+        id: "1".to_string(),
+        name: "alice".to_string(),
+        tags: a.tags.clone(),
+    };
+    let changes = diff(
+        &encode_self_describing(&a).unwrap(),
+        &encode_self_describing(&b).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        changes.get("id"),
+        Some(&DifferenceKind::TypeChanged {
+            a: Value::UInt(1),
+            b: Value::String("1".to_string()),
+        })
+    );
+    assert!(matches!(
+        changes.get("limits"),
+        Some(DifferenceKind::Removed(_))
+    ));
+    let text = changes.to_string();
+    assert!(
+        text.contains("! id: integer UInt(1) -> string String(\"1\")"),
+        "{}",
+        text
+    );
+
+    // Whole-value changes are reported at the root
+    let changes = diff(&encode(&1u32).unwrap(), &encode(&"1").unwrap()).unwrap();
+    assert_eq!(changes.iter().next().unwrap().path, "");
+    assert!(changes.to_string().starts_with("! (root):"));
+}
+
+#[test]
+fn test_invalid_payloads() {
+    let bytes = encode(&customer()).unwrap();
+    assert!(diff(&bytes, &[0x00, 0x01, 0x02]).is_err());
+    assert!(diff(&bytes[..bytes.len() - 1], &bytes).is_err());
+    assert!(diff(&[], &bytes).is_err());
+}
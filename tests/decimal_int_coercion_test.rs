@@ -0,0 +1,105 @@
+#![cfg(feature = "rust_decimal")]
+
+use rust_decimal::Decimal;
+use senax_encoder::{decode, encode, pack, unpack, Decode, Encode};
+use std::str::FromStr;
+
+#[derive(Encode, Decode, Debug, PartialEq)]
+struct PriceV1 {
+    amount: u64,
+}
+
+#[derive(Encode, Decode, Debug, PartialEq)]
+struct PriceV2 {
+    amount: Decimal,
+}
+
+#[test]
+fn test_integer_field_decodes_as_decimal() {
+    let v1 = PriceV1 { amount: 1250 };
+    let v2: PriceV2 = decode(&mut encode(&v1).unwrap()).unwrap();
+    assert_eq!(v2.amount, Decimal::from(1250));
+}
+
+#[test]
+fn test_scale_zero_decimal_field_decodes_as_integer() {
+    let v2 = PriceV2 {
+        amount: Decimal::from(1250),
+    };
+    let v1: PriceV1 = decode(&mut encode(&v2).unwrap()).unwrap();
+    assert_eq!(v1, PriceV1 { amount: 1250 });
+}
+
+#[test]
+fn test_decimal_decodes_as_every_integer_type() {
+    let mut bytes = encode(&Decimal::from(-42)).unwrap();
+    assert_eq!(decode::<i8>(&mut bytes).unwrap(), -42);
+    let mut bytes = encode(&Decimal::from(-42)).unwrap();
+    assert_eq!(decode::<i32>(&mut bytes).unwrap(), -42);
+    let mut bytes = encode(&Decimal::from(-42)).unwrap();
+    assert_eq!(decode::<i128>(&mut bytes).unwrap(), -42);
+    let mut bytes = encode(&Decimal::from(200)).unwrap();
+    assert_eq!(decode::<u8>(&mut bytes).unwrap(), 200);
+    let mut bytes = encode(&Decimal::MAX).unwrap();
+    assert_eq!(
+        decode::<u128>(&mut bytes).unwrap(),
+        79_228_162_514_264_337_593_543_950_335
+    );
+    let mut bytes = encode(&Decimal::ZERO).unwrap();
+    assert_eq!(decode::<u16>(&mut bytes).unwrap(), 0);
+}
+
+#[test]
+fn test_packed_decimal_unpacks_as_integer() {
+    let mut bytes = pack(&Decimal::from(7)).unwrap();
+    assert_eq!(unpack::<u64>(&mut bytes).unwrap(), 7);
+    let mut bytes = pack(&7u64).unwrap();
+    assert_eq!(unpack::<Decimal>(&mut bytes).unwrap(), Decimal::from(7));
+}
+
+#[test]
+fn test_decimal_out_of_range_for_integer_fails() {
+    let mut bytes = encode(&Decimal::from(256)).unwrap();
+    let err = decode::<u8>(&mut bytes).unwrap_err();
+    assert!(err.to_string().contains("out of range for u8"), "{err}");
+
+    let mut bytes = encode(&Decimal::from(-1)).unwrap();
+    let err = decode::<u32>(&mut bytes).unwrap_err();
+    assert!(err.to_string().contains("out of range for u32"), "{err}");
+
+    let mut bytes = encode(&(Decimal::from(i64::MAX) + Decimal::ONE)).unwrap();
+    assert!(decode::<i64>(&mut bytes).is_err());
+}
+
+#[test]
+fn test_fractional_decimal_does_not_decode_as_integer() {
+    let mut bytes = encode(&Decimal::from_str("12.50").unwrap()).unwrap();
+    assert!(decode::<u64>(&mut bytes).is_err());
+
+    // A zero fraction still carries a scale, so it is rejected too.
+    let mut bytes = encode(&Decimal::from_str("12.00").unwrap()).unwrap();
+    assert!(decode::<u64>(&mut bytes).is_err());
+}
+
+#[test]
+fn test_float_does_not_decode_as_integer() {
+    let mut bytes = encode(&12.0f64).unwrap();
+    assert!(decode::<u64>(&mut bytes).is_err());
+    let mut bytes = encode(&-12.0f64).unwrap();
+    assert!(decode::<i64>(&mut bytes).is_err());
+}
+
+#[test]
+fn test_integer_beyond_decimal_range_fails() {
+    let mut bytes = encode(&i128::MAX).unwrap();
+    let err = decode::<Decimal>(&mut bytes).unwrap_err();
+    assert!(
+        err.to_string().contains("out of range for Decimal"),
+        "{err}"
+    );
+
+    let mut bytes = encode(&i128::MIN).unwrap();
+    assert!(decode::<Decimal>(&mut bytes).is_err());
+    let mut bytes = encode(&u128::MAX).unwrap();
+    assert!(decode::<Decimal>(&mut bytes).is_err());
+}
@@ -0,0 +1,43 @@
+use bytes::{Buf, BytesMut};
+use senax_encoder::{Decode, Decoder, Encode, Encoder};
+
+#[derive(Encode, Decode, Debug, PartialEq)]
+struct Entry {
+    key: String,
+    value: Vec<u64>,
+}
+
+#[test]
+fn test_counts_match_encoded_lengths() {
+    let entries = vec![
+        Entry {
+            key: "a".into(),
+            value: vec![1, 2, 3],
+        },
+        Entry {
+            key: "long key".repeat(10),
+            value: vec![u64::MAX],
+        },
+    ];
+    let mut writer = BytesMut::new();
+    let mut lengths = Vec::new();
+    for entry in &entries {
+        let start = writer.len();
+        entry.encode(&mut writer).unwrap();
+        lengths.push(writer.len() - start);
+    }
+    let mut reader = writer.freeze();
+    for (entry, length) in entries.iter().zip(lengths) {
+        let (decoded, used) = Entry::decode_counted(&mut reader).unwrap();
+        assert_eq!(&decoded, entry);
+        assert_eq!(used, length);
+    }
+    assert_eq!(reader.remaining(), 0);
+}
+
+#[test]
+fn test_errors_are_passed_through() {
+    let mut writer = BytesMut::new();
+    "text".to_string().encode(&mut writer).unwrap();
+    assert!(u32::decode_counted(&mut writer.freeze()).is_err());
+}
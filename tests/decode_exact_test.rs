@@ -0,0 +1,59 @@
+use bytes::{BufMut, Bytes, BytesMut};
+use senax_encoder::{decode, decode_exact, encode, Decode, Encode, EncoderError, ErrorCode};
+
+#[derive(Encode, Decode, Debug, PartialEq)]
+struct Message {
+    id: u32,
+    body: String,
+}
+
+fn message() -> Message {
+    Message {
+        id: 7,
+        body: "hello".to_string(),
+    }
+}
+
+fn with_tail(bytes: &Bytes, tail: &[u8]) -> Bytes {
+    let mut buf = BytesMut::from(&bytes[..]);
+    buf.put_slice(tail);
+    buf.freeze()
+}
+
+#[test]
+fn test_exact_frame() {
+    let mut bytes = encode(&message()).unwrap();
+    assert_eq!(decode_exact::<Message>(&mut bytes).unwrap(), message());
+    assert!(bytes.is_empty());
+}
+
+#[test]
+fn test_trailing_bytes_rejected() {
+    let bytes = encode(&message()).unwrap();
+    for tail in [&[0u8][..], &[0xff, 0xff, 0xff], &bytes[..]] {
+        let mut framed = with_tail(&bytes, tail);
+        // Plain decode ignores the tail
+        assert_eq!(decode::<Message>(&mut framed.clone()).unwrap(), message());
+
+        let err = decode_exact::<Message>(&mut framed).unwrap_err();
+        assert!(matches!(err, EncoderError::TrailingData { remaining } if remaining == tail.len()));
+        assert_eq!(err.code(), ErrorCode::TrailingData);
+        assert_eq!(framed.len(), tail.len());
+    }
+}
+
+#[test]
+fn test_decode_errors_take_precedence() {
+    let bytes = encode(&message()).unwrap();
+    let mut truncated = bytes.slice(..bytes.len() - 1);
+    assert!(matches!(
+        decode_exact::<Message>(&mut truncated),
+        Err(EncoderError::UnexpectedEof { .. })
+    ));
+
+    let mut wrong_magic = with_tail(&Bytes::from_static(&[0, 0]), &bytes[2..]);
+    assert!(matches!(
+        decode_exact::<Message>(&mut wrong_magic),
+        Err(EncoderError::Decode(_))
+    ));
+}
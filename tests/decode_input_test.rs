@@ -0,0 +1,137 @@
+use bytes::Bytes;
+use senax_encoder::value::Value;
+use senax_encoder::{
+    decode, decode_from_arc_bytes, decode_from_vec, decode_with_config, encode, unpack,
+    with_decode_config, Decode, DecodeConfig, Encode, Pack, Unpack,
+};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+#[derive(Encode, Decode, Pack, Unpack, Debug, PartialEq, Clone)]
+struct Record {
+    id: u32,
+    name: String,
+    tags: Vec<String>,
+    attributes: BTreeMap<String, i64>,
+    payload: Bytes,
+    raw: Vec<u8>,
+    parent: Option<Box<Record>>,
+}
+
+fn record() -> Record {
+    Record {
+        id: 7,
+        name: "root".to_string(),
+        tags: vec!["a".to_string(), "b".to_string()],
+        attributes: [("x".to_string(), -1), ("y".to_string(), 2)].into(),
+        payload: Bytes::from(vec![1u8; 300]),
+        raw: vec![9; 10],
+        parent: Some(Box::new(Record {
+            id: 1,
+            name: "parent".to_string(),
+            tags: vec![],
+            attributes: BTreeMap::new(),
+            payload: Bytes::from_static(b"p"),
+            raw: vec![],
+            parent: None,
+        })),
+    }
+}
+
+fn copy_out() -> DecodeConfig {
+    DecodeConfig {
+        copy_out: true,
+        ..Default::default()
+    }
+}
+
+fn points_into(slice: &[u8], input: &[u8]) -> bool {
+    input.as_ptr_range().contains(&slice.as_ptr())
+}
+
+#[test]
+fn test_inputs_decode_the_same() {
+    let value = record();
+    let encoded = encode(&value).unwrap();
+
+    let from_bytes: Record = decode(&mut encoded.clone()).unwrap();
+    let from_vec: Record = decode_from_vec(encoded.to_vec()).unwrap();
+    let from_arc: Record = decode_from_arc_bytes(Arc::from(encoded.as_ref())).unwrap();
+    let copied: Record = decode_with_config(&mut encoded.clone(), &copy_out()).unwrap();
+
+    assert_eq!(from_bytes, value);
+    assert_eq!(from_vec, value);
+    assert_eq!(from_arc, value);
+    assert_eq!(copied, value);
+}
+
+#[test]
+fn test_errors_are_the_same() {
+    let encoded = encode(&record()).unwrap();
+    let truncated = encoded.slice(..encoded.len() - 5);
+
+    let from_bytes = decode::<Record>(&mut truncated.clone()).unwrap_err();
+    let from_vec = decode_from_vec::<Record>(truncated.to_vec()).unwrap_err();
+    let from_arc = decode_from_arc_bytes::<Record>(Arc::from(truncated.as_ref())).unwrap_err();
+    assert_eq!(from_bytes.to_string(), from_vec.to_string());
+    assert_eq!(from_bytes.to_string(), from_arc.to_string());
+
+    assert!(decode_from_vec::<u32>(vec![]).is_err());
+    assert!(decode_from_vec::<u32>(vec![0, 0, 1]).is_err());
+}
+
+#[test]
+fn test_bytes_share_the_input_by_default() {
+    let encoded = encode(&record()).unwrap();
+    let decoded: Record = decode(&mut encoded.clone()).unwrap();
+    assert!(points_into(&decoded.payload, &encoded));
+    // Other types own their data
+    assert!(!points_into(decoded.name.as_bytes(), &encoded));
+    assert!(!points_into(&decoded.raw, &encoded));
+
+    let arc: Arc<[u8]> = Arc::from(encoded.as_ref());
+    let decoded: Record = decode_from_arc_bytes(arc.clone()).unwrap();
+    assert!(points_into(&decoded.payload, &arc));
+    assert_eq!(Arc::strong_count(&arc), 2);
+    drop(decoded);
+    assert_eq!(Arc::strong_count(&arc), 1);
+}
+
+#[test]
+fn test_copy_out_releases_the_input() {
+    let encoded = encode(&record()).unwrap();
+    let decoded: Record = decode_with_config(&mut encoded.clone(), &copy_out()).unwrap();
+    assert!(!points_into(&decoded.payload, &encoded));
+    assert!(!points_into(
+        &decoded.parent.as_ref().unwrap().payload,
+        &encoded
+    ));
+
+    let arc: Arc<[u8]> = Arc::from(encoded.as_ref());
+    let decoded: Record =
+        with_decode_config(&copy_out(), || decode_from_arc_bytes(arc.clone())).unwrap();
+    assert_eq!(Arc::strong_count(&arc), 1);
+    assert_eq!(decoded, record());
+}
+
+#[test]
+fn test_copy_out_applies_to_unpack_and_values() {
+    let packed = senax_encoder::pack(&record()).unwrap();
+    let decoded: Record = with_decode_config(&copy_out(), || unpack(&mut packed.clone())).unwrap();
+    assert!(!points_into(&decoded.payload, &packed));
+    let decoded: Record = unpack(&mut packed.clone()).unwrap();
+    assert!(points_into(&decoded.payload, &packed));
+
+    let encoded = encode(&Bytes::from(vec![5u8; 64])).unwrap();
+    let Value::Binary(shared) = decode::<Value>(&mut encoded.clone()).unwrap() else {
+        panic!("expected binary");
+    };
+    assert!(points_into(&shared, &encoded));
+    let Value::Binary(copied) =
+        decode_with_config::<Value>(&mut encoded.clone(), &copy_out()).unwrap()
+    else {
+        panic!("expected binary");
+    };
+    assert!(!points_into(&copied, &encoded));
+    assert_eq!(shared, copied);
+}
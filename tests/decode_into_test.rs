@@ -0,0 +1,79 @@
+use bytes::{Bytes, BytesMut};
+use senax_encoder::core::{decode_btree_map_into, decode_hash_map_into, decode_vec_into};
+use senax_encoder::{Decode, Encode, Encoder};
+use std::collections::{BTreeMap, HashMap};
+
+#[derive(Encode, Decode, Debug, PartialEq, Clone)]
+struct Point {
+    x: i32,
+    y: i32,
+}
+
+fn encoded<T: Encoder>(value: &T) -> Bytes {
+    let mut writer = BytesMut::new();
+    value.encode(&mut writer).unwrap();
+    writer.freeze()
+}
+
+#[test]
+fn test_vec_is_cleared_and_reused() {
+    let first: Vec<Point> = (0..20).map(|i| Point { x: i, y: -i }).collect();
+    let second = vec![Point { x: 7, y: 8 }];
+
+    let mut buffer: Vec<Point> = Vec::new();
+    decode_vec_into(&mut encoded(&first), &mut buffer).unwrap();
+    assert_eq!(buffer, first);
+    let capacity = buffer.capacity();
+    let pointer = buffer.as_ptr();
+
+    decode_vec_into(&mut encoded(&second), &mut buffer).unwrap();
+    assert_eq!(buffer, second);
+    assert_eq!(buffer.capacity(), capacity);
+    assert_eq!(buffer.as_ptr(), pointer);
+}
+
+#[test]
+fn test_hash_map_is_cleared_and_reused() {
+    let first: HashMap<String, u32> = (0..50).map(|i| (format!("key-{}", i), i)).collect();
+    let second = HashMap::from([("only".to_string(), 1u32)]);
+
+    let mut map = HashMap::new();
+    decode_hash_map_into(&mut encoded(&first), &mut map).unwrap();
+    assert_eq!(map, first);
+    let capacity = map.capacity();
+
+    decode_hash_map_into(&mut encoded(&second), &mut map).unwrap();
+    assert_eq!(map, second);
+    assert_eq!(map.capacity(), capacity);
+}
+
+#[test]
+fn test_btree_map_is_cleared() {
+    let mut map = BTreeMap::from([(99u32, Point { x: 0, y: 0 })]);
+    let message = BTreeMap::from([(1u32, Point { x: 1, y: 2 }), (2, Point { x: 3, y: 4 })]);
+    decode_btree_map_into(&mut encoded(&message), &mut map).unwrap();
+    assert_eq!(map, message);
+}
+
+#[test]
+fn test_reader_advances_past_value() {
+    let mut writer = BytesMut::new();
+    vec![1u8, 2, 3].encode(&mut writer).unwrap();
+    42u32.encode(&mut writer).unwrap();
+    let mut reader = writer.freeze();
+
+    let mut buffer = vec![9u8; 4];
+    decode_vec_into(&mut reader, &mut buffer).unwrap();
+    assert_eq!(buffer, vec![1, 2, 3]);
+    assert_eq!(
+        <u32 as senax_encoder::Decoder>::decode(&mut reader).unwrap(),
+        42
+    );
+}
+
+#[test]
+fn test_wrong_type_is_an_error() {
+    let mut buffer = vec![1u32];
+    assert!(decode_vec_into(&mut encoded(&"text".to_string()), &mut buffer).is_err());
+    assert!(buffer.is_empty());
+}
@@ -0,0 +1,196 @@
+use bytes::{BufMut, Bytes, BytesMut};
+use senax_encoder::core::{TAG_ARRAY_VEC_SET_LONG, TAG_MAP};
+use senax_encoder::{
+    decode, decode_with_options, encode, pack, unpack, with_decode_config, Decode, DecodeLimit,
+    DecodeOptions, Decoder, Encode, Encoder, EncoderError, ErrorCode, Unpacker, Value,
+};
+use std::collections::{BTreeMap, HashMap};
+
+#[derive(Encode, Decode, Debug, PartialEq)]
+struct Upload {
+    name: String,
+    chunks: Vec<Vec<u8>>,
+    tags: HashMap<String, String>,
+}
+
+fn upload() -> Upload {
+    Upload {
+        name: "report.pdf".to_string(),
+        chunks: vec![vec![1, 2, 3], vec![4]],
+        tags: [("kind".to_string(), "pdf".to_string())].into(),
+    }
+}
+
+/// A payload announcing `len` elements with `tag`, followed by only a few bytes.
+fn huge_header(tag: u8, len: usize) -> Bytes {
+    let mut buf = BytesMut::new();
+    buf.put_u8(tag);
+    len.encode(&mut buf).unwrap();
+    buf.put_slice(&[0; 4]);
+    buf.freeze()
+}
+
+fn limit_kind(err: EncoderError) -> Option<DecodeLimit> {
+    match err {
+        EncoderError::LimitExceeded { kind, .. } => Some(kind),
+        _ => None,
+    }
+}
+
+#[test]
+fn test_untrusted_lengths_do_not_preallocate() {
+    // Without any limits these fail on the missing elements instead of reserving terabytes
+    for len in [1 << 40, usize::MAX / 2, usize::MAX] {
+        let header = huge_header(TAG_ARRAY_VEC_SET_LONG, len);
+        assert!(Vec::<u64>::decode(&mut header.clone()).is_err());
+        assert!(Vec::<[u64; 64]>::unpack(&mut header.clone()).is_err());
+        assert!(Vec::<Value>::decode(&mut header.clone()).is_err());
+
+        let header = huge_header(TAG_MAP, len);
+        assert!(HashMap::<u64, String>::decode(&mut header.clone()).is_err());
+        assert!(HashMap::<u64, String>::unpack(&mut header.clone()).is_err());
+        assert!(BTreeMap::<u64, u64>::decode(&mut header.clone()).is_err());
+    }
+}
+
+#[test]
+fn test_default_options_accept_everything() {
+    let mut bytes = encode(&upload()).unwrap();
+    let decoded: Upload = decode_with_options(&mut bytes, &DecodeOptions::default()).unwrap();
+    assert_eq!(decoded, upload());
+}
+
+#[test]
+fn test_max_string_len() {
+    let options = DecodeOptions {
+        max_string_len: Some(10),
+        ..Default::default()
+    };
+    let mut bytes = encode(&upload()).unwrap();
+    assert_eq!(
+        decode_with_options::<Upload>(&mut bytes, &options).unwrap(),
+        upload()
+    );
+
+    let long = Upload {
+        name: "quarterly-report.pdf".to_string(),
+        ..upload()
+    };
+    let mut bytes = encode(&long).unwrap();
+    let err = decode_with_options::<Upload>(&mut bytes, &options).unwrap_err();
+    assert!(matches!(
+        err,
+        EncoderError::LimitExceeded {
+            kind: DecodeLimit::StringLength,
+            len: 20,
+            limit: 10
+        }
+    ));
+    assert_eq!(err.code(), ErrorCode::LimitExceeded);
+
+    // Byte strings and string map keys count too
+    let mut bytes = encode(&Bytes::from(vec![0u8; 11])).unwrap();
+    assert!(decode_with_options::<Bytes>(&mut bytes, &options).is_err());
+    let map: HashMap<String, u8> = [("k".repeat(11), 1)].into();
+    let mut bytes = encode(&map).unwrap();
+    let err = decode_with_options::<HashMap<String, u8>>(&mut bytes, &options).unwrap_err();
+    assert_eq!(limit_kind(err), Some(DecodeLimit::StringLength));
+}
+
+#[test]
+fn test_max_collection_len() {
+    let options = DecodeOptions {
+        max_collection_len: Some(3),
+        ..Default::default()
+    };
+    let mut bytes = encode(&vec![1u8, 2, 3]).unwrap();
+    assert!(decode_with_options::<Vec<u8>>(&mut bytes, &options).is_ok());
+
+    let mut bytes = encode(&vec![1u8, 2, 3, 4]).unwrap();
+    let err = decode_with_options::<Vec<u8>>(&mut bytes, &options).unwrap_err();
+    assert!(matches!(
+        err,
+        EncoderError::LimitExceeded {
+            kind: DecodeLimit::CollectionLength,
+            len: 4,
+            limit: 3
+        }
+    ));
+
+    // Checked before the elements are read, so a lying header fails the same way
+    let mut header = huge_header(TAG_ARRAY_VEC_SET_LONG, 1 << 40);
+    let err = with_decode_config(&options, || Vec::<u64>::decode(&mut header)).unwrap_err();
+    assert_eq!(limit_kind(err), Some(DecodeLimit::CollectionLength));
+
+    let map: BTreeMap<u8, u8> = (0..4).map(|i| (i, i)).collect();
+    let mut packed = pack(&map).unwrap();
+    let err = with_decode_config(&options, || unpack::<BTreeMap<u8, u8>>(&mut packed)).unwrap_err();
+    assert_eq!(limit_kind(err), Some(DecodeLimit::CollectionLength));
+
+    let mut bytes = encode(&vec![1u8, 2, 3, 4]).unwrap();
+    let err = decode_with_options::<Value>(&mut bytes, &options).unwrap_err();
+    assert_eq!(limit_kind(err), Some(DecodeLimit::CollectionLength));
+}
+
+#[test]
+fn test_max_alloc_bytes() {
+    // 1000 empty `Vec<u8>`s take a byte each on the wire but 24 bytes each in memory
+    let empties = vec![Vec::<u8>::new(); 1000];
+    let bytes = encode(&empties).unwrap();
+    let element = std::mem::size_of::<Vec<u8>>();
+
+    let roomy = DecodeOptions {
+        max_alloc_bytes: Some(1000 * element),
+        ..Default::default()
+    };
+    assert_eq!(
+        decode_with_options::<Vec<Vec<u8>>>(&mut bytes.clone(), &roomy).unwrap(),
+        empties
+    );
+
+    let tight = DecodeOptions {
+        max_alloc_bytes: Some(1000 * element - 1),
+        ..Default::default()
+    };
+    let err = decode_with_options::<Vec<Vec<u8>>>(&mut bytes.clone(), &tight).unwrap_err();
+    assert!(matches!(
+        err,
+        EncoderError::LimitExceeded {
+            kind: DecodeLimit::AllocatedBytes,
+            ..
+        }
+    ));
+
+    // Strings are charged their length, accumulated over the whole decode
+    let strings = vec!["x".repeat(100); 10];
+    let mut bytes = encode(&strings).unwrap();
+    let budget = DecodeOptions {
+        max_alloc_bytes: Some(10 * std::mem::size_of::<String>() + 999),
+        ..Default::default()
+    };
+    let err = decode_with_options::<Vec<String>>(&mut bytes, &budget).unwrap_err();
+    assert_eq!(limit_kind(err), Some(DecodeLimit::AllocatedBytes));
+}
+
+#[test]
+fn test_budget_is_per_call() {
+    let options = DecodeOptions {
+        max_alloc_bytes: Some(1000),
+        ..Default::default()
+    };
+    let bytes = encode(&"x".repeat(600)).unwrap();
+    for _ in 0..3 {
+        assert!(decode_with_options::<String>(&mut bytes.clone(), &options).is_ok());
+    }
+    // Within one call the budget is shared
+    let result = with_decode_config(&options, || {
+        String::decode(&mut bytes.slice(2..))?;
+        String::decode(&mut bytes.slice(2..))
+    });
+    assert_eq!(
+        limit_kind(result.unwrap_err()),
+        Some(DecodeLimit::AllocatedBytes)
+    );
+    // Plain decoding is unaffected afterwards
+    assert!(decode::<String>(&mut bytes.clone()).is_ok());
+}
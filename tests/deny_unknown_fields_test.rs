@@ -0,0 +1,102 @@
+use senax_encoder::{
+    decode, decode_with_config, encode, Decode, DecodeConfig, Encode, EncoderError,
+    EnumDecodeError, ErrorCode, StructDecodeError,
+};
+
+#[derive(Encode, Decode, Debug, PartialEq)]
+struct TransferV2 {
+    amount: u64,
+    currency: String,
+    reroute_to: Option<String>,
+}
+
+#[derive(Encode, Decode, Debug, PartialEq)]
+struct Transfer {
+    amount: u64,
+    currency: String,
+    #[senax(skip_decode)]
+    reroute_to: Option<String>,
+}
+
+#[derive(Encode, Decode, Debug, PartialEq)]
+struct Settlement {
+    amount: u64,
+    currency: String,
+}
+
+#[derive(Encode, Decode, Debug, PartialEq)]
+enum EventV2 {
+    Paid { amount: u64, memo: String },
+}
+
+#[derive(Encode, Decode, Debug, PartialEq)]
+enum Event {
+    Paid { amount: u64 },
+}
+
+fn deny() -> DecodeConfig {
+    DecodeConfig {
+        deny_unknown_fields: true,
+        ..Default::default()
+    }
+}
+
+fn transfer_v2(reroute_to: Option<&str>) -> bytes::Bytes {
+    encode(&TransferV2 {
+        amount: 100,
+        currency: "EUR".into(),
+        reroute_to: reroute_to.map(Into::into),
+    })
+    .unwrap()
+}
+
+#[test]
+fn test_unknown_fields_are_skipped_by_default() {
+    let settlement: Settlement = decode(&mut transfer_v2(Some("x"))).unwrap();
+    assert_eq!(settlement.amount, 100);
+}
+
+#[test]
+fn test_unknown_struct_fields_are_denied() {
+    let err = decode_with_config::<Settlement>(&mut transfer_v2(Some("x")), &deny()).unwrap_err();
+    assert!(matches!(
+        err,
+        EncoderError::StructDecode(StructDecodeError::UnknownField {
+            struct_name: "Settlement",
+            ..
+        })
+    ));
+    assert_eq!(err.code(), ErrorCode::StructUnknownField);
+
+    // Payloads without extra fields still decode
+    let settlement: Settlement = decode_with_config(&mut transfer_v2(None), &deny()).unwrap();
+    assert_eq!(settlement.currency, "EUR");
+}
+
+#[test]
+fn test_skip_decode_fields_are_known() {
+    let transfer: Transfer = decode_with_config(&mut transfer_v2(Some("x")), &deny()).unwrap();
+    assert_eq!(transfer.reroute_to, None);
+}
+
+#[test]
+fn test_unknown_variant_fields_are_denied() {
+    let mut bytes = encode(&EventV2::Paid {
+        amount: 5,
+        memo: "hi".into(),
+    })
+    .unwrap();
+    assert_eq!(
+        decode::<Event>(&mut bytes.clone()).unwrap(),
+        Event::Paid { amount: 5 }
+    );
+    let err = decode_with_config::<Event>(&mut bytes, &deny()).unwrap_err();
+    assert!(matches!(
+        err,
+        EncoderError::EnumDecode(EnumDecodeError::UnknownField {
+            enum_name: "Event",
+            variant_name: "Paid",
+            ..
+        })
+    ));
+}
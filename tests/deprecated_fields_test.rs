@@ -0,0 +1,153 @@
+use senax_encoder::deprecated_fields::{set_deprecated_field_hook, DeprecatedField};
+use senax_encoder::{collect_deprecated_fields, decode, encode, Decode, Encode};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+#[derive(Encode, Decode, Debug, PartialEq, Default)]
+struct ProfileV1 {
+    id: u64,
+    fax: String,
+    legacy_score: Option<u32>,
+}
+
+#[derive(Encode, Decode, Debug, PartialEq, Default)]
+struct ProfileV2 {
+    id: u64,
+    #[senax(deprecated)]
+    fax: String,
+    #[senax(deprecated)]
+    legacy_score: Option<u32>,
+}
+
+#[derive(Encode, Decode, Debug, PartialEq)]
+enum EventV1 {
+    Login { user: String, ip: String },
+    Logout,
+}
+
+#[derive(Encode, Decode, Debug, PartialEq)]
+enum EventV2 {
+    Login {
+        user: String,
+        #[senax(deprecated)]
+        ip: String,
+    },
+    Logout,
+}
+
+fn old_profile() -> ProfileV1 {
+    ProfileV1 {
+        id: 7,
+        fax: "555-0100".to_string(),
+        legacy_score: Some(42),
+    }
+}
+
+#[test]
+fn test_deprecated_fields_are_still_decoded() {
+    let mut bytes = encode(&old_profile()).unwrap();
+    let (decoded, stats) = collect_deprecated_fields(|| decode::<ProfileV2>(&mut bytes));
+    assert_eq!(
+        decoded.unwrap(),
+        ProfileV2 {
+            id: 7,
+            fax: "555-0100".to_string(),
+            legacy_score: Some(42),
+        }
+    );
+    assert_eq!(stats.count("ProfileV2", "fax"), 1);
+    assert_eq!(stats.count("ProfileV2", "legacy_score"), 1);
+    assert_eq!(stats.count("ProfileV2", "id"), 0);
+    assert_eq!(stats.total(), 2);
+}
+
+#[test]
+fn test_deprecated_fields_are_not_encoded() {
+    let value = ProfileV2 {
+        id: 7,
+        fax: "555-0100".to_string(),
+        legacy_score: Some(42),
+    };
+    let mut bytes = encode(&value).unwrap();
+    assert_eq!(
+        bytes,
+        encode(&ProfileV2 {
+            id: 7,
+            ..Default::default()
+        })
+        .unwrap()
+    );
+
+    // Readers that still require the field reject the new payloads
+    assert!(decode::<ProfileV1>(&mut bytes.clone()).is_err());
+
+    let (decoded, stats) = collect_deprecated_fields(|| decode::<ProfileV2>(&mut bytes));
+    assert_eq!(decoded.unwrap().fax, "");
+    assert!(stats.is_empty());
+}
+
+#[test]
+fn test_deprecated_enum_variant_fields() {
+    let mut bytes = encode(&EventV1::Login {
+        user: "alice".to_string(),
+        ip: "10.0.0.1".to_string(),
+    })
+    .unwrap();
+    let (decoded, stats) = collect_deprecated_fields(|| decode::<EventV2>(&mut bytes));
+    assert_eq!(
+        decoded.unwrap(),
+        EventV2::Login {
+            user: "alice".to_string(),
+            ip: "10.0.0.1".to_string(),
+        }
+    );
+    assert_eq!(
+        stats.iter().collect::<Vec<_>>(),
+        vec![DeprecatedField {
+            type_name: "EventV2::Login",
+            field_name: "ip",
+            count: 1,
+        }]
+    );
+}
+
+#[test]
+fn test_nested_collectors() {
+    let batch = encode(&vec![old_profile(), old_profile(), old_profile()]).unwrap();
+    let (_, outer) = collect_deprecated_fields(|| {
+        let (decoded, inner) =
+            collect_deprecated_fields(|| decode::<Vec<ProfileV2>>(&mut batch.clone()));
+        assert_eq!(decoded.unwrap().len(), 3);
+        assert_eq!(inner.count("ProfileV2", "fax"), 3);
+
+        let mut bytes = encode(&old_profile()).unwrap();
+        decode::<ProfileV2>(&mut bytes).unwrap();
+    });
+    assert_eq!(outer.count("ProfileV2", "fax"), 4);
+    assert_eq!(outer.total(), 8);
+
+    // Reads outside a collector are not counted
+    let mut bytes = encode(&old_profile()).unwrap();
+    decode::<ProfileV2>(&mut bytes).unwrap();
+    let (_, stats) = collect_deprecated_fields(|| ());
+    assert!(stats.is_empty());
+}
+
+static HOOK_CALLS: AtomicU64 = AtomicU64::new(0);
+
+fn count_fax(type_name: &'static str, field_name: &'static str) {
+    if type_name == "ProfileV2" && field_name == "fax" {
+        HOOK_CALLS.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+#[test]
+fn test_hook_sees_every_read() {
+    set_deprecated_field_hook(Some(count_fax));
+    for _ in 0..3 {
+        let mut bytes = encode(&old_profile()).unwrap();
+        decode::<ProfileV2>(&mut bytes).unwrap();
+    }
+    set_deprecated_field_hook(None);
+    // Other tests running in parallel may add to the count
+    assert!(HOOK_CALLS.load(Ordering::Relaxed) >= 3);
+}
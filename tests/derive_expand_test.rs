@@ -0,0 +1,97 @@
+#![cfg(feature = "derive-test")]
+
+//! Expansion snapshots in `tests/expand`. Run with `SENAX_BLESS=1` to accept changes to the
+//! generated code, then review the diff.
+
+use senax_encoder::derive_test::{assert_expansion, expand_derives, pretty};
+
+#[test]
+fn test_named_struct_expansion() {
+    assert_expansion(
+        "tests/expand/named_struct.expanded.rs",
+        expand_derives! {
+            #[derive(Encode, Decode)]
+            struct User {
+                #[senax(id = 1)]
+                id: u64,
+                name: String,
+                #[senax(default)]
+                email: Option<String>,
+            }
+        },
+    );
+}
+
+#[test]
+fn test_generic_struct_expansion() {
+    assert_expansion(
+        "tests/expand/generic_struct.expanded.rs",
+        expand_derives! {
+            #[derive(Encode, Decode)]
+            struct Page<T: Encoder + Decoder + 'static> {
+                items: Vec<T>,
+                next: Option<T>,
+            }
+        },
+    );
+}
+
+#[test]
+fn test_enum_expansion() {
+    assert_expansion(
+        "tests/expand/enum.expanded.rs",
+        expand_derives! {
+            #[derive(Encode, Decode, Pack, Unpack)]
+            enum Shape {
+                Empty,
+                Circle(f64),
+                Rect { width: u32, height: u32 },
+            }
+        },
+    );
+}
+
+#[test]
+fn test_tuple_struct_pack_expansion() {
+    assert_expansion(
+        "tests/expand/tuple_struct_pack.expanded.rs",
+        expand_derives! {
+            #[derive(Pack, Unpack)]
+            struct Point(i32, i32);
+        },
+    );
+}
+
+#[test]
+fn test_expansion_is_valid_rust() {
+    let expansion = expand_derives! {
+        #[derive(Encode)]
+        #[senax(max_size)]
+        struct Header { version: u8, flags: u16 }
+    };
+    let pretty = pretty(expansion);
+    assert!(
+        pretty.contains("impl senax_encoder::Encoder for Header"),
+        "{}",
+        pretty
+    );
+    assert!(pretty.contains("MaxEncodedSize"), "{}", pretty);
+}
+
+#[test]
+fn test_changed_expansion_is_reported() {
+    let path = std::env::temp_dir().join(format!("senax-expand-{}.rs", std::process::id()));
+    std::fs::write(&path, "struct Stale;\n").unwrap();
+    let result = std::panic::catch_unwind(|| {
+        assert_expansion(
+            &path,
+            expand_derives! { #[derive(Pack)] struct Point(i32); },
+        )
+    });
+    std::fs::remove_file(&path).unwrap();
+    if std::env::var_os(senax_encoder::derive_test::BLESS_ENV).is_none() {
+        let message = *result.unwrap_err().downcast::<String>().unwrap();
+        assert!(message.contains("expansion differs"), "{}", message);
+        assert!(message.contains("struct Stale;"), "{}", message);
+    }
+}
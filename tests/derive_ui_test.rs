@@ -0,0 +1,11 @@
+#![cfg(feature = "derive-test")]
+
+//! Shapes the derives must accept (`tests/ui/pass`, compiled and run) or reject with a stable
+//! message (`tests/ui/fail`). Run with `TRYBUILD=overwrite` to refresh `.stderr` files.
+
+#[test]
+fn test_derive_ui() {
+    let cases = trybuild::TestCases::new();
+    cases.pass("tests/ui/pass/*.rs");
+    cases.compile_fail("tests/ui/fail/*.rs");
+}
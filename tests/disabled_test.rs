@@ -1,4 +1,4 @@
-use senax_encoder::{Decoder, Encoder, Packer, Unpacker};
+use senax_encoder::{Decoder, Encoder, EncoderError, Packer, Unpacker};
 use senax_encoder_derive::{Decode, Encode, Pack, Unpack};
 
 #[derive(Encode, Decode, Pack, Unpack)]
@@ -73,25 +73,28 @@ fn test_normal_struct_pack() {
 
 // disable_encode tests
 #[test]
-#[should_panic(expected = "Encode trait is disabled")]
 fn test_disabled_encode_struct_encode() {
     let s = DisabledEncodeStruct { field1: 42 };
     let mut writer = bytes::BytesMut::new();
-    s.encode(&mut writer).unwrap();
+    assert!(matches!(
+        s.encode(&mut writer),
+        Err(EncoderError::Encode(msg)) if msg.contains("Encode trait is disabled")
+    ));
 }
 
 #[test]
-#[should_panic(expected = "Encode trait is disabled")]
 fn test_disabled_encode_struct_is_default() {
     let s = DisabledEncodeStruct { field1: 42 };
-    let _ = s.is_default();
+    assert!(!s.is_default());
 }
 
 #[test]
-#[should_panic(expected = "Decode trait is disabled")]
 fn test_disabled_encode_struct_decode() {
     let mut reader = bytes::Bytes::new();
-    let _ = DisabledEncodeStruct::decode(&mut reader).unwrap();
+    assert!(matches!(
+        DisabledEncodeStruct::decode(&mut reader),
+        Err(EncoderError::Decode(msg)) if msg.contains("Decode trait is disabled")
+    ));
 }
 
 #[test]
@@ -105,18 +108,22 @@ fn test_disabled_encode_struct_pack_works() {
 
 // disable_pack tests
 #[test]
-#[should_panic(expected = "Pack trait is disabled")]
 fn test_disabled_pack_struct_pack() {
     let s = DisabledPackStruct { field1: 42 };
     let mut writer = bytes::BytesMut::new();
-    s.pack(&mut writer).unwrap();
+    assert!(matches!(
+        s.pack(&mut writer),
+        Err(EncoderError::Encode(msg)) if msg.contains("Pack trait is disabled")
+    ));
 }
 
 #[test]
-#[should_panic(expected = "Unpack trait is disabled")]
 fn test_disabled_pack_struct_unpack() {
     let mut reader = bytes::Bytes::new();
-    let _ = DisabledPackStruct::unpack(&mut reader).unwrap();
+    assert!(matches!(
+        DisabledPackStruct::unpack(&mut reader),
+        Err(EncoderError::Decode(msg)) if msg.contains("Unpack trait is disabled")
+    ));
 }
 
 #[test]
@@ -129,19 +136,23 @@ fn test_disabled_pack_struct_encode_works() {
 
 // disable_both tests
 #[test]
-#[should_panic(expected = "Encode trait is disabled")]
 fn test_disabled_both_struct_encode() {
     let s = DisabledBothStruct { field1: 42 };
     let mut writer = bytes::BytesMut::new();
-    s.encode(&mut writer).unwrap();
+    assert!(matches!(
+        s.encode(&mut writer),
+        Err(EncoderError::Encode(msg)) if msg.contains("Encode trait is disabled")
+    ));
 }
 
 #[test]
-#[should_panic(expected = "Pack trait is disabled")]
 fn test_disabled_both_struct_pack() {
     let s = DisabledBothStruct { field1: 42 };
     let mut writer = bytes::BytesMut::new();
-    s.pack(&mut writer).unwrap();
+    assert!(matches!(
+        s.pack(&mut writer),
+        Err(EncoderError::Encode(msg)) if msg.contains("Pack trait is disabled")
+    ));
 }
 
 // Enum tests
@@ -154,18 +165,22 @@ fn test_normal_enum_encode() {
 }
 
 #[test]
-#[should_panic(expected = "Encode trait is disabled")]
 fn test_disabled_encode_enum_encode() {
     let e = DisabledEncodeEnum::Variant1;
     let mut writer = bytes::BytesMut::new();
-    e.encode(&mut writer).unwrap();
+    assert!(matches!(
+        e.encode(&mut writer),
+        Err(EncoderError::Encode(msg)) if msg.contains("Encode trait is disabled")
+    ));
 }
 
 #[test]
-#[should_panic(expected = "Decode trait is disabled")]
 fn test_disabled_encode_enum_decode() {
     let mut reader = bytes::Bytes::new();
-    let _ = DisabledEncodeEnum::decode(&mut reader).unwrap();
+    assert!(matches!(
+        DisabledEncodeEnum::decode(&mut reader),
+        Err(EncoderError::Decode(msg)) if msg.contains("Decode trait is disabled")
+    ));
 }
 
 #[test]
@@ -177,18 +192,22 @@ fn test_disabled_encode_enum_pack_works() {
 }
 
 #[test]
-#[should_panic(expected = "Pack trait is disabled")]
 fn test_disabled_pack_enum_pack() {
     let e = DisabledPackEnum::Variant2(42);
     let mut writer = bytes::BytesMut::new();
-    e.pack(&mut writer).unwrap();
+    assert!(matches!(
+        e.pack(&mut writer),
+        Err(EncoderError::Encode(msg)) if msg.contains("Pack trait is disabled")
+    ));
 }
 
 #[test]
-#[should_panic(expected = "Unpack trait is disabled")]
 fn test_disabled_pack_enum_unpack() {
     let mut reader = bytes::Bytes::new();
-    let _ = DisabledPackEnum::unpack(&mut reader).unwrap();
+    assert!(matches!(
+        DisabledPackEnum::unpack(&mut reader),
+        Err(EncoderError::Decode(msg)) if msg.contains("Unpack trait is disabled")
+    ));
 }
 
 #[test]
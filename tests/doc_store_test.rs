@@ -0,0 +1,211 @@
+#![cfg(feature = "doc-store")]
+
+use bytes::Bytes;
+use senax_encoder::core::field_id_from_name;
+use senax_encoder::doc_store::{Collection, DocStore};
+use senax_encoder::{encode, Decode, Encode, QueryMatch, Value};
+
+#[derive(Encode, Decode, Debug, PartialEq, Clone)]
+struct Order {
+    customer: String,
+    status: Status,
+    total: u64,
+    items: Vec<Item>,
+}
+
+#[derive(Encode, Decode, Debug, PartialEq, Clone)]
+struct Item {
+    sku: String,
+    quantity: u32,
+}
+
+#[derive(Encode, Decode, Debug, PartialEq, Clone)]
+enum Status {
+    Open,
+    Shipped { carrier: String },
+}
+
+#[derive(Encode, Decode, Debug, PartialEq)]
+struct OrderV2 {
+    customer: String,
+    total: u64,
+    #[senax(default)]
+    priority: bool,
+}
+
+fn order(customer: &str, status: Status, total: u64) -> Order {
+    Order {
+        customer: customer.to_string(),
+        status,
+        total,
+        items: vec![Item {
+            sku: format!("sku-{}", total),
+            quantity: 1,
+        }],
+    }
+}
+
+fn sample() -> Collection {
+    let mut orders = Collection::new();
+    orders.insert(&order("alice", Status::Open, 10)).unwrap();
+    orders
+        .insert(&order(
+            "bob",
+            Status::Shipped {
+                carrier: "dhl".into(),
+            },
+            250,
+        ))
+        .unwrap();
+    orders.insert(&order("alice", Status::Open, 99)).unwrap();
+    orders
+}
+
+#[test]
+fn test_insert_and_get() {
+    let orders = sample();
+    assert_eq!(orders.len(), 3);
+    assert_eq!(orders.get::<Order>(1).unwrap().unwrap().customer, "bob");
+    assert!(orders.get::<Order>(3).unwrap().is_none());
+}
+
+#[test]
+fn test_find_scan_and_index_agree() {
+    let mut orders = sample();
+    let customer = field_id_from_name("customer");
+    let scanned = orders.find(customer, &"alice".to_string()).unwrap();
+    assert_eq!(scanned, vec![0, 2]);
+
+    orders.create_index(customer).unwrap();
+    assert!(orders.has_index(customer));
+    assert_eq!(
+        orders.find(customer, &"alice".to_string()).unwrap(),
+        scanned
+    );
+
+    // The index follows later inserts
+    orders.insert(&order("alice", Status::Open, 5)).unwrap();
+    assert_eq!(
+        orders.find(customer, &"alice".to_string()).unwrap(),
+        vec![0, 2, 3]
+    );
+    assert!(orders
+        .find(customer, &"carol".to_string())
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn test_find_compares_encoded_values() {
+    let mut orders = sample();
+    let total = field_id_from_name("total");
+    // Integers of any width encode the same way
+    assert_eq!(orders.find(total, &250u16).unwrap(), vec![1]);
+    orders.create_index(total).unwrap();
+    assert_eq!(orders.find(total, &250u64).unwrap(), vec![1]);
+
+    let status = field_id_from_name("status");
+    orders.create_index(status).unwrap();
+    assert_eq!(orders.find(status, &Status::Open).unwrap(), vec![0, 2]);
+    assert_eq!(
+        orders
+            .find(
+                status,
+                &Status::Shipped {
+                    carrier: "dhl".into()
+                }
+            )
+            .unwrap(),
+        vec![1]
+    );
+}
+
+#[test]
+fn test_project_and_scan() {
+    let orders = sample();
+    assert_eq!(
+        orders.project(1, "items[0].sku").unwrap(),
+        Some(QueryMatch::Scalar(Value::String("sku-250".into())))
+    );
+    assert_eq!(orders.project(1, "missing").unwrap(), None);
+    assert_eq!(orders.project(7, "customer").unwrap(), None);
+
+    let mut totals = Vec::new();
+    orders
+        .scan(field_id_from_name("total"), |id, value| {
+            totals.push((id, value));
+            Ok(())
+        })
+        .unwrap();
+    assert_eq!(
+        totals,
+        vec![
+            (0, QueryMatch::Scalar(Value::UInt(10))),
+            (1, QueryMatch::Scalar(Value::UInt(250))),
+            (2, QueryMatch::Scalar(Value::UInt(99))),
+        ]
+    );
+}
+
+#[test]
+fn test_mixed_versions_in_one_collection() {
+    let mut orders = sample();
+    orders
+        .insert(&OrderV2 {
+            customer: "alice".into(),
+            total: 7,
+            priority: true,
+        })
+        .unwrap();
+    let customer = field_id_from_name("customer");
+    assert_eq!(
+        orders.find(customer, &"alice".to_string()).unwrap(),
+        vec![0, 2, 3]
+    );
+    let old: OrderV2 = orders.get(0).unwrap().unwrap();
+    assert!(!old.priority);
+    assert_eq!(old.total, 10);
+}
+
+#[test]
+fn test_container_round_trip() {
+    let orders = sample();
+    let data = orders.to_container().unwrap();
+    let mut restored = Collection::from_container(Bytes::from(data)).unwrap();
+    assert_eq!(restored.len(), 3);
+    for id in 0..3 {
+        assert_eq!(restored.get_raw(id), orders.get_raw(id));
+    }
+    let customer = field_id_from_name("customer");
+    restored.create_index(customer).unwrap();
+    assert_eq!(
+        restored.find(customer, &"bob".to_string()).unwrap(),
+        vec![1]
+    );
+}
+
+#[test]
+fn test_insert_raw_rejects_malformed_payloads() {
+    let mut orders = Collection::new();
+    let bytes = encode(&order("alice", Status::Open, 1)).unwrap();
+    assert!(orders.insert_raw(bytes.slice(..bytes.len() - 1)).is_err());
+    let mut trailing = bytes.to_vec();
+    trailing.push(0);
+    assert!(orders.insert_raw(Bytes::from(trailing)).is_err());
+    assert!(orders.is_empty());
+    assert_eq!(orders.insert_raw(bytes).unwrap(), 0);
+}
+
+#[test]
+fn test_store_collections() {
+    let mut store = DocStore::new();
+    store
+        .collection_mut("orders")
+        .insert(&order("alice", Status::Open, 1))
+        .unwrap();
+    store.collection_mut("archive");
+    assert_eq!(store.names().collect::<Vec<_>>(), vec!["archive", "orders"]);
+    assert_eq!(store.collection("orders").unwrap().len(), 1);
+    assert!(store.drop_collection("archive").is_some());
+    assert!(store.collection("archive").is_none());
+}
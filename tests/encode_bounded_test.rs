@@ -0,0 +1,68 @@
+use senax_encoder::{decode, encode, encode_bounded, Decode, Encode, EncoderError};
+use std::collections::HashMap;
+
+#[derive(Encode, Decode, Debug, PartialEq)]
+struct Message {
+    topic: String,
+    payload: Vec<u8>,
+    headers: HashMap<String, String>,
+}
+
+fn message(payload_len: usize) -> Message {
+    Message {
+        topic: "events".to_string(),
+        payload: vec![7; payload_len],
+        headers: HashMap::new(),
+    }
+}
+
+#[test]
+fn test_encode_bounded_within_limit_matches_encode() {
+    let value = message(32);
+    let full = encode(&value).unwrap();
+    let bounded = encode_bounded(&value, full.len()).unwrap();
+    assert_eq!(bounded, full);
+    let decoded: Message = decode(&mut bounded.clone()).unwrap();
+    assert_eq!(decoded, value);
+}
+
+#[test]
+fn test_encode_bounded_rejects_oversized_payload() {
+    let value = message(32);
+    let full = encode(&value).unwrap();
+    let result = encode_bounded(&value, full.len() - 1);
+    assert!(matches!(
+        result,
+        Err(EncoderError::TooLarge { actual, limit }) if limit == full.len() - 1 && actual > limit
+    ));
+}
+
+#[test]
+fn test_encode_bounded_stops_early() {
+    let value = message(1_000_000);
+    match encode_bounded(&value, 1_500) {
+        Err(EncoderError::TooLarge { actual, limit }) => {
+            assert_eq!(limit, 1_500);
+            // Stopped near the limit instead of writing the whole payload
+            assert!(actual > limit && actual < 10_000, "actual = {}", actual);
+        }
+        other => panic!("expected TooLarge, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_encode_bounded_long_string_checked_before_copy() {
+    let text = "x".repeat(100_000);
+    match encode_bounded(&text, 64) {
+        Err(EncoderError::TooLarge { limit: 64, .. }) => {}
+        other => panic!("expected TooLarge, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_limit_does_not_leak_after_call() {
+    let value = message(4_096);
+    assert!(encode_bounded(&value, 16).is_err());
+    // Plain encode is unbounded again
+    assert!(encode(&value).unwrap().len() > 4_096);
+}
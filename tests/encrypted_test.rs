@@ -0,0 +1,100 @@
+#![cfg(feature = "chacha20poly1305")]
+
+use bytes::Bytes;
+use senax_encoder::field_crypto::FieldKey;
+use senax_encoder::{
+    decode_encrypted, encode, encode_encrypted, random_nonce, Decode, Encode, EncoderError,
+    ENCRYPTED_MAGIC,
+};
+
+#[derive(Encode, Decode, Debug, PartialEq)]
+struct SessionToken {
+    user_id: u64,
+    scopes: Vec<String>,
+    expires_at: u64,
+}
+
+fn token() -> SessionToken {
+    SessionToken {
+        user_id: 42,
+        scopes: vec!["read".to_string(), "write".to_string()],
+        expires_at: 1_700_000_000,
+    }
+}
+
+fn key() -> FieldKey {
+    FieldKey::new([3; 32])
+}
+
+#[test]
+fn test_round_trip() {
+    let sealed = encode_encrypted(&token(), &key(), &random_nonce()).unwrap();
+    assert_eq!(&sealed[..2], &ENCRYPTED_MAGIC.to_le_bytes());
+    assert_eq!(sealed[2], 1);
+    // Header, nonce and tag around the encoded payload
+    assert_eq!(sealed.len(), 3 + 24 + encode(&token()).unwrap().len() + 16);
+    let decoded: SessionToken = decode_encrypted(&mut sealed.clone(), &key()).unwrap();
+    assert_eq!(decoded, token());
+}
+
+#[test]
+fn test_nonce_is_used_as_given() {
+    let nonce = [9; 24];
+    let a = encode_encrypted(&token(), &key(), &nonce).unwrap();
+    let b = encode_encrypted(&token(), &key(), &nonce).unwrap();
+    assert_eq!(a, b);
+    assert_eq!(&a[3..27], &nonce);
+    let c = encode_encrypted(&token(), &key(), &random_nonce()).unwrap();
+    assert_ne!(a, c);
+}
+
+#[test]
+fn test_plaintext_is_hidden() {
+    let sealed = encode_encrypted(&"top secret".to_string(), &key(), &random_nonce()).unwrap();
+    assert!(!sealed.windows(10).any(|w| w == b"top secret"));
+}
+
+#[test]
+fn test_wrong_key_rejected() {
+    let sealed = encode_encrypted(&token(), &key(), &random_nonce()).unwrap();
+    let err =
+        decode_encrypted::<SessionToken>(&mut sealed.clone(), &FieldKey::new([4; 32])).unwrap_err();
+    assert!(matches!(err, EncoderError::Decode(_)));
+}
+
+#[test]
+fn test_tampering_rejected() {
+    let sealed = encode_encrypted(&token(), &key(), &random_nonce()).unwrap();
+    // Every byte, including the header, is authenticated
+    for i in 0..sealed.len() {
+        let mut tampered = sealed.to_vec();
+        tampered[i] ^= 0x01;
+        assert!(
+            decode_encrypted::<SessionToken>(&mut Bytes::from(tampered), &key()).is_err(),
+            "byte {i}"
+        );
+    }
+    // The payload is the whole buffer, so a short one is corrupt rather than still arriving
+    for len in [0, 2, 3, 26, sealed.len() - 1] {
+        let mut truncated = sealed.slice(..len);
+        let err = decode_encrypted::<SessionToken>(&mut truncated, &key()).unwrap_err();
+        assert!(matches!(err, EncoderError::Decode(_)), "{len}: {err:?}");
+    }
+}
+
+#[test]
+fn test_unknown_algorithm() {
+    let mut sealed = encode_encrypted(&token(), &key(), &random_nonce())
+        .unwrap()
+        .to_vec();
+    sealed[2] = 2;
+    let err = decode_encrypted::<SessionToken>(&mut Bytes::from(sealed), &key()).unwrap_err();
+    assert!(matches!(err, EncoderError::Decode(msg) if msg.contains("algorithm")));
+}
+
+#[test]
+fn test_trailing_data_rejected() {
+    // A sealed value of another type that decodes as a prefix must not be accepted silently
+    let sealed = encode_encrypted(&(1u8, 2u8), &key(), &random_nonce()).unwrap();
+    assert!(decode_encrypted::<u8>(&mut sealed.clone(), &key()).is_err());
+}
@@ -0,0 +1,54 @@
+use bytes::{Bytes, BytesMut};
+use senax_encoder::{decode, encode, encode_bounded, Decode, Decoder, Encode, Encoder, ErrorCode};
+
+#[derive(Encode, Decode, Debug, PartialEq)]
+struct Required {
+    id: u32,
+}
+
+#[derive(Encode, Decode, Debug, PartialEq)]
+struct Empty {}
+
+#[derive(Encode, Decode, Debug, PartialEq)]
+enum Mode {
+    #[senax(id = 1)]
+    On,
+}
+
+#[derive(Encode, Decode, Debug, PartialEq)]
+enum OtherMode {
+    #[senax(id = 2)]
+    Off,
+}
+
+#[test]
+fn test_error_codes() {
+    let err = decode::<u32>(&mut Bytes::new()).unwrap_err();
+    assert_eq!(err.code(), ErrorCode::UnexpectedEof);
+
+    let err = encode_bounded(&vec![0u64; 100], 8).unwrap_err();
+    assert_eq!(err.code(), ErrorCode::TooLarge);
+
+    let mut bytes = encode(&Empty {}).unwrap();
+    let err = decode::<Required>(&mut bytes).unwrap_err();
+    assert_eq!(err.code(), ErrorCode::StructMissingRequiredField);
+
+    let mut buf = BytesMut::new();
+    OtherMode::Off.encode(&mut buf).unwrap();
+    let err = Mode::decode(&mut buf.freeze()).unwrap_err();
+    assert_eq!(err.code(), ErrorCode::EnumUnknownVariantId);
+
+    let mut buf = BytesMut::new();
+    "x".to_string().encode(&mut buf).unwrap();
+    let err = bool::decode(&mut buf.freeze()).unwrap_err();
+    assert_eq!(err.code(), ErrorCode::Decode);
+}
+
+#[test]
+fn test_error_code_values_are_stable() {
+    assert_eq!(ErrorCode::Encode.as_u16(), 1);
+    assert_eq!(ErrorCode::UnexpectedEof.as_u16(), 3);
+    assert_eq!(ErrorCode::StructInvalidTag.as_u16(), 100);
+    assert_eq!(ErrorCode::EnumUnknownTag.as_u16(), 200);
+    assert_eq!(ErrorCode::TooLarge.description(), "payload too large");
+}
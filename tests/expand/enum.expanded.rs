@@ -0,0 +1,325 @@
+#[deny(
+    clippy::unwrap_used,
+    clippy::expect_used,
+    clippy::panic,
+    clippy::unreachable,
+    clippy::todo,
+    clippy::unimplemented
+)]
+impl senax_encoder::Encoder for Shape {
+    fn encode(
+        &self,
+        writer: &mut senax_encoder::bytes::BytesMut,
+    ) -> senax_encoder::Result<()> {
+        use senax_encoder::bytes::{Buf, BufMut};
+        match self {
+            Shape::Empty => {
+                writer.put_u8(senax_encoder::core::TAG_ENUM);
+                senax_encoder::self_describing::write_field_header(
+                    writer,
+                    11249646103307311220u64,
+                    "Empty",
+                )?;
+            }
+            Shape::Circle(field0) => {
+                writer.put_u8(senax_encoder::core::TAG_ENUM_UNNAMED);
+                senax_encoder::self_describing::write_field_header(
+                    writer,
+                    14089855573739564790u64,
+                    "Circle",
+                )?;
+                let count: usize = 1usize;
+                senax_encoder::Encoder::encode(&count, writer)?;
+                senax_encoder::Encoder::encode(&field0, writer)?;
+            }
+            Shape::Rect { width, height } => {
+                writer.put_u8(senax_encoder::core::TAG_ENUM_NAMED);
+                senax_encoder::self_describing::write_field_header(
+                    writer,
+                    4434803280450816939u64,
+                    "Rect",
+                )?;
+                senax_encoder::self_describing::write_field_header(
+                    writer,
+                    8245894047076337558u64,
+                    "width",
+                )?;
+                senax_encoder::Encoder::encode(&width, writer)?;
+                senax_encoder::self_describing::write_field_header(
+                    writer,
+                    12717095085819724020u64,
+                    "height",
+                )?;
+                senax_encoder::Encoder::encode(&height, writer)?;
+                senax_encoder::core::write_field_id_optimized(writer, 0)?;
+            }
+        }
+        Ok(())
+    }
+    fn is_default(&self) -> bool {
+        false
+    }
+}
+#[deny(
+    clippy::unwrap_used,
+    clippy::expect_used,
+    clippy::panic,
+    clippy::unreachable,
+    clippy::todo,
+    clippy::unimplemented
+)]
+impl senax_encoder::Decoder for Shape {
+    fn decode(reader: &mut senax_encoder::bytes::Bytes) -> senax_encoder::Result<Self> {
+        use senax_encoder::bytes::{Buf, BufMut};
+        if reader.remaining() == 0 {
+            return Err(senax_encoder::EncoderError::UnexpectedEof {
+                needed: 1,
+                available: 0,
+            });
+        }
+        let tag = reader.get_u8();
+        match tag {
+            senax_encoder::core::TAG_ENUM => {
+                let variant_id = senax_encoder::core::read_field_id_optimized(reader)?;
+                match variant_id {
+                    x if x == 11249646103307311220u64 => Ok(Shape::Empty),
+                    _ => {
+                        Err(
+                            senax_encoder::EncoderError::EnumDecode(senax_encoder::EnumDecodeError::UnknownVariantId {
+                                variant_id,
+                                enum_name: stringify!(Shape),
+                            }),
+                        )
+                    }
+                }
+            }
+            senax_encoder::core::TAG_ENUM_NAMED => {
+                let variant_id = senax_encoder::core::read_field_id_optimized(reader)?;
+                match variant_id {
+                    x if x == 4434803280450816939u64 => {
+                        struct FieldValues<__SenaxField0, __SenaxField1> {
+                            width: __SenaxField0,
+                            height: __SenaxField1,
+                        }
+                        let mut field_values = FieldValues::<Option<u32>, Option<u32>> {
+                            width: None,
+                            height: None,
+                        };
+                        loop {
+                            let field_id = {
+                                if reader.remaining() == 0 {
+                                    break;
+                                }
+                                let id = senax_encoder::core::read_field_id_optimized(
+                                    reader,
+                                )?;
+                                if id == 0 {
+                                    break;
+                                }
+                                id
+                            };
+                            match field_id {
+                                x if x == 8245894047076337558u64 => {
+                                    field_values.width = Some(
+                                        <u32 as senax_encoder::Decoder>::decode(reader)?,
+                                    );
+                                }
+                                x if x == 12717095085819724020u64 => {
+                                    field_values.height = Some(
+                                        <u32 as senax_encoder::Decoder>::decode(reader)?,
+                                    );
+                                }
+                                unknown_id => {
+                                    if senax_encoder::current_decode_config()
+                                        .deny_unknown_fields
+                                    {
+                                        return Err(
+                                            senax_encoder::EncoderError::EnumDecode(senax_encoder::EnumDecodeError::UnknownField {
+                                                field_id: unknown_id,
+                                                enum_name: stringify!(Shape),
+                                                variant_name: stringify!(Rect),
+                                            }),
+                                        );
+                                    }
+                                    senax_encoder::unknown_fields::skip_unknown_field(
+                                        reader,
+                                        concat!(stringify!(Shape), "::", stringify!(Rect)),
+                                        unknown_id,
+                                    )?;
+                                }
+                            }
+                        }
+                        Ok(Shape::Rect {
+                            width: field_values
+                                .width
+                                .ok_or_else(|| senax_encoder::EncoderError::EnumDecode(senax_encoder::EnumDecodeError::MissingRequiredField {
+                                    field: stringify!(width),
+                                    enum_name: stringify!(Shape),
+                                    variant_name: stringify!(Rect),
+                                }))?,
+                            height: field_values
+                                .height
+                                .ok_or_else(|| senax_encoder::EncoderError::EnumDecode(senax_encoder::EnumDecodeError::MissingRequiredField {
+                                    field: stringify!(height),
+                                    enum_name: stringify!(Shape),
+                                    variant_name: stringify!(Rect),
+                                }))?,
+                        })
+                    }
+                    _ => {
+                        Err(
+                            senax_encoder::EncoderError::EnumDecode(senax_encoder::EnumDecodeError::UnknownVariantId {
+                                variant_id,
+                                enum_name: stringify!(Shape),
+                            }),
+                        )
+                    }
+                }
+            }
+            senax_encoder::core::TAG_ENUM_UNNAMED => {
+                let variant_id = senax_encoder::core::read_field_id_optimized(reader)?;
+                match variant_id {
+                    x if x == 14089855573739564790u64 => {
+                        let count = <usize as senax_encoder::Decoder>::decode(reader)?;
+                        if count != 1usize {
+                            return Err(
+                                senax_encoder::EncoderError::EnumDecode(senax_encoder::EnumDecodeError::FieldCountMismatch {
+                                    enum_name: stringify!(Shape),
+                                    variant_name: stringify!(Circle),
+                                    expected: 1usize,
+                                    actual: count,
+                                }),
+                            );
+                        }
+                        Ok(
+                            Shape::Circle(
+                                <f64 as senax_encoder::Decoder>::decode(reader)?,
+                            ),
+                        )
+                    }
+                    _ => {
+                        Err(
+                            senax_encoder::EncoderError::EnumDecode(senax_encoder::EnumDecodeError::UnknownVariantId {
+                                variant_id,
+                                enum_name: stringify!(Shape),
+                            }),
+                        )
+                    }
+                }
+            }
+            unknown_tag => {
+                Err(
+                    senax_encoder::EncoderError::EnumDecode(senax_encoder::EnumDecodeError::UnknownTag {
+                        tag: unknown_tag,
+                        enum_name: stringify!(Shape),
+                    }),
+                )
+            }
+        }
+    }
+}
+#[deny(
+    clippy::unwrap_used,
+    clippy::expect_used,
+    clippy::panic,
+    clippy::unreachable,
+    clippy::todo,
+    clippy::unimplemented
+)]
+impl senax_encoder::Packer for Shape {
+    fn pack(
+        &self,
+        writer: &mut senax_encoder::bytes::BytesMut,
+    ) -> senax_encoder::Result<()> {
+        use senax_encoder::bytes::{Buf, BufMut};
+        match self {
+            Shape::Empty => {
+                senax_encoder::core::write_field_id_optimized(
+                    writer,
+                    11249646103307311220u64,
+                )?;
+            }
+            Shape::Circle(field0) => {
+                senax_encoder::core::write_field_id_optimized(
+                    writer,
+                    14089855573739564790u64,
+                )?;
+                let count: usize = 1usize;
+                senax_encoder::Encoder::encode(&count, writer)?;
+                senax_encoder::Packer::pack(&field0, writer)?;
+            }
+            Shape::Rect { width, height } => {
+                senax_encoder::core::write_field_id_optimized(
+                    writer,
+                    4434803280450816939u64,
+                )?;
+                writer.put_u64_le(8081923838570080504u64);
+                senax_encoder::Packer::pack(width, writer)?;
+                senax_encoder::Packer::pack(height, writer)?;
+            }
+        }
+        Ok(())
+    }
+}
+#[deny(
+    clippy::unwrap_used,
+    clippy::expect_used,
+    clippy::panic,
+    clippy::unreachable,
+    clippy::todo,
+    clippy::unimplemented
+)]
+impl senax_encoder::Unpacker for Shape {
+    fn unpack(reader: &mut senax_encoder::bytes::Bytes) -> senax_encoder::Result<Self> {
+        use senax_encoder::bytes::{Buf, BufMut};
+        let variant_id = senax_encoder::core::read_field_id_optimized(reader)?;
+        match variant_id {
+            x if x == 11249646103307311220u64 => Ok(Shape::Empty),
+            x if x == 14089855573739564790u64 => {
+                let field_count = <usize as senax_encoder::Decoder>::decode(reader)?;
+                if field_count != 1usize {
+                    return Err(
+                        senax_encoder::EncoderError::EnumDecode(senax_encoder::EnumDecodeError::FieldCountMismatch {
+                            enum_name: stringify!(Shape),
+                            variant_name: stringify!(Circle),
+                            expected: 1usize,
+                            actual: field_count,
+                        }),
+                    );
+                }
+                Ok(Shape::Circle(<f64 as senax_encoder::Unpacker>::unpack(reader)?))
+            }
+            x if x == 4434803280450816939u64 => {
+                if reader.remaining() < 8 {
+                    return Err(senax_encoder::EncoderError::UnexpectedEof {
+                        needed: 8,
+                        available: reader.remaining(),
+                    });
+                }
+                let received_hash = reader.get_u64_le();
+                if received_hash != 8081923838570080504u64 {
+                    return Err(
+                        senax_encoder::EncoderError::EnumDecode(senax_encoder::EnumDecodeError::StructureHashMismatch {
+                            enum_name: stringify!(Shape),
+                            variant_name: stringify!(Rect),
+                            expected: 8081923838570080504u64,
+                            actual: received_hash,
+                        }),
+                    );
+                }
+                Ok(Shape::Rect {
+                    width: <u32 as senax_encoder::Unpacker>::unpack(reader)?,
+                    height: <u32 as senax_encoder::Unpacker>::unpack(reader)?,
+                })
+            }
+            _ => {
+                Err(
+                    senax_encoder::EncoderError::EnumDecode(senax_encoder::EnumDecodeError::UnknownVariantId {
+                        variant_id,
+                        enum_name: stringify!(Shape),
+                    }),
+                )
+            }
+        }
+    }
+}
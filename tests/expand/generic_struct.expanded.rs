@@ -0,0 +1,121 @@
+#[deny(
+    clippy::unwrap_used,
+    clippy::expect_used,
+    clippy::panic,
+    clippy::unreachable,
+    clippy::todo,
+    clippy::unimplemented
+)]
+impl<T: Encoder + Decoder + 'static> senax_encoder::Encoder for Page<T>
+where
+    T: senax_encoder::Encoder + 'static,
+{
+    fn encode(
+        &self,
+        writer: &mut senax_encoder::bytes::BytesMut,
+    ) -> senax_encoder::Result<()> {
+        use senax_encoder::bytes::{Buf, BufMut};
+        writer.put_u8(senax_encoder::core::TAG_STRUCT_NAMED);
+        senax_encoder::self_describing::write_field_header(
+            writer,
+            13382052802895243243u64,
+            "items",
+        )?;
+        senax_encoder::Encoder::encode(&self.items, writer)?;
+        if let Some(val) = &self.next {
+            senax_encoder::self_describing::write_field_header(
+                writer,
+                16175431968555483933u64,
+                "next",
+            )?;
+            senax_encoder::Encoder::encode(val, writer)?;
+        }
+        senax_encoder::core::write_field_id_optimized(writer, 0)?;
+        Ok(())
+    }
+    fn is_default(&self) -> bool {
+        false
+    }
+}
+#[deny(
+    clippy::unwrap_used,
+    clippy::expect_used,
+    clippy::panic,
+    clippy::unreachable,
+    clippy::todo,
+    clippy::unimplemented
+)]
+impl<T: Encoder + Decoder + 'static> senax_encoder::Decoder for Page<T>
+where
+    T: senax_encoder::Decoder + 'static,
+{
+    fn decode(reader: &mut senax_encoder::bytes::Bytes) -> senax_encoder::Result<Self> {
+        use senax_encoder::bytes::{Buf, BufMut};
+        if reader.remaining() == 0 {
+            return Err(senax_encoder::EncoderError::UnexpectedEof {
+                needed: 1,
+                available: 0,
+            });
+        }
+        let tag = reader.get_u8();
+        struct FieldValues<__SenaxField0, __SenaxField1> {
+            items: __SenaxField0,
+            next: __SenaxField1,
+        }
+        let mut field_values = FieldValues::<Option<Vec<T>>, Option<T>> {
+            items: None,
+            next: None,
+        };
+        if tag == senax_encoder::core::TAG_STRUCT_NAMED {
+            loop {
+                let field_id = senax_encoder::core::read_field_id_optimized(reader)?;
+                if field_id == 0 {
+                    break;
+                }
+                match field_id {
+                    x if x == 13382052802895243243u64 => {
+                        field_values.items = Some(
+                            <Vec<T> as senax_encoder::Decoder>::decode(reader)?,
+                        );
+                    }
+                    x if x == 16175431968555483933u64 => {
+                        field_values.next = Some(
+                            <T as senax_encoder::Decoder>::decode(reader)?,
+                        );
+                    }
+                    unknown_id => {
+                        if senax_encoder::current_decode_config().deny_unknown_fields {
+                            return Err(
+                                senax_encoder::EncoderError::StructDecode(senax_encoder::StructDecodeError::UnknownField {
+                                    field_id: unknown_id,
+                                    struct_name: stringify!(Page),
+                                }),
+                            );
+                        }
+                        senax_encoder::unknown_fields::skip_unknown_field(
+                            reader,
+                            stringify!(Page),
+                            unknown_id,
+                        )?;
+                    }
+                }
+            }
+        } else {
+            return Err(
+                senax_encoder::EncoderError::StructDecode(senax_encoder::StructDecodeError::InvalidTag {
+                    expected: senax_encoder::core::TAG_STRUCT_NAMED,
+                    actual: tag,
+                }),
+            );
+        }
+        Ok(Page {
+            items: field_values
+                .items
+                .ok_or_else(|| senax_encoder::EncoderError::StructDecode(senax_encoder::StructDecodeError::MissingRequiredField {
+                    field: stringify!(items),
+                    struct_name: stringify!(Page),
+                }))?,
+            next: field_values.next,
+        })
+    }
+}
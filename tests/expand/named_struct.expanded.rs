@@ -0,0 +1,137 @@
+#[deny(
+    clippy::unwrap_used,
+    clippy::expect_used,
+    clippy::panic,
+    clippy::unreachable,
+    clippy::todo,
+    clippy::unimplemented
+)]
+impl senax_encoder::Encoder for User {
+    fn encode(
+        &self,
+        writer: &mut senax_encoder::bytes::BytesMut,
+    ) -> senax_encoder::Result<()> {
+        use senax_encoder::bytes::{Buf, BufMut};
+        writer.put_u8(senax_encoder::core::TAG_STRUCT_NAMED);
+        senax_encoder::self_describing::write_field_header(writer, 1u64, "id")?;
+        senax_encoder::Encoder::encode(&self.id, writer)?;
+        senax_encoder::self_describing::write_field_header(
+            writer,
+            4190884490747648382u64,
+            "name",
+        )?;
+        senax_encoder::Encoder::encode(&self.name, writer)?;
+        if let Some(val) = &self.email {
+            senax_encoder::self_describing::write_field_header(
+                writer,
+                15291464999391453190u64,
+                "email",
+            )?;
+            senax_encoder::Encoder::encode(val, writer)?;
+        }
+        senax_encoder::core::write_field_id_optimized(writer, 0)?;
+        Ok(())
+    }
+    fn is_default(&self) -> bool {
+        false
+    }
+}
+#[deny(
+    clippy::unwrap_used,
+    clippy::expect_used,
+    clippy::panic,
+    clippy::unreachable,
+    clippy::todo,
+    clippy::unimplemented
+)]
+impl senax_encoder::Decoder for User {
+    fn decode(reader: &mut senax_encoder::bytes::Bytes) -> senax_encoder::Result<Self> {
+        use senax_encoder::bytes::{Buf, BufMut};
+        if reader.remaining() == 0 {
+            return Err(senax_encoder::EncoderError::UnexpectedEof {
+                needed: 1,
+                available: 0,
+            });
+        }
+        let tag = reader.get_u8();
+        struct FieldValues<__SenaxField0, __SenaxField1, __SenaxField2> {
+            id: __SenaxField0,
+            name: __SenaxField1,
+            email: __SenaxField2,
+        }
+        let mut field_values = FieldValues::<
+            Option<u64>,
+            Option<String>,
+            Option<String>,
+        > {
+            id: None,
+            name: None,
+            email: None,
+        };
+        if tag == senax_encoder::core::TAG_STRUCT_NAMED {
+            loop {
+                let field_id = senax_encoder::core::read_field_id_aliased(
+                    reader,
+                    &[("id", 1u64)],
+                )?;
+                if field_id == 0 {
+                    break;
+                }
+                match field_id {
+                    x if x == 1u64 => {
+                        field_values.id = Some(
+                            <u64 as senax_encoder::Decoder>::decode(reader)?,
+                        );
+                    }
+                    x if x == 4190884490747648382u64 => {
+                        field_values.name = Some(
+                            <String as senax_encoder::Decoder>::decode(reader)?,
+                        );
+                    }
+                    x if x == 15291464999391453190u64 => {
+                        field_values.email = Some(
+                            <String as senax_encoder::Decoder>::decode(reader)?,
+                        );
+                    }
+                    unknown_id => {
+                        if senax_encoder::current_decode_config().deny_unknown_fields {
+                            return Err(
+                                senax_encoder::EncoderError::StructDecode(senax_encoder::StructDecodeError::UnknownField {
+                                    field_id: unknown_id,
+                                    struct_name: stringify!(User),
+                                }),
+                            );
+                        }
+                        senax_encoder::unknown_fields::skip_unknown_field(
+                            reader,
+                            stringify!(User),
+                            unknown_id,
+                        )?;
+                    }
+                }
+            }
+        } else {
+            return Err(
+                senax_encoder::EncoderError::StructDecode(senax_encoder::StructDecodeError::InvalidTag {
+                    expected: senax_encoder::core::TAG_STRUCT_NAMED,
+                    actual: tag,
+                }),
+            );
+        }
+        Ok(User {
+            id: field_values
+                .id
+                .ok_or_else(|| senax_encoder::EncoderError::StructDecode(senax_encoder::StructDecodeError::MissingRequiredField {
+                    field: stringify!(id),
+                    struct_name: stringify!(User),
+                }))?,
+            name: field_values
+                .name
+                .ok_or_else(|| senax_encoder::EncoderError::StructDecode(senax_encoder::StructDecodeError::MissingRequiredField {
+                    field: stringify!(name),
+                    struct_name: stringify!(User),
+                }))?,
+            email: field_values.email,
+        })
+    }
+}
@@ -0,0 +1,50 @@
+#[deny(
+    clippy::unwrap_used,
+    clippy::expect_used,
+    clippy::panic,
+    clippy::unreachable,
+    clippy::todo,
+    clippy::unimplemented
+)]
+impl senax_encoder::Packer for Point {
+    fn pack(
+        &self,
+        writer: &mut senax_encoder::bytes::BytesMut,
+    ) -> senax_encoder::Result<()> {
+        use senax_encoder::bytes::{Buf, BufMut};
+        let count: usize = 2usize;
+        senax_encoder::Encoder::encode(&count, writer)?;
+        senax_encoder::Packer::pack(&self.0, writer)?;
+        senax_encoder::Packer::pack(&self.1, writer)?;
+        Ok(())
+    }
+}
+#[deny(
+    clippy::unwrap_used,
+    clippy::expect_used,
+    clippy::panic,
+    clippy::unreachable,
+    clippy::todo,
+    clippy::unimplemented
+)]
+impl senax_encoder::Unpacker for Point {
+    fn unpack(reader: &mut senax_encoder::bytes::Bytes) -> senax_encoder::Result<Self> {
+        use senax_encoder::bytes::{Buf, BufMut};
+        let field_count = <usize as senax_encoder::Decoder>::decode(reader)?;
+        if field_count != 2usize {
+            return Err(
+                senax_encoder::EncoderError::StructDecode(senax_encoder::StructDecodeError::FieldCountMismatch {
+                    struct_name: stringify!(Point),
+                    expected: 2usize,
+                    actual: field_count,
+                }),
+            );
+        }
+        Ok(
+            Point(
+                <i32 as senax_encoder::Unpacker>::unpack(reader)?,
+                <i32 as senax_encoder::Unpacker>::unpack(reader)?,
+            ),
+        )
+    }
+}
@@ -0,0 +1,154 @@
+use bytes::Bytes;
+use senax_encoder::core::field_id_from_name;
+use senax_encoder::ext_block::{ExtBlock, Extension};
+use senax_encoder::{decode, encode, pack, unpack, Decode, Encode, Pack, Unpack};
+
+#[derive(Encode, Decode, Debug, PartialEq)]
+struct Audit {
+    approved_by: String,
+}
+
+impl Extension for Audit {
+    const EXT_ID: u64 = field_id_from_name("com.example.audit");
+}
+
+#[derive(Encode, Decode, Debug, PartialEq)]
+struct Routing {
+    region: String,
+    priority: u8,
+}
+
+impl Extension for Routing {
+    const EXT_ID: u64 = field_id_from_name("org.vendor.routing");
+}
+
+#[derive(Encode, Decode, Pack, Unpack, Debug, PartialEq, Default)]
+struct Order {
+    id: u64,
+    #[senax(ext_block)]
+    ext: ExtBlock,
+}
+
+/// The same type as an older build that has no extension block.
+#[derive(Encode, Decode, Debug, PartialEq)]
+struct OrderV1 {
+    id: u64,
+}
+
+/// A build that reads the block as plain bytes.
+#[derive(Encode, Decode, Debug, PartialEq)]
+struct OrderRaw {
+    id: u64,
+    #[senax(default)]
+    ext: Bytes,
+}
+
+fn order_with_extensions() -> Order {
+    let mut order = Order {
+        id: 7,
+        ext: ExtBlock::new(),
+    };
+    order
+        .ext
+        .insert(&Audit {
+            approved_by: "kim".to_string(),
+        })
+        .unwrap();
+    order
+        .ext
+        .insert(&Routing {
+            region: "eu".to_string(),
+            priority: 2,
+        })
+        .unwrap();
+    order
+}
+
+#[test]
+fn test_extensions_roundtrip() {
+    let order = order_with_extensions();
+    let decoded: Order = decode(&mut encode(&order).unwrap()).unwrap();
+    assert_eq!(decoded, order);
+    assert_eq!(
+        decoded.ext.get::<Audit>().unwrap().unwrap().approved_by,
+        "kim"
+    );
+    assert_eq!(decoded.ext.get::<Routing>().unwrap().unwrap().priority, 2);
+
+    let unpacked: Order = unpack(&mut pack(&order).unwrap()).unwrap();
+    assert_eq!(unpacked, order);
+}
+
+#[test]
+fn test_unknown_extensions_survive_reencode() {
+    let bytes = encode(&order_with_extensions()).unwrap();
+
+    // A reader that only knows `Audit` keeps `Routing` untouched
+    let mut decoded: Order = decode(&mut bytes.clone()).unwrap();
+    assert_eq!(encode(&decoded).unwrap(), bytes);
+
+    decoded
+        .ext
+        .insert(&Audit {
+            approved_by: "lee".to_string(),
+        })
+        .unwrap();
+    let reencoded: Order = decode(&mut encode(&decoded).unwrap()).unwrap();
+    assert_eq!(
+        reencoded.ext.get::<Audit>().unwrap().unwrap().approved_by,
+        "lee"
+    );
+    assert_eq!(
+        reencoded.ext.get::<Routing>().unwrap(),
+        Some(Routing {
+            region: "eu".to_string(),
+            priority: 2
+        })
+    );
+    // Replacing keeps the order of the entries
+    let ids: Vec<u64> = reencoded
+        .ext
+        .entries()
+        .unwrap()
+        .iter()
+        .map(|e| e.0)
+        .collect();
+    assert_eq!(ids, [Audit::EXT_ID, Routing::EXT_ID]);
+}
+
+#[test]
+fn test_interop_with_older_types() {
+    let bytes = encode(&order_with_extensions()).unwrap();
+    assert_eq!(decode::<OrderV1>(&mut bytes.clone()).unwrap().id, 7);
+
+    let raw: OrderRaw = decode(&mut bytes.clone()).unwrap();
+    let block = ExtBlock::from_raw(raw.ext);
+    assert!(block.contains::<Routing>().unwrap());
+
+    // Missing blocks decode empty, and empty blocks are not written
+    let order: Order = decode(&mut encode(&OrderV1 { id: 3 }).unwrap()).unwrap();
+    assert!(order.ext.is_empty());
+    assert_eq!(encode(&order).unwrap(), encode(&OrderV1 { id: 3 }).unwrap());
+}
+
+#[test]
+fn test_remove() {
+    let mut order = order_with_extensions();
+    assert!(order.ext.remove::<Audit>().unwrap());
+    assert!(!order.ext.remove::<Audit>().unwrap());
+    assert_eq!(order.ext.get::<Audit>().unwrap(), None);
+    assert!(order.ext.remove::<Routing>().unwrap());
+    assert!(order.ext.is_empty());
+}
+
+#[test]
+fn test_malformed_region_is_kept_verbatim() {
+    let raw = OrderRaw {
+        id: 1,
+        ext: Bytes::from_static(&[0x05, 0xff]),
+    };
+    let bytes = encode(&raw).unwrap();
+    let order: Order = decode(&mut bytes.clone()).unwrap();
+    assert!(order.ext.get::<Audit>().is_err());
+    assert_eq!(encode(&order).unwrap(), bytes);
+}
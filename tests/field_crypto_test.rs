@@ -0,0 +1,99 @@
+#![cfg(feature = "chacha20poly1305")]
+
+use bytes::{Bytes, BytesMut};
+use senax_encoder::field_crypto::{decode_encrypted_field, with_field_key, FieldKey};
+use senax_encoder::{decode, encode, Decode, Encode, Encoder, EncoderError};
+
+#[derive(Encode, Decode, Debug, PartialEq, Clone)]
+struct Patient {
+    id: u64,
+    #[senax(encrypt)]
+    name: String,
+    #[senax(encrypt)]
+    phone: Option<String>,
+    #[senax(encrypt, skip_default)]
+    notes: Vec<String>,
+}
+
+#[derive(Encode, Decode, Debug, PartialEq)]
+struct Secret {
+    #[senax(encrypt, id = 1)]
+    value: String,
+}
+
+#[derive(Encode, Decode, Debug, PartialEq)]
+struct MovedSecret {
+    #[senax(encrypt, id = 2)]
+    value: String,
+}
+
+fn patient() -> Patient {
+    Patient {
+        id: 7,
+        name: "Jane Roe".to_string(),
+        phone: Some("555-0100".to_string()),
+        notes: vec!["allergic to penicillin".to_string()],
+    }
+}
+
+#[test]
+fn test_encrypted_fields_roundtrip() {
+    let key = FieldKey::new([1; 32]);
+    let value = patient();
+    let bytes = with_field_key(&key, || encode(&value)).unwrap();
+    let decoded: Patient = with_field_key(&key, || decode(&mut bytes.clone())).unwrap();
+    assert_eq!(decoded, value);
+}
+
+#[test]
+fn test_encrypted_fields_hide_plaintext() {
+    let key = FieldKey::new([2; 32]);
+    let bytes = with_field_key(&key, || encode(&patient())).unwrap();
+    let haystack = bytes.as_ref();
+    for needle in [&b"Jane Roe"[..], b"555-0100", b"penicillin"] {
+        assert!(!haystack.windows(needle.len()).any(|w| w == needle));
+    }
+    // A fresh nonce is used every time
+    let again = with_field_key(&key, || encode(&patient())).unwrap();
+    assert_ne!(bytes, again);
+}
+
+#[test]
+fn test_encrypted_fields_require_matching_key() {
+    let bytes = with_field_key(&FieldKey::new([3; 32]), || encode(&patient())).unwrap();
+    let wrong = with_field_key(&FieldKey::new([4; 32]), || {
+        decode::<Patient>(&mut bytes.clone())
+    });
+    assert!(matches!(wrong, Err(EncoderError::Decode(_))));
+    assert!(decode::<Patient>(&mut bytes.clone()).is_err());
+    assert!(matches!(encode(&patient()), Err(EncoderError::Encode(_))));
+}
+
+#[test]
+fn test_encrypted_field_bound_to_field_id() {
+    let key = FieldKey::new([5; 32]);
+    let secret = Secret {
+        value: "s3cret".to_string(),
+    };
+    let bytes = with_field_key(&key, || encode(&secret)).unwrap();
+    // [magic: 2][TAG_STRUCT_NAMED][field id 1][value]...
+    assert_eq!(bytes[3], 1);
+    let mut moved = bytes.to_vec();
+    moved[3] = 2;
+    let result = with_field_key(&key, || {
+        decode::<MovedSecret>(&mut bytes::Bytes::from(moved))
+    });
+    assert!(matches!(result, Err(EncoderError::Decode(_))));
+}
+
+#[test]
+fn test_short_encrypted_field_is_corrupt() {
+    let key = FieldKey::new([5; 32]);
+    // A complete binary value too short to hold the nonce
+    let mut writer = BytesMut::new();
+    Bytes::from_static(&[0; 3]).encode(&mut writer).unwrap();
+    let result = with_field_key(&key, || {
+        decode_encrypted_field::<String>(&mut writer.freeze(), 1)
+    });
+    assert!(matches!(result, Err(EncoderError::Decode(_))), "{result:?}");
+}
@@ -0,0 +1,114 @@
+use bytes::BytesMut;
+use senax_encoder::core::{encode_flat_key_map, TAG_MAP_FLAT_KEYS};
+use senax_encoder::{decode, encode, validate_payload, Decode, Encode, Value};
+use std::collections::{BTreeMap, HashMap};
+
+#[derive(Encode, Decode, Debug, PartialEq)]
+struct Index {
+    #[senax(flat_keys)]
+    by_tenant_and_day: BTreeMap<(u32, u16), u64>,
+    #[senax(flat_keys)]
+    by_pair: HashMap<(String, i8, bool), String>,
+}
+
+#[derive(Encode, Decode, Debug, PartialEq)]
+struct IndexTagged {
+    by_tenant_and_day: BTreeMap<(u32, u16), u64>,
+    by_pair: HashMap<(String, i8, bool), String>,
+}
+
+fn sample() -> Index {
+    Index {
+        by_tenant_and_day: BTreeMap::from([((1, 10), 100), ((1, 11), 110), ((2, 10), 200)]),
+        by_pair: HashMap::from([(("a".to_string(), -1, true), "x".to_string())]),
+    }
+}
+
+#[test]
+fn test_flat_keys_round_trip_and_are_smaller() {
+    let value = sample();
+    let bytes = encode(&value).unwrap();
+    let decoded: Index = decode(&mut bytes.clone()).unwrap();
+    assert_eq!(decoded, value);
+    assert!(validate_payload::<Index>(&bytes).is_valid());
+
+    let tagged = encode(&IndexTagged {
+        by_tenant_and_day: value.by_tenant_and_day.clone(),
+        by_pair: value.by_pair.clone(),
+    })
+    .unwrap();
+    // Two bytes of tuple header per key, one byte of arity per map
+    assert_eq!(tagged.len() - bytes.len(), 2 * 4 - 2);
+}
+
+#[test]
+fn test_flat_key_layout() {
+    let map = BTreeMap::from([((1u8, 2u8), 3u8)]);
+    let mut writer = BytesMut::new();
+    encode_flat_key_map(&map, &mut writer).unwrap();
+    assert_eq!(writer.as_ref(), &[TAG_MAP_FLAT_KEYS, 2, 1, 1, 2, 3]);
+}
+
+#[test]
+fn test_adding_flat_keys_reads_old_payloads() {
+    let old = IndexTagged {
+        by_tenant_and_day: BTreeMap::from([((7, 1), 70)]),
+        by_pair: HashMap::new(),
+    };
+    let bytes = encode(&old).unwrap();
+    let decoded: Index = decode(&mut bytes.clone()).unwrap();
+    assert_eq!(decoded.by_tenant_and_day, old.by_tenant_and_day);
+}
+
+#[test]
+fn test_flat_keys_wrong_arity_is_rejected() {
+    #[derive(Encode)]
+    struct Wide {
+        #[senax(flat_keys, rename = "by_tenant_and_day")]
+        keys: BTreeMap<(u32, u16, u8), u64>,
+    }
+    #[derive(Decode, Debug)]
+    struct Narrow {
+        #[senax(flat_keys)]
+        #[allow(dead_code)]
+        by_tenant_and_day: BTreeMap<(u32, u16), u64>,
+    }
+    let bytes = encode(&Wide {
+        keys: BTreeMap::from([((1, 2, 3), 4)]),
+    })
+    .unwrap();
+    let err = decode::<Narrow>(&mut bytes.clone()).unwrap_err();
+    assert!(err.to_string().contains("2 elements"), "{}", err);
+}
+
+#[test]
+fn test_flat_keys_in_dynamic_values() {
+    let bytes = encode(&sample()).unwrap();
+    let value: Value = decode(&mut bytes.clone()).unwrap();
+    let map = value.field_by_id(senax_encoder::core::field_id_from_name("by_tenant_and_day"));
+    match map {
+        Some(Value::Map(entries)) => {
+            assert_eq!(entries.len(), 3);
+            assert_eq!(
+                entries[0],
+                (
+                    Value::Tuple(vec![Value::UInt(1), Value::UInt(10)]),
+                    Value::UInt(100)
+                )
+            );
+        }
+        other => panic!("unexpected {:?}", other),
+    }
+}
+
+#[test]
+fn test_unknown_flat_key_fields_are_skipped() {
+    #[derive(Decode, Debug, PartialEq)]
+    struct PairsOnly {
+        #[senax(flat_keys)]
+        by_pair: HashMap<(String, i8, bool), String>,
+    }
+    let bytes = encode(&sample()).unwrap();
+    let decoded: PairsOnly = decode(&mut bytes.clone()).unwrap();
+    assert_eq!(decoded.by_pair, sample().by_pair);
+}
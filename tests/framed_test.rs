@@ -0,0 +1,151 @@
+#![cfg(feature = "futures")]
+
+use futures::executor::block_on;
+use futures::io::{AsyncWrite, Cursor};
+use futures::{stream, Sink, SinkExt, StreamExt, TryStreamExt};
+use senax_encoder::framed::{SenaxSink, SenaxStream};
+use senax_encoder::record_log::RecordLogReader;
+use senax_encoder::{Decode, Encode, EncoderError};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+#[derive(Encode, Decode, Debug, PartialEq, Clone)]
+struct Event {
+    id: u64,
+    name: String,
+}
+
+fn event(id: u64) -> Event {
+    Event {
+        id,
+        name: format!("event-{}", id),
+    }
+}
+
+fn encoded(events: &[Event]) -> Vec<u8> {
+    let mut sink = SenaxSink::new(Vec::new());
+    block_on(sink.send_all(&mut stream::iter(events.iter().cloned().map(Ok)))).unwrap();
+    sink.into_inner()
+}
+
+#[test]
+fn test_sink_and_stream_round_trip() {
+    let events: Vec<Event> = (0..100).map(event).collect();
+    let bytes = encoded(&events);
+    let stream = SenaxStream::<_, Event>::new(Cursor::new(bytes));
+    let decoded: Vec<Event> = block_on(stream.try_collect()).unwrap();
+    assert_eq!(decoded, events);
+}
+
+#[test]
+fn test_output_is_a_record_log() {
+    let bytes = encoded(&[event(1), event(2)]);
+    let mut reader = RecordLogReader::new(bytes.as_slice());
+    assert_eq!(reader.read_value::<Event>().unwrap(), Some(event(1)));
+    assert_eq!(reader.read_value::<Event>().unwrap(), Some(event(2)));
+    assert_eq!(reader.read_value::<Event>().unwrap(), None);
+}
+
+/// A writer that accepts at most `chunk` bytes per call and is pending every other call.
+struct SlowWriter {
+    written: Vec<u8>,
+    chunk: usize,
+    ready: bool,
+}
+
+impl AsyncWrite for SlowWriter {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<std::io::Result<usize>> {
+        self.ready = !self.ready;
+        if !self.ready {
+            cx.waker().wake_by_ref();
+            return Poll::Pending;
+        }
+        let n = buf.len().min(self.chunk);
+        self.written.extend_from_slice(&buf[..n]);
+        Poll::Ready(Ok(n))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+}
+
+#[test]
+fn test_sink_applies_backpressure() {
+    let writer = SlowWriter {
+        written: Vec::new(),
+        chunk: 7,
+        ready: false,
+    };
+    let mut sink = SenaxSink::new(writer).with_backpressure_boundary(64);
+    assert_eq!(sink.backpressure_boundary(), 64);
+    for i in 0..50 {
+        block_on(futures::future::poll_fn(|cx| {
+            Pin::new(&mut sink).poll_ready(cx)
+        }))
+        .unwrap();
+        Pin::new(&mut sink).start_send(event(i)).unwrap();
+        // The buffer is written out before it grows past the boundary by more than one frame
+        let frame_len = 4 + senax_encoder::encode(&event(i)).unwrap().len();
+        assert!(
+            sink.buffered_len() < 64 + frame_len,
+            "{}",
+            sink.buffered_len()
+        );
+    }
+    block_on(sink.close()).unwrap();
+    assert_eq!(sink.buffered_len(), 0);
+
+    let bytes = sink.into_inner().written;
+    let stream = SenaxStream::<_, Event>::new(Cursor::new(bytes));
+    let decoded: Vec<Event> = block_on(stream.try_collect()).unwrap();
+    assert_eq!(decoded, (0..50).map(event).collect::<Vec<_>>());
+}
+
+#[test]
+fn test_truncated_stream_reports_unexpected_eof() {
+    let mut bytes = encoded(&[event(1), event(2)]);
+    bytes.truncate(bytes.len() - 3);
+    let mut stream = SenaxStream::<_, Event>::new(Cursor::new(bytes));
+    assert_eq!(block_on(stream.next()).unwrap().unwrap(), event(1));
+    assert!(matches!(
+        block_on(stream.next()),
+        Some(Err(EncoderError::UnexpectedEof { .. }))
+    ));
+    assert!(block_on(stream.next()).is_none());
+}
+
+#[test]
+fn test_bad_frame_does_not_end_the_stream() {
+    let mut bytes = Vec::new();
+    bytes.extend_from_slice(&3u32.to_le_bytes());
+    bytes.extend_from_slice(&[1, 2, 3]);
+    bytes.extend_from_slice(&encoded(&[event(7)]));
+    let mut stream = SenaxStream::<_, Event>::new(Cursor::new(bytes));
+    assert!(block_on(stream.next()).unwrap().is_err());
+    assert_eq!(block_on(stream.next()).unwrap().unwrap(), event(7));
+    assert!(block_on(stream.next()).is_none());
+}
+
+#[test]
+fn test_oversized_frame_ends_the_stream() {
+    let bytes = encoded(&[event(1)]);
+    let mut stream = SenaxStream::<_, Event>::new(Cursor::new(bytes)).with_max_frame_len(4);
+    let err = block_on(stream.next()).unwrap().unwrap_err();
+    assert!(err.to_string().contains("exceeds the limit"), "{}", err);
+    assert!(block_on(stream.next()).is_none());
+}
+
+#[test]
+fn test_empty_reader_is_an_empty_stream() {
+    let mut stream = SenaxStream::<_, Event>::new(Cursor::new(Vec::new()));
+    assert!(block_on(stream.next()).is_none());
+}
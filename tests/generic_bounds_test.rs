@@ -0,0 +1,131 @@
+use senax_encoder::{decode, encode, pack, unpack, Decode, Encode, Pack, Unpack};
+use std::collections::BTreeMap;
+use std::marker::PhantomData;
+
+#[derive(Encode, Decode, Pack, Unpack, Debug, PartialEq)]
+struct Wrapper<T> {
+    inner: T,
+}
+
+#[derive(Encode, Decode, Pack, Unpack, Debug, PartialEq)]
+struct Page<K, V>
+where
+    K: Ord,
+{
+    entries: BTreeMap<K, Vec<V>>,
+    cursor: Option<K>,
+}
+
+#[derive(Encode, Decode, Pack, Unpack, Debug, PartialEq)]
+enum Either<L, R> {
+    Left(L),
+    Right { value: R },
+}
+
+/// Implements none of the codec traits.
+#[derive(Debug, PartialEq, Default)]
+struct Opaque;
+
+#[derive(Encode, Decode, Debug, PartialEq)]
+struct Tagged<T> {
+    id: u64,
+    // Only used in a skipped field, so `T` gets no bounds
+    #[senax(skip)]
+    marker: PhantomData<T>,
+}
+
+#[derive(Encode, Decode, Debug, PartialEq)]
+#[senax(bound = "T: senax_encoder::Encoder + senax_encoder::Decoder + Default + 'static")]
+struct WithDefault<T> {
+    name: String,
+    #[senax(default)]
+    value: T,
+}
+
+#[test]
+fn test_generic_struct() {
+    for value in [
+        Wrapper {
+            inner: "text".to_string(),
+        },
+        Wrapper {
+            inner: String::new(),
+        },
+    ] {
+        assert_eq!(
+            decode::<Wrapper<String>>(&mut encode(&value).unwrap()).unwrap(),
+            value
+        );
+        assert_eq!(
+            unpack::<Wrapper<String>>(&mut pack(&value).unwrap()).unwrap(),
+            value
+        );
+    }
+
+    let nested = Wrapper {
+        inner: Wrapper {
+            inner: vec![1u32, 2],
+        },
+    };
+    let mut bytes = encode(&nested).unwrap();
+    assert_eq!(
+        decode::<Wrapper<Wrapper<Vec<u32>>>>(&mut bytes).unwrap(),
+        nested
+    );
+
+    let page = Page {
+        entries: [("a".to_string(), vec![1i64, -1])].into(),
+        cursor: Some("a".to_string()),
+    };
+    let mut bytes = encode(&page).unwrap();
+    assert_eq!(decode::<Page<String, i64>>(&mut bytes).unwrap(), page);
+}
+
+#[test]
+fn test_generic_enum() {
+    for value in [
+        Either::<u8, String>::Left(3),
+        Either::Right {
+            value: "r".to_string(),
+        },
+    ] {
+        assert_eq!(
+            decode::<Either<u8, String>>(&mut encode(&value).unwrap()).unwrap(),
+            value
+        );
+        assert_eq!(
+            unpack::<Either<u8, String>>(&mut pack(&value).unwrap()).unwrap(),
+            value
+        );
+    }
+}
+
+#[test]
+fn test_unused_parameters_are_not_bounded() {
+    let tagged = Tagged::<Opaque> {
+        id: 7,
+        marker: PhantomData,
+    };
+    let mut bytes = encode(&tagged).unwrap();
+    assert_eq!(decode::<Tagged<Opaque>>(&mut bytes).unwrap(), tagged);
+}
+
+#[test]
+fn test_custom_bound() {
+    #[derive(Encode, Decode)]
+    struct NameOnly {
+        name: String,
+    }
+
+    let mut bytes = encode(&NameOnly {
+        name: "n".to_string(),
+    })
+    .unwrap();
+    assert_eq!(
+        decode::<WithDefault<u32>>(&mut bytes).unwrap(),
+        WithDefault {
+            name: "n".to_string(),
+            value: 0,
+        }
+    );
+}
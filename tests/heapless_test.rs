@@ -0,0 +1,75 @@
+#![cfg(feature = "heapless")]
+
+use bytes::BytesMut;
+use senax_encoder::{
+    Decode, Decoder, Encode, Encoder, EncoderError, MaxEncodedSize, Pack, Packer, Unpack, Unpacker,
+};
+
+#[derive(Encode, Decode, Pack, Unpack, Debug, PartialEq)]
+#[senax(max_size)]
+struct Telemetry {
+    device: heapless::String<16>,
+    samples: heapless::Vec<u16, 8>,
+}
+
+fn telemetry() -> Telemetry {
+    let mut samples = heapless::Vec::new();
+    samples.extend_from_slice(&[1, 200, 40_000]).unwrap();
+    Telemetry {
+        device: heapless::String::try_from("sensor-7").unwrap(),
+        samples,
+    }
+}
+
+#[test]
+fn test_heapless_roundtrip() {
+    let value = telemetry();
+    let mut buf = BytesMut::new();
+    value.encode(&mut buf).unwrap();
+    assert!(buf.len() <= Telemetry::MAX_ENCODED_SIZE);
+    assert_eq!(Telemetry::decode(&mut buf.freeze()).unwrap(), value);
+
+    let mut buf = BytesMut::new();
+    value.pack(&mut buf).unwrap();
+    assert_eq!(Telemetry::unpack(&mut buf.freeze()).unwrap(), value);
+}
+
+#[test]
+fn test_heapless_is_wire_compatible_with_std() {
+    let mut buf = BytesMut::new();
+    vec![1u16, 2, 3].encode(&mut buf).unwrap();
+    let vec = heapless::Vec::<u16, 4>::decode(&mut buf.freeze()).unwrap();
+    assert_eq!(vec.as_slice(), &[1, 2, 3]);
+
+    let mut buf = BytesMut::new();
+    heapless::String::<8>::try_from("hi")
+        .unwrap()
+        .encode(&mut buf)
+        .unwrap();
+    assert_eq!(String::decode(&mut buf.freeze()).unwrap(), "hi");
+}
+
+#[test]
+fn test_heapless_capacity_overflow() {
+    let mut buf = BytesMut::new();
+    vec![0u8; 5].encode(&mut buf).unwrap();
+    let result = heapless::Vec::<u8, 4>::decode(&mut buf.freeze());
+    assert!(matches!(
+        result,
+        Err(EncoderError::CapacityExceeded {
+            len: 5,
+            capacity: 4
+        })
+    ));
+
+    let mut buf = BytesMut::new();
+    "much too long".to_string().encode(&mut buf).unwrap();
+    let result = heapless::String::<4>::decode(&mut buf.freeze());
+    assert!(matches!(
+        result,
+        Err(EncoderError::CapacityExceeded {
+            len: 13,
+            capacity: 4
+        })
+    ));
+}
@@ -0,0 +1,152 @@
+use senax_encoder::hello::{Hello, WireMode, FORMAT_VERSION, HELLO_MAGIC};
+use senax_encoder::schema::HasSchema;
+use senax_encoder::{decode_with_config, encode, Decode, Encode};
+use std::collections::HashMap;
+
+mod v1 {
+    use super::*;
+
+    #[derive(Encode, Decode, Debug, PartialEq)]
+    #[senax(schema)]
+    pub struct Order {
+        pub id: u64,
+        /// Number of items
+        pub quantity: u32,
+    }
+}
+
+mod v2 {
+    use super::*;
+
+    #[derive(Encode, Decode, Debug, PartialEq)]
+    #[senax(schema)]
+    pub struct Order {
+        pub id: u64,
+        pub quantity: u32,
+        pub note: Option<String>,
+    }
+}
+
+#[derive(Encode, Decode, Debug, PartialEq)]
+#[senax(schema)]
+struct Cancel {
+    id: u64,
+}
+
+#[test]
+fn test_frame_roundtrip() {
+    let hello = Hello::new().with_schema::<v1::Order>("Order");
+    assert_eq!(hello.format_version, FORMAT_VERSION);
+    assert_eq!(hello.crate_version(), env!("CARGO_PKG_VERSION"));
+
+    let frame = hello.encode_frame().unwrap();
+    assert_eq!(&frame[..2], &HELLO_MAGIC.to_le_bytes());
+    assert_eq!(Hello::decode_frame(&mut frame.clone()).unwrap(), hello);
+
+    // An application message is not a hello
+    let mut message = encode(&1u32).unwrap();
+    assert!(Hello::decode_frame(&mut message).is_err());
+}
+
+#[test]
+fn test_negotiation_is_symmetric() {
+    let cases = [
+        (
+            WireMode::ALL.to_vec(),
+            WireMode::ALL.to_vec(),
+            WireMode::Canonical,
+        ),
+        (
+            vec![WireMode::Lenient, WireMode::Strict],
+            WireMode::ALL.to_vec(),
+            WireMode::Strict,
+        ),
+        (
+            vec![WireMode::Lenient],
+            vec![WireMode::Strict, WireMode::Lenient],
+            WireMode::Lenient,
+        ),
+    ];
+    for (ours, theirs, expected) in cases {
+        let a = Hello::new().with_modes(&ours);
+        let b = Hello::new().with_modes(&theirs);
+        let ab = a.negotiate(&b).unwrap();
+        assert_eq!(ab.mode, expected);
+        assert_eq!(ab, b.negotiate(&a).unwrap());
+    }
+
+    let strict = Hello::new().with_modes(&[WireMode::Strict]);
+    let lenient = Hello::new().with_modes(&[WireMode::Lenient]);
+    assert!(strict.negotiate(&lenient).is_err());
+}
+
+#[test]
+fn test_unknown_modes_and_versions() {
+    let mut newer = Hello::new().with_modes(&[WireMode::Strict]);
+    newer.modes.insert(0, "compressed".to_string());
+    let agreed = Hello::new().negotiate(&newer).unwrap();
+    assert_eq!(agreed.mode, WireMode::Strict);
+    assert_eq!(WireMode::from_name("compressed"), None);
+
+    let mut future = Hello::new();
+    future.format_version = FORMAT_VERSION + 1;
+    assert!(Hello::new().negotiate(&future).is_err());
+}
+
+#[test]
+fn test_schema_fingerprints() {
+    let old = Hello::new()
+        .with_schema::<v1::Order>("Order")
+        .with_schema::<Cancel>("Cancel");
+    let same = Hello::new()
+        .with_schema::<v1::Order>("Order")
+        .with_schema::<Cancel>("Cancel");
+    assert!(old.negotiate(&same).unwrap().schemas_match());
+
+    // Schemas listed by only one side are not compared
+    let new = Hello::new().with_schema::<v2::Order>("Order");
+    let agreed = old.negotiate(&new).unwrap();
+    assert_eq!(agreed.schema_mismatches, ["Order"]);
+    assert!(!agreed.schemas_match());
+
+    // Doc comments do not change the fingerprint
+    let mut undocumented = v1::Order::schema();
+    let senax_encoder::schema::TypeSchema::Struct(schema) = &mut undocumented else {
+        unreachable!()
+    };
+    assert!(schema.fields[1].doc.is_some());
+    schema.fields[1].doc = None;
+    assert_eq!(
+        undocumented.fingerprint(),
+        v1::Order::schema().fingerprint()
+    );
+}
+
+#[test]
+fn test_negotiated_configs() {
+    let agreed = Hello::new().negotiate(&Hello::new()).unwrap();
+    assert!(agreed.encode_config().sort_maps);
+    assert!(agreed.decode_config().deny_unknown_fields);
+    assert!(agreed.decode_config().strict_sets);
+
+    // A strict peer rejects the field a newer writer added
+    let mut bytes = encode(&v2::Order {
+        id: 1,
+        quantity: 2,
+        note: Some("gift".to_string()),
+    })
+    .unwrap();
+    let result = decode_with_config::<v1::Order>(&mut bytes, &agreed.decode_config());
+    assert!(result.is_err());
+
+    let lenient = WireMode::Lenient;
+    assert!(lenient.decode_config().lenient_tuples);
+    assert!(!lenient.encode_config().sort_maps);
+
+    // Canonical encoding is deterministic for hash maps
+    let map: HashMap<u32, u32> = (0..32).map(|i| (i, i)).collect();
+    let first = senax_encoder::encode_with_config(&map, &agreed.encode_config()).unwrap();
+    let copy: HashMap<u32, u32> = map.clone().into_iter().collect();
+    let second = senax_encoder::encode_with_config(&copy, &agreed.encode_config()).unwrap();
+    assert_eq!(first, second);
+}
@@ -0,0 +1,129 @@
+use bytes::BytesMut;
+use senax_encoder::core::{TAG_MAP, TAG_MAP_ID_KEYS};
+use senax_encoder::{
+    decode, encode, pack, query, unpack, validate_payload, Decode, Decoder, Encode, Encoder, IdMap,
+    Pack, QueryMatch, Unpack, Value,
+};
+use std::collections::{BTreeMap, HashMap};
+
+#[derive(Encode, Decode, Pack, Unpack, Debug, PartialEq, Clone)]
+struct Entity {
+    x: i32,
+    y: i32,
+}
+
+#[derive(Encode, Decode, Pack, Unpack, Debug, PartialEq)]
+struct World {
+    tick: u64,
+    entities: IdMap<Entity>,
+}
+
+#[derive(Encode, Decode, Debug, PartialEq)]
+struct WorldV1 {
+    tick: u64,
+    entities: HashMap<u64, Entity>,
+}
+
+fn world() -> World {
+    World {
+        tick: 9,
+        entities: [
+            (3, Entity { x: 1, y: 2 }),
+            (250, Entity { x: -1, y: 0 }),
+            (1 << 40, Entity { x: 5, y: 5 }),
+        ]
+        .into_iter()
+        .collect(),
+    }
+}
+
+#[test]
+fn test_keys_use_field_id_encoding() {
+    let map = IdMap::from(BTreeMap::from([(7u64, true), (300, false)]));
+    let mut writer = BytesMut::new();
+    map.encode(&mut writer).unwrap();
+    let mut expected = vec![TAG_MAP_ID_KEYS, 2, 7, 1, 255];
+    expected.extend_from_slice(&300u64.to_le_bytes());
+    expected.push(0);
+    assert_eq!(writer.as_ref(), expected.as_slice());
+
+    // A tagged u64 key of 200 takes two bytes; as an ID it takes one
+    let small = IdMap::from(BTreeMap::from([(200u64, 0u8)]));
+    let plain = BTreeMap::from([(200u64, 0u8)]);
+    assert!(encode(&small).unwrap().len() < encode(&plain).unwrap().len());
+}
+
+#[test]
+fn test_round_trip_in_structs() {
+    let value = world();
+    let bytes = encode(&value).unwrap();
+    assert_eq!(decode::<World>(&mut bytes.clone()).unwrap(), value);
+    assert!(validate_payload::<World>(&bytes).is_valid());
+
+    let packed = pack(&value).unwrap();
+    assert_eq!(unpack::<World>(&mut packed.clone()).unwrap(), value);
+}
+
+#[test]
+fn test_empty_map_is_default() {
+    let map: IdMap<u32> = IdMap::default();
+    assert!(map.is_default());
+    let mut writer = BytesMut::new();
+    map.encode(&mut writer).unwrap();
+    assert!(IdMap::<u32>::decode(&mut writer.freeze())
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn test_decodes_plain_integer_keyed_maps() {
+    let old = WorldV1 {
+        tick: 9,
+        entities: world().entities.into_inner().into_iter().collect(),
+    };
+    let bytes = encode(&old).unwrap();
+    assert_eq!(decode::<World>(&mut bytes.clone()).unwrap(), world());
+
+    let mut writer = BytesMut::new();
+    BTreeMap::from([(1u64, 2u32)]).encode(&mut writer).unwrap();
+    assert_eq!(writer[0], TAG_MAP);
+    let map = IdMap::<u32>::decode(&mut writer.freeze()).unwrap();
+    assert_eq!(map.get(&1), Some(&2));
+}
+
+#[test]
+fn test_dynamic_values_and_queries() {
+    let bytes = encode(&world()).unwrap();
+    let value: Value = decode(&mut bytes.clone()).unwrap();
+    let entities = value
+        .field_by_id(senax_encoder::core::field_id_from_name("entities"))
+        .unwrap();
+    match entities {
+        Value::Map(entries) => {
+            let keys: Vec<_> = entries.iter().map(|(k, _)| k.clone()).collect();
+            assert_eq!(
+                keys,
+                vec![Value::UInt(3), Value::UInt(250), Value::UInt(1 << 40)]
+            );
+        }
+        other => panic!("unexpected {:?}", other),
+    }
+
+    assert_eq!(
+        query(&bytes, "entities.250.x").unwrap(),
+        Some(QueryMatch::Scalar(Value::Int(-1)))
+    );
+    assert_eq!(
+        query(&bytes, &format!("entities.{}.y", 1u64 << 40)).unwrap(),
+        Some(QueryMatch::Scalar(Value::UInt(5)))
+    );
+    assert_eq!(query(&bytes, "entities.4").unwrap(), None);
+}
+
+#[test]
+fn test_truncated_map_is_rejected() {
+    let bytes = encode(&world()).unwrap();
+    let truncated = bytes.slice(..bytes.len() - 3);
+    assert!(decode::<World>(&mut truncated.clone()).is_err());
+    assert!(!validate_payload::<World>(&truncated).is_valid());
+}
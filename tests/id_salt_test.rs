@@ -0,0 +1,81 @@
+use senax_encoder::core::{field_id_from_name, field_id_from_salted_name};
+use senax_encoder::wire_info::WireInfo;
+use senax_encoder::{decode, encode, pack, unpack, Decode, Encode, Pack, Unpack};
+
+/// The original meaning: `amount` in whole currency units.
+#[derive(Encode, Decode, Pack, Unpack, Debug, PartialEq)]
+struct Payment {
+    id: u64,
+    amount: i64,
+}
+
+/// Same shape, but `amount` is now in cents, so old payloads must not decode.
+#[derive(Encode, Decode, Pack, Unpack, Debug, PartialEq)]
+#[senax(id_salt = "v2", wire_info)]
+struct PaymentV2 {
+    id: u64,
+    amount: i64,
+    #[senax(id = 7)]
+    pinned: u8,
+    #[senax(rename = "note")]
+    memo: Option<String>,
+}
+
+#[derive(Encode, Decode, Debug, PartialEq)]
+enum Command {
+    Start { delay: u32 },
+    Stop,
+}
+
+#[derive(Encode, Decode, Debug, PartialEq)]
+#[senax(id_salt = "v2", wire_info)]
+enum CommandV2 {
+    Start { delay: u32 },
+    Stop,
+}
+
+#[test]
+fn test_salted_type_roundtrips() {
+    let payment = PaymentV2 {
+        id: 1,
+        amount: 1250,
+        pinned: 3,
+        memo: Some("rent".to_string()),
+    };
+    let decoded: PaymentV2 = decode(&mut encode(&payment).unwrap()).unwrap();
+    assert_eq!(decoded, payment);
+    let unpacked: PaymentV2 = unpack(&mut pack(&payment).unwrap()).unwrap();
+    assert_eq!(unpacked, payment);
+
+    let decoded: CommandV2 = decode(&mut encode(&CommandV2::Start { delay: 5 }).unwrap()).unwrap();
+    assert_eq!(decoded, CommandV2::Start { delay: 5 });
+}
+
+#[test]
+fn test_salt_changes_hashed_ids() {
+    let fields = PaymentV2::wire_fields();
+    assert_eq!(fields[0].id, field_id_from_salted_name("v2", "id"));
+    assert_eq!(fields[1].id, field_id_from_salted_name("v2", "amount"));
+    assert_ne!(fields[1].id, field_id_from_name("amount"));
+    // Explicit IDs are kept; renamed fields hash the new name with the salt
+    assert_eq!(fields[2].id, 7);
+    assert_eq!(fields[3].id, field_id_from_salted_name("v2", "note"));
+
+    let variants = CommandV2::wire_variants();
+    assert_eq!(variants[0].id, field_id_from_salted_name("v2", "Start"));
+    assert_eq!(
+        variants[0].fields[0].id,
+        field_id_from_salted_name("v2", "delay")
+    );
+    assert_eq!(variants[1].id, field_id_from_salted_name("v2", "Stop"));
+}
+
+#[test]
+fn test_salt_breaks_compatibility() {
+    let old = Payment { id: 1, amount: 12 };
+    assert!(decode::<PaymentV2>(&mut encode(&old).unwrap()).is_err());
+    assert!(unpack::<PaymentV2>(&mut pack(&old).unwrap()).is_err());
+
+    assert!(decode::<CommandV2>(&mut encode(&Command::Stop).unwrap()).is_err());
+    assert!(decode::<Command>(&mut encode(&CommandV2::Stop).unwrap()).is_err());
+}
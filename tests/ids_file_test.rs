@@ -0,0 +1,108 @@
+use senax_encoder::core::field_id_from_name;
+use senax_encoder::wire_info::WireInfo;
+use senax_encoder::{decode, encode, pack, unpack, Decode, Encode, Pack, Unpack};
+
+#[derive(Encode, Decode, Debug, PartialEq)]
+#[senax(ids_file = "tests/senax-ids.toml", wire_info)]
+struct Order {
+    id: u64,
+    customer: String,
+    #[senax(id = 3)]
+    total: u32,
+    note: Option<String>,
+    email: String,
+}
+
+/// The same type before its IDs were pinned.
+#[derive(Encode, Decode, Debug, PartialEq)]
+struct LegacyOrder {
+    #[senax(id = 1)]
+    id: u64,
+    #[senax(id = 2)]
+    customer: String,
+    #[senax(id = 3)]
+    total: u32,
+    #[senax(id = 4)]
+    note: Option<String>,
+    email: String,
+}
+
+#[derive(Encode, Decode, Pack, Unpack, Debug, PartialEq)]
+#[senax(ids_file = "tests/senax-ids.toml", wire_info)]
+enum Status {
+    Pending,
+    Shipped { carrier: String, tracking: u64 },
+    Cancelled(String),
+}
+
+fn order() -> Order {
+    Order {
+        id: 7,
+        customer: "alice".to_string(),
+        total: 1200,
+        note: Some("leave at door".to_string()),
+        email: "alice@example.com".to_string(),
+    }
+}
+
+#[test]
+fn test_fields_use_pinned_ids() {
+    let ids: Vec<_> = Order::wire_fields()
+        .iter()
+        .map(|field| (field.name, field.id))
+        .collect();
+    assert_eq!(
+        ids,
+        vec![
+            ("id", 1),
+            ("customer", 2),
+            ("total", 3),
+            ("note", 4),
+            ("email", field_id_from_name("email")),
+        ]
+    );
+
+    let mut bytes = encode(&order()).unwrap();
+    assert_eq!(decode::<Order>(&mut bytes.clone()).unwrap(), order());
+    let legacy = decode::<LegacyOrder>(&mut bytes).unwrap();
+    assert_eq!(legacy.email, "alice@example.com");
+
+    let mut bytes = encode(&legacy).unwrap();
+    assert_eq!(decode::<Order>(&mut bytes).unwrap(), order());
+}
+
+#[test]
+fn test_variants_use_pinned_ordinals() {
+    let variants: Vec<_> = Status::wire_variants()
+        .iter()
+        .map(|variant| {
+            let fields: Vec<_> = variant.fields.iter().map(|f| (f.name, f.id)).collect();
+            (variant.name, variant.id, fields)
+        })
+        .collect();
+    assert_eq!(
+        variants,
+        vec![
+            ("Pending", 1, vec![]),
+            ("Shipped", 2, vec![("carrier", 1), ("tracking", 2)]),
+            ("Cancelled", 3, vec![("0", 0)]),
+        ]
+    );
+
+    // The magic number, the tag and a one-byte variant ID
+    assert_eq!(encode(&Status::Pending).unwrap().len(), 2 + 2);
+
+    for status in [
+        Status::Pending,
+        Status::Shipped {
+            carrier: "UPS".to_string(),
+            tracking: 42,
+        },
+        Status::Cancelled("out of stock".to_string()),
+    ] {
+        let mut bytes = encode(&status).unwrap();
+        assert_eq!(decode::<Status>(&mut bytes).unwrap(), status);
+        let mut bytes = pack(&status).unwrap();
+        assert_eq!(unpack::<Status>(&mut bytes).unwrap(), status);
+    }
+}
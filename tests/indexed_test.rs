@@ -0,0 +1,108 @@
+use bytes::Bytes;
+use senax_encoder::indexed::{IndexedReader, IndexedWriter, INDEXED_FOOTER_MAGIC};
+use senax_encoder::{Decode, Encode, EncoderError};
+
+#[derive(Encode, Decode, Debug, PartialEq, Clone)]
+struct Trade {
+    sequence: u64,
+    symbol: String,
+    quantity: i64,
+}
+
+fn trade(sequence: u64) -> Trade {
+    Trade {
+        sequence,
+        symbol: format!("SYM{}", sequence % 7),
+        quantity: sequence as i64 - 50,
+    }
+}
+
+fn container(count: u64) -> Bytes {
+    let mut writer = IndexedWriter::new(Vec::new());
+    for sequence in 0..count {
+        let index = writer.append_value(&trade(sequence * 2)).unwrap();
+        assert_eq!(index, sequence as usize);
+    }
+    assert_eq!(writer.len(), count as usize);
+    writer.finish().unwrap().into()
+}
+
+#[test]
+fn test_indexed_random_access() {
+    let reader = IndexedReader::new(container(100)).unwrap();
+    assert_eq!(reader.len(), 100);
+    for index in [0, 37, 99] {
+        assert_eq!(reader.get::<Trade>(index).unwrap(), trade(index as u64 * 2));
+    }
+    assert!(reader.record(100).is_err());
+
+    let all: Vec<Trade> = reader.iter().collect::<Result<_, _>>().unwrap();
+    assert_eq!(all.len(), 100);
+    assert_eq!(all[42], trade(84));
+}
+
+#[test]
+fn test_indexed_binary_search() {
+    let reader = IndexedReader::new(container(100)).unwrap();
+    let by_sequence = |t: &Trade| t.sequence;
+    assert_eq!(reader.binary_search_by_key(&0, by_sequence).unwrap(), Ok(0));
+    assert_eq!(
+        reader.binary_search_by_key(&198, by_sequence).unwrap(),
+        Ok(99)
+    );
+    assert_eq!(
+        reader.binary_search_by_key(&51, by_sequence).unwrap(),
+        Err(26)
+    );
+    assert_eq!(
+        reader.binary_search_by_key(&500, by_sequence).unwrap(),
+        Err(100)
+    );
+}
+
+#[test]
+fn test_indexed_empty_and_raw_records() {
+    let reader = IndexedReader::new(container(0)).unwrap();
+    assert!(reader.is_empty());
+    assert_eq!(
+        reader
+            .binary_search_by_key(&1, |t: &Trade| t.sequence)
+            .unwrap(),
+        Err(0)
+    );
+
+    let mut writer = IndexedWriter::new(Vec::new());
+    writer.append(b"first").unwrap();
+    writer.append(b"").unwrap();
+    writer.append(b"third").unwrap();
+    let reader = IndexedReader::new(writer.finish().unwrap().into()).unwrap();
+    assert_eq!(reader.record(0).unwrap(), Bytes::from_static(b"first"));
+    assert!(reader.record(1).unwrap().is_empty());
+    assert_eq!(reader.record(2).unwrap(), Bytes::from_static(b"third"));
+}
+
+#[test]
+fn test_indexed_rejects_corrupt_footer() {
+    let data = container(3);
+
+    let truncated = data.slice(..data.len() - 1);
+    assert!(IndexedReader::new(truncated).is_err());
+
+    assert!(matches!(
+        IndexedReader::new(Bytes::from_static(&[0; 4])),
+        Err(EncoderError::UnexpectedEof { .. })
+    ));
+
+    // A record count larger than the container
+    let mut bogus = vec![0u8; 4];
+    bogus.extend_from_slice(&1000u64.to_le_bytes());
+    bogus.extend_from_slice(&INDEXED_FOOTER_MAGIC.to_le_bytes());
+    assert!(IndexedReader::new(bogus.into()).is_err());
+
+    // An offset past the end of the records
+    let mut bogus = b"abc".to_vec();
+    bogus.extend_from_slice(&10u64.to_le_bytes());
+    bogus.extend_from_slice(&1u64.to_le_bytes());
+    bogus.extend_from_slice(&INDEXED_FOOTER_MAGIC.to_le_bytes());
+    assert!(IndexedReader::new(bogus.into()).is_err());
+}
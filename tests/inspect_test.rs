@@ -0,0 +1,163 @@
+use senax_encoder::core::{field_id_from_name, TAG_STRUCT_NAMED};
+use senax_encoder::self_describing::{encode_named, encode_self_describing};
+use senax_encoder::{encode, inspect, inspect_tree, Encode, Encoder, Value};
+use std::collections::BTreeMap;
+
+#[derive(Encode)]
+enum Shape {
+    Empty,
+    Circle(f64),
+    Rect { width: u32, height: u32 },
+}
+
+#[derive(Encode)]
+struct Drawing {
+    #[senax(id = 1)]
+    id: u64,
+    #[senax(id = 2)]
+    title: String,
+    #[senax(id = 3)]
+    shapes: Vec<Shape>,
+    #[senax(id = 4)]
+    layers: BTreeMap<String, i32>,
+    #[senax(id = 5)]
+    note: Option<String>,
+}
+
+fn drawing() -> Drawing {
+    Drawing {
+        id: 300,
+        title: "plan".to_string(),
+        shapes: vec![
+            Shape::Empty,
+            Shape::Circle(1.5),
+            Shape::Rect {
+                width: 3,
+                height: 4,
+            },
+        ],
+        layers: [("base".to_string(), -1)].into(),
+        note: Some("draft".to_string()),
+    }
+}
+
+#[test]
+fn test_dump() {
+    let text = inspect(&encode(&drawing()).unwrap());
+    let expected = r#"000000  5a a5                          encode payload
+000002  b7                               struct
+000003  01 83 ac                           id 1: uint = UInt(300)
+000006  02 8f 70 6c 61 6e                  id 2: string (4) = String("plan")
+00000c  03 bf                              id 3: list (3)
+00000e  b9 ff 74 58 7d 1c 87 c5 ..           [0] enum id 11249646103307311220
+000018  bb ff f6 ee 4d 39 fe 38 ..           [1] enum id 14089855573739564790 (1)
+000023  90 31 2e 35 65 30                      [0] string (5) = String("1.5e0")
+000029  ba ff ab 0b 02 44 16 96 ..           [2] enum id 4434803280450816939
+000033  ff 96 47 7e 7b f2 4c 6f ..             id 8245894047076337558: uint = UInt(3)
+00003d  ff f4 80 26 3d 9a 32 7c ..             id 12717095085819724020: uint = UInt(4)
+000047  00                                   end
+000048  04 c4 01                           id 4: map (1)
+00004b  8f 62 61 73 65                       key: string (4) = String("base")
+000050  88 00                                value: int = Int(-1)
+000052  05 90 64 72 61 66 74               id 5: string (5) = String("draft")
+000059  00                               end
+"#;
+    assert_eq!(text, expected);
+    // Variant IDs are the hashed variant names
+    let rect = format!("[2] enum id {}\n", field_id_from_name("Rect"));
+    assert!(text.contains(&rect));
+}
+
+#[test]
+fn test_tree() {
+    let bytes = encode(&drawing()).unwrap();
+    let root = inspect_tree(&bytes);
+    assert!(root.is_complete());
+    assert_eq!((root.offset, root.len), (0, bytes.len()));
+
+    let drawing = &root.children[0];
+    assert_eq!(drawing.tag, Some(TAG_STRUCT_NAMED));
+    assert_eq!(drawing.children.len(), 5);
+    let title = &drawing.children[1];
+    assert_eq!(title.field.as_ref().unwrap().id, 2);
+    assert_eq!(title.count, Some(4));
+    assert_eq!(title.value, Some(Value::String("plan".to_string())));
+    // The field ID is part of the node
+    assert_eq!(
+        &bytes[title.offset..title.offset + title.len],
+        &title.header[..]
+    );
+    assert_eq!(title.header[0], 2);
+
+    let shapes = &drawing.children[2];
+    assert_eq!(shapes.count, Some(3));
+    let rect = &shapes.children[2];
+    assert_eq!(rect.kind, "enum");
+    assert_eq!(rect.children[1].value, Some(Value::UInt(4)));
+
+    // Children are contiguous and end where the parent does, before the terminator
+    let last = drawing.children.last().unwrap();
+    assert_eq!(last.offset + last.len, drawing.offset + drawing.len - 1);
+}
+
+#[test]
+fn test_names_are_shown() {
+    for bytes in [
+        encode_self_describing(&drawing()).unwrap(),
+        encode_named(&drawing()).unwrap(),
+    ] {
+        let root = inspect_tree(&bytes);
+        assert!(root.is_complete(), "{}", root);
+        let text = root.to_string();
+        assert!(text.contains("`height`: uint = UInt(4)"), "{}", text);
+        assert!(text.contains("[2] enum `Rect`\n"), "{}", text);
+    }
+    // IDs not derived from the name are shown next to it
+    let text = inspect(&encode_self_describing(&drawing()).unwrap());
+    assert!(text.contains("`title` (id 2): string (4)"), "{}", text);
+    assert!(inspect(&encode_self_describing(&drawing()).unwrap()).contains("name table ("));
+}
+
+#[test]
+fn test_bare_values() {
+    let mut writer = bytes::BytesMut::new();
+    (7u8, "x", vec![None, Some(-2i64)])
+        .encode(&mut writer)
+        .unwrap();
+    let root = inspect_tree(&writer);
+    assert!(root.is_complete());
+    assert_eq!(root.kind, "tuple");
+    assert_eq!(root.count, Some(3));
+    assert_eq!(root.children[2].children[0].kind, "none");
+    assert_eq!(
+        root.children[2].children[1].children[0].value,
+        Some(Value::Int(-2))
+    );
+}
+
+#[test]
+fn test_malformed_payloads() {
+    let bytes = encode(&drawing()).unwrap();
+
+    // Truncated: everything up to the cut is still shown
+    let root = inspect_tree(&bytes[..10]);
+    let failed = root.first_error().unwrap();
+    assert_eq!(failed.kind, "string");
+    assert!(root.children[0].children[0].error.is_none());
+    let text = root.to_string();
+    assert!(text.contains("id 1: uint = UInt(300)"), "{}", text);
+    assert!(text.lines().last().unwrap().contains("error: "), "{}", text);
+
+    // Trailing bytes
+    let mut extended = bytes.to_vec();
+    extended.push(0);
+    let root = inspect_tree(&extended);
+    assert_eq!(root.error.as_deref(), Some("1 trailing bytes"));
+
+    // Unknown tags
+    let root = inspect_tree(&[0x5a, 0xa5, 0xb7, 0x01, 0xff]);
+    assert_eq!(root.first_error().unwrap().offset, 3);
+    assert!(!root.is_complete());
+
+    assert!(!inspect_tree(&[]).is_complete());
+}
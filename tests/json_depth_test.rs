@@ -0,0 +1,128 @@
+#![cfg(feature = "serde_json")]
+
+use bytes::{BufMut, Bytes, BytesMut};
+use senax_encoder::core::{TAG_JSON_ARRAY, TAG_JSON_NULL, TAG_JSON_OBJECT};
+use senax_encoder::{
+    decode, encode, with_decode_config, DecodeConfig, Decoder, Encoder, EncoderError,
+    DEFAULT_MAX_DEPTH,
+};
+use serde_json::{json, Value};
+
+/// A payload of `depth` single-element arrays around a null.
+fn nested_arrays(depth: usize) -> Bytes {
+    let mut writer = BytesMut::new();
+    for _ in 0..depth {
+        writer.put_u8(TAG_JSON_ARRAY);
+        1usize.encode(&mut writer).unwrap();
+    }
+    writer.put_u8(TAG_JSON_NULL);
+    writer.freeze()
+}
+
+fn nested_value(depth: usize) -> Value {
+    let mut value = Value::Null;
+    for i in 0..depth {
+        value = if i % 2 == 0 {
+            Value::Array(vec![value])
+        } else {
+            json!({ "child": value })
+        };
+    }
+    value
+}
+
+#[test]
+fn test_hostile_nesting_fails_without_overflowing_the_stack() {
+    let err = Value::decode(&mut nested_arrays(1_000_000)).unwrap_err();
+    assert!(matches!(
+        err,
+        EncoderError::DepthLimitExceeded {
+            limit: DEFAULT_MAX_DEPTH
+        }
+    ));
+}
+
+#[test]
+fn test_depth_limit_boundary() {
+    let value = Value::decode(&mut nested_arrays(DEFAULT_MAX_DEPTH)).unwrap();
+    let mut writer = BytesMut::new();
+    value.encode(&mut writer).unwrap();
+    assert_eq!(writer.freeze(), nested_arrays(DEFAULT_MAX_DEPTH));
+    assert!(Value::decode(&mut nested_arrays(DEFAULT_MAX_DEPTH + 1)).is_err());
+}
+
+#[test]
+fn test_depth_limit_follows_decode_config() {
+    let config = DecodeConfig {
+        max_depth: 4,
+        ..Default::default()
+    };
+    let shallow = nested_value(4);
+    let deep = nested_value(5);
+    let result = with_decode_config(&config, || decode::<Value>(&mut encode(&shallow).unwrap()));
+    assert_eq!(result.unwrap(), shallow);
+    let err = with_decode_config(&config, || decode::<Value>(&mut encode(&deep).unwrap()));
+    assert!(matches!(
+        err.unwrap_err(),
+        EncoderError::DepthLimitExceeded { limit: 4 }
+    ));
+
+    let config = DecodeConfig {
+        max_depth: 1000,
+        ..Default::default()
+    };
+    let deep = nested_value(1000);
+    let result = with_decode_config(&config, || decode::<Value>(&mut encode(&deep).unwrap()));
+    assert_eq!(result.unwrap(), deep);
+}
+
+#[test]
+fn test_empty_containers_do_not_count_as_levels() {
+    let config = DecodeConfig {
+        max_depth: 1,
+        ..Default::default()
+    };
+    let value = json!([[], {}, [], {}]);
+    let result = with_decode_config(&config, || decode::<Value>(&mut encode(&value).unwrap()));
+    assert_eq!(result.unwrap(), value);
+}
+
+#[test]
+fn test_nested_wire_format_is_unchanged() {
+    let value = json!({ "a": [1, null], "b": {} });
+    let mut expected = BytesMut::new();
+    expected.put_u8(TAG_JSON_OBJECT);
+    2usize.encode(&mut expected).unwrap();
+    "a".to_string().encode(&mut expected).unwrap();
+    expected.put_u8(TAG_JSON_ARRAY);
+    2usize.encode(&mut expected).unwrap();
+    json!(1).encode(&mut expected).unwrap();
+    expected.put_u8(TAG_JSON_NULL);
+    "b".to_string().encode(&mut expected).unwrap();
+    expected.put_u8(TAG_JSON_OBJECT);
+    0usize.encode(&mut expected).unwrap();
+    let mut writer = BytesMut::new();
+    value.encode(&mut writer).unwrap();
+    assert_eq!(writer.freeze(), expected.freeze());
+}
+
+#[test]
+fn test_mixed_nesting_round_trip() {
+    let value = json!({
+        "users": [
+            { "name": "kim", "tags": ["admin", "ops"], "quota": { "disk": 10.5, "seats": null } },
+            { "name": "lee", "tags": [], "quota": {} }
+        ],
+        "total": -2
+    });
+    let decoded: Value = decode(&mut encode(&value).unwrap()).unwrap();
+    assert_eq!(decoded, value);
+}
+
+#[test]
+fn test_truncated_nested_payload_fails() {
+    let bytes = encode(&json!({ "a": [1, [2, 3]] })).unwrap();
+    for len in 0..bytes.len() {
+        assert!(decode::<Value>(&mut bytes.slice(..len)).is_err());
+    }
+}
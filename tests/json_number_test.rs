@@ -0,0 +1,50 @@
+#![cfg(feature = "arbitrary_precision")]
+
+use bytes::BytesMut;
+use senax_encoder::core::TAG_JSON_NUMBER;
+use senax_encoder::{decode, encode, validate_payload, Decoder, Encoder};
+use serde_json::{json, Number, Value};
+
+#[test]
+fn test_big_numbers_round_trip_exactly() {
+    for text in [
+        "123456789012345678901234567890",
+        "-98765432109876543210",
+        "3.14159265358979323846264338327950288",
+        "1e400",
+    ] {
+        let value = Value::Number(text.parse::<Number>().unwrap());
+        let bytes = encode(&value).unwrap();
+        let decoded: Value = decode(&mut bytes.clone()).unwrap();
+        assert_eq!(decoded.to_string(), text);
+        assert!(validate_payload::<Value>(&bytes).is_valid());
+    }
+}
+
+#[test]
+fn test_small_integers_keep_integer_markers() {
+    let mut writer = BytesMut::new();
+    Value::from(42u64).encode(&mut writer).unwrap();
+    assert_eq!(&writer[..2], &[TAG_JSON_NUMBER, 0]);
+
+    let mut writer = BytesMut::new();
+    Value::from(-7i64).encode(&mut writer).unwrap();
+    assert_eq!(&writer[..2], &[TAG_JSON_NUMBER, 1]);
+}
+
+#[test]
+fn test_decimal_numbers_inside_documents() {
+    let document: Value = serde_json::from_str(
+        r#"{"balance": 100000000000000000000.000000000000000001, "items": [1, 2.5]}"#,
+    )
+    .unwrap();
+    let mut writer = BytesMut::new();
+    document.encode(&mut writer).unwrap();
+    let decoded = Value::decode(&mut writer.freeze()).unwrap();
+    assert_eq!(decoded, document);
+    assert_eq!(
+        decoded["balance"].to_string(),
+        "100000000000000000000.000000000000000001"
+    );
+    assert_eq!(decoded["items"], json!([1, 2.5]));
+}
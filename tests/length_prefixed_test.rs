@@ -0,0 +1,196 @@
+use bytes::{BufMut, Bytes, BytesMut};
+use senax_encoder::record_log::{RecordLogReader, RecordLogWriter};
+use senax_encoder::{
+    decode_framed, encode, encode_framed, try_decode_framed, try_decode_framed_with_max_len,
+    Decode, Encode, EncoderError, DEFAULT_MAX_FRAME_LEN,
+};
+
+#[derive(Encode, Decode, Debug, PartialEq, Clone)]
+struct Message {
+    seq: u64,
+    body: String,
+}
+
+fn message(seq: u64, len: usize) -> Message {
+    Message {
+        seq,
+        body: "x".repeat(len),
+    }
+}
+
+#[test]
+fn test_header_is_u32_le() {
+    let small = message(1, 10);
+    let frame = encode_framed(&small).unwrap();
+    let payload = encode(&small).unwrap();
+    assert_eq!(frame.len(), payload.len() + 4);
+    assert_eq!(&frame[..4], &(payload.len() as u32).to_le_bytes());
+    assert_eq!(&frame[4..], &payload[..]);
+
+    let large = message(2, 1000);
+    let frame = encode_framed(&large).unwrap();
+    assert_eq!(frame.len(), encode(&large).unwrap().len() + 4);
+    assert_eq!(decode_framed::<Message>(&mut frame.clone()).unwrap(), large);
+}
+
+#[test]
+fn test_frames_match_record_log() {
+    let messages = [message(1, 0), message(2, 300)];
+    let mut log = Vec::new();
+    let mut writer = RecordLogWriter::new(&mut log);
+    for m in &messages {
+        writer.append_value(m).unwrap();
+    }
+
+    let mut framed = BytesMut::new();
+    for m in &messages {
+        framed.put_slice(&encode_framed(m).unwrap());
+    }
+    assert_eq!(framed, log);
+
+    let mut reader = RecordLogReader::new(&framed[..]);
+    for m in &messages {
+        assert_eq!(reader.read_value::<Message>().unwrap().as_ref(), Some(m));
+    }
+    let mut log = Bytes::from(log);
+    for m in &messages {
+        assert_eq!(&decode_framed::<Message>(&mut log).unwrap(), m);
+    }
+}
+
+#[test]
+fn test_decode_framed_sequence() {
+    let messages: Vec<Message> = (0..5).map(|i| message(i, i as usize * 50)).collect();
+    let mut stream = BytesMut::new();
+    for m in &messages {
+        stream.put_slice(&encode_framed(m).unwrap());
+    }
+    let mut stream = stream.freeze();
+    for m in &messages {
+        assert_eq!(&decode_framed::<Message>(&mut stream).unwrap(), m);
+    }
+    assert!(stream.is_empty());
+    assert!(matches!(
+        decode_framed::<Message>(&mut stream),
+        Err(EncoderError::UnexpectedEof { .. })
+    ));
+
+    // A cut-short frame leaves the reader at its header
+    let frame = encode_framed(&message(9, 3)).unwrap();
+    let mut truncated = frame.slice(..frame.len() - 1);
+    assert!(matches!(
+        decode_framed::<Message>(&mut truncated),
+        Err(EncoderError::UnexpectedEof { .. })
+    ));
+    assert_eq!(truncated, frame.slice(..frame.len() - 1));
+}
+
+#[test]
+fn test_try_decode_byte_by_byte() {
+    let messages = [message(1, 0), message(2, 200), message(3, 70_000)];
+    let mut stream = Vec::new();
+    for m in &messages {
+        stream.extend_from_slice(&encode_framed(m).unwrap());
+    }
+
+    let mut buf = BytesMut::new();
+    let mut received = Vec::new();
+    for chunk in stream.chunks(7) {
+        buf.extend_from_slice(chunk);
+        while let Some(m) = try_decode_framed::<Message>(&mut buf).unwrap() {
+            received.push(m);
+        }
+    }
+    assert_eq!(received, messages);
+    assert!(buf.is_empty());
+
+    let mut empty = BytesMut::new();
+    assert_eq!(try_decode_framed::<Message>(&mut empty).unwrap(), None);
+}
+
+#[test]
+fn test_try_decode_errors() {
+    // A frame whose payload does not decode is consumed, and the next one still reads
+    let mut buf = BytesMut::new();
+    let bad = encode(&"not a message".to_string()).unwrap();
+    buf.put_u32_le(bad.len() as u32);
+    buf.put_slice(&bad);
+    buf.put_slice(&encode_framed(&message(1, 1)).unwrap());
+    assert!(try_decode_framed::<Message>(&mut buf).is_err());
+    assert_eq!(
+        try_decode_framed::<Message>(&mut buf).unwrap(),
+        Some(message(1, 1))
+    );
+
+    // Extra bytes inside a frame are rejected
+    let payload = encode(&message(2, 2)).unwrap();
+    let mut buf = BytesMut::new();
+    buf.put_u32_le(payload.len() as u32 + 1);
+    buf.put_slice(&payload);
+    buf.put_u8(0);
+    assert!(matches!(
+        try_decode_framed::<Message>(&mut buf),
+        Err(EncoderError::TrailingData { remaining: 1 })
+    ));
+    assert!(buf.is_empty());
+
+    // A partial header waits for more bytes without consuming anything
+    let mut buf = BytesMut::from(&[0xff, 1, 2][..]);
+    assert_eq!(try_decode_framed::<Message>(&mut buf).unwrap(), None);
+    assert_eq!(buf.len(), 3);
+
+    let mut frame = Bytes::from(vec![0xff, 1, 2]);
+    assert!(matches!(
+        decode_framed::<Message>(&mut frame),
+        Err(EncoderError::UnexpectedEof {
+            needed: 4,
+            available: 3
+        })
+    ));
+}
+
+#[test]
+fn test_try_decode_rejects_oversized_frames() {
+    // A header announcing ~4 GiB fails before the payload arrives
+    let mut buf = BytesMut::new();
+    buf.put_u32_le(u32::MAX - 3);
+    buf.put_slice(b"short");
+    assert!(matches!(
+        try_decode_framed::<Message>(&mut buf),
+        Err(EncoderError::Decode(_))
+    ));
+    assert_eq!(buf.len(), 9);
+
+    // Lengths reserved for record log markers are not frame headers
+    let mut buf = BytesMut::new();
+    buf.put_u32_le(u32::MAX);
+    assert!(matches!(
+        try_decode_framed_with_max_len::<Message>(&mut buf, usize::MAX),
+        Err(EncoderError::Decode(_))
+    ));
+
+    let mut buf = BytesMut::new();
+    buf.put_u32_le(DEFAULT_MAX_FRAME_LEN as u32 + 1);
+    assert!(try_decode_framed::<Message>(&mut buf).is_err());
+    let mut buf = BytesMut::new();
+    buf.put_u32_le(DEFAULT_MAX_FRAME_LEN as u32);
+    assert_eq!(try_decode_framed::<Message>(&mut buf).unwrap(), None);
+}
+
+#[test]
+fn test_try_decode_with_max_len() {
+    let frame = encode_framed(&message(1, 20)).unwrap();
+    let payload_len = frame.len() - 4;
+
+    let mut buf = BytesMut::from(&frame[..]);
+    assert!(matches!(
+        try_decode_framed_with_max_len::<Message>(&mut buf, payload_len - 1),
+        Err(EncoderError::Decode(_))
+    ));
+    assert_eq!(buf.len(), frame.len());
+    assert_eq!(
+        try_decode_framed_with_max_len::<Message>(&mut buf, payload_len).unwrap(),
+        Some(message(1, 20))
+    );
+    assert!(buf.is_empty());
+}
@@ -0,0 +1,146 @@
+use senax_encoder::{
+    decode, decode_with_config, encode, impl_senax_tuple, pack, unpack, with_decode_config, Decode,
+    DecodeConfig, Encode,
+};
+
+fn lenient() -> DecodeConfig {
+    DecodeConfig {
+        lenient_tuples: true,
+        ..Default::default()
+    }
+}
+
+#[derive(Encode, Decode, Debug, PartialEq)]
+struct PointV1 {
+    position: (f64, f64),
+}
+
+#[derive(Encode, Decode, Debug, PartialEq)]
+struct PointV2 {
+    position: [f64; 2],
+}
+
+#[derive(Debug, PartialEq)]
+struct Wide(
+    u8,
+    u8,
+    u8,
+    u8,
+    u8,
+    u8,
+    u8,
+    u8,
+    u8,
+    u8,
+    u8,
+    u8,
+    u8,
+    u8,
+    u8,
+    u8,
+    u8,
+);
+
+impl_senax_tuple!(Wide {
+    0: u8,
+    1: u8,
+    2: u8,
+    3: u8,
+    4: u8,
+    5: u8,
+    6: u8,
+    7: u8,
+    8: u8,
+    9: u8,
+    10: u8,
+    11: u8,
+    12: u8,
+    13: u8,
+    14: u8,
+    15: u8,
+    16: u8,
+});
+
+#[test]
+fn test_rejected_by_default() {
+    let mut buf = encode(&(1.5f64, -2.0f64)).unwrap();
+    assert!(decode::<[f64; 2]>(&mut buf).is_err());
+    let mut buf = encode(&[1.5f64, -2.0]).unwrap();
+    assert!(decode::<(f64, f64)>(&mut buf).is_err());
+}
+
+#[test]
+fn test_array_from_tuple() {
+    let mut buf = encode(&(1.5f64, -2.0f64)).unwrap();
+    let array: [f64; 2] = decode_with_config(&mut buf, &lenient()).unwrap();
+    assert_eq!(array, [1.5, -2.0]);
+
+    let mut buf = encode(&("a".to_string(), "b".to_string(), "c".to_string())).unwrap();
+    let array: [String; 3] = decode_with_config(&mut buf, &lenient()).unwrap();
+    assert_eq!(array, ["a", "b", "c"]);
+
+    let mut buf = encode(&()).unwrap();
+    decode_with_config::<[u8; 0]>(&mut buf, &lenient()).unwrap();
+}
+
+#[test]
+fn test_tuple_from_array() {
+    let mut buf = encode(&[1.5f64, -2.0]).unwrap();
+    let tuple: (f64, f64) = decode_with_config(&mut buf, &lenient()).unwrap();
+    assert_eq!(tuple, (1.5, -2.0));
+
+    // Arrays longer than the short tag range use the long form
+    let mut buf = encode(&[7u8; 17]).unwrap();
+    let wide: Wide = decode_with_config(&mut buf, &lenient()).unwrap();
+    assert_eq!(
+        wide,
+        Wide(7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7)
+    );
+
+    let mut buf = encode(&Vec::<u8>::new()).unwrap();
+    decode_with_config::<()>(&mut buf, &lenient()).unwrap();
+}
+
+#[test]
+fn test_arity_and_element_types_must_match() {
+    let mut buf = encode(&(1.5f64, -2.0f64)).unwrap();
+    assert!(decode_with_config::<[f64; 3]>(&mut buf, &lenient()).is_err());
+    let mut buf = encode(&[1.5f64, -2.0, 0.0]).unwrap();
+    assert!(decode_with_config::<(f64, f64)>(&mut buf, &lenient()).is_err());
+    let mut buf = encode(&("x".to_string(), 1u32)).unwrap();
+    assert!(decode_with_config::<[u32; 2]>(&mut buf, &lenient()).is_err());
+}
+
+#[test]
+fn test_struct_field_refactor() {
+    let v1 = PointV1 {
+        position: (3.0, 4.0),
+    };
+    let v2 = PointV2 {
+        position: [3.0, 4.0],
+    };
+    let mut buf = encode(&v1).unwrap();
+    assert!(decode::<PointV2>(&mut buf.clone()).is_err());
+    assert_eq!(
+        decode_with_config::<PointV2>(&mut buf, &lenient()).unwrap(),
+        v2
+    );
+
+    // Data written after the refactor can still be read by old readers
+    let mut buf = encode(&v2).unwrap();
+    assert_eq!(
+        decode_with_config::<PointV1>(&mut buf, &lenient()).unwrap(),
+        v1
+    );
+}
+
+#[test]
+fn test_unpack() {
+    let mut buf = pack(&(1u16, 2u16)).unwrap();
+    let array: [u16; 2] = with_decode_config(&lenient(), || unpack(&mut buf)).unwrap();
+    assert_eq!(array, [1, 2]);
+
+    let mut buf = pack(&[1u16, 2]).unwrap();
+    let tuple: (u16, u16) = with_decode_config(&lenient(), || unpack(&mut buf)).unwrap();
+    assert_eq!(tuple, (1, 2));
+}
@@ -0,0 +1,51 @@
+use senax_encoder::core::{FIELD_NAME_MARKER, MAX_SHORT_FIELD_ID};
+use senax_encoder::hello::HELLO_MAGIC;
+use senax_encoder::self_describing::{NAMED_MAGIC, SELF_DESCRIBING_MAGIC};
+use senax_encoder::session::{SESSION_ID_DEFINE, SESSION_ID_REF, SESSION_MAGIC};
+use senax_encoder::{encode, pack};
+
+fn leading_magic(bytes: &[u8]) -> u16 {
+    u16::from_le_bytes([bytes[0], bytes[1]])
+}
+
+fn assert_distinct<T: PartialEq + std::fmt::Debug>(values: &[(&str, T)]) {
+    for (i, (a, x)) in values.iter().enumerate() {
+        for (b, y) in &values[i + 1..] {
+            assert_ne!(x, y, "{} and {} share {:?}", a, b, x);
+        }
+    }
+}
+
+#[test]
+fn test_magic_numbers_are_distinct() {
+    #[allow(unused_mut)]
+    let mut magics = vec![
+        ("encode", leading_magic(&encode(&0u8).unwrap())),
+        ("pack", leading_magic(&pack(&0u8).unwrap())),
+        ("self-describing", SELF_DESCRIBING_MAGIC),
+        ("named", NAMED_MAGIC),
+        ("session", SESSION_MAGIC),
+        ("hello", HELLO_MAGIC),
+    ];
+    #[cfg(any(feature = "zstd", feature = "lz4"))]
+    magics.push(("compressed", senax_encoder::COMPRESSED_MAGIC));
+    #[cfg(feature = "chacha20poly1305")]
+    magics.push(("encrypted", senax_encoder::ENCRYPTED_MAGIC));
+    assert_distinct(&magics);
+}
+
+#[test]
+fn test_reserved_field_id_bytes_are_distinct() {
+    let reserved = [
+        ("terminator", 0),
+        ("long ID", 255),
+        ("field name", FIELD_NAME_MARKER),
+        ("session ID reference", SESSION_ID_REF),
+        ("session ID definition", SESSION_ID_DEFINE),
+    ];
+    assert_distinct(&reserved);
+    // None of them can be read as a one-byte field ID
+    for (name, byte) in &reserved[1..] {
+        assert!(u64::from(*byte) > MAX_SHORT_FIELD_ID, "{}", name);
+    }
+}
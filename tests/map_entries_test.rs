@@ -0,0 +1,51 @@
+use bytes::{BufMut, BytesMut};
+use senax_encoder::core::{decode_map_entries, unpack_map_entries, TAG_MAP};
+use senax_encoder::{Encoder, Packer};
+use std::collections::BTreeMap;
+
+#[test]
+fn test_decode_map_entries_preserves_wire_order_and_duplicates() {
+    // Hand-built map with a duplicate key, as a foreign producer might write it.
+    let mut writer = BytesMut::new();
+    writer.put_u8(TAG_MAP);
+    3usize.encode(&mut writer).unwrap();
+    for (k, v) in [("b", 1u32), ("a", 2), ("b", 3)] {
+        k.to_string().encode(&mut writer).unwrap();
+        v.encode(&mut writer).unwrap();
+    }
+
+    let entries: Vec<(String, u32)> = decode_map_entries(&mut writer.freeze()).unwrap();
+    assert_eq!(
+        entries,
+        vec![
+            ("b".to_string(), 1),
+            ("a".to_string(), 2),
+            ("b".to_string(), 3)
+        ]
+    );
+}
+
+#[test]
+fn test_decode_map_entries_from_btreemap() {
+    let map: BTreeMap<u8, i64> = BTreeMap::from([(3, -3), (1, -1), (2, -2)]);
+    let mut writer = BytesMut::new();
+    map.encode(&mut writer).unwrap();
+    let entries: Vec<(u8, i64)> = decode_map_entries(&mut writer.freeze()).unwrap();
+    assert_eq!(entries, vec![(1, -1), (2, -2), (3, -3)]);
+}
+
+#[test]
+fn test_unpack_map_entries() {
+    let map: BTreeMap<u8, String> = BTreeMap::from([(1, "x".to_string()), (2, "y".to_string())]);
+    let mut writer = BytesMut::new();
+    map.pack(&mut writer).unwrap();
+    let entries: Vec<(u8, String)> = unpack_map_entries(&mut writer.freeze()).unwrap();
+    assert_eq!(entries, vec![(1, "x".to_string()), (2, "y".to_string())]);
+}
+
+#[test]
+fn test_decode_map_entries_rejects_non_map() {
+    let mut writer = BytesMut::new();
+    vec![1u32, 2].encode(&mut writer).unwrap();
+    assert!(decode_map_entries::<u32, u32>(&mut writer.freeze()).is_err());
+}
@@ -0,0 +1,102 @@
+use bytes::{Bytes, BytesMut};
+use senax_encoder::max_size::check_max_len;
+use senax_encoder::{decode, encode, Decode, Encode, EncoderError};
+use std::collections::HashMap;
+
+#[derive(Encode, Decode, Debug, PartialEq)]
+struct SignUp {
+    #[senax(max_len = 8)]
+    username: String,
+    #[senax(max_len = 2)]
+    tags: Vec<String>,
+    #[senax(max_len = 4)]
+    avatar: Option<Bytes>,
+    #[senax(max_len = 1)]
+    extra: HashMap<String, String>,
+}
+
+#[derive(Encode, Decode, Debug, PartialEq)]
+struct Unbounded {
+    username: String,
+    tags: Vec<String>,
+    avatar: Option<Bytes>,
+}
+
+fn sign_up() -> SignUp {
+    SignUp {
+        username: "alice".into(),
+        tags: vec!["a".into(), "b".into()],
+        avatar: Some(Bytes::from_static(b"\x89PNG")),
+        extra: HashMap::new(),
+    }
+}
+
+#[test]
+fn test_values_within_bounds_round_trip() {
+    let value = sign_up();
+    let bytes = encode(&value).unwrap();
+    let decoded: SignUp = decode(&mut bytes.clone()).unwrap();
+    assert_eq!(decoded, value);
+}
+
+#[test]
+fn test_encode_rejects_long_fields() {
+    let mut value = sign_up();
+    value.username = "a".repeat(9);
+    let err = encode(&value).unwrap_err();
+    assert!(matches!(
+        err,
+        EncoderError::CapacityExceeded {
+            len: 9,
+            capacity: 8
+        }
+    ));
+
+    let mut value = sign_up();
+    value.avatar = Some(Bytes::from_static(b"12345"));
+    assert!(encode(&value).is_err());
+}
+
+#[test]
+fn test_decode_rejects_long_fields() {
+    let bytes = encode(&Unbounded {
+        username: "mallory".repeat(100),
+        tags: vec![],
+        avatar: None,
+    })
+    .unwrap();
+    let err = decode::<SignUp>(&mut bytes.clone()).unwrap_err();
+    assert!(matches!(
+        err,
+        EncoderError::CapacityExceeded {
+            len: 700,
+            capacity: 8
+        }
+    ));
+
+    let bytes = encode(&Unbounded {
+        username: "bob".into(),
+        tags: vec!["a".into(), "b".into(), "c".into()],
+        avatar: None,
+    })
+    .unwrap();
+    assert!(decode::<SignUp>(&mut bytes.clone()).is_err());
+
+    let bytes = encode(&Unbounded {
+        username: "bob".into(),
+        tags: vec![],
+        avatar: Some(Bytes::from_static(b"too long")),
+    })
+    .unwrap();
+    assert!(decode::<SignUp>(&mut bytes.clone()).is_err());
+}
+
+#[test]
+fn test_check_max_len_counts_elements() {
+    assert!(check_max_len("héllo", 6).is_ok());
+    assert!(check_max_len("héllo", 5).is_err());
+    assert!(check_max_len(&vec![1u8; 3], 3).is_ok());
+    let mut writer = BytesMut::new();
+    writer.extend_from_slice(&[0; 4]);
+    assert!(check_max_len(&writer.freeze(), 3).is_err());
+}
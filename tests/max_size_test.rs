@@ -0,0 +1,129 @@
+use bytes::BytesMut;
+use senax_encoder::max_size::{field_id_size, max_len_size, uint_size};
+use senax_encoder::{Decode, Encode, Encoder, MaxEncodedSize};
+use std::collections::BTreeMap;
+
+#[derive(Encode, Decode, Debug, PartialEq)]
+#[senax(max_size)]
+struct Header {
+    version: u8,
+    flags: u32,
+    sequence: u64,
+    offset: i64,
+    ratio: f64,
+    checksum: [u8; 4],
+    parent: Option<u128>,
+    #[senax(max_len = 16)]
+    label: String,
+    #[senax(max_len = 3)]
+    hops: Vec<u16>,
+    #[senax(max_len = 2)]
+    attributes: BTreeMap<u8, bool>,
+    #[senax(skip_encode)]
+    cached: Vec<String>,
+}
+
+#[derive(Encode, Decode, Debug, PartialEq)]
+#[senax(max_size)]
+struct Pair(u16, Option<char>);
+
+#[derive(Encode, Decode, Debug, PartialEq)]
+#[senax(max_size)]
+enum Command {
+    Ping,
+    Move { x: i32, y: i32 },
+    Rename(#[senax(max_len = 8)] String),
+    Batch(Pair, Pair),
+}
+
+#[derive(Encode, Decode, Debug, PartialEq)]
+#[senax(max_size, string_repr)]
+enum Level {
+    Low,
+    #[senax(rename = "very-high")]
+    VeryHigh,
+}
+
+#[derive(Encode, Debug, PartialEq)]
+#[senax(max_size)]
+struct Wrapper<T: Encoder> {
+    inner: T,
+}
+
+fn encoded_len<T: Encoder>(value: &T) -> usize {
+    let mut buf = BytesMut::new();
+    value.encode(&mut buf).unwrap();
+    buf.len()
+}
+
+fn largest_header() -> Header {
+    Header {
+        version: u8::MAX,
+        flags: u32::MAX,
+        sequence: u64::MAX,
+        offset: i64::MIN,
+        ratio: -2.2250738585072014e-308,
+        checksum: [255; 4],
+        parent: Some(u128::MAX),
+        label: "x".repeat(16),
+        hops: vec![u16::MAX; 3],
+        attributes: [(200, true), (201, true)].into_iter().collect(),
+        cached: vec!["not encoded".to_string(); 100],
+    }
+}
+
+#[test]
+fn test_scalar_bounds_are_tight() {
+    assert_eq!(encoded_len(&u8::MAX), u8::MAX_ENCODED_SIZE);
+    assert_eq!(encoded_len(&u64::MAX), u64::MAX_ENCODED_SIZE);
+    assert_eq!(encoded_len(&i128::MIN), i128::MAX_ENCODED_SIZE);
+    assert_eq!(encoded_len(&char::MAX), char::MAX_ENCODED_SIZE);
+    assert!(encoded_len(&-1.1754944e-38f32) <= f32::MAX_ENCODED_SIZE);
+    assert_eq!(encoded_len(&()), <()>::MAX_ENCODED_SIZE);
+    assert_eq!(encoded_len(&(1u8, 2u8)), <(u8, u8)>::MAX_ENCODED_SIZE - 2);
+    assert!(encoded_len(&f64::MIN_POSITIVE) <= f64::MAX_ENCODED_SIZE);
+    assert!(encoded_len(&f64::NEG_INFINITY) <= f64::MAX_ENCODED_SIZE);
+}
+
+#[test]
+fn test_derived_struct_bound() {
+    let header = largest_header();
+    assert!(encoded_len(&header) <= Header::MAX_ENCODED_SIZE);
+    assert!(encoded_len(&Pair(u16::MAX, Some(char::MAX))) <= Pair::MAX_ENCODED_SIZE);
+}
+
+#[test]
+fn test_derived_enum_bound() {
+    let commands = [
+        Command::Ping,
+        Command::Move {
+            x: i32::MIN,
+            y: i32::MIN,
+        },
+        Command::Rename("abcdefgh".to_string()),
+        Command::Batch(Pair(u16::MAX, Some(char::MAX)), Pair(0, None)),
+    ];
+    for command in &commands {
+        assert!(encoded_len(command) <= Command::MAX_ENCODED_SIZE);
+    }
+    assert_eq!(encoded_len(&Level::VeryHigh), Level::MAX_ENCODED_SIZE);
+    assert!(Level::MAX_ENCODED_SIZE <= max_len_size(9, 1));
+}
+
+#[test]
+fn test_generic_struct_bound() {
+    assert_eq!(
+        <Wrapper<u32>>::MAX_ENCODED_SIZE,
+        // CRC64-derived field IDs take the 9-byte form
+        1 + field_id_size(u64::MAX) + u32::MAX_ENCODED_SIZE + 1
+    );
+    assert!(encoded_len(&Wrapper { inner: u32::MAX }) <= <Wrapper<u32>>::MAX_ENCODED_SIZE);
+}
+
+#[test]
+fn test_stack_buffer() {
+    const LEN: usize = <Command as MaxEncodedSize>::MAX_ENCODED_SIZE;
+    let buf = [0u8; LEN];
+    assert_eq!(buf.len(), LEN);
+    assert!(uint_size(LEN as u128) >= 1);
+}
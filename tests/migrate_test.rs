@@ -0,0 +1,222 @@
+use senax_encoder::migrate::{
+    assert_dual_consistent, encode_dual, migrate_dir, migrate_log, verify_dual,
+};
+use senax_encoder::record_log::{RecordLogReader, RecordLogWriter};
+use senax_encoder::{decode, Decode, Encode, EncoderError};
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+
+#[derive(Encode, Decode, Debug, PartialEq)]
+struct EventV1 {
+    id: u32,
+    name: String,
+}
+
+#[derive(Encode, Decode, Debug, PartialEq)]
+struct EventV2 {
+    id: u64,
+    name: String,
+    tags: Vec<String>,
+}
+
+impl From<EventV1> for EventV2 {
+    fn from(old: EventV1) -> Self {
+        EventV2 {
+            id: old.id as u64,
+            name: old.name,
+            tags: vec!["migrated".to_string()],
+        }
+    }
+}
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "senax-migrate-test-{}-{}",
+        name,
+        std::process::id()
+    ));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn write_v1_log(path: &PathBuf, ids: std::ops::Range<u32>) {
+    let mut writer = RecordLogWriter::new(File::create(path).unwrap());
+    for id in ids {
+        writer
+            .append_value(&EventV1 {
+                id,
+                name: format!("event-{}", id),
+            })
+            .unwrap();
+    }
+    writer.flush().unwrap();
+}
+
+fn read_v2_log(path: &PathBuf) -> Vec<EventV2> {
+    let mut reader = RecordLogReader::new(File::open(path).unwrap());
+    let mut values = Vec::new();
+    while let Some(value) = reader.read_value::<EventV2>().unwrap() {
+        values.push(value);
+    }
+    values
+}
+
+#[test]
+fn test_migrate_log_converts_all_records() {
+    let dir = temp_dir("all");
+    let source = dir.join("events.log");
+    let target = dir.join("events.v2.log");
+    write_v1_log(&source, 0..5);
+
+    let mut calls = Vec::new();
+    let progress =
+        migrate_log::<EventV1, EventV2>(&source, &target, |p| calls.push(p.migrated)).unwrap();
+    assert_eq!(progress.migrated, 5);
+    assert_eq!(progress.resumed, 0);
+    assert_eq!(progress.bytes_read, fs::metadata(&source).unwrap().len());
+    assert_eq!(calls, vec![1, 2, 3, 4, 5]);
+
+    let values = read_v2_log(&target);
+    assert_eq!(values.len(), 5);
+    assert_eq!(
+        values[3],
+        EventV2 {
+            id: 3,
+            name: "event-3".to_string(),
+            tags: vec!["migrated".to_string()],
+        }
+    );
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_migrate_log_resumes_and_truncates_torn_frame() {
+    let dir = temp_dir("resume");
+    let source = dir.join("events.log");
+    let target = dir.join("events.v2.log");
+    write_v1_log(&source, 0..2);
+    migrate_log::<EventV1, EventV2>(&source, &target, |_| {}).unwrap();
+
+    // Simulate a crash in the middle of writing the next frame.
+    let mut file = OpenOptions::new().append(true).open(&target).unwrap();
+    file.write_all(&[42, 0, 0, 0, 1, 2]).unwrap();
+    drop(file);
+
+    write_v1_log(&source, 0..4);
+    let progress = migrate_log::<EventV1, EventV2>(&source, &target, |_| {}).unwrap();
+    assert_eq!(progress.resumed, 2);
+    assert_eq!(progress.migrated, 2);
+
+    let ids: Vec<u64> = read_v2_log(&target).into_iter().map(|e| e.id).collect();
+    assert_eq!(ids, vec![0, 1, 2, 3]);
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_migrate_log_rejects_target_longer_than_source() {
+    let dir = temp_dir("longer");
+    let source = dir.join("events.log");
+    let target = dir.join("events.v2.log");
+    write_v1_log(&source, 0..3);
+    migrate_log::<EventV1, EventV2>(&source, &target, |_| {}).unwrap();
+
+    write_v1_log(&source, 0..1);
+    let result = migrate_log::<EventV1, EventV2>(&source, &target, |_| {});
+    assert!(matches!(result, Err(EncoderError::Decode(_))));
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_migrate_dir() {
+    let dir = temp_dir("dir");
+    let source_dir = dir.join("v1");
+    let target_dir = dir.join("v2");
+    fs::create_dir_all(source_dir.join("nested")).unwrap();
+    write_v1_log(&source_dir.join("a.log"), 0..3);
+    write_v1_log(&source_dir.join("b.log"), 10..12);
+
+    let mut seen = Vec::new();
+    let total = migrate_dir::<EventV1, EventV2>(&source_dir, &target_dir, |path, p| {
+        seen.push((path.file_name().unwrap().to_owned(), p.migrated))
+    })
+    .unwrap();
+    assert_eq!(total.migrated, 5);
+    assert_eq!(seen.len(), 5);
+    assert_eq!(seen[4], ("b.log".into(), 2));
+
+    let ids: Vec<u64> = read_v2_log(&target_dir.join("b.log"))
+        .into_iter()
+        .map(|e| e.id)
+        .collect();
+    assert_eq!(ids, vec![10, 11]);
+    assert!(!target_dir.join("nested").exists());
+
+    // Running again resumes every file without duplicating records.
+    let total = migrate_dir::<EventV1, EventV2>(&source_dir, &target_dir, |_, _| {}).unwrap();
+    assert_eq!(total.migrated, 0);
+    assert_eq!(total.resumed, 5);
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_record_log_reports_torn_frame() {
+    let mut bytes = Vec::new();
+    let mut writer = RecordLogWriter::new(&mut bytes);
+    writer.append(b"hello").unwrap();
+    bytes.extend_from_slice(&[9, 0]);
+
+    let mut reader = RecordLogReader::new(bytes.as_slice());
+    assert_eq!(reader.read_record().unwrap().unwrap().as_ref(), b"hello");
+    assert_eq!(reader.offset(), 9);
+    assert!(matches!(
+        reader.read_record(),
+        Err(EncoderError::UnexpectedEof { .. })
+    ));
+}
+
+fn dual_pair() -> (EventV1, EventV2) {
+    (
+        EventV1 {
+            id: 7,
+            name: "deploy".into(),
+        },
+        EventV2 {
+            id: 7,
+            name: "deploy".into(),
+            tags: vec!["migrated".into()],
+        },
+    )
+}
+
+#[test]
+fn test_encode_dual_writes_both_forms() {
+    let (old, new) = dual_pair();
+    let (old_bytes, new_bytes) = encode_dual(&old, &new).unwrap();
+    assert_eq!(decode::<EventV1>(&mut old_bytes.clone()).unwrap(), old);
+    assert_eq!(decode::<EventV2>(&mut new_bytes.clone()).unwrap(), new);
+    verify_dual::<EventV1, EventV2>(&old_bytes, &new_bytes).unwrap();
+    assert_dual_consistent(&old, &new);
+}
+
+#[test]
+fn test_verify_dual_reports_mismatch() {
+    let (old, mut new) = dual_pair();
+    new.name = "rollback".into();
+    let (old_bytes, new_bytes) = encode_dual(&old, &new).unwrap();
+    let err = verify_dual::<EventV1, EventV2>(&old_bytes, &new_bytes).unwrap_err();
+    assert!(err.to_string().contains("Dual-write mismatch"), "{}", err);
+    assert!(err.to_string().contains("rollback"), "{}", err);
+
+    // Payloads that do not decode are reported too
+    assert!(verify_dual::<EventV1, EventV2>(&old_bytes, &old_bytes.slice(..3)).is_err());
+}
+
+#[test]
+#[should_panic(expected = "Dual-write mismatch")]
+fn test_assert_dual_consistent_panics_on_mismatch() {
+    let (old, mut new) = dual_pair();
+    new.tags.clear();
+    assert_dual_consistent(&old, &new);
+}
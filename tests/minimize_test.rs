@@ -0,0 +1,174 @@
+use bytes::{BufMut, Bytes, BytesMut};
+use senax_encoder::minimize::minimize_payload;
+use senax_encoder::{decode, encode, Decode, Encode, EncoderError};
+use std::collections::{BTreeMap, HashMap};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+#[derive(Encode, Decode, Debug, PartialEq, Clone)]
+enum Item {
+    Book { title: String, pages: u32 },
+    Gift(String, u8),
+    Empty,
+}
+
+#[derive(Encode, Decode, Debug, PartialEq, Clone)]
+struct Cart {
+    id: u64,
+    owner: Option<String>,
+    items: Vec<Item>,
+    counts: BTreeMap<String, u32>,
+    by_id: HashMap<u32, Vec<u8>>,
+    position: (i32, i32),
+    blob: Bytes,
+}
+
+fn cart() -> Cart {
+    Cart {
+        id: 987_654_321,
+        owner: Some("somebody with a long name".to_string()),
+        items: vec![
+            Item::Empty,
+            Item::Book {
+                title: "A".repeat(100),
+                pages: 300,
+            },
+            Item::Gift("wrapped".to_string(), 200),
+            Item::Book {
+                title: "poison".to_string(),
+                pages: 12,
+            },
+        ],
+        counts: [("x".to_string(), 1), ("y".to_string(), 2)].into(),
+        by_id: [(1, vec![1, 2, 3]), (2, vec![])].into(),
+        position: (-5, 70_000),
+        blob: Bytes::from(vec![9u8; 64]),
+    }
+}
+
+/// A buggy consumer: panics on books titled "poison".
+fn process(cart: &Cart) -> u32 {
+    let mut pages = 0;
+    for item in &cart.items {
+        if let Item::Book { title, pages: p } = item {
+            if title.starts_with("poison") {
+                panic!("poisoned book");
+            }
+            pages += p;
+        }
+    }
+    pages
+}
+
+fn panics(payload: &Bytes) -> bool {
+    let Ok(cart) = decode::<Cart>(&mut payload.clone()) else {
+        return false;
+    };
+    catch_unwind(AssertUnwindSafe(|| process(&cart))).is_err()
+}
+
+#[test]
+fn test_minimizes_panic_reproducer() {
+    let original = encode(&cart()).unwrap();
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(|_| {}));
+    let report = minimize_payload(&original, panics);
+    std::panic::set_hook(default_hook);
+    let report = report.unwrap();
+
+    assert_eq!(report.original_len, original.len());
+    assert!(report.payload.len() < original.len() / 3);
+    assert!(report.reductions > 0 && report.attempts >= report.reductions);
+
+    let minimal: Cart = decode(&mut report.payload.clone()).unwrap();
+    assert_eq!(
+        minimal,
+        Cart {
+            id: 0,
+            owner: None,
+            items: vec![Item::Book {
+                title: "poison".to_string(),
+                pages: 0,
+            }],
+            counts: BTreeMap::new(),
+            by_id: HashMap::new(),
+            position: (0, 0),
+            blob: Bytes::new(),
+        }
+    );
+}
+
+#[test]
+fn test_result_is_one_minimal() {
+    // Fails while the cart holds at least two items with a combined title length of 8 or more
+    let fails = |payload: &Bytes| match decode::<Cart>(&mut payload.clone()) {
+        Ok(cart) => {
+            let titles: usize = cart
+                .items
+                .iter()
+                .map(|item| match item {
+                    Item::Book { title, .. } | Item::Gift(title, _) => title.len(),
+                    Item::Empty => 0,
+                })
+                .sum();
+            cart.items.len() >= 2 && titles >= 8
+        }
+        Err(_) => false,
+    };
+    let report = minimize_payload(&encode(&cart()).unwrap(), fails).unwrap();
+    let minimal: Cart = decode(&mut report.payload.clone()).unwrap();
+    assert_eq!(minimal.items.len(), 2);
+    assert!(fails(&report.payload));
+
+    // Minimizing again finds nothing left to remove
+    let again = minimize_payload(&report.payload, fails).unwrap();
+    assert_eq!(again.payload, report.payload);
+    assert_eq!(again.reductions, 0);
+}
+
+#[test]
+fn test_minimizes_decode_errors() {
+    // A production payload that a newer reader rejects: `pages` no longer fits in a u8
+    #[derive(Decode, Debug)]
+    #[allow(dead_code)]
+    enum NarrowItem {
+        Book { title: String, pages: u8 },
+        Gift(String, u8),
+        Empty,
+    }
+    let fails = |payload: &Bytes| {
+        matches!(
+            decode::<Vec<NarrowItem>>(&mut payload.clone()),
+            Err(EncoderError::Decode(message)) if message.contains("u8")
+        )
+    };
+    let report = minimize_payload(&encode(&cart().items).unwrap(), fails).unwrap();
+
+    // Only the offending field of one item is left
+    #[derive(Decode, Debug, PartialEq)]
+    enum PagesOnly {
+        Book { pages: u32 },
+    }
+    let minimal: Vec<PagesOnly> = decode(&mut report.payload.clone()).unwrap();
+    assert_eq!(minimal, vec![PagesOnly::Book { pages: 300 }]);
+}
+
+#[test]
+fn test_drops_trailing_bytes() {
+    let mut buf = BytesMut::from(&encode(&42u32).unwrap()[..]);
+    buf.put_slice(b"garbage");
+    let report =
+        minimize_payload(&buf, |payload| decode::<u32>(&mut payload.clone()).is_ok()).unwrap();
+    assert_eq!(report.payload, encode(&0u32).unwrap());
+}
+
+#[test]
+fn test_rejects_unusable_input() {
+    let payload = encode(&cart()).unwrap();
+    assert!(matches!(
+        minimize_payload(&payload, |_| false),
+        Err(EncoderError::Decode(_))
+    ));
+    assert!(minimize_payload(&payload[..payload.len() - 1], |_| true).is_err());
+    assert!(minimize_payload(&payload[2..], |_| true).is_err());
+    assert!(minimize_payload(&[], |_| true).is_err());
+}
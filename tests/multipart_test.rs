@@ -0,0 +1,124 @@
+use bytes::Bytes;
+use senax_encoder::multipart::{
+    encode_multipart, MultipartAssembler, DEFAULT_MAX_TOTAL_LEN, MULTIPART_HEADER_LEN,
+};
+use senax_encoder::{encode, Decode, Encode, EncoderError};
+
+#[derive(Encode, Decode, Debug, PartialEq)]
+struct Upload {
+    name: String,
+    data: Vec<u8>,
+}
+
+fn upload(len: usize) -> Upload {
+    Upload {
+        name: "archive.tar".to_string(),
+        data: (0..len).map(|i| i as u8).collect(),
+    }
+}
+
+#[test]
+fn test_multipart_roundtrip_in_order() {
+    let value = upload(10_000);
+    let frames = encode_multipart(&value, 512).unwrap();
+    assert!(frames.len() > 1);
+    assert!(frames.iter().all(|frame| frame.len() <= 512));
+
+    let mut assembler = MultipartAssembler::new();
+    let last = frames.len() - 1;
+    for (i, frame) in frames.into_iter().enumerate() {
+        let result = assembler.push_value::<Upload>(frame).unwrap();
+        if i < last {
+            assert!(result.is_none());
+        } else {
+            assert_eq!(result, Some(upload(10_000)));
+        }
+    }
+    // The assembler is ready for the next message
+    assert_eq!(assembler.expected(), None);
+}
+
+#[test]
+fn test_multipart_out_of_order_matches_encode() {
+    let value = upload(3_000);
+    let mut frames = encode_multipart(&value, 100).unwrap();
+    frames.reverse();
+    let mut assembler = MultipartAssembler::new();
+    let mut payload = None;
+    for frame in frames {
+        payload = assembler.push(frame).unwrap();
+    }
+    assert_eq!(payload.unwrap(), encode(&value).unwrap());
+}
+
+#[test]
+fn test_multipart_small_value_is_single_frame() {
+    let frames = encode_multipart(&42u32, 1024).unwrap();
+    assert_eq!(frames.len(), 1);
+    let mut assembler = MultipartAssembler::new();
+    assert_eq!(
+        assembler.push_value::<u32>(frames[0].clone()).unwrap(),
+        Some(42)
+    );
+}
+
+#[test]
+fn test_multipart_frame_size_too_small() {
+    let result = encode_multipart(&upload(10), MULTIPART_HEADER_LEN);
+    assert!(matches!(result, Err(EncoderError::Encode(_))));
+}
+
+#[test]
+fn test_multipart_rejects_duplicate_and_mismatched_frames() {
+    let frames = encode_multipart(&upload(1_000), 64).unwrap();
+    let mut assembler = MultipartAssembler::new();
+    assembler.push(frames[0].clone()).unwrap();
+    assert!(assembler.push(frames[0].clone()).is_err());
+
+    let other = encode_multipart(&upload(10_000), 64).unwrap();
+    assert!(assembler.push(other[1].clone()).is_err());
+    assert_eq!(assembler.received(), 1);
+
+    assembler.reset();
+    assert!(matches!(
+        assembler.push(Bytes::from_static(&[0, 0, 0])),
+        Err(EncoderError::UnexpectedEof { .. })
+    ));
+}
+
+#[test]
+fn test_multipart_rejects_huge_frame_count() {
+    // One 8-byte header announcing u32::MAX frames must not size a buffer from the count
+    let mut frame = Vec::new();
+    frame.extend_from_slice(&0u32.to_le_bytes());
+    frame.extend_from_slice(&u32::MAX.to_le_bytes());
+    frame.push(1);
+
+    let mut assembler = MultipartAssembler::new();
+    assert_eq!(assembler.max_total_len(), DEFAULT_MAX_TOTAL_LEN);
+    let err = assembler.push(Bytes::from(frame)).unwrap_err();
+    assert!(matches!(err, EncoderError::Decode(_)), "{:?}", err);
+    assert_eq!(assembler.expected(), None);
+    assert_eq!(assembler.received(), 0);
+}
+
+#[test]
+fn test_multipart_max_total_len() {
+    let value = upload(1_000);
+    let frames = encode_multipart(&value, 128).unwrap();
+    let payload_len = encode(&value).unwrap().len();
+
+    let mut assembler = MultipartAssembler::new().with_max_total_len(payload_len - 1);
+    let result: Result<Vec<_>, _> = frames
+        .iter()
+        .map(|frame| assembler.push(frame.clone()))
+        .collect();
+    assert!(matches!(result, Err(EncoderError::Decode(_))));
+
+    let mut assembler = MultipartAssembler::new().with_max_total_len(payload_len);
+    let mut decoded = None;
+    for frame in frames {
+        decoded = assembler.push_value::<Upload>(frame).unwrap();
+    }
+    assert_eq!(decoded, Some(value));
+}
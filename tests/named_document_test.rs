@@ -0,0 +1,139 @@
+use senax_encoder::core::{field_id_from_name, FIELD_NAME_MARKER};
+use senax_encoder::debug::diff;
+use senax_encoder::self_describing::{
+    decode_named, decode_self_describing, encode_named, NAMED_MAGIC,
+};
+use senax_encoder::{decode, encode, query, Decode, Encode, QueryMatch, Value};
+
+#[derive(Encode, Decode, Debug, PartialEq, Clone)]
+enum Shape {
+    Empty,
+    #[senax(id = 3)]
+    Circle(f64),
+    Rect {
+        #[senax(id = 9)]
+        width: u32,
+        height: u32,
+    },
+}
+
+#[derive(Encode, Decode, Debug, PartialEq, Clone)]
+#[senax(rename_all = "camelCase")]
+struct Drawing {
+    #[senax(id = 1)]
+    drawing_id: u64,
+    title: String,
+    #[senax(rename = "items")]
+    shapes: Vec<Shape>,
+    note: Option<String>,
+}
+
+#[derive(Encode, Decode, Debug, PartialEq)]
+#[senax(id_salt = "v2")]
+struct Salted {
+    a: u8,
+    b: String,
+}
+
+#[derive(Encode, Decode, Debug, PartialEq)]
+#[senax(rename_all = "camelCase")]
+struct DrawingSummary {
+    title: String,
+    #[senax(default)]
+    layer_count: u32,
+}
+
+fn drawing() -> Drawing {
+    Drawing {
+        drawing_id: 42,
+        title: "plan".to_string(),
+        shapes: vec![
+            Shape::Empty,
+            Shape::Circle(1.5),
+            Shape::Rect {
+                width: 3,
+                height: 4,
+            },
+        ],
+        note: None,
+    }
+}
+
+#[test]
+fn test_round_trip() {
+    let value = drawing();
+    let bytes = encode_named(&value).unwrap();
+    assert_eq!(&bytes[..2], &NAMED_MAGIC.to_le_bytes());
+    assert_eq!(decode_named::<Drawing>(&mut bytes.clone()).unwrap(), value);
+
+    // Field and variant IDs not derived from the name are matched by name too
+    let salted = Salted {
+        a: 1,
+        b: "x".to_string(),
+    };
+    let bytes = encode_named(&salted).unwrap();
+    assert_eq!(decode_named::<Salted>(&mut bytes.clone()).unwrap(), salted);
+
+    // Named documents are not plain payloads, and the other way around
+    assert!(decode::<Drawing>(&mut bytes.clone()).is_err());
+    assert!(decode_named::<Drawing>(&mut encode(&value).unwrap()).is_err());
+}
+
+#[test]
+fn test_names_are_written_instead_of_ids() {
+    let bytes = encode_named(&drawing()).unwrap();
+    let contains = |name: &str| {
+        let mut header = vec![FIELD_NAME_MARKER];
+        header.extend(encode(&name).unwrap().slice(2..));
+        bytes.windows(header.len()).any(|w| w == header)
+    };
+    for name in ["drawingId", "title", "items", "Circle", "Rect", "width"] {
+        assert!(contains(name), "{} missing", name);
+    }
+    assert!(!contains("shapes"));
+    assert!(!contains("note"));
+}
+
+#[test]
+fn test_read_without_types() {
+    let bytes = encode_named(&drawing()).unwrap();
+    let value = decode_self_describing(&mut bytes.clone()).unwrap();
+    assert_eq!(value.field("drawingId"), Some(&Value::UInt(42)));
+    let Some(Value::List(items)) = value.field("items") else {
+        panic!("expected a list");
+    };
+    let Value::Enum(rect) = &items[2] else {
+        panic!("expected an enum");
+    };
+    assert_eq!(rect.name.as_deref(), Some("Rect"));
+    // Without the type, IDs are the hash of the name
+    assert_eq!(rect.id, field_id_from_name("Rect"));
+
+    assert_eq!(
+        query(&bytes, "items[2].height").unwrap(),
+        Some(QueryMatch::Scalar(Value::UInt(4)))
+    );
+    assert!(diff(&bytes, &bytes).unwrap().is_empty());
+}
+
+#[test]
+fn test_unknown_names_are_skipped() {
+    let bytes = encode_named(&drawing()).unwrap();
+    let summary: DrawingSummary = decode_named(&mut bytes.clone()).unwrap();
+    assert_eq!(
+        summary,
+        DrawingSummary {
+            title: "plan".to_string(),
+            layer_count: 0,
+        }
+    );
+}
+
+#[test]
+fn test_plain_encoding_is_unchanged() {
+    // Named encodes do not leak into later plain encodes on the same thread
+    let before = encode(&drawing()).unwrap();
+    encode_named(&drawing()).unwrap();
+    assert_eq!(encode(&drawing()).unwrap(), before);
+    assert_eq!(decode::<Drawing>(&mut before.clone()).unwrap(), drawing());
+}
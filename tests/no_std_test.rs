@@ -0,0 +1,91 @@
+//! Uses only the API available without the `std` feature. Also run it against the `no_std`
+//! build with `cargo test --no-default-features --test no_std_test`.
+
+extern crate alloc;
+
+use alloc::boxed::Box;
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::string::String;
+use alloc::vec::Vec;
+use senax_encoder::{
+    current_decode_config, decode, decode_exact, encode, pack, unpack, Decode, DecodeConfig,
+    Encode, EncoderError, ErrorCode, Pack, Unpack, DEFAULT_MAX_DEPTH,
+};
+
+#[derive(Encode, Decode, Pack, Unpack, Debug, PartialEq)]
+struct Telemetry {
+    device: String,
+    readings: Vec<f32>,
+    counters: BTreeMap<String, u64>,
+    flags: BTreeSet<u8>,
+    #[senax(default)]
+    battery: Option<u8>,
+}
+
+#[derive(Encode, Decode, Debug, PartialEq)]
+enum Expr {
+    Leaf(i64),
+    Neg(Box<Expr>),
+}
+
+fn sample() -> Telemetry {
+    Telemetry {
+        device: "probe-7".into(),
+        readings: Vec::from([20.5, 21.0, -3.25]),
+        counters: BTreeMap::from([("resets".into(), 2), ("packets".into(), 9000)]),
+        flags: BTreeSet::from([1, 4]),
+        battery: Some(87),
+    }
+}
+
+#[test]
+fn test_encode_and_pack_round_trip() {
+    let telemetry = sample();
+    let mut bytes = encode(&telemetry).unwrap();
+    assert_eq!(decode_exact::<Telemetry>(&mut bytes).unwrap(), telemetry);
+    let mut bytes = pack(&telemetry).unwrap();
+    assert_eq!(unpack::<Telemetry>(&mut bytes).unwrap(), telemetry);
+}
+
+#[test]
+fn test_default_config_applies() {
+    assert_eq!(current_decode_config(), DecodeConfig::default());
+}
+
+#[test]
+fn test_depth_limit_applies_to_recursive_types() {
+    let mut expr = Expr::Leaf(1);
+    for _ in 0..DEFAULT_MAX_DEPTH + 1 {
+        expr = Expr::Neg(Box::new(expr));
+    }
+    let mut bytes = encode(&expr).unwrap();
+    let err = decode::<Expr>(&mut bytes).unwrap_err();
+    assert!(matches!(err, EncoderError::DepthLimitExceeded { .. }));
+
+    // The depth is back to zero after the failed decode
+    let shallow = Expr::Neg(Box::new(Expr::Leaf(-5)));
+    let mut bytes = encode(&shallow).unwrap();
+    assert_eq!(decode::<Expr>(&mut bytes).unwrap(), shallow);
+}
+
+#[test]
+fn test_error_codes() {
+    let err = decode::<Telemetry>(&mut bytes::Bytes::new()).unwrap_err();
+    assert_eq!(err.code(), ErrorCode::UnexpectedEof);
+}
+
+#[test]
+fn test_duration_round_trip() {
+    let timeout = core::time::Duration::from_millis(1500);
+    let mut bytes = encode(&timeout).unwrap();
+    assert_eq!(decode::<core::time::Duration>(&mut bytes).unwrap(), timeout);
+}
+
+#[cfg(feature = "heapless")]
+#[test]
+fn test_heapless_round_trip() {
+    let mut label: heapless::String<8> = heapless::String::new();
+    label.push_str("node-1").unwrap();
+    let mut bytes = encode(&label).unwrap();
+    assert_eq!(decode::<heapless::String<8>>(&mut bytes).unwrap(), label);
+}
@@ -0,0 +1,134 @@
+use bytes::{BufMut, Bytes, BytesMut};
+use senax_encoder::core::{TAG_BINARY, TAG_TUPLE};
+use senax_encoder::{
+    decode, encode, pack, unpack, validate_payload, Decode, Decoder, Encode, Encoder, NullableVec,
+    Pack, Unpack, Value,
+};
+
+#[derive(Encode, Decode, Pack, Unpack, Debug, PartialEq)]
+struct Readings {
+    timestamps: Vec<i64>,
+    temperature: NullableVec<f64>,
+    label: NullableVec<String>,
+}
+
+#[derive(Encode, Decode, Debug, PartialEq)]
+struct ReadingsV1 {
+    timestamps: Vec<i64>,
+    temperature: Vec<Option<f64>>,
+    label: Vec<Option<String>>,
+}
+
+fn sparse(len: usize, every: usize) -> Vec<Option<u32>> {
+    (0..len)
+        .map(|i| (i % every == 0).then_some(i as u32))
+        .collect()
+}
+
+#[test]
+fn test_layout() {
+    let column = NullableVec::from(vec![Some(1u8), None, None, Some(2)]);
+    let mut writer = BytesMut::new();
+    column.encode(&mut writer).unwrap();
+
+    let mut expected = BytesMut::new();
+    expected.put_u8(TAG_TUPLE);
+    3usize.encode(&mut expected).unwrap();
+    4usize.encode(&mut expected).unwrap();
+    expected.put_u8(TAG_BINARY);
+    1usize.encode(&mut expected).unwrap();
+    expected.put_u8(0b1001);
+    vec![1u8, 2].encode(&mut expected).unwrap();
+    assert_eq!(writer, expected);
+}
+
+#[test]
+fn test_round_trip() {
+    for values in [
+        vec![],
+        vec![None; 9],
+        sparse(8, 1),
+        sparse(17, 3),
+        sparse(1000, 100),
+    ] {
+        let column = NullableVec::from(values.clone());
+        let mut bytes = encode(&column).unwrap();
+        assert_eq!(decode::<NullableVec<u32>>(&mut bytes).unwrap().0, values);
+        let mut packed = pack(&column).unwrap();
+        assert_eq!(unpack::<NullableVec<u32>>(&mut packed).unwrap().0, values);
+    }
+}
+
+#[test]
+fn test_sparse_data_is_smaller() {
+    let values = sparse(1000, 100);
+    let plain = encode(&values).unwrap().len();
+    let column = encode(&NullableVec::from(values)).unwrap().len();
+    assert!(column * 4 < plain, "{} vs {}", column, plain);
+
+    // Dense data costs at most about a bit per element more
+    let values = sparse(1000, 1);
+    let plain = encode(&values).unwrap().len();
+    let column = encode(&NullableVec::from(values)).unwrap().len();
+    assert!(column <= plain, "{} vs {}", column, plain);
+}
+
+#[test]
+fn test_in_structs_and_legacy_payloads() {
+    let readings = Readings {
+        timestamps: vec![1, 2, 3],
+        temperature: vec![Some(20.5), None, Some(21.0)].into(),
+        label: vec![None, Some("calibrated".to_string()), None].into(),
+    };
+    let bytes = encode(&readings).unwrap();
+    assert_eq!(decode::<Readings>(&mut bytes.clone()).unwrap(), readings);
+    assert_eq!(
+        unpack::<Readings>(&mut pack(&readings).unwrap()).unwrap(),
+        readings
+    );
+    assert!(validate_payload::<Readings>(&bytes).is_valid());
+
+    // Fields written as `Vec<Option<T>>` still decode
+    let v1 = ReadingsV1 {
+        timestamps: readings.timestamps.clone(),
+        temperature: readings.temperature.0.clone(),
+        label: readings.label.0.clone(),
+    };
+    let mut bytes = encode(&v1).unwrap();
+    assert_eq!(decode::<Readings>(&mut bytes).unwrap(), readings);
+}
+
+#[test]
+fn test_reads_as_value() {
+    let column = NullableVec::from(vec![None, Some(5u32)]);
+    let value = decode::<Value>(&mut encode(&column).unwrap()).unwrap();
+    assert_eq!(
+        value,
+        Value::Tuple(vec![
+            Value::UInt(2),
+            Value::Binary(Bytes::from_static(&[0b10])),
+            Value::List(vec![Value::UInt(5)]),
+        ])
+    );
+}
+
+#[test]
+fn test_rejects_inconsistent_columns() {
+    let column = |len: usize, bitmap: &[u8], values: Vec<u32>| {
+        let mut writer = BytesMut::new();
+        writer.put_u8(TAG_TUPLE);
+        3usize.encode(&mut writer).unwrap();
+        len.encode(&mut writer).unwrap();
+        Bytes::copy_from_slice(bitmap).encode(&mut writer).unwrap();
+        values.encode(&mut writer).unwrap();
+        writer.freeze()
+    };
+    assert!(NullableVec::<u32>::decode(&mut column(3, &[0b101], vec![1, 2])).is_ok());
+    // Bitmap too long for the element count
+    assert!(NullableVec::<u32>::decode(&mut column(3, &[0b101, 0], vec![1, 2])).is_err());
+    // More values than set bits
+    assert!(NullableVec::<u32>::decode(&mut column(3, &[0b001], vec![1, 2])).is_err());
+    // Truncated
+    let bytes = column(3, &[0b101], vec![1, 2]);
+    assert!(NullableVec::<u32>::decode(&mut bytes.slice(..bytes.len() - 1)).is_err());
+}
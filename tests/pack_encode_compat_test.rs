@@ -0,0 +1,60 @@
+use senax_encoder::compat::{assert_pack_encode_equivalent, compare_pack_encode};
+use senax_encoder::core::TAG_STRUCT_UNNAMED;
+use senax_encoder::{Encode, Pack};
+
+#[derive(Encode, Pack, Default)]
+struct Sample(u8, u64, i16, i128, bool, char, String);
+
+#[derive(Encode, Pack, Default)]
+struct WithFloat(u32, f64);
+
+#[derive(Encode, Pack, Default)]
+struct Named {
+    id: u32,
+}
+
+#[test]
+fn test_scalar_tuple_structs_coincide_after_header() {
+    assert_pack_encode_equivalent::<Sample>();
+    let report =
+        compare_pack_encode(&Sample(100, u64::MAX, -3, i128::MIN, true, 'é', "x".into())).unwrap();
+    assert!(report.differs_only_by_header(), "{}", report);
+    assert_eq!(report.encoded[0], TAG_STRUCT_UNNAMED);
+    assert_eq!(report.first_difference(), Some(0));
+}
+
+#[test]
+fn test_scalars_are_identical() {
+    for report in [
+        compare_pack_encode(&1234u32).unwrap(),
+        compare_pack_encode(&-9i64).unwrap(),
+        compare_pack_encode(&"text".to_string()).unwrap(),
+    ] {
+        assert!(report.is_identical(), "{}", report);
+    }
+}
+
+#[test]
+fn test_differences_are_described() {
+    let report = compare_pack_encode(&WithFloat(1, 0.5)).unwrap();
+    assert!(!report.is_equivalent());
+    assert_eq!(report.first_difference(), Some(0));
+    assert!(report
+        .to_string()
+        .starts_with("encode and pack bodies differ at byte 0"));
+
+    // u8 values above 127 are packed as a raw byte
+    let report = compare_pack_encode(&200u8).unwrap();
+    assert_eq!(report.encoded.as_ref(), &[131, 72]);
+    assert_eq!(report.packed.as_ref(), &[200]);
+
+    assert!(!compare_pack_encode(&Named { id: 1 })
+        .unwrap()
+        .is_equivalent());
+}
+
+#[test]
+#[should_panic(expected = "WithFloat: encode and pack bodies differ")]
+fn test_assert_reports_floats() {
+    assert_pack_encode_equivalent::<WithFloat>();
+}
@@ -1675,3 +1675,37 @@ fn test_ulid_default_and_non_default_pack_unpack() {
         assert_eq!(original, unpacked, "Failed for ULID: {}", original);
     }
 }
+
+#[test]
+fn test_repack_and_unpack_to_encode() {
+    let original = SimpleStruct {
+        id: 7,
+        name: "archive".to_string(),
+        active: true,
+    };
+
+    let mut encoded = encode(&original).unwrap();
+    let encoded_len = encoded.len();
+    let packed = senax_encoder::repack::<SimpleStruct>(&mut encoded).unwrap();
+    assert!(packed.len() < encoded_len);
+    assert_eq!(packed, pack(&original).unwrap());
+
+    let mut reader = packed.clone();
+    let unpacked: SimpleStruct = unpack(&mut reader).unwrap();
+    assert_eq!(original, unpacked);
+
+    let mut reader = packed;
+    let mut reencoded = senax_encoder::unpack_to_encode::<SimpleStruct>(&mut reader).unwrap();
+    let decoded: SimpleStruct = senax_encoder::decode(&mut reencoded).unwrap();
+    assert_eq!(original, decoded);
+}
+
+#[test]
+fn test_repack_rejects_wrong_magic() {
+    let original = TupleStruct(1, "x".to_string(), false);
+    let mut packed = pack(&original).unwrap();
+    assert!(senax_encoder::repack::<TupleStruct>(&mut packed).is_err());
+
+    let mut encoded = encode(&original).unwrap();
+    assert!(senax_encoder::unpack_to_encode::<TupleStruct>(&mut encoded).is_err());
+}
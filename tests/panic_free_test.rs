@@ -0,0 +1,172 @@
+use bytes::Bytes;
+use senax_encoder::debug::{diff, inspect, inspect_tree};
+use senax_encoder::self_describing::{
+    decode_named, decode_self_describing, encode_named, encode_self_describing,
+};
+use senax_encoder::{
+    decode, decode_exact, encode, pack, peek_info, query, unpack, validate_payload, Decode, Encode,
+    NullableVec, Pack, Unpack, Value,
+};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+#[derive(Encode, Decode, Pack, Unpack, Debug, PartialEq, Default, Clone)]
+enum Kind {
+    #[default]
+    Empty,
+    Point(i32, i32),
+    Label {
+        text: String,
+        weight: Option<f32>,
+    },
+}
+
+#[derive(Encode, Decode, Pack, Unpack, Debug, PartialEq, Default, Clone)]
+struct Record {
+    id: u64,
+    delta: i128,
+    ratio: f64,
+    name: String,
+    tags: Vec<String>,
+    blob: Bytes,
+    scores: BTreeMap<String, u32>,
+    index: HashMap<u16, Vec<i8>>,
+    set: BTreeSet<u8>,
+    pair: (u8, String, bool),
+    fixed: [u16; 3],
+    note: Option<String>,
+    sparse: NullableVec<u32>,
+    kinds: Vec<Kind>,
+    char_field: char,
+}
+
+fn record() -> Record {
+    Record {
+        id: 1 << 40,
+        delta: -(1 << 100),
+        ratio: 0.5,
+        name: "record".to_string(),
+        tags: vec!["a".to_string(), "bc".to_string()],
+        blob: Bytes::from_static(&[1, 2, 3]),
+        scores: [("x".to_string(), 300)].into(),
+        index: [(7, vec![-1, 2])].into(),
+        set: [1, 2, 3].into(),
+        pair: (9, "p".to_string(), true),
+        fixed: [1, 1000, 65535],
+        note: Some("n".to_string()),
+        sparse: vec![None, Some(5), None].into(),
+        kinds: vec![
+            Kind::Empty,
+            Kind::Point(-3, 4),
+            Kind::Label {
+                text: "t".to_string(),
+                weight: Some(1.5),
+            },
+        ],
+        char_field: 'é',
+    }
+}
+
+/// A small xorshift generator, so failures reproduce without a seed file.
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+}
+
+/// Truncations, single-byte corruptions and random tails of `bytes`.
+fn mutations(bytes: &[u8], rng: &mut Rng) -> Vec<Vec<u8>> {
+    let mut out = Vec::new();
+    for len in 0..bytes.len() {
+        out.push(bytes[..len].to_vec());
+    }
+    for i in 0..bytes.len() {
+        for value in [0x00, 0x01, 0x7f, 0x80, 0xfe, 0xff, rng.next() as u8] {
+            let mut mutated = bytes.to_vec();
+            mutated[i] = value;
+            out.push(mutated);
+        }
+    }
+    for _ in 0..200 {
+        let keep = rng.next() as usize % (bytes.len() + 1);
+        let mut mutated = bytes[..keep].to_vec();
+        for _ in 0..rng.next() % 24 {
+            mutated.push(rng.next() as u8);
+        }
+        out.push(mutated);
+    }
+    out
+}
+
+/// Runs every byte-reading entry point on `input` and fails the test if any of them panics.
+fn read_everything(input: &[u8]) {
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        let bytes = Bytes::copy_from_slice(input);
+        let _ = decode::<Record>(&mut bytes.clone());
+        let _ = decode_exact::<Record>(&mut bytes.clone());
+        let _ = decode::<Value>(&mut bytes.clone());
+        let _ = unpack::<Record>(&mut bytes.clone());
+        let _ = decode_self_describing(&mut bytes.clone());
+        let _ = decode_named::<Record>(&mut bytes.clone());
+        let _ = validate_payload::<Record>(input);
+        let _ = peek_info(&bytes);
+        let _ = query(&bytes, "kinds[2].text");
+        let _ = inspect(input);
+        let _ = inspect_tree(input);
+        let _ = diff(input, input);
+    }));
+    assert!(result.is_ok(), "panicked on input {:02x?}", input);
+}
+
+#[test]
+fn test_malformed_payloads_do_not_panic() {
+    let mut rng = Rng(0x9e37_79b9_7f4a_7c15);
+    let value = record();
+    for payload in [
+        encode(&value).unwrap(),
+        pack(&value).unwrap(),
+        encode_self_describing(&value).unwrap(),
+        encode_named(&value).unwrap(),
+        encode(&Record::default()).unwrap(),
+    ] {
+        for input in mutations(&payload, &mut rng) {
+            read_everything(&input);
+        }
+    }
+}
+
+#[test]
+fn test_random_payloads_do_not_panic() {
+    let mut rng = Rng(0x2545_f491_4f6c_dd1d);
+    for _ in 0..20_000 {
+        let len = rng.next() as usize % 40;
+        let mut input: Vec<u8> = (0..len).map(|_| rng.next() as u8).collect();
+        // Start most inputs with a valid magic number so decoding gets past it
+        if len >= 2 && rng.next() & 3 != 0 {
+            input[..2].copy_from_slice(&encode(&0u8).unwrap()[..2]);
+        }
+        read_everything(&input);
+    }
+}
+
+#[cfg(feature = "chrono")]
+#[test]
+fn test_out_of_range_dates_are_errors() {
+    use bytes::{BufMut, BytesMut};
+    use senax_encoder::core::TAG_CHRONO_NAIVE_DATE;
+    use senax_encoder::{Decoder, Encoder, Unpacker};
+
+    for days in [i64::MAX, i64::MIN, 1 << 40] {
+        let mut writer = BytesMut::new();
+        writer.put_u8(TAG_CHRONO_NAIVE_DATE);
+        days.encode(&mut writer).unwrap();
+        let bytes = writer.freeze();
+        assert!(chrono::NaiveDate::decode(&mut bytes.clone()).is_err());
+        assert!(chrono::NaiveDate::unpack(&mut bytes.clone()).is_err());
+    }
+}
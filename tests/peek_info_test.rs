@@ -0,0 +1,125 @@
+use bytes::Bytes;
+use senax_encoder::core::{
+    field_id_from_name, TAG_BINARY, TAG_ENUM_UNNAMED, TAG_MAP_STRING_KEYS, TAG_STRUCT_NAMED,
+};
+use senax_encoder::self_describing::{encode_named, encode_self_describing};
+use senax_encoder::{encode, peek_info, Encode, StringKeyMap, StructKind};
+
+#[derive(Encode)]
+enum Event {
+    Heartbeat,
+    Move(i32, i32),
+    #[senax(id = 7)]
+    Upload {
+        name: String,
+        data: Bytes,
+    },
+}
+
+#[derive(Encode)]
+struct Envelope {
+    topic: String,
+    event: Event,
+}
+
+#[derive(Encode)]
+struct Point(i32, i32, i32);
+
+#[test]
+fn test_structs_and_variants() {
+    let bytes = encode(&Envelope {
+        topic: "t".to_string(),
+        event: Event::Heartbeat,
+    })
+    .unwrap();
+    let info = peek_info(&bytes).unwrap();
+    assert_eq!(info.tag, TAG_STRUCT_NAMED);
+    assert_eq!(info.struct_kind, Some(StructKind::Named));
+    assert_eq!(info.variant_id, None);
+    assert_eq!(info.approx_len, bytes.len() - 2);
+
+    let info = peek_info(&encode(&Event::Move(1, 2)).unwrap()).unwrap();
+    assert_eq!(info.tag, TAG_ENUM_UNNAMED);
+    assert_eq!(info.struct_kind, Some(StructKind::Unnamed));
+    assert_eq!(info.variant_id, Some(field_id_from_name("Move")));
+    assert_eq!(info.count, Some(2));
+
+    let upload = Event::Upload {
+        name: "a".to_string(),
+        data: Bytes::from(vec![0; 1000]),
+    };
+    let info = peek_info(&encode(&upload).unwrap()).unwrap();
+    assert_eq!(info.variant_id, Some(7));
+    assert_eq!(info.struct_kind, Some(StructKind::Named));
+    assert!(info.approx_len > 1000);
+
+    let info = peek_info(&encode(&Event::Heartbeat).unwrap()).unwrap();
+    assert_eq!(info.struct_kind, Some(StructKind::Unit));
+
+    let info = peek_info(&encode(&Point(1, 2, 3)).unwrap()).unwrap();
+    assert_eq!(info.struct_kind, Some(StructKind::Unnamed));
+    assert_eq!(info.count, Some(3));
+}
+
+#[test]
+fn test_announced_counts() {
+    let info = peek_info(&encode(&vec![1u8; 300]).unwrap()).unwrap();
+    assert_eq!(info.count, Some(300));
+    assert_eq!(info.struct_kind, None);
+
+    let info = peek_info(&encode(&"x".repeat(70)).unwrap()).unwrap();
+    assert_eq!(info.count, Some(70));
+
+    let info = peek_info(&encode(&Bytes::from_static(b"abc")).unwrap()).unwrap();
+    assert_eq!((info.tag, info.count), (TAG_BINARY, Some(3)));
+
+    let map: StringKeyMap<u8> = [("a".to_string(), 1), ("b".to_string(), 2)]
+        .into_iter()
+        .collect();
+    let info = peek_info(&encode(&map).unwrap()).unwrap();
+    assert_eq!((info.tag, info.count), (TAG_MAP_STRING_KEYS, Some(2)));
+
+    let info = peek_info(&encode(&42u32).unwrap()).unwrap();
+    assert_eq!((info.count, info.approx_len), (None, 1));
+}
+
+#[test]
+fn test_other_formats() {
+    let upload = Event::Upload {
+        name: "a".to_string(),
+        data: Bytes::new(),
+    };
+    let info = peek_info(&encode_self_describing(&upload).unwrap()).unwrap();
+    assert_eq!(info.variant_id, Some(7));
+
+    let info = peek_info(&encode_self_describing(&Event::Move(1, 2)).unwrap()).unwrap();
+    assert_eq!(info.count, Some(2));
+
+    // Named documents carry the variant name; the ID is its hash
+    let info = peek_info(&encode_named(&upload).unwrap()).unwrap();
+    assert_eq!(info.variant_id, Some(field_id_from_name("Upload")));
+}
+
+#[test]
+fn test_does_not_consume_or_validate() {
+    let bytes = encode(&Event::Move(1, 2)).unwrap();
+    let before = bytes.clone();
+    peek_info(&bytes).unwrap();
+    assert_eq!(bytes, before);
+
+    // Only the header is read
+    let truncated = bytes.slice(..bytes.len() - 1);
+    assert_eq!(
+        peek_info(&truncated).unwrap().variant_id,
+        Some(field_id_from_name("Move"))
+    );
+}
+
+#[test]
+fn test_errors() {
+    assert!(peek_info(&Bytes::new()).is_err());
+    assert!(peek_info(&Bytes::from_static(&[0x5a, 0xa5])).is_err());
+    assert!(peek_info(&Bytes::from_static(&[0xda, 0xda, 0x01])).is_err());
+    // Variant ID cut off
+    assert!(peek_info(&Bytes::from_static(&[0x5a, 0xa5, 185, 255, 1])).is_err());
+}
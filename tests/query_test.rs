@@ -0,0 +1,143 @@
+use bytes::Bytes;
+use senax_encoder::core::field_id_from_name;
+use senax_encoder::self_describing::encode_self_describing;
+use senax_encoder::{decode, encode, query, Decode, Encode, QueryMatch, Value};
+use std::collections::BTreeMap;
+
+#[derive(Encode, Decode, Debug, PartialEq, Clone)]
+struct Item {
+    name: String,
+    quantity: u32,
+}
+
+#[derive(Encode, Decode, Debug, PartialEq, Clone)]
+enum Shipping {
+    Pickup,
+    Courier(String, u8),
+    Post { zip: String },
+}
+
+#[derive(Encode, Decode, Debug, PartialEq, Clone)]
+struct Order {
+    #[senax(id = 7)]
+    id: u64,
+    items: Vec<Item>,
+    note: Option<String>,
+    coupon: Option<String>,
+    #[senax(rename = "ship")]
+    shipping: Shipping,
+    tags: BTreeMap<String, i32>,
+}
+
+fn order() -> Order {
+    Order {
+        id: 1001,
+        items: vec![
+            Item {
+                name: "apple".to_string(),
+                quantity: 3,
+            },
+            Item {
+                name: "pear".to_string(),
+                quantity: 1,
+            },
+        ],
+        note: Some("fragile".to_string()),
+        coupon: None,
+        shipping: Shipping::Courier("acme".to_string(), 2),
+        tags: [("priority".to_string(), -1)].into_iter().collect(),
+    }
+}
+
+fn scalar(value: Value) -> Option<QueryMatch> {
+    Some(QueryMatch::Scalar(value))
+}
+
+#[test]
+fn test_field_id_from_name_matches_derive() {
+    let bytes = encode(&Item {
+        name: String::new(),
+        quantity: 0,
+    })
+    .unwrap();
+    // magic, TAG_STRUCT_NAMED, 255 marker, then the u64 ID of the first field
+    let id = u64::from_le_bytes(bytes[4..12].try_into().unwrap());
+    assert_eq!(id, field_id_from_name("name"));
+}
+
+#[test]
+fn test_query_paths() {
+    for bytes in [
+        encode(&order()).unwrap(),
+        encode_self_describing(&order()).unwrap(),
+    ] {
+        assert_eq!(query(&bytes, "7").unwrap(), scalar(Value::UInt(1001)));
+        assert_eq!(
+            query(&bytes, "items[1].name").unwrap(),
+            scalar(Value::String("pear".to_string()))
+        );
+        assert_eq!(
+            query(&bytes, "items[0].quantity").unwrap(),
+            scalar(Value::UInt(3))
+        );
+        assert_eq!(
+            query(&bytes, "note").unwrap(),
+            scalar(Value::String("fragile".to_string()))
+        );
+        assert_eq!(query(&bytes, "ship[1]").unwrap(), scalar(Value::UInt(2)));
+        assert_eq!(
+            query(&bytes, "tags.priority").unwrap(),
+            scalar(Value::Int(-1))
+        );
+
+        // Paths that do not exist
+        assert_eq!(query(&bytes, "coupon").unwrap(), None);
+        assert_eq!(query(&bytes, "items[2].name").unwrap(), None);
+        assert_eq!(query(&bytes, "shipping").unwrap(), None);
+        assert_eq!(query(&bytes, "items.name").unwrap(), None);
+        assert_eq!(query(&bytes, "tags.missing").unwrap(), None);
+    }
+}
+
+#[test]
+fn test_query_composite_returns_raw_bytes() {
+    let bytes = encode(&order()).unwrap();
+    let Some(QueryMatch::Raw(raw)) = query(&bytes, "items[1]").unwrap() else {
+        panic!("expected raw bytes");
+    };
+    let mut framed = vec![0x5A, 0xA5];
+    framed.extend_from_slice(&raw);
+    let item: Item = decode(&mut Bytes::from(framed)).unwrap();
+    assert_eq!(item, order().items[1]);
+}
+
+#[test]
+fn test_query_named_variant_and_top_level_index() {
+    let mut value = order();
+    value.shipping = Shipping::Post {
+        zip: "100-0001".to_string(),
+    };
+    let bytes = encode(&value).unwrap();
+    assert_eq!(
+        query(&bytes, "ship.zip").unwrap(),
+        scalar(Value::String("100-0001".to_string()))
+    );
+
+    let list = encode(&vec![10u32, 20, 30]).unwrap();
+    assert_eq!(query(&list, "[2]").unwrap(), scalar(Value::UInt(30)));
+
+    let unit = encode(&Shipping::Pickup).unwrap();
+    assert!(matches!(
+        query(&unit, "").unwrap_err(),
+        senax_encoder::EncoderError::Decode(_)
+    ));
+}
+
+#[test]
+fn test_query_rejects_invalid_paths() {
+    let bytes = encode(&order()).unwrap();
+    for path in ["items[", "items[x]", "items..name", "items]1[", ".name"] {
+        assert!(query(&bytes, path).is_err(), "path {:?}", path);
+    }
+    assert!(query(&Bytes::from_static(&[1, 2, 3]), "id").is_err());
+}
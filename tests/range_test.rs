@@ -0,0 +1,93 @@
+use senax_encoder::{decode, encode, Decode, Encode, EncoderError, ErrorCode, StructDecodeError};
+
+#[derive(Encode, Decode, Debug, PartialEq)]
+struct Reading {
+    #[senax(min = 1, max = 100)]
+    percent: u8,
+    #[senax(min = -40)]
+    celsius: i32,
+    #[senax(max = 1.0)]
+    ratio: f64,
+    #[senax(min = 10, max = 20)]
+    limit: Option<u16>,
+}
+
+#[derive(Encode, Decode, Debug, PartialEq)]
+struct RawReading {
+    percent: u8,
+    celsius: i32,
+    ratio: f64,
+    limit: Option<u16>,
+}
+
+fn raw(percent: u8, celsius: i32, ratio: f64, limit: Option<u16>) -> RawReading {
+    RawReading {
+        percent,
+        celsius,
+        ratio,
+        limit,
+    }
+}
+
+fn decode_reading(value: &RawReading) -> senax_encoder::Result<Reading> {
+    decode(&mut encode(value).unwrap())
+}
+
+#[test]
+fn test_values_in_range_decode() {
+    let reading = decode_reading(&raw(100, -40, 1.0, Some(10))).unwrap();
+    assert_eq!(
+        reading,
+        Reading {
+            percent: 100,
+            celsius: -40,
+            ratio: 1.0,
+            limit: Some(10),
+        }
+    );
+    assert_eq!(
+        decode_reading(&raw(1, 500, -3.5, None)).unwrap().limit,
+        None
+    );
+}
+
+#[test]
+fn test_out_of_range_errors_name_the_field() {
+    let err = decode_reading(&raw(0, 0, 0.5, None)).unwrap_err();
+    assert_eq!(err.code(), ErrorCode::StructOutOfRange);
+    assert_eq!(
+        err.to_string(),
+        "Field 'percent' of struct Reading is 0, outside the allowed range 1..=100"
+    );
+
+    let err = decode_reading(&raw(50, -41, 0.5, None)).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Field 'celsius' of struct Reading is -41, outside the allowed range -40.."
+    );
+
+    let err = decode_reading(&raw(50, 0, 1.5, None)).unwrap_err();
+    assert!(matches!(
+        err,
+        EncoderError::StructDecode(StructDecodeError::OutOfRange { field: "ratio", ref range, .. })
+            if &**range == "..=1"
+    ));
+
+    assert!(decode_reading(&raw(50, 0, 0.5, Some(21))).is_err());
+}
+
+#[test]
+fn test_nan_is_rejected() {
+    assert!(decode_reading(&raw(50, 0, f64::NAN, None)).is_err());
+}
+
+#[test]
+fn test_encode_is_not_checked() {
+    let reading = Reading {
+        percent: 200,
+        celsius: 0,
+        ratio: 0.0,
+        limit: None,
+    };
+    assert!(encode(&reading).is_ok());
+}
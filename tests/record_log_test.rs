@@ -0,0 +1,28 @@
+use senax_encoder::record_log::{RecordLogReader, RecordLogWriter, DEFAULT_MAX_RECORD_LEN};
+use senax_encoder::EncoderError;
+
+#[test]
+fn test_oversized_length_is_rejected_before_reading() {
+    // A corrupt length field announcing ~4 GiB
+    let mut log = (u32::MAX - 3).to_le_bytes().to_vec();
+    log.extend_from_slice(b"short");
+
+    let mut reader = RecordLogReader::new(log.as_slice());
+    assert_eq!(reader.max_record_len(), DEFAULT_MAX_RECORD_LEN);
+    let err = reader.read_record().unwrap_err();
+    assert!(matches!(err, EncoderError::Decode(_)), "{:?}", err);
+    assert_eq!(reader.offset(), 0);
+}
+
+#[test]
+fn test_max_record_len() {
+    let mut log = Vec::new();
+    let mut writer = RecordLogWriter::new(&mut log);
+    writer.append(&[1; 8]).unwrap();
+    writer.append(&[2; 9]).unwrap();
+
+    let mut reader = RecordLogReader::new(log.as_slice()).with_max_record_len(8);
+    assert_eq!(reader.read_record().unwrap().as_deref(), Some(&[1; 8][..]));
+    assert!(matches!(reader.read_record(), Err(EncoderError::Decode(_))));
+    assert_eq!(reader.offset(), 12);
+}
@@ -0,0 +1,168 @@
+use senax_encoder::record_log::{RecordLogReader, RecordLogWriter, TXN_BEGIN, TXN_COMMIT};
+use senax_encoder::{encode, Decode, Encode, EncoderError};
+
+#[derive(Encode, Decode, Debug, PartialEq, Clone)]
+struct Entry {
+    account: String,
+    amount: i64,
+}
+
+fn entry(account: &str, amount: i64) -> Entry {
+    Entry {
+        account: account.to_string(),
+        amount,
+    }
+}
+
+fn read_all(log: &[u8]) -> (Vec<Entry>, u64) {
+    let mut reader = RecordLogReader::new(log);
+    let mut entries = Vec::new();
+    while let Some(entry) = reader.read_value::<Entry>().unwrap() {
+        entries.push(entry);
+    }
+    (entries, reader.offset())
+}
+
+#[test]
+fn test_commit() {
+    let mut log = Vec::new();
+    let mut writer = RecordLogWriter::new(&mut log);
+    writer.append_value(&entry("opening", 100)).unwrap();
+    let mut txn = writer.transaction();
+    assert!(txn.is_empty());
+    txn.append_value(&entry("alice", -30)).unwrap();
+    txn.append_value(&entry("bob", 30)).unwrap();
+    assert_eq!(txn.len(), 2);
+    txn.commit().unwrap();
+    writer.append_value(&entry("closing", 100)).unwrap();
+
+    let (entries, offset) = read_all(&log);
+    assert_eq!(
+        entries,
+        [
+            entry("opening", 100),
+            entry("alice", -30),
+            entry("bob", 30),
+            entry("closing", 100)
+        ]
+    );
+    assert_eq!(offset, log.len() as u64);
+}
+
+#[test]
+fn test_rollback_and_drop_write_nothing() {
+    let mut log = Vec::new();
+    let mut writer = RecordLogWriter::new(&mut log);
+    let mut txn = writer.transaction();
+    txn.append_value(&entry("alice", -30)).unwrap();
+    txn.rollback();
+    {
+        let mut txn = writer.transaction();
+        txn.append_value(&entry("bob", 30)).unwrap();
+    }
+    writer.transaction().commit().unwrap();
+    assert!(log.is_empty());
+}
+
+#[test]
+fn test_uncommitted_tail_is_skipped() {
+    let mut committed = Vec::new();
+    let mut writer = RecordLogWriter::new(&mut committed);
+    writer.append_value(&entry("opening", 100)).unwrap();
+    let committed_len = committed.len() as u64;
+
+    let mut full = committed.clone();
+    let mut writer = RecordLogWriter::new(&mut full);
+    let mut txn = writer.transaction();
+    txn.append_value(&entry("alice", -30)).unwrap();
+    txn.append_value(&entry("bob", 30)).unwrap();
+    txn.commit().unwrap();
+
+    // Every crash point after the begin marker leaves only the committed prefix
+    for len in committed.len() + 4..full.len() {
+        let (entries, offset) = read_all(&full[..len]);
+        assert_eq!(entries, [entry("opening", 100)], "cut at {}", len);
+        assert_eq!(offset, committed_len, "cut at {}", len);
+    }
+    let (entries, offset) = read_all(&full);
+    assert_eq!(entries.len(), 3);
+    assert_eq!(offset, full.len() as u64);
+}
+
+#[test]
+fn test_truncate_at_offset_and_append() {
+    let mut log = Vec::new();
+    let mut writer = RecordLogWriter::new(&mut log);
+    let mut txn = writer.transaction();
+    txn.append_value(&entry("alice", -30)).unwrap();
+    txn.append_value(&entry("bob", 30)).unwrap();
+    txn.commit().unwrap();
+    let mut txn = writer.transaction();
+    txn.append_value(&entry("carol", -5)).unwrap();
+    txn.append_value(&entry("dave", 5)).unwrap();
+    txn.commit().unwrap();
+    log.truncate(log.len() - 3);
+
+    let (_, offset) = read_all(&log);
+    log.truncate(offset as usize);
+    let mut writer = RecordLogWriter::new(&mut log);
+    writer.append_value(&entry("erin", 1)).unwrap();
+
+    let (entries, _) = read_all(&log);
+    assert_eq!(
+        entries,
+        [entry("alice", -30), entry("bob", 30), entry("erin", 1)]
+    );
+}
+
+#[test]
+fn test_malformed_markers() {
+    let payload = encode(&entry("alice", 1)).unwrap();
+    let frame = |log: &mut Vec<u8>| {
+        log.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+        log.extend_from_slice(&payload);
+    };
+
+    // Commit count does not match the records in the transaction
+    let mut log = TXN_BEGIN.to_le_bytes().to_vec();
+    frame(&mut log);
+    log.extend_from_slice(&TXN_COMMIT.to_le_bytes());
+    log.extend_from_slice(&2u32.to_le_bytes());
+    let mut reader = RecordLogReader::new(log.as_slice());
+    assert!(matches!(reader.read_record(), Err(EncoderError::Decode(_))));
+
+    // Commit without a transaction
+    let mut log = TXN_COMMIT.to_le_bytes().to_vec();
+    log.extend_from_slice(&0u32.to_le_bytes());
+    let mut reader = RecordLogReader::new(log.as_slice());
+    assert!(matches!(reader.read_record(), Err(EncoderError::Decode(_))));
+
+    // Nested transaction
+    let mut log = TXN_BEGIN.to_le_bytes().to_vec();
+    frame(&mut log);
+    log.extend_from_slice(&TXN_BEGIN.to_le_bytes());
+    let mut reader = RecordLogReader::new(log.as_slice());
+    assert!(matches!(reader.read_record(), Err(EncoderError::Decode(_))));
+}
+
+#[test]
+fn test_torn_plain_frame_is_still_an_error() {
+    let mut log = Vec::new();
+    RecordLogWriter::new(&mut log)
+        .append_value(&entry("alice", 1))
+        .unwrap();
+    log.pop();
+    let mut reader = RecordLogReader::new(log.as_slice());
+    assert!(matches!(
+        reader.read_record(),
+        Err(EncoderError::UnexpectedEof { .. })
+    ));
+
+    // So is a torn begin marker, which cannot be told apart from a torn frame length
+    let begin = TXN_BEGIN.to_le_bytes();
+    let mut reader = RecordLogReader::new(&begin[..2]);
+    assert!(matches!(
+        reader.read_record(),
+        Err(EncoderError::UnexpectedEof { .. })
+    ));
+}
@@ -0,0 +1,184 @@
+use bytes::{Bytes, BytesMut};
+use senax_encoder::{
+    decode, decode_with_config, encode, pack, unpack, with_decode_config, Decode, DecodeConfig,
+    Decoder, Encode, Encoder, EncoderError, ErrorCode, Pack, Packer, Unpack, Unpacker,
+    DEFAULT_MAX_DEPTH,
+};
+use std::rc::Rc;
+
+#[derive(Encode, Decode, Pack, Unpack, Debug, PartialEq)]
+enum Expr {
+    Num(i64),
+    Add(Box<Expr>, Box<Expr>),
+    Neg { inner: Box<Expr> },
+    Call { name: String, args: Vec<Expr> },
+}
+
+#[derive(Encode, Decode, Pack, Unpack, Debug, PartialEq)]
+struct Tree {
+    label: String,
+    children: Vec<Tree>,
+    parent_hint: Option<Rc<Self>>,
+}
+
+// Recursive only through `Group`, so the attribute is needed
+#[derive(Encode, Decode, Debug, PartialEq)]
+#[senax(recursive)]
+struct Item {
+    group: Option<Box<Group>>,
+}
+
+#[derive(Encode, Decode, Debug, PartialEq)]
+struct Group {
+    items: Vec<Item>,
+}
+
+fn expr() -> Expr {
+    Expr::Add(
+        Box::new(Expr::Num(1)),
+        Box::new(Expr::Call {
+            name: "max".to_string(),
+            args: vec![
+                Expr::Neg {
+                    inner: Box::new(Expr::Num(2)),
+                },
+                Expr::Num(3),
+            ],
+        }),
+    )
+}
+
+/// Writes `levels` nested values around `leaf` without building (and recursively encoding or
+/// dropping) the value itself: each level is the bytes of `wrap(leaf)` before and after `leaf`.
+fn nested<T>(
+    levels: usize,
+    leaf: T,
+    wrap: fn(T) -> T,
+    write: fn(&T, &mut BytesMut) -> senax_encoder::Result<()>,
+) -> Bytes {
+    let mut buf = BytesMut::new();
+    write(&leaf, &mut buf).unwrap();
+    let leaf_bytes = buf.split().freeze();
+    write(&wrap(leaf), &mut buf).unwrap();
+    let one = buf.freeze();
+    let at = one
+        .windows(leaf_bytes.len())
+        .position(|w| w == &leaf_bytes[..])
+        .unwrap();
+    let (before, after) = (&one[..at], &one[at + leaf_bytes.len()..]);
+    let mut out = BytesMut::new();
+    for _ in 0..levels {
+        out.extend_from_slice(before);
+    }
+    out.extend_from_slice(&leaf_bytes);
+    for _ in 0..levels {
+        out.extend_from_slice(after);
+    }
+    out.freeze()
+}
+
+fn neg(inner: Expr) -> Expr {
+    Expr::Neg {
+        inner: Box::new(inner),
+    }
+}
+
+#[test]
+fn test_round_trip() {
+    let value = expr();
+    assert_eq!(decode::<Expr>(&mut encode(&value).unwrap()).unwrap(), value);
+    assert_eq!(unpack::<Expr>(&mut pack(&value).unwrap()).unwrap(), value);
+
+    let root = Rc::new(Tree {
+        label: "root".to_string(),
+        children: Vec::new(),
+        parent_hint: None,
+    });
+    let tree = Tree {
+        label: "a".to_string(),
+        children: vec![Tree {
+            label: "b".to_string(),
+            children: Vec::new(),
+            parent_hint: Some(root),
+        }],
+        parent_hint: None,
+    };
+    assert_eq!(decode::<Tree>(&mut encode(&tree).unwrap()).unwrap(), tree);
+    assert_eq!(unpack::<Tree>(&mut pack(&tree).unwrap()).unwrap(), tree);
+}
+
+#[test]
+fn test_depth_limit() {
+    // The outermost value counts as the first level
+    let mut ok = nested(DEFAULT_MAX_DEPTH - 1, Expr::Num(0), neg, Expr::encode);
+    assert!(Expr::decode(&mut ok).is_ok());
+
+    let mut deep = nested(DEFAULT_MAX_DEPTH, Expr::Num(0), neg, Expr::encode);
+    let err = Expr::decode(&mut deep).unwrap_err();
+    assert!(matches!(
+        err,
+        EncoderError::DepthLimitExceeded {
+            limit: DEFAULT_MAX_DEPTH
+        }
+    ));
+    assert_eq!(err.code(), ErrorCode::DepthLimitExceeded);
+
+    // Far deeper than the stack could take
+    let mut hostile = nested(1_000_000, Expr::Num(0), neg, Expr::encode);
+    assert!(Expr::decode(&mut hostile).is_err());
+    let mut hostile = nested(1_000_000, Expr::Num(0), neg, Expr::pack);
+    assert!(Expr::unpack(&mut hostile).is_err());
+}
+
+#[test]
+fn test_configured_depth() {
+    let shallow = DecodeConfig {
+        max_depth: 3,
+        ..Default::default()
+    };
+    let mut bytes = encode(&expr()).unwrap();
+    assert!(matches!(
+        decode_with_config::<Expr>(&mut bytes.clone(), &shallow),
+        Err(EncoderError::DepthLimitExceeded { limit: 3 })
+    ));
+    let enough = DecodeConfig {
+        max_depth: 4,
+        ..Default::default()
+    };
+    assert_eq!(
+        decode_with_config::<Expr>(&mut bytes, &enough).unwrap(),
+        expr()
+    );
+
+    let deeper = DecodeConfig {
+        max_depth: DEFAULT_MAX_DEPTH * 2,
+        ..Default::default()
+    };
+    let mut bytes = nested(DEFAULT_MAX_DEPTH, Expr::Num(0), neg, Expr::encode);
+    assert!(with_decode_config(&deeper, || Expr::decode(&mut bytes)).is_ok());
+}
+
+#[test]
+fn test_depth_is_restored_after_errors() {
+    for _ in 0..3 {
+        let mut hostile = nested(10_000, Expr::Num(0), neg, Expr::encode);
+        assert!(Expr::decode(&mut hostile).is_err());
+    }
+    let mut ok = nested(DEFAULT_MAX_DEPTH - 1, Expr::Num(0), neg, Expr::encode);
+    assert!(Expr::decode(&mut ok).is_ok());
+}
+
+#[test]
+fn test_recursive_attribute() {
+    let wrap = |item: Item| Item {
+        group: Some(Box::new(Group { items: vec![item] })),
+    };
+    let value = wrap(wrap(Item { group: None }));
+    assert_eq!(decode::<Item>(&mut encode(&value).unwrap()).unwrap(), value);
+
+    let mut hostile = nested(1_000_000, Item { group: None }, wrap, Item::encode);
+    assert!(matches!(
+        Item::decode(&mut hostile),
+        Err(EncoderError::DepthLimitExceeded { .. })
+    ));
+}
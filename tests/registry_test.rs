@@ -0,0 +1,78 @@
+use bytes::{Bytes, BytesMut};
+use senax_encoder::{message_registry, Decode, Encode, EncoderError, EnumDecodeError};
+
+#[derive(Encode, Decode, Debug, PartialEq, Clone)]
+struct Ping {
+    nonce: u64,
+}
+
+#[derive(Encode, Decode, Debug, PartialEq, Clone)]
+struct Order {
+    symbol: String,
+    quantity: i64,
+}
+
+message_registry! {
+    #[derive(Debug, PartialEq, Clone)]
+    pub enum Message {
+        Ping(Ping) = 1,
+        Order(Order) = 1000,
+        Raw(Vec<u8>) = 7,
+    }
+}
+
+fn roundtrip(message: &Message) -> Message {
+    let mut buf = BytesMut::new();
+    message.encode_message(&mut buf).unwrap();
+    let mut bytes = buf.freeze();
+    let decoded = Message::decode_message(&mut bytes).unwrap();
+    assert!(bytes.is_empty());
+    decoded
+}
+
+#[test]
+fn test_registry_roundtrip() {
+    let messages = [
+        Message::from(Ping { nonce: 5 }),
+        Message::from(Order {
+            symbol: "ACME".to_string(),
+            quantity: -20,
+        }),
+        Message::from(vec![1u8, 2, 3]),
+    ];
+    for message in &messages {
+        assert_eq!(&roundtrip(message), message);
+    }
+    assert_eq!(Message::TYPE_IDS, &[1, 1000, 7]);
+    assert_eq!(messages[1].message_type_id(), 1000);
+}
+
+#[test]
+fn test_registry_frame_layout() {
+    let mut buf = BytesMut::new();
+    Message::Ping(Ping { nonce: 1 })
+        .encode_message(&mut buf)
+        .unwrap();
+    assert_eq!(buf[0], 1);
+
+    // The payload after the type ID is a plain encoded message
+    let mut payload = buf.freeze().slice(1..);
+    assert_eq!(
+        Message::decode_payload(1, &mut payload).unwrap(),
+        Message::Ping(Ping { nonce: 1 })
+    );
+}
+
+#[test]
+fn test_registry_unknown_type_id() {
+    let result = Message::decode_payload(42, &mut Bytes::new());
+    assert!(matches!(
+        result,
+        Err(EncoderError::EnumDecode(
+            EnumDecodeError::UnknownVariantId {
+                variant_id: 42,
+                enum_name: "Message",
+            }
+        ))
+    ));
+}
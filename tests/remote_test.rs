@@ -0,0 +1,148 @@
+use senax_encoder::schema::{HasSchema, TypeSchema};
+use senax_encoder::{
+    decode, encode, pack, senax_remote, unpack, Decode, Encode, EncoderError, MaxEncodedSize, Pack,
+    Unpack,
+};
+use std::net::Ipv4Addr;
+
+// Stands in for a type from another crate
+mod calendar {
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    pub enum Weekday {
+        Mon,
+        Tue,
+        Wed,
+        Thu,
+        Fri,
+        Sat,
+        Sun,
+    }
+}
+use calendar::Weekday;
+
+const WEEK: [Weekday; 7] = [
+    Weekday::Mon,
+    Weekday::Tue,
+    Weekday::Wed,
+    Weekday::Thu,
+    Weekday::Fri,
+    Weekday::Sat,
+    Weekday::Sun,
+];
+
+fn weekday_from_u8(n: u8) -> senax_encoder::Result<Weekday> {
+    WEEK.get(n as usize)
+        .copied()
+        .ok_or_else(|| EncoderError::Decode(format!("invalid weekday {}", n)))
+}
+
+senax_remote! {
+    /// Writes a `Weekday` as its number from Monday.
+    pub struct WeekdayDef(Weekday) as u8 {
+        into: |day| *day as u8,
+        from: weekday_from_u8,
+    }
+}
+
+senax_remote! {
+    struct Ipv4Def(Ipv4Addr) as u32 {
+        into: |addr| u32::from(*addr),
+        from: |bits| Ok(Ipv4Addr::from(bits)),
+    }
+}
+
+#[derive(Encode, Decode, Pack, Unpack, Debug, PartialEq)]
+#[senax(schema, max_size)]
+struct Shift {
+    #[senax(with = "WeekdayDef")]
+    day: Weekday,
+    #[senax(with = "WeekdayDef")]
+    swap_day: Option<Weekday>,
+    #[senax(with = "Ipv4Def")]
+    terminal: Ipv4Addr,
+}
+
+#[derive(Encode, Decode, Debug, PartialEq)]
+struct ShiftV1 {
+    day: u8,
+    swap_day: Option<u8>,
+    terminal: u32,
+}
+
+#[test]
+fn test_round_trip() {
+    for shift in [
+        Shift {
+            day: Weekday::Fri,
+            swap_day: Some(Weekday::Sun),
+            terminal: Ipv4Addr::new(10, 0, 0, 7),
+        },
+        Shift {
+            day: Weekday::Mon,
+            swap_day: None,
+            terminal: Ipv4Addr::UNSPECIFIED,
+        },
+    ] {
+        let mut bytes = encode(&shift).unwrap();
+        assert_eq!(decode::<Shift>(&mut bytes).unwrap(), shift);
+        let mut packed = pack(&shift).unwrap();
+        assert_eq!(unpack::<Shift>(&mut packed).unwrap(), shift);
+    }
+}
+
+#[test]
+fn test_written_as_representation() {
+    let shift = Shift {
+        day: Weekday::Wed,
+        swap_day: Some(Weekday::Sat),
+        terminal: Ipv4Addr::new(127, 0, 0, 1),
+    };
+    let v1 = ShiftV1 {
+        day: 2,
+        swap_day: Some(5),
+        terminal: 0x7f000001,
+    };
+    assert_eq!(encode(&shift).unwrap(), encode(&v1).unwrap());
+    assert_eq!(decode::<Shift>(&mut encode(&v1).unwrap()).unwrap(), shift);
+
+    // Conversion failures surface as decode errors
+    let mut bytes = encode(&ShiftV1 {
+        day: 9,
+        swap_day: None,
+        terminal: 0,
+    })
+    .unwrap();
+    assert!(matches!(
+        decode::<Shift>(&mut bytes),
+        Err(EncoderError::Decode(_))
+    ));
+}
+
+#[test]
+fn test_schema_and_max_size_describe_representation() {
+    let TypeSchema::Struct(schema) = Shift::schema() else {
+        panic!("expected a struct schema");
+    };
+    assert_eq!(schema.fields[0].ty, u8::schema());
+    assert_eq!(schema.fields[1].ty, Option::<u8>::schema());
+    assert_eq!(schema.fields[2].ty, u32::schema());
+
+    let shift = Shift {
+        day: Weekday::Sun,
+        swap_day: Some(Weekday::Sun),
+        terminal: Ipv4Addr::BROADCAST,
+    };
+    assert!(encode(&shift).unwrap().len() - 2 <= Shift::MAX_ENCODED_SIZE);
+}
+
+#[test]
+fn test_direct_use() {
+    let mut buf = bytes::BytesMut::new();
+    senax_encoder::remote::encode::<WeekdayDef>(&Weekday::Thu, &mut buf).unwrap();
+    let mut bytes = buf.freeze();
+    assert_eq!(bytes, encode(&3u8).unwrap().slice(2..));
+    assert_eq!(
+        senax_encoder::remote::decode::<WeekdayDef>(&mut bytes).unwrap(),
+        Weekday::Thu
+    );
+}
@@ -0,0 +1,140 @@
+// The legacy types spell their names the way an older service wrote them
+#![allow(non_snake_case, non_camel_case_types)]
+
+use senax_encoder::core::field_id_from_name;
+use senax_encoder::self_describing::{decode_self_describing, encode_self_describing};
+use senax_encoder::value::Value;
+use senax_encoder::{decode, encode, Decode, Encode};
+
+/// A struct written by a service whose fields used camelCase names.
+#[derive(Encode, Decode, Debug, PartialEq)]
+struct LegacyAccount {
+    userId: u64,
+    displayName: String,
+    isActive: bool,
+}
+
+/// The same struct after standardizing on snake_case.
+#[derive(Encode, Decode, Debug, PartialEq)]
+#[senax(rename_all = "camelCase")]
+struct Account {
+    user_id: u64,
+    display_name: String,
+    #[senax(rename = "isActive")]
+    active: bool,
+}
+
+#[test]
+fn test_rename_all_keeps_wire_ids() {
+    let legacy = LegacyAccount {
+        userId: 7,
+        displayName: "Ana".to_string(),
+        isActive: true,
+    };
+    let account: Account = decode(&mut encode(&legacy).unwrap()).unwrap();
+    assert_eq!(
+        account,
+        Account {
+            user_id: 7,
+            display_name: "Ana".to_string(),
+            active: true,
+        }
+    );
+
+    let back: LegacyAccount = decode(&mut encode(&account).unwrap()).unwrap();
+    assert_eq!(back, legacy);
+}
+
+#[test]
+fn test_rename_all_names_in_self_describing_payloads() {
+    let account = Account {
+        user_id: 1,
+        display_name: "Bo".to_string(),
+        active: false,
+    };
+    let value: Value =
+        decode_self_describing(&mut encode_self_describing(&account).unwrap()).unwrap();
+    assert!(value.field("userId").is_some());
+    assert!(value.field("displayName").is_some());
+    assert!(value.field("user_id").is_none());
+}
+
+#[derive(Encode, Decode, Debug, PartialEq)]
+#[senax(rename_all = "snake_case")]
+enum Event {
+    UserCreated {
+        user_id: u64,
+    },
+    HTTPRequest(String),
+    #[senax(id = 99)]
+    Shutdown,
+}
+
+#[derive(Encode, Decode, Debug, PartialEq)]
+enum LegacyEvent {
+    user_created {
+        user_id: u64,
+    },
+    http_request(String),
+    #[senax(id = 99)]
+    shutdown,
+}
+
+#[test]
+fn test_rename_all_applies_to_variants() {
+    let events = [
+        (
+            Event::UserCreated { user_id: 3 },
+            LegacyEvent::user_created { user_id: 3 },
+        ),
+        (
+            Event::HTTPRequest("/".into()),
+            LegacyEvent::http_request("/".into()),
+        ),
+        (Event::Shutdown, LegacyEvent::shutdown),
+    ];
+    for (event, legacy) in events {
+        assert_eq!(encode(&event).unwrap(), encode(&legacy).unwrap());
+        let decoded: Event = decode(&mut encode(&legacy).unwrap()).unwrap();
+        assert_eq!(decoded, event);
+    }
+}
+
+#[derive(Encode, Decode, Debug, PartialEq)]
+#[senax(rename_all = "SCREAMING_SNAKE_CASE")]
+struct Screaming {
+    max_retries: u32,
+}
+
+#[derive(Encode, Decode, Debug, PartialEq)]
+#[senax(rename_all = "kebab-case")]
+struct Kebab {
+    max_retries: u32,
+}
+
+#[derive(Encode, Decode, Debug, PartialEq)]
+#[senax(rename_all = "PascalCase")]
+struct Pascal {
+    max_retries: u32,
+}
+
+#[test]
+fn test_rename_rules() {
+    fn field_id<T: senax_encoder::Encoder>(value: &T) -> u64 {
+        let bytes = encode(value).unwrap();
+        let mut reader = bytes.slice(3..); // magic and struct tag
+        senax_encoder::core::read_field_id_optimized(&mut reader).unwrap()
+    }
+    assert_eq!(
+        field_id(&Screaming { max_retries: 1 }),
+        field_id_from_name("MAX_RETRIES")
+    );
+    assert_eq!(
+        field_id(&Kebab { max_retries: 1 }),
+        field_id_from_name("max-retries")
+    );
+    assert_eq!(
+        field_id(&Pascal { max_retries: 1 }),
+        field_id_from_name("MaxRetries")
+    );
+}
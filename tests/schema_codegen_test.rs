@@ -0,0 +1,82 @@
+use senax_encoder::core::field_id_from_name;
+use senax_encoder::schema::codegen::{golang, typescript};
+use senax_encoder::schema::{FieldSchema, StructSchema, TypeSchema};
+
+fn address() -> TypeSchema {
+    TypeSchema::Struct(StructSchema::new(
+        "Address",
+        vec![FieldSchema::new("city", TypeSchema::String)],
+    ))
+}
+
+fn user() -> TypeSchema {
+    TypeSchema::Struct(StructSchema::new(
+        "User",
+        vec![
+            FieldSchema::new("user_name", TypeSchema::String),
+            FieldSchema::new("age", TypeSchema::UInt).with_id(300),
+            FieldSchema::new("email", TypeSchema::Option(Box::new(TypeSchema::String))),
+            FieldSchema::new("home", address()),
+            FieldSchema::new("past", TypeSchema::List(Box::new(address()))),
+            FieldSchema::new(
+                "scores",
+                TypeSchema::Map(Box::new(TypeSchema::String), Box::new(TypeSchema::Float)),
+            ),
+            FieldSchema::new("extra", TypeSchema::Any),
+        ],
+    ))
+}
+
+#[test]
+fn test_typescript_declares_types_and_decoders() {
+    let ts = typescript(&user());
+    assert!(ts.contains("export class SenaxReader {"));
+    assert!(ts.contains("export interface User {"));
+    assert!(ts.contains("  user_name: string;"));
+    assert!(ts.contains("  email?: string;"));
+    assert!(ts.contains("  past: Array<Address>;"));
+    assert!(ts.contains("  scores: Map<string, number>;"));
+    assert!(ts.contains("  extra?: unknown;"));
+    assert!(ts.contains("    home: defaultAddress(),"));
+    assert!(ts.contains("      case 300n: out.age = r.readUInt(); break;"));
+    let email_id = field_id_from_name("email");
+    assert!(ts.contains(&format!(
+        "      case {}n: out.email = r.readString(); break;",
+        email_id
+    )));
+    assert!(ts.contains("export function decodeUser(bytes: Uint8Array): User {"));
+    // Shared structs are emitted once
+    assert_eq!(ts.matches("export interface Address {").count(), 1);
+}
+
+#[test]
+fn test_golang_declares_types_and_decoders() {
+    let go = golang(&user());
+    assert!(go.starts_with("// Code generated by senax-encoder"));
+    assert!(go.contains("package senax"));
+    assert!(go.contains("type User struct {"));
+    assert!(go.contains("\tUserName string"));
+    assert!(go.contains("\tEmail *string"));
+    assert!(go.contains("\tPast []Address"));
+    assert!(go.contains("\tScores map[string]float64"));
+    assert!(go.contains("\t\tcase 300:\n\t\t\tout.Age = r.ReadUInt()"));
+    assert!(go.contains("out.Email = ptr(r.ReadString())"));
+    assert!(go.contains("func DecodeUser(buf []byte) (User, error) {"));
+    assert_eq!(go.matches("type Address struct {").count(), 1);
+}
+
+#[test]
+fn test_non_struct_root() {
+    let schema = TypeSchema::List(Box::new(TypeSchema::Option(Box::new(TypeSchema::Int))));
+    let ts = typescript(&schema);
+    assert!(
+        ts.contains("export function decodeValue(bytes: Uint8Array): Array<bigint | undefined> {")
+    );
+    assert!(ts.contains("return r.readList(() => r.readOption(() => r.readInt()));"));
+
+    let go = golang(&schema);
+    assert!(go.contains("func DecodeValue(buf []byte) ([]*int64, error) {"));
+    assert!(go.contains(
+        "readList(r, func() *int64 { return readOption(r, func() int64 { return r.ReadInt() }) })"
+    ));
+}
@@ -0,0 +1,291 @@
+use senax_encoder::core::field_id_from_name;
+use senax_encoder::schema::{
+    check_compat, CompatChange, Compatibility, FieldSchema, HasSchema, SchemaChange, StructSchema,
+    TypeSchema,
+};
+use senax_encoder::Encode;
+
+mod v1 {
+    use senax_encoder::Encode;
+
+    #[derive(Encode)]
+    #[senax(schema)]
+    pub struct Order {
+        pub id: u64,
+        pub customer: String,
+        pub note: Option<String>,
+        pub lines: Vec<Line>,
+        pub status: Status,
+    }
+
+    #[derive(Encode)]
+    #[senax(schema)]
+    pub struct Line {
+        pub sku: String,
+        pub quantity: u32,
+    }
+
+    #[derive(Encode)]
+    #[senax(schema)]
+    #[allow(dead_code)]
+    pub enum Status {
+        Open,
+        Shipped { carrier: String },
+        Legacy,
+    }
+}
+
+mod v2 {
+    use senax_encoder::Encode;
+
+    #[derive(Encode)]
+    #[senax(schema)]
+    pub struct Order {
+        pub id: u64,
+        #[senax(rename = "customer")]
+        pub customer_name: String,
+        #[senax(default)]
+        pub priority: u8,
+        pub lines: Vec<Line>,
+        pub status: Status,
+    }
+
+    #[derive(Encode)]
+    #[senax(schema)]
+    pub struct Line {
+        pub sku: String,
+        pub quantity: i64,
+    }
+
+    #[derive(Encode)]
+    #[senax(schema)]
+    #[allow(dead_code)]
+    pub enum Status {
+        Open,
+        Shipped { carrier: String },
+        Cancelled,
+    }
+}
+
+fn change(path: &str, change: SchemaChange, compatibility: Compatibility) -> CompatChange {
+    CompatChange {
+        path: path.to_string(),
+        change,
+        compatibility,
+    }
+}
+
+#[test]
+fn test_identical_schemas() {
+    let report = check_compat(&v1::Order::schema(), &v1::Order::schema());
+    assert!(report.changes().is_empty());
+    assert_eq!(report.compatibility(), Compatibility::Full);
+    assert!(report.is_backward_compatible() && report.is_forward_compatible());
+}
+
+#[test]
+fn test_classifies_changes() {
+    let report = check_compat(&v1::Order::schema(), &v2::Order::schema());
+    assert_eq!(
+        report.changes(),
+        [
+            change(
+                "value.note",
+                SchemaChange::FieldRemoved {
+                    id: field_id_from_name("note"),
+                    required: false,
+                },
+                Compatibility::Full,
+            ),
+            change(
+                "value.lines.quantity",
+                SchemaChange::TypeChanged {
+                    old: "unsigned integer",
+                    new: "signed integer",
+                },
+                Compatibility::Backward,
+            ),
+            change(
+                "value.status::Legacy",
+                SchemaChange::VariantRemoved {
+                    id: field_id_from_name("Legacy"),
+                },
+                Compatibility::Forward,
+            ),
+            change(
+                "value.status::Cancelled",
+                SchemaChange::VariantAdded {
+                    id: field_id_from_name("Cancelled"),
+                },
+                Compatibility::Backward,
+            ),
+            change(
+                "value.priority",
+                SchemaChange::FieldAdded {
+                    id: field_id_from_name("priority"),
+                    required: false,
+                },
+                Compatibility::Full,
+            ),
+        ]
+    );
+    assert_eq!(report.compatibility(), Compatibility::Breaking);
+    assert!(report.is_breaking());
+    assert_eq!(report.incompatible_changes().count(), 3);
+
+    let text = report.to_string();
+    assert!(text.starts_with("breaking\n"), "{}", text);
+    assert!(
+        text.contains("  value.status::Cancelled: variant added"),
+        "{}",
+        text
+    );
+}
+
+fn user(fields: Vec<FieldSchema>) -> TypeSchema {
+    TypeSchema::Struct(StructSchema::new("User", fields))
+}
+
+#[test]
+fn test_required_fields() {
+    let v1 = user(vec![FieldSchema::new("name", TypeSchema::String)]);
+    let v2 = user(vec![
+        FieldSchema::new("name", TypeSchema::String),
+        FieldSchema::new("email", TypeSchema::String),
+    ]);
+
+    // Old readers skip the new field, new readers fail on old data without it
+    let report = check_compat(&v1, &v2);
+    assert_eq!(report.compatibility(), Compatibility::Forward);
+    // Removing it again is the mirror image
+    let report = check_compat(&v2, &v1);
+    assert_eq!(report.compatibility(), Compatibility::Backward);
+    assert_eq!(
+        report.changes()[0].change,
+        SchemaChange::FieldRemoved {
+            id: field_id_from_name("email"),
+            required: true,
+        }
+    );
+
+    // Renaming a field keeps its ID
+    let renamed = user(vec![
+        FieldSchema::new("full_name", TypeSchema::String).with_id(field_id_from_name("name"))
+    ]);
+    let report = check_compat(&v1, &renamed);
+    assert_eq!(
+        report.changes()[0].change,
+        SchemaChange::Renamed {
+            old: "name".to_string(),
+            new: "full_name".to_string(),
+        }
+    );
+    assert_eq!(report.compatibility(), Compatibility::Full);
+
+    // A default makes the field safe to add
+    let v2 = user(vec![
+        FieldSchema::new("name", TypeSchema::String),
+        FieldSchema::new("email", TypeSchema::String).with_default(),
+    ]);
+    assert_eq!(check_compat(&v1, &v2).compatibility(), Compatibility::Full);
+
+    // Making an optional field required
+    let v1 = user(vec![FieldSchema::new(
+        "name",
+        TypeSchema::Option(Box::new(TypeSchema::String)),
+    )]);
+    let v2 = user(vec![FieldSchema::new("name", TypeSchema::String)]);
+    let report = check_compat(&v1, &v2);
+    assert_eq!(
+        report.changes()[0].change,
+        SchemaChange::RequiredChanged { required: true }
+    );
+    assert_eq!(report.compatibility(), Compatibility::Forward);
+}
+
+#[test]
+fn test_breaking_changes() {
+    let v1 = user(vec![
+        FieldSchema::new("age", TypeSchema::UInt),
+        FieldSchema::new("pos", TypeSchema::Tuple(vec![TypeSchema::Int; 2])),
+    ]);
+    let v2 = user(vec![
+        FieldSchema::new("age", TypeSchema::String),
+        FieldSchema::new("pos", TypeSchema::Tuple(vec![TypeSchema::Int; 3])),
+        FieldSchema::new("years", TypeSchema::Option(Box::new(TypeSchema::UInt)))
+            .with_id(field_id_from_name("age")),
+    ]);
+    let report = check_compat(&v1, &v2);
+    let changes: Vec<_> = report
+        .changes()
+        .iter()
+        .map(|c| (c.path.as_str(), c.change.clone(), c.compatibility))
+        .collect();
+    assert_eq!(
+        changes,
+        [
+            (
+                "value",
+                SchemaChange::DuplicateId {
+                    id: field_id_from_name("age"),
+                    first: "age".to_string(),
+                    second: "years".to_string(),
+                },
+                Compatibility::Breaking,
+            ),
+            (
+                "value.age",
+                SchemaChange::TypeChanged {
+                    old: "unsigned integer",
+                    new: "string",
+                },
+                Compatibility::Breaking,
+            ),
+            (
+                "value.pos",
+                SchemaChange::ArityChanged { old: 2, new: 3 },
+                Compatibility::Breaking,
+            ),
+        ]
+    );
+
+    // `Any` matches every type
+    let v2 = user(vec![
+        FieldSchema::new("age", TypeSchema::Any),
+        FieldSchema::new("pos", TypeSchema::Any),
+    ]);
+    assert!(check_compat(&v1, &v2).changes().is_empty());
+}
+
+#[test]
+fn test_compatibility_combination() {
+    use Compatibility::*;
+    assert_eq!(Full.and(Backward), Backward);
+    assert_eq!(Backward.and(Backward), Backward);
+    assert_eq!(Backward.and(Forward), Breaking);
+    assert_eq!(Forward.and(Breaking), Breaking);
+    assert!(Full.is_backward() && Full.is_forward());
+    assert!(!Breaking.is_backward() && !Breaking.is_forward());
+}
+
+#[derive(Encode)]
+#[senax(schema)]
+struct Defaults {
+    a: u32,
+    #[senax(default)]
+    b: u32,
+    #[senax(skip_default)]
+    c: u32,
+    #[senax(skip_decode)]
+    d: u32,
+    e: Option<u32>,
+}
+
+#[test]
+fn test_derived_required_flags() {
+    let TypeSchema::Struct(schema) = Defaults::schema() else {
+        panic!("expected a struct schema");
+    };
+    let required: Vec<_> = schema.fields.iter().map(|f| f.is_required()).collect();
+    assert_eq!(required, [true, false, false, false, false]);
+}
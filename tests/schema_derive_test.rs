@@ -0,0 +1,190 @@
+use senax_encoder::core::field_id_from_name;
+use senax_encoder::schema::{
+    codegen, EnumSchema, FieldSchema, HasSchema, SchemaResolver, StructSchema, TupleStructSchema,
+    TypeSchema, VariantSchema,
+};
+use senax_encoder::value::Value;
+use senax_encoder::{decode, encode, Decode, Decoder, Encode, Encoder};
+use std::collections::{BTreeMap, HashSet};
+
+#[derive(Encode, Decode, Debug, PartialEq)]
+#[senax(schema)]
+struct UserId(u64);
+
+#[derive(Encode, Decode, Debug, PartialEq)]
+#[senax(schema, rename_all = "camelCase")]
+struct Account {
+    #[senax(id = 1)]
+    account_id: UserId,
+    display_name: String,
+    #[senax(rename = "mail")]
+    email: Option<String>,
+    scores: BTreeMap<String, f64>,
+    roles: HashSet<Role>,
+    position: (i32, i32),
+    #[senax(skip_encode)]
+    session: u32,
+    #[senax(deprecated)]
+    fax: String,
+}
+
+#[derive(Encode, Decode, Debug, PartialEq, Eq, Hash)]
+#[senax(schema)]
+enum Role {
+    #[senax(id = 1)]
+    Admin,
+    Member {
+        team: String,
+    },
+    Guest(u32, bool),
+}
+
+#[derive(Encode, Decode, Debug, PartialEq)]
+#[senax(schema, string_repr)]
+enum Level {
+    Low,
+    #[senax(rename = "HIGH")]
+    High,
+}
+
+#[derive(Encode, Decode, Debug, PartialEq)]
+#[senax(schema)]
+struct Tree<T: Encoder + Decoder + 'static> {
+    value: T,
+    children: Vec<Tree<T>>,
+}
+
+#[test]
+fn test_struct_schema() {
+    let expected = TypeSchema::Struct(StructSchema::new(
+        "Account",
+        vec![
+            FieldSchema::new(
+                "accountId",
+                TypeSchema::TupleStruct(TupleStructSchema {
+                    name: "UserId".to_string(),
+                    fields: vec![TypeSchema::UInt],
+                }),
+            )
+            .with_id(1),
+            FieldSchema::new("displayName", TypeSchema::String),
+            FieldSchema::new("mail", TypeSchema::Option(Box::new(TypeSchema::String))),
+            FieldSchema::new(
+                "scores",
+                TypeSchema::Map(Box::new(TypeSchema::String), Box::new(TypeSchema::Float)),
+            ),
+            FieldSchema::new("roles", TypeSchema::List(Box::new(Role::schema()))),
+            FieldSchema::new(
+                "position",
+                TypeSchema::Tuple(vec![TypeSchema::Int, TypeSchema::Int]),
+            ),
+        ],
+    ));
+    assert_eq!(Account::schema(), expected);
+
+    // The IDs are the ones the encoder writes
+    let account = Account {
+        account_id: UserId(7),
+        display_name: "alice".to_string(),
+        email: Some("alice@example.com".to_string()),
+        scores: [("math".to_string(), 9.5)].into(),
+        roles: [Role::Admin].into(),
+        position: (1, -1),
+        session: 3,
+        fax: String::new(),
+    };
+    let mut bytes = encode(&account).unwrap();
+    let Value::Struct(fields) = decode::<Value>(&mut bytes).unwrap() else {
+        panic!("expected a struct value");
+    };
+    let TypeSchema::Struct(schema) = expected else {
+        unreachable!()
+    };
+    let written: Vec<_> = fields.iter().map(|f| f.id).collect();
+    let described: Vec<_> = schema.fields.iter().map(|f| f.id).collect();
+    assert_eq!(written, described);
+}
+
+#[test]
+fn test_enum_schema() {
+    assert_eq!(
+        Role::schema(),
+        TypeSchema::Enum(EnumSchema {
+            name: "Role".to_string(),
+            variants: vec![
+                VariantSchema {
+                    name: "Admin".to_string(),
+                    id: 1,
+                    fields: None,
+                },
+                VariantSchema {
+                    name: "Member".to_string(),
+                    id: field_id_from_name("Member"),
+                    fields: Some(TypeSchema::Struct(StructSchema::new(
+                        "Member",
+                        vec![FieldSchema::new("team", TypeSchema::String)],
+                    ))),
+                },
+                VariantSchema {
+                    name: "Guest".to_string(),
+                    id: field_id_from_name("Guest"),
+                    fields: Some(TypeSchema::Tuple(vec![TypeSchema::UInt, TypeSchema::Bool])),
+                },
+            ],
+            string_repr: false,
+        })
+    );
+
+    let TypeSchema::Enum(level) = Level::schema() else {
+        panic!("expected an enum schema");
+    };
+    assert!(level.string_repr);
+    let names: Vec<_> = level.variants.iter().map(|v| v.name.as_str()).collect();
+    assert_eq!(names, ["Low", "HIGH"]);
+}
+
+#[test]
+fn test_recursive_types() {
+    let TypeSchema::Struct(tree) = Tree::<u8>::schema() else {
+        panic!("expected a struct schema");
+    };
+    assert_eq!(tree.fields[0].ty, TypeSchema::UInt);
+    assert_eq!(
+        tree.fields[1].ty,
+        TypeSchema::List(Box::new(TypeSchema::Any))
+    );
+
+    // The same type nested in a sibling field is described again
+    assert_eq!(
+        <(Tree<u8>, Tree<u8>)>::schema(),
+        TypeSchema::Tuple(vec![Tree::<u8>::schema(), Tree::<u8>::schema()])
+    );
+}
+
+#[test]
+fn test_schemas_round_trip_and_resolve() {
+    let schema = Account::schema();
+    let mut bytes = encode(&schema).unwrap();
+    let decoded: TypeSchema = decode(&mut bytes).unwrap();
+    assert_eq!(decoded, schema);
+
+    SchemaResolver::new(&schema, &schema).unwrap();
+    SchemaResolver::new(&Role::schema(), &Role::schema()).unwrap();
+
+    // A changed tuple element type is reported
+    let mut changed = schema.clone();
+    if let TypeSchema::Struct(s) = &mut changed {
+        s.fields[5].ty = TypeSchema::Tuple(vec![TypeSchema::Int, TypeSchema::String]);
+    }
+    let err = SchemaResolver::new(&schema, &changed).unwrap_err();
+    assert!(err.to_string().contains("value.position"), "{}", err);
+}
+
+#[test]
+fn test_codegen_reads_tuples_and_enums_as_untyped_values() {
+    let ts = codegen::typescript(&Account::schema());
+    assert!(ts.contains("roles: Array<unknown>;"), "{}", ts);
+    assert!(ts.contains("position?: unknown;"), "{}", ts);
+    let go = codegen::golang(&Account::schema());
+    assert!(go.contains("Position any"), "{}", go);
+}
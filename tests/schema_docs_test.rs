@@ -0,0 +1,160 @@
+use senax_encoder::core::field_id_from_name;
+use senax_encoder::schema::codegen::{golang, markdown, typescript};
+use senax_encoder::schema::{HasSchema, TypeSchema};
+use senax_encoder::{decode, encode, Decode, Encode};
+
+/// An order placed by a customer.
+#[derive(Encode, Decode, Debug, PartialEq)]
+#[senax(schema)]
+struct Order {
+    /// The order number.
+    #[senax(id = 1)]
+    id: u64,
+    /// Who placed the order.
+    ///
+    /// Matches `Customer.id` | never empty.
+    customer: String,
+    #[senax(default)]
+    lines: Vec<Line>,
+    /// Free text, if any.
+    note: Option<String>,
+    status: Status,
+}
+
+#[derive(Encode, Decode, Debug, PartialEq)]
+#[senax(schema)]
+struct Line {
+    /// Stock keeping unit.
+    sku: String,
+    quantity: u32,
+}
+
+#[derive(Encode, Decode, Debug, PartialEq)]
+#[senax(schema)]
+enum Status {
+    Open,
+    Shipped {
+        /// The carrier handling the delivery.
+        carrier: String,
+    },
+}
+
+#[derive(Encode, Decode, Debug, PartialEq)]
+#[senax(schema)]
+struct Undocumented {
+    id: u64,
+}
+
+fn fields(schema: TypeSchema) -> Vec<(String, Option<String>)> {
+    let TypeSchema::Struct(schema) = schema else {
+        panic!("expected a struct schema");
+    };
+    schema.fields.into_iter().map(|f| (f.name, f.doc)).collect()
+}
+
+#[test]
+fn test_docs_are_captured() {
+    let docs = fields(Order::schema());
+    assert_eq!(
+        docs[0],
+        ("id".to_string(), Some("The order number.".to_string()))
+    );
+    assert_eq!(
+        docs[1].1.as_deref(),
+        Some("Who placed the order.\n\nMatches `Customer.id` | never empty.")
+    );
+    assert_eq!(docs[2].1, None);
+
+    let TypeSchema::Enum(status) = Status::schema() else {
+        panic!("expected an enum schema");
+    };
+    let Some(shipped) = status.variants[1].fields.clone() else {
+        panic!("expected fields");
+    };
+    assert_eq!(
+        fields(shipped)[0].1.as_deref(),
+        Some("The carrier handling the delivery.")
+    );
+    assert!(fields(Undocumented::schema())[0].1.is_none());
+}
+
+#[test]
+fn test_docs_are_not_written_to_payloads() {
+    #[derive(Encode)]
+    struct Plain {
+        id: u64,
+    }
+
+    let bytes = encode(&Undocumented { id: 5 }).unwrap();
+    assert_eq!(bytes, encode(&Plain { id: 5 }).unwrap());
+    assert_eq!(
+        decode::<Undocumented>(&mut bytes.clone()).unwrap(),
+        Undocumented { id: 5 }
+    );
+
+    // Schemas with and without docs stay readable by each other
+    let schema = Order::schema();
+    let decoded: TypeSchema = decode(&mut encode(&schema).unwrap()).unwrap();
+    assert_eq!(decoded, schema);
+}
+
+#[test]
+fn test_markdown() {
+    let md = markdown(&Order::schema());
+    let expected = r#"## Order
+
+| Field | ID | Type | Required | Description |
+|---|---|---|---|---|
+| `id` | 1 | uint | yes | The order number. |
+| `customer` | 1331277141224594917 | string | yes | Who placed the order.<br><br>Matches `Customer.id` \| never empty. |
+| `lines` | 826401739278535577 | list&lt;[Line](#line)&gt; | no |  |
+| `note` | 10732702548645970498 | option&lt;string&gt; | no | Free text, if any. |
+| `status` | 16477208882361427967 | [Status](#status) | yes |  |
+
+## Line
+
+| Field | ID | Type | Required | Description |
+|---|---|---|---|---|
+| `sku` | 17042585110070829462 | string | yes | Stock keeping unit. |
+| `quantity` | 2659515865594842913 | uint | yes |  |
+
+## Status
+
+| Variant | ID | Fields |
+|---|---|---|
+| `Open` | 8010062246221941349 |  |
+| `Shipped` | 8412991014849270107 | [Shipped](#shipped) |
+
+## Shipped
+
+| Field | ID | Type | Required | Description |
+|---|---|---|---|---|
+| `carrier` | 18042950570625151283 | string | yes | The carrier handling the delivery. |
+
+"#;
+    assert_eq!(md, expected);
+    // IDs in the tables are the field and variant IDs written to payloads
+    assert!(md.contains(&format!(
+        "| `customer` | {} |",
+        field_id_from_name("customer")
+    )));
+    assert!(md.contains(&format!("| `Open` | {} |", field_id_from_name("Open"))));
+}
+
+#[test]
+fn test_codegen_comments() {
+    let ts = typescript(&Order::schema());
+    assert!(
+        ts.contains("  /** The order number. */\n  id: bigint;"),
+        "{}",
+        ts
+    );
+    assert!(ts.contains("  /** Who placed the order.\n   *\n   * Matches"));
+    let go = golang(&Order::schema());
+    assert!(
+        go.contains("\t// The order number.\n\tId uint64\n"),
+        "{}",
+        go
+    );
+    assert!(go.contains("\t// Who placed the order.\n\t//\n"));
+}
@@ -0,0 +1,187 @@
+use senax_encoder::schema::{FieldSchema, SchemaResolver, StructSchema, TypeSchema};
+use senax_encoder::{decode, encode, Decode, Encode};
+
+#[derive(Encode, Decode, Debug, PartialEq, Clone)]
+struct AddressV1 {
+    street: String,
+    zip: u32,
+}
+
+#[derive(Encode, Decode, Debug, PartialEq, Clone)]
+struct CustomerV1 {
+    customer_name: String,
+    visits: u32,
+    address: AddressV1,
+    history: Vec<AddressV1>,
+    nickname: Option<String>,
+    obsolete: bool,
+}
+
+#[derive(Encode, Decode, Debug, PartialEq, Clone)]
+struct AddressV2 {
+    line1: String,
+    zip: u32,
+    country: String,
+}
+
+#[derive(Encode, Decode, Debug, PartialEq, Clone)]
+struct CustomerV2 {
+    name: String,
+    visits: i64,
+    address: AddressV2,
+    history: Vec<AddressV2>,
+    nickname: Option<String>,
+    tags: Vec<String>,
+    referrer: Option<String>,
+}
+
+fn address_v1() -> TypeSchema {
+    TypeSchema::Struct(StructSchema::new(
+        "Address",
+        vec![
+            FieldSchema::new("street", TypeSchema::String),
+            FieldSchema::new("zip", TypeSchema::UInt),
+        ],
+    ))
+}
+
+fn address_v2() -> TypeSchema {
+    TypeSchema::Struct(StructSchema::new(
+        "Address",
+        vec![
+            FieldSchema::new("line1", TypeSchema::String).with_aliases(["street"]),
+            FieldSchema::new("zip", TypeSchema::UInt),
+            FieldSchema::new("country", TypeSchema::String),
+        ],
+    ))
+}
+
+fn customer_v1() -> TypeSchema {
+    TypeSchema::Struct(StructSchema::new(
+        "Customer",
+        vec![
+            FieldSchema::new("customer_name", TypeSchema::String),
+            FieldSchema::new("visits", TypeSchema::UInt),
+            FieldSchema::new("address", address_v1()),
+            FieldSchema::new("history", TypeSchema::List(Box::new(address_v1()))),
+            FieldSchema::new("nickname", TypeSchema::Option(Box::new(TypeSchema::String))),
+            FieldSchema::new("obsolete", TypeSchema::Bool),
+        ],
+    ))
+}
+
+fn customer_v2() -> TypeSchema {
+    TypeSchema::Struct(StructSchema::new(
+        "Customer",
+        vec![
+            FieldSchema::new("name", TypeSchema::String).with_aliases(["customer_name"]),
+            FieldSchema::new("visits", TypeSchema::Int),
+            FieldSchema::new("address", address_v2()),
+            FieldSchema::new("history", TypeSchema::List(Box::new(address_v2()))),
+            FieldSchema::new("nickname", TypeSchema::Option(Box::new(TypeSchema::String))),
+            FieldSchema::new("tags", TypeSchema::List(Box::new(TypeSchema::String))),
+            FieldSchema::new("referrer", TypeSchema::Option(Box::new(TypeSchema::String))),
+        ],
+    ))
+}
+
+fn address(street: &str, zip: u32) -> AddressV1 {
+    AddressV1 {
+        street: street.to_string(),
+        zip,
+    }
+}
+
+#[test]
+fn test_resolver_renames_drops_and_defaults() {
+    let old = CustomerV1 {
+        customer_name: "Alice".to_string(),
+        visits: 12,
+        address: address("1 Main St", 10001),
+        history: vec![address("9 Old Rd", 20002)],
+        nickname: Some("Al".to_string()),
+        obsolete: true,
+    };
+    let bytes = encode(&old).unwrap();
+
+    // Without the resolver the renamed field is missing
+    assert!(decode::<CustomerV2>(&mut bytes.clone()).is_err());
+
+    let resolver = SchemaResolver::new(&customer_v1(), &customer_v2()).unwrap();
+    let customer: CustomerV2 = resolver.decode(&mut bytes.clone()).unwrap();
+    assert_eq!(
+        customer,
+        CustomerV2 {
+            name: "Alice".to_string(),
+            visits: 12,
+            address: AddressV2 {
+                line1: "1 Main St".to_string(),
+                zip: 10001,
+                country: String::new(),
+            },
+            history: vec![AddressV2 {
+                line1: "9 Old Rd".to_string(),
+                zip: 20002,
+                country: String::new(),
+            }],
+            nickname: Some("Al".to_string()),
+            tags: Vec::new(),
+            referrer: None,
+        }
+    );
+}
+
+#[test]
+fn test_resolver_matches_explicit_ids() {
+    #[derive(Encode)]
+    struct Old {
+        #[senax(id = 3)]
+        amount: u64,
+    }
+
+    #[derive(Decode, Debug, PartialEq)]
+    struct New {
+        #[senax(id = 3)]
+        total: u64,
+    }
+
+    let writer = TypeSchema::Struct(StructSchema::new(
+        "Payment",
+        vec![FieldSchema::new("amount", TypeSchema::UInt).with_id(3)],
+    ));
+    let reader = TypeSchema::Struct(StructSchema::new(
+        "Payment",
+        vec![FieldSchema::new("total", TypeSchema::UInt).with_id(3)],
+    ));
+    let resolver = SchemaResolver::new(&writer, &reader).unwrap();
+    let bytes = encode(&Old { amount: 500 }).unwrap();
+    assert_eq!(
+        resolver.decode::<New>(&mut bytes.clone()).unwrap(),
+        New { total: 500 }
+    );
+}
+
+#[test]
+fn test_resolver_rejects_incompatible_types() {
+    let writer = TypeSchema::Struct(StructSchema::new(
+        "Event",
+        vec![FieldSchema::new("at", TypeSchema::String)],
+    ));
+    let reader = TypeSchema::Struct(StructSchema::new(
+        "Event",
+        vec![FieldSchema::new("at", TypeSchema::UInt)],
+    ));
+    let err = SchemaResolver::new(&writer, &reader).unwrap_err();
+    assert!(err.to_string().contains("value.at"), "{}", err);
+
+    // Narrowing is rejected as well
+    assert!(SchemaResolver::new(&TypeSchema::Int, &TypeSchema::UInt).is_err());
+}
+
+#[test]
+fn test_schema_roundtrip() {
+    let schema = customer_v2();
+    let mut bytes = encode(&schema).unwrap();
+    let decoded: TypeSchema = decode(&mut bytes).unwrap();
+    assert_eq!(decoded, schema);
+}
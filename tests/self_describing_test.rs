@@ -0,0 +1,147 @@
+use bytes::Bytes;
+use senax_encoder::self_describing::{decode_self_describing, encode_self_describing};
+use senax_encoder::value::{Variant, VariantFields};
+use senax_encoder::{decode, encode, Decode, Decoder, Encode, Value};
+
+#[derive(Encode, Decode, Debug, PartialEq, Clone)]
+enum Status {
+    Active,
+    Suspended { reason: String },
+    Moved(u32, String),
+}
+
+#[derive(Encode, Decode, Debug, PartialEq, Clone)]
+struct Point {
+    x: i32,
+    y: i32,
+}
+
+#[derive(Encode, Decode, Debug, PartialEq, Clone)]
+struct Account {
+    id: u64,
+    #[senax(rename = "display_name")]
+    name: String,
+    nickname: Option<String>,
+    status: Status,
+    path: Vec<Point>,
+}
+
+fn account() -> Account {
+    Account {
+        id: 7,
+        name: "Alice".to_string(),
+        nickname: None,
+        status: Status::Suspended {
+            reason: "audit".to_string(),
+        },
+        path: vec![Point { x: 1, y: -2 }, Point { x: 3, y: 4 }],
+    }
+}
+
+fn variant(value: &Value) -> &Variant {
+    match value {
+        Value::Enum(variant) => variant,
+        other => panic!("expected enum, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_self_describing_field_names() {
+    let bytes = encode_self_describing(&account()).unwrap();
+    let value = decode_self_describing(&mut bytes.clone()).unwrap();
+
+    assert_eq!(value.field("id"), Some(&Value::UInt(7)));
+    assert_eq!(
+        value.field("display_name"),
+        Some(&Value::String("Alice".to_string()))
+    );
+    assert_eq!(value.field("name"), None);
+    // Omitted None fields are not written
+    assert_eq!(value.field("nickname"), None);
+
+    let status = variant(value.field("status").unwrap());
+    assert_eq!(status.name.as_deref(), Some("Suspended"));
+    let VariantFields::Named(fields) = &status.fields else {
+        panic!("expected named fields");
+    };
+    assert_eq!(fields[0].name.as_deref(), Some("reason"));
+    assert_eq!(fields[0].value, Value::String("audit".to_string()));
+
+    let Some(Value::List(points)) = value.field("path") else {
+        panic!("expected list");
+    };
+    assert_eq!(points[1].field("x"), Some(&Value::UInt(3)));
+    assert_eq!(points[0].field("y"), Some(&Value::Int(-2)));
+}
+
+#[test]
+fn test_self_describing_names_are_interned() {
+    let bytes = encode_self_describing(&account()).unwrap();
+    // "x" is stored once in the name table although both points use it
+    let occurrences = bytes.windows(2).filter(|w| *w == [140, b'x']).count();
+    assert_eq!(occurrences, 1);
+}
+
+#[test]
+fn test_self_describing_unit_and_tuple_variants() {
+    for (status, name) in [
+        (Status::Active, "Active"),
+        (Status::Moved(3, "north".to_string()), "Moved"),
+    ] {
+        let bytes = encode_self_describing(&status).unwrap();
+        let value = decode_self_describing(&mut bytes.clone()).unwrap();
+        assert_eq!(variant(&value).name.as_deref(), Some(name));
+    }
+
+    let bytes = encode_self_describing(&Status::Moved(3, "north".to_string())).unwrap();
+    let value = decode_self_describing(&mut bytes.clone()).unwrap();
+    assert_eq!(
+        variant(&value).fields,
+        VariantFields::Unnamed(vec![Value::UInt(3), Value::String("north".to_string())])
+    );
+}
+
+#[test]
+fn test_plain_encode_is_unchanged() {
+    let value = account();
+    let _ = encode_self_describing(&value).unwrap();
+    let mut bytes = encode(&value).unwrap();
+    let decoded: Account = decode(&mut bytes).unwrap();
+    assert_eq!(decoded, value);
+}
+
+#[test]
+fn test_value_decodes_plain_payload_without_names() {
+    let mut bytes = encode(&account()).unwrap().slice(2..);
+    let value = Value::decode(&mut bytes).unwrap();
+    assert!(bytes.is_empty());
+    let fields = value.fields().unwrap();
+    assert_eq!(fields.len(), 4);
+    assert!(fields.iter().all(|field| field.name.is_none()));
+    assert_eq!(value.field_by_id(fields[0].id), Some(&Value::UInt(7)));
+}
+
+#[test]
+fn test_value_scalars_and_collections() {
+    let mut bytes = encode(&(Some(1.5f64), vec![true, false], Bytes::from_static(b"ab")))
+        .unwrap()
+        .slice(2..);
+    assert_eq!(
+        Value::decode(&mut bytes).unwrap(),
+        Value::Tuple(vec![
+            Value::Some(Box::new(Value::String("1.5e0".to_string()))),
+            Value::List(vec![Value::UInt(1), Value::UInt(0)]),
+            Value::Binary(Bytes::from_static(b"ab")),
+        ])
+    );
+}
+
+#[test]
+fn test_self_describing_rejects_bad_input() {
+    let plain = encode(&account()).unwrap();
+    assert!(decode_self_describing(&mut plain.clone()).is_err());
+
+    let bytes = encode_self_describing(&account()).unwrap();
+    let truncated = bytes.slice(..bytes.len() - 3);
+    assert!(decode_self_describing(&mut truncated.clone()).is_err());
+}
@@ -0,0 +1,288 @@
+#![cfg(feature = "senax_serde")]
+
+use bytes::Bytes;
+use senax_encoder::senax_serde::{from_value, to_value, SenaxSerde};
+use senax_encoder::value::Value;
+use senax_encoder::{decode, encode, Decode, Encode};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+enum SerdeShape {
+    Empty,
+    Circle(f64),
+    Rect(u32, u32),
+    Polygon {
+        points: Vec<(i32, i32)>,
+        closed: bool,
+    },
+}
+
+#[derive(Encode, Decode, Debug, PartialEq, Clone)]
+enum DerivedShape {
+    Empty,
+    Circle(f64),
+    Rect(u32, u32),
+    Polygon {
+        points: Vec<(i32, i32)>,
+        closed: bool,
+    },
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+struct SerdeUserId(u64);
+
+#[derive(Encode, Decode, Debug, PartialEq, Clone)]
+struct DerivedUserId(u64);
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+struct SerdeRecord {
+    id: SerdeUserId,
+    name: String,
+    nickname: Option<String>,
+    score: f32,
+    balance: i128,
+    initial: char,
+    active: bool,
+    tags: Vec<String>,
+    counts: HashMap<String, u32>,
+    by_id: BTreeMap<u16, String>,
+    shapes: Vec<SerdeShape>,
+    pair: (u8, Option<i8>),
+    marker: (),
+}
+
+#[derive(Encode, Decode, Debug, PartialEq, Clone)]
+struct DerivedRecord {
+    id: DerivedUserId,
+    name: String,
+    nickname: Option<String>,
+    score: f32,
+    balance: i128,
+    initial: char,
+    active: bool,
+    tags: Vec<String>,
+    counts: HashMap<String, u32>,
+    by_id: BTreeMap<u16, String>,
+    shapes: Vec<DerivedShape>,
+    pair: (u8, Option<i8>),
+    marker: (),
+}
+
+fn serde_record() -> SerdeRecord {
+    SerdeRecord {
+        id: SerdeUserId(42),
+        name: "alice".to_string(),
+        nickname: None,
+        score: 1.5,
+        balance: -12_345_678_901_234_567_890,
+        initial: 'é',
+        active: true,
+        tags: vec!["a".to_string(), "b".to_string()],
+        counts: [("x".to_string(), 1), ("y".to_string(), 2)].into(),
+        by_id: [(1, "one".to_string()), (300, "three hundred".to_string())].into(),
+        shapes: vec![
+            SerdeShape::Empty,
+            SerdeShape::Circle(0.25),
+            SerdeShape::Rect(3, 4),
+            SerdeShape::Polygon {
+                points: vec![(0, 0), (1, -1)],
+                closed: true,
+            },
+        ],
+        pair: (7, Some(-3)),
+        marker: (),
+    }
+}
+
+fn derived_record() -> DerivedRecord {
+    DerivedRecord {
+        id: DerivedUserId(42),
+        name: "alice".to_string(),
+        nickname: None,
+        score: 1.5,
+        balance: -12_345_678_901_234_567_890,
+        initial: 'é',
+        active: true,
+        tags: vec!["a".to_string(), "b".to_string()],
+        counts: [("x".to_string(), 1), ("y".to_string(), 2)].into(),
+        by_id: [(1, "one".to_string()), (300, "three hundred".to_string())].into(),
+        shapes: vec![
+            DerivedShape::Empty,
+            DerivedShape::Circle(0.25),
+            DerivedShape::Rect(3, 4),
+            DerivedShape::Polygon {
+                points: vec![(0, 0), (1, -1)],
+                closed: true,
+            },
+        ],
+        pair: (7, Some(-3)),
+        marker: (),
+    }
+}
+
+#[test]
+fn test_roundtrip() {
+    let mut bytes = encode(&SenaxSerde(serde_record())).unwrap();
+    let decoded: SenaxSerde<SerdeRecord> = decode(&mut bytes).unwrap();
+    assert_eq!(decoded.into_inner(), serde_record());
+
+    let mut record = serde_record();
+    record.nickname = Some("al".to_string());
+    let mut bytes = encode(&SenaxSerde(&record)).unwrap();
+    let decoded: SenaxSerde<SerdeRecord> = decode(&mut bytes).unwrap();
+    assert_eq!(*decoded, record);
+}
+
+#[test]
+fn test_serde_payloads_decode_as_derived_types() {
+    let mut bytes = encode(&SenaxSerde(serde_record())).unwrap();
+    let decoded: DerivedRecord = decode(&mut bytes).unwrap();
+    assert_eq!(decoded, derived_record());
+}
+
+#[test]
+fn test_derived_payloads_decode_as_serde_types() {
+    let mut record = derived_record();
+    record.nickname = Some("al".to_string());
+    let mut bytes = encode(&record).unwrap();
+    let decoded: SenaxSerde<SerdeRecord> = decode(&mut bytes).unwrap();
+    assert_eq!(decoded.nickname.as_deref(), Some("al"));
+    assert_eq!(decoded.shapes, serde_record().shapes);
+}
+
+#[test]
+fn test_same_bytes_as_derive() {
+    // One map entry, as `HashMap` iteration order differs between instances
+    let mut serde_value = serde_record();
+    serde_value.counts.remove("y");
+    let mut derived = derived_record();
+    derived.counts.remove("y");
+    assert_eq!(
+        encode(&SenaxSerde(serde_value)).unwrap(),
+        encode(&derived).unwrap()
+    );
+}
+
+#[derive(Encode, Decode, Debug, PartialEq)]
+struct Envelope {
+    seq: u64,
+    body: SenaxSerde<SerdeShape>,
+    extra: Option<SenaxSerde<SerdeUserId>>,
+}
+
+#[test]
+fn test_serde_fields_in_derived_types() {
+    let envelope = Envelope {
+        seq: 1,
+        body: SenaxSerde(SerdeShape::Rect(1, 2)),
+        extra: Some(SerdeUserId(9).into()),
+    };
+    let mut bytes = encode(&envelope).unwrap();
+    assert_eq!(decode::<Envelope>(&mut bytes).unwrap(), envelope);
+
+    let list = vec![SenaxSerde(SerdeUserId(1)), SenaxSerde(SerdeUserId(2))];
+    let mut bytes = encode(&list).unwrap();
+    assert_eq!(
+        decode::<Vec<SenaxSerde<SerdeUserId>>>(&mut bytes).unwrap(),
+        list
+    );
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct SerdeRecordV1 {
+    name: String,
+    nickname: Option<String>,
+    #[serde(default)]
+    visits: u32,
+}
+
+#[test]
+fn test_schema_evolution() {
+    // Unknown fields are skipped, missing `Option` and `#[serde(default)]` fields are filled in
+    let mut bytes = encode(&SenaxSerde(serde_record())).unwrap();
+    let decoded: SenaxSerde<SerdeRecordV1> = decode(&mut bytes).unwrap();
+    assert_eq!(
+        decoded.into_inner(),
+        SerdeRecordV1 {
+            name: "alice".to_string(),
+            nickname: None,
+            visits: 0,
+        }
+    );
+
+    // Missing required fields fail
+    let mut bytes = encode(&SenaxSerde(SerdeRecordV1 {
+        name: "bob".to_string(),
+        nickname: None,
+        visits: 3,
+    }))
+    .unwrap();
+    assert!(decode::<SenaxSerde<SerdeRecord>>(&mut bytes).is_err());
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+enum SerdeShapeV0 {
+    Empty,
+}
+
+#[test]
+fn test_unknown_variant_fails() {
+    let mut bytes = encode(&SenaxSerde(SerdeShape::Circle(1.0))).unwrap();
+    let err = decode::<SenaxSerde<SerdeShapeV0>>(&mut bytes).unwrap_err();
+    assert!(err.to_string().contains("Unknown variant ID"), "{}", err);
+}
+
+struct Blob(Vec<u8>);
+
+impl Serialize for Blob {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_bytes(&self.0)
+    }
+}
+
+#[test]
+fn test_values() {
+    assert_eq!(
+        to_value(&Blob(vec![1, 2, 3])).unwrap(),
+        Value::Binary(Bytes::from_static(&[1, 2, 3]))
+    );
+    assert_eq!(to_value(&-1i8).unwrap(), Value::Int(-1));
+    assert_eq!(
+        to_value(&2.5f64).unwrap(),
+        Value::String("2.5e0".to_string())
+    );
+
+    // `Bytes` payloads decode into a byte buffer
+    let mut bytes = encode(&Bytes::from_static(b"raw")).unwrap();
+    let value = decode::<Value>(&mut bytes).unwrap();
+    let decoded: serde_bytes_like::ByteBuf = from_value(value).unwrap();
+    assert_eq!(decoded.0, b"raw");
+}
+
+mod serde_bytes_like {
+    use serde::de::{Deserialize, Deserializer, Visitor};
+    use std::fmt;
+
+    pub struct ByteBuf(pub Vec<u8>);
+
+    impl<'de> Deserialize<'de> for ByteBuf {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            struct BytesVisitor;
+
+            impl Visitor<'_> for BytesVisitor {
+                type Value = ByteBuf;
+
+                fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                    f.write_str("bytes")
+                }
+
+                fn visit_byte_buf<E>(self, v: Vec<u8>) -> Result<ByteBuf, E> {
+                    Ok(ByteBuf(v))
+                }
+            }
+
+            deserializer.deserialize_byte_buf(BytesVisitor)
+        }
+    }
+}
@@ -0,0 +1,186 @@
+#![cfg(feature = "zeroize")]
+
+use bytes::{Bytes, BytesMut};
+use senax_encoder::sensitive::{check_sensitive, Zeroize, Zeroizing};
+use senax_encoder::{decode, encode, Decode, Decoder, Encode, Encoder, EncoderError};
+use std::cell::RefCell;
+
+thread_local! {
+    /// Secrets seen by `Tracked::zeroize`, in order.
+    static WIPED: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+}
+
+/// A secret string that records when it is wiped.
+#[derive(Debug, Default, PartialEq)]
+struct Tracked(String);
+
+impl Encoder for Tracked {
+    fn encode(&self, writer: &mut BytesMut) -> senax_encoder::Result<()> {
+        self.0.encode(writer)
+    }
+
+    fn is_default(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl Decoder for Tracked {
+    fn decode(reader: &mut Bytes) -> senax_encoder::Result<Self> {
+        String::decode(reader).map(Tracked)
+    }
+}
+
+impl Zeroize for Tracked {
+    fn zeroize(&mut self) {
+        if !self.0.is_empty() {
+            WIPED.with(|w| w.borrow_mut().push(self.0.clone()));
+        }
+        self.0.zeroize();
+    }
+}
+
+fn take_wiped() -> Vec<String> {
+    WIPED.with(|w| std::mem::take(&mut *w.borrow_mut()))
+}
+
+#[derive(Encode, Decode, Debug, PartialEq)]
+struct Login {
+    user: String,
+    #[senax(sensitive)]
+    password: Tracked,
+    #[senax(sensitive, default)]
+    recovery: Option<Tracked>,
+    attempts: u8,
+}
+
+#[derive(Encode, Decode, Debug, PartialEq)]
+struct Token {
+    #[senax(sensitive, max_len = 8)]
+    secret: Zeroizing<String>,
+    #[senax(sensitive, min = 1, max = 60)]
+    ttl: u32,
+}
+
+/// `Token` without the limits, to write payloads that break them.
+#[derive(Encode, Decode, Debug, PartialEq)]
+struct PlainToken {
+    secret: String,
+    ttl: u32,
+}
+
+#[derive(Encode, Decode, Debug, PartialEq)]
+enum Credential {
+    Password {
+        #[senax(sensitive)]
+        password: Tracked,
+        expires: u64,
+    },
+}
+
+fn login() -> Login {
+    Login {
+        user: "kim".to_string(),
+        password: Tracked("hunter2".to_string()),
+        recovery: Some(Tracked("backup".to_string())),
+        attempts: 3,
+    }
+}
+
+/// `Login` with `attempts` as text, which `Login` cannot read.
+#[derive(Encode)]
+struct BadLogin {
+    user: String,
+    password: String,
+    recovery: String,
+    attempts: String,
+}
+
+fn corrupt_login() -> Bytes {
+    encode(&BadLogin {
+        user: "kim".to_string(),
+        password: "hunter2".to_string(),
+        recovery: "backup".to_string(),
+        attempts: "three".to_string(),
+    })
+    .unwrap()
+}
+
+#[test]
+fn test_round_trip_unchanged() {
+    take_wiped();
+    let mut bytes = encode(&login()).unwrap();
+    assert_eq!(decode::<Login>(&mut bytes).unwrap(), login());
+    // The values were moved out, so only empty slots were dropped
+    assert!(take_wiped().is_empty());
+}
+
+#[test]
+fn test_failed_decode_wipes_decoded_secrets() {
+    take_wiped();
+    assert!(decode::<Login>(&mut corrupt_login()).is_err());
+    let mut wiped = take_wiped();
+    wiped.sort();
+    assert_eq!(wiped, ["backup", "hunter2"]);
+}
+
+#[test]
+fn test_enum_variant_fields() {
+    take_wiped();
+    let value = Credential::Password {
+        password: Tracked("pw".to_string()),
+        expires: 10,
+    };
+    let mut bytes = encode(&value).unwrap();
+    assert_eq!(decode::<Credential>(&mut bytes).unwrap(), value);
+
+    // Dropping the tail of the payload fails after the password was read
+    let bytes = encode(&value).unwrap();
+    let mut truncated = bytes.slice(..bytes.len() - 2);
+    assert!(decode::<Credential>(&mut truncated).is_err());
+    assert_eq!(take_wiped(), ["pw"]);
+}
+
+#[test]
+fn test_checks_on_sensitive_fields() {
+    let token = Token {
+        secret: Zeroizing::new("abc".to_string()),
+        ttl: 30,
+    };
+    let mut bytes = encode(&token).unwrap();
+    assert_eq!(decode::<Token>(&mut bytes).unwrap(), token);
+
+    let long = PlainToken {
+        secret: "too long secret".to_string(),
+        ttl: 30,
+    };
+    let mut bytes = encode(&long).unwrap();
+    let err = decode::<Token>(&mut bytes).unwrap_err();
+    assert!(matches!(err, EncoderError::CapacityExceeded { .. }));
+
+    let out_of_range = PlainToken {
+        secret: "abc".to_string(),
+        ttl: 90,
+    };
+    let mut bytes = encode(&out_of_range).unwrap();
+    assert!(decode::<Token>(&mut bytes).is_err());
+}
+
+#[test]
+fn test_check_sensitive_wipes_rejected_value() {
+    take_wiped();
+    let ok = check_sensitive(Tracked("kept".to_string()), |_| Ok(())).unwrap();
+    assert_eq!(ok.0, "kept");
+    let err = check_sensitive(Tracked("rejected".to_string()), |_| {
+        Err(EncoderError::Decode("no".to_string()))
+    });
+    assert!(err.is_err());
+    assert_eq!(take_wiped(), ["rejected"]);
+}
+
+#[test]
+fn test_zeroizing_codec() {
+    let secret = Zeroizing::new(vec![1u8, 2, 3]);
+    let mut bytes = encode(&secret).unwrap();
+    assert_eq!(bytes, encode(&vec![1u8, 2, 3]).unwrap());
+    assert_eq!(decode::<Zeroizing<Vec<u8>>>(&mut bytes).unwrap(), secret);
+}
@@ -0,0 +1,117 @@
+use bytes::{BufMut, BytesMut};
+use senax_encoder::core::TAG_ARRAY_VEC_SET_BASE;
+use senax_encoder::seq::{
+    decode_seq, encode_seq_try_from_iter, SeqIter, TryEncodeError, SEQ_CHUNK_LEN,
+};
+use senax_encoder::{Decode, Decoder, Encode, Encoder, EncoderError};
+
+#[derive(Encode, Decode, Debug, PartialEq, Clone)]
+struct Row {
+    id: u64,
+    name: String,
+}
+
+fn row(id: u64) -> Row {
+    Row {
+        id,
+        name: format!("row-{}", id),
+    }
+}
+
+#[test]
+fn test_sequence_round_trip() {
+    let rows: Vec<Row> = (0..10).map(row).collect();
+    let mut writer = BytesMut::new();
+    let count =
+        encode_seq_try_from_iter(rows.iter().cloned().map(Ok::<_, String>), &mut writer).unwrap();
+    assert_eq!(count, 10);
+    assert_eq!(decode_seq::<Row>(&mut writer.freeze()).unwrap(), rows);
+}
+
+#[test]
+fn test_empty_sequence_is_a_single_terminator() {
+    let mut writer = BytesMut::new();
+    let count =
+        encode_seq_try_from_iter(std::iter::empty::<Result<u32, ()>>(), &mut writer).unwrap();
+    assert_eq!(count, 0);
+    assert_eq!(writer.as_ref(), &[TAG_ARRAY_VEC_SET_BASE]);
+    assert!(decode_seq::<u32>(&mut writer.freeze()).unwrap().is_empty());
+}
+
+#[test]
+fn test_source_error_rolls_back() {
+    let mut writer = BytesMut::new();
+    writer.put_slice(b"prefix");
+    let source = (0..100u64).map(|i| {
+        if i == 70 {
+            Err("cursor closed")
+        } else {
+            Ok(row(i))
+        }
+    });
+    let err = encode_seq_try_from_iter(source, &mut writer).unwrap_err();
+    assert!(matches!(err, TryEncodeError::Source("cursor closed")));
+    assert_eq!(writer.as_ref(), b"prefix");
+}
+
+/// Fails to encode values above `u8::MAX`, after writing part of them.
+struct Small(u32);
+
+impl Encoder for Small {
+    fn encode(&self, writer: &mut BytesMut) -> senax_encoder::Result<()> {
+        self.0.encode(writer)?;
+        if self.0 > u8::MAX as u32 {
+            return Err(EncoderError::Encode("value too large".into()));
+        }
+        Ok(())
+    }
+
+    fn is_default(&self) -> bool {
+        self.0 == 0
+    }
+}
+
+#[test]
+fn test_encode_error_rolls_back() {
+    let mut writer = BytesMut::new();
+    let source = [1, 2, 300].into_iter().map(|v| Ok::<_, ()>(Small(v)));
+    let err = encode_seq_try_from_iter(source, &mut writer).unwrap_err();
+    assert!(matches!(
+        err,
+        TryEncodeError::Encode(EncoderError::Encode(_))
+    ));
+    assert!(writer.is_empty());
+}
+
+#[test]
+fn test_sequence_spans_several_chunks() {
+    let total = SEQ_CHUNK_LEN * 2 + 3;
+    let mut writer = BytesMut::new();
+    let count = encode_seq_try_from_iter((0..total as u32).map(Ok::<_, ()>), &mut writer).unwrap();
+    assert_eq!(count, total);
+    let decoded = decode_seq::<u32>(&mut writer.freeze()).unwrap();
+    assert_eq!(decoded.len(), total);
+    assert!(decoded.iter().enumerate().all(|(i, v)| *v == i as u32));
+}
+
+#[test]
+fn test_iterator_reads_lazily_and_stops_after_terminator() {
+    let mut writer = BytesMut::new();
+    encode_seq_try_from_iter((1..=3u64).map(|i| Ok::<_, ()>(row(i))), &mut writer).unwrap();
+    42u32.encode(&mut writer).unwrap();
+    let mut bytes = writer.freeze();
+
+    let mut iter = SeqIter::<Row>::new(&mut bytes);
+    assert_eq!(iter.next().unwrap().unwrap(), row(1));
+    let rest: Vec<Row> = iter.map(|r| r.unwrap()).collect();
+    assert_eq!(rest, vec![row(2), row(3)]);
+    assert_eq!(u32::decode(&mut bytes).unwrap(), 42);
+}
+
+#[test]
+fn test_truncated_sequence_is_an_error() {
+    let mut writer = BytesMut::new();
+    encode_seq_try_from_iter((0..5u32).map(Ok::<_, ()>), &mut writer).unwrap();
+    let mut bytes = writer.freeze().slice(..6);
+    assert!(decode_seq::<u32>(&mut bytes).is_err());
+}
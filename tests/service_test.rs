@@ -0,0 +1,187 @@
+use bytes::{Bytes, BytesMut};
+use senax_encoder::core::{field_id_from_name, write_field_id_optimized};
+use senax_encoder::service::{serve, CallError, FramedTransport, Status};
+use senax_encoder::{senax_service, Decode, Encode, EncoderError};
+use std::net::{TcpListener, TcpStream};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+#[derive(Encode, Decode, Debug, PartialEq, Clone)]
+struct AddRequest {
+    a: i64,
+    b: i64,
+}
+
+#[derive(Encode, Decode, Debug, PartialEq, Clone)]
+struct AddReply {
+    sum: i64,
+}
+
+#[derive(Encode, Decode, Debug, PartialEq, Clone)]
+struct Counter {
+    value: u64,
+}
+
+senax_service! {
+    /// Arithmetic over the wire.
+    pub service Calculator {
+        client: CalculatorClient,
+        server: CalculatorServer,
+        /// Adds two numbers.
+        fn add(AddRequest) -> AddReply;
+        fn increment(()) -> Counter;
+        fn divide((i64, i64)) -> i64;
+    }
+}
+
+#[derive(Default)]
+struct Calc {
+    counter: AtomicU64,
+}
+
+impl Calculator for Calc {
+    fn add(&self, request: AddRequest) -> Result<AddReply, Status> {
+        Ok(AddReply {
+            sum: request.a + request.b,
+        })
+    }
+
+    fn increment(&self, _: ()) -> Result<Counter, Status> {
+        Ok(Counter {
+            value: self.counter.fetch_add(1, Ordering::SeqCst) + 1,
+        })
+    }
+
+    fn divide(&self, (a, b): (i64, i64)) -> Result<i64, Status> {
+        if b == 0 {
+            return Err(Status::new(Status::INVALID_ARGUMENT, "division by zero"));
+        }
+        Ok(a / b)
+    }
+}
+
+#[test]
+fn test_in_process_calls() {
+    let server = CalculatorServer::new(Calc::default());
+    let mut client = CalculatorClient::new(|request| Ok(server.handle(request)));
+
+    assert_eq!(
+        client.add(&AddRequest { a: 2, b: 40 }).unwrap(),
+        AddReply { sum: 42 }
+    );
+    assert_eq!(client.increment(&()).unwrap().value, 1);
+    assert_eq!(client.increment(&()).unwrap().value, 2);
+    assert_eq!(client.divide(&(9, 3)).unwrap(), 3);
+}
+
+#[test]
+fn test_method_errors_reach_client() {
+    let server = CalculatorServer::new(Calc::default());
+    let mut client = CalculatorClient::new(|request| Ok(server.handle(request)));
+
+    match client.divide(&(1, 0)) {
+        Err(CallError::Status(status)) => {
+            assert_eq!(status.code, Status::INVALID_ARGUMENT);
+            assert_eq!(status.message, "division by zero");
+        }
+        other => panic!("expected status error, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_method_ids() {
+    assert_eq!(
+        CalculatorServer::<Calc>::METHOD_IDS,
+        &[
+            field_id_from_name("add"),
+            field_id_from_name("increment"),
+            field_id_from_name("divide"),
+        ]
+    );
+    assert_eq!(
+        CalculatorServer::<Calc>::method_name(field_id_from_name("divide")),
+        Some("divide")
+    );
+    assert_eq!(CalculatorServer::<Calc>::method_name(1), None);
+}
+
+#[test]
+fn test_request_layout() {
+    let server = CalculatorServer::new(Calc::default());
+    let mut seen = Vec::new();
+    let mut client = CalculatorClient::new(|request: Bytes| {
+        seen.push(request.clone());
+        Ok(server.handle(request))
+    });
+    client.add(&AddRequest { a: 1, b: 2 }).unwrap();
+
+    let mut expected = BytesMut::new();
+    write_field_id_optimized(&mut expected, field_id_from_name("add")).unwrap();
+    senax_encoder::Encoder::encode(&AddRequest { a: 1, b: 2 }, &mut expected).unwrap();
+    assert_eq!(seen, vec![expected.freeze()]);
+}
+
+#[test]
+fn test_unknown_method_is_unimplemented() {
+    let server = CalculatorServer::new(Calc::default());
+    let mut request = BytesMut::new();
+    write_field_id_optimized(&mut request, field_id_from_name("multiply")).unwrap();
+    let response = server.handle(request.freeze());
+
+    match senax_encoder::service::decode_response::<i64>(response) {
+        Err(CallError::Status(status)) => assert_eq!(status.code, Status::UNIMPLEMENTED),
+        other => panic!("expected unimplemented, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_malformed_request_is_invalid_argument() {
+    let server = CalculatorServer::new(Calc::default());
+    let mut client = CalculatorClient::new(|request: Bytes| {
+        // Drop the request body
+        Ok(server.handle(request.slice(..1)))
+    });
+
+    match client.add(&AddRequest { a: 1, b: 2 }) {
+        Err(CallError::Status(status)) => assert_eq!(status.code, Status::INVALID_ARGUMENT),
+        other => panic!("expected invalid argument, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_transport_errors() {
+    let mut client = CalculatorClient::new(|_: Bytes| -> senax_encoder::Result<Bytes> {
+        Err(EncoderError::Decode("connection reset".to_string()))
+    });
+    assert!(matches!(
+        client.increment(&()),
+        Err(CallError::Transport(EncoderError::Decode(_)))
+    ));
+
+    let mut client = CalculatorClient::new(|_: Bytes| Ok(Bytes::from_static(&[9])));
+    assert!(matches!(
+        client.increment(&()),
+        Err(CallError::Transport(EncoderError::Decode(_)))
+    ));
+}
+
+#[test]
+fn test_framed_transport_over_tcp() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let server = std::thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        let server = CalculatorServer::new(Calc::default());
+        serve(stream, |request| server.handle(request)).unwrap();
+        server.into_inner().counter.load(Ordering::SeqCst)
+    });
+
+    let mut client = CalculatorClient::new(FramedTransport::new(TcpStream::connect(addr).unwrap()));
+    for i in 0..10 {
+        assert_eq!(client.add(&AddRequest { a: i, b: i }).unwrap().sum, 2 * i);
+        assert_eq!(client.increment(&()).unwrap().value, i as u64 + 1);
+    }
+    assert!(matches!(client.divide(&(1, 0)), Err(CallError::Status(_))));
+    drop(client);
+
+    assert_eq!(server.join().unwrap(), 10);
+}
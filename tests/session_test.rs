@@ -0,0 +1,289 @@
+use senax_encoder::session::{
+    SessionConfig, SessionDecoder, SessionEncoder, SESSION_FRAME_DATA, SESSION_FRAME_RESET,
+};
+use senax_encoder::{decode, encode, Decode, Encode, IdMap};
+use std::collections::BTreeMap;
+
+#[derive(Encode, Decode, Debug, PartialEq, Clone)]
+enum Level {
+    Debug,
+    Warning,
+    Error { code: u32, detail: String },
+}
+
+#[derive(Encode, Decode, Debug, PartialEq, Clone)]
+struct LogLine {
+    service: String,
+    host: String,
+    level: Level,
+    message: String,
+    tags: Vec<String>,
+    attributes: BTreeMap<u32, String>,
+    counters: IdMap<String>,
+    trace: Option<String>,
+}
+
+fn line(i: u32) -> LogLine {
+    LogLine {
+        service: "checkout-service".into(),
+        host: "eu-west-1a.internal".into(),
+        level: if i.is_multiple_of(2) {
+            Level::Warning
+        } else {
+            Level::Error {
+                code: 503,
+                detail: "upstream timeout".into(),
+            }
+        },
+        message: format!("request {} took too long", i),
+        tags: vec!["payments".into(), "latency".into()],
+        attributes: BTreeMap::from([(1, "region-eu".into())]),
+        counters: [(3, "retries".to_string())].into_iter().collect(),
+        trace: Some("trace-abcdef".into()),
+    }
+}
+
+fn frame_kind(frame: &[u8]) -> u8 {
+    frame[2]
+}
+
+#[test]
+fn test_messages_round_trip_and_shrink() {
+    let mut encoder = SessionEncoder::new();
+    let mut decoder = SessionDecoder::new();
+    let mut sizes = Vec::new();
+    for i in 0..10 {
+        let frame = encoder.encode(&line(i)).unwrap();
+        sizes.push(frame.len());
+        assert_eq!(
+            decoder.decode::<LogLine>(&mut frame.clone()).unwrap(),
+            line(i)
+        );
+    }
+    let plain = encode(&line(5)).unwrap().len();
+    assert!(sizes[5] * 2 < plain, "{} vs {}", sizes[5], plain);
+    assert!(sizes[5] < sizes[0]);
+    assert_eq!(encoder.dictionary_len(), decoder.dictionary_len());
+}
+
+#[test]
+fn test_resolved_payload_matches_plain_encoding() {
+    let mut encoder = SessionEncoder::new();
+    let mut decoder = SessionDecoder::new();
+    for i in 0..3 {
+        let frame = encoder.encode(&line(i)).unwrap();
+        let payload = decoder.decode_payload(&mut frame.clone()).unwrap();
+        assert_eq!(payload, encode(&line(i)).unwrap().slice(2..));
+    }
+}
+
+#[test]
+fn test_first_frame_resets_and_later_frames_are_data() {
+    let mut encoder = SessionEncoder::new();
+    let first = encoder.encode(&line(0)).unwrap();
+    let second = encoder.encode(&line(1)).unwrap();
+    assert_eq!(frame_kind(&first), SESSION_FRAME_RESET);
+    assert_eq!(frame_kind(&second), SESSION_FRAME_DATA);
+    assert_eq!(encoder.generation(), 1);
+
+    // A decoder that missed the reset frame waits for the next one
+    let mut late = SessionDecoder::new();
+    assert!(late.needs_reset());
+    assert!(late.decode::<LogLine>(&mut second.clone()).is_err());
+
+    encoder.reset();
+    assert_eq!(encoder.dictionary_len(), 0);
+    let third = encoder.encode(&line(2)).unwrap();
+    assert_eq!(frame_kind(&third), SESSION_FRAME_RESET);
+    assert_eq!(late.decode::<LogLine>(&mut third.clone()).unwrap(), line(2));
+    assert!(!late.needs_reset());
+    assert_eq!(late.generation(), 2);
+}
+
+#[test]
+fn test_lost_frame_requires_resync() {
+    let mut encoder = SessionEncoder::new();
+    let mut decoder = SessionDecoder::new();
+    let first = encoder.encode(&line(0)).unwrap();
+    let _lost = encoder.encode(&line(1)).unwrap();
+    let third = encoder.encode(&line(2)).unwrap();
+
+    decoder.decode::<LogLine>(&mut first.clone()).unwrap();
+    let err = decoder.decode::<LogLine>(&mut third.clone()).unwrap_err();
+    assert!(err.to_string().contains("out of sync"), "{}", err);
+    assert!(decoder.needs_reset());
+
+    // Later data frames are rejected until the peer resets
+    let fourth = encoder.encode(&line(3)).unwrap();
+    assert!(decoder.decode::<LogLine>(&mut fourth.clone()).is_err());
+    encoder.reset();
+    let fifth = encoder.encode(&line(4)).unwrap();
+    assert_eq!(
+        decoder.decode::<LogLine>(&mut fifth.clone()).unwrap(),
+        line(4)
+    );
+}
+
+#[test]
+fn test_full_dictionary_falls_back_to_plain_values() {
+    let config = SessionConfig {
+        max_entries: 2,
+        ..Default::default()
+    };
+    let mut encoder = SessionEncoder::with_config(config);
+    let mut decoder = SessionDecoder::with_config(config);
+    for i in 0..4 {
+        let frame = encoder.encode(&line(i)).unwrap();
+        assert_eq!(
+            decoder.decode::<LogLine>(&mut frame.clone()).unwrap(),
+            line(i)
+        );
+    }
+    assert_eq!(encoder.dictionary_len(), 4);
+
+    // A peer with a smaller limit refuses the extra definitions
+    let mut small = SessionDecoder::with_config(SessionConfig {
+        max_entries: 1,
+        ..Default::default()
+    });
+    let mut encoder = SessionEncoder::with_config(config);
+    let frame = encoder.encode(&line(0)).unwrap();
+    assert!(small.decode::<LogLine>(&mut frame.clone()).is_err());
+    assert!(small.needs_reset());
+}
+
+#[test]
+fn test_short_strings_are_not_interned() {
+    let mut encoder = SessionEncoder::new();
+    encoder
+        .encode(&vec!["a".to_string(), "bc".to_string()])
+        .unwrap();
+    assert_eq!(encoder.dictionary_len(), 0);
+}
+
+#[test]
+fn test_non_container_values_pass_through() {
+    let mut encoder = SessionEncoder::new();
+    let mut decoder = SessionDecoder::new();
+    let frame = encoder.encode(&"a top-level string".to_string()).unwrap();
+    assert_eq!(
+        decoder.decode::<String>(&mut frame.clone()).unwrap(),
+        "a top-level string"
+    );
+    let frame = encoder.encode(&(1u8, 2.5f64, vec![0u8; 3])).unwrap();
+    assert_eq!(
+        decoder
+            .decode::<(u8, f64, Vec<u8>)>(&mut frame.clone())
+            .unwrap(),
+        (1, 2.5, vec![0, 0, 0])
+    );
+}
+
+#[test]
+fn test_malformed_frames_are_rejected() {
+    let mut decoder = SessionDecoder::new();
+    assert!(decoder.decode::<u32>(&mut bytes::Bytes::new()).is_err());
+    let plain = encode(&line(0)).unwrap();
+    assert!(decoder.decode::<LogLine>(&mut plain.clone()).is_err());
+
+    let mut encoder = SessionEncoder::new();
+    let frame = encoder.encode(&line(0)).unwrap();
+    let truncated = frame.slice(..frame.len() - 4);
+    assert!(decoder.decode::<LogLine>(&mut truncated.clone()).is_err());
+    assert!(decoder.needs_reset());
+    assert!(decode::<LogLine>(&mut frame.clone()).is_err());
+}
+
+/// Sends `value` through a session twice (the second frame uses the dictionary) and checks the
+/// decoded value and the resolved payload.
+#[cfg(any(feature = "serde_json", feature = "chrono", feature = "time"))]
+fn assert_session_round_trip<T>(value: &T)
+where
+    T: senax_encoder::Encoder + senax_encoder::Decoder + PartialEq + std::fmt::Debug,
+{
+    let mut encoder = SessionEncoder::new();
+    let mut decoder = SessionDecoder::new();
+    for _ in 0..2 {
+        let frame = encoder.encode(value).unwrap();
+        let payload = decoder.decode_payload(&mut frame.clone()).unwrap();
+        assert_eq!(payload, encode(value).unwrap().slice(2..));
+        assert_eq!(&T::decode(&mut payload.clone()).unwrap(), value);
+    }
+}
+
+#[cfg(feature = "serde_json")]
+#[test]
+fn test_json_fields_pass_through() {
+    use serde_json::json;
+
+    #[derive(Encode, Decode, Debug, PartialEq)]
+    struct Record {
+        name: String,
+        data: serde_json::Value,
+    }
+
+    for data in [
+        json!({"b": true}),
+        json!({
+            "flags": [true, false, null],
+            "count": -3,
+            "ratio": 0.5,
+            "nested": {"ok": false, "label": "checkout-service"},
+        }),
+    ] {
+        assert_session_round_trip(&Record {
+            name: "checkout-service".into(),
+            data,
+        });
+    }
+}
+
+#[cfg(feature = "chrono")]
+#[test]
+fn test_chrono_fields_pass_through() {
+    use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+
+    #[derive(Encode, Decode, Debug, PartialEq)]
+    struct Record {
+        name: String,
+        at: DateTime<Utc>,
+        day: NaiveDate,
+        start: NaiveTime,
+        local: NaiveDateTime,
+    }
+
+    let at = DateTime::from_timestamp(1_700_000_000, 123_456_789).unwrap();
+    assert_session_round_trip(&Record {
+        name: "checkout-service".into(),
+        at,
+        day: at.date_naive(),
+        start: at.time(),
+        local: at.naive_utc(),
+    });
+}
+
+#[cfg(feature = "time")]
+#[test]
+fn test_time_fields_pass_through() {
+    use time::{OffsetDateTime, PrimitiveDateTime};
+
+    #[derive(Encode, Decode, Debug, PartialEq)]
+    struct Record {
+        name: String,
+        at: OffsetDateTime,
+        local: PrimitiveDateTime,
+    }
+
+    let at = OffsetDateTime::UNIX_EPOCH;
+    assert_session_round_trip(&Record {
+        name: "checkout-service".into(),
+        at,
+        local: PrimitiveDateTime::new(at.date(), at.time()),
+    });
+    let at = OffsetDateTime::from_unix_timestamp(1_700_000_000).unwrap();
+    assert_session_round_trip(&Record {
+        name: "checkout-service".into(),
+        at,
+        local: PrimitiveDateTime::new(at.date(), at.time()),
+    });
+}
@@ -0,0 +1,85 @@
+use senax_encoder::core::{TAG_F32, TAG_F64};
+use senax_encoder::{
+    decode, encode, encode_with_config, pack, unpack, with_encode_config, Decode, Encode,
+    EncodeConfig, Pack, Unpack, Value,
+};
+
+fn shrink() -> EncodeConfig {
+    EncodeConfig {
+        shrink_floats: true,
+        ..Default::default()
+    }
+}
+
+#[derive(Encode, Decode, Pack, Unpack, Debug, PartialEq)]
+struct Sample {
+    temperature: f64,
+    humidity: f64,
+    readings: Vec<f64>,
+}
+
+fn sample() -> Sample {
+    Sample {
+        temperature: 21.5,
+        humidity: 0.1,
+        readings: vec![1.0, 0.25, -3.0e10, 1.0e300],
+    }
+}
+
+#[test]
+fn test_exact_values_are_shrunk() {
+    for value in [1.0f64, -0.5, 21.5, 1024.0, f32::MAX as f64, f64::INFINITY] {
+        let bytes = encode_with_config(&value, &shrink()).unwrap();
+        // After the magic number, a tag and 4 bytes
+        assert_eq!(bytes[2], TAG_F32, "{value}");
+        assert_eq!(bytes.len(), 7);
+        assert_eq!(decode::<f64>(&mut bytes.clone()).unwrap(), value);
+
+        let packed = with_encode_config(&shrink(), || pack(&value)).unwrap();
+        assert_eq!(&packed[2..], &bytes[2..]);
+        assert_eq!(unpack::<f64>(&mut packed.clone()).unwrap(), value);
+    }
+}
+
+#[test]
+fn test_inexact_values_keep_full_width() {
+    for value in [0.1f64, 1.0e300, f64::MIN_POSITIVE, 16_777_217.0] {
+        let bytes = encode_with_config(&value, &shrink()).unwrap();
+        assert_eq!(bytes, encode(&value).unwrap(), "{value}");
+        let packed = with_encode_config(&shrink(), || pack(&value)).unwrap();
+        assert_eq!(packed[2], TAG_F64);
+        assert_eq!(unpack::<f64>(&mut packed.clone()).unwrap(), value);
+    }
+    let bytes = encode_with_config(&f64::NAN, &shrink()).unwrap();
+    assert!(decode::<f64>(&mut bytes.clone()).unwrap().is_nan());
+}
+
+#[test]
+fn test_off_by_default() {
+    assert_ne!(encode(&1.0f64).unwrap()[2], TAG_F32);
+    assert_eq!(pack(&1.0f64).unwrap()[2], TAG_F64);
+    // f32 values are not affected
+    let bytes = encode_with_config(&1.5f32, &shrink()).unwrap();
+    assert_eq!(bytes, encode(&1.5f32).unwrap());
+}
+
+#[test]
+fn test_structs_round_trip() {
+    let bytes = encode_with_config(&sample(), &shrink()).unwrap();
+    assert!(bytes.len() < encode(&sample()).unwrap().len());
+    assert_eq!(decode::<Sample>(&mut bytes.clone()).unwrap(), sample());
+
+    let packed = with_encode_config(&shrink(), || pack(&sample())).unwrap();
+    assert!(packed.len() < pack(&sample()).unwrap().len());
+    assert_eq!(unpack::<Sample>(&mut packed.clone()).unwrap(), sample());
+
+    // Untyped readers see the widened value
+    let value: Value = decode(&mut bytes.clone()).unwrap();
+    assert_eq!(value.field("temperature"), Some(&Value::Float(21.5)));
+}
+
+#[test]
+fn test_shrunk_values_decode_as_f32() {
+    let bytes = encode_with_config(&2.75f64, &shrink()).unwrap();
+    assert_eq!(decode::<f32>(&mut bytes.clone()).unwrap(), 2.75);
+}
@@ -0,0 +1,138 @@
+use senax_encoder::{decode, encode, Decode, Encode};
+
+fn is_zero(value: &u32) -> bool {
+    *value == 0
+}
+
+fn is_blank(value: &Option<String>) -> bool {
+    value.as_deref().is_none_or(|s| s.trim().is_empty())
+}
+
+#[derive(Encode, Decode, Debug, PartialEq, Default)]
+struct Session {
+    user: String,
+    #[senax(skip)]
+    cache: Vec<u8>,
+    #[senax(skip_encode_if = "Vec::is_empty")]
+    roles: Vec<String>,
+    #[senax(skip_encode_if = "is_zero")]
+    retries: u32,
+    #[senax(skip_encode_if = "is_blank")]
+    note: Option<String>,
+}
+
+#[derive(Encode, Decode, Debug, PartialEq)]
+struct SessionV1 {
+    user: String,
+}
+
+#[derive(Encode, Decode, Debug, PartialEq)]
+struct SessionWithCache {
+    user: String,
+    cache: Vec<u8>,
+}
+
+#[derive(Encode, Decode, Debug, PartialEq)]
+enum Event {
+    Login {
+        user: String,
+        #[senax(skip_encode_if = "is_zero")]
+        attempt: u32,
+        #[senax(skip)]
+        trace: String,
+    },
+}
+
+#[test]
+fn test_skip() {
+    let session = Session {
+        user: "alice".to_string(),
+        cache: vec![1, 2, 3],
+        ..Default::default()
+    };
+    let bytes = encode(&session).unwrap();
+    // Only `user` is written
+    assert_eq!(
+        bytes,
+        encode(&SessionV1 {
+            user: "alice".to_string()
+        })
+        .unwrap()
+    );
+    let decoded: Session = decode(&mut bytes.clone()).unwrap();
+    assert_eq!(decoded.cache, Vec::<u8>::new());
+
+    // A value written by another type is not read either
+    let mut bytes = encode(&SessionWithCache {
+        user: "alice".to_string(),
+        cache: vec![1],
+    })
+    .unwrap();
+    assert_eq!(
+        decode::<Session>(&mut bytes).unwrap().cache,
+        Vec::<u8>::new()
+    );
+}
+
+#[test]
+fn test_skip_encode_if() {
+    let empty = Session {
+        user: "alice".to_string(),
+        note: Some("  ".to_string()),
+        ..Default::default()
+    };
+    let full = Session {
+        user: "alice".to_string(),
+        roles: vec!["admin".to_string()],
+        retries: 2,
+        note: Some("vip".to_string()),
+        ..Default::default()
+    };
+    let empty_bytes = encode(&empty).unwrap();
+    let full_bytes = encode(&full).unwrap();
+    assert!(empty_bytes.len() < full_bytes.len());
+    assert_eq!(
+        empty_bytes,
+        encode(&SessionV1 {
+            user: "alice".to_string()
+        })
+        .unwrap()
+    );
+
+    assert_eq!(decode::<Session>(&mut full_bytes.clone()).unwrap(), full);
+    // Skipped fields decode as their default
+    let decoded: Session = decode(&mut empty_bytes.clone()).unwrap();
+    assert_eq!(
+        decoded,
+        Session {
+            user: "alice".to_string(),
+            ..Default::default()
+        }
+    );
+}
+
+#[test]
+fn test_enum_variant_fields() {
+    let event = Event::Login {
+        user: "alice".to_string(),
+        attempt: 0,
+        trace: "abc".to_string(),
+    };
+    let mut bytes = encode(&event).unwrap();
+    assert_eq!(
+        decode::<Event>(&mut bytes).unwrap(),
+        Event::Login {
+            user: "alice".to_string(),
+            attempt: 0,
+            trace: String::new(),
+        }
+    );
+
+    let event = Event::Login {
+        user: "alice".to_string(),
+        attempt: 3,
+        trace: String::new(),
+    };
+    let mut bytes = encode(&event).unwrap();
+    assert_eq!(decode::<Event>(&mut bytes).unwrap(), event);
+}
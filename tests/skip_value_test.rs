@@ -0,0 +1,350 @@
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+#[cfg(feature = "uuid")]
+use senax_encoder::core::TAG_UUID;
+use senax_encoder::core::{
+    encode_flat_key_map, skip_n_values, skip_value, TAG_ARRAY_VEC_SET_BASE, TAG_ARRAY_VEC_SET_LONG,
+    TAG_BINARY, TAG_DECIMAL, TAG_DURATION, TAG_ENUM, TAG_ENUM_NAMED, TAG_ENUM_UNNAMED, TAG_F32,
+    TAG_F64, TAG_MAP, TAG_MAP_FLAT_KEYS, TAG_MAP_ID_KEYS, TAG_MAP_STRING_KEYS, TAG_NEGATIVE,
+    TAG_NONE, TAG_ONE, TAG_SOME, TAG_STRING_BASE, TAG_STRING_LONG, TAG_STRUCT_NAMED,
+    TAG_STRUCT_UNIT, TAG_STRUCT_UNNAMED, TAG_SYSTEM_TIME, TAG_TUPLE, TAG_U128, TAG_U16, TAG_U32,
+    TAG_U64, TAG_U8, TAG_U8_127, TAG_ZERO,
+};
+#[cfg(feature = "chrono")]
+use senax_encoder::core::{
+    TAG_CHRONO_DATETIME, TAG_CHRONO_NAIVE_DATE, TAG_CHRONO_NAIVE_DATETIME, TAG_CHRONO_NAIVE_TIME,
+};
+#[cfg(feature = "serde_json")]
+use senax_encoder::core::{TAG_JSON_NULL, TAG_JSON_OBJECT};
+use senax_encoder::{Decode, Decoder, Encode, Encoder, EncoderError, IdMap, StringKeyMap};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::collections::{BTreeMap, HashMap};
+use std::time::{Duration, UNIX_EPOCH};
+
+/// Counts allocations made by the current thread.
+struct CountingAllocator;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let _ = ALLOCATIONS.try_with(|c| c.set(c.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+fn allocations_during(f: impl FnOnce()) -> usize {
+    let before = ALLOCATIONS.with(|c| c.get());
+    f();
+    ALLOCATIONS.with(|c| c.get()) - before
+}
+
+#[derive(Encode, Decode, Debug, PartialEq)]
+enum Shape {
+    Point,
+    Circle { radius: f64 },
+    Line(i32, i32, i32, i32),
+}
+
+#[derive(Encode, Decode, Debug, PartialEq)]
+struct Document {
+    id: u64,
+    title: String,
+    body: String,
+    blob: Bytes,
+    scores: Vec<i64>,
+    shapes: Vec<Shape>,
+    labels: BTreeMap<String, String>,
+    by_id: HashMap<u32, (u8, String)>,
+    parent: Option<Box<Document>>,
+}
+
+fn document() -> Document {
+    Document {
+        id: u64::MAX,
+        title: "title".to_string(),
+        body: "x".repeat(100_000),
+        blob: Bytes::from(vec![7u8; 70_000]),
+        scores: vec![0, -1, 127, 128, -70_000, i64::MIN],
+        shapes: vec![
+            Shape::Point,
+            Shape::Circle { radius: 1.5 },
+            Shape::Line(1, -2, 3, -4),
+        ],
+        labels: [("k".to_string(), "v".repeat(40))].into(),
+        by_id: [(1, (2, "three".to_string()))].into(),
+        parent: Some(Box::new(Document {
+            id: 1,
+            title: String::new(),
+            body: String::new(),
+            blob: Bytes::new(),
+            scores: vec![],
+            shapes: vec![],
+            labels: BTreeMap::new(),
+            by_id: HashMap::new(),
+            parent: None,
+        })),
+    }
+}
+
+fn encoded<T: Encoder>(value: &T) -> Bytes {
+    let mut buf = BytesMut::new();
+    value.encode(&mut buf).unwrap();
+    buf.freeze()
+}
+
+#[test]
+fn test_skip_consumes_exactly_one_value() {
+    let mut buf = BytesMut::new();
+    document().encode(&mut buf).unwrap();
+    "x".repeat(300).encode(&mut buf).unwrap();
+    Bytes::from(vec![1u8; 300]).encode(&mut buf).unwrap();
+    42u16.encode(&mut buf).unwrap();
+    let mut reader = buf.freeze();
+
+    skip_value(&mut reader).unwrap();
+    skip_value(&mut reader).unwrap();
+    skip_value(&mut reader).unwrap();
+    assert_eq!(u16::decode(&mut reader).unwrap(), 42);
+    assert!(reader.is_empty());
+}
+
+#[test]
+fn test_skip_does_not_allocate() {
+    let values = [
+        encoded(&document()),
+        encoded(&"y".repeat(1 << 20)),
+        encoded(&Bytes::from(vec![0u8; 1 << 20])),
+        encoded(&vec!["abc".to_string(); 1000]),
+        encoded(&-5i128),
+    ];
+    for value in values {
+        let mut reader = value.clone();
+        let allocations = allocations_during(|| skip_value(&mut reader).unwrap());
+        assert_eq!(allocations, 0);
+        assert!(reader.is_empty());
+    }
+}
+
+#[test]
+fn test_skip_n_values_matches_skip_value() {
+    let mut buf = BytesMut::new();
+    for i in 0..200u32 {
+        match i % 4 {
+            0 => (i as u8 % 100).encode(&mut buf).unwrap(),
+            1 => format!("s{}", i).encode(&mut buf).unwrap(),
+            2 => (i * 100_000).encode(&mut buf).unwrap(),
+            _ => Shape::Line(i as i32, 0, 0, 0).encode(&mut buf).unwrap(),
+        }
+    }
+    true.encode(&mut buf).unwrap();
+    let input = buf.freeze();
+
+    let mut one_by_one = input.clone();
+    for _ in 0..200 {
+        skip_value(&mut one_by_one).unwrap();
+    }
+    let mut batched = input.clone();
+    let allocations = allocations_during(|| skip_n_values(&mut batched, 200).unwrap());
+    assert_eq!(allocations, 0);
+    assert_eq!(batched, one_by_one);
+    assert!(bool::decode(&mut batched).unwrap());
+
+    let mut none = input.clone();
+    skip_n_values(&mut none, 0).unwrap();
+    assert_eq!(none, input);
+}
+
+#[test]
+fn test_truncated_values_fail() {
+    let values = [
+        encoded(&document()),
+        encoded(&"z".repeat(300)),
+        encoded(&Bytes::from(vec![1u8; 300])),
+        encoded(&vec![1u32, 2, 3]),
+    ];
+    for value in values {
+        for len in 0..value.len() {
+            let mut reader = value.slice(..len);
+            assert!(skip_value(&mut reader).is_err(), "prefix of {} bytes", len);
+            let mut reader = value.slice(..len);
+            assert!(skip_n_values(&mut reader, 1).is_err());
+        }
+    }
+
+    let mut strings = encoded(&("ab".to_string(), "cd".to_string()));
+    strings.advance(2); // tuple tag and length
+    let mut truncated = strings.slice(..strings.len() - 1);
+    assert!(matches!(
+        skip_n_values(&mut truncated, 2),
+        Err(EncoderError::UnexpectedEof { .. })
+    ));
+}
+
+#[derive(Encode, Decode, Debug, PartialEq)]
+struct Marker;
+
+#[derive(Encode, Decode, Debug, PartialEq)]
+struct Pair(u8, String);
+
+/// One encoded value for every tag `skip_value` handles, with the smallest encodings so the
+/// value ends right at the end of the buffer.
+fn every_tag() -> Vec<(&'static str, Bytes)> {
+    let mut values = vec![
+        ("zero", encoded(&0u8)),
+        ("one", encoded(&1u8)),
+        ("small uint", encoded(&127u8)),
+        ("u8", encoded(&200u8)),
+        ("u16", encoded(&1_000u16)),
+        ("u32", encoded(&70_000u32)),
+        ("u64", encoded(&(1u64 << 40))),
+        ("u128", encoded(&(1u128 << 70))),
+        ("negative", encoded(&-5i64)),
+        ("short string", encoded(&"abc".to_string())),
+        ("long string", encoded(&"x".repeat(100))),
+        ("binary", encoded(&Bytes::from_static(b"\x00\x01"))),
+        ("short vec", encoded(&vec![1u8, 2])),
+        ("long vec", encoded(&vec![1u32; 20])),
+        ("unit struct", encoded(&Marker)),
+        ("named struct", encoded(&document().parent.unwrap())),
+        ("unnamed struct", encoded(&Pair(1, "a".into()))),
+        ("unit variant", encoded(&Shape::Point)),
+        ("named variant", encoded(&Shape::Circle { radius: 1.0 })),
+        ("unnamed variant", encoded(&Shape::Line(1, 2, 3, 4))),
+        ("tuple", encoded(&(1u8, "a".to_string()))),
+        ("map", encoded(&BTreeMap::from([(1u32, 2u32)]))),
+        (
+            "string key map",
+            encoded(&StringKeyMap::from(BTreeMap::from([(
+                "k".to_string(),
+                1u8,
+            )]))),
+        ),
+        (
+            "id key map",
+            encoded(&[(3u64, 1u8)].into_iter().collect::<IdMap<u8>>()),
+        ),
+        ("duration", encoded(&Duration::new(1, 1))),
+        ("system time", encoded(&UNIX_EPOCH)),
+        ("none", encoded(&None::<u8>)),
+        ("some", encoded(&Some(1u8))),
+    ];
+
+    let mut flat = BytesMut::new();
+    encode_flat_key_map(&BTreeMap::from([((1u8, 2u8), 3u8)]), &mut flat).unwrap();
+    values.push(("flat key map", flat.freeze()));
+
+    // Legacy binary float and decimal formats, still accepted by the decoders
+    let mut float = BytesMut::new();
+    float.put_u8(TAG_F32);
+    float.put_f32_le(1.5);
+    values.push(("f32", float.freeze()));
+    let mut float = BytesMut::new();
+    float.put_u8(TAG_F64);
+    float.put_f64_le(2.5);
+    values.push(("f64", float.freeze()));
+    let mut decimal = BytesMut::new();
+    decimal.put_u8(TAG_DECIMAL);
+    12_345i128.encode(&mut decimal).unwrap();
+    2u32.encode(&mut decimal).unwrap();
+    values.push(("decimal", decimal.freeze()));
+
+    #[cfg(feature = "chrono")]
+    {
+        use chrono::{DateTime, NaiveDate, NaiveTime};
+        let at = DateTime::from_timestamp(1, 0).unwrap();
+        values.push(("datetime", encoded(&at)));
+        values.push(("naive date", encoded(&NaiveDate::default())));
+        values.push(("naive time", encoded(&NaiveTime::default())));
+        values.push(("naive datetime", encoded(&at.naive_utc())));
+    }
+    #[cfg(feature = "uuid")]
+    values.push(("uuid", encoded(&uuid::Uuid::nil())));
+    #[cfg(feature = "serde_json")]
+    {
+        use serde_json::json;
+        values.push(("json null", encoded(&json!(null))));
+        values.push(("json bool", encoded(&json!(true))));
+        values.push(("json number", encoded(&json!(-1.5))));
+        values.push(("json string", encoded(&json!("a"))));
+        values.push(("json array", encoded(&json!([false, 1]))));
+        values.push(("json object", encoded(&json!({"b": true}))));
+    }
+    values
+}
+
+#[test]
+fn test_skip_every_tag() {
+    let values = every_tag();
+    let mut tags: Vec<u8> = values.iter().map(|(_, value)| value[0]).collect();
+    tags.sort_unstable();
+    tags.dedup();
+
+    let mut expected = vec![
+        TAG_ZERO,
+        TAG_ONE,
+        TAG_U8_127,
+        TAG_NONE,
+        TAG_SOME,
+        TAG_U8,
+        TAG_U16,
+        TAG_U32,
+        TAG_U64,
+        TAG_U128,
+        TAG_NEGATIVE,
+        TAG_F32,
+        TAG_F64,
+        TAG_STRING_BASE + 3,
+        TAG_STRING_LONG,
+        TAG_BINARY,
+        TAG_STRUCT_UNIT,
+        TAG_STRUCT_NAMED,
+        TAG_STRUCT_UNNAMED,
+        TAG_ENUM,
+        TAG_ENUM_NAMED,
+        TAG_ENUM_UNNAMED,
+        TAG_ARRAY_VEC_SET_BASE + 2,
+        TAG_ARRAY_VEC_SET_LONG,
+        TAG_TUPLE,
+        TAG_MAP,
+        TAG_MAP_STRING_KEYS,
+        TAG_MAP_FLAT_KEYS,
+        TAG_MAP_ID_KEYS,
+        TAG_DURATION,
+        TAG_SYSTEM_TIME,
+        TAG_DECIMAL,
+    ];
+    #[cfg(feature = "chrono")]
+    expected.extend([
+        TAG_CHRONO_DATETIME,
+        TAG_CHRONO_NAIVE_DATE,
+        TAG_CHRONO_NAIVE_TIME,
+        TAG_CHRONO_NAIVE_DATETIME,
+    ]);
+    #[cfg(feature = "uuid")]
+    expected.push(TAG_UUID);
+    #[cfg(feature = "serde_json")]
+    expected.extend(TAG_JSON_NULL..=TAG_JSON_OBJECT);
+    expected.sort_unstable();
+    assert_eq!(tags, expected);
+
+    let mut all = BytesMut::new();
+    for (name, value) in &values {
+        let mut reader = value.clone();
+        skip_value(&mut reader).unwrap_or_else(|e| panic!("{}: {}", name, e));
+        assert!(reader.is_empty(), "{}: {} bytes left", name, reader.len());
+        all.extend_from_slice(value);
+    }
+    let mut reader = all.freeze();
+    skip_n_values(&mut reader, values.len()).unwrap();
+    assert!(reader.is_empty());
+}
@@ -0,0 +1,141 @@
+#![cfg(any(feature = "smallvec", feature = "arrayvec"))]
+
+#[cfg(feature = "smallvec")]
+mod smallvec_tests {
+    use senax_encoder::{decode, encode, pack, unpack, Decode, Encode};
+    use smallvec::{smallvec, SmallVec};
+
+    #[derive(Encode, Decode, Debug, PartialEq)]
+    struct PathV1 {
+        segments: Vec<u32>,
+    }
+
+    #[derive(Encode, Decode, Debug, PartialEq)]
+    struct PathV2 {
+        segments: SmallVec<[u32; 4]>,
+    }
+
+    #[test]
+    fn test_smallvec_matches_vec_encoding() {
+        let inline: SmallVec<[u32; 4]> = smallvec![1, 2, 3];
+        assert_eq!(encode(&inline).unwrap(), encode(&vec![1u32, 2, 3]).unwrap());
+        let spilled: SmallVec<[String; 2]> =
+            smallvec!["a".to_string(), "b".to_string(), "c".to_string()];
+        assert!(spilled.spilled());
+        assert_eq!(
+            encode(&spilled).unwrap(),
+            encode(&vec!["a".to_string(), "b".to_string(), "c".to_string()]).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_smallvec_field_swaps_with_vec() {
+        let v1 = PathV1 {
+            segments: vec![10, 20, 30, 40, 50],
+        };
+        let v2: PathV2 = decode(&mut encode(&v1).unwrap()).unwrap();
+        assert_eq!(v2.segments.as_slice(), &[10, 20, 30, 40, 50]);
+        assert!(v2.segments.spilled());
+
+        let back: PathV1 = decode(&mut encode(&v2).unwrap()).unwrap();
+        assert_eq!(back, v1);
+    }
+
+    #[test]
+    fn test_smallvec_pack_round_trip() {
+        let value: SmallVec<[i16; 3]> = smallvec![-1, 0, 1];
+        let mut bytes = pack(&value).unwrap();
+        assert_eq!(unpack::<SmallVec<[i16; 3]>>(&mut bytes).unwrap(), value);
+        let mut bytes = pack(&value).unwrap();
+        assert_eq!(unpack::<Vec<i16>>(&mut bytes).unwrap(), vec![-1, 0, 1]);
+    }
+}
+
+#[cfg(feature = "arrayvec")]
+mod arrayvec_tests {
+    use arrayvec::{ArrayString, ArrayVec};
+    use senax_encoder::{decode, encode, pack, unpack, Decode, Encode, EncoderError, Pack, Unpack};
+
+    #[derive(Encode, Decode, Debug, PartialEq)]
+    struct ReadingV1 {
+        sensor: String,
+        samples: Vec<i32>,
+    }
+
+    #[derive(Encode, Decode, Pack, Unpack, Debug, PartialEq)]
+    struct ReadingV2 {
+        sensor: ArrayString<16>,
+        samples: ArrayVec<i32, 8>,
+    }
+
+    #[test]
+    fn test_arrayvec_matches_vec_encoding() {
+        let samples: ArrayVec<i32, 8> = [3, -4, 5].into_iter().collect();
+        assert_eq!(encode(&samples).unwrap(), encode(&vec![3, -4, 5]).unwrap());
+        let sensor = ArrayString::<16>::from("thermo-1").unwrap();
+        assert_eq!(
+            encode(&sensor).unwrap(),
+            encode(&"thermo-1".to_string()).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_arrayvec_fields_swap_with_vec_and_string() {
+        let v1 = ReadingV1 {
+            sensor: "thermo-1".into(),
+            samples: vec![215, 216, 214],
+        };
+        let v2: ReadingV2 = decode(&mut encode(&v1).unwrap()).unwrap();
+        assert_eq!(v2.sensor.as_str(), "thermo-1");
+        assert_eq!(v2.samples.as_slice(), &[215, 216, 214]);
+
+        let back: ReadingV1 = decode(&mut encode(&v2).unwrap()).unwrap();
+        assert_eq!(back, v1);
+    }
+
+    #[test]
+    fn test_arrayvec_over_capacity_fails() {
+        let mut bytes = encode(&vec![1u8, 2, 3, 4, 5]).unwrap();
+        let err = decode::<ArrayVec<u8, 4>>(&mut bytes).unwrap_err();
+        assert!(matches!(
+            err,
+            EncoderError::CapacityExceeded {
+                len: 5,
+                capacity: 4
+            }
+        ));
+    }
+
+    #[test]
+    fn test_arraystring_over_capacity_fails() {
+        let long = "x".repeat(40);
+        let mut bytes = encode(&long).unwrap();
+        let err = decode::<ArrayString<32>>(&mut bytes).unwrap_err();
+        assert!(matches!(
+            err,
+            EncoderError::CapacityExceeded {
+                len: 40,
+                capacity: 32
+            }
+        ));
+
+        // Strings past the short-string tag range still decode when they fit
+        let mut bytes = encode(&long).unwrap();
+        assert_eq!(
+            decode::<ArrayString<64>>(&mut bytes).unwrap().as_str(),
+            long
+        );
+    }
+
+    #[test]
+    fn test_arrayvec_pack_round_trip() {
+        let value = ReadingV2 {
+            sensor: ArrayString::from("hygro").unwrap(),
+            samples: [1, 2].into_iter().collect(),
+        };
+        let mut bytes = pack(&value).unwrap();
+        assert_eq!(unpack::<ReadingV2>(&mut bytes).unwrap(), value);
+        let mut bytes = pack(&value.samples).unwrap();
+        assert_eq!(unpack::<Vec<i32>>(&mut bytes).unwrap(), vec![1, 2]);
+    }
+}
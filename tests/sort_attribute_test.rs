@@ -0,0 +1,75 @@
+use senax_encoder::{decode, encode, pack, unpack, Decode, Encode, Pack, Unpack};
+use std::collections::HashSet;
+
+#[derive(Encode, Decode, Pack, Unpack, Debug, PartialEq)]
+struct CacheKey {
+    #[senax(sort)]
+    tags: Vec<String>,
+    #[senax(sort)]
+    ids: HashSet<u64>,
+    #[senax(sort)]
+    scopes: Option<Vec<u8>>,
+    order: Vec<u32>,
+}
+
+#[derive(Encode, Decode, Debug, PartialEq)]
+struct PlainKey {
+    tags: Vec<String>,
+    ids: Vec<u64>,
+    scopes: Option<Vec<u8>>,
+    order: Vec<u32>,
+}
+
+fn key(tags: &[&str], ids: &[u64], scopes: Option<Vec<u8>>) -> CacheKey {
+    CacheKey {
+        tags: tags.iter().map(|t| t.to_string()).collect(),
+        ids: ids.iter().copied().collect(),
+        scopes,
+        order: vec![3, 1, 2],
+    }
+}
+
+#[test]
+fn test_sorted_fields_encode_deterministically() {
+    let a = key(&["b", "c", "a"], &[30, 10, 20, 40], Some(vec![9, 2, 5]));
+    let b = key(&["a", "c", "b"], &[40, 20, 10, 30], Some(vec![5, 9, 2]));
+    assert_eq!(encode(&a).unwrap(), encode(&b).unwrap());
+    assert_eq!(pack(&a).unwrap(), pack(&b).unwrap());
+}
+
+#[test]
+fn test_sorted_fields_decode_in_ascending_order() {
+    let value = key(&["b", "c", "a"], &[30, 10, 20], Some(vec![9, 2, 5]));
+    let plain: PlainKey = decode(&mut encode(&value).unwrap()).unwrap();
+    assert_eq!(plain.tags, vec!["a", "b", "c"]);
+    assert_eq!(plain.ids, vec![10, 20, 30]);
+    assert_eq!(plain.scopes, Some(vec![2, 5, 9]));
+    // Fields without the attribute keep their order
+    assert_eq!(plain.order, vec![3, 1, 2]);
+}
+
+#[test]
+fn test_sorted_fields_round_trip() {
+    let value = key(&["x", "a"], &[2, 1], None);
+    let decoded: CacheKey = decode(&mut encode(&value).unwrap()).unwrap();
+    assert_eq!(decoded.tags, vec!["a", "x"]);
+    assert_eq!(decoded.ids, value.ids);
+    assert_eq!(decoded.scopes, None);
+
+    let unpacked: CacheKey = unpack(&mut pack(&value).unwrap()).unwrap();
+    assert_eq!(unpacked.tags, vec!["a", "x"]);
+    assert_eq!(unpacked.ids, value.ids);
+    assert_eq!(unpacked.scopes, None);
+}
+
+#[test]
+fn test_sort_does_not_change_the_wire_format() {
+    let sorted = key(&["a", "b"], &[1], Some(vec![1, 2]));
+    let plain = PlainKey {
+        tags: vec!["a".into(), "b".into()],
+        ids: vec![1],
+        scopes: Some(vec![1, 2]),
+        order: vec![3, 1, 2],
+    };
+    assert_eq!(encode(&sorted).unwrap(), encode(&plain).unwrap());
+}
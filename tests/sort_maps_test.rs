@@ -0,0 +1,85 @@
+use bytes::BytesMut;
+use senax_encoder::{
+    decode, encode_with_config, with_encode_config, Decode, Encode, EncodeConfig, Encoder, Packer,
+};
+use std::collections::{BTreeMap, HashMap};
+
+#[derive(Encode, Decode, Debug, PartialEq)]
+struct Snapshot {
+    counts: HashMap<String, u32>,
+    nested: HashMap<u64, HashMap<i32, String>>,
+}
+
+fn sorted() -> EncodeConfig {
+    EncodeConfig {
+        sort_maps: true,
+        ..Default::default()
+    }
+}
+
+fn build_map(order: impl Iterator<Item = u32>) -> HashMap<String, u32> {
+    let mut map = HashMap::new();
+    for i in order {
+        map.insert(format!("key-{}", i), i);
+    }
+    map
+}
+
+#[test]
+fn test_sort_maps_is_independent_of_insertion_and_hash_order() {
+    let a = build_map(0..100);
+    let b = build_map((0..100).rev());
+    let bytes_a = encode_with_config(&a, &sorted()).unwrap();
+    let bytes_b = encode_with_config(&b, &sorted()).unwrap();
+    assert_eq!(bytes_a, bytes_b);
+
+    let mut reader = bytes_a;
+    let decoded: HashMap<String, u32> = decode(&mut reader).unwrap();
+    assert_eq!(decoded, a);
+}
+
+#[test]
+fn test_sort_maps_orders_by_encoded_key_bytes() {
+    let map: HashMap<u8, u8> = (0..50).map(|i| (i, i)).collect();
+    let sorted_bytes = encode_with_config(&map, &sorted()).unwrap();
+    let btree: BTreeMap<u8, u8> = map.into_iter().collect();
+    let btree_bytes = senax_encoder::encode(&btree).unwrap();
+    assert_eq!(sorted_bytes, btree_bytes);
+}
+
+#[test]
+fn test_sort_maps_applies_to_nested_maps_and_pack() {
+    let value = Snapshot {
+        counts: build_map(0..20),
+        nested: (0..10)
+            .map(|i| (i, (0..10).map(|j| (-j, j.to_string())).collect()))
+            .collect(),
+    };
+    let first = encode_with_config(&value, &sorted()).unwrap();
+    let rebuilt = Snapshot {
+        counts: build_map((0..20).rev()),
+        nested: (0..10)
+            .rev()
+            .map(|i| (i, (0..10).rev().map(|j| (-j, j.to_string())).collect()))
+            .collect(),
+    };
+    assert_eq!(first, encode_with_config(&rebuilt, &sorted()).unwrap());
+
+    let pack_sorted = |map: &HashMap<String, u32>| {
+        let mut writer = BytesMut::new();
+        with_encode_config(&sorted(), || map.pack(&mut writer)).unwrap();
+        writer.freeze()
+    };
+    assert_eq!(pack_sorted(&value.counts), pack_sorted(&rebuilt.counts));
+}
+
+#[test]
+fn test_encode_config_is_restored() {
+    let map = build_map(0..3);
+    let mut writer = BytesMut::new();
+    with_encode_config(&sorted(), || map.encode(&mut writer)).unwrap();
+    assert_eq!(
+        senax_encoder::current_encode_config(),
+        EncodeConfig::default()
+    );
+}
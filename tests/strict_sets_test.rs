@@ -0,0 +1,97 @@
+use bytes::BytesMut;
+use senax_encoder::{
+    decode, decode_with_config, encode, pack, unpack, with_decode_config, DecodeConfig, Decoder,
+    Encoder, EncoderError,
+};
+use std::collections::{BTreeSet, HashSet};
+
+fn strict() -> DecodeConfig {
+    DecodeConfig {
+        strict_sets: true,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_lenient_sets_merge_duplicates() {
+    let mut buf = encode(&vec![3u32, 1, 3, 2, 1]).unwrap();
+    let set: BTreeSet<u32> = decode(&mut buf).unwrap();
+    assert_eq!(set, BTreeSet::from([1, 2, 3]));
+}
+
+#[test]
+fn test_strict_sets_report_duplicate_index() {
+    let mut buf = encode(&vec![3u32, 1, 3, 2, 1]).unwrap();
+    let err = decode_with_config::<HashSet<u32>>(&mut buf, &strict()).unwrap_err();
+    assert!(matches!(
+        err,
+        EncoderError::DuplicateSetElement { index: 2 }
+    ));
+
+    let mut buf = encode(&vec!["a".to_string(), "b".to_string(), "b".to_string()]).unwrap();
+    let err = decode_with_config::<BTreeSet<String>>(&mut buf, &strict()).unwrap_err();
+    assert!(matches!(
+        err,
+        EncoderError::DuplicateSetElement { index: 2 }
+    ));
+}
+
+#[test]
+fn test_strict_sets_accept_unique_elements() {
+    let original: HashSet<u32> = [1, 2, 3].into_iter().collect();
+    let mut buf = encode(&original).unwrap();
+    let decoded: HashSet<u32> = decode_with_config(&mut buf, &strict()).unwrap();
+    assert_eq!(original, decoded);
+
+    let mut buf = encode(&vec![1u32, 2, 3]).unwrap();
+    let decoded: BTreeSet<u32> = decode_with_config(&mut buf, &strict()).unwrap();
+    assert_eq!(decoded, BTreeSet::from([1, 2, 3]));
+}
+
+#[test]
+fn test_strict_sets_with_unpack() {
+    let mut buf = pack(&vec![5u8, 5]).unwrap();
+    let result = with_decode_config(&strict(), || unpack::<BTreeSet<u8>>(&mut buf));
+    assert!(matches!(
+        result,
+        Err(EncoderError::DuplicateSetElement { index: 1 })
+    ));
+}
+
+#[test]
+fn test_decode_config_is_restored() {
+    let mut writer = BytesMut::new();
+    vec![1u32, 1].encode(&mut writer).unwrap();
+    let bytes = writer.freeze();
+
+    let result = with_decode_config(&strict(), || HashSet::<u32>::decode(&mut bytes.clone()));
+    assert!(result.is_err());
+    assert_eq!(
+        senax_encoder::current_decode_config(),
+        DecodeConfig::default()
+    );
+
+    let set = HashSet::<u32>::decode(&mut bytes.clone()).unwrap();
+    assert_eq!(set.len(), 1);
+}
+
+#[test]
+fn test_sets_decode_as_vec() {
+    let mut buf = encode(&BTreeSet::from([1u32, 2, 3])).unwrap();
+    let vec: Vec<u32> = decode(&mut buf).unwrap();
+    assert_eq!(vec, vec![1, 2, 3]);
+}
+
+#[cfg(feature = "indexmap")]
+#[test]
+fn test_strict_index_set() {
+    use indexmap::IndexSet;
+    let mut buf = encode(&vec![2u32, 1, 2]).unwrap();
+    let lenient: IndexSet<u32> = decode(&mut buf.clone()).unwrap();
+    assert_eq!(lenient.into_iter().collect::<Vec<_>>(), vec![2, 1]);
+    let err = decode_with_config::<IndexSet<u32>>(&mut buf, &strict()).unwrap_err();
+    assert!(matches!(
+        err,
+        EncoderError::DuplicateSetElement { index: 2 }
+    ));
+}
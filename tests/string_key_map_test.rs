@@ -0,0 +1,145 @@
+use bytes::{BufMut, Bytes, BytesMut};
+use senax_encoder::core::{TAG_MAP, TAG_MAP_STRING_KEYS};
+use senax_encoder::schema::{SchemaResolver, TypeSchema};
+use senax_encoder::{
+    decode, encode, pack, query, unpack, validate_payload, Decode, Decoder, Encode, Encoder,
+    QueryMatch, StringKeyMap, Value,
+};
+use std::collections::{BTreeMap, HashMap};
+
+#[derive(Encode, Decode, Debug, PartialEq)]
+struct Labels {
+    labels: StringKeyMap<String>,
+    counts: StringKeyMap<u32>,
+    ids: BTreeMap<u32, String>,
+}
+
+fn sample() -> Labels {
+    Labels {
+        labels: [
+            ("env".to_string(), "prod".to_string()),
+            ("region".to_string(), "eu-west-1".to_string()),
+        ]
+        .into_iter()
+        .collect(),
+        counts: [("errors".to_string(), 2), ("requests".to_string(), 150)]
+            .into_iter()
+            .collect(),
+        ids: BTreeMap::from([(7, "seven".to_string())]),
+    }
+}
+
+#[test]
+fn test_plain_string_keyed_maps_keep_tagged_keys() {
+    let mut expected = BytesMut::new();
+    expected.put_u8(TAG_MAP);
+    1usize.encode(&mut expected).unwrap();
+    "a".to_string().encode(&mut expected).unwrap();
+    1u32.encode(&mut expected).unwrap();
+
+    let map = BTreeMap::from([("a".to_string(), 1u32)]);
+    let mut writer = BytesMut::new();
+    map.encode(&mut writer).unwrap();
+    assert_eq!(writer, expected);
+
+    let map = HashMap::from([("a".to_string(), 1u32)]);
+    let mut writer = BytesMut::new();
+    map.encode(&mut writer).unwrap();
+    assert_eq!(writer, expected);
+}
+
+#[test]
+fn test_string_key_map_uses_untagged_keys() {
+    let map = StringKeyMap::from(BTreeMap::from([
+        ("a".to_string(), 1u32),
+        ("bc".to_string(), 2),
+    ]));
+    let mut writer = BytesMut::new();
+    map.encode(&mut writer).unwrap();
+    assert_eq!(
+        writer.as_ref(),
+        &[TAG_MAP_STRING_KEYS, 2, 1, b'a', 1, 2, b'b', b'c', 2]
+    );
+}
+
+#[test]
+fn test_string_key_map_round_trip() {
+    let value = sample();
+    let bytes = encode(&value).unwrap();
+    let decoded: Labels = decode(&mut bytes.clone()).unwrap();
+    assert_eq!(decoded, value);
+
+    // Pack output matches BTreeMap<String, V>
+    let packed = pack(&value.counts).unwrap();
+    assert_eq!(packed, pack(&value.counts.0).unwrap());
+    assert_eq!(
+        unpack::<StringKeyMap<u32>>(&mut packed.clone()).unwrap(),
+        value.counts
+    );
+}
+
+#[test]
+fn test_string_key_map_reads_plain_maps() {
+    // A field switched from BTreeMap<String, V> still reads payloads written before the change
+    let map = BTreeMap::from([("k".to_string(), 5u32)]);
+    let mut writer = BytesMut::new();
+    map.encode(&mut writer).unwrap();
+
+    let decoded = StringKeyMap::<u32>::decode(&mut writer.freeze()).unwrap();
+    assert_eq!(decoded.into_inner(), map);
+}
+
+#[test]
+fn test_plain_maps_reject_untagged_keys() {
+    let map = StringKeyMap::from(BTreeMap::from([("k".to_string(), 5u32)]));
+    let mut writer = BytesMut::new();
+    map.encode(&mut writer).unwrap();
+    assert!(HashMap::<String, u32>::decode(&mut writer.freeze()).is_err());
+}
+
+#[test]
+fn test_invalid_utf8_key_is_rejected() {
+    let mut writer = BytesMut::new();
+    writer.put_u8(TAG_MAP_STRING_KEYS);
+    1usize.encode(&mut writer).unwrap();
+    2usize.encode(&mut writer).unwrap();
+    writer.put_slice(&[0xC3, 0x28]);
+    5u32.encode(&mut writer).unwrap();
+    let bytes = writer.freeze();
+
+    let err = StringKeyMap::<u32>::decode(&mut bytes.clone()).unwrap_err();
+    assert!(
+        err.to_string().contains("Invalid UTF-8 in map key"),
+        "{}",
+        err
+    );
+
+    let mut payload = BytesMut::new();
+    payload.put_u16_le(0xA55A);
+    payload.put_slice(&bytes);
+    let report = validate_payload::<StringKeyMap<u32>>(&payload.freeze());
+    assert!(!report.is_valid());
+}
+
+#[test]
+fn test_string_key_map_in_dynamic_values_and_queries() {
+    let bytes: Bytes = encode(&sample()).unwrap();
+    let value: Value = decode(&mut bytes.clone()).unwrap();
+    assert_eq!(
+        value.field_by_id(senax_encoder::core::field_id_from_name("counts")),
+        Some(&Value::Map(vec![
+            (Value::String("errors".into()), Value::UInt(2)),
+            (Value::String("requests".into()), Value::UInt(150)),
+        ]))
+    );
+    assert_eq!(
+        query(&bytes, "labels.region").unwrap(),
+        Some(QueryMatch::Scalar(Value::String("eu-west-1".into())))
+    );
+    assert!(validate_payload::<Labels>(&bytes).is_valid());
+
+    // The dynamic model re-encodes maps with tagged keys, which StringKeyMap reads too
+    let resolver = SchemaResolver::new(&TypeSchema::Any, &TypeSchema::Any).unwrap();
+    let decoded: Labels = resolver.decode(&mut bytes.clone()).unwrap();
+    assert_eq!(decoded, sample());
+}
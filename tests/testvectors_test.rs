@@ -0,0 +1,88 @@
+use senax_encoder::testvectors::{test_vectors, value_to_json, write_test_vectors};
+use senax_encoder::value::{Field, Variant, VariantFields};
+use senax_encoder::Value;
+use std::collections::HashSet;
+
+fn vector(name: &str) -> senax_encoder::testvectors::TestVector {
+    test_vectors()
+        .unwrap()
+        .into_iter()
+        .find(|v| v.name == name)
+        .unwrap()
+}
+
+#[test]
+fn test_corpus_covers_valid_and_invalid_payloads() {
+    let vectors = test_vectors().unwrap();
+    let names: HashSet<_> = vectors.iter().map(|v| v.name).collect();
+    assert_eq!(names.len(), vectors.len(), "vector names must be unique");
+    for vector in &vectors {
+        assert_eq!(
+            vector.expected.is_none(),
+            vector.name.starts_with("invalid_"),
+            "{}",
+            vector.name
+        );
+    }
+}
+
+#[test]
+fn test_expected_values() {
+    assert_eq!(vector("uint_u8").bytes.as_ref(), &[0x5A, 0xA5, 131, 0]);
+    assert_eq!(vector("int_i128_min").expected, Some(Value::Int(i128::MIN)));
+    assert_eq!(
+        vector("string_long").expected,
+        Some(Value::String("b".repeat(41)))
+    );
+    assert_eq!(
+        vector("enum_unnamed").expected,
+        Some(Value::Enum(Box::new(Variant {
+            id: senax_encoder::core::field_id_from_name("Line"),
+            name: None,
+            fields: VariantFields::Unnamed(vec![Value::Int(-1), Value::UInt(1)]),
+        })))
+    );
+}
+
+#[test]
+fn test_json_rendering() {
+    let value = Value::Struct(vec![
+        Field {
+            id: 1,
+            name: Some("a\"b".into()),
+            value: Value::List(vec![Value::UInt(u128::MAX), Value::Float(0.5)]),
+        },
+        Field {
+            id: 2,
+            name: None,
+            value: Value::Map(vec![(Value::String("k".into()), Value::None)]),
+        },
+    ]);
+    assert_eq!(
+        value_to_json(&value),
+        concat!(
+            r#"{"type":"struct","fields":["#,
+            r#"{"id":"1","name":"a\"b","value":{"type":"list","items":["#,
+            r#"{"type":"uint","value":"340282366920938463463374607431768211455"},"#,
+            r#"{"type":"float","value":"0.5"}]}},"#,
+            r#"{"id":"2","name":null,"value":{"type":"map","entries":["#,
+            r#"[{"type":"string","value":"k"},{"type":"none"}]]}}]}"#
+        )
+    );
+    assert_eq!(
+        vector("invalid_utf8").to_json(),
+        r#"{"name":"invalid_utf8","description":"String bytes that are not UTF-8","hex":"5aa58dc328","expected":null}"#
+    );
+}
+
+#[test]
+fn test_write_test_vectors() {
+    let dir = std::env::temp_dir().join(format!("senax-testvectors-{}", std::process::id()));
+    let count = write_test_vectors(&dir).unwrap();
+    assert_eq!(count, test_vectors().unwrap().len());
+    let bytes = std::fs::read(dir.join("map_string_keys.bin")).unwrap();
+    assert_eq!(bytes, vector("map_string_keys").bytes.to_vec());
+    let json = std::fs::read_to_string(dir.join("map_string_keys.json")).unwrap();
+    assert!(json.starts_with(r#"{"name":"map_string_keys","#));
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,46 @@
+#![cfg(feature = "textenc")]
+
+use senax_encoder::{decode_base64, decode_hex, encode, encode_base64, encode_hex, Decode, Encode};
+
+#[derive(Encode, Decode, Debug, PartialEq)]
+struct Header {
+    token: String,
+    scopes: Vec<String>,
+    expires: u64,
+}
+
+fn sample() -> Header {
+    Header {
+        token: "abc".to_string(),
+        scopes: vec!["read".to_string(), "write".to_string()],
+        expires: 1_700_000_000,
+    }
+}
+
+#[test]
+fn test_base64_round_trip() {
+    let text = encode_base64(&sample()).unwrap();
+    assert!(text.is_ascii());
+    let decoded: Header = decode_base64(&text).unwrap();
+    assert_eq!(decoded, sample());
+    // Surrounding whitespace (e.g. from config files) is ignored
+    let decoded: Header = decode_base64(&format!(" {}\n", text)).unwrap();
+    assert_eq!(decoded, sample());
+}
+
+#[test]
+fn test_hex_round_trip() {
+    let text = encode_hex(&sample()).unwrap();
+    assert_eq!(text.len(), encode(&sample()).unwrap().len() * 2);
+    assert!(text.starts_with("5aa5"));
+    let decoded: Header = decode_hex(&text.to_uppercase()).unwrap();
+    assert_eq!(decoded, sample());
+}
+
+#[test]
+fn test_invalid_text_is_rejected() {
+    assert!(decode_base64::<Header>("not base64!").is_err());
+    assert!(decode_hex::<Header>("zz").is_err());
+    // Valid hex but not a senax payload
+    assert!(decode_hex::<Header>("0000").is_err());
+}
@@ -0,0 +1,255 @@
+#![cfg(feature = "time")]
+
+use bytes::{Bytes, BytesMut};
+use senax_encoder::{
+    decode, encode, pack, unpack, Decode, Decoder, Encode, Encoder, Pack, Packer, Unpack, Unpacker,
+};
+use time::{Date, Month, OffsetDateTime, PrimitiveDateTime, Time, UtcOffset};
+
+#[derive(Encode, Decode, Pack, Unpack, Debug, PartialEq)]
+struct Event {
+    name: String,
+    at: OffsetDateTime,
+    local: PrimitiveDateTime,
+    day: Date,
+    start: Time,
+    #[senax(default)]
+    end: Option<Time>,
+}
+
+fn date(year: i32, month: Month, day: u8) -> Date {
+    Date::from_calendar_date(year, month, day).unwrap()
+}
+
+fn time(hour: u8, minute: u8, second: u8, nano: u32) -> Time {
+    Time::from_hms_nano(hour, minute, second, nano).unwrap()
+}
+
+fn event() -> Event {
+    let day = date(2024, Month::February, 29);
+    let start = time(9, 30, 15, 123_456_789);
+    Event {
+        name: "launch".to_string(),
+        at: PrimitiveDateTime::new(day, start).assume_utc(),
+        local: PrimitiveDateTime::new(day, start),
+        day,
+        start,
+        end: Some(time(23, 59, 59, 999_999_999)),
+    }
+}
+
+fn raw<T: Encoder>(value: &T) -> Bytes {
+    let mut buf = BytesMut::new();
+    value.encode(&mut buf).unwrap();
+    buf.freeze()
+}
+
+fn raw_pack<T: Packer>(value: &T) -> Bytes {
+    let mut buf = BytesMut::new();
+    value.pack(&mut buf).unwrap();
+    buf.freeze()
+}
+
+#[test]
+fn test_round_trip() {
+    let value = event();
+    assert_eq!(
+        decode::<Event>(&mut encode(&value).unwrap()).unwrap(),
+        value
+    );
+    assert_eq!(unpack::<Event>(&mut pack(&value).unwrap()).unwrap(), value);
+}
+
+#[test]
+fn test_pre_epoch_values_round_trip() {
+    let day = date(1900, Month::January, 1);
+    let at = PrimitiveDateTime::new(day, time(0, 0, 0, 500)).assume_utc();
+    assert_eq!(OffsetDateTime::decode(&mut raw(&at)).unwrap(), at);
+    assert_eq!(OffsetDateTime::unpack(&mut raw_pack(&at)).unwrap(), at);
+    assert_eq!(Date::decode(&mut raw(&day)).unwrap(), day);
+    assert_eq!(Date::unpack(&mut raw_pack(&day)).unwrap(), day);
+}
+
+#[test]
+fn test_offset_is_normalized_to_utc() {
+    let offset = UtcOffset::from_hms(9, 0, 0).unwrap();
+    let at = event().at.to_offset(offset);
+    let decoded = OffsetDateTime::decode(&mut raw(&at)).unwrap();
+    assert_eq!(decoded, at);
+    assert_eq!(decoded.offset(), UtcOffset::UTC);
+}
+
+#[test]
+fn test_epoch_is_default() {
+    let epoch = OffsetDateTime::UNIX_EPOCH;
+    assert!(epoch.is_default());
+    assert!(PrimitiveDateTime::new(epoch.date(), epoch.time()).is_default());
+    assert!(epoch.date().is_default());
+    assert!(Time::MIDNIGHT.is_default());
+    assert!(!event().at.is_default());
+    assert_eq!(
+        OffsetDateTime::unpack(&mut raw_pack(&epoch)).unwrap(),
+        epoch
+    );
+}
+
+#[test]
+fn test_system_time_cross_decodes() {
+    let at = event().at;
+    let system_time = std::time::UNIX_EPOCH
+        + std::time::Duration::new(at.unix_timestamp() as u64, at.nanosecond());
+    assert_eq!(
+        std::time::SystemTime::decode(&mut raw(&at)).unwrap(),
+        system_time
+    );
+    assert_eq!(OffsetDateTime::decode(&mut raw(&system_time)).unwrap(), at);
+}
+
+#[test]
+fn test_out_of_range_values_fail() {
+    let mut buf = BytesMut::new();
+    buf.extend_from_slice(&raw(&time(0, 0, 0, 0))[..1]);
+    86_400u32.encode(&mut buf).unwrap();
+    0u32.encode(&mut buf).unwrap();
+    assert!(Time::decode(&mut buf.freeze()).is_err());
+
+    let mut buf = BytesMut::new();
+    buf.extend_from_slice(&raw(&date(2000, Month::January, 1))[..1]);
+    (i32::MAX as i64).encode(&mut buf).unwrap();
+    assert!(Date::decode(&mut buf.freeze()).is_err());
+
+    let mut buf = BytesMut::new();
+    buf.extend_from_slice(&raw(&OffsetDateTime::UNIX_EPOCH)[..1]);
+    0i64.encode(&mut buf).unwrap();
+    1_000_000_000u32.encode(&mut buf).unwrap();
+    assert!(OffsetDateTime::decode(&mut buf.freeze()).is_err());
+}
+
+#[test]
+fn test_mismatched_tag_fails() {
+    let at = event().at;
+    assert!(Date::decode(&mut raw(&at)).is_err());
+    assert!(Time::decode(&mut raw(&at)).is_err());
+    assert!(PrimitiveDateTime::decode(&mut raw(&at)).is_err());
+}
+
+#[test]
+fn test_older_readers_skip_time_fields() {
+    #[derive(Encode, Decode, Debug, PartialEq)]
+    struct V1 {
+        id: u64,
+    }
+
+    #[derive(Encode, Decode, Debug, PartialEq)]
+    struct V2<T> {
+        id: u64,
+        at: T,
+    }
+
+    // The time field is the last value in the payload
+    fn check<T: Encoder + 'static>(at: T) {
+        let bytes = encode(&V2 { id: 7, at }).unwrap();
+        assert_eq!(decode::<V1>(&mut bytes.clone()).unwrap(), V1 { id: 7 });
+    }
+
+    let epoch = OffsetDateTime::UNIX_EPOCH;
+    let value = event();
+    check(epoch);
+    check(value.at);
+    check(PrimitiveDateTime::new(epoch.date(), epoch.time()));
+    check(value.local);
+    check(date(1970, Month::January, 2));
+    check(value.day);
+    check(time(0, 0, 1, 0));
+    check(value.start);
+
+    #[derive(Encode, Decode, Debug, PartialEq)]
+    struct NameOnly {
+        name: String,
+    }
+    let old: NameOnly = decode(&mut encode(&value).unwrap()).unwrap();
+    assert_eq!(old.name, "launch");
+}
+
+#[cfg(feature = "chrono")]
+mod chrono_compat {
+    use super::*;
+    use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+
+    #[test]
+    fn test_offset_date_time_matches_chrono_date_time() {
+        let at = event().at;
+        let chrono_at =
+            DateTime::<Utc>::from_timestamp(at.unix_timestamp(), at.nanosecond()).unwrap();
+        assert_eq!(raw(&at), raw(&chrono_at));
+        assert_eq!(raw_pack(&at), raw_pack(&chrono_at));
+        assert_eq!(DateTime::<Utc>::decode(&mut raw(&at)).unwrap(), chrono_at);
+        assert_eq!(OffsetDateTime::decode(&mut raw(&chrono_at)).unwrap(), at);
+    }
+
+    #[test]
+    fn test_primitive_date_time_matches_naive_date_time() {
+        let local = event().local;
+        let naive = NaiveDate::from_ymd_opt(2024, 2, 29)
+            .unwrap()
+            .and_hms_nano_opt(9, 30, 15, 123_456_789)
+            .unwrap();
+        assert_eq!(raw(&local), raw(&naive));
+        assert_eq!(raw_pack(&local), raw_pack(&naive));
+        assert_eq!(NaiveDateTime::unpack(&mut raw_pack(&local)).unwrap(), naive);
+        assert_eq!(PrimitiveDateTime::decode(&mut raw(&naive)).unwrap(), local);
+    }
+
+    #[test]
+    fn test_date_and_time_match_naive_date_and_time() {
+        for (day, naive_day) in [
+            (event().day, NaiveDate::from_ymd_opt(2024, 2, 29).unwrap()),
+            (
+                date(1, Month::January, 1),
+                NaiveDate::from_ymd_opt(1, 1, 1).unwrap(),
+            ),
+        ] {
+            assert_eq!(raw(&day), raw(&naive_day));
+            assert_eq!(raw_pack(&day), raw_pack(&naive_day));
+            assert_eq!(Date::decode(&mut raw(&naive_day)).unwrap(), day);
+        }
+
+        let start = event().start;
+        let naive_start = NaiveTime::from_hms_nano_opt(9, 30, 15, 123_456_789).unwrap();
+        assert_eq!(raw(&start), raw(&naive_start));
+        assert_eq!(raw_pack(&start), raw_pack(&naive_start));
+        assert_eq!(NaiveTime::decode(&mut raw(&start)).unwrap(), naive_start);
+        assert_eq!(Time::unpack(&mut raw_pack(&naive_start)).unwrap(), start);
+    }
+
+    #[test]
+    fn test_chrono_leap_second_is_rejected() {
+        let leap = NaiveTime::from_hms_nano_opt(23, 59, 59, 1_500_000_000).unwrap();
+        assert!(Time::decode(&mut raw(&leap)).is_err());
+    }
+
+    #[test]
+    fn test_structs_interoperate() {
+        #[derive(Encode, Decode, Debug, PartialEq)]
+        struct ChronoEvent {
+            name: String,
+            at: DateTime<Utc>,
+            local: NaiveDateTime,
+            day: NaiveDate,
+            start: NaiveTime,
+            #[senax(default)]
+            end: Option<NaiveTime>,
+        }
+
+        let value = event();
+        let chrono_value = decode::<ChronoEvent>(&mut encode(&value).unwrap()).unwrap();
+        assert_eq!(
+            chrono_value.local.to_string(),
+            "2024-02-29 09:30:15.123456789"
+        );
+        assert_eq!(
+            decode::<Event>(&mut encode(&chrono_value).unwrap()).unwrap(),
+            value
+        );
+    }
+}
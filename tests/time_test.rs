@@ -0,0 +1,187 @@
+use bytes::{BufMut, BytesMut};
+use senax_encoder::core::{TAG_DURATION, TAG_SYSTEM_TIME};
+use senax_encoder::{
+    decode, encode, pack, unpack, validate_payload, Decode, Decoder, Encode, Encoder, Pack, Unpack,
+};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+#[derive(Encode, Decode, Pack, Unpack, Debug, PartialEq)]
+struct Job {
+    name: String,
+    timeout: Duration,
+    created_at: SystemTime,
+    #[senax(default)]
+    finished_at: Option<SystemTime>,
+}
+
+#[derive(Encode, Decode, Debug, PartialEq)]
+struct JobV0 {
+    name: String,
+}
+
+fn job() -> Job {
+    Job {
+        name: "reindex".into(),
+        timeout: Duration::new(90, 250_000_000),
+        created_at: UNIX_EPOCH + Duration::new(1_700_000_000, 123_456_789),
+        finished_at: Some(UNIX_EPOCH + Duration::from_secs(1_700_000_090)),
+    }
+}
+
+fn round_trip<T: Encoder + Decoder>(value: &T) -> T {
+    let mut writer = BytesMut::new();
+    value.encode(&mut writer).unwrap();
+    let mut bytes = writer.freeze();
+    let decoded = T::decode(&mut bytes).unwrap();
+    assert!(bytes.is_empty());
+    decoded
+}
+
+#[test]
+fn test_duration_round_trip() {
+    for duration in [
+        Duration::ZERO,
+        Duration::from_nanos(1),
+        Duration::from_millis(1500),
+        Duration::new(86_400 * 365, 999_999_999),
+        Duration::MAX,
+    ] {
+        assert_eq!(round_trip(&duration), duration);
+    }
+}
+
+#[test]
+fn test_duration_is_compact() {
+    let mut writer = BytesMut::new();
+    Duration::from_secs(30).encode(&mut writer).unwrap();
+    // Tag, one-byte seconds and zero nanoseconds
+    assert_eq!(&writer[..], &[TAG_DURATION, 30, 0]);
+    assert!(Duration::ZERO.is_default());
+}
+
+#[test]
+fn test_system_time_round_trip() {
+    let now = SystemTime::now();
+    for time in [
+        UNIX_EPOCH,
+        now,
+        UNIX_EPOCH + Duration::new(4_102_444_800, 1),
+        UNIX_EPOCH - Duration::from_secs(86_400),
+        UNIX_EPOCH - Duration::new(0, 1),
+        UNIX_EPOCH - Duration::new(1_000, 250_000_000),
+    ] {
+        assert_eq!(round_trip(&time), time);
+    }
+}
+
+#[test]
+fn test_system_time_before_epoch_rounds_seconds_down() {
+    let mut writer = BytesMut::new();
+    (UNIX_EPOCH - Duration::new(1, 250_000_000))
+        .encode(&mut writer)
+        .unwrap();
+    let mut expected = BytesMut::new();
+    expected.put_u8(TAG_SYSTEM_TIME);
+    (-2i64).encode(&mut expected).unwrap();
+    750_000_000u32.encode(&mut expected).unwrap();
+    assert_eq!(writer, expected);
+}
+
+#[test]
+fn test_struct_round_trip() {
+    let job = job();
+    let decoded: Job = decode(&mut encode(&job).unwrap()).unwrap();
+    assert_eq!(decoded, job);
+    let unpacked: Job = unpack(&mut pack(&job).unwrap()).unwrap();
+    assert_eq!(unpacked, job);
+    assert!(validate_payload::<Job>(&encode(&job).unwrap()).is_valid());
+}
+
+#[test]
+fn test_older_readers_skip_time_fields() {
+    let old: JobV0 = decode(&mut encode(&job()).unwrap()).unwrap();
+    assert_eq!(old.name, "reindex");
+}
+
+#[test]
+fn test_invalid_nanoseconds_are_rejected() {
+    let mut writer = BytesMut::new();
+    writer.put_u8(TAG_DURATION);
+    1u64.encode(&mut writer).unwrap();
+    1_000_000_000u32.encode(&mut writer).unwrap();
+    let err = Duration::decode(&mut writer.freeze()).unwrap_err();
+    assert!(err.to_string().contains("nanoseconds"), "{err}");
+
+    let mut writer = BytesMut::new();
+    writer.put_u8(TAG_SYSTEM_TIME);
+    0i64.encode(&mut writer).unwrap();
+    u32::MAX.encode(&mut writer).unwrap();
+    assert!(SystemTime::decode(&mut writer.freeze()).is_err());
+}
+
+#[test]
+fn test_wrong_tag_is_rejected() {
+    let mut writer = BytesMut::new();
+    30u64.encode(&mut writer).unwrap();
+    let err = Duration::decode(&mut writer.freeze()).unwrap_err();
+    assert!(err.to_string().contains("Expected Duration tag"), "{err}");
+
+    let mut writer = BytesMut::new();
+    Duration::from_secs(30).encode(&mut writer).unwrap();
+    assert!(SystemTime::decode(&mut writer.freeze()).is_err());
+}
+
+#[cfg(feature = "chrono")]
+#[test]
+fn test_system_time_and_chrono_decode_each_other() {
+    use chrono::{DateTime, Utc};
+
+    let time = UNIX_EPOCH + Duration::new(1_700_000_000, 500);
+    let chrono_time: DateTime<Utc> = decode(&mut encode(&time).unwrap()).unwrap();
+    assert_eq!(
+        chrono_time,
+        DateTime::from_timestamp(1_700_000_000, 500).unwrap()
+    );
+
+    let before_epoch = DateTime::from_timestamp(-2, 750_000_000).unwrap();
+    let time: SystemTime = decode(&mut encode(&before_epoch).unwrap()).unwrap();
+    assert_eq!(time, UNIX_EPOCH - Duration::new(1, 250_000_000));
+    assert_eq!(SystemTime::from(before_epoch), time);
+}
+
+#[cfg(feature = "chrono")]
+#[test]
+fn test_older_readers_skip_trailing_chrono_fields() {
+    use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+
+    #[derive(Encode, Decode, Debug, PartialEq)]
+    struct V1 {
+        id: u64,
+    }
+
+    #[derive(Encode, Decode, Debug, PartialEq)]
+    struct V2<T> {
+        id: u64,
+        at: T,
+    }
+
+    // A short varint-encoded value as the last field of the payload
+    fn check<T: Encoder + 'static>(at: T) {
+        let bytes = encode(&V2 { id: 7, at }).unwrap();
+        let old: V1 = decode(&mut bytes.clone()).unwrap();
+        assert_eq!(old, V1 { id: 7 });
+    }
+
+    check(DateTime::<Utc>::from_timestamp(0, 0).unwrap());
+    check(DateTime::<Utc>::from_timestamp(1_700_000_000, 123_456_789).unwrap());
+    check(NaiveDate::from_ymd_opt(1970, 1, 2).unwrap());
+    check(NaiveDate::from_ymd_opt(2024, 2, 29).unwrap());
+    check(NaiveTime::from_hms_opt(0, 0, 1).unwrap());
+    check(NaiveTime::from_hms_nano_opt(23, 59, 59, 999_999_999).unwrap());
+    check(NaiveDateTime::default());
+    check(
+        DateTime::<Utc>::from_timestamp(-86_400, 1)
+            .unwrap()
+            .naive_utc(),
+    );
+}
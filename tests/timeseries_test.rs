@@ -0,0 +1,121 @@
+use bytes::Bytes;
+use senax_encoder::timeseries::{
+    compress, decompress, Sample, TimeSeries, TimeSeriesReader, TimeSeriesWriter,
+};
+use senax_encoder::{decode, encode, pack, unpack, Decode, Encode, Pack, Unpack};
+
+fn samples(values: impl IntoIterator<Item = (i64, f64)>) -> Vec<Sample> {
+    values
+        .into_iter()
+        .map(|(timestamp, value)| Sample { timestamp, value })
+        .collect()
+}
+
+#[test]
+fn test_regular_series_compresses_to_bits() {
+    let series = samples((0..1000).map(|i| (1_700_000_000 + i * 60, 42.0)));
+    let block = compress(&series).unwrap();
+    // Header, the first delta (2 + 7 bits) and an unchanged value, then two bits per sample
+    assert_eq!(block.len(), 20 + (10 + 998 * 2usize).div_ceil(8));
+    assert_eq!(decompress(block).unwrap(), series);
+}
+
+#[test]
+fn test_irregular_timestamps_and_values_round_trip() {
+    let series = samples([
+        (0, 1.0),
+        (10, 1.5),
+        (21, -3.25),
+        (21, -3.25),
+        (500, 1e300),
+        (400, f64::MIN_POSITIVE),
+        (100_000, 0.0),
+        (i64::MAX, -0.0),
+        (i64::MIN, f64::INFINITY),
+        (5, f64::NEG_INFINITY),
+        (6, 123456.789),
+        (7, 123456.79),
+    ]);
+    let decoded = decompress(compress(&series).unwrap()).unwrap();
+    assert_eq!(decoded.len(), series.len());
+    for (a, b) in decoded.iter().zip(&series) {
+        assert_eq!(a.timestamp, b.timestamp);
+        assert_eq!(a.value.to_bits(), b.value.to_bits());
+    }
+}
+
+#[test]
+fn test_nan_payloads_round_trip_bit_for_bit() {
+    let nan = f64::from_bits(0x7FF8_0000_0000_1234);
+    let block = compress(&samples([(1, 0.5), (2, nan), (3, f64::NAN)])).unwrap();
+    let decoded = decompress(block).unwrap();
+    assert_eq!(decoded[1].value.to_bits(), nan.to_bits());
+    assert_eq!(decoded[2].value.to_bits(), f64::NAN.to_bits());
+}
+
+#[test]
+fn test_streaming_reader() {
+    let mut writer = TimeSeriesWriter::new();
+    assert!(writer.is_empty());
+    for i in 0..10 {
+        writer.push(i * 1000, i as f64 * 0.5).unwrap();
+    }
+    assert_eq!(writer.len(), 10);
+    let mut reader = TimeSeriesReader::new(writer.finish().unwrap()).unwrap();
+    assert_eq!(reader.remaining(), 10);
+    let first = reader.next().unwrap().unwrap();
+    assert_eq!(
+        first,
+        Sample {
+            timestamp: 0,
+            value: 0.0
+        }
+    );
+    assert_eq!(reader.remaining(), 9);
+    assert_eq!(reader.last().unwrap().unwrap().timestamp, 9000);
+}
+
+#[test]
+fn test_empty_series() {
+    let block = compress(&[]).unwrap();
+    assert_eq!(block.as_ref(), &[0, 0, 0, 0]);
+    assert!(decompress(block).unwrap().is_empty());
+}
+
+#[test]
+fn test_truncated_block_is_rejected() {
+    let block = compress(&samples((0..50).map(|i| (i * i, i as f64 / 3.0)))).unwrap();
+    for len in [0, 3, 10, block.len() - 1] {
+        assert!(decompress(block.slice(..len)).is_err(), "len {}", len);
+    }
+    let mut reader = TimeSeriesReader::new(block.slice(..block.len() - 1)).unwrap();
+    assert!(reader.any(|sample| sample.is_err()));
+    assert!(reader.next().is_none());
+}
+
+#[derive(Encode, Decode, Pack, Unpack, Debug, PartialEq)]
+struct Metric {
+    name: String,
+    #[senax(default)]
+    points: TimeSeries,
+}
+
+#[test]
+fn test_time_series_field() {
+    let metric = Metric {
+        name: "cpu".into(),
+        points: TimeSeries(samples((0..100).map(|i| (i * 15, (i % 4) as f64)))),
+    };
+    let bytes = encode(&metric).unwrap();
+    assert_eq!(decode::<Metric>(&mut bytes.clone()).unwrap(), metric);
+    let packed = pack(&metric).unwrap();
+    assert_eq!(unpack::<Metric>(&mut packed.clone()).unwrap(), metric);
+
+    // The field is plain binary holding the block
+    #[derive(Decode)]
+    struct RawMetric {
+        points: Bytes,
+    }
+    let raw: RawMetric = decode(&mut bytes.clone()).unwrap();
+    assert_eq!(decompress(raw.points).unwrap(), metric.points.0);
+}
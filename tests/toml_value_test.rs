@@ -0,0 +1,44 @@
+#![cfg(feature = "toml")]
+
+use senax_encoder::{decode, encode};
+use toml::Value;
+
+const CONFIG: &str = r#"
+title = "billing"
+replicas = 3
+offset = -2
+ratio = 0.75
+enabled = true
+deployed_at = 2024-05-01T12:30:00Z
+
+[database]
+hosts = ["db1", "db2"]
+pool = { min = 1, max = 16 }
+"#;
+
+#[test]
+fn test_toml_value_round_trip() {
+    let value: Value = toml::from_str(CONFIG).unwrap();
+    let bytes = encode(&value).unwrap();
+    let decoded: Value = decode(&mut bytes.clone()).unwrap();
+    assert_eq!(decoded, value);
+    assert!(matches!(decoded["deployed_at"], Value::Datetime(_)));
+}
+
+#[test]
+fn test_toml_rejects_values_it_cannot_hold() {
+    let bytes = encode(&Value::Integer(1)).unwrap();
+    let mut null = bytes.to_vec();
+    null.truncate(2);
+    null.push(senax_encoder::core::TAG_JSON_NULL);
+    assert!(decode::<Value>(&mut null.into()).is_err());
+}
+
+#[cfg(feature = "serde_json")]
+#[test]
+fn test_toml_payload_decodes_as_json_value() {
+    let value: Value = toml::from_str("name = \"api\"\nports = [80, 443]").unwrap();
+    let bytes = encode(&value).unwrap();
+    let json: serde_json::Value = decode(&mut bytes.clone()).unwrap();
+    assert_eq!(json, serde_json::json!({"name": "api", "ports": [80, 443]}));
+}
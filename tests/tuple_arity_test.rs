@@ -0,0 +1,177 @@
+use bytes::BytesMut;
+use senax_encoder::core::TAG_TUPLE;
+use senax_encoder::{decode, encode, impl_senax_tuple, pack, unpack, Decoder, Encoder, Value};
+
+type Tuple16 = (
+    u8,
+    u16,
+    u32,
+    u64,
+    i8,
+    i16,
+    i32,
+    i64,
+    bool,
+    String,
+    Vec<u8>,
+    Option<u32>,
+    f64,
+    char,
+    (u8, u8),
+    [u8; 2],
+);
+
+fn tuple16() -> Tuple16 {
+    (
+        1,
+        2,
+        3,
+        4,
+        -5,
+        -6,
+        -7,
+        -8,
+        true,
+        "ten".into(),
+        vec![11],
+        Some(12),
+        13.5,
+        'x',
+        (15, 15),
+        [16, 16],
+    )
+}
+
+#[test]
+fn test_sixteen_element_tuples() {
+    let value = tuple16();
+    let bytes = encode(&value).unwrap();
+    assert_eq!(bytes[2..4], [TAG_TUPLE, 16]);
+    // std implements PartialEq and Debug for tuples of up to 12 elements only
+    let decoded = decode::<Tuple16>(&mut bytes.clone()).unwrap();
+    assert_eq!(encode(&decoded).unwrap(), bytes);
+    assert_eq!(
+        (decoded.9, decoded.14, decoded.15),
+        ("ten".to_string(), (15, 15), [16, 16])
+    );
+    let packed = pack(&value).unwrap();
+    let unpacked = unpack::<Tuple16>(&mut packed.clone()).unwrap();
+    assert_eq!(pack(&unpacked).unwrap(), packed);
+}
+
+#[derive(Debug, PartialEq)]
+struct Wide(
+    u32,
+    u32,
+    u32,
+    u32,
+    u32,
+    u32,
+    u32,
+    u32,
+    u32,
+    u32,
+    u32,
+    u32,
+    u32,
+    u32,
+    u32,
+    u32,
+    u32,
+    String,
+);
+
+impl_senax_tuple!(Wide {
+    0: u32,
+    1: u32,
+    2: u32,
+    3: u32,
+    4: u32,
+    5: u32,
+    6: u32,
+    7: u32,
+    8: u32,
+    9: u32,
+    10: u32,
+    11: u32,
+    12: u32,
+    13: u32,
+    14: u32,
+    15: u32,
+    16: u32,
+    17: String,
+});
+
+fn wide() -> Wide {
+    Wide(
+        0,
+        1,
+        2,
+        3,
+        4,
+        5,
+        6,
+        7,
+        8,
+        9,
+        10,
+        11,
+        12,
+        13,
+        14,
+        15,
+        16,
+        "seventeen".into(),
+    )
+}
+
+#[test]
+fn test_custom_arity_round_trip() {
+    let value = wide();
+    let bytes = encode(&value).unwrap();
+    assert_eq!(bytes[2..4], [TAG_TUPLE, 18]);
+    assert_eq!(decode::<Wide>(&mut bytes.clone()).unwrap(), value);
+    assert_eq!(unpack::<Wide>(&mut pack(&value).unwrap()).unwrap(), value);
+
+    // Written like a tuple, so dynamic readers see a tuple
+    match decode::<Value>(&mut bytes.clone()).unwrap() {
+        Value::Tuple(elements) => assert_eq!(elements.len(), 18),
+        other => panic!("expected a tuple, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_custom_arity_is_default_and_mismatch() {
+    let zero = Wide(
+        0,
+        0,
+        0,
+        0,
+        0,
+        0,
+        0,
+        0,
+        0,
+        0,
+        0,
+        0,
+        0,
+        0,
+        0,
+        0,
+        0,
+        String::new(),
+    );
+    assert!(zero.is_default());
+    assert!(!wide().is_default());
+
+    let mut writer = BytesMut::new();
+    (1u32, 2u32).encode(&mut writer).unwrap();
+    let err = Wide::decode(&mut writer.freeze()).unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("Expected 18-tuple but got 2-tuple"),
+        "{}",
+        err
+    );
+}
@@ -0,0 +1,7 @@
+use senax_encoder::Decode;
+
+#[derive(Decode)]
+#[senax(accept_named)]
+struct Point(#[senax(rename = "x")] i32, i32);
+
+fn main() {}
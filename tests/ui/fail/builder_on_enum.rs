@@ -0,0 +1,10 @@
+use senax_encoder::Decode;
+
+#[derive(Decode)]
+#[senax(builder)]
+enum Command {
+    Start,
+    Stop,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use senax_encoder::Encode;
+
+#[derive(Encode)]
+struct Profile {
+    #[senax(sort, encrypt)]
+    tags: Vec<String>,
+}
+
+fn main() {}
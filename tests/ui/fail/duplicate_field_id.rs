@@ -0,0 +1,11 @@
+use senax_encoder::{Decode, Encode};
+
+#[derive(Encode, Decode)]
+struct Clash {
+    #[senax(id = 7)]
+    first: u32,
+    #[senax(id = 7)]
+    second: u32,
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+use senax_encoder::{Pack, Unpack};
+
+#[derive(Pack, Unpack)]
+enum Shape {
+    #[senax(id = 3)]
+    Circle,
+    #[senax(id = 3)]
+    Square,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use senax_encoder::Encode;
+
+#[derive(Encode)]
+#[senax(id_salt = "")]
+struct Account {
+    user_id: u64,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use senax_encoder::Encode;
+
+#[derive(Encode)]
+struct Account {
+    #[senax(id = "seven")]
+    user_id: u64,
+}
+
+fn main() {}
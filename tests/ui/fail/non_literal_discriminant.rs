@@ -0,0 +1,12 @@
+use senax_encoder::Encode;
+
+const BASE: isize = 10;
+
+#[derive(Encode)]
+#[senax(discriminant_ids)]
+enum Status {
+    Active = BASE,
+    Closed,
+}
+
+fn main() {}
@@ -0,0 +1,14 @@
+use senax_encoder::Decode;
+
+mod legacy {
+    #[derive(Debug, Default)]
+    pub struct Option;
+}
+
+#[derive(Decode)]
+struct Account {
+    user_id: u64,
+    flags: legacy::Option,
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use senax_encoder::Decode;
+
+#[derive(Decode)]
+struct Envelope<T> {
+    #[senax(prefix)]
+    kind: u8,
+    body: T,
+}
+
+fn main() {}
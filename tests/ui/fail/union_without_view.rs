@@ -0,0 +1,9 @@
+use senax_encoder::Encode;
+
+#[derive(Encode)]
+union Bits {
+    int: u32,
+    float: f32,
+}
+
+fn main() {}
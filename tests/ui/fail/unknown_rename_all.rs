@@ -0,0 +1,9 @@
+use senax_encoder::Encode;
+
+#[derive(Encode)]
+#[senax(rename_all = "Title Case")]
+struct Account {
+    user_id: u64,
+}
+
+fn main() {}
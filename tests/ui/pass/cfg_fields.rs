@@ -0,0 +1,32 @@
+use senax_encoder::{decode, encode, pack, unpack, Decode, Encode, Pack, Unpack};
+
+#[derive(Encode, Decode, Pack, Unpack, Debug, PartialEq)]
+struct Settings {
+    name: String,
+    #[cfg(any())]
+    removed: u32,
+    #[cfg(all())]
+    kept: u32,
+}
+
+#[derive(Encode, Decode, Pack, Unpack, Debug, PartialEq)]
+enum Command {
+    Run {
+        #[cfg(any())]
+        removed: bool,
+        force: bool,
+    },
+    #[cfg(any())]
+    Removed,
+    Stop,
+}
+
+fn main() {
+    let settings = Settings { name: "s".into(), kept: 1 };
+    assert_eq!(decode::<Settings>(&mut encode(&settings).unwrap()).unwrap(), settings);
+    assert_eq!(unpack::<Settings>(&mut pack(&settings).unwrap()).unwrap(), settings);
+
+    let command = Command::Run { force: true };
+    assert_eq!(decode::<Command>(&mut encode(&command).unwrap()).unwrap(), command);
+    assert_eq!(decode::<Command>(&mut encode(&Command::Stop).unwrap()).unwrap(), Command::Stop);
+}
@@ -0,0 +1,48 @@
+use senax_encoder::{decode, encode, pack, unpack, Decode, Decoder, Encode, Encoder, Pack, Packer, Unpack, Unpacker};
+use std::collections::BTreeMap;
+
+#[derive(Encode, Decode, Pack, Unpack, Debug, PartialEq)]
+struct Page<T: Encoder + Decoder + Packer + Unpacker + 'static> {
+    items: Vec<T>,
+    next: Option<T>,
+    #[senax(default)]
+    total: u64,
+}
+
+#[derive(Encode, Decode, Pack, Unpack, Debug, PartialEq)]
+struct Indexed<K, V>
+where
+    K: Encoder + Decoder + Packer + Unpacker + Ord + 'static,
+    V: Encoder + Decoder + Packer + Unpacker + 'static,
+{
+    entries: BTreeMap<K, V>,
+}
+
+#[derive(Encode, Decode, Pack, Unpack, Debug, PartialEq)]
+enum Either<L, R>
+where
+    L: Encoder + Decoder + Packer + Unpacker + 'static,
+    R: Encoder + Decoder + Packer + Unpacker + 'static,
+{
+    Left(L),
+    Right { value: R, #[senax(default)] note: Option<String> },
+    Neither,
+}
+
+#[derive(Encode, Decode, Pack, Unpack, Debug, PartialEq)]
+struct Pair<A: Encoder + Decoder + Packer + Unpacker + 'static, const N: usize>(A, [u8; N]);
+
+fn main() {
+    let page = Page { items: vec![1u32, 2], next: Some(3), total: 3 };
+    assert_eq!(decode::<Page<u32>>(&mut encode(&page).unwrap()).unwrap(), page);
+    assert_eq!(unpack::<Page<u32>>(&mut pack(&page).unwrap()).unwrap(), page);
+
+    let indexed = Indexed { entries: BTreeMap::from([("a".to_string(), vec![1i8])]) };
+    assert_eq!(decode::<Indexed<String, Vec<i8>>>(&mut encode(&indexed).unwrap()).unwrap(), indexed);
+
+    let either: Either<u8, String> = Either::Right { value: "r".into(), note: None };
+    assert_eq!(decode::<Either<u8, String>>(&mut encode(&either).unwrap()).unwrap(), either);
+
+    let pair = Pair(true, [1, 2, 3]);
+    assert_eq!(decode::<Pair<bool, 3>>(&mut encode(&pair).unwrap()).unwrap(), pair);
+}
@@ -0,0 +1,32 @@
+use senax_encoder::{decode, encode, Decode, Encode, Pack};
+use std::marker::PhantomData;
+
+#[derive(Encode, Pack)]
+struct View<'a> {
+    name: &'a String,
+    tags: &'a Vec<String>,
+}
+
+#[derive(Encode, Decode, Debug, PartialEq)]
+struct Owned {
+    name: String,
+    tags: Vec<String>,
+}
+
+#[derive(Encode, Decode, Debug, PartialEq, Default)]
+struct Tagged<'a> {
+    id: u32,
+    #[senax(skip_encode, skip_decode)]
+    marker: PhantomData<&'a ()>,
+}
+
+fn main() {
+    let name = "config".to_string();
+    let tags = vec!["a".to_string()];
+    let bytes = encode(&View { name: &name, tags: &tags }).unwrap();
+    let owned: Owned = decode(&mut bytes.clone()).unwrap();
+    assert_eq!(owned, Owned { name, tags });
+
+    let tagged = Tagged { id: 4, marker: PhantomData };
+    assert_eq!(decode::<Tagged>(&mut encode(&tagged).unwrap()).unwrap(), tagged);
+}
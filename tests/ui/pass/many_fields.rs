@@ -0,0 +1,256 @@
+use senax_encoder::{decode, encode, pack, unpack, Decode, Encode, Pack, Unpack};
+
+#[derive(Encode, Decode, Pack, Unpack, Debug, PartialEq)]
+struct Wide {
+    field_000: u8,
+    field_001: u64,
+    field_002: String,
+    field_003: Option<i32>,
+    field_004: Vec<u16>,
+    field_005: u8,
+    field_006: u64,
+    field_007: String,
+    field_008: Option<i32>,
+    field_009: Vec<u16>,
+    field_010: u8,
+    field_011: u64,
+    field_012: String,
+    field_013: Option<i32>,
+    field_014: Vec<u16>,
+    field_015: u8,
+    field_016: u64,
+    field_017: String,
+    field_018: Option<i32>,
+    field_019: Vec<u16>,
+    field_020: u8,
+    field_021: u64,
+    field_022: String,
+    field_023: Option<i32>,
+    field_024: Vec<u16>,
+    field_025: u8,
+    field_026: u64,
+    field_027: String,
+    field_028: Option<i32>,
+    field_029: Vec<u16>,
+    field_030: u8,
+    field_031: u64,
+    field_032: String,
+    field_033: Option<i32>,
+    field_034: Vec<u16>,
+    field_035: u8,
+    field_036: u64,
+    field_037: String,
+    field_038: Option<i32>,
+    field_039: Vec<u16>,
+    field_040: u8,
+    field_041: u64,
+    field_042: String,
+    field_043: Option<i32>,
+    field_044: Vec<u16>,
+    field_045: u8,
+    field_046: u64,
+    field_047: String,
+    field_048: Option<i32>,
+    field_049: Vec<u16>,
+    field_050: u8,
+    field_051: u64,
+    field_052: String,
+    field_053: Option<i32>,
+    field_054: Vec<u16>,
+    field_055: u8,
+    field_056: u64,
+    field_057: String,
+    field_058: Option<i32>,
+    field_059: Vec<u16>,
+    field_060: u8,
+    field_061: u64,
+    field_062: String,
+    field_063: Option<i32>,
+    field_064: Vec<u16>,
+    field_065: u8,
+    field_066: u64,
+    field_067: String,
+    field_068: Option<i32>,
+    field_069: Vec<u16>,
+    field_070: u8,
+    field_071: u64,
+    field_072: String,
+    field_073: Option<i32>,
+    field_074: Vec<u16>,
+    field_075: u8,
+    field_076: u64,
+    field_077: String,
+    field_078: Option<i32>,
+    field_079: Vec<u16>,
+    field_080: u8,
+    field_081: u64,
+    field_082: String,
+    field_083: Option<i32>,
+    field_084: Vec<u16>,
+    field_085: u8,
+    field_086: u64,
+    field_087: String,
+    field_088: Option<i32>,
+    field_089: Vec<u16>,
+    field_090: u8,
+    field_091: u64,
+    field_092: String,
+    field_093: Option<i32>,
+    field_094: Vec<u16>,
+    field_095: u8,
+    field_096: u64,
+    field_097: String,
+    field_098: Option<i32>,
+    field_099: Vec<u16>,
+    field_100: u8,
+    field_101: u64,
+    field_102: String,
+    field_103: Option<i32>,
+    field_104: Vec<u16>,
+    field_105: u8,
+    field_106: u64,
+    field_107: String,
+    field_108: Option<i32>,
+    field_109: Vec<u16>,
+    field_110: u8,
+    field_111: u64,
+    field_112: String,
+    field_113: Option<i32>,
+    field_114: Vec<u16>,
+    field_115: u8,
+    field_116: u64,
+    field_117: String,
+    field_118: Option<i32>,
+    field_119: Vec<u16>,
+}
+
+fn wide(i: usize) -> Wide {
+    Wide {
+        field_000: i as u8,
+        field_001: i as u64,
+        field_002: i.to_string(),
+        field_003: Some(i as i32),
+        field_004: vec![i as u16],
+        field_005: i as u8,
+        field_006: i as u64,
+        field_007: i.to_string(),
+        field_008: Some(i as i32),
+        field_009: vec![i as u16],
+        field_010: i as u8,
+        field_011: i as u64,
+        field_012: i.to_string(),
+        field_013: Some(i as i32),
+        field_014: vec![i as u16],
+        field_015: i as u8,
+        field_016: i as u64,
+        field_017: i.to_string(),
+        field_018: Some(i as i32),
+        field_019: vec![i as u16],
+        field_020: i as u8,
+        field_021: i as u64,
+        field_022: i.to_string(),
+        field_023: Some(i as i32),
+        field_024: vec![i as u16],
+        field_025: i as u8,
+        field_026: i as u64,
+        field_027: i.to_string(),
+        field_028: Some(i as i32),
+        field_029: vec![i as u16],
+        field_030: i as u8,
+        field_031: i as u64,
+        field_032: i.to_string(),
+        field_033: Some(i as i32),
+        field_034: vec![i as u16],
+        field_035: i as u8,
+        field_036: i as u64,
+        field_037: i.to_string(),
+        field_038: Some(i as i32),
+        field_039: vec![i as u16],
+        field_040: i as u8,
+        field_041: i as u64,
+        field_042: i.to_string(),
+        field_043: Some(i as i32),
+        field_044: vec![i as u16],
+        field_045: i as u8,
+        field_046: i as u64,
+        field_047: i.to_string(),
+        field_048: Some(i as i32),
+        field_049: vec![i as u16],
+        field_050: i as u8,
+        field_051: i as u64,
+        field_052: i.to_string(),
+        field_053: Some(i as i32),
+        field_054: vec![i as u16],
+        field_055: i as u8,
+        field_056: i as u64,
+        field_057: i.to_string(),
+        field_058: Some(i as i32),
+        field_059: vec![i as u16],
+        field_060: i as u8,
+        field_061: i as u64,
+        field_062: i.to_string(),
+        field_063: Some(i as i32),
+        field_064: vec![i as u16],
+        field_065: i as u8,
+        field_066: i as u64,
+        field_067: i.to_string(),
+        field_068: Some(i as i32),
+        field_069: vec![i as u16],
+        field_070: i as u8,
+        field_071: i as u64,
+        field_072: i.to_string(),
+        field_073: Some(i as i32),
+        field_074: vec![i as u16],
+        field_075: i as u8,
+        field_076: i as u64,
+        field_077: i.to_string(),
+        field_078: Some(i as i32),
+        field_079: vec![i as u16],
+        field_080: i as u8,
+        field_081: i as u64,
+        field_082: i.to_string(),
+        field_083: Some(i as i32),
+        field_084: vec![i as u16],
+        field_085: i as u8,
+        field_086: i as u64,
+        field_087: i.to_string(),
+        field_088: Some(i as i32),
+        field_089: vec![i as u16],
+        field_090: i as u8,
+        field_091: i as u64,
+        field_092: i.to_string(),
+        field_093: Some(i as i32),
+        field_094: vec![i as u16],
+        field_095: i as u8,
+        field_096: i as u64,
+        field_097: i.to_string(),
+        field_098: Some(i as i32),
+        field_099: vec![i as u16],
+        field_100: i as u8,
+        field_101: i as u64,
+        field_102: i.to_string(),
+        field_103: Some(i as i32),
+        field_104: vec![i as u16],
+        field_105: i as u8,
+        field_106: i as u64,
+        field_107: i.to_string(),
+        field_108: Some(i as i32),
+        field_109: vec![i as u16],
+        field_110: i as u8,
+        field_111: i as u64,
+        field_112: i.to_string(),
+        field_113: Some(i as i32),
+        field_114: vec![i as u16],
+        field_115: i as u8,
+        field_116: i as u64,
+        field_117: i.to_string(),
+        field_118: Some(i as i32),
+        field_119: vec![i as u16],
+    }
+}
+
+fn main() {
+    let value = wide(7);
+    assert_eq!(decode::<Wide>(&mut encode(&value).unwrap()).unwrap(), value);
+    assert_eq!(unpack::<Wide>(&mut pack(&value).unwrap()).unwrap(), value);
+}
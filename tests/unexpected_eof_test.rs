@@ -0,0 +1,123 @@
+use bytes::{Bytes, BytesMut};
+use senax_encoder::{
+    decode, decode_framed, encode, encode_framed, try_decode_framed, Decode, Decoder, Encode,
+    Encoder, EncoderError,
+};
+
+#[derive(Encode, Decode, Debug, PartialEq)]
+struct Record {
+    id: u64,
+    name: String,
+}
+
+fn raw<T: Encoder>(value: &T) -> Bytes {
+    let mut buf = BytesMut::new();
+    value.encode(&mut buf).unwrap();
+    buf.freeze()
+}
+
+#[test]
+fn test_truncated_u64_reports_counts() {
+    let bytes = raw(&u64::MAX);
+    let mut truncated = bytes.slice(..4);
+    let err = u64::decode(&mut truncated).unwrap_err();
+    assert!(matches!(
+        err,
+        EncoderError::UnexpectedEof {
+            needed: 8,
+            available: 3
+        }
+    ));
+}
+
+#[test]
+fn test_truncated_string_reports_counts() {
+    let bytes = raw(&"hello world".to_string());
+    let mut truncated = bytes.slice(..bytes.len() - 4);
+    let err = String::decode(&mut truncated).unwrap_err();
+    assert!(matches!(
+        err,
+        EncoderError::UnexpectedEof {
+            needed: 11,
+            available: 7
+        }
+    ));
+}
+
+#[test]
+fn test_empty_input_reports_counts() {
+    let err = decode::<Record>(&mut Bytes::new()).unwrap_err();
+    assert!(matches!(err, EncoderError::UnexpectedEof { needed: 2, .. }));
+    let err = u32::decode(&mut Bytes::new()).unwrap_err();
+    assert!(matches!(
+        err,
+        EncoderError::UnexpectedEof {
+            needed: 1,
+            available: 0
+        }
+    ));
+}
+
+#[test]
+fn test_every_prefix_is_unexpected_eof() {
+    let bytes = encode(&Record {
+        id: 1 << 40,
+        name: "a record name".to_string(),
+    })
+    .unwrap();
+    for len in 0..bytes.len() {
+        let err = decode::<Record>(&mut bytes.slice(..len)).unwrap_err();
+        match err {
+            EncoderError::UnexpectedEof { needed, available } => assert!(needed > available),
+            other => panic!("prefix of {} bytes: {:?}", len, other),
+        }
+    }
+}
+
+#[test]
+fn test_corrupt_data_is_not_unexpected_eof() {
+    let mut bytes = raw(&"text".to_string());
+    let err = bool::decode(&mut bytes).unwrap_err();
+    assert!(!matches!(err, EncoderError::UnexpectedEof { .. }));
+}
+
+#[test]
+fn test_framed_partial_frame_reports_counts() {
+    let frame = encode_framed(&"hello world".to_string()).unwrap();
+    let frame_len = frame.len() - 4;
+    let mut truncated = frame.slice(..frame.len() - 5);
+    let err = decode_framed::<String>(&mut truncated).unwrap_err();
+    match err {
+        EncoderError::UnexpectedEof { needed, available } => {
+            assert_eq!(needed, frame_len);
+            assert_eq!(available, frame_len - 5);
+        }
+        other => panic!("{:?}", other),
+    }
+}
+
+#[test]
+fn test_read_loop_waits_for_more_bytes() {
+    let records: Vec<Record> = (0..3)
+        .map(|id| Record {
+            id,
+            name: format!("record-{}", id),
+        })
+        .collect();
+    let mut wire = BytesMut::new();
+    for record in &records {
+        wire.extend_from_slice(&encode_framed(record).unwrap());
+    }
+
+    // Feed the stream one byte at a time, as a socket might
+    let mut buf = BytesMut::new();
+    let mut decoded = Vec::new();
+    for byte in wire.iter() {
+        buf.extend_from_slice(&[*byte]);
+        while let Some(record) = try_decode_framed::<Record>(&mut buf).unwrap() {
+            decoded.push(record);
+        }
+    }
+    assert_eq!(decoded, records);
+    assert!(buf.is_empty());
+}
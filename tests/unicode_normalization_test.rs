@@ -0,0 +1,105 @@
+#![cfg(feature = "unicode-normalization")]
+
+use senax_encoder::value::Value;
+use senax_encoder::{
+    decode, decode_with_config, encode, unpack, with_decode_config, Decode, DecodeConfig, Encode,
+    Pack, Unpack,
+};
+use std::collections::BTreeMap;
+
+/// "é" as `e` followed by U+0301 COMBINING ACUTE ACCENT.
+const DECOMPOSED: &str = "Cafe\u{301}";
+/// "é" as U+00E9.
+const COMPOSED: &str = "Caf\u{e9}";
+
+fn nfc() -> DecodeConfig {
+    DecodeConfig {
+        normalize_nfc: true,
+        ..Default::default()
+    }
+}
+
+#[derive(Encode, Decode, Pack, Unpack, Debug, PartialEq)]
+struct Account {
+    user_id: String,
+    aliases: Vec<String>,
+    labels: BTreeMap<String, u32>,
+    nickname: Option<String>,
+}
+
+fn account(name: &str) -> Account {
+    Account {
+        user_id: name.to_string(),
+        aliases: vec![name.to_string(), "plain".to_string()],
+        labels: [(name.to_string(), 1)].into(),
+        nickname: Some(name.to_string()),
+    }
+}
+
+#[test]
+fn test_strings_are_left_alone_by_default() {
+    let mut buf = encode(&account(DECOMPOSED)).unwrap();
+    let decoded: Account = decode(&mut buf).unwrap();
+    assert_eq!(decoded, account(DECOMPOSED));
+    assert_ne!(decoded, account(COMPOSED));
+}
+
+#[test]
+fn test_nfc_applies_to_nested_strings() {
+    let mut buf = encode(&account(DECOMPOSED)).unwrap();
+    let decoded: Account = decode_with_config(&mut buf, &nfc()).unwrap();
+    assert_eq!(decoded, account(COMPOSED));
+
+    let mut buf = encode(&account(COMPOSED)).unwrap();
+    let decoded: Account = decode_with_config(&mut buf, &nfc()).unwrap();
+    assert_eq!(decoded, account(COMPOSED));
+}
+
+#[test]
+fn test_nfc_applies_to_unpack() {
+    let packed = senax_encoder::pack(&account(DECOMPOSED)).unwrap();
+    let decoded: Account = with_decode_config(&nfc(), || unpack(&mut packed.clone())).unwrap();
+    assert_eq!(decoded, account(COMPOSED));
+}
+
+#[test]
+fn test_equivalent_map_keys_merge() {
+    let map: BTreeMap<String, u32> =
+        [(DECOMPOSED.to_string(), 1), (COMPOSED.to_string(), 2)].into();
+    let mut buf = encode(&map).unwrap();
+    let decoded: BTreeMap<String, u32> = decode_with_config(&mut buf, &nfc()).unwrap();
+    assert_eq!(decoded.len(), 1);
+    assert!(decoded.contains_key(COMPOSED));
+}
+
+#[test]
+fn test_nfc_applies_to_values() {
+    let mut buf = encode(&DECOMPOSED.to_string()).unwrap();
+    let value: Value = decode_with_config(&mut buf, &nfc()).unwrap();
+    assert_eq!(value, Value::String(COMPOSED.to_string()));
+}
+
+#[cfg(feature = "smol_str")]
+#[test]
+fn test_nfc_applies_to_smol_str() {
+    let mut buf = encode(&DECOMPOSED.to_string()).unwrap();
+    let decoded: smol_str::SmolStr = decode_with_config(&mut buf, &nfc()).unwrap();
+    assert_eq!(decoded, COMPOSED);
+}
+
+#[cfg(feature = "heapless")]
+#[test]
+fn test_nfc_applies_to_heapless_strings() {
+    let mut buf = encode(&DECOMPOSED.to_string()).unwrap();
+    let decoded: heapless::String<8> = decode_with_config(&mut buf, &nfc()).unwrap();
+    assert_eq!(decoded.as_str(), COMPOSED);
+
+    // U+0958 DEVANAGARI LETTER QA is excluded from composition and decomposes to two
+    // characters, so normalization makes it longer
+    let mut buf = encode(&"\u{958}".to_string()).unwrap();
+    let result = decode_with_config::<heapless::String<3>>(&mut buf, &nfc());
+    assert!(matches!(
+        result,
+        Err(senax_encoder::EncoderError::CapacityExceeded { capacity: 3, .. })
+    ));
+}
@@ -0,0 +1,72 @@
+use senax_encoder::{decode, encode, pack, unpack, Decode, Encode, Pack, Unpack};
+
+/// An FFI-style untagged union; the tag lives next to it in `Register`.
+#[derive(Encode, Decode, Pack, Unpack, Clone, Copy)]
+#[senax(union_as = "WordView")]
+#[repr(C)]
+union Word {
+    int: i64,
+    float: f64,
+}
+
+#[derive(Encode, Decode, Pack, Unpack, Debug, PartialEq)]
+enum WordView {
+    Int(i64),
+    Float(f64),
+}
+
+// The view picks the active field; here the low bit marks floats
+impl From<&Word> for WordView {
+    fn from(word: &Word) -> Self {
+        // SAFETY: both fields are 8 plain bytes
+        let bits = unsafe { word.int };
+        if bits & 1 == 0 {
+            WordView::Int(bits >> 1)
+        } else {
+            WordView::Float(f64::from_bits(bits as u64 & !1))
+        }
+    }
+}
+
+impl From<WordView> for Word {
+    fn from(view: WordView) -> Self {
+        match view {
+            WordView::Int(v) => Word { int: v << 1 },
+            WordView::Float(f) => Word {
+                int: (f.to_bits() | 1) as i64,
+            },
+        }
+    }
+}
+
+#[derive(Encode, Decode, Pack, Unpack)]
+struct Register {
+    name: String,
+    word: Word,
+}
+
+fn view(word: &Word) -> WordView {
+    WordView::from(word)
+}
+
+#[test]
+fn test_union_round_trips_through_view() {
+    let register = Register {
+        name: "r0".into(),
+        word: WordView::Int(-42).into(),
+    };
+    let decoded: Register = decode(&mut encode(&register).unwrap()).unwrap();
+    assert_eq!(decoded.name, "r0");
+    assert_eq!(view(&decoded.word), WordView::Int(-42));
+
+    let word: Word = WordView::Float(2.5).into();
+    let unpacked: Word = unpack(&mut pack(&word).unwrap()).unwrap();
+    assert_eq!(view(&unpacked), WordView::Float(2.5));
+}
+
+#[test]
+fn test_union_is_written_as_its_view() {
+    let word: Word = WordView::Int(7).into();
+    assert_eq!(encode(&word).unwrap(), encode(&WordView::Int(7)).unwrap());
+    assert_eq!(pack(&word).unwrap(), pack(&WordView::Int(7)).unwrap());
+}
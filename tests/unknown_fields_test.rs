@@ -0,0 +1,138 @@
+use senax_encoder::core::field_id_from_name;
+use senax_encoder::unknown_fields::SkippedField;
+use senax_encoder::{
+    collect_unknown_fields, decode, encode, with_decode_config, Decode, DecodeConfig, Encode,
+};
+
+#[derive(Encode, Decode, Debug, PartialEq)]
+struct UserV2 {
+    id: u64,
+    name: String,
+    email: String,
+    tags: Vec<String>,
+}
+
+#[derive(Encode, Decode, Debug, PartialEq)]
+struct User {
+    id: u64,
+    name: String,
+}
+
+#[derive(Encode, Decode, Debug, PartialEq)]
+enum EventV2 {
+    Created { id: u64, source: String },
+}
+
+#[derive(Encode, Decode, Debug, PartialEq)]
+enum Event {
+    Created { id: u64 },
+}
+
+fn user_v2(id: u64) -> UserV2 {
+    UserV2 {
+        id,
+        name: "alice".into(),
+        email: "alice@example.com".into(),
+        tags: vec!["admin".into()],
+    }
+}
+
+#[test]
+fn test_skipped_fields_are_counted_per_type_and_id() {
+    let payloads: Vec<_> = (0..3).map(|i| encode(&user_v2(i)).unwrap()).collect();
+    let (users, stats) = collect_unknown_fields(|| {
+        payloads
+            .iter()
+            .map(|bytes| decode::<User>(&mut bytes.clone()).unwrap())
+            .collect::<Vec<_>>()
+    });
+    assert_eq!(users.len(), 3);
+    assert_eq!(stats.total(), 6);
+    assert_eq!(stats.count("User", field_id_from_name("email")), 3);
+    assert_eq!(stats.count("User", field_id_from_name("tags")), 3);
+
+    let mut expected = vec![field_id_from_name("email"), field_id_from_name("tags")];
+    expected.sort();
+    assert_eq!(stats.field_ids("User"), expected);
+    assert!(stats.iter().all(
+        |SkippedField {
+             type_name, count, ..
+         }| { type_name == "User" && count == 3 }
+    ));
+}
+
+#[test]
+fn test_enum_variants_are_named() {
+    let bytes = encode(&EventV2::Created {
+        id: 1,
+        source: "api".into(),
+    })
+    .unwrap();
+    let (event, stats) = collect_unknown_fields(|| decode::<Event>(&mut bytes.clone()));
+    assert_eq!(event.unwrap(), Event::Created { id: 1 });
+    assert_eq!(
+        stats.field_ids("Event::Created"),
+        vec![field_id_from_name("source")]
+    );
+}
+
+#[test]
+fn test_known_payloads_record_nothing() {
+    let bytes = encode(&User {
+        id: 1,
+        name: "bob".into(),
+    })
+    .unwrap();
+    let (_, stats) = collect_unknown_fields(|| decode::<User>(&mut bytes.clone()).unwrap());
+    assert!(stats.is_empty());
+    assert_eq!(stats.total(), 0);
+}
+
+#[test]
+fn test_nothing_is_recorded_outside_a_collector() {
+    let bytes = encode(&user_v2(1)).unwrap();
+    decode::<User>(&mut bytes.clone()).unwrap();
+    let (_, stats) = collect_unknown_fields(|| ());
+    assert!(stats.is_empty());
+}
+
+#[test]
+fn test_nested_collectors_fold_into_outer() {
+    let bytes = encode(&user_v2(1)).unwrap();
+    let (inner, outer) = collect_unknown_fields(|| {
+        decode::<User>(&mut bytes.clone()).unwrap();
+        let (_, inner) = collect_unknown_fields(|| decode::<User>(&mut bytes.clone()).unwrap());
+        inner
+    });
+    assert_eq!(inner.total(), 2);
+    assert_eq!(outer.total(), 4);
+}
+
+#[test]
+fn test_denied_unknown_fields_are_not_counted() {
+    let bytes = encode(&user_v2(1)).unwrap();
+    let strict = DecodeConfig {
+        deny_unknown_fields: true,
+        ..Default::default()
+    };
+    let (result, stats) = collect_unknown_fields(|| {
+        with_decode_config(&strict, || decode::<User>(&mut bytes.clone()))
+    });
+    assert!(result.is_err());
+    assert!(stats.is_empty());
+}
+
+#[test]
+fn test_collector_is_removed_after_panic() {
+    let bytes = encode(&user_v2(1)).unwrap();
+    let result = std::panic::catch_unwind(|| {
+        collect_unknown_fields(|| {
+            decode::<User>(&mut bytes.clone()).unwrap();
+            panic!("handler failed");
+        })
+    });
+    assert!(result.is_err());
+    decode::<User>(&mut bytes.clone()).unwrap();
+    let (_, stats) = collect_unknown_fields(|| ());
+    assert!(stats.is_empty());
+}
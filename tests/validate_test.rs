@@ -0,0 +1,137 @@
+use bytes::{BufMut, BytesMut};
+use senax_encoder::core::{TAG_ARRAY_VEC_SET_LONG, TAG_STRING_LONG, TAG_STRUCT_NAMED};
+use senax_encoder::validate::MAX_VALIDATION_DEPTH;
+use senax_encoder::{encode, validate_payload, Decode, Encode};
+use std::collections::BTreeMap;
+
+#[derive(Encode, Decode, Debug, PartialEq)]
+enum Kind {
+    Empty,
+    Pair(i32, i64),
+    Named { label: String },
+}
+
+#[derive(Encode, Decode, Debug, PartialEq)]
+struct Event {
+    id: u32,
+    delta: i64,
+    name: String,
+    tags: Vec<String>,
+    attrs: BTreeMap<String, Option<u16>>,
+    kinds: Vec<Kind>,
+    blob: Vec<u8>,
+    ratio: f64,
+}
+
+fn sample() -> Event {
+    Event {
+        id: 7,
+        delta: -123_456_789,
+        name: "ingest".to_string(),
+        tags: vec!["a".to_string(), "b".repeat(100)],
+        attrs: BTreeMap::from([("x".to_string(), Some(300)), ("y".to_string(), None)]),
+        kinds: vec![
+            Kind::Empty,
+            Kind::Pair(-1, i64::MIN),
+            Kind::Named {
+                label: "n".to_string(),
+            },
+        ],
+        blob: vec![0; 64],
+        ratio: 0.25,
+    }
+}
+
+fn magic() -> BytesMut {
+    let mut writer = BytesMut::new();
+    writer.put_u16_le(0xA55A);
+    writer
+}
+
+#[test]
+fn test_valid_payload() {
+    let bytes = encode(&sample()).unwrap();
+    let report = validate_payload::<Event>(&bytes);
+    assert!(report.is_valid(), "{:?}", report);
+    assert_eq!(report.bytes_consumed, bytes.len());
+    assert!(report.type_name.ends_with("Event"));
+    assert!(report.values > 10);
+    assert!(report.max_depth >= 3);
+}
+
+#[test]
+fn test_every_truncation_is_rejected() {
+    let bytes = encode(&sample()).unwrap();
+    for len in 0..bytes.len() {
+        let report = validate_payload::<Event>(&bytes[..len]);
+        assert!(report.error.is_some(), "truncated to {} bytes", len);
+    }
+}
+
+#[test]
+fn test_trailing_bytes_are_reported() {
+    let mut writer = BytesMut::from(&encode(&1u32).unwrap()[..]);
+    writer.put_u8(0);
+    let report = validate_payload::<u32>(&writer);
+    assert!(report.error.is_none());
+    assert_eq!(report.trailing_bytes, 1);
+    assert!(!report.is_valid());
+}
+
+#[test]
+fn test_bad_magic_and_unknown_tag() {
+    let report = validate_payload::<u32>(&[0xDA, 0xDA, 1]);
+    assert_eq!(report.error.unwrap().offset, 2);
+
+    let mut writer = magic();
+    writer.put_u8(TAG_STRUCT_NAMED);
+    writer.put_u8(1);
+    writer.put_u8(250); // not a known tag
+    writer.put_u8(0);
+    let report = validate_payload::<u32>(&writer);
+    let issue = report.error.unwrap();
+    assert_eq!(issue.offset, 5);
+    assert!(issue.message.contains("Unknown tag 250"));
+}
+
+#[test]
+fn test_oversized_lengths_are_rejected() {
+    let mut writer = magic();
+    writer.put_u8(TAG_ARRAY_VEC_SET_LONG);
+    writer.put_u8(senax_encoder::core::TAG_U64);
+    writer.put_u64_le(u64::MAX / 4);
+    let report = validate_payload::<Vec<u8>>(&writer);
+    assert!(report.error.is_some());
+    assert_eq!(report.values, 1);
+}
+
+#[test]
+fn test_invalid_utf8_is_rejected() {
+    let mut writer = magic();
+    writer.put_u8(TAG_STRING_LONG);
+    writer.put_u8(2);
+    writer.put_slice(&[0xff, 0xfe]);
+    let report = validate_payload::<String>(&writer);
+    assert!(report.error.unwrap().message.contains("UTF-8"));
+}
+
+#[test]
+fn test_missing_terminator_is_rejected() {
+    let mut writer = magic();
+    writer.put_u8(TAG_STRUCT_NAMED);
+    writer.put_u8(1);
+    writer.put_u8(5);
+    let report = validate_payload::<u32>(&writer);
+    assert!(report.error.is_some());
+}
+
+#[test]
+fn test_nesting_limit() {
+    let mut writer = magic();
+    for _ in 0..MAX_VALIDATION_DEPTH + 1 {
+        writer.put_u8(senax_encoder::core::TAG_SOME);
+    }
+    writer.put_u8(senax_encoder::core::TAG_NONE);
+    let report = validate_payload::<u32>(&writer);
+    assert!(report.error.unwrap().message.contains("depth"));
+}
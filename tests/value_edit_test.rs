@@ -0,0 +1,198 @@
+use senax_encoder::core::field_id_from_name;
+use senax_encoder::value::{from_value, to_value, Field, VariantFields};
+use senax_encoder::{decode, encode, Decode, Encode, Value};
+use std::collections::BTreeMap;
+
+#[derive(Encode, Decode, Debug, PartialEq, Clone)]
+enum Status {
+    Active,
+    Suspended { reason: String },
+}
+
+#[derive(Encode, Decode, Debug, PartialEq, Clone)]
+struct Account {
+    id: u64,
+    name: String,
+    #[senax(id = 40)]
+    balance: i64,
+    ratio: f64,
+    verified: bool,
+    nickname: Option<String>,
+    tags: Vec<String>,
+    limits: BTreeMap<String, u32>,
+    status: Status,
+}
+
+#[derive(Encode, Decode, Debug, PartialEq)]
+struct AccountV2 {
+    id: u64,
+    name: String,
+    email: Option<String>,
+}
+
+fn account() -> Account {
+    Account {
+        id: 7,
+        name: "alice".to_string(),
+        balance: -20,
+        ratio: 0.25,
+        verified: true,
+        nickname: Some("al".to_string()),
+        tags: vec!["vip".to_string()],
+        limits: [("daily".to_string(), 100)].into(),
+        status: Status::Suspended {
+            reason: "audit".to_string(),
+        },
+    }
+}
+
+#[test]
+fn test_decode_edit_and_reencode() {
+    let bytes = encode(&account()).unwrap();
+    let mut value: Value = decode(&mut bytes.clone()).unwrap();
+
+    // Re-encoding an untouched value reproduces the payload
+    assert_eq!(encode(&value).unwrap(), bytes);
+
+    // Fields are found by name even though the payload only carries IDs
+    assert_eq!(value.field("name").and_then(Value::as_str), Some("alice"));
+    assert_eq!(value.field_by_id(40).and_then(Value::as_i64), Some(-20));
+    assert_eq!(value.field("ratio").and_then(Value::as_f64), Some(0.25));
+    assert_eq!(value.field("verified").and_then(Value::as_bool), Some(true));
+    assert_eq!(
+        value
+            .field("nickname")
+            .and_then(Value::as_option)
+            .and_then(Value::as_str),
+        Some("al")
+    );
+
+    assert_eq!(
+        value.set_field("name", "bob"),
+        Some(Value::String("alice".to_string()))
+    );
+    *value.field_by_id_mut(40).unwrap() = 15i64.into();
+    value.set_field("ratio", 0.5);
+    value
+        .field_mut("tags")
+        .and_then(Value::as_list_mut)
+        .unwrap()
+        .push("new".into());
+    value
+        .field_mut("status")
+        .unwrap()
+        .set_field("reason", "cleared");
+    assert!(value.remove_field("nickname").is_some());
+
+    let edited: Account = decode(&mut encode(&value).unwrap()).unwrap();
+    let mut expected = account();
+    expected.name = "bob".to_string();
+    expected.balance = 15;
+    expected.ratio = 0.5;
+    expected.tags.push("new".to_string());
+    expected.status = Status::Suspended {
+        reason: "cleared".to_string(),
+    };
+    expected.nickname = None;
+    assert_eq!(edited, expected);
+}
+
+#[test]
+fn test_added_fields() {
+    let mut value = to_value(&AccountV2 {
+        id: 1,
+        name: "alice".to_string(),
+        email: None,
+    })
+    .unwrap();
+    assert_eq!(value.field("email"), None);
+    assert_eq!(value.set_field("email", "a@example.com"), None);
+    let fields = value.fields().unwrap();
+    assert_eq!(fields.last(), Some(&Field::new("email", "a@example.com")));
+    assert_eq!(fields.last().unwrap().id, field_id_from_name("email"));
+
+    let decoded: AccountV2 = from_value(&value).unwrap();
+    assert_eq!(decoded.email.as_deref(), Some("a@example.com"));
+}
+
+#[test]
+fn test_conversions() {
+    assert_eq!(Value::from(3u8), Value::UInt(3));
+    assert_eq!(Value::from(3i32), Value::UInt(3));
+    assert_eq!(Value::from(-3i32), Value::Int(-3));
+    assert_eq!(Value::from(true), Value::UInt(1));
+    assert_eq!(Value::from(Some("x")), Value::Some(Box::new("x".into())));
+    assert_eq!(Value::from(None::<u32>), Value::None);
+    assert_eq!(
+        Value::from(vec![1u32, 2]),
+        Value::List(vec![Value::UInt(1), Value::UInt(2)])
+    );
+
+    // Converted values encode exactly like the Rust types
+    for (value, bytes) in [
+        (Value::from(-3i32), encode(&-3i32).unwrap()),
+        (Value::from(1.5f64), encode(&1.5f64).unwrap()),
+        (Value::from(false), encode(&false).unwrap()),
+        (
+            Value::from(vec!["a", "b"]),
+            encode(&vec!["a", "b"]).unwrap(),
+        ),
+    ] {
+        assert_eq!(encode(&value).unwrap(), bytes);
+    }
+    assert_eq!(from_value::<f64>(&Value::from(1.5f64)).unwrap(), 1.5);
+}
+
+#[test]
+fn test_values_inside_typed_data() {
+    #[derive(Encode, Decode, Debug, PartialEq)]
+    struct Envelope {
+        kind: String,
+        #[senax(skip_default)]
+        body: Value,
+    }
+
+    let envelope = Envelope {
+        kind: "account".to_string(),
+        body: to_value(&account()).unwrap(),
+    };
+    let decoded: Envelope = decode(&mut encode(&envelope).unwrap()).unwrap();
+    assert_eq!(decoded, envelope);
+    assert_eq!(from_value::<Account>(&decoded.body).unwrap(), account());
+
+    let empty = Envelope {
+        kind: "none".to_string(),
+        body: Value::None,
+    };
+    let decoded: Envelope = decode(&mut encode(&empty).unwrap()).unwrap();
+    assert_eq!(decoded.body, Value::None);
+}
+
+#[test]
+fn test_editing_enum_values() {
+    let mut value = to_value(&Status::Active).unwrap();
+    assert!(value.fields_mut().is_none());
+    let Value::Enum(variant) = &mut value else {
+        panic!("expected an enum");
+    };
+    variant.id = field_id_from_name("Suspended");
+    variant.fields = VariantFields::Named(vec![Field::new("reason", "manual")]);
+    assert_eq!(
+        from_value::<Status>(&value).unwrap(),
+        Status::Suspended {
+            reason: "manual".to_string()
+        }
+    );
+}
+
+#[test]
+fn test_try_set_field() {
+    let mut value = to_value(&account()).unwrap();
+    assert_eq!(value.try_set_field("name", "bob"), Ok(Some("alice".into())));
+    assert_eq!(value.try_set_field("email", "b@example.com"), Ok(None));
+
+    // Values without named fields hand the value back instead of panicking
+    let mut list = Value::List(vec![]);
+    assert_eq!(list.try_set_field("name", "bob"), Err("bob".into()));
+    assert_eq!(list, Value::List(vec![]));
+}
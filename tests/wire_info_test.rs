@@ -0,0 +1,195 @@
+use bytes::{Bytes, BytesMut};
+use senax_encoder::core::{field_id_from_name, TAG_STRING_BASE};
+use senax_encoder::wire_info::{annotate, WireField, WireInfo};
+use senax_encoder::{Encode, Encoder};
+
+#[derive(Encode, Default)]
+#[senax(wire_info)]
+struct Point {
+    #[senax(id = 1)]
+    x: i32,
+    #[senax(id = 2)]
+    y: i32,
+    label: Option<String>,
+    #[senax(skip_encode)]
+    #[allow(dead_code)]
+    cache: u64,
+}
+
+#[derive(Encode, Default)]
+#[senax(wire_info)]
+struct Pair(u8, String);
+
+#[derive(Encode, Default)]
+#[senax(wire_info)]
+enum Shape {
+    #[default]
+    Empty,
+    #[senax(id = 10)]
+    Circle { radius: f64 },
+    #[senax(id = 11)]
+    Line(i32, i32),
+}
+
+#[derive(Encode, Default)]
+#[senax(wire_info)]
+struct Wrapper<T: Encoder + 'static> {
+    items: Vec<T>,
+}
+
+fn encoded<T: Encoder>(value: &T) -> Bytes {
+    let mut writer = BytesMut::new();
+    value.encode(&mut writer).unwrap();
+    writer.freeze()
+}
+
+#[test]
+fn test_struct_fields() {
+    assert_eq!(
+        Point::wire_fields(),
+        &[
+            WireField {
+                name: "x",
+                id: 1,
+                type_name: "i32"
+            },
+            WireField {
+                name: "y",
+                id: 2,
+                type_name: "i32"
+            },
+            WireField {
+                name: "label",
+                id: field_id_from_name("label"),
+                type_name: "Option<String>"
+            },
+        ]
+    );
+    assert!(Point::wire_variants().is_empty());
+}
+
+#[test]
+fn test_tuple_struct_fields_use_positions() {
+    let fields = Pair::wire_fields();
+    assert_eq!(fields.len(), 2);
+    assert_eq!((fields[1].name, fields[1].id), ("1", 1));
+    assert_eq!(fields[1].type_name, "String");
+}
+
+#[test]
+fn test_enum_variants() {
+    let variants = Shape::wire_variants();
+    assert_eq!(variants.len(), 3);
+    assert_eq!(variants[0].id, field_id_from_name("Empty"));
+    assert_eq!((variants[1].name, variants[1].id), ("Circle", 10));
+    assert_eq!(variants[1].fields[0].name, "radius");
+    assert_eq!(variants[2].fields.len(), 2);
+    assert!(Shape::wire_fields().is_empty());
+}
+
+#[test]
+fn test_generic_struct() {
+    assert_eq!(Wrapper::<u32>::wire_fields()[0].type_name, "Vec<T>");
+}
+
+#[test]
+fn test_wire_example_annotates_default_value() {
+    let example = Point::wire_example().unwrap();
+    let lines: Vec<&str> = example.lines().collect();
+    assert_eq!(lines.len(), 4, "{}", example);
+    assert!(lines[0].starts_with("b7 ") && lines[0].ends_with("struct"));
+    assert!(lines[1].starts_with("01 00 ") && lines[1].ends_with("`x`: i32 (id 1) = UInt(0)"));
+    assert!(lines[2].contains("`y`: i32 (id 2)"));
+    assert!(lines[3].starts_with("00 ") && lines[3].ends_with("end"));
+}
+
+#[test]
+fn test_annotate_enum_variants() {
+    let circle = Shape::Circle { radius: 1.5 };
+    let text = annotate(&encoded(&circle), &[], Shape::wire_variants()).unwrap();
+    assert!(text.contains("variant `Circle` (id 10)"), "{}", text);
+    assert!(text.contains("`radius`: f64"), "{}", text);
+
+    let line = Shape::Line(3, -4);
+    let text = annotate(&encoded(&line), &[], Shape::wire_variants()).unwrap();
+    assert!(text.contains("variant `Line` (id 11)"), "{}", text);
+    assert!(text.contains("2 fields"), "{}", text);
+    assert!(text.contains("`1`: i32 = Int(-4)"), "{}", text);
+
+    let text = Shape::wire_example().unwrap();
+    assert!(text.contains("variant `Empty`"), "{}", text);
+}
+
+#[test]
+fn test_annotate_unknown_fields_and_plain_values() {
+    let point = Point {
+        x: 1,
+        label: Some("a".into()),
+        ..Default::default()
+    };
+    let text = annotate(&encoded(&point), &[], &[]).unwrap();
+    assert!(text.contains("unknown field (id 1)"), "{}", text);
+
+    let text = annotate(&encoded(&42u32), &[], &[]).unwrap();
+    assert_eq!(text.trim_end(), format!("{:<30} UInt(42)", "2a"));
+}
+
+#[test]
+fn test_truncated_payload_is_an_error() {
+    let bytes = encoded(&Pair(1, "abc".into()));
+    assert!(annotate(&bytes.slice(..bytes.len() - 1), Pair::wire_fields(), &[]).is_err());
+    assert!(annotate(&Bytes::new(), &[], &[]).is_err());
+}
+
+#[test]
+fn test_fmt_wire() {
+    let point = Point {
+        x: 1,
+        y: 0,
+        label: Some("a".into()),
+        cache: 9,
+    };
+    let text = point.fmt_wire();
+    let lines: Vec<&str> = text.lines().collect();
+    assert_eq!(lines.len(), 5, "{}", text);
+    assert_eq!(lines[0], "struct → b7");
+    assert_eq!(lines[1], "`x` → id 1 → tag 0x01 (uint) → 01 01");
+    let label = format!(
+        "`label` → id {} → tag 0x{:02x} (string) → ",
+        field_id_from_name("label"),
+        TAG_STRING_BASE + 1
+    );
+    assert!(lines[3].starts_with(&label), "{}", text);
+    assert_eq!(lines[4], "end → 00");
+
+    // The bytes of all lines make up the encoding
+    let bytes: Vec<u8> = lines
+        .iter()
+        .flat_map(|line| line.rsplit(" → ").next().unwrap().split(' '))
+        .map(|b| u8::from_str_radix(b, 16).unwrap())
+        .collect();
+    assert_eq!(bytes, encoded(&point));
+}
+
+#[test]
+fn test_fmt_wire_enums_and_tuples() {
+    let text = Shape::Line(3, -4).fmt_wire();
+    assert!(text.starts_with("variant `Line` (id 11) → "), "{}", text);
+    assert!(text.contains("2 fields → 02"), "{}", text);
+    assert!(
+        text.contains("`0` → id 0 → tag 0x03 (uint) → 03"),
+        "{}",
+        text
+    );
+    assert!(text.contains("`1` → id 1 → tag 0x"), "{}", text);
+
+    let text = Shape::Circle { radius: 1.5 }.fmt_wire();
+    assert!(text.contains("`radius` → id "), "{}", text);
+    // Floats are written as decimal strings
+    assert!(text.contains("(string)"), "{}", text);
+
+    let text = Pair(7, "ab".into()).fmt_wire();
+    assert!(text.starts_with("tuple struct, 2 fields → "), "{}", text);
+    assert!(text.contains("`1` → id 1 → "), "{}", text);
+    assert!(text.contains("(string)"), "{}", text);
+}
@@ -0,0 +1,49 @@
+#![cfg(feature = "serde_yaml")]
+
+use senax_encoder::{decode, encode};
+use serde_yaml::Value;
+
+const CONFIG: &str = r#"
+service: billing
+replicas: 3
+offset: -2
+ratio: 0.75
+enabled: true
+owner: ~
+tags: [blue, green]
+limits:
+  cpu: 500m
+  memory: 1Gi
+1: numeric key
+"#;
+
+#[test]
+fn test_yaml_value_round_trip() {
+    let value: Value = serde_yaml::from_str(CONFIG).unwrap();
+    let bytes = encode(&value).unwrap();
+    let decoded: Value = decode(&mut bytes.clone()).unwrap();
+    assert_eq!(decoded, value);
+}
+
+#[test]
+fn test_tagged_value_is_written_as_mapping() {
+    let value: Value = serde_yaml::from_str("!Celsius 21").unwrap();
+    let bytes = encode(&value).unwrap();
+    let decoded: Value = decode(&mut bytes.clone()).unwrap();
+    assert_eq!(
+        decoded,
+        serde_yaml::from_str::<Value>("'!Celsius': 21").unwrap()
+    );
+}
+
+#[cfg(feature = "serde_json")]
+#[test]
+fn test_yaml_payload_decodes_as_json_value() {
+    let value: Value = serde_yaml::from_str("name: api\nports: [80, 443]\nweight: 1.5").unwrap();
+    let bytes = encode(&value).unwrap();
+    let json: serde_json::Value = decode(&mut bytes.clone()).unwrap();
+    assert_eq!(
+        json,
+        serde_json::json!({"name": "api", "ports": [80, 443], "weight": 1.5})
+    );
+}